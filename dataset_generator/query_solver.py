@@ -5,6 +5,46 @@ import platform
 import json
 from .solver_output_types import HeroDecisionOutput, OpponentDecisionOutput, ChanceNodeOutput, ActionEvaluation
 
+class PsfGameConfig(ctypes.Structure):
+    """Mirror of the `PsfGameConfig` struct of the Rust library."""
+    _fields_ = [
+        ("oop_range", ctypes.c_char_p),
+        ("ip_range", ctypes.c_char_p),
+        ("flop", ctypes.c_char_p),
+        ("turn", ctypes.c_char_p),
+        ("river", ctypes.c_char_p),
+        ("starting_pot", ctypes.c_int),
+        ("effective_stack", ctypes.c_int),
+        ("bet_sizes", ctypes.c_char_p),
+        ("raise_sizes", ctypes.c_char_p),
+        ("use_compression", ctypes.c_uint8),
+        ("max_threads", ctypes.c_uint),
+        ("max_memory_bytes", ctypes.c_uint64),
+    ]
+
+
+class PsfResultSizes(ctypes.Structure):
+    """Mirror of the `PsfResultSizes` struct of the Rust library."""
+    _fields_ = [
+        ("num_actions", ctypes.c_size_t),
+        ("num_private_hands", ctypes.c_size_t * 2),
+        ("strategy_len", ctypes.c_size_t),
+        ("hand_string_stride", ctypes.c_size_t),
+    ]
+
+
+class PsfAction(ctypes.Structure):
+    """Mirror of the `PsfAction` struct of the Rust library."""
+    _fields_ = [
+        ("kind", ctypes.c_int),
+        ("amount", ctypes.c_int),
+    ]
+
+
+# Progress callback of psf_solve: (iteration, exploitability, user_data)
+PSF_PROGRESS_CALLBACK = ctypes.CFUNCTYPE(None, ctypes.c_uint, ctypes.c_float, ctypes.c_void_p)
+
+
 def run_solver_from_rust(
     expected_node_type: str,
     oop_range_str,
@@ -52,23 +92,126 @@ def run_solver_from_rust(
     # Load the shared library
     solver_lib = ctypes.CDLL(lib_path_abs)
 
-    # Define argument types for the FFI function
-    solver_lib.run_solver_for_gamestate_ffi.argtypes = [
-        ctypes.c_char_p,  # oop_range_c_str
-        ctypes.c_char_p,  # ip_range_c_str
-        ctypes.c_char_p,  # flop_c_str
-        ctypes.c_char_p,  # turn_card_opt_c_str
-        ctypes.c_char_p,  # river_card_opt_c_str
-        ctypes.c_int,     # initial_pot
-        ctypes.c_int,     # eff_stack
-        ctypes.c_uint8,   # use_compression_flag_c (0 or 1)
-        ctypes.c_uint,    # max_iterations_val
-        ctypes.c_float,   # target_exploit_percentage_val
-        ctypes.c_uint8,   # should_print_progress_c (0 or 1)
+    # Define argument types for the handle-based FFI functions
+    solver_lib.psf_game_create.argtypes = [ctypes.POINTER(PsfGameConfig)]
+    solver_lib.psf_game_create.restype = ctypes.c_void_p
+    solver_lib.psf_solve.argtypes = [
+        ctypes.c_void_p,  # game handle
+        ctypes.c_uint,    # max_iterations
+        ctypes.c_float,   # target_exploitability_ratio (e.g., 0.005 = 0.5% of the pot)
+        ctypes.c_uint8,   # print_progress (0 or 1)
+        PSF_PROGRESS_CALLBACK,            # progress (may be NULL)
+        ctypes.c_void_p,                  # user_data passed to progress
+        ctypes.POINTER(ctypes.c_uint8),   # cancel_flag (may be NULL; set to 1 to stop)
     ]
-    # Define the return type - expecting a C string (JSON from Rust)
-    solver_lib.run_solver_for_gamestate_ffi.restype = ctypes.c_char_p
-    
+    solver_lib.psf_solve.restype = ctypes.c_float
+    solver_lib.psf_get_strategy.argtypes = [
+        ctypes.c_void_p,                  # game handle
+        ctypes.POINTER(ctypes.c_float),   # buf (may be NULL to query the length)
+        ctypes.c_size_t,                  # len
+    ]
+    solver_lib.psf_get_strategy.restype = ctypes.c_ssize_t
+    solver_lib.psf_result_sizes.argtypes = [ctypes.c_void_p, ctypes.POINTER(PsfResultSizes)]
+    solver_lib.psf_result_sizes.restype = ctypes.c_int
+    for name in [
+        "psf_get_expected_values",
+        "psf_get_expected_values_detail",
+        "psf_get_equity",
+        "psf_get_weights",
+    ]:
+        func = getattr(solver_lib, name)
+        func.argtypes = [
+            ctypes.c_void_p,
+            ctypes.c_int,
+            ctypes.POINTER(ctypes.c_float),
+            ctypes.c_size_t,
+        ]
+        func.restype = ctypes.c_ssize_t
+    solver_lib.psf_get_hand_strings.argtypes = [
+        ctypes.c_void_p,
+        ctypes.c_int,
+        ctypes.c_char_p,
+        ctypes.c_size_t,
+    ]
+    solver_lib.psf_get_hand_strings.restype = ctypes.c_ssize_t
+    solver_lib.psf_play.argtypes = [ctypes.c_void_p, ctypes.c_size_t]
+    solver_lib.psf_play.restype = ctypes.c_int
+    solver_lib.psf_available_actions.argtypes = [
+        ctypes.c_void_p,
+        ctypes.POINTER(PsfAction),
+        ctypes.c_size_t,
+    ]
+    solver_lib.psf_available_actions.restype = ctypes.c_ssize_t
+    solver_lib.psf_apply_line.argtypes = [
+        ctypes.c_void_p,
+        ctypes.POINTER(ctypes.c_size_t),
+        ctypes.c_size_t,
+    ]
+    solver_lib.psf_apply_line.restype = ctypes.c_int
+    solver_lib.psf_back_to_root.argtypes = [ctypes.c_void_p]
+    solver_lib.psf_back_to_root.restype = ctypes.c_int
+    solver_lib.psf_current_board.argtypes = [
+        ctypes.c_void_p,
+        ctypes.POINTER(ctypes.c_uint8),
+        ctypes.c_size_t,
+    ]
+    solver_lib.psf_current_board.restype = ctypes.c_ssize_t
+    solver_lib.psf_save.argtypes = [ctypes.c_void_p, ctypes.c_char_p, ctypes.c_int]
+    solver_lib.psf_save.restype = ctypes.c_int
+    solver_lib.psf_load.argtypes = [ctypes.c_char_p]
+    solver_lib.psf_load.restype = ctypes.c_void_p
+    solver_lib.psf_last_error_code.argtypes = []
+    solver_lib.psf_last_error_code.restype = ctypes.c_int
+    # psf_node_json returns a library-owned string, so it is declared as c_void_p and read with
+    # ctypes.string_at before being released by psf_string_free
+    solver_lib.psf_node_json.argtypes = [
+        ctypes.c_void_p,
+        ctypes.c_char_p,
+        ctypes.c_uint,
+        ctypes.c_uint8,
+        ctypes.c_uint8,
+    ]
+    solver_lib.psf_node_json.restype = ctypes.c_void_p
+    solver_lib.psf_string_free.argtypes = [ctypes.c_void_p]
+    solver_lib.psf_string_free.restype = None
+    solver_lib.psf_lock_strategy.argtypes = [
+        ctypes.c_void_p,
+        ctypes.POINTER(ctypes.c_float),
+        ctypes.c_size_t,
+    ]
+    solver_lib.psf_lock_strategy.restype = ctypes.c_int
+    solver_lib.psf_unlock_strategy.argtypes = [ctypes.c_void_p]
+    solver_lib.psf_unlock_strategy.restype = ctypes.c_int
+    solver_lib.psf_range_normalize.argtypes = [
+        ctypes.c_char_p,
+        ctypes.c_char_p,
+        ctypes.c_size_t,
+    ]
+    solver_lib.psf_range_normalize.restype = ctypes.c_ssize_t
+    solver_lib.psf_range_combos.argtypes = [
+        ctypes.c_char_p,
+        ctypes.c_char_p,
+        ctypes.c_char_p,
+        ctypes.c_size_t,
+        ctypes.POINTER(ctypes.c_float),
+        ctypes.c_size_t,
+    ]
+    solver_lib.psf_range_combos.restype = ctypes.c_ssize_t
+    solver_lib.psf_range_equity.argtypes = [
+        ctypes.c_char_p,
+        ctypes.c_char_p,
+        ctypes.c_char_p,
+        ctypes.POINTER(ctypes.c_float),
+        ctypes.c_size_t,
+        ctypes.POINTER(ctypes.c_float),
+        ctypes.c_size_t,
+    ]
+    solver_lib.psf_range_equity.restype = ctypes.c_double
+    solver_lib.psf_free.argtypes = [ctypes.c_void_p]
+    solver_lib.psf_free.restype = None
+    solver_lib.psf_last_error.argtypes = []
+    solver_lib.psf_last_error.restype = ctypes.c_char_p
+
     def to_c_char_p_or_null(s):
         return s.encode('utf-8') if s is not None and s != "" else None
 
@@ -113,19 +256,18 @@ def run_solver_from_rust(
             ActionEvaluation(action_description="DEFAULT_CHECK", ev_for_hero=0.0)
         ])
 
-    # The actual FFI call is currently stubbed.
-    # When enabled, the Rust FFI function would be called here.
-    # It would need to accept parameters defining the game state and the type of evaluation requested.
-    # e.g., rust_json_output_bytes = solver_lib.run_solver_for_gamestate_ffi(
-    #     # ... arguments ...
-    # )
-    #
-    # If the Rust side returns a JSON string:
-    # rust_json_output_str = rust_json_output_bytes.decode('utf-8')
-    # parsed_json = json.loads(rust_json_output_str)
+    # The actual FFI calls are currently stubbed.
+    # When enabled, the game would be created, solved, and queried through the handle, e.g.:
+    # game = solver_lib.psf_game_create(ctypes.byref(PsfGameConfig(...)))
+    # solver_lib.psf_solve(game, max_iterations_val, target_exploit_percentage_val, ...)
+    # length = solver_lib.psf_get_strategy(game, None, 0)
+    # buf = (ctypes.c_float * length)()
+    # solver_lib.psf_get_strategy(game, buf, length)
+    # ... (solver_lib.psf_play(game, action) to move to a later node)
+    # solver_lib.psf_free(game)
     #
-    # Then, based on expected_node_type or a type field in the JSON,
-    # you would parse into the Pydantic model:
+    # The strategy and the evaluation requested by expected_node_type would then be
+    # converted into a JSON-like dict and parsed into the Pydantic model:
     # if expected_node_type == "hero_decision":
     #     dummy_pydantic_object = HeroDecisionOutput(**parsed_json)
     # elif expected_node_type == "opponent_decision":
@@ -1,4 +1,4 @@
-use postflop_solver::*;
+use postflop_solver_ffi::*;
 
 fn main() {
     // ranges of OOP and IP in string format
@@ -59,10 +59,10 @@ fn main() {
     );
 
     // allocate memory without compression (use 32-bit float)
-    game.allocate_memory(false);
+    game.allocate_memory(false, None).unwrap();
 
     // allocate memory with compression (use 16-bit integer)
-    // game.allocate_memory(true);
+    // game.allocate_memory(true, None).unwrap();
 
     // solve the game
     let max_num_iterations = 1000;
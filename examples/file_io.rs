@@ -1,4 +1,4 @@
-use postflop_solver::*;
+use postflop_solver_ffi::*;
 
 fn main() {
     // see `basic.rs` for the explanation of the following code
@@ -33,7 +33,7 @@ fn main() {
 
     let action_tree = ActionTree::new(tree_config).unwrap();
     let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
-    game.allocate_memory(false);
+    game.allocate_memory(false, None).unwrap();
 
     let max_num_iterations = 1000;
     let target_exploitability = game.tree_config().starting_pot as f32 * 0.005;
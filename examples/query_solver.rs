@@ -1,8 +1,7 @@
-use postflop_solver::*;
-use std::os::raw::{c_char, c_int, c_float, c_uint};
-use std::ffi::{CStr, CString};
+use postflop_solver_ffi::*;
 
 // Function to run the solver with configurable game state
+#[allow(clippy::too_many_arguments)]
 fn run_solver_for_gamestate(
     oop_range_str: &str,
     ip_range_str: &str,
@@ -23,13 +22,21 @@ fn run_solver_for_gamestate(
     let ip_range_parsed = ip_range_str.parse().expect("Failed to parse IP range");
 
     let flop_cards = flop_from_str(flop_str).expect("Failed to parse flop string");
-    
+
     let turn_card_val = turn_card_opt_str.map_or(NOT_DEALT, |s| {
-        if s.trim().is_empty() { NOT_DEALT } else { card_from_str(s).expect("Failed to parse turn card string") }
+        if s.trim().is_empty() {
+            NOT_DEALT
+        } else {
+            card_from_str(s).expect("Failed to parse turn card string")
+        }
     });
-    
+
     let river_card_val = river_card_opt_str.map_or(NOT_DEALT, |s| {
-        if s.trim().is_empty() { NOT_DEALT } else { card_from_str(s).expect("Failed to parse river card string") }
+        if s.trim().is_empty() {
+            NOT_DEALT
+        } else {
+            card_from_str(s).expect("Failed to parse river card string")
+        }
     });
 
     let card_config = CardConfig {
@@ -69,22 +76,47 @@ fn run_solver_for_gamestate(
         5 => BoardState::River,
         _ => panic!("Unexpected number of board cards: {}", num_board_cards),
     };
-    println!("Game configured. Initial state: {:?}, Current player: {:?}", current_board_state_print, game.current_player());
-    println!("OOP private cards: {:?}", holes_to_strings(game.private_cards(0)).unwrap_or_default().len());
-    println!("IP private cards: {:?}", holes_to_strings(game.private_cards(1)).unwrap_or_default().len());
+    println!(
+        "Game configured. Initial state: {:?}, Current player: {:?}",
+        current_board_state_print,
+        game.current_player()
+    );
+    println!(
+        "OOP private cards: {:?}",
+        holes_to_strings(game.private_cards(0))
+            .unwrap_or_default()
+            .len()
+    );
+    println!(
+        "IP private cards: {:?}",
+        holes_to_strings(game.private_cards(1))
+            .unwrap_or_default()
+            .len()
+    );
 
     // 2. Allocate Memory
     // -------------------
-    game.allocate_memory(use_compression_flag);
+    game.allocate_memory(use_compression_flag, None).unwrap();
     println!("Memory allocated (compression: {}).", use_compression_flag);
 
     // 3. Run the Solver
     // -----------------
     let target_exploitability = initial_pot as f32 * target_exploit_percentage_val;
-    
-    println!("Starting solver for {} iterations or target exploitability {:.2}...", max_iterations_val, target_exploitability);
-    let exploitability = solve(&mut game, max_iterations_val, target_exploitability, should_print_progress);
-    println!("Solver finished. Final Exploitability: {:.4e} (target was {:.4e})", exploitability, target_exploitability);
+
+    println!(
+        "Starting solver for {} iterations or target exploitability {:.2}...",
+        max_iterations_val, target_exploitability
+    );
+    let exploitability = solve(
+        &mut game,
+        max_iterations_val,
+        target_exploitability,
+        should_print_progress,
+    );
+    println!(
+        "Solver finished. Final Exploitability: {:.4e} (target was {:.4e})",
+        exploitability, target_exploitability
+    );
 
     // 4. Get and Print Solver Output (for the current node, typically the root after solve)
     // -------------------------------------------------------------------------------------
@@ -95,7 +127,10 @@ fn run_solver_for_gamestate(
     println!("Available Actions: {:?}", actions);
 
     let current_player_idx = game.current_player();
-    println!("Current player to act: {}", if current_player_idx == 0 { "OOP" } else { "IP" });
+    println!(
+        "Current player to act: {}",
+        if current_player_idx == 0 { "OOP" } else { "IP" }
+    );
 
     let player_hands = game.private_cards(current_player_idx);
     let player_hands_str = holes_to_strings(player_hands).unwrap_or_default();
@@ -111,13 +146,15 @@ fn run_solver_for_gamestate(
             }
             println!();
         }
-        if player_hands_str.len() > 10 { println!("... (strategy for more hands not shown for brevity)"); }
+        if player_hands_str.len() > 10 {
+            println!("... (strategy for more hands not shown for brevity)");
+        }
     } else {
         println!("No actions available or strategy is empty at the current node.");
     }
 
-    game.back_to_root(); 
-    game.cache_normalized_weights(); 
+    game.back_to_root();
+    game.cache_normalized_weights();
     println!("\n--- Expected Values (EV) for OOP (Player 0) at the root ---");
     let oop_ev = game.expected_values(0);
     let oop_hands_for_ev = game.private_cards(0);
@@ -127,7 +164,9 @@ fn run_solver_for_gamestate(
             println!("Hand {}: EV {:.3}", hand_str, oop_ev[i]);
         }
     }
-    if oop_hands_str_for_ev.len() > 10 { println!("... (EV for more hands not shown for brevity)"); }
+    if oop_hands_str_for_ev.len() > 10 {
+        println!("... (EV for more hands not shown for brevity)");
+    }
 
     println!("\n--- Equity for OOP (Player 0) at the root ---");
     let oop_equity = game.equity(0);
@@ -136,8 +175,10 @@ fn run_solver_for_gamestate(
             println!("Hand {}: Equity {:.1}%", hand_str, oop_equity[i] * 100.0);
         }
     }
-    if oop_hands_str_for_ev.len() > 10 { println!("... (Equity for more hands not shown for brevity)"); }
-    
+    if oop_hands_str_for_ev.len() > 10 {
+        println!("... (Equity for more hands not shown for brevity)");
+    }
+
     let oop_weights = game.normalized_weights(0);
     if !oop_ev.is_empty() && !oop_weights.is_empty() && oop_ev.len() == oop_weights.len() {
         let average_ev_oop = compute_average(&oop_ev, oop_weights); // Assuming compute_average is available
@@ -153,10 +194,10 @@ fn run_solver_for_gamestate(
     }
 
     // 5. Further Interaction (Example) - Commented out as in original, can be enabled if needed
-    // game.back_to_root(); 
+    // game.back_to_root();
     // if !game.available_actions().is_empty() {
     //     println!("\nPlaying first available action at root: {:?}", game.available_actions()[0]);
-    //     game.play(0); 
+    //     game.play(0);
     //     println!("New current player: {}", if game.current_player() == 0 { "OOP" } else { "IP" });
     //     println!("Available actions now: {:?}", game.available_actions());
     // }
@@ -171,7 +212,7 @@ fn main() {
     let flop = "Td9d6h";
     let turn = Some("Qc");
     let river = None; // River is not dealt yet in the original example
-    
+
     let initial_pot_val: i32 = 200;
     let effective_stack_val: i32 = 900;
     let bet_sizes_tuple = ("60%,e,a", "2.5x");
@@ -195,6 +236,6 @@ fn main() {
         target_exploitability_percentage_val,
         print_progress_val,
     );
-    
+
     println!("\n--- Example Main Finished ---");
-} 
\ No newline at end of file
+}
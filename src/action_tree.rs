@@ -1,5 +1,6 @@
 use crate::bet_size::*;
 use crate::card::*;
+use crate::error::*;
 use crate::mutex_like::*;
 
 #[cfg(feature = "bincode")]
@@ -58,7 +59,7 @@ pub enum BoardState {
 ///
 /// # Examples
 /// ```
-/// use postflop_solver::*;
+/// use postflop_solver_ffi::*;
 ///
 /// let bet_sizes = BetSizeOptions::try_from(("60%, e, a", "2.5x")).unwrap();
 /// let donk_sizes = DonkSizeOptions::try_from("50%").unwrap();
@@ -175,7 +176,7 @@ type EjectedActionTree = (
 impl ActionTree {
     /// Creates a new [`ActionTree`] with the specified configuration.
     #[inline]
-    pub fn new(config: TreeConfig) -> Result<Self, String> {
+    pub fn new(config: TreeConfig) -> Result<Self, PostFlopError> {
         Self::check_config(&config)?;
         let mut ret = Self {
             config,
@@ -220,7 +221,7 @@ impl ActionTree {
     ///   must be a bet action (including raise and all-in action).
     /// - Chance actions (i.e., dealing turn and river cards) must be omitted from the `line`.
     #[inline]
-    pub fn add_line(&mut self, line: &[Action]) -> Result<(), String> {
+    pub fn add_line(&mut self, line: &[Action]) -> Result<(), PostFlopError> {
         let removed_index = self.removed_lines.iter().position(|x| x == line);
         let is_replaced = self.add_line_recursive(
             &mut self.root.lock(),
@@ -249,7 +250,7 @@ impl ActionTree {
     /// - If the current node is removed by this method, the current node is moved to the nearest
     ///   ancestor node that is not removed.
     #[inline]
-    pub fn remove_line(&mut self, line: &[Action]) -> Result<(), String> {
+    pub fn remove_line(&mut self, line: &[Action]) -> Result<(), PostFlopError> {
         Self::remove_line_recursive(&mut self.root.lock(), line)?;
         let was_added = self.added_lines.iter().any(|l| l == line);
         self.added_lines.retain(|l| !l.starts_with(line));
@@ -277,7 +278,7 @@ impl ActionTree {
 
     /// Applies the given action history from the root node.
     #[inline]
-    pub fn apply_history(&mut self, history: &[Action]) -> Result<(), String> {
+    pub fn apply_history(&mut self, history: &[Action]) -> Result<(), PostFlopError> {
         self.back_to_root();
         for &action in history {
             self.play(action)?;
@@ -311,10 +312,12 @@ impl ActionTree {
     /// If the current node is a chance node, the chance action is automatically played before
     /// playing the given action.
     #[inline]
-    pub fn play(&mut self, action: Action) -> Result<(), String> {
+    pub fn play(&mut self, action: Action) -> Result<(), PostFlopError> {
         let node = self.current_node_skip_chance();
         if !node.actions.contains(&action) {
-            return Err(format!("Action `{action:?}` is not available"));
+            return Err(PostFlopError::InvalidArgument(format!(
+                "Action `{action:?}` is not available"
+            )));
         }
 
         self.history.push(action);
@@ -323,9 +326,9 @@ impl ActionTree {
 
     /// Undoes the last action. Returns `Ok(())` if the action is successfully undone.
     #[inline]
-    pub fn undo(&mut self) -> Result<(), String> {
+    pub fn undo(&mut self) -> Result<(), PostFlopError> {
         if self.history.is_empty() {
-            return Err("No action to undo".to_string());
+            return Err(PostFlopError::InvalidState("No action to undo".to_string()));
         }
 
         self.history.pop();
@@ -339,7 +342,7 @@ impl ActionTree {
     ///
     /// [`add_line`]: #method.add_line
     #[inline]
-    pub fn add_action(&mut self, action: Action) -> Result<(), String> {
+    pub fn add_action(&mut self, action: Action) -> Result<(), PostFlopError> {
         let mut action_line = self.history.clone();
         action_line.push(action);
         self.add_line(&action_line)
@@ -352,7 +355,7 @@ impl ActionTree {
     ///
     /// [`remove_line`]: #method.remove_line
     #[inline]
-    pub fn remove_action(&mut self, action: Action) -> Result<(), String> {
+    pub fn remove_action(&mut self, action: Action) -> Result<(), PostFlopError> {
         let mut action_line = self.history.clone();
         action_line.push(action);
         self.remove_line(&action_line)
@@ -365,7 +368,7 @@ impl ActionTree {
     ///
     /// [`remove_line`]: #method.remove_line
     #[inline]
-    pub fn remove_current_node(&mut self) -> Result<(), String> {
+    pub fn remove_current_node(&mut self) -> Result<(), PostFlopError> {
         let history = self.history.clone();
         self.remove_line(&history)
     }
@@ -377,6 +380,24 @@ impl ActionTree {
         self.total_bet_amount_recursive(&self.root.lock(), &self.history, info)
     }
 
+    /// Creates a tree whose root is a copy of the node reached by `history` from `root`.
+    ///
+    /// `history` is a list of action indices, where each chance action is represented by the dealt
+    /// card. The amounts are shifted so that the amount of the new root is zero, and the lines are
+    /// not inherited.
+    pub(crate) fn from_subtree(
+        config: TreeConfig,
+        root: &ActionTreeNode,
+        history: &[usize],
+    ) -> Result<Self, PostFlopError> {
+        Self::check_config(&config)?;
+        Ok(Self {
+            config,
+            root: Box::new(MutexLike::new(copy_subtree(root, history))),
+            ..Default::default()
+        })
+    }
+
     /// Ejects the fields.
     #[inline]
     pub(crate) fn eject(self) -> EjectedActionTree {
@@ -390,10 +411,10 @@ impl ActionTree {
             let mut node = &*self.root.lock() as *const ActionTreeNode;
             for action in &self.history {
                 while (*node).is_chance() {
-                    node = &*(*node).children[0].lock();
+                    node = &*(&(*node).children)[0].lock();
                 }
                 let index = (*node).actions.iter().position(|x| x == action).unwrap();
-                node = &*(*node).children[index].lock();
+                node = &*(&(*node).children)[index].lock();
             }
             &*node
         }
@@ -405,7 +426,7 @@ impl ActionTree {
         unsafe {
             let mut node = self.current_node() as *const ActionTreeNode;
             while (*node).is_chance() {
-                node = &*(*node).children[0].lock();
+                node = &*(&(*node).children)[0].lock();
             }
             &*node
         }
@@ -413,61 +434,61 @@ impl ActionTree {
 
     /// Checks the configuration.
     #[inline]
-    fn check_config(config: &TreeConfig) -> Result<(), String> {
+    fn check_config(config: &TreeConfig) -> Result<(), PostFlopError> {
         if config.starting_pot <= 0 {
-            return Err(format!(
+            return Err(PostFlopError::InvalidConfig(format!(
                 "Starting pot must be positive: {}",
                 config.starting_pot
-            ));
+            )));
         }
 
         if config.effective_stack <= 0 {
-            return Err(format!(
+            return Err(PostFlopError::InvalidConfig(format!(
                 "Effective stack must be positive: {}",
                 config.effective_stack
-            ));
+            )));
         }
 
         if config.rake_rate < 0.0 {
-            return Err(format!(
+            return Err(PostFlopError::InvalidConfig(format!(
                 "Rake rate must be non-negative: {}",
                 config.rake_rate
-            ));
+            )));
         }
 
         if config.rake_rate > 1.0 {
-            return Err(format!(
+            return Err(PostFlopError::InvalidConfig(format!(
                 "Rake rate must be less than or equal to 1.0: {}",
                 config.rake_rate
-            ));
+            )));
         }
 
         if config.rake_cap < 0.0 {
-            return Err(format!(
+            return Err(PostFlopError::InvalidConfig(format!(
                 "Rake cap must be non-negative: {}",
                 config.rake_cap
-            ));
+            )));
         }
 
         if config.add_allin_threshold < 0.0 {
-            return Err(format!(
+            return Err(PostFlopError::InvalidConfig(format!(
                 "Add all-in threshold must be non-negative: {}",
                 config.add_allin_threshold
-            ));
+            )));
         }
 
         if config.force_allin_threshold < 0.0 {
-            return Err(format!(
+            return Err(PostFlopError::InvalidConfig(format!(
                 "Force all-in threshold must be non-negative: {}",
                 config.force_allin_threshold
-            ));
+            )));
         }
 
         if config.merging_threshold < 0.0 {
-            return Err(format!(
+            return Err(PostFlopError::InvalidConfig(format!(
                 "Merging threshold must be non-negative: {}",
                 config.merging_threshold
-            ));
+            )));
         }
 
         Ok(())
@@ -767,13 +788,15 @@ impl ActionTree {
         line: &[Action],
         was_removed: bool,
         info: BuildTreeInfo,
-    ) -> Result<bool, String> {
+    ) -> Result<bool, PostFlopError> {
         if line.is_empty() {
-            return Err("Empty line".to_string());
+            return Err(PostFlopError::InvalidArgument("Empty line".to_string()));
         }
 
         if node.is_terminal() {
-            return Err("Unexpected terminal node".to_string());
+            return Err(PostFlopError::InvalidArgument(
+                "Unexpected terminal node".to_string(),
+            ));
         }
 
         if node.is_chance() {
@@ -793,7 +816,9 @@ impl ActionTree {
 
         if line.len() > 1 {
             if search_result.is_err() {
-                return Err(format!("Action does not exist: {action:?}"));
+                return Err(PostFlopError::InvalidArgument(format!(
+                    "Action does not exist: {action:?}"
+                )));
             }
 
             return self.add_line_recursive(
@@ -805,12 +830,16 @@ impl ActionTree {
         }
 
         if search_result.is_ok() {
-            return Err(format!("Action already exists: {action:?}"));
+            return Err(PostFlopError::InvalidArgument(format!(
+                "Action already exists: {action:?}"
+            )));
         }
 
         let is_bet_action = matches!(action, Action::Bet(_) | Action::Raise(_) | Action::AllIn(_));
         if info.allin_flag && is_bet_action {
-            return Err(format!("Bet action after all-in: {action:?}"));
+            return Err(PostFlopError::InvalidArgument(format!(
+                "Bet action after all-in: {action:?}"
+            )));
         }
 
         let player_stack = info.stack[player as usize];
@@ -847,17 +876,19 @@ impl ActionTree {
         if !was_removed && !is_valid_bet {
             match action {
                 Action::Bet(amount) | Action::Raise(amount) => {
-                    return Err(format!(
+                    return Err(PostFlopError::InvalidArgument(format!(
                         "Invalid bet amount: {amount} (min: {min_amount}, max: {max_amount})"
-                    ));
+                    )));
                 }
                 Action::AllIn(amount) => {
-                    return Err(format!(
+                    return Err(PostFlopError::InvalidArgument(format!(
                         "Invalid all-in amount: {amount} (expected: {max_amount})"
-                    ));
+                    )));
                 }
                 _ => {
-                    return Err(format!("Invalid action: {action:?}"));
+                    return Err(PostFlopError::InvalidArgument(format!(
+                        "Invalid action: {action:?}"
+                    )));
                 }
             };
         }
@@ -911,13 +942,18 @@ impl ActionTree {
     }
 
     /// Recursive function to remove a given line from the tree.
-    fn remove_line_recursive(node: &mut ActionTreeNode, line: &[Action]) -> Result<(), String> {
+    fn remove_line_recursive(
+        node: &mut ActionTreeNode,
+        line: &[Action],
+    ) -> Result<(), PostFlopError> {
         if line.is_empty() {
-            return Err("Empty line".to_string());
+            return Err(PostFlopError::InvalidArgument("Empty line".to_string()));
         }
 
         if node.is_terminal() {
-            return Err("Unexpected terminal node".to_string());
+            return Err(PostFlopError::InvalidArgument(
+                "Unexpected terminal node".to_string(),
+            ));
         }
 
         if node.is_chance() {
@@ -927,7 +963,9 @@ impl ActionTree {
         let action = line[0];
         let search_result = node.actions.binary_search(&action);
         if search_result.is_err() {
-            return Err(format!("Action does not exist: {action:?}"));
+            return Err(PostFlopError::InvalidArgument(format!(
+                "Action does not exist: {action:?}"
+            )));
         }
 
         if line.len() > 1 {
@@ -987,6 +1025,32 @@ impl ActionTreeNode {
     }
 }
 
+/// Returns a copy of the subtree rooted at the node reached by `history` from `node`.
+pub(crate) fn copy_subtree(node: &ActionTreeNode, history: &[usize]) -> ActionTreeNode {
+    match history.split_first() {
+        None => copy_node_recursive(node, node.amount),
+        Some((&action, rest)) => {
+            let index = if node.is_chance() { 0 } else { action };
+            copy_subtree(&node.children[index].lock(), rest)
+        }
+    }
+}
+
+/// Copies the given node recursively, subtracting `offset` from the amounts.
+fn copy_node_recursive(node: &ActionTreeNode, offset: i32) -> ActionTreeNode {
+    ActionTreeNode {
+        player: node.player,
+        board_state: node.board_state,
+        amount: node.amount - offset,
+        actions: node.actions.clone(),
+        children: node
+            .children
+            .iter()
+            .map(|child| MutexLike::new(copy_node_recursive(&child.lock(), offset)))
+            .collect(),
+    }
+}
+
 impl BuildTreeInfo {
     #[inline]
     fn new(stack: i32) -> Self {
@@ -1051,7 +1115,11 @@ pub(crate) fn count_num_action_nodes(node: &ActionTreeNode) -> [u64; 3] {
     ret
 }
 
-fn count_num_action_nodes_recursive(node: &ActionTreeNode, street: usize, count: &mut [u64; 3]) {
+pub(crate) fn count_num_action_nodes_recursive(
+    node: &ActionTreeNode,
+    street: usize,
+    count: &mut [u64; 3],
+) {
     count[street] += 1;
     if node.is_terminal() {
         // do nothing
@@ -0,0 +1,886 @@
+use crate::bunching::*;
+use crate::card::*;
+use crate::error::*;
+use crate::hand::*;
+use crate::hand_label::*;
+use crate::range::*;
+use crate::utility::*;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// Number of equity buckets used by [`runout_equity_shift`].
+pub const NUM_EQUITY_BUCKETS: usize = 5;
+
+/// Result of [`blocker_effects`].
+///
+/// The villain's range is divided into three regions by [`classify_hand`]:
+///
+/// - strong: two pair or better,
+/// - continuing: any pair or better, or any straight/flush draw (strong hands included),
+/// - folding: the remaining hands.
+///
+/// The `*_removed` fields are the fractions of the weights of each region removed by the hand.
+/// The `*_share_shift` fields are the changes of the share of each region in the whole villain's
+/// range, i.e., (share after removal) - (share before removal).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlockerEffects {
+    /// Fraction of the total weight removed.
+    pub total_removed: f64,
+
+    /// Fraction of the weight of the strong region removed.
+    pub strong_removed: f64,
+
+    /// Fraction of the weight of the continuing region removed.
+    pub continue_removed: f64,
+
+    /// Fraction of the weight of the folding region removed.
+    pub fold_removed: f64,
+
+    /// Change of the share of the strong region.
+    pub strong_share_shift: f64,
+
+    /// Change of the share of the continuing region.
+    pub continue_share_shift: f64,
+
+    /// Change of the share of the folding region.
+    pub fold_share_shift: f64,
+}
+
+/// Quantifies how much the hand blocks each region of the villain's range on the board.
+///
+/// A positive `fold_share_shift` means that the hand makes the villain's range fold more often,
+/// which is a typical property of a good bluffing candidate. See [`BlockerEffects`] for details.
+///
+/// # Examples
+/// ```
+/// use postflop_solver_ffi::*;
+///
+/// let board = flop_from_str("Kh9h4h").unwrap();
+/// let villain_range = "KQ,K9s,AhQh,AhJh,Th8h,QJ,JT,87o".parse::<Range>().unwrap();
+///
+/// // Ah blocks the nut flushes
+/// let hand = (card_from_str("Ah").unwrap(), card_from_str("2c").unwrap());
+/// let effects = blocker_effects(hand, &villain_range, &board).unwrap();
+/// assert!(effects.strong_share_shift < 0.0);
+/// ```
+pub fn blocker_effects(
+    hand: (Card, Card),
+    villain_range: &Range,
+    board: &[Card],
+) -> Result<BlockerEffects, PostFlopError> {
+    if !(3..=5).contains(&board.len()) {
+        return Err(PostFlopError::InvalidArgument(format!(
+            "Invalid board length: {}",
+            board.len()
+        )));
+    }
+
+    let board_mask = board_to_mask(board)?;
+    let hand_mask = board_to_mask(&[hand.0, hand.1])?;
+    if hand.0 == hand.1 || board_mask & hand_mask != 0 {
+        return Err(PostFlopError::InvalidHand {
+            input: hole_to_string(hand).unwrap_or_default(),
+            reason: "Hand conflicts with the board".to_string(),
+        });
+    }
+
+    // [total, strong, continue, fold] x [before, after]
+    let mut weights = [[0.0; 2]; 4];
+    let (hands, hand_weights) = villain_range.get_hands_weights(board_mask);
+    for (&villain_hand, &weight) in hands.iter().zip(hand_weights.iter()) {
+        let class = classify_hand(villain_hand, board)?;
+        let weight = weight as f64;
+        let is_strong = class.made_hand >= MadeHand::TwoPair;
+        let is_continue = class.made_hand >= MadeHand::WeakPair || class.draw >= DrawType::Gutshot;
+        let villain_mask: u64 = (1 << villain_hand.0) | (1 << villain_hand.1);
+        let is_blocked = villain_mask & hand_mask != 0;
+
+        for (region, flag) in [
+            (0, true),
+            (1, is_strong),
+            (2, is_continue),
+            (3, !is_continue),
+        ] {
+            if flag {
+                weights[region][0] += weight;
+                if !is_blocked {
+                    weights[region][1] += weight;
+                }
+            }
+        }
+    }
+
+    if weights[0][0] == 0.0 || weights[0][1] == 0.0 {
+        return Err(PostFlopError::InvalidArgument(
+            "Villain's range is empty".to_string(),
+        ));
+    }
+
+    let removed = |region: usize| {
+        let [before, after] = weights[region];
+        if before == 0.0 {
+            0.0
+        } else {
+            1.0 - after / before
+        }
+    };
+
+    let share_shift =
+        |region: usize| weights[region][1] / weights[0][1] - weights[region][0] / weights[0][0];
+
+    Ok(BlockerEffects {
+        total_removed: removed(0),
+        strong_removed: removed(1),
+        continue_removed: removed(2),
+        fold_removed: removed(3),
+        strong_share_shift: share_shift(1),
+        continue_share_shift: share_shift(2),
+        fold_share_shift: share_shift(3),
+    })
+}
+
+/// Equity of the ranges after a specific turn card, returned by [`runout_equity_shift`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TurnEquityShift {
+    /// Turn card.
+    pub card: Card,
+
+    /// Equity of the OOP range against the IP range after the turn card.
+    pub equity: f64,
+
+    /// Change of the OOP equity from the flop, i.e., `equity - flop_equity`.
+    pub equity_shift: f64,
+
+    /// Share of each player's range in each equity bucket after the turn card.
+    pub distribution: [[f64; NUM_EQUITY_BUCKETS]; 2],
+
+    /// Change of `distribution` from the flop.
+    pub distribution_shift: [[f64; NUM_EQUITY_BUCKETS]; 2],
+}
+
+/// Result of [`runout_equity_shift`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunoutEquityShift {
+    /// Equity of the OOP range against the IP range on the flop.
+    pub flop_equity: f64,
+
+    /// Share of each player's range in each equity bucket on the flop.
+    pub flop_distribution: [[f64; NUM_EQUITY_BUCKETS]; 2],
+
+    /// Results for each possible turn card in ascending order of card IDs.
+    pub turns: Vec<TurnEquityShift>,
+}
+
+/// Computes how each possible turn card changes the range-vs-range equity and the nuttiness of
+/// both ranges.
+///
+/// The nuttiness is represented by the distribution of the hand equities: the `i`-th bucket of
+/// `distribution` contains the share of the range whose equity against the opponent's range is in
+/// [`i / NUM_EQUITY_BUCKETS`, `(i + 1) / NUM_EQUITY_BUCKETS`). All values are exact, i.e., all
+/// river cards are enumerated.
+///
+/// **Time complexity:** *O*(49 * 48 * #(hands) log #(hands))
+///
+/// # Examples
+/// ```
+/// use postflop_solver_ffi::*;
+///
+/// let oop_range = "AA,KK,AK".parse::<Range>().unwrap();
+/// let ip_range = "QQ-TT,AQs".parse::<Range>().unwrap();
+/// let flop = flop_from_str("Td9d6h").unwrap();
+///
+/// let report = runout_equity_shift(&oop_range, &ip_range, flop).unwrap();
+/// assert_eq!(report.turns.len(), 49);
+/// ```
+pub fn runout_equity_shift(
+    range_oop: &Range,
+    range_ip: &Range,
+    flop: [Card; 3],
+) -> Result<RunoutEquityShift, PostFlopError> {
+    let flop_mask = board_to_mask(&flop)?;
+
+    let (hands_oop, weights_oop) = range_oop.get_hands_weights(flop_mask);
+    let (hands_ip, weights_ip) = range_ip.get_hands_weights(flop_mask);
+    if hands_oop.is_empty() || hands_ip.is_empty() {
+        return Err(PostFlopError::InvalidArgument("Range is empty".to_string()));
+    }
+
+    let hands = [hands_oop.as_slice(), hands_ip.as_slice()];
+    let weights = [weights_oop.as_slice(), weights_ip.as_slice()];
+    let turn_cards = (0..52)
+        .filter(|&card| flop_mask & (1 << card) == 0)
+        .collect::<Vec<Card>>();
+
+    // per-hand sums of (winning weights, matchup weights) for each turn card
+    let turn_sums = into_par_iter(0..turn_cards.len())
+        .map(|i| {
+            let turn = turn_cards[i];
+            let mut sums = [
+                vec![(0.0, 0.0); hands[0].len()],
+                vec![(0.0, 0.0); hands[1].len()],
+            ];
+            for &river in &turn_cards {
+                if river != turn {
+                    let board = [flop[0], flop[1], flop[2], turn, river];
+                    let result = river_equity_sums(&board, hands, weights);
+                    for player in 0..2 {
+                        for (sum, value) in sums[player].iter_mut().zip(&result[player]) {
+                            sum.0 += value.0;
+                            sum.1 += value.1;
+                        }
+                    }
+                }
+            }
+            sums
+        })
+        .collect::<Vec<_>>();
+
+    let mut flop_sums = [
+        vec![(0.0, 0.0); hands[0].len()],
+        vec![(0.0, 0.0); hands[1].len()],
+    ];
+    for sums in &turn_sums {
+        for player in 0..2 {
+            for (flop_sum, value) in flop_sums[player].iter_mut().zip(&sums[player]) {
+                flop_sum.0 += value.0;
+                flop_sum.1 += value.1;
+            }
+        }
+    }
+
+    let (flop_equity, flop_distribution) = summarize_equity_sums(&flop_sums, weights);
+
+    let turns = turn_cards
+        .iter()
+        .zip(turn_sums.iter())
+        .map(|(&card, sums)| {
+            let (equity, distribution) = summarize_equity_sums(sums, weights);
+            let mut distribution_shift = distribution;
+            for player in 0..2 {
+                for bucket in 0..NUM_EQUITY_BUCKETS {
+                    distribution_shift[player][bucket] -= flop_distribution[player][bucket];
+                }
+            }
+            TurnEquityShift {
+                card,
+                equity,
+                equity_shift: equity - flop_equity,
+                distribution,
+                distribution_shift,
+            }
+        })
+        .collect();
+
+    Ok(RunoutEquityShift {
+        flop_equity,
+        flop_distribution,
+        turns,
+    })
+}
+
+/// Result of [`bunching_equity`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BunchingEquity {
+    /// Equities of OOP and IP considering the bunching effect.
+    pub equity: [f64; 2],
+
+    /// Equities of OOP and IP ignoring the folded players.
+    pub equity_without_bunching: [f64; 2],
+}
+
+/// Computes the range-vs-range equity under the bunching effect without building a game tree.
+///
+/// `board` consists of 3 to 5 cards, and its first three cards must be suit-isomorphic to the
+/// flop of `bunching_data` (see [`BunchingData::specialize`]). The remaining board cards are
+/// enumerated, and each combination of the two hands and the runout is weighted by the number of
+/// hand combinations of the folded players. The equity without the bunching effect is also
+/// returned so that the impact of the bunching effect can be quantified before solving.
+///
+/// **Time complexity:** *O*(#(runouts) * #(OOP private hands) * #(IP private hands)).
+pub fn bunching_equity(
+    range_oop: &Range,
+    range_ip: &Range,
+    bunching_data: &BunchingData,
+    board: &[Card],
+) -> Result<BunchingEquity, PostFlopError> {
+    let board_mask = board_to_mask(board)?;
+    bunching_data.specialize(board)?;
+
+    let (hands_oop, weights_oop) = range_oop.get_hands_weights(board_mask);
+    let (hands_ip, weights_ip) = range_ip.get_hands_weights(board_mask);
+    if hands_oop.is_empty() || hands_ip.is_empty() {
+        return Err(PostFlopError::InvalidArgument("Range is empty".to_string()));
+    }
+
+    let remaining = (0..52)
+        .filter(|&card| board_mask & (1 << card) == 0)
+        .collect::<Vec<Card>>();
+
+    let runouts = match board.len() {
+        3 => (0..remaining.len())
+            .flat_map(|i| (i + 1..remaining.len()).map(move |j| (i, j)))
+            .map(|(i, j)| vec![remaining[i], remaining[j]])
+            .collect(),
+        4 => remaining.iter().map(|&card| vec![card]).collect(),
+        _ => vec![Vec::new()],
+    };
+
+    // (bunching winning weights, bunching weights, winning weights, weights)
+    let sums = into_par_iter(0..runouts.len())
+        .map(|i| {
+            let mut full_board = board.to_vec();
+            full_board.extend_from_slice(&runouts[i]);
+            let full_mask = board_to_mask(&full_board).unwrap();
+            let view = bunching_data.specialize(&full_board).unwrap();
+
+            let board_hand = full_board
+                .iter()
+                .fold(Hand::new(), |h, &c| h.add_card(c as usize));
+            let strength = |&(c1, c2): &(Card, Card)| {
+                if full_mask & ((1 << c1) | (1 << c2)) != 0 {
+                    None
+                } else {
+                    let hand = board_hand.add_card(c1 as usize).add_card(c2 as usize);
+                    Some(hand.evaluate())
+                }
+            };
+
+            let strength_oop = hands_oop.iter().map(strength).collect::<Vec<_>>();
+            let strength_ip = hands_ip.iter().map(strength).collect::<Vec<_>>();
+            let mut sums = [0.0; 4];
+
+            for (i, &hand_oop) in hands_oop.iter().enumerate() {
+                let Some(strength_oop) = strength_oop[i] else {
+                    continue;
+                };
+
+                let weight_oop = weights_oop[i];
+                let mask_oop: u64 = (1 << hand_oop.0) | (1 << hand_oop.1);
+
+                for (j, &hand_ip) in hands_ip.iter().enumerate() {
+                    let Some(strength_ip) = strength_ip[j] else {
+                        continue;
+                    };
+
+                    if mask_oop & ((1 << hand_ip.0) | (1 << hand_ip.1)) != 0 {
+                        continue;
+                    }
+
+                    let weight_ip = weights_ip[j];
+
+                    let weight = weight_oop as f64 * weight_ip as f64;
+                    let num_combinations = view.num_combinations(hand_oop, hand_ip) as f64;
+                    let win = match strength_oop.cmp(&strength_ip) {
+                        std::cmp::Ordering::Greater => 1.0,
+                        std::cmp::Ordering::Equal => 0.5,
+                        std::cmp::Ordering::Less => 0.0,
+                    };
+
+                    sums[0] += weight * num_combinations * win;
+                    sums[1] += weight * num_combinations;
+                    sums[2] += weight * win;
+                    sums[3] += weight;
+                }
+            }
+
+            sums
+        })
+        .collect::<Vec<_>>();
+
+    let mut total = [0.0; 4];
+    for s in &sums {
+        for k in 0..4 {
+            total[k] += s[k];
+        }
+    }
+
+    if total[1] == 0.0 {
+        return Err(PostFlopError::InvalidConfig(
+            "Valid combination not found".to_string(),
+        ));
+    }
+
+    let equity = total[0] / total[1];
+    let equity_without_bunching = total[2] / total[3];
+
+    Ok(BunchingEquity {
+        equity: [equity, 1.0 - equity],
+        equity_without_bunching: [equity_without_bunching, 1.0 - equity_without_bunching],
+    })
+}
+
+/// Result of [`range_equity`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeEquity {
+    /// Equities of OOP and IP.
+    pub equity: [f64; 2],
+
+    /// Hands of OOP and IP that do not conflict with the board, in the same order as
+    /// [`Range::get_hands_weights`].
+    pub hands: [Vec<(Card, Card)>; 2],
+
+    /// Equity of each hand of `hands`, or NaN if the hand has no valid matchup.
+    pub hand_equity: [Vec<f64>; 2],
+}
+
+/// Computes the range-vs-range equity and the equity of each hand without building a game tree.
+///
+/// `board` consists of 3 to 5 cards, and the remaining board cards are enumerated.
+///
+/// **Time complexity:** *O*(#(runouts) * #(private hands) * log(#(private hands))).
+///
+/// # Examples
+/// ```
+/// use postflop_solver_ffi::*;
+///
+/// let oop_range = "AA".parse::<Range>().unwrap();
+/// let ip_range = "KK".parse::<Range>().unwrap();
+/// let mut board = flop_from_str("Qs7h2d").unwrap().to_vec();
+/// board.extend([card_from_str("3c").unwrap(), card_from_str("4c").unwrap()]);
+///
+/// let result = range_equity(&oop_range, &ip_range, &board).unwrap();
+/// assert_eq!(result.equity, [1.0, 0.0]);
+/// ```
+pub fn range_equity(
+    range_oop: &Range,
+    range_ip: &Range,
+    board: &[Card],
+) -> Result<RangeEquity, PostFlopError> {
+    if !(3..=5).contains(&board.len()) {
+        return Err(PostFlopError::InvalidArgument(format!(
+            "Invalid board length: {}",
+            board.len()
+        )));
+    }
+
+    let board_mask = board_to_mask(board)?;
+    let (hands_oop, weights_oop) = range_oop.get_hands_weights(board_mask);
+    let (hands_ip, weights_ip) = range_ip.get_hands_weights(board_mask);
+    if hands_oop.is_empty() || hands_ip.is_empty() {
+        return Err(PostFlopError::InvalidArgument("Range is empty".to_string()));
+    }
+
+    let hands = [hands_oop.as_slice(), hands_ip.as_slice()];
+    let weights = [weights_oop.as_slice(), weights_ip.as_slice()];
+    let remaining = (0..52)
+        .filter(|&card| board_mask & (1 << card) == 0)
+        .collect::<Vec<Card>>();
+
+    let runouts = match board.len() {
+        3 => (0..remaining.len())
+            .flat_map(|i| (i + 1..remaining.len()).map(move |j| (i, j)))
+            .map(|(i, j)| vec![remaining[i], remaining[j]])
+            .collect(),
+        4 => remaining.iter().map(|&card| vec![card]).collect(),
+        _ => vec![Vec::new()],
+    };
+
+    let runout_sums = into_par_iter(0..runouts.len())
+        .map(|i| {
+            let mut full_board = [0; 5];
+            full_board[..board.len()].copy_from_slice(board);
+            full_board[board.len()..].copy_from_slice(&runouts[i]);
+            river_equity_sums(&full_board, hands, weights)
+        })
+        .collect::<Vec<_>>();
+
+    let mut sums = [
+        vec![(0.0, 0.0); hands[0].len()],
+        vec![(0.0, 0.0); hands[1].len()],
+    ];
+    for runout_sum in &runout_sums {
+        for player in 0..2 {
+            for (sum, value) in sums[player].iter_mut().zip(&runout_sum[player]) {
+                sum.0 += value.0;
+                sum.1 += value.1;
+            }
+        }
+    }
+
+    let (mut numerator, mut denominator) = (0.0, 0.0);
+    for (&(win, matchups), &weight) in sums[0].iter().zip(weights[0]) {
+        numerator += weight as f64 * win;
+        denominator += weight as f64 * matchups;
+    }
+
+    if denominator == 0.0 {
+        return Err(PostFlopError::InvalidConfig(
+            "Valid combination not found".to_string(),
+        ));
+    }
+
+    let equity = numerator / denominator;
+    let hand_equity = sums.map(|sums| {
+        sums.iter()
+            .map(|&(win, matchups)| match matchups {
+                0.0 => f64::NAN,
+                _ => win / matchups,
+            })
+            .collect()
+    });
+
+    Ok(RangeEquity {
+        equity: [equity, 1.0 - equity],
+        hands: [hands_oop, hands_ip],
+        hand_equity,
+    })
+}
+
+/// Returns the minimum defense frequency (MDF) against a bet.
+///
+/// `pot` is the pot size before the bet, and `bet` is the amount to call. The MDF is the
+/// frequency with which the defender must continue so that a bluff with zero equity does not make
+/// an immediate profit, i.e., `pot / (pot + bet)`.
+///
+/// # Examples
+/// ```
+/// use postflop_solver_ffi::*;
+///
+/// assert_eq!(minimum_defense_frequency(100, 50), 2.0 / 3.0);
+/// assert_eq!(alpha(100, 50), 1.0 / 3.0);
+/// assert_eq!(optimal_bluff_ratio(100, 50), 0.25);
+/// ```
+#[inline]
+pub fn minimum_defense_frequency(pot: i32, bet: i32) -> f32 {
+    pot as f32 / (pot + bet) as f32
+}
+
+/// Returns the break-even fold frequency (alpha) of a bluff, i.e., `bet / (pot + bet)`.
+///
+/// A bluff with zero equity makes an immediate profit if the defender folds more often than this
+/// frequency. See [`minimum_defense_frequency`] for the arguments.
+#[inline]
+pub fn alpha(pot: i32, bet: i32) -> f32 {
+    bet as f32 / (pot + bet) as f32
+}
+
+/// Returns the fraction of bluffs in a polarized betting range that makes the defender
+/// indifferent between calling and folding, i.e., `bet / (pot + 2 * bet)`.
+///
+/// See [`minimum_defense_frequency`] for the arguments.
+#[inline]
+pub fn optimal_bluff_ratio(pot: i32, bet: i32) -> f32 {
+    bet as f32 / (pot + 2 * bet) as f32
+}
+
+/// Computes the OOP range equity and the equity distributions from the per-hand sums.
+fn summarize_equity_sums(
+    sums: &[Vec<(f64, f64)>; 2],
+    weights: [&[f32]; 2],
+) -> (f64, [[f64; NUM_EQUITY_BUCKETS]; 2]) {
+    let mut numerator = 0.0;
+    let mut denominator = 0.0;
+    let mut distribution = [[0.0; NUM_EQUITY_BUCKETS]; 2];
+
+    for player in 0..2 {
+        let mut total_weight = 0.0;
+        for (&(win, matchups), &weight) in sums[player].iter().zip(weights[player]) {
+            if matchups == 0.0 {
+                continue;
+            }
+            let weight = weight as f64;
+            if player == 0 {
+                numerator += weight * win;
+                denominator += weight * matchups;
+            }
+            let equity = win / matchups;
+            let bucket =
+                ((equity * NUM_EQUITY_BUCKETS as f64) as usize).min(NUM_EQUITY_BUCKETS - 1);
+            distribution[player][bucket] += weight;
+            total_weight += weight;
+        }
+        if total_weight > 0.0 {
+            distribution[player]
+                .iter_mut()
+                .for_each(|x| *x /= total_weight);
+        }
+    }
+
+    let equity = if denominator > 0.0 {
+        numerator / denominator
+    } else {
+        0.5
+    };
+
+    (equity, distribution)
+}
+
+/// (strength, card1, card2, weight, index)
+type EquityItem = (u16, Card, Card, f64, usize);
+
+/// Computes the per-hand equity sums of both players on the river board.
+///
+/// For each hand, returns the pair of (sum of the opponent's weights that the hand beats, plus half
+/// of the weights that tie, sum of the opponent's weights that do not conflict with the hand).
+/// Hands that conflict with the board are excluded and their results are `(0.0, 0.0)`.
+pub(crate) fn river_equity_sums(
+    board: &[Card; 5],
+    hands: [&[(Card, Card)]; 2],
+    weights: [&[f32]; 2],
+) -> [Vec<(f64, f64)>; 2] {
+    let board_mask = board.iter().fold(0u64, |acc, &c| acc | (1 << c));
+    let board_hand = board
+        .iter()
+        .fold(Hand::new(), |h, &c| h.add_card(c as usize));
+
+    let mut items: [Vec<EquityItem>; 2] = Default::default();
+    let mut weight_by_index = [vec![0.0; 52 * 51 / 2], vec![0.0; 52 * 51 / 2]];
+    for player in 0..2 {
+        for (index, (&(c1, c2), &w)) in hands[player].iter().zip(weights[player]).enumerate() {
+            let hand_mask: u64 = (1 << c1) | (1 << c2);
+            if hand_mask & board_mask != 0 || w <= 0.0 {
+                continue;
+            }
+            let hand = board_hand.add_card(c1 as usize).add_card(c2 as usize);
+            items[player].push((hand.evaluate(), c1, c2, w as f64, index));
+            weight_by_index[player][card_pair_to_index(c1, c2)] += w as f64;
+        }
+        items[player].sort_unstable_by_key(|item| item.0);
+    }
+
+    let mut result = [
+        vec![(0.0, 0.0); hands[0].len()],
+        vec![(0.0, 0.0); hands[1].len()],
+    ];
+
+    for player in 0..2 {
+        let mine = &items[player];
+        let opp = &items[player ^ 1];
+
+        let mut total = 0.0;
+        let mut total_card = [0.0; 52];
+        for &(_, c1, c2, w, _) in opp {
+            total += w;
+            total_card[c1 as usize] += w;
+            total_card[c2 as usize] += w;
+        }
+
+        let mut less = 0.0;
+        let mut less_card = [0.0; 52];
+        let mut j = 0;
+
+        for (i, &(strength, c1, c2, _, index)) in mine.iter().enumerate() {
+            if i == 0 || mine[i - 1].0 != strength {
+                while j < opp.len() && opp[j].0 < strength {
+                    let (_, o1, o2, ow, _) = opp[j];
+                    less += ow;
+                    less_card[o1 as usize] += ow;
+                    less_card[o2 as usize] += ow;
+                    j += 1;
+                }
+            }
+
+            let (mut tie, mut tie_c1, mut tie_c2) = (0.0, 0.0, 0.0);
+            for &(_, o1, o2, ow, _) in opp[j..].iter().take_while(|item| item.0 == strength) {
+                tie += ow;
+                if o1 == c1 || o2 == c1 {
+                    tie_c1 += ow;
+                }
+                if o1 == c2 || o2 == c2 {
+                    tie_c2 += ow;
+                }
+            }
+
+            let same = weight_by_index[player ^ 1][card_pair_to_index(c1, c2)];
+            let win = less - less_card[c1 as usize] - less_card[c2 as usize];
+            let tie = tie - tie_c1 - tie_c2 + same;
+            let matchups = total - total_card[c1 as usize] - total_card[c2 as usize] + same;
+            result[player][index] = (win + 0.5 * tie, matchups);
+        }
+    }
+
+    result
+}
+
+/// Converts a list of cards into a bit mask, checking for invalid and duplicate cards.
+fn board_to_mask(cards: &[Card]) -> Result<u64, PostFlopError> {
+    let mut mask: u64 = 0;
+    for &card in cards {
+        if card >= 52 {
+            return Err(PostFlopError::InvalidCard {
+                input: card.to_string(),
+                reason: format!("Invalid card: {card}"),
+            });
+        }
+        if mask & (1 << card) != 0 {
+            return Err(PostFlopError::BoardConflict { card });
+        }
+        mask |= 1 << card;
+    }
+    Ok(mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocker() {
+        let board = flop_from_str("Kh9h4h").unwrap();
+        let range = "KQ,K9s,AhQh,AhJh,Th8h,QJ,JT,87o".parse::<Range>().unwrap();
+
+        let ah2c = (card_from_str("Ah").unwrap(), card_from_str("2c").unwrap());
+        let effects = blocker_effects(ah2c, &range, &board).unwrap();
+        assert!(effects.strong_removed > 0.0);
+        assert!(effects.strong_share_shift < 0.0);
+        assert!(effects.fold_share_shift > 0.0);
+
+        // 2c3c blocks nothing
+        let c2c3 = (card_from_str("2c").unwrap(), card_from_str("3c").unwrap());
+        let effects = blocker_effects(c2c3, &range, &board).unwrap();
+        assert_eq!(effects.total_removed, 0.0);
+        assert!(effects.strong_share_shift.abs() < 1e-12);
+
+        let total_shift = effects.continue_share_shift + effects.fold_share_shift;
+        assert!(total_shift.abs() < 1e-12);
+
+        let kh2c = (card_from_str("Kh").unwrap(), card_from_str("2c").unwrap());
+        assert!(blocker_effects(kh2c, &range, &board).is_err());
+    }
+
+    #[test]
+    fn runout_equity() {
+        let flop = flop_from_str("Td9d6h").unwrap();
+
+        // same ranges: equity is exactly 0.5 on every turn
+        let range = "TT+,AJs+,KQo,98s".parse::<Range>().unwrap();
+        let report = runout_equity_shift(&range, &range, flop).unwrap();
+        assert_eq!(report.turns.len(), 49);
+        assert!((report.flop_equity - 0.5).abs() < 1e-9);
+        for turn in &report.turns {
+            assert!((turn.equity - 0.5).abs() < 1e-9);
+            assert!(turn.equity_shift.abs() < 1e-9);
+            assert!((turn.distribution[0].iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        }
+
+        // an ace helps AA/AK against QQ-TT
+        let oop = "AA,AK".parse::<Range>().unwrap();
+        let ip = "QQ,JJ".parse::<Range>().unwrap();
+        let report = runout_equity_shift(&oop, &ip, flop).unwrap();
+        let ace = report.turns.iter().find(|t| t.card == 48).unwrap();
+        let deuce = report.turns.iter().find(|t| t.card == 0).unwrap();
+        assert!(ace.equity_shift > 0.0);
+        assert!(ace.equity > deuce.equity);
+
+        // the average of the turn equities is the flop equity
+        let average = report.turns.iter().map(|t| t.equity).sum::<f64>() / 49.0;
+        assert!((average - report.flop_equity).abs() < 0.02);
+    }
+
+    #[test]
+    fn range_vs_range_equity() {
+        let flop = flop_from_str("Td9d6h").unwrap();
+        let oop = "AA,AK,T9s".parse::<Range>().unwrap();
+        let ip = "QQ,JJ,87s".parse::<Range>().unwrap();
+
+        // the flop equity is the same as that of the runout report
+        let result = range_equity(&oop, &ip, &flop).unwrap();
+        let report = runout_equity_shift(&oop, &ip, flop).unwrap();
+        assert!((result.equity[0] - report.flop_equity).abs() < 1e-9);
+        assert!((result.equity[0] + result.equity[1] - 1.0).abs() < 1e-9);
+        assert_eq!(
+            result.hands[0],
+            oop.get_hands_weights(board_to_mask(&flop).unwrap()).0
+        );
+        assert_eq!(result.hand_equity[0].len(), result.hands[0].len());
+
+        // overpairs on the river
+        let mut board = flop.to_vec();
+        board.extend([card_from_str("2c").unwrap(), card_from_str("3s").unwrap()]);
+        let aces = "AA".parse::<Range>().unwrap();
+        let pairs = "KK,QQ".parse::<Range>().unwrap();
+        let result = range_equity(&aces, &pairs, &board).unwrap();
+        assert_eq!(result.equity, [1.0, 0.0]);
+        assert!(result.hand_equity[0].iter().all(|&e| e == 1.0));
+        assert!(result.hand_equity[1].iter().all(|&e| e == 0.0));
+
+        // same ranges
+        let result = range_equity(&oop, &oop, &board).unwrap();
+        assert!((result.equity[0] - 0.5).abs() < 1e-9);
+
+        // hands conflicting with the board are excluded
+        let board = flop_from_str("Th9h6c").unwrap();
+        let result = range_equity(&oop, &ip, &board).unwrap();
+        assert_eq!(result.hands[0].len(), 6 + 16 + 3);
+        assert!(range_equity(&oop, &ip, &board[..2]).is_err());
+        assert!(range_equity(&"".parse().unwrap(), &ip, &board).is_err());
+    }
+
+    #[test]
+    fn bunching() {
+        let fold_range = "22+,A2+,K2s+,K8o+,Q9s+,QJo,J9s+,T9s"
+            .parse::<Range>()
+            .unwrap();
+        let flop = flop_from_str("Td9d6h").unwrap();
+        let mut bunching_data = BunchingData::new(&[fold_range], flop).unwrap();
+        bunching_data.process(false);
+
+        let oop = "AA,KK,AKs,AQo,T9s,76s".parse::<Range>().unwrap();
+        let ip = "QQ-88,AJs,KQo,87s".parse::<Range>().unwrap();
+
+        // brute force
+        let mut board = flop.to_vec();
+        board.push(card_from_str("2c").unwrap());
+        let board_mask = board_to_mask(&board).unwrap();
+        let (hands_oop, weights_oop) = oop.get_hands_weights(board_mask);
+        let (hands_ip, weights_ip) = ip.get_hands_weights(board_mask);
+        let mut sums = [0.0; 4];
+
+        for river in (0..52).filter(|&c| board_mask & (1 << c) == 0) {
+            let mut full_board = board.clone();
+            full_board.push(river);
+
+            for (&(c1, c2), &w1) in hands_oop.iter().zip(&weights_oop) {
+                for (&(c3, c4), &w2) in hands_ip.iter().zip(&weights_ip) {
+                    let mut cards = full_board.clone();
+                    cards.extend([c1, c2]);
+                    let Ok(rank_oop) = evaluate_hand(&cards) else {
+                        continue;
+                    };
+
+                    cards.truncate(5);
+                    cards.extend([c3, c4]);
+                    let Ok(rank_ip) = evaluate_hand(&cards) else {
+                        continue;
+                    };
+
+                    cards.extend([c1, c2]);
+                    let dead_mask = cards.iter().fold(0u64, |acc, &c| acc | (1 << c));
+                    if dead_mask.count_ones() != 9 {
+                        continue;
+                    }
+
+                    let num_combinations = fold_range
+                        .get_hands_weights(dead_mask)
+                        .1
+                        .iter()
+                        .map(|&w| w as f64)
+                        .sum::<f64>();
+
+                    let w = w1 as f64 * w2 as f64;
+                    let win = match rank_oop.cmp(&rank_ip) {
+                        std::cmp::Ordering::Greater => 1.0,
+                        std::cmp::Ordering::Equal => 0.5,
+                        std::cmp::Ordering::Less => 0.0,
+                    };
+
+                    sums[0] += w * num_combinations * win;
+                    sums[1] += w * num_combinations;
+                    sums[2] += w * win;
+                    sums[3] += w;
+                }
+            }
+        }
+
+        let result = bunching_equity(&oop, &ip, &bunching_data, &board).unwrap();
+        assert!((result.equity[0] - sums[0] / sums[1]).abs() < 1e-9);
+        assert!((result.equity_without_bunching[0] - sums[2] / sums[3]).abs() < 1e-9);
+        assert!((result.equity[0] + result.equity[1] - 1.0).abs() < 1e-9);
+        assert!(result.equity[0] != result.equity_without_bunching[0]);
+
+        // the flop must be suit-isomorphic
+        let board = flop_from_str("Td9h6h").unwrap();
+        assert!(bunching_equity(&oop, &ip, &bunching_data, &board).is_err());
+    }
+}
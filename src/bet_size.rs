@@ -1,3 +1,5 @@
+use crate::error::*;
+
 #[cfg(feature = "bincode")]
 use bincode::{Decode, Encode};
 
@@ -10,7 +12,7 @@ use bincode::{Decode, Encode};
 /// - x: Multiple of the previous bet. Valid for only raises. (e.g., "2.5x")
 /// - c: Constant value. Must be an integer. (e.g., "100c")
 /// - c + r: Constant value with raise cap (for FLHE). Both values must be integers.
-///          Valid only for raises. (e.g., "20c3r")
+///   Valid only for raises. (e.g., "20c3r")
 /// - e: Geometric size.
 ///   - e: Same as "3e" for the flop, "2e" for the turn, and "1e" (equivalent to "a") for the river.
 ///   - Xe: The geometric size with X streets remaining. X must be a positive integer. (e.g., "2e")
@@ -20,8 +22,8 @@ use bincode::{Decode, Encode};
 ///
 /// # Examples
 /// ```
-/// use postflop_solver::BetSize::*;
-/// use postflop_solver::BetSizeOptions;
+/// use postflop_solver_ffi::BetSize::*;
+/// use postflop_solver_ffi::BetSizeOptions;
 ///
 /// let bet_size = BetSizeOptions::try_from(("50%, 100c, 2e, a", "2.5x")).unwrap();
 ///
@@ -81,7 +83,7 @@ pub enum BetSize {
 }
 
 impl TryFrom<(&str, &str)> for BetSizeOptions {
-    type Error = String;
+    type Error = PostFlopError;
 
     /// Attempts to convert comma-separated strings into bet sizes.
     ///
@@ -102,11 +104,11 @@ impl TryFrom<(&str, &str)> for BetSizeOptions {
         let mut raise = Vec::new();
 
         for bet_size in bet_sizes {
-            bet.push(bet_size_from_str(bet_size, false)?);
+            bet.push(parse_bet_size(bet_size, false)?);
         }
 
         for raise_size in raise_sizes {
-            raise.push(bet_size_from_str(raise_size, true)?);
+            raise.push(parse_bet_size(raise_size, true)?);
         }
 
         bet.sort_unstable_by(|l, r| l.partial_cmp(r).unwrap());
@@ -117,7 +119,7 @@ impl TryFrom<(&str, &str)> for BetSizeOptions {
 }
 
 impl TryFrom<&str> for DonkSizeOptions {
-    type Error = String;
+    type Error = PostFlopError;
 
     /// Attempts to convert comma-separated strings into bet sizes.
     ///
@@ -132,7 +134,7 @@ impl TryFrom<&str> for DonkSizeOptions {
         let mut donk = Vec::new();
 
         for donk_size in donk_sizes {
-            donk.push(bet_size_from_str(donk_size, false)?);
+            donk.push(parse_bet_size(donk_size, false)?);
         }
 
         donk.sort_unstable_by(|l, r| l.partial_cmp(r).unwrap());
@@ -141,6 +143,14 @@ impl TryFrom<&str> for DonkSizeOptions {
     }
 }
 
+/// Parses a bet size, converting the error into [`PostFlopError::InvalidBetSize`].
+fn parse_bet_size(s: &str, is_raise: bool) -> Result<BetSize, PostFlopError> {
+    bet_size_from_str(s, is_raise).map_err(|reason| PostFlopError::InvalidBetSize {
+        input: s.to_string(),
+        reason,
+    })
+}
+
 fn parse_float(s: &str) -> Option<f64> {
     if s.contains('+') || s.contains('-') || s.contains(|c: char| c.is_ascii_alphabetic()) {
         None
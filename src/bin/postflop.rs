@@ -0,0 +1,830 @@
+//! Command-line interface of the solver (requires the `cli` feature).
+//!
+//! Run `postflop help` for the usage. The spot file of the `solve` command is a TOML file such as:
+//!
+//! ```toml
+//! oop_range = "66+,A8s+,A5s-A4s,AJo+,K9s+,KQo,QTs+,JTs,96s+,85s+,75s+,65s,54s"
+//! ip_range = "QQ-22,AQs-A2s,ATo+,K5s+,KJo+,Q8s+,J8s+,T7s+,96s+,86s+,75s+,64s+,53s+"
+//! board = "Td9d6h Qc"
+//! starting_pot = 200
+//! effective_stack = 900
+//! bet_sizes = "60%, e, a"
+//! raise_sizes = "2.5x"
+//!
+//! [river]
+//! donk_sizes = "50%"
+//!
+//! [solver]
+//! max_iterations = 1000
+//! target_exploitability = 0.5
+//! ```
+
+use postflop_solver_ffi::*;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::process::ExitCode;
+use toml::{Table, Value};
+
+const USAGE: &str = "\
+Usage: postflop <command> [arguments]
+
+Commands:
+  solve <spot.toml> [-o <game.bin>]
+      Solves the spot and saves the game (default: the spot path with the extension `.bin`).
+  query <game.bin> [<line>] [--combos]
+      Prints the strategy of the node reached by the line, per hand class or per combination.
+  export <game.bin> [-o <file>] [--format json|csv] [--line <line>]... [--street <street>]...
+         [--precision <digits>] [--equity] [--no-ev]
+      Exports the solved tree (default: JSON to the standard output).
+  report <game.bin> [<line>]
+      Prints the aggregated statistics of the nodes below the node reached by the line.
+  help
+      Prints this message.
+
+Lines are whitespace-separated tokens such as \"X B75 C 5d\" (see `PostFlopGame::apply_line_str`).
+
+Spot file keys:
+  oop_range, ip_range, board, starting_pot, effective_stack (required)
+  bet_sizes, raise_sizes, rake_rate, rake_cap, add_allin_threshold, force_allin_threshold,
+  merging_threshold (optional)
+  [flop], [turn], [river]: bet_sizes, raise_sizes, oop_bet_sizes, oop_raise_sizes, ip_bet_sizes,
+  ip_raise_sizes, donk_sizes (turn and river only)
+  [solver]: max_iterations (default: 1000), target_exploitability (% of the pot, default: 0.5),
+  compression (default: false)";
+
+/// Options that take a value.
+const VALUE_OPTIONS: [&str; 6] = [
+    "-o",
+    "--output",
+    "--format",
+    "--line",
+    "--street",
+    "--precision",
+];
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Runs the command of the given arguments (excluding the program name).
+fn run(args: &[String]) -> Result<(), String> {
+    let Some((command, args)) = args.split_first() else {
+        return Err(format!("No command specified\n\n{USAGE}"));
+    };
+
+    let mut args = Args::parse(args)?;
+    let stdout = &mut io::stdout().lock();
+
+    match command.as_str() {
+        "solve" => {
+            let spot_path = args.positional(0, "spot file")?;
+            let output = args.value(&["-o", "--output"]);
+            args.finish(1)?;
+            let output = output.unwrap_or_else(|| {
+                let path = Path::new(&spot_path).with_extension("bin");
+                path.to_string_lossy().into_owned()
+            });
+            solve_command(&spot_path, &output, stdout)
+        }
+
+        "query" => {
+            let mut game = load_game(&args.positional(0, "game file")?)?;
+            let line = args.positional(1, "line").unwrap_or_default();
+            let combos = args.flag("--combos");
+            args.finish(2)?;
+            query_command(&mut game, &line, combos, stdout)
+        }
+
+        "export" => {
+            let mut game = load_game(&args.positional(0, "game file")?)?;
+            let output = args.value(&["-o", "--output"]);
+            let format = match (args.value(&["--format"]), &output) {
+                (Some(format), _) => format.to_lowercase(),
+                (None, Some(path)) if path.to_lowercase().ends_with(".csv") => "csv".to_string(),
+                _ => "json".to_string(),
+            };
+            let options = export_options(&mut args)?;
+            args.finish(1)?;
+            match output {
+                Some(path) => {
+                    let file = File::create(&path)
+                        .map_err(|e| format!("Failed to create file: {path}: {e}"))?;
+                    export_command(&mut game, &format, &options, &mut BufWriter::new(file))
+                }
+                None => export_command(&mut game, &format, &options, stdout),
+            }
+        }
+
+        "report" => {
+            let mut game = load_game(&args.positional(0, "game file")?)?;
+            let line = args.positional(1, "line").unwrap_or_default();
+            args.finish(2)?;
+            report_command(&mut game, &line, stdout)
+        }
+
+        "help" | "-h" | "--help" => writeln!(stdout, "{USAGE}").map_err(write_error),
+
+        _ => Err(format!("Unknown command: {command}\n\n{USAGE}")),
+    }
+}
+
+/// Parsed command-line arguments of a command.
+#[derive(Debug, Default)]
+struct Args {
+    positional: Vec<String>,
+    options: Vec<(String, String)>,
+    flags: Vec<String>,
+}
+
+impl Args {
+    /// Splits the arguments into positional arguments, options with a value, and flags.
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut ret = Self::default();
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            if VALUE_OPTIONS.contains(&arg.as_str()) {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| format!("Option {arg} requires a value"))?;
+                ret.options.push((arg.clone(), value.clone()));
+            } else if arg.starts_with('-') && arg.len() > 1 {
+                ret.flags.push(arg.clone());
+            } else {
+                ret.positional.push(arg.clone());
+            }
+        }
+
+        Ok(ret)
+    }
+
+    /// Returns the `index`-th positional argument.
+    fn positional(&self, index: usize, name: &str) -> Result<String, String> {
+        self.positional
+            .get(index)
+            .cloned()
+            .ok_or_else(|| format!("Missing argument: {name}\n\n{USAGE}"))
+    }
+
+    /// Removes and returns the last value of the given option.
+    fn value(&mut self, names: &[&str]) -> Option<String> {
+        self.values(names).pop()
+    }
+
+    /// Removes and returns all values of the given option.
+    fn values(&mut self, names: &[&str]) -> Vec<String> {
+        let (matched, rest) = std::mem::take(&mut self.options)
+            .into_iter()
+            .partition(|(name, _)| names.contains(&name.as_str()));
+        self.options = rest;
+        matched.into_iter().map(|(_, value)| value).collect()
+    }
+
+    /// Removes the given flag and returns whether it was specified.
+    fn flag(&mut self, name: &str) -> bool {
+        let len = self.flags.len();
+        self.flags.retain(|flag| flag != name);
+        self.flags.len() != len
+    }
+
+    /// Returns an error if there are unused options or more than `num_positional` positional
+    /// arguments.
+    fn finish(&self, num_positional: usize) -> Result<(), String> {
+        if let Some((name, _)) = self.options.first() {
+            return Err(format!("Unexpected option: {name}"));
+        }
+        if let Some(flag) = self.flags.first() {
+            return Err(format!("Unknown option: {flag}"));
+        }
+        if let Some(arg) = self.positional.get(num_positional) {
+            return Err(format!("Unexpected argument: {arg}"));
+        }
+        Ok(())
+    }
+}
+
+/// Solver settings of a spot file.
+#[derive(Debug, Clone, PartialEq)]
+struct SolverConfig {
+    max_iterations: u32,
+    target_exploitability: f32,
+    compression: bool,
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        Self {
+            max_iterations: 1000,
+            target_exploitability: 0.5,
+            compression: false,
+        }
+    }
+}
+
+/// Parses a spot file into the card configuration, the tree configuration, and the solver
+/// settings.
+fn parse_spot(text: &str) -> Result<(CardConfig, TreeConfig, SolverConfig), String> {
+    let mut table = text
+        .parse::<Table>()
+        .map_err(|e| format!("Failed to parse spot file: {e}"))?;
+    let table = &mut table;
+
+    let oop_range = required(take_str(table, "oop_range")?, "oop_range")?;
+    let ip_range = required(take_str(table, "ip_range")?, "ip_range")?;
+    let board = board_from_str(&required(take_str(table, "board")?, "board")?)?;
+
+    let mut flop = match board[..] {
+        [c1, c2, c3, ..] if board.len() <= 5 => [c1, c2, c3],
+        _ => return Err(format!("Board must have 3 to 5 cards: {}", board.len())),
+    };
+    flop.sort_unstable();
+
+    let card_config = CardConfig {
+        range: [oop_range.parse()?, ip_range.parse()?],
+        flop,
+        turn: board.get(3).copied().unwrap_or(NOT_DEALT),
+        river: board.get(4).copied().unwrap_or(NOT_DEALT),
+    };
+
+    let mut tree_config = TreeConfig {
+        initial_state: match board.len() {
+            3 => BoardState::Flop,
+            4 => BoardState::Turn,
+            _ => BoardState::River,
+        },
+        starting_pot: required(take_int(table, "starting_pot")?, "starting_pot")?,
+        effective_stack: required(take_int(table, "effective_stack")?, "effective_stack")?,
+        ..Default::default()
+    };
+
+    let float_fields = [
+        ("rake_rate", &mut tree_config.rake_rate),
+        ("rake_cap", &mut tree_config.rake_cap),
+        ("add_allin_threshold", &mut tree_config.add_allin_threshold),
+        (
+            "force_allin_threshold",
+            &mut tree_config.force_allin_threshold,
+        ),
+        ("merging_threshold", &mut tree_config.merging_threshold),
+    ];
+    for (key, field) in float_fields {
+        if let Some(value) = take_float(table, key)? {
+            *field = value;
+        }
+    }
+
+    let bet = take_str(table, "bet_sizes")?.unwrap_or_default();
+    let raise = take_str(table, "raise_sizes")?.unwrap_or_default();
+    for street in ["flop", "turn", "river"] {
+        let mut street_table = take_table(table, street)?.unwrap_or_default();
+        let street_table = &mut street_table;
+        let bet = take_str(street_table, "bet_sizes")?.unwrap_or_else(|| bet.clone());
+        let raise = take_str(street_table, "raise_sizes")?.unwrap_or_else(|| raise.clone());
+
+        let mut sizes = Vec::with_capacity(2);
+        for player in ["oop", "ip"] {
+            let key = format!("{player}_bet_sizes");
+            let player_bet = take_str(street_table, &key)?.unwrap_or_else(|| bet.clone());
+            let key = format!("{player}_raise_sizes");
+            let player_raise = take_str(street_table, &key)?.unwrap_or_else(|| raise.clone());
+            sizes.push(BetSizeOptions::try_from((
+                player_bet.as_str(),
+                player_raise.as_str(),
+            ))?);
+        }
+        let sizes = [sizes[0].clone(), sizes[1].clone()];
+
+        let donk_sizes = match street {
+            "flop" => None,
+            _ => take_str(street_table, "donk_sizes")?
+                .map(|donk| DonkSizeOptions::try_from(donk.as_str()))
+                .transpose()?,
+        };
+
+        match street {
+            "flop" => tree_config.flop_bet_sizes = sizes,
+            "turn" => {
+                (tree_config.turn_bet_sizes, tree_config.turn_donk_sizes) = (sizes, donk_sizes)
+            }
+            _ => (tree_config.river_bet_sizes, tree_config.river_donk_sizes) = (sizes, donk_sizes),
+        }
+
+        check_unknown_keys(street_table, &format!("[{street}]"))?;
+    }
+
+    let mut solver_config = SolverConfig::default();
+    if let Some(mut solver_table) = take_table(table, "solver")? {
+        let solver_table = &mut solver_table;
+        if let Some(max_iterations) = take_int(solver_table, "max_iterations")? {
+            solver_config.max_iterations = max_iterations;
+        }
+        if let Some(target) = take_float(solver_table, "target_exploitability")? {
+            solver_config.target_exploitability = target as f32;
+        }
+        if let Some(compression) = take_bool(solver_table, "compression")? {
+            solver_config.compression = compression;
+        }
+        check_unknown_keys(solver_table, "[solver]")?;
+    }
+
+    check_unknown_keys(table, "spot file")?;
+    Ok((card_config, tree_config, solver_config))
+}
+
+/// Returns an error if the required key is missing.
+fn required<T>(value: Option<T>, key: &str) -> Result<T, String> {
+    value.ok_or_else(|| format!("Missing key in spot file: {key}"))
+}
+
+/// Removes and returns the string value of the given key.
+fn take_str(table: &mut Table, key: &str) -> Result<Option<String>, String> {
+    match table.remove(key) {
+        None => Ok(None),
+        Some(Value::String(value)) => Ok(Some(value)),
+        Some(value) => Err(type_error(key, "a string", &value)),
+    }
+}
+
+/// Removes and returns the integer value of the given key.
+fn take_int<T: TryFrom<i64>>(table: &mut Table, key: &str) -> Result<Option<T>, String> {
+    match table.remove(key) {
+        None => Ok(None),
+        Some(Value::Integer(value)) => T::try_from(value)
+            .map(Some)
+            .map_err(|_| format!("Value of {key} is out of range: {value}")),
+        Some(value) => Err(type_error(key, "an integer", &value)),
+    }
+}
+
+/// Removes and returns the numeric value of the given key.
+fn take_float(table: &mut Table, key: &str) -> Result<Option<f64>, String> {
+    match table.remove(key) {
+        None => Ok(None),
+        Some(Value::Float(value)) => Ok(Some(value)),
+        Some(Value::Integer(value)) => Ok(Some(value as f64)),
+        Some(value) => Err(type_error(key, "a number", &value)),
+    }
+}
+
+/// Removes and returns the boolean value of the given key.
+fn take_bool(table: &mut Table, key: &str) -> Result<Option<bool>, String> {
+    match table.remove(key) {
+        None => Ok(None),
+        Some(Value::Boolean(value)) => Ok(Some(value)),
+        Some(value) => Err(type_error(key, "a boolean", &value)),
+    }
+}
+
+/// Removes and returns the table of the given key.
+fn take_table(table: &mut Table, key: &str) -> Result<Option<Table>, String> {
+    match table.remove(key) {
+        None => Ok(None),
+        Some(Value::Table(value)) => Ok(Some(value)),
+        Some(value) => Err(type_error(key, "a table", &value)),
+    }
+}
+
+fn type_error(key: &str, expected: &str, value: &Value) -> String {
+    format!(
+        "Value of {key} must be {expected}, found {}",
+        value.type_str()
+    )
+}
+
+/// Returns an error if the table has a key that was not consumed.
+fn check_unknown_keys(table: &Table, context: &str) -> Result<(), String> {
+    match table.keys().next() {
+        Some(key) => Err(format!("Unknown key in {context}: {key}")),
+        None => Ok(()),
+    }
+}
+
+/// Solves the spot file and saves the game.
+fn solve_command<W: Write>(spot_path: &str, output: &str, out: &mut W) -> Result<(), String> {
+    let text = std::fs::read_to_string(spot_path)
+        .map_err(|e| format!("Failed to read spot file: {spot_path}: {e}"))?;
+    let (card_config, tree_config, solver_config) = parse_spot(&text)?;
+
+    let mut diagnostics = card_config.validate();
+    diagnostics.extend(tree_config.validate());
+    for diagnostic in diagnostics.iter().filter(|d| !d.is_error()) {
+        eprintln!("warning: {}", diagnostic.message);
+    }
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.is_error())
+        .map(|d| d.message.as_str())
+        .collect::<Vec<_>>();
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+
+    let starting_pot = tree_config.starting_pot;
+    let action_tree = ActionTree::new(tree_config)?;
+    let mut game = PostFlopGame::with_config(card_config, action_tree)?;
+
+    let (uncompressed, compressed) = game.memory_usage();
+    let memory_usage = match solver_config.compression {
+        false => uncompressed,
+        true => compressed,
+    };
+    writeln!(out, "Memory usage: {:.2} GB", memory_usage as f64 / 1e9).map_err(write_error)?;
+    game.allocate_memory(solver_config.compression, None)?;
+
+    let target = starting_pot as f32 * solver_config.target_exploitability / 100.0;
+    let exploitability = solve(&mut game, solver_config.max_iterations, target, true);
+    writeln!(
+        out,
+        "Exploitability: {:.3} ({:.3}% of the pot)",
+        exploitability,
+        exploitability / starting_pot as f32 * 100.0
+    )
+    .map_err(write_error)?;
+
+    save_data_to_file(&game, "", output, None)?;
+    writeln!(out, "Saved: {output}").map_err(write_error)
+}
+
+/// Loads a solved game.
+fn load_game(path: &str) -> Result<PostFlopGame, String> {
+    let (game, _) = load_data_from_file::<PostFlopGame, _>(path, None)?;
+    if !game.is_solved() {
+        return Err(format!("Game is not solved: {path}"));
+    }
+    Ok(game)
+}
+
+/// Applies the line and checks that the node is a decision node.
+fn apply_line(game: &mut PostFlopGame, line: &str) -> Result<(), String> {
+    game.apply_line_str(line).map_err(|e| e.to_string())?;
+    if game.is_terminal_node() {
+        return Err(format!("Line reaches a terminal node: {line}"));
+    }
+    Ok(())
+}
+
+/// Returns the board cards of the current node (e.g., `"Td9d6h Qc"`).
+fn board_string(game: &PostFlopGame) -> String {
+    let board = game.current_board();
+    let flop = board[..3].iter().rev().map(|&c| card_to_string(c).unwrap());
+    let rest = board[3..].iter().map(|&c| card_to_string(c).unwrap());
+    [flop.collect::<String>()]
+        .into_iter()
+        .chain(rest)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Prints the strategy of the node reached by the line.
+fn query_command<W: Write>(
+    game: &mut PostFlopGame,
+    line: &str,
+    combos: bool,
+    out: &mut W,
+) -> Result<(), String> {
+    apply_line(game, line)?;
+
+    if game.is_chance_node() {
+        let cards = game
+            .available_actions()
+            .into_iter()
+            .map(action_to_token)
+            .collect::<Vec<_>>();
+        writeln!(out, "Board: {}", board_string(game)).map_err(write_error)?;
+        return writeln!(out, "Chance node: {}", cards.join(" ")).map_err(write_error);
+    }
+
+    game.cache_normalized_weights();
+    let player = game.current_player();
+    let actions = game.available_actions();
+    let [bet_oop, bet_ip] = game.total_bet_amount();
+    let pot = game.tree_config().starting_pot + bet_oop + bet_ip;
+
+    writeln!(
+        out,
+        "Board: {} | Player: {} | Pot: {pot}",
+        board_string(game),
+        ["OOP", "IP"][player]
+    )
+    .map_err(write_error)?;
+
+    let mut header = format!("{:<6} {:>8}", "Hand", "Weight");
+    for &action in &actions {
+        header += &format!(" {:>8}", action_to_token(action));
+    }
+    if combos {
+        header += &format!(" {:>8} {:>8}", "EV", "Equity");
+    }
+    writeln!(out, "{header}").map_err(write_error)?;
+
+    let mut write_row = |hand: &str, weight: f32, frequencies: &[f32], extra: &str| {
+        let mut row = format!("{hand:<6} {weight:>8.3}");
+        for frequency in frequencies {
+            row += &format!(" {:>7.1}%", frequency * 100.0);
+        }
+        writeln!(out, "{row}{extra}").map_err(write_error)
+    };
+
+    if combos {
+        let hands = holes_to_strings(game.private_cards(player))?;
+        let weights = game.normalized_weights(player);
+        let strategy = game.strategy();
+        let expected_values = game.expected_values(player);
+        let equity = game.equity(player);
+        let num_hands = hands.len();
+
+        for (index, hand) in hands.iter().enumerate() {
+            if weights[index] == 0.0 {
+                continue;
+            }
+            let frequencies = (0..actions.len())
+                .map(|action| strategy[action * num_hands + index])
+                .collect::<Vec<_>>();
+            let extra = format!(
+                " {:>8.3} {:>7.1}%",
+                expected_values[index],
+                equity[index] * 100.0
+            );
+            write_row(hand, weights[index], &frequencies, &extra)?;
+        }
+    } else {
+        for class in game.strategy_by_class() {
+            if class.combos > 0.0 {
+                write_row(&class.label, class.combos, &class.frequencies, "")?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads the export options from the arguments.
+fn export_options(args: &mut Args) -> Result<ExportOptions, String> {
+    let mut options = ExportOptions {
+        lines: args.values(&["--line"]),
+        expected_values: !args.flag("--no-ev"),
+        equity: args.flag("--equity"),
+        ..Default::default()
+    };
+
+    let streets = args.values(&["--street"]);
+    if !streets.is_empty() {
+        options.streets = streets
+            .iter()
+            .map(|street| match street.to_lowercase().as_str() {
+                "flop" => Ok(BoardState::Flop),
+                "turn" => Ok(BoardState::Turn),
+                "river" => Ok(BoardState::River),
+                _ => Err(format!("Invalid street: {street}")),
+            })
+            .collect::<Result<_, _>>()?;
+    }
+
+    if let Some(precision) = args.value(&["--precision"]) {
+        options.precision = precision
+            .parse()
+            .map_err(|_| format!("Invalid precision: {precision}"))?;
+    }
+
+    Ok(options)
+}
+
+/// Exports the solved tree in the given format.
+fn export_command<W: Write>(
+    game: &mut PostFlopGame,
+    format: &str,
+    options: &ExportOptions,
+    out: &mut W,
+) -> Result<(), String> {
+    match format {
+        "json" => game.export_json(out, options)?,
+        "csv" => game.export_csv(out, options)?,
+        _ => return Err(format!("Invalid format: {format} (expected json or csv)")),
+    }
+    out.flush().map_err(write_error)
+}
+
+/// Prints the aggregated statistics of the nodes below the node reached by the line.
+fn report_command<W: Write>(
+    game: &mut PostFlopGame,
+    line: &str,
+    out: &mut W,
+) -> Result<(), String> {
+    apply_line(game, line)?;
+
+    let tokens = |actions: &[Action]| {
+        let tokens = actions.iter().map(|&a| action_to_token(a));
+        tokens.collect::<Vec<_>>().join(" ")
+    };
+    let frequencies = |actions: &[Action], frequencies: &[f32]| {
+        let iter = actions.iter().zip(frequencies);
+        iter.map(|(&a, f)| format!("{} {:.1}%", action_to_token(a), f * 100.0))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    writeln!(
+        out,
+        "{:<24} {:<6} {:>6} {:>9} {:>9} {:>7} {:>7} {:>9} {:>9}  Frequencies",
+        "Line", "Player", "Pot", "OOP comb", "IP comb", "OOP eq", "IP eq", "OOP EV", "IP EV"
+    )
+    .map_err(write_error)?;
+
+    if game.is_chance_node() {
+        let line = tokens(&game.history_actions());
+        for report in game.runout_report() {
+            let card = card_to_string(report.card)?;
+            let player = report.player.map_or("-", |player| ["OOP", "IP"][player]);
+            writeln!(
+                out,
+                "{:<24} {:<6} {:>6} {:>9.2} {:>9.2} {:>6.1}% {:>6.1}% {:>9.3} {:>9.3}  {}",
+                format!("{line} {card}").trim_start(),
+                player,
+                "",
+                report.combos[0],
+                report.combos[1],
+                report.equity[0] * 100.0,
+                report.equity[1] * 100.0,
+                report.expected_value[0],
+                report.expected_value[1],
+                frequencies(&report.actions, &report.frequencies),
+            )
+            .map_err(write_error)?;
+        }
+        return Ok(());
+    }
+
+    let street = [BoardState::Flop, BoardState::Turn, BoardState::River];
+    let street = street[game.current_board().len() - 3];
+    for report in game.line_report(Some(street)) {
+        writeln!(
+            out,
+            "{:<24} {:<6} {:>6} {:>9.2} {:>9.2} {:>6.1}% {:>6.1}% {:>9.3} {:>9.3}  {}",
+            tokens(&report.line),
+            ["OOP", "IP"][report.player],
+            report.pot,
+            report.combos[0],
+            report.combos[1],
+            report.equity[0] * 100.0,
+            report.equity[1] * 100.0,
+            report.expected_value[0],
+            report.expected_value[1],
+            frequencies(&report.actions, &report.frequencies),
+        )
+        .map_err(write_error)?;
+    }
+
+    Ok(())
+}
+
+fn write_error(e: io::Error) -> String {
+    format!("Failed to write output: {e}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPOT: &str = r#"
+        oop_range = "AA,KK,QQ,AKs"
+        ip_range = "QQ-JJ,AQs,KQs"
+        board = "Td9d6h Qc 2s"
+        starting_pot = 100
+        effective_stack = 150
+        bet_sizes = "50%"
+        raise_sizes = "a"
+        rake_rate = 0.05
+        rake_cap = 3
+
+        [river]
+        ip_bet_sizes = "100%"
+        donk_sizes = "30%"
+
+        [solver]
+        max_iterations = 100
+        target_exploitability = 1.0
+    "#;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn spot_file() {
+        let (card_config, tree_config, solver_config) = parse_spot(SPOT).unwrap();
+        assert_eq!(card_config.flop, flop_from_str("Td9d6h").unwrap());
+        assert_eq!(card_config.river, card_from_str("2s").unwrap());
+        assert_eq!(tree_config.initial_state, BoardState::River);
+        assert_eq!(tree_config.rake_cap, 3.0);
+        assert_eq!(
+            tree_config.river_bet_sizes[0],
+            BetSizeOptions::try_from(("50%", "a")).unwrap()
+        );
+        assert_eq!(
+            tree_config.river_bet_sizes[1],
+            BetSizeOptions::try_from(("100%", "a")).unwrap()
+        );
+        assert!(tree_config.river_donk_sizes.is_some());
+        assert!(tree_config.turn_donk_sizes.is_none());
+        assert_eq!(solver_config.max_iterations, 100);
+
+        let error = |text: &str| parse_spot(text).unwrap_err();
+        assert_eq!(
+            error(&SPOT.replace("rake_cap", "rake_caps")),
+            "Unknown key in spot file: rake_caps"
+        );
+        assert_eq!(
+            error(&SPOT.replace("donk_sizes", "donk")),
+            "Unknown key in [river]: donk"
+        );
+        assert_eq!(
+            error(&SPOT.replace("starting_pot = 100", "starting_pot = \"100\"")),
+            "Value of starting_pot must be an integer, found string"
+        );
+        assert!(error(&SPOT.replace("\"50%\"", "\"50\"")).contains("50"));
+    }
+
+    #[test]
+    fn arguments() {
+        let mut args = Args::parse(&strings(&[
+            "game.bin", "--line", "X", "-o", "out.csv", "--line", "B50", "--equity",
+        ]))
+        .unwrap();
+        assert_eq!(args.positional(0, "game file").unwrap(), "game.bin");
+        assert_eq!(args.values(&["--line"]), ["X", "B50"]);
+        assert_eq!(args.value(&["-o", "--output"]).unwrap(), "out.csv");
+        assert!(args.finish(1).is_err());
+        assert!(args.flag("--equity"));
+        assert!(args.finish(1).is_ok());
+        assert!(args.finish(0).is_err());
+
+        assert!(Args::parse(&strings(&["--precision"])).is_err());
+        assert!(run(&strings(&["solve"])).is_err());
+        assert!(run(&strings(&["unknown"])).is_err());
+    }
+
+    #[test]
+    fn commands() {
+        let dir = std::env::temp_dir().join(format!("postflop-cli-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let spot_path = dir.join("spot.toml");
+        std::fs::write(&spot_path, SPOT).unwrap();
+
+        let game_path = dir.join("spot.bin");
+        let mut out = Vec::new();
+        solve_command(
+            spot_path.to_str().unwrap(),
+            game_path.to_str().unwrap(),
+            &mut out,
+        )
+        .unwrap();
+        assert!(String::from_utf8(out).unwrap().contains("Saved"));
+
+        let mut game = load_game(game_path.to_str().unwrap()).unwrap();
+
+        let mut out = Vec::new();
+        query_command(&mut game, "", false, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("Board: Td9d6h Qc 2s | Player: OOP | Pot: 100"));
+        assert!(out.contains("AKs"));
+
+        let mut out = Vec::new();
+        query_command(&mut game, "X", true, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("Player: IP"));
+        assert!(out.contains("QdQc") || out.contains("JdJc"));
+
+        assert!(query_command(&mut game, "X X", false, &mut Vec::new()).is_err());
+        assert!(query_command(&mut game, "Z", false, &mut Vec::new()).is_err());
+
+        let mut out = Vec::new();
+        export_command(&mut game, "csv", &ExportOptions::default(), &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("line,board,player,hand"));
+        assert!(export_command(
+            &mut game,
+            "xml",
+            &ExportOptions::default(),
+            &mut out.into_bytes()
+        )
+        .is_err());
+
+        let mut out = Vec::new();
+        report_command(&mut game, "", &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(
+            out.lines().count(),
+            1 + game.line_report(Some(BoardState::River)).len()
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
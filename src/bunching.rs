@@ -1,12 +1,20 @@
 use crate::atomic_float::*;
 use crate::card::*;
+use crate::error::*;
+use crate::flop::*;
 use crate::range::*;
 use crate::utility::*;
 use std::io::{self, Write};
 use std::mem;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 #[cfg(feature = "bincode")]
-use bincode::{Decode, Encode};
+use bincode::{
+    de::Decoder,
+    enc::Encoder,
+    error::{DecodeError, EncodeError},
+    Decode, Encode,
+};
 
 #[cfg(feature = "rayon")]
 use rayon::prelude::*;
@@ -17,8 +25,17 @@ const COMB_49_3: usize = 18424;
 const COMB_49_4: usize = 211876;
 const COMB_49_5: usize = 1906884;
 const COMB_49_6: usize = 13983816;
+const COMB_49_7: usize = 85900584;
 const COMB_49_8: usize = 450978066;
 
+const COMB_49_UPTO_6: [usize; 7] = [
+    1, COMB_49_1, COMB_49_2, COMB_49_3, COMB_49_4, COMB_49_5, COMB_49_6,
+];
+
+// total number of subsets of size at most 6
+const NUM_SUBSETS_UPTO_6: usize =
+    1 + COMB_49_1 + COMB_49_2 + COMB_49_3 + COMB_49_4 + COMB_49_5 + COMB_49_6;
+
 const COMB_TABLE: [[usize; 49]; 8] = [
     [
         0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
@@ -81,7 +98,7 @@ const COMB_TABLE: [[usize; 49]; 8] = [
 /// # Examples
 ///
 /// ```no_run
-/// use postflop_solver::*;
+/// use postflop_solver_ffi::*;
 ///
 /// let utg_range_str = "...";
 /// let mp_range_str = "...";
@@ -89,7 +106,7 @@ const COMB_TABLE: [[usize; 49]; 8] = [
 /// let sb_range_str = "...";
 ///
 /// let mut data = BunchingData::new(
-///     // support up to 4 fold players
+///     // support up to 5 fold players
 ///     &[
 ///         utg_range_str.parse().unwrap(),
 ///         mp_range_str.parse().unwrap(),
@@ -118,7 +135,21 @@ const COMB_TABLE: [[usize; 49]; 8] = [
 /// | 2 | 1.77MB |
 /// | 3 | 123MB |
 /// | 4 | 3.42GB |
-#[cfg_attr(feature = "bincode", derive(Decode, Encode))]
+/// | 5 | 4.18GB |
+///
+/// **Warning**: With 5 folded players, the precomputation extends the 4-player table by the fifth
+/// range, which takes considerably more time and memory than the 4-player case.
+///
+/// # Serialization
+///
+/// If the `bincode` feature is enabled, the precomputed data can be saved by [`save_data_to_file`]
+/// and loaded by [`load_data_from_file`] (optionally with zstd compression if the `zstd` feature
+/// is enabled) to avoid repeating the precomputation.
+/// The loaded data can also be used for any suit-isomorphic flop (see [`suit_mapping_from`]).
+///
+/// [`save_data_to_file`]: crate::save_data_to_file
+/// [`load_data_from_file`]: crate::load_data_from_file
+/// [`suit_mapping_from`]: #method.suit_mapping_from
 pub struct BunchingData {
     // input
     fold_ranges: Vec<Range>,
@@ -134,7 +165,10 @@ pub struct BunchingData {
     temp_table3: Vec<AtomicF64>,
 
     // sums of each subset (computed in phase 2)
-    sum: [Vec<AtomicF64>; 7],
+    sum: [Vec<AtomicF64>; 8],
+
+    // sums of each subset including the fifth player (computed in phase 2)
+    next_sum: [Vec<AtomicF64>; 7],
 
     // inclusion-exclusion sums (computed in phase 3)
     result4: Vec<AtomicF32>,
@@ -185,33 +219,39 @@ fn compress_mask(mut mask: u64, flop: [Card; 3]) -> u64 {
 impl BunchingData {
     /// Creates a new `BunchingConfig` instance.
     ///
-    /// `fold_ranges` can contain at most 4 ranges (6-max).
+    /// `fold_ranges` can contain at most 5 ranges (7-max). Each range is the folding range of one
+    /// player, and its weights are treated as folding frequencies: for example, the weight `0.3`
+    /// of `76s` means that the player folds `76s` 30% of the time, and each of its combinations is
+    /// counted as 0.3 combinations.
     #[inline]
-    pub fn new(fold_ranges: &[Range], mut flop: [Card; 3]) -> Result<Self, String> {
+    pub fn new(fold_ranges: &[Range], mut flop: [Card; 3]) -> Result<Self, PostFlopError> {
         let mut fold_ranges_vec = Vec::new();
 
         for range in fold_ranges {
             if !range.is_empty() {
                 if !range.is_suit_symmetric() {
-                    return Err("Fold ranges must be suit-symmetric".to_string());
+                    return Err(PostFlopError::InvalidArgument(
+                        "Fold ranges must be suit-symmetric".to_string(),
+                    ));
                 }
                 fold_ranges_vec.push(*range);
             }
         }
 
         if fold_ranges_vec.is_empty() {
-            return Err("Fold ranges is empty".to_string());
+            return Err(PostFlopError::InvalidArgument(
+                "Fold ranges is empty".to_string(),
+            ));
         }
 
-        if fold_ranges_vec.len() > 4 {
-            return Err("The number of folded players must be at most 4".to_string());
+        if fold_ranges_vec.len() > 5 {
+            return Err(PostFlopError::InvalidArgument(
+                "The number of folded players must be at most 5".to_string(),
+            ));
         }
 
         flop.sort_unstable();
-
-        if flop[0] == flop[1] || flop[1] == flop[2] || flop[2] >= 52 {
-            return Err("Invalid flop".to_string());
-        }
+        check_sorted_flop(&flop)?;
 
         Ok(Self {
             fold_ranges: fold_ranges_vec,
@@ -222,6 +262,7 @@ impl BunchingData {
             temp_table2: Vec::new(),
             temp_table3: Vec::new(),
             sum: Default::default(),
+            next_sum: Default::default(),
             result4: Vec::new(),
             result5: Vec::new(),
             result6: Vec::new(),
@@ -240,6 +281,88 @@ impl BunchingData {
         self.flop
     }
 
+    /// Returns the suit mapping from the given flop to the flop of this instance.
+    ///
+    /// Since the fold ranges are suit-symmetric, the precomputed data can be reused for any
+    /// suit-isomorphic flop by mapping the suits of the cards. Returns an error if `flop` is not
+    /// suit-isomorphic to the flop of this instance.
+    pub fn suit_mapping_from(&self, mut flop: [Card; 3]) -> Result<[u8; 4], PostFlopError> {
+        flop.sort_unstable();
+        check_sorted_flop(&flop)?;
+
+        let (canonical_src, mapping_src) = canonicalize_flop(flop);
+        let (canonical_dst, mapping_dst) = canonicalize_flop(self.flop);
+        if canonical_src != canonical_dst {
+            return Err(PostFlopError::InvalidArgument(format!(
+                "Flop cards do not match: {} and {}",
+                flop.iter()
+                    .map(|&c| card_to_string(c))
+                    .collect::<Result<String, _>>()?,
+                self.flop
+                    .iter()
+                    .map(|&c| card_to_string(c))
+                    .collect::<Result<String, _>>()?,
+            )));
+        }
+
+        let mut inverse_dst = [0; 4];
+        for suit in 0..4 {
+            inverse_dst[mapping_dst[suit] as usize] = suit as u8;
+        }
+
+        Ok(mapping_src.map(|suit| inverse_dst[suit as usize]))
+    }
+
+    /// Returns a view of this instance specialized to the given board.
+    ///
+    /// `board` consists of 3 to 5 cards, and its first three cards must be suit-isomorphic to the
+    /// flop of this instance. Since the precomputed data covers all turn and river cards, the
+    /// specialization does not require any additional computation; build the flop-level data
+    /// once and specialize it for each runout.
+    /// The same applies to [`PostFlopGame::set_bunching_effect`]: a single instance can be
+    /// attached to games with any turn and river cards.
+    ///
+    /// [`PostFlopGame::set_bunching_effect`]: crate::PostFlopGame::set_bunching_effect
+    pub fn specialize(&self, board: &[Card]) -> Result<BunchingBoard<'_>, PostFlopError> {
+        if !self.is_ready() {
+            return Err(PostFlopError::InvalidState(
+                "Bunching configuration is not ready".to_string(),
+            ));
+        }
+
+        if !(3..=5).contains(&board.len()) {
+            return Err(PostFlopError::InvalidArgument(format!(
+                "Invalid board length: {}",
+                board.len()
+            )));
+        }
+
+        let mut board_mask: u64 = 0;
+        for &card in board {
+            if card >= 52 {
+                return Err(PostFlopError::InvalidCard {
+                    input: card.to_string(),
+                    reason: "Invalid board".to_string(),
+                });
+            }
+            if board_mask & (1 << card) != 0 {
+                return Err(PostFlopError::BoardConflict { card });
+            }
+            board_mask |= 1 << card;
+        }
+
+        let suit_mapping = self.suit_mapping_from([board[0], board[1], board[2]])?;
+        let flop_mask: u64 = board[..3].iter().map(|&c| 1 << c).sum();
+
+        Ok(BunchingBoard {
+            data: self,
+            board: board.to_vec(),
+            board_mask,
+            runout_mask: Self::map_mask(board_mask & !flop_mask, &suit_mapping),
+            suit_mapping,
+        })
+    }
+
     /// Returns whether the instance is ready to use.
     #[inline]
     pub fn is_ready(&self) -> bool {
@@ -270,7 +393,7 @@ impl BunchingData {
         sum += vec_memory_usage(&self.temp_table2);
         sum += vec_memory_usage(&self.temp_table3);
 
-        for vec in &self.sum {
+        for vec in self.sum.iter().chain(self.next_sum.iter()) {
             sum += vec_memory_usage(vec);
         }
 
@@ -289,6 +412,189 @@ impl BunchingData {
         self.phase3(print_progress);
     }
 
+    /// Processes all remaining phases while reporting the progress.
+    ///
+    /// `callback(phase, percent)` is called after each step, where `phase` and `percent` are the
+    /// values of [`phase`] and [`progress_percent`] at that time.
+    ///
+    /// `cancel` is checked before each step. If it is set to `true`, this method returns `Err`
+    /// without discarding the progress, and the processing can be resumed by calling this method
+    /// again.
+    ///
+    /// [`phase`]: #method.phase
+    /// [`progress_percent`]: #method.progress_percent
+    pub fn process_with_callback<F: FnMut(u8, u8)>(
+        &mut self,
+        cancel: &AtomicBool,
+        mut callback: F,
+    ) -> Result<(), PostFlopError> {
+        while !self.is_ready() {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(PostFlopError::Cancelled);
+            }
+
+            match (self.phase, self.progress_percent) {
+                (0, _) => self.phase1_prepare(),
+                (1, 100) => self.phase2_prepare(),
+                (2, 100) => self.phase3_prepare(),
+                (1, _) => self.phase1_proceed_by_percent(),
+                (2, _) => self.phase2_proceed_by_percent(),
+                _ => self.phase3_proceed_by_percent(),
+            }
+
+            callback(self.phase, self.progress_percent);
+        }
+
+        Ok(())
+    }
+
+    /// Approximately processes all phases by sampling the hands of the folded players.
+    ///
+    /// Instead of enumerating all hand combinations of the folded players, this method draws
+    /// `num_samples` combinations at random (deterministically for the given `seed`) and estimates
+    /// the number of combinations from them.
+    /// This is much faster and requires much less memory than [`process`] with 3 or more folded
+    /// players, at the cost of sampling noise: the relative error is roughly proportional to
+    /// `1 / sqrt(num_samples)`.
+    /// Therefore, this mode is suitable for exploratory solves, and [`process`] should be used for
+    /// final results.
+    ///
+    /// [`process`]: #method.process
+    #[inline]
+    pub fn process_approximate(
+        &mut self,
+        num_samples: usize,
+        seed: u64,
+        print_progress: bool,
+    ) -> Result<(), PostFlopError> {
+        if print_progress {
+            print!("Phase 1-2/3: Sampling...");
+            io::stdout().flush().unwrap();
+        }
+
+        self.phase12_sample(num_samples, seed)?;
+
+        if print_progress {
+            println!("\rPhase 1-2/3: Done.       ");
+        }
+
+        self.phase3(print_progress);
+        Ok(())
+    }
+
+    /// Manually processes the phases 1 and 2 approximately by sampling.
+    ///
+    /// After calling this method, the phase 3 can be processed as usual.
+    /// See [`process_approximate`] for details.
+    ///
+    /// [`process_approximate`]: #method.process_approximate
+    pub fn phase12_sample(&mut self, num_samples: usize, seed: u64) -> Result<(), PostFlopError> {
+        if self.phase != 0 {
+            panic!("Invalid state");
+        }
+
+        if num_samples == 0 {
+            return Err(PostFlopError::InvalidArgument(
+                "Number of samples must be positive".to_string(),
+            ));
+        }
+
+        // cumulative weights of each fold range
+        let hands = self
+            .fold_ranges
+            .iter()
+            .map(|range| {
+                let mut table = vec![0.0; COMB_49_2];
+                Self::phase1_compress(&mut table, range, self.flop);
+
+                let mut cum_freq = 0.0;
+                let mut ret = Vec::new();
+                for (index, &freq) in table.iter().enumerate() {
+                    if freq > 0.0 {
+                        cum_freq += freq;
+                        ret.push((cum_freq, index_to_mask(index, 2)));
+                    }
+                }
+                ret
+            })
+            .collect::<Vec<_>>();
+
+        let num_cards = 2 * self.fold_ranges.len();
+        let max_size = usize::min(num_cards, 6);
+
+        for k in 0..=max_size {
+            self.sum[k] = (0..COMB_49_UPTO_6[k])
+                .map(|_| AtomicF64::new(0.0))
+                .collect();
+        }
+
+        let num_ones = (0u32..(1 << num_cards))
+            .map(|i| i.count_ones() as usize)
+            .collect::<Vec<_>>();
+
+        let total_freq = hands
+            .iter()
+            .map(|list| list.last().map_or(0.0, |&(cum_freq, _)| cum_freq))
+            .product::<f64>();
+
+        const CHUNK_SIZE: usize = 10000;
+        let sum = &self.sum;
+
+        if total_freq > 0.0 {
+            into_par_iter(0..num_samples.div_ceil(CHUNK_SIZE)).for_each(|chunk| {
+                let mut rng = Rng::new(Rng::new(seed.wrapping_add(chunk as u64)).next_u64());
+                let chunk_size = usize::min(CHUNK_SIZE, num_samples - chunk * CHUNK_SIZE);
+
+                for _ in 0..chunk_size {
+                    let mut mask = 0;
+                    for list in &hands {
+                        let r = rng.next_f64() * list.last().unwrap().0;
+                        let index = list.partition_point(|&(cum_freq, _)| cum_freq <= r);
+                        mask |= list[usize::min(index, list.len() - 1)].1;
+                    }
+
+                    // rejects overlapping hands
+                    if mask.count_ones() as usize != num_cards {
+                        continue;
+                    }
+
+                    let mut mask_copy = mask;
+                    let mut mask_bit = [0; 10];
+                    for bit in mask_bit.iter_mut().take(num_cards) {
+                        *bit = mask_copy & mask_copy.wrapping_neg();
+                        mask_copy ^= *bit;
+                    }
+
+                    for (i, &k) in num_ones.iter().enumerate() {
+                        if k > max_size {
+                            continue;
+                        }
+
+                        let mut sub_mask = 0;
+                        for (j, &bit) in mask_bit.iter().enumerate().take(num_cards) {
+                            if i & (1 << j) != 0 {
+                                sub_mask |= bit;
+                            }
+                        }
+
+                        sum[k][mask_to_index(sub_mask, k)].add(1.0);
+                    }
+                }
+            });
+        }
+
+        // rescales the sample counts to the (estimated) number of combinations
+        let scale = total_freq / num_samples as f64;
+        for vec in &self.sum[..=max_size] {
+            vec.iter().for_each(|x| x.store(x.load() * scale));
+        }
+
+        self.phase = 2;
+        self.progress_percent = 100;
+
+        Ok(())
+    }
+
     /// Processes the phase 1.
     #[inline]
     pub fn phase1(&mut self, print_progress: bool) {
@@ -399,10 +705,14 @@ impl BunchingData {
             self.sum[5] = (0..COMB_49_5).map(|_| AtomicF64::new(0.0)).collect();
         }
 
-        if self.fold_ranges.len() == 4 {
+        if self.fold_ranges.len() >= 4 {
             self.sum[6] = (0..COMB_49_6).map(|_| AtomicF64::new(0.0)).collect();
         }
 
+        if self.fold_ranges.len() == 5 {
+            self.sum[7] = (0..COMB_49_7).map(|_| AtomicF64::new(0.0)).collect();
+        }
+
         self.phase = 2;
         self.progress_percent = 0;
     }
@@ -451,18 +761,41 @@ impl BunchingData {
             panic!("Invalid state");
         }
 
+        let percent = self.progress_percent as usize;
+        let (start, end) = (percent, percent + 1);
+
         match self.fold_ranges.len() {
-            1 => self.phase2_process::<2>(),
-            2 => self.phase2_process::<4>(),
-            3 => self.phase2_process::<6>(),
-            _ => self.phase2_process::<8>(),
+            1 => self.phase2_process::<2>(COMB_49_2 * start / 100, COMB_49_2 * end / 100),
+            2 => self.phase2_process::<4>(COMB_49_4 * start / 100, COMB_49_4 * end / 100),
+            3 => self.phase2_process::<6>(COMB_49_6 * start / 100, COMB_49_6 * end / 100),
+            4 => self.phase2_process::<8>(COMB_49_8 * start / 100, COMB_49_8 * end / 100),
+            _ if percent < 50 => {
+                self.phase2_process::<8>(COMB_49_8 * start / 50, COMB_49_8 * end / 50);
+            }
+            _ => {
+                if percent == 50 {
+                    self.next_sum =
+                        COMB_49_UPTO_6.map(|len| (0..len).map(|_| AtomicF64::new(0.0)).collect());
+                }
+                let start = NUM_SUBSETS_UPTO_6 * (start - 50) / 50;
+                let end = NUM_SUBSETS_UPTO_6 * (end - 50) / 50;
+                self.phase2_extend(start, end);
+            }
         }
 
         self.progress_percent += 1;
 
-        if self.progress_percent == 100 && self.fold_ranges.len() == 4 {
+        if self.progress_percent == 100 && self.fold_ranges.len() >= 4 {
             self.temp_table3 = Vec::new();
         }
+
+        if self.progress_percent == 100 && self.fold_ranges.len() == 5 {
+            let next_sum = mem::take(&mut self.next_sum);
+            for (dst, src) in self.sum.iter_mut().zip(next_sum) {
+                *dst = src;
+            }
+            self.sum[7] = Vec::new();
+        }
     }
 
     /// Manually proceeds the phase 3 by one percent.
@@ -493,6 +826,24 @@ impl BunchingData {
         }
     }
 
+    /// Maps the card mask of a suit-isomorphic flop by [`suit_mapping_from`].
+    ///
+    /// [`suit_mapping_from`]: #method.suit_mapping_from
+    #[inline]
+    pub(crate) fn map_mask(mut mask: u64, suit_mapping: &[u8; 4]) -> u64 {
+        if *suit_mapping == [0, 1, 2, 3] {
+            return mask;
+        }
+
+        let mut ret = 0;
+        while mask != 0 {
+            let card = mask.trailing_zeros() as Card;
+            ret |= 1 << map_card_suit(card, suit_mapping);
+            mask &= mask - 1;
+        }
+        ret
+    }
+
     pub(crate) fn result_4cards(&self, mask: u64) -> f32 {
         let index = mask_to_index(compress_mask(mask, self.flop), 4);
         self.result4[index].load()
@@ -694,17 +1045,17 @@ impl BunchingData {
 
     /* Phase 2: Main process */
 
-    fn phase2_process<const K: usize>(&mut self) {
-        let (src_len, src_table) = match K {
-            2 => (COMB_49_2, &self.sum[2]),
-            4 => (COMB_49_4, &self.sum[4]),
-            6 => (COMB_49_6, &self.sum[6]),
-            8 => (COMB_49_8, &self.temp_table3),
+    fn phase2_process<const K: usize>(&mut self, start_index: usize, end_index: usize) {
+        let src_table = match K {
+            2 => &self.sum[2],
+            4 => &self.sum[4],
+            6 => &self.sum[6],
+            8 => &self.temp_table3,
             _ => unreachable!(),
         };
 
-        let start_index = (src_len as f64 * self.progress_percent as f64 / 100.0) as usize;
-        let end_index = (src_len as f64 * (self.progress_percent + 1) as f64 / 100.0) as usize;
+        // the fifth player requires the sums of 7-card subsets of the 4-player table
+        let max_size = if self.fold_ranges.len() == 5 { 7 } else { 6 };
 
         let num_ones = (0u32..(1 << K) - 1)
             .map(|i| i.count_ones() as u8)
@@ -733,7 +1084,7 @@ impl BunchingData {
                     }
 
                     for i in 0..(1 << K) - 1 {
-                        if num_ones[i] > 6 {
+                        if num_ones[i] > max_size {
                             continue;
                         }
 
@@ -751,6 +1102,74 @@ impl BunchingData {
             });
     }
 
+    /* Phase 2: Extension to the fifth player */
+
+    fn phase2_extend(&mut self, start_index: usize, end_index: usize) {
+        let mut table = vec![0.0; COMB_49_2];
+        Self::phase1_compress(&mut table, &self.fold_ranges[4], self.flop);
+
+        let hands = table
+            .iter()
+            .enumerate()
+            .filter(|&(_, &freq)| freq > 0.0)
+            .map(|(index, &freq)| (index_to_mask(index, 2), freq))
+            .collect::<Vec<_>>();
+
+        let lookup = |mask: u64| {
+            let k = mask.count_ones() as usize;
+            let index = mask_to_index(mask, k);
+            match k {
+                8 => self.temp_table3[index].load(),
+                _ => self.sum[k][index].load(),
+            }
+        };
+
+        Self::extend_sums(&self.next_sum, lookup, &hands, start_index, end_index);
+    }
+
+    /// Adds one player to the subset sums.
+    ///
+    /// `lookup(mask)` returns the subset sum of the current players, and `hands` is the list of
+    /// hands of the new player with their weights. The result for the subsets of size at most 6
+    /// is stored in `dst` for the flattened index range `start_index..end_index`.
+    ///
+    /// The disjointness of the new hand `h` and the current cards `D` is expanded by the
+    /// inclusion-exclusion principle: `[D & h == 0] = sum_{T subset of D & h} (-1)^|T|`.
+    fn extend_sums(
+        dst: &[Vec<AtomicF64>; 7],
+        lookup: impl Fn(u64) -> f64 + Sync,
+        hands: &[(u64, f64)],
+        start_index: usize,
+        end_index: usize,
+    ) {
+        let mut offset = 0;
+
+        for (k, &len) in COMB_49_UPTO_6.iter().enumerate() {
+            let start = usize::max(start_index, offset);
+            let end = usize::min(end_index, offset + len);
+
+            if start < end {
+                into_par_iter(start - offset..end - offset).for_each(|index| {
+                    let mask = index_to_mask(index, k);
+                    let mut result = 0.0;
+
+                    for &(hand, freq) in hands {
+                        let base = mask & !hand;
+                        let card1 = hand & hand.wrapping_neg();
+                        let card2 = hand ^ card1;
+                        result += freq
+                            * (lookup(base) - lookup(base | card1) - lookup(base | card2)
+                                + lookup(base | hand));
+                    }
+
+                    dst[k][index].store(result);
+                });
+            }
+
+            offset += len;
+        }
+    }
+
     /* Phase 3: Main process */
 
     fn phase3_process<const N: usize>(&mut self, start_index: usize, end_index: usize) {
@@ -808,6 +1227,133 @@ impl BunchingData {
     }
 }
 
+/// A view of [`BunchingData`] specialized to a board, obtained by [`BunchingData::specialize`].
+pub struct BunchingBoard<'a> {
+    data: &'a BunchingData,
+    board: Vec<Card>,
+    board_mask: u64,
+    runout_mask: u64,
+    suit_mapping: [u8; 4],
+}
+
+impl<'a> BunchingBoard<'a> {
+    /// Returns the underlying data.
+    #[inline]
+    pub fn data(&self) -> &BunchingData {
+        self.data
+    }
+
+    /// Returns the board.
+    #[inline]
+    pub fn board(&self) -> &[Card] {
+        &self.board
+    }
+
+    /// Returns the weighted number of hand combinations of the folded players when the two
+    /// remaining players hold `hand1` and `hand2`.
+    ///
+    /// Returns `0.0` if the hands conflict with each other or with the board.
+    #[inline]
+    pub fn num_combinations(&self, hand1: (Card, Card), hand2: (Card, Card)) -> f32 {
+        let cards = [hand1.0, hand1.1, hand2.0, hand2.1];
+        let mut hand_mask: u64 = 0;
+        for card in cards {
+            if card >= 52 || (self.board_mask | hand_mask) & (1 << card) != 0 {
+                return 0.0;
+            }
+            hand_mask |= 1 << card;
+        }
+
+        let mask = BunchingData::map_mask(hand_mask, &self.suit_mapping) | self.runout_mask;
+        match self.board.len() {
+            3 => self.data.result_4cards(mask),
+            4 => self.data.result_5cards(mask),
+            _ => self.data.result_6cards(mask),
+        }
+    }
+}
+
+#[cfg(feature = "bincode")]
+impl Encode for BunchingData {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        // the 5-player extension is only in progress during the phase 2
+        if !self.sum[7].is_empty() || self.next_sum.iter().any(|s| !s.is_empty()) {
+            return Err(EncodeError::Other(
+                "Cannot encode 5-player bunching data in phase 2",
+            ));
+        }
+
+        // keep the layout compatible with the 4-player version
+        self.fold_ranges.encode(encoder)?;
+        self.flop.encode(encoder)?;
+        self.phase.encode(encoder)?;
+        self.progress_percent.encode(encoder)?;
+        self.temp_table1.encode(encoder)?;
+        self.temp_table2.encode(encoder)?;
+        self.temp_table3.encode(encoder)?;
+        for sum in &self.sum[..7] {
+            sum.encode(encoder)?;
+        }
+        self.result4.encode(encoder)?;
+        self.result5.encode(encoder)?;
+        self.result6.encode(encoder)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "bincode")]
+impl<C> Decode<C> for BunchingData {
+    fn decode<D: Decoder<Context = C>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        let fold_ranges: Vec<Range> = Decode::decode(decoder)?;
+        let flop: [Card; 3] = Decode::decode(decoder)?;
+
+        if fold_ranges.is_empty() || fold_ranges.len() > 5 {
+            return Err(DecodeError::Other("Invalid number of fold ranges"));
+        }
+
+        if !(flop[0] < flop[1] && flop[1] < flop[2] && flop[2] < 52) {
+            return Err(DecodeError::Other("Invalid flop"));
+        }
+
+        let mut ret = Self {
+            fold_ranges,
+            flop,
+            phase: Decode::decode(decoder)?,
+            progress_percent: Decode::decode(decoder)?,
+            temp_table1: Decode::decode(decoder)?,
+            temp_table2: Decode::decode(decoder)?,
+            temp_table3: Decode::decode(decoder)?,
+            sum: Default::default(),
+            next_sum: Default::default(),
+            result4: Vec::new(),
+            result5: Vec::new(),
+            result6: Vec::new(),
+        };
+
+        for sum in &mut ret.sum[..7] {
+            *sum = Decode::decode(decoder)?;
+        }
+
+        ret.result4 = Decode::decode(decoder)?;
+        ret.result5 = Decode::decode(decoder)?;
+        ret.result6 = Decode::decode(decoder)?;
+
+        if ret.is_ready()
+            && (ret.result4.len() != COMB_49_4
+                || ret.result5.len() != COMB_49_5
+                || ret.result6.len() != COMB_49_6)
+        {
+            return Err(DecodeError::Other("Invalid result table"));
+        }
+
+        Ok(ret)
+    }
+}
+
+#[cfg(feature = "bincode")]
+bincode::impl_borrow_decode!(BunchingData);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1014,6 +1560,204 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bunching_weighted() {
+        let range1 = "AA:0.5,KK";
+        let range2 = "QQ:0.25";
+
+        let mut bunching = BunchingData::new(
+            &[range1.parse().unwrap(), range2.parse().unwrap()],
+            flop_from_str("4d3h2s").unwrap(),
+        )
+        .unwrap();
+
+        bunching.phase1(false);
+        bunching.phase2(false);
+
+        assert_eq!(bunching.sum[0][0].load(), (6.0 * 0.5 + 6.0) * (6.0 * 0.25));
+
+        bunching.phase3(false);
+
+        // dead: Ac, Kc, Qc, Qd
+        let mask = (1 << 48) | (1 << 44) | (1 << 40) | (1 << 41);
+        assert_eq!(bunching.result_4cards(mask), (3.0 * 0.5 + 3.0) * 0.25);
+
+        // dead: Ac, Ad, Ah, Kc, Qc
+        let mask = (1 << 48) | (1 << 49) | (1 << 50) | (1 << 44) | (1 << 40);
+        assert_eq!(bunching.result_5cards(mask), 3.0 * (3.0 * 0.25));
+    }
+
+    #[test]
+    fn test_specialize() {
+        let range = "22+,A2s+,K9s+,QTs+,A5o+,KTo+".parse::<Range>().unwrap();
+        let flop = flop_from_str("Td9d6h").unwrap();
+        let mut bunching = BunchingData::new(&[range], flop).unwrap();
+        bunching.process(false);
+
+        // brute-force count of the folded player's combinations
+        let count = |dead: &[Card]| {
+            let dead_mask: u64 = dead.iter().map(|&c| 1 << c).sum();
+            range
+                .get_hands_weights(dead_mask)
+                .1
+                .iter()
+                .map(|&w| w as f64)
+                .sum::<f64>() as f32
+        };
+
+        let hands = [
+            (
+                holes_from_str("AhKh").unwrap()[0],
+                holes_from_str("QcQs").unwrap()[0],
+            ),
+            (
+                holes_from_str("Td2c").unwrap()[0],
+                holes_from_str("AsAd").unwrap()[0],
+            ),
+            (
+                holes_from_str("9c8c").unwrap()[0],
+                holes_from_str("7d6d").unwrap()[0],
+            ),
+        ];
+
+        for board in ["Td9d6h", "Td9d6hAc", "Td9d6hAcKd", "Th9h6dKh2s"] {
+            let board = (0..board.len() / 2)
+                .map(|i| card_from_str(&board[2 * i..2 * i + 2]).unwrap())
+                .collect::<Vec<_>>();
+            let specialized = bunching.specialize(&board).unwrap();
+
+            for &(hand1, hand2) in &hands {
+                let mut dead = board.clone();
+                dead.extend([hand1.0, hand1.1, hand2.0, hand2.1]);
+                let expected = if (1..dead.len()).any(|i| dead[..i].contains(&dead[i])) {
+                    0.0
+                } else {
+                    count(&dead)
+                };
+                assert_eq!(specialized.num_combinations(hand1, hand2), expected);
+            }
+        }
+
+        assert!(bunching
+            .specialize(&flop_from_str("Td9h6d").unwrap())
+            .is_err());
+    }
+
+    #[test]
+    fn test_process_with_callback() {
+        let range = "22+,A2+,K9+".parse().unwrap();
+        let flop = flop_from_str("Td9d6h").unwrap();
+        let mut bunching = BunchingData::new(&[range], flop).unwrap();
+
+        let cancel = AtomicBool::new(false);
+        let mut history = Vec::new();
+        let result = bunching.process_with_callback(&cancel, |phase, percent| {
+            history.push((phase, percent));
+            if (phase, percent) == (2, 50) {
+                cancel.store(true, Ordering::Relaxed);
+            }
+        });
+
+        assert!(result.is_err());
+        assert_eq!(history.len(), 152);
+        assert_eq!(history[0], (1, 0));
+        assert_eq!(history[101], (2, 0));
+        assert_eq!((bunching.phase(), bunching.progress_percent()), (2, 50));
+
+        cancel.store(false, Ordering::Relaxed);
+        let mut num_calls = 0;
+        bunching
+            .process_with_callback(&cancel, |_, _| num_calls += 1)
+            .unwrap();
+
+        assert!(bunching.is_ready());
+        assert_eq!(num_calls, 50 + 101);
+
+        let mut expected = BunchingData::new(&[range], flop).unwrap();
+        expected.process(false);
+
+        for (a, b) in bunching.result6.iter().zip(expected.result6.iter()) {
+            assert_eq!(a.load(), b.load());
+        }
+    }
+
+    #[test]
+    fn test_process_approximate() {
+        let range1 = "22+,A2+,K2+,Q2+,J5+,T7+,97+,87";
+        let range2 = "55+,A8+,KT+,QT+,JT";
+        let flop = flop_from_str("QsJh2h").unwrap();
+        let fold_ranges = [range1.parse().unwrap(), range2.parse().unwrap()];
+
+        let mut exact = BunchingData::new(&fold_ranges, flop).unwrap();
+        let mut approximate = BunchingData::new(&fold_ranges, flop).unwrap();
+
+        exact.process(false);
+        approximate.process_approximate(1000000, 0, false).unwrap();
+        assert!(approximate.is_ready());
+
+        let mut max_error = 0.0f32;
+        for (a, b) in approximate.result6.iter().zip(exact.result6.iter()) {
+            if b.load() > 0.0 {
+                max_error = max_error.max((a.load() / b.load() - 1.0).abs());
+            }
+        }
+
+        assert!(max_error < 0.02, "max_error = {max_error}");
+    }
+
+    #[test]
+    fn test_extend_sums() {
+        let range1 = "AA,KK,AK";
+        let range2 = "KK,QQ,KQ,AK";
+        let range3 = "AK,AQ,QQ,JJ";
+        let flop = flop_from_str("Ks8h2d").unwrap();
+
+        let mut bunching2 =
+            BunchingData::new(&[range1.parse().unwrap(), range2.parse().unwrap()], flop).unwrap();
+
+        let mut bunching3 = BunchingData::new(
+            &[
+                range1.parse().unwrap(),
+                range2.parse().unwrap(),
+                range3.parse().unwrap(),
+            ],
+            flop,
+        )
+        .unwrap();
+
+        bunching2.phase1(false);
+        bunching2.phase2(false);
+        bunching3.phase1(false);
+        bunching3.phase2(false);
+
+        let mut table = vec![0.0; COMB_49_2];
+        BunchingData::phase1_compress(&mut table, &range3.parse().unwrap(), bunching3.flop);
+        let hands = table
+            .iter()
+            .enumerate()
+            .filter(|&(_, &freq)| freq > 0.0)
+            .map(|(index, &freq)| (index_to_mask(index, 2), freq))
+            .collect::<Vec<_>>();
+
+        let lookup = |mask: u64| {
+            let k = mask.count_ones() as usize;
+            match k {
+                0..=4 => bunching2.sum[k][mask_to_index(mask, k)].load(),
+                _ => 0.0,
+            }
+        };
+
+        let dst = COMB_49_UPTO_6.map(|len| (0..len).map(|_| AtomicF64::new(0.0)).collect());
+        BunchingData::extend_sums(&dst, lookup, &hands, 0, NUM_SUBSETS_UPTO_6);
+
+        // equality is exact because the result is an integer (< 2^53)
+        for k in 0..=6 {
+            for (a, b) in dst[k].iter().zip(bunching3.sum[k].iter()) {
+                assert_eq!(a.load(), b.load());
+            }
+        }
+    }
+
     #[test]
     #[ignore]
     fn test_bunching_independent_4() {
@@ -1062,6 +1806,56 @@ mod tests {
         );
     }
 
+    #[test]
+    #[ignore]
+    fn test_bunching_independent_5() {
+        let range1 = "33,32,22";
+        let range2 = "55,54,44";
+        let range3 = "77,76,66";
+        let range4 = "TT,T9,99";
+        let range5 = "QQ,QJ,JJ";
+
+        let mut bunching = BunchingData::new(
+            &[
+                range1.parse().unwrap(),
+                range2.parse().unwrap(),
+                range3.parse().unwrap(),
+                range4.parse().unwrap(),
+                range5.parse().unwrap(),
+            ],
+            flop_from_str("8s8h8d").unwrap(),
+        )
+        .unwrap();
+
+        bunching.phase1(true);
+        bunching.phase2(true);
+
+        assert_eq!(bunching.sum[0][0].load(), f64::powi(8.0 * 7.0 / 2.0, 5));
+
+        for (i, a) in bunching.sum[1].iter().enumerate() {
+            if i == 24 || i >= 41 {
+                assert_eq!(a.load(), 0.0); // 8c, Kx, Ax
+            } else {
+                assert_eq!(a.load(), f64::powi(8.0 * 7.0 / 2.0, 4) * 7.0);
+            }
+        }
+
+        bunching.phase3(true);
+
+        assert_eq!(
+            bunching.result4[0].load(),
+            (4.0 * 3.0 / 2.0) * f32::powi(8.0 * 7.0 / 2.0, 4)
+        );
+        assert_eq!(
+            bunching.result5[0].load(),
+            (3.0 * 2.0 / 2.0) * f32::powi(8.0 * 7.0 / 2.0, 4)
+        );
+        assert_eq!(
+            bunching.result6[0].load(),
+            (2.0 * 1.0 / 2.0) * f32::powi(8.0 * 7.0 / 2.0, 4)
+        );
+    }
+
     #[test]
     #[ignore]
     fn test_bunching_wizard() {
@@ -1,7 +1,11 @@
 use crate::hand::*;
 use crate::range::*;
+use crate::utility::*;
 use std::mem;
 
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
 #[cfg(feature = "bincode")]
 use bincode::{Decode, Encode};
 
@@ -20,7 +24,7 @@ pub const NOT_DEALT: Card = Card::MAX;
 ///
 /// # Examples
 /// ```
-/// use postflop_solver::*;
+/// use postflop_solver_ffi::*;
 ///
 /// let oop_range = "66+,A8s+,A5s-A4s,AJo+,K9s+,KQo,QTs+,JTs,96s+,85s+,75s+,65s,54s";
 /// let ip_range = "QQ-22,AQs-A2s,ATo+,K5s+,KJo+,Q8s+,J8s+,T7s+,96s+,86s+,75s+,64s+,53s+";
@@ -116,30 +120,34 @@ impl CardConfig {
             Indices::default()
         };
 
-        let mut ret_turn = vec![Indices::default(); 52];
-        for board in 0..52 {
-            if !self.flop.contains(&board)
-                && (self.turn == NOT_DEALT || self.turn == board)
-                && self.river == NOT_DEALT
-            {
-                ret_turn[board as usize] =
-                    Self::valid_indices_internal(private_cards, board, NOT_DEALT);
-            }
-        }
+        let ret_turn = into_par_iter(0..52)
+            .map(|board| {
+                let board = board as Card;
+                if !self.flop.contains(&board)
+                    && (self.turn == NOT_DEALT || self.turn == board)
+                    && self.river == NOT_DEALT
+                {
+                    Self::valid_indices_internal(private_cards, board, NOT_DEALT)
+                } else {
+                    Indices::default()
+                }
+            })
+            .collect::<Vec<_>>();
 
-        let mut ret_river = vec![Indices::default(); 52 * 51 / 2];
-        for board1 in 0..52 {
-            for board2 in board1 + 1..52 {
+        let ret_river = into_par_iter(0..52 * 51 / 2)
+            .map(|index| {
+                let (board1, board2) = index_to_card_pair(index);
                 if !self.flop.contains(&board1)
                     && !self.flop.contains(&board2)
                     && (self.turn == NOT_DEALT || board1 == self.turn || board2 == self.turn)
                     && (self.river == NOT_DEALT || board1 == self.river || board2 == self.river)
                 {
-                    let index = card_pair_to_index(board1, board2);
-                    ret_river[index] = Self::valid_indices_internal(private_cards, board1, board2);
+                    Self::valid_indices_internal(private_cards, board1, board2)
+                } else {
+                    Indices::default()
                 }
-            }
-        }
+            })
+            .collect::<Vec<_>>();
 
         (ret_flop, ret_turn, ret_river)
     }
@@ -184,64 +192,61 @@ impl CardConfig {
         &self,
         private_cards: &PrivateCards,
     ) -> Vec<[Vec<StrengthItem>; 2]> {
-        let mut ret = vec![Default::default(); 52 * 51 / 2];
-
         let mut board = Hand::new();
         for &card in &self.flop {
             board = board.add_card(card as usize);
         }
 
-        for board1 in 0..52 {
-            for board2 in board1 + 1..52 {
-                if !board.contains(board1 as usize)
-                    && !board.contains(board2 as usize)
-                    && (self.turn == NOT_DEALT || board1 == self.turn || board2 == self.turn)
-                    && (self.river == NOT_DEALT || board1 == self.river || board2 == self.river)
+        into_par_iter(0..52 * 51 / 2)
+            .map(|index| {
+                let (board1, board2) = index_to_card_pair(index);
+                if board.contains(board1 as usize)
+                    || board.contains(board2 as usize)
+                    || (self.turn != NOT_DEALT && board1 != self.turn && board2 != self.turn)
+                    || (self.river != NOT_DEALT && board1 != self.river && board2 != self.river)
                 {
-                    let board = board.add_card(board1 as usize).add_card(board2 as usize);
-                    let mut strength = [
-                        Vec::with_capacity(private_cards[0].len() + 2),
-                        Vec::with_capacity(private_cards[1].len() + 2),
-                    ];
-
-                    for player in 0..2 {
-                        // add the weakest and strongest sentinels
-                        strength[player].push(StrengthItem {
-                            strength: 0,
-                            index: 0,
-                        });
-                        strength[player].push(StrengthItem {
-                            strength: u16::MAX,
-                            index: u16::MAX,
-                        });
-
-                        strength[player].extend(
-                            private_cards[player].iter().enumerate().filter_map(
-                                |(index, &(c1, c2))| {
-                                    let (c1, c2) = (c1 as usize, c2 as usize);
-                                    if board.contains(c1) || board.contains(c2) {
-                                        None
-                                    } else {
-                                        let hand = board.add_card(c1).add_card(c2);
-                                        Some(StrengthItem {
-                                            strength: hand.evaluate() + 1, // +1 to avoid 0
-                                            index: index as u16,
-                                        })
-                                    }
-                                },
-                            ),
-                        );
-
-                        strength[player].shrink_to_fit();
-                        strength[player].sort_unstable();
-                    }
+                    return Default::default();
+                }
 
-                    ret[card_pair_to_index(board1, board2)] = strength;
+                let board = board.add_card(board1 as usize).add_card(board2 as usize);
+                let mut strength = [
+                    Vec::with_capacity(private_cards[0].len() + 2),
+                    Vec::with_capacity(private_cards[1].len() + 2),
+                ];
+
+                for player in 0..2 {
+                    // add the weakest and strongest sentinels
+                    strength[player].push(StrengthItem {
+                        strength: 0,
+                        index: 0,
+                    });
+                    strength[player].push(StrengthItem {
+                        strength: u16::MAX,
+                        index: u16::MAX,
+                    });
+
+                    strength[player].extend(private_cards[player].iter().enumerate().filter_map(
+                        |(index, &(c1, c2))| {
+                            let (c1, c2) = (c1 as usize, c2 as usize);
+                            if board.contains(c1) || board.contains(c2) {
+                                None
+                            } else {
+                                let hand = board.add_card(c1).add_card(c2);
+                                Some(StrengthItem {
+                                    strength: hand.evaluate() + 1, // +1 to avoid 0
+                                    index: index as u16,
+                                })
+                            }
+                        },
+                    ));
+
+                    strength[player].shrink_to_fit();
+                    strength[player].sort_unstable();
                 }
-            }
-        }
 
-        ret
+                strength
+            })
+            .collect()
     }
 
     pub(crate) fn isomorphism(&self, private_cards: &[Vec<(Card, Card)>; 2]) -> IsomorphismData {
@@ -393,8 +398,10 @@ impl CardConfig {
             for (i, &(c1, c2)) in cards.iter().enumerate() {
                 let c1 = replacer(c1);
                 let c2 = replacer(c2);
+                // the hand may be missing if the hands are merged by the abstraction, in which
+                // case the merged hand is mapped to itself
                 let index = reverse_table[card_pair_to_index(c1, c2)];
-                if i < index {
+                if i < index && index != usize::MAX {
                     swap_list[player].push((i as u16, index as u16));
                 }
             }
@@ -0,0 +1,212 @@
+use crate::action_tree::*;
+use crate::game::*;
+use crate::range::*;
+use crate::spot::*;
+
+/// Unit of the amounts formatted by [`DisplayConfig`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AmountUnit {
+    /// Raw chips (e.g., `"150"`).
+    #[default]
+    Chips,
+
+    /// Big blinds (e.g., `"1.5bb"`).
+    BigBlinds,
+
+    /// Percentage of the pot (e.g., `"75%"`).
+    PotPercent,
+}
+
+/// Configuration of the display format of actions and amounts.
+///
+/// The amounts of [`Action::Bet`], [`Action::Raise`], and [`Action::AllIn`] are the amounts put
+/// in on the street, so they are displayed as is in [`AmountUnit::Chips`] and
+/// [`AmountUnit::BigBlinds`]. In [`AmountUnit::PotPercent`], an action is displayed as the raise
+/// over the previous bet relative to the pot after calling, which is the same convention as the
+/// pot-relative bet sizes of [`BetSizeOptions`] (e.g., a `"75%"` bet is displayed as `75%`).
+///
+/// [`BetSizeOptions`]: crate::BetSizeOptions
+///
+/// # Examples
+/// ```
+/// use postflop_solver_ffi::*;
+///
+/// let config = DisplayConfig {
+///     unit: AmountUnit::BigBlinds,
+///     big_blind: 100,
+///     precision: 2,
+/// };
+/// assert_eq!(config.format_amount(250, 1000), "2.5bb");
+/// assert_eq!(config.format_action(Action::Bet(750), 1000, 0), "Bet 7.5bb");
+///
+/// let config = DisplayConfig {
+///     unit: AmountUnit::PotPercent,
+///     ..config
+/// };
+/// assert_eq!(config.format_action(Action::Bet(750), 1000, 0), "Bet 75%");
+/// assert_eq!(config.format_action(Action::Raise(2000), 2500, 500), "Raise 60%");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayConfig {
+    /// Unit of the amounts.
+    pub unit: AmountUnit,
+
+    /// Size of the big blind in chips, used by [`AmountUnit::BigBlinds`].
+    pub big_blind: i32,
+
+    /// Maximum number of digits after the decimal point. Trailing zeros are omitted.
+    pub precision: usize,
+}
+
+impl Default for DisplayConfig {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            unit: AmountUnit::Chips,
+            big_blind: SPOT_CHIPS_PER_BB,
+            precision: 1,
+        }
+    }
+}
+
+impl DisplayConfig {
+    /// Formats the given amount of chips. `pot` is the reference of [`AmountUnit::PotPercent`].
+    pub fn format_amount(&self, amount: i32, pot: i32) -> String {
+        match self.unit {
+            AmountUnit::Chips => amount.to_string(),
+            AmountUnit::BigBlinds => {
+                let value = amount as f64 / self.big_blind as f64;
+                format!("{}bb", self.format_number(value))
+            }
+            AmountUnit::PotPercent => {
+                let value = 100.0 * amount as f64 / pot as f64;
+                format!("{}%", self.format_number(value))
+            }
+        }
+    }
+
+    /// Formats the given action.
+    ///
+    /// `pot` is the pot size after calling the previous bet, and `prev_amount` is the amount of
+    /// the previous bet on the street (zero if there is none); they are used only by
+    /// [`AmountUnit::PotPercent`]. Dealt cards are displayed as the cards (e.g., `"5d"`).
+    pub fn format_action(&self, action: Action, pot: i32, prev_amount: i32) -> String {
+        let amount = |amount: i32| match self.unit {
+            AmountUnit::PotPercent => self.format_amount(amount - prev_amount, pot),
+            _ => self.format_amount(amount, pot),
+        };
+
+        match action {
+            Action::None => String::new(),
+            Action::Fold => "Fold".to_string(),
+            Action::Check => "Check".to_string(),
+            Action::Call => "Call".to_string(),
+            Action::Bet(a) => format!("Bet {}", amount(a)),
+            Action::Raise(a) => format!("Raise {}", amount(a)),
+            Action::AllIn(a) => format!("All-in {}", amount(a)),
+            Action::Chance(card) => card_to_string(card).unwrap(),
+        }
+    }
+
+    /// Formats the number with the precision, omitting trailing zeros.
+    fn format_number(&self, value: f64) -> String {
+        let precision = self.precision;
+        let mut s = format!("{value:.precision$}");
+        if s.contains('.') {
+            s = s.trim_end_matches('0').trim_end_matches('.').to_string();
+        }
+        if s == "-0" {
+            s.remove(0);
+        }
+        s
+    }
+}
+
+impl PostFlopGame {
+    /// Returns the available actions of the current node formatted by the given configuration
+    /// (see [`DisplayConfig::format_action`]).
+    ///
+    /// **Time complexity:** *O*(#(actions in history) + #(actions)).
+    pub fn display_actions(&self, config: &DisplayConfig) -> Vec<String> {
+        let [bet_oop, bet_ip] = self.total_bet_amount();
+        let pot = self.tree_config().starting_pot + 2 * bet_oop.max(bet_ip);
+
+        let prev_amount = self.last_bet_amount();
+
+        self.available_actions()
+            .into_iter()
+            .map(|action| config.format_action(action, pot, prev_amount))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::*;
+
+    #[test]
+    fn format_amount() {
+        let mut config = DisplayConfig::default();
+        assert_eq!(config.format_amount(150, 100), "150");
+
+        config.unit = AmountUnit::BigBlinds;
+        assert_eq!(config.format_amount(150, 100), "1.5bb");
+        assert_eq!(config.format_amount(200, 100), "2bb");
+        assert_eq!(config.format_amount(0, 100), "0bb");
+        assert_eq!(config.format_amount(133, 100), "1.3bb");
+
+        config.unit = AmountUnit::PotPercent;
+        config.precision = 2;
+        assert_eq!(config.format_amount(33, 100), "33%");
+        assert_eq!(config.format_amount(100, 300), "33.33%");
+    }
+
+    #[test]
+    fn display_actions() {
+        let card_config = CardConfig {
+            range: ["AA,KK".parse().unwrap(), "QQ,JJ".parse().unwrap()],
+            flop: flop_from_str("Td9d6h").unwrap(),
+            turn: card_from_str("2c").unwrap(),
+            river: card_from_str("3s").unwrap(),
+        };
+        let tree_config = TreeConfig {
+            initial_state: BoardState::River,
+            starting_pot: 200,
+            effective_stack: 1000,
+            river_bet_sizes: [
+                ("50%, a", "").try_into().unwrap(),
+                ("", "60%, a").try_into().unwrap(),
+            ],
+            ..Default::default()
+        };
+        let action_tree = ActionTree::new(tree_config).unwrap();
+        let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
+        game.allocate_memory(false, None).unwrap();
+
+        let mut config = DisplayConfig::default();
+        assert_eq!(
+            game.display_actions(&config),
+            ["Check", "Bet 100", "All-in 1000"]
+        );
+
+        config.unit = AmountUnit::BigBlinds;
+        assert_eq!(
+            game.display_actions(&config),
+            ["Check", "Bet 1bb", "All-in 10bb"]
+        );
+
+        config.unit = AmountUnit::PotPercent;
+        assert_eq!(
+            game.display_actions(&config),
+            ["Check", "Bet 50%", "All-in 500%"]
+        );
+
+        // pot after calling: 400, raise over the previous bet: 340 - 100 = 240
+        game.play(1);
+        assert_eq!(
+            game.display_actions(&config),
+            ["Fold", "Call", "Raise 60%", "All-in 225%"]
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use crate::card::*;
+use crate::game::LineError;
+use crate::range::*;
+use thiserror::Error;
+
+#[cfg(feature = "bincode")]
+use crate::file::LoadError;
+
+/// Error returned by the fallible operations of this crate.
+///
+/// The message of each variant is the same as the one of the former `String` errors, and
+/// `PostFlopError` converts into `String` for callers that only need the message.
+#[derive(Debug, Clone, PartialEq, Error)]
+#[non_exhaustive]
+pub enum PostFlopError {
+    /// A range string could not be parsed.
+    #[error("{reason}")]
+    InvalidRange {
+        /// The invalid comma-separated element of the range (e.g., `"AKx"`).
+        input: String,
+
+        /// Zero-based index of the invalid element in the range.
+        position: usize,
+
+        /// Description of the error.
+        reason: String,
+    },
+
+    /// A card string could not be parsed.
+    #[error("{reason}")]
+    InvalidCard {
+        /// The invalid input.
+        input: String,
+
+        /// Description of the error.
+        reason: String,
+    },
+
+    /// A card appears more than once on the board.
+    #[error("Duplicate card: {}", card_to_string(*card).unwrap_or_default())]
+    BoardConflict {
+        /// The conflicting card.
+        card: Card,
+    },
+
+    /// A hand string could not be parsed, or the hand is not available in the game.
+    #[error("{reason}")]
+    InvalidHand {
+        /// The invalid input (e.g., `"AhAh"`).
+        input: String,
+
+        /// Description of the error.
+        reason: String,
+    },
+
+    /// A line string could not be applied (see [`PostFlopGame::apply_line_str`]).
+    ///
+    /// [`PostFlopGame::apply_line_str`]: crate::PostFlopGame::apply_line_str
+    #[error(transparent)]
+    InvalidLine(LineError),
+
+    /// A bet size string could not be parsed.
+    #[error("{reason}")]
+    InvalidBetSize {
+        /// The invalid input.
+        input: String,
+
+        /// Description of the error.
+        reason: String,
+    },
+
+    /// The tree configuration or the card configuration is invalid.
+    #[error("{0}")]
+    InvalidConfig(String),
+
+    /// An argument is out of range or inconsistent with the game (e.g., an invalid player index).
+    #[error("{0}")]
+    InvalidArgument(String),
+
+    /// The operation is not available in the current state (e.g., the game is not solved, or the
+    /// current node is not a decision node).
+    #[error("{0}")]
+    InvalidState(String),
+
+    /// The operation is not supported by the game configuration or by the enabled features.
+    #[error("{0}")]
+    Unsupported(String),
+
+    /// The operation was cancelled by the caller.
+    #[error("Cancelled")]
+    Cancelled,
+
+    /// Reading or writing a file failed.
+    #[error("{0}")]
+    Io(String),
+
+    /// The estimated memory usage exceeds the given limit.
+    #[error("Memory usage exceeds the limit: {required} bytes (limit: {available} bytes)")]
+    MemoryLimit {
+        /// Estimated memory usage (in bytes).
+        required: u64,
+
+        /// Given limit (in bytes).
+        available: u64,
+    },
+
+    /// The memory could not be allocated.
+    #[error("Failed to allocate {required} bytes: {reason}")]
+    AllocationFailed {
+        /// Estimated memory usage (in bytes).
+        required: u64,
+
+        /// Description of the error.
+        reason: String,
+    },
+
+    /// Loading a saved game failed.
+    #[cfg(feature = "bincode")]
+    #[error(transparent)]
+    Load(LoadError),
+
+    /// Any other error.
+    #[error("{0}")]
+    Other(String),
+}
+
+impl From<String> for PostFlopError {
+    #[inline]
+    fn from(message: String) -> Self {
+        Self::Other(message)
+    }
+}
+
+impl From<&str> for PostFlopError {
+    #[inline]
+    fn from(message: &str) -> Self {
+        Self::Other(message.to_string())
+    }
+}
+
+impl From<PostFlopError> for String {
+    #[inline]
+    fn from(error: PostFlopError) -> Self {
+        error.to_string()
+    }
+}
+
+impl From<LineError> for PostFlopError {
+    #[inline]
+    fn from(error: LineError) -> Self {
+        Self::InvalidLine(error)
+    }
+}
+
+impl From<std::io::Error> for PostFlopError {
+    #[inline]
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error.to_string())
+    }
+}
+
+#[cfg(feature = "bincode")]
+impl From<LoadError> for PostFlopError {
+    #[inline]
+    fn from(error: LoadError) -> Self {
+        match error {
+            LoadError::MemoryLimitExceeded { estimated, limit } => Self::MemoryLimit {
+                required: estimated,
+                available: limit,
+            },
+            error => Self::Load(error),
+        }
+    }
+}
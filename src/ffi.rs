@@ -0,0 +1,1845 @@
+//! Handle-based C API.
+//!
+//! A game is created by [`psf_game_create`] and owned by the caller until it is released by
+//! [`psf_free`]. Functions that fail return a null pointer, a negative value, or NaN, and the error
+//! message and code can be obtained by [`psf_last_error`] and [`psf_last_error_code`].
+
+use crate::action_tree::*;
+use crate::analysis::*;
+use crate::card::*;
+use crate::error::*;
+#[cfg(feature = "bincode")]
+use crate::file::*;
+use crate::game::*;
+use crate::interface::*;
+use crate::range::*;
+use crate::solver::*;
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::ffi::{c_void, CStr, CString};
+#[cfg(feature = "bincode")]
+use std::fs::File;
+use std::io::{self, Write};
+#[cfg(feature = "bincode")]
+use std::io::{BufReader, BufWriter};
+use std::mem;
+use std::os::raw::{c_char, c_double, c_float, c_int, c_uint};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Configuration of a game passed to [`psf_game_create`].
+///
+/// All strings must be null-terminated UTF-8 strings. `turn`, `river`, `bet_sizes`, and
+/// `raise_sizes` may be null or empty.
+#[repr(C)]
+pub struct PsfGameConfig {
+    /// Range of OOP (e.g., `"66+,A8s+,AJo+"`).
+    pub oop_range: *const c_char,
+
+    /// Range of IP.
+    pub ip_range: *const c_char,
+
+    /// Flop cards (e.g., `"Td9d6h"`).
+    pub flop: *const c_char,
+
+    /// Turn card, if dealt.
+    pub turn: *const c_char,
+
+    /// River card, if dealt.
+    pub river: *const c_char,
+
+    /// Starting pot size.
+    pub starting_pot: c_int,
+
+    /// Initial effective stack.
+    pub effective_stack: c_int,
+
+    /// Bet sizes of all streets (see [`BetSizeOptions`](crate::BetSizeOptions)). If null or empty,
+    /// only all-in is used.
+    pub bet_sizes: *const c_char,
+
+    /// Raise sizes of all streets (see [`BetSizeOptions`](crate::BetSizeOptions)).
+    pub raise_sizes: *const c_char,
+
+    /// Whether to compress the storage (`0` = no, otherwise yes).
+    pub use_compression: u8,
+
+    /// Maximum number of threads used by [`psf_solve`] (`0` = the global thread pool, which uses
+    /// all cores). Ignored if the `rayon` feature is disabled.
+    pub max_threads: c_uint,
+
+    /// Maximum memory usage of the game in bytes (`0` = no limit). [`psf_game_create`] fails
+    /// without allocating the memory if the estimated memory usage exceeds it.
+    pub max_memory_bytes: u64,
+}
+
+/// Sizes of the results at the current node, obtained by [`psf_result_sizes`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PsfResultSizes {
+    /// Number of available actions (`0` at a terminal node). At a chance node, isomorphic cards
+    /// are grouped into one action.
+    pub num_actions: usize,
+
+    /// Number of private hands of each player (OOP, IP), i.e., the length of the per-hand
+    /// results.
+    pub num_private_hands: [usize; 2],
+
+    /// Length of the strategy (`0` unless the current node is a decision node).
+    pub strategy_len: usize,
+
+    /// Number of bytes per hand written by [`psf_get_hand_strings`].
+    pub hand_string_stride: usize,
+}
+
+/// Number of bytes per hand written by [`psf_get_hand_strings`].
+pub const PSF_HAND_STRING_STRIDE: usize = 5;
+
+/// [`PsfAction`] kind: Fold action.
+pub const PSF_ACTION_FOLD: c_int = 1;
+
+/// [`PsfAction`] kind: Check action.
+pub const PSF_ACTION_CHECK: c_int = 2;
+
+/// [`PsfAction`] kind: Call action.
+pub const PSF_ACTION_CALL: c_int = 3;
+
+/// [`PsfAction`] kind: Bet action with an amount.
+pub const PSF_ACTION_BET: c_int = 4;
+
+/// [`PsfAction`] kind: Raise action with an amount.
+pub const PSF_ACTION_RAISE: c_int = 5;
+
+/// [`PsfAction`] kind: All-in action with an amount.
+pub const PSF_ACTION_ALLIN: c_int = 6;
+
+/// [`PsfAction`] kind: Chance action with a card ID.
+pub const PSF_ACTION_CHANCE: c_int = 7;
+
+/// Action written by [`psf_available_actions`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PsfAction {
+    /// One of the `PSF_ACTION_*` constants (`0` if none).
+    pub kind: c_int,
+
+    /// Amount of a bet, raise, or all-in action, or the card ID of a chance action; `0` otherwise.
+    pub amount: c_int,
+}
+
+impl From<Action> for PsfAction {
+    #[inline]
+    fn from(action: Action) -> Self {
+        let (kind, amount) = match action {
+            Action::None => (0, 0),
+            Action::Fold => (PSF_ACTION_FOLD, 0),
+            Action::Check => (PSF_ACTION_CHECK, 0),
+            Action::Call => (PSF_ACTION_CALL, 0),
+            Action::Bet(amount) => (PSF_ACTION_BET, amount),
+            Action::Raise(amount) => (PSF_ACTION_RAISE, amount),
+            Action::AllIn(amount) => (PSF_ACTION_ALLIN, amount),
+            Action::Chance(card) => (PSF_ACTION_CHANCE, card as c_int),
+        };
+        Self { kind, amount }
+    }
+}
+
+/// Opaque handle of a game created by [`psf_game_create`].
+pub struct PsfGame {
+    game: PostFlopGame,
+    buffer: Vec<f32>,
+    #[cfg(feature = "rayon")]
+    pool: Option<rayon::ThreadPool>,
+    /// Locking strategies (`None` to unlock) to apply at the next solve, keyed by the history.
+    pending_locks: BTreeMap<Vec<usize>, Option<Vec<f32>>>,
+}
+
+impl PsfGame {
+    #[inline]
+    fn new(game: PostFlopGame) -> Self {
+        Self {
+            game,
+            buffer: Vec::new(),
+            #[cfg(feature = "rayon")]
+            pool: None,
+            pending_locks: BTreeMap::new(),
+        }
+    }
+}
+
+/// Error code: no error.
+pub const PSF_OK: c_int = 0;
+
+/// Error code: the call failed for a reason not covered by the other codes.
+pub const PSF_ERROR: c_int = -1;
+
+/// Error code: reading or writing a file failed.
+pub const PSF_ERROR_IO: c_int = -2;
+
+/// Error code: the file is not a game saved by this library.
+pub const PSF_ERROR_INVALID_FILE: c_int = -3;
+
+/// Error code: the file was saved by an incompatible version of this library.
+pub const PSF_ERROR_VERSION: c_int = -4;
+
+/// Error code: the file is corrupted.
+pub const PSF_ERROR_CORRUPTED: c_int = -5;
+
+/// Error code: the file or the game requires a feature that is not available (e.g., zstd
+/// compression).
+pub const PSF_ERROR_UNSUPPORTED: c_int = -6;
+
+/// Error code: a range or a hand could not be parsed.
+pub const PSF_ERROR_INVALID_RANGE: c_int = -7;
+
+/// Error code: a board card could not be parsed or appears more than once.
+pub const PSF_ERROR_INVALID_BOARD: c_int = -8;
+
+/// Error code: the bet sizes or the other game configuration are invalid.
+pub const PSF_ERROR_INVALID_CONFIG: c_int = -9;
+
+/// Error code: the estimated memory usage exceeds the given limit, or the memory cannot be
+/// allocated.
+pub const PSF_ERROR_MEMORY_LIMIT: c_int = -10;
+
+/// Error code: an argument is null, out of range, or not available at the current node (e.g., an
+/// invalid player or action).
+pub const PSF_ERROR_INVALID_ARGUMENT: c_int = -11;
+
+/// Error code: the call is not allowed in the current state (e.g., the game is not solved, or the
+/// current node is not a decision node).
+pub const PSF_ERROR_INVALID_STATE: c_int = -12;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+    static LAST_ERROR_CODE: Cell<c_int> = const { Cell::new(PSF_OK) };
+}
+
+/// Returns the error message of the last failed call on the current thread, or null if no call has
+/// failed.
+///
+/// The returned string is valid until the next failed call on the current thread.
+#[no_mangle]
+pub extern "C" fn psf_last_error() -> *const c_char {
+    LAST_ERROR.with(|error| error.borrow().as_ref().map_or(ptr::null(), |e| e.as_ptr()))
+}
+
+/// Returns the error code (`PSF_ERROR*`) of the last failed call on the current thread, or
+/// [`PSF_OK`] if no call has failed.
+#[no_mangle]
+pub extern "C" fn psf_last_error_code() -> c_int {
+    LAST_ERROR_CODE.with(Cell::get)
+}
+
+/// Creates a game with the given configuration and allocates its memory.
+///
+/// Returns null on failure; [`psf_last_error_code`] then tells whether a range
+/// ([`PSF_ERROR_INVALID_RANGE`]), the board ([`PSF_ERROR_INVALID_BOARD`]), or the other
+/// configuration ([`PSF_ERROR_INVALID_CONFIG`]) is invalid, or the memory limit is exceeded
+/// ([`PSF_ERROR_MEMORY_LIMIT`]). The returned handle must be released by [`psf_free`].
+///
+/// # Safety
+/// `config` must be null or point to a valid [`PsfGameConfig`].
+#[no_mangle]
+pub unsafe extern "C" fn psf_game_create(config: *const PsfGameConfig) -> *mut PsfGame {
+    let result = ffi_call(|| {
+        let config = unsafe { config.as_ref() }.ok_or_else(|| invalid_argument("Null config"))?;
+
+        let oop_range = unsafe { c_str(config.oop_range) }?.unwrap_or_default();
+        let ip_range = unsafe { c_str(config.ip_range) }?.unwrap_or_default();
+        let flop = unsafe { c_str(config.flop) }?.unwrap_or_default();
+        let turn = unsafe { c_str(config.turn) }?;
+        let river = unsafe { c_str(config.river) }?;
+        let bet_sizes = unsafe { c_str(config.bet_sizes) }?.unwrap_or_default();
+        let raise_sizes = unsafe { c_str(config.raise_sizes) }?.unwrap_or_default();
+
+        let mut game = build_game(
+            [oop_range, ip_range],
+            (flop, turn, river),
+            config.starting_pot,
+            config.effective_stack,
+            (bet_sizes, raise_sizes),
+        )?;
+
+        let use_compression = config.use_compression != 0;
+        let memory_limit = (config.max_memory_bytes > 0).then_some(config.max_memory_bytes);
+        game.allocate_memory(use_compression, memory_limit)?;
+
+        #[cfg(feature = "rayon")]
+        let pool = match config.max_threads {
+            0 => None,
+            num_threads => Some(
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(num_threads as usize)
+                    .build()
+                    .map_err(|e| {
+                        PostFlopError::Other(format!("Failed to create thread pool: {e}"))
+                    })?,
+            ),
+        };
+
+        let handle = PsfGame {
+            #[cfg(feature = "rayon")]
+            pool,
+            ..PsfGame::new(game)
+        };
+
+        Ok(Box::into_raw(Box::new(handle)))
+    });
+
+    result.unwrap_or(ptr::null_mut())
+}
+
+/// Progress callback of [`psf_solve`], called with the number of performed iterations, the latest
+/// exploitability, and the `user_data` passed to [`psf_solve`].
+pub type PsfProgressCallback = unsafe extern "C" fn(c_uint, c_float, *mut c_void);
+
+/// Solves the game and returns the exploitability.
+///
+/// If the game is already solved, the solution is discarded and the game is solved again from
+/// scratch, e.g., after changing the locking strategies by [`psf_lock_strategy`]. The strategies
+/// locked or unlocked since the last solve are applied before solving. The solving stops after
+/// `max_iterations` iterations or when the exploitability falls below
+/// `target_exploitability_ratio` times the starting pot (e.g., `0.005` for 0.5% of the pot). The
+/// current node is moved back to the root. Returns NaN on failure.
+///
+/// The solving runs in the thread pool of `max_threads` threads if it is specified in the
+/// [`PsfGameConfig`].
+///
+/// If `progress` is not null, it is called synchronously before the first iteration and after
+/// each iteration (see [`solve_with_callback`]); with a dedicated thread pool, it is called on a
+/// thread of the pool. If `cancel_flag` is not null, the solving is stopped when it is set to
+/// nonzero (e.g., from another thread or from `progress`), and the strategy obtained so far is
+/// finalized.
+///
+/// # Safety
+/// `game` must be null or a valid handle. `cancel_flag` must be null or valid during the call, and
+/// it must be written atomically (e.g., as `atomic_uchar` in C) while the game is being solved.
+#[no_mangle]
+pub unsafe extern "C" fn psf_solve(
+    game: *mut PsfGame,
+    max_iterations: c_uint,
+    target_exploitability_ratio: c_float,
+    print_progress: u8,
+    progress: Option<PsfProgressCallback>,
+    user_data: *mut c_void,
+    cancel_flag: *const u8,
+) -> c_float {
+    let result = ffi_call(|| {
+        let psf = unsafe { handle(game) }?;
+        apply_pending_locks(psf)?;
+        let starting_pot = psf.game.tree_config().starting_pot as f32;
+        let target_exploitability = starting_pot * target_exploitability_ratio;
+        let cancel_flag =
+            (!cancel_flag.is_null()).then(|| unsafe { AtomicU8::from_ptr(cancel_flag as *mut u8) });
+
+        let user_data = UserData(user_data);
+        let solve = |game: &mut PostFlopGame| {
+            solve_with_callback(
+                game,
+                max_iterations,
+                target_exploitability,
+                |iteration, exploitability| {
+                    if print_progress != 0 {
+                        print!("\riteration: {iteration} / {max_iterations} ");
+                        print!("(exploitability = {exploitability:.4e})");
+                        io::stdout().flush().unwrap();
+                    }
+                    if let Some(progress) = progress {
+                        unsafe { progress(iteration, exploitability, user_data.get()) };
+                    }
+                    cancel_flag.is_none_or(|flag| flag.load(Ordering::Relaxed) == 0)
+                },
+            )
+        };
+
+        #[cfg(feature = "rayon")]
+        let exploitability = match &psf.pool {
+            Some(pool) => pool.install(|| solve(&mut psf.game)),
+            None => solve(&mut psf.game),
+        };
+
+        #[cfg(not(feature = "rayon"))]
+        let exploitability = solve(&mut psf.game);
+
+        let game = &mut psf.game;
+
+        if print_progress != 0 {
+            println!();
+        }
+
+        game.back_to_root();
+        Ok(exploitability)
+    });
+
+    result.unwrap_or(f32::NAN)
+}
+
+/// Locks the strategy of the current node to `strategy` of length `len` at the next solve.
+///
+/// `strategy` is laid out as in [`psf_get_strategy`], and its length must be equal to the one
+/// returned by [`psf_get_strategy`]. Hands whose frequencies are all zero (or negative) are not
+/// locked, and the frequencies of the other hands are normalized (see
+/// [`PostFlopGame::lock_current_strategy`]). Since the game cannot be changed while solved, the
+/// lock takes effect when the game is solved again by [`psf_solve`]; until then, the results of the
+/// previous solve are kept.
+///
+/// Returns `0` on success and `-1` on failure (e.g., at a chance node).
+///
+/// # Safety
+/// `game` must be null or a valid handle, and `strategy` must be null or valid for `len` reads.
+#[no_mangle]
+pub unsafe extern "C" fn psf_lock_strategy(
+    game: *mut PsfGame,
+    strategy: *const c_float,
+    len: usize,
+) -> c_int {
+    let result = ffi_call(|| {
+        let PsfGame {
+            game,
+            pending_locks,
+            ..
+        } = unsafe { handle(game) }?;
+        if game.is_terminal_node() || game.is_chance_node() {
+            return Err(PostFlopError::InvalidState(
+                "Current node is not a decision node".to_string(),
+            ));
+        }
+        if strategy.is_null() {
+            return Err(PostFlopError::InvalidArgument("Null strategy".to_string()));
+        }
+
+        let player = game.current_player();
+        let expected_len = game.available_actions().len() * game.num_private_hands(player);
+        if len != expected_len {
+            return Err(PostFlopError::InvalidArgument(format!(
+                "Invalid strategy length: {len} (expected: {expected_len})"
+            )));
+        }
+
+        let strategy = unsafe { slice::from_raw_parts(strategy, len) };
+        pending_locks.insert(game.history().to_vec(), Some(strategy.to_vec()));
+        Ok(0)
+    });
+
+    result.unwrap_or(-1)
+}
+
+/// Unlocks the strategy of the current node at the next solve (see [`psf_lock_strategy`]).
+///
+/// Returns `0` on success and `-1` on failure.
+///
+/// # Safety
+/// `game` must be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn psf_unlock_strategy(game: *mut PsfGame) -> c_int {
+    let result = ffi_call(|| {
+        let PsfGame {
+            game,
+            pending_locks,
+            ..
+        } = unsafe { handle(game) }?;
+        if game.is_terminal_node() || game.is_chance_node() {
+            return Err(PostFlopError::InvalidState(
+                "Current node is not a decision node".to_string(),
+            ));
+        }
+
+        pending_locks.insert(game.history().to_vec(), None);
+        Ok(0)
+    });
+
+    result.unwrap_or(-1)
+}
+
+/// Resets the solution if the game is solved and applies the pending locking strategies.
+fn apply_pending_locks(psf: &mut PsfGame) -> Result<(), PostFlopError> {
+    let game = &mut psf.game;
+    if game.is_solved() {
+        let (strategy_format, value_format) = game.allocated_storage_formats().unwrap();
+        game.allocate_memory_with_formats(strategy_format, value_format, None)?;
+    }
+
+    for (history, strategy) in mem::take(&mut psf.pending_locks) {
+        game.apply_history(&history);
+        match strategy {
+            Some(strategy) => game.lock_current_strategy(&strategy),
+            None => game.unlock_current_strategy(),
+        }
+    }
+
+    game.back_to_root();
+    Ok(())
+}
+
+/// `user_data` of [`psf_solve`], which is passed to the callback on a thread of the pool.
+struct UserData(*mut c_void);
+
+// SAFETY: the callback is called synchronously during `psf_solve`, so the caller owns `user_data`
+unsafe impl Send for UserData {}
+unsafe impl Sync for UserData {}
+
+impl UserData {
+    #[inline]
+    fn get(&self) -> *mut c_void {
+        self.0
+    }
+}
+
+/// Writes the sizes of the results at the current node to `out`.
+///
+/// Returns `0` on success and `-1` on failure.
+///
+/// # Safety
+/// `game` must be null or a valid handle, and `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn psf_result_sizes(game: *mut PsfGame, out: *mut PsfResultSizes) -> c_int {
+    let result = ffi_call(|| {
+        let game = &unsafe { handle(game) }?.game;
+        let out = unsafe { out.as_mut() }.ok_or_else(|| invalid_argument("Null output"))?;
+
+        let num_private_hands = [game.num_private_hands(0), game.num_private_hands(1)];
+        let is_decision_node = !game.is_terminal_node() && !game.is_chance_node();
+        let num_actions = match game.is_terminal_node() {
+            true => 0,
+            false => game.available_actions().len(),
+        };
+
+        *out = PsfResultSizes {
+            num_actions,
+            num_private_hands,
+            strategy_len: match is_decision_node {
+                true => num_actions * num_private_hands[game.current_player()],
+                false => 0,
+            },
+            hand_string_stride: PSF_HAND_STRING_STRIDE,
+        };
+
+        Ok(0)
+    });
+
+    result.unwrap_or(-1)
+}
+
+/// Writes the strategy of the current node to `buf` and returns the number of elements.
+///
+/// The strategy is laid out as `[#(actions)][#(private hands of the current player)]`. If `buf` is
+/// null or `len` is smaller than the number of elements, nothing is written, so the required
+/// length can be queried by passing null. Returns `-1` on failure (e.g., at a chance node).
+///
+/// # Safety
+/// `game` must be null or a valid handle, and `buf` must be null or valid for `len` writes.
+#[no_mangle]
+pub unsafe extern "C" fn psf_get_strategy(
+    game: *mut PsfGame,
+    buf: *mut c_float,
+    len: usize,
+) -> isize {
+    let result = ffi_call(|| {
+        let PsfGame { game, buffer, .. } = unsafe { handle(game) }?;
+        if !game.is_solved() {
+            return Err(PostFlopError::InvalidState(
+                "Game is not solved".to_string(),
+            ));
+        }
+        if game.is_terminal_node() || game.is_chance_node() {
+            return Err(PostFlopError::InvalidState(
+                "Current node is not a decision node".to_string(),
+            ));
+        }
+
+        game.strategy_into(buffer);
+        Ok(unsafe { copy_to_buffer(buffer, buf, len) })
+    });
+
+    result.unwrap_or(-1)
+}
+
+/// Writes the expected value of each private hand of the given player at the current node to
+/// `buf` and returns the number of elements (`#(private hands of the player)`).
+///
+/// See [`PostFlopGame::expected_values`] for the details of the values, and [`psf_get_strategy`]
+/// for the buffer contract.
+///
+/// # Safety
+/// `game` must be null or a valid handle, and `buf` must be null or valid for `len` writes.
+#[no_mangle]
+pub unsafe extern "C" fn psf_get_expected_values(
+    game: *mut PsfGame,
+    player: c_int,
+    buf: *mut c_float,
+    len: usize,
+) -> isize {
+    let result = ffi_call(|| {
+        let PsfGame { game, buffer, .. } = unsafe { handle(game) }?;
+        let player = player_index(player)?;
+        if !game.is_solved() {
+            return Err(PostFlopError::InvalidState(
+                "Game is not solved".to_string(),
+            ));
+        }
+
+        game.cache_normalized_weights();
+        game.expected_values_into(player, buffer);
+        Ok(unsafe { copy_to_buffer(buffer, buf, len) })
+    });
+
+    result.unwrap_or(-1)
+}
+
+/// Writes the expected value of each action of each private hand of the given player at the
+/// current node to `buf` and returns the number of elements.
+///
+/// If the current node is a decision node of the player, the values are laid out as
+/// `[#(actions)][#(private hands of the player)]`; otherwise, as
+/// `[#(private hands of the player)]`. See [`PostFlopGame::expected_values_detail`] for the
+/// details, and [`psf_get_strategy`] for the buffer contract.
+///
+/// # Safety
+/// `game` must be null or a valid handle, and `buf` must be null or valid for `len` writes.
+#[no_mangle]
+pub unsafe extern "C" fn psf_get_expected_values_detail(
+    game: *mut PsfGame,
+    player: c_int,
+    buf: *mut c_float,
+    len: usize,
+) -> isize {
+    let result = ffi_call(|| {
+        let PsfGame { game, buffer, .. } = unsafe { handle(game) }?;
+        let player = player_index(player)?;
+        if !game.is_solved() {
+            return Err(PostFlopError::InvalidState(
+                "Game is not solved".to_string(),
+            ));
+        }
+
+        game.cache_normalized_weights();
+        game.expected_values_detail_into(player, buffer);
+        Ok(unsafe { copy_to_buffer(buffer, buf, len) })
+    });
+
+    result.unwrap_or(-1)
+}
+
+/// Writes the equity of each private hand of the given player at the current node to `buf` and
+/// returns the number of elements (`#(private hands of the player)`).
+///
+/// See [`PostFlopGame::equity`] for the details of the values, and [`psf_get_strategy`] for the
+/// buffer contract.
+///
+/// # Safety
+/// `game` must be null or a valid handle, and `buf` must be null or valid for `len` writes.
+#[no_mangle]
+pub unsafe extern "C" fn psf_get_equity(
+    game: *mut PsfGame,
+    player: c_int,
+    buf: *mut c_float,
+    len: usize,
+) -> isize {
+    let result = ffi_call(|| {
+        let PsfGame { game, buffer, .. } = unsafe { handle(game) }?;
+        let player = player_index(player)?;
+        game.cache_normalized_weights();
+        game.equity_into(player, buffer);
+        Ok(unsafe { copy_to_buffer(buffer, buf, len) })
+    });
+
+    result.unwrap_or(-1)
+}
+
+/// Writes the normalized weight of each private hand of the given player at the current node to
+/// `buf` and returns the number of elements (`#(private hands of the player)`).
+///
+/// The weights can be used to average the per-hand results (see
+/// [`PostFlopGame::normalized_weights`]). See [`psf_get_strategy`] for the buffer contract.
+///
+/// # Safety
+/// `game` must be null or a valid handle, and `buf` must be null or valid for `len` writes.
+#[no_mangle]
+pub unsafe extern "C" fn psf_get_weights(
+    game: *mut PsfGame,
+    player: c_int,
+    buf: *mut c_float,
+    len: usize,
+) -> isize {
+    let result = ffi_call(|| {
+        let game = &mut unsafe { handle(game) }?.game;
+        let player = player_index(player)?;
+        game.cache_normalized_weights();
+        Ok(unsafe { copy_to_buffer(game.normalized_weights(player), buf, len) })
+    });
+
+    result.unwrap_or(-1)
+}
+
+/// Writes the private hands of the given player as strings to `buf` and returns the number of
+/// hands.
+///
+/// Each hand occupies [`PSF_HAND_STRING_STRIDE`] bytes: four characters (e.g., `"AsKh"`, the
+/// higher card first) followed by a null terminator, in the same order as the per-hand results. If
+/// `buf` is null or `len` (in bytes) is smaller than `#(hands) * PSF_HAND_STRING_STRIDE`, nothing
+/// is written. Returns `-1` on failure.
+///
+/// # Safety
+/// `game` must be null or a valid handle, and `buf` must be null or valid for `len` writes.
+#[no_mangle]
+pub unsafe extern "C" fn psf_get_hand_strings(
+    game: *mut PsfGame,
+    player: c_int,
+    buf: *mut c_char,
+    len: usize,
+) -> isize {
+    let result = ffi_call(|| {
+        let game = &unsafe { handle(game) }?.game;
+        let player = player_index(player)?;
+        unsafe { copy_hands_to_buffer(game.private_cards(player), buf, len) }
+    });
+
+    result.unwrap_or(-1)
+}
+
+/// Returns the current node, or the node of `line` if it is not null or empty, in JSON format (see
+/// [`PostFlopGame::export_node_json`]).
+///
+/// `line` is applied from the root in the format of [`PostFlopGame::apply_line_str`], and the
+/// current node is not changed. The values are written with `precision` digits after the decimal
+/// point, and the expected values and the equities are included if `expected_values` and
+/// `equity` are nonzero, respectively.
+///
+/// Returns null on failure. The returned string must be released by [`psf_string_free`].
+///
+/// # Safety
+/// `game` must be null or a valid handle, and `line` must be null or a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn psf_node_json(
+    game: *mut PsfGame,
+    line: *const c_char,
+    precision: c_uint,
+    expected_values: u8,
+    equity: u8,
+) -> *mut c_char {
+    let result = ffi_call(|| {
+        let game = &mut unsafe { handle(game) }?.game;
+        let line = unsafe { c_str(line) }?;
+        if !game.is_solved() {
+            return Err(PostFlopError::InvalidState(
+                "Game is not solved".to_string(),
+            ));
+        }
+
+        let options = ExportOptions {
+            precision: precision as usize,
+            expected_values: expected_values != 0,
+            equity: equity != 0,
+            ..Default::default()
+        };
+
+        let json = match line {
+            None => game.export_node_json(&options),
+            Some(line) => {
+                let history = game.history().to_vec();
+                game.apply_line_str(line)?;
+                let json = game.export_node_json(&options);
+                game.apply_history(&history);
+                json
+            }
+        };
+
+        Ok(CString::new(json).unwrap().into_raw())
+    });
+
+    result.unwrap_or(ptr::null_mut())
+}
+
+/// Releases a string returned by this library. Passing null is a no-op.
+///
+/// # Safety
+/// `s` must be null or a string returned by this library, and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn psf_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+/// Writes the available actions at the current node to `buf` and returns the number of actions.
+///
+/// At a chance node, isomorphic cards are grouped into one action (see
+/// [`PostFlopGame::available_actions`]). See [`psf_get_strategy`] for the buffer contract.
+///
+/// # Safety
+/// `game` must be null or a valid handle, and `buf` must be null or valid for `len` writes.
+#[no_mangle]
+pub unsafe extern "C" fn psf_available_actions(
+    game: *mut PsfGame,
+    buf: *mut PsfAction,
+    len: usize,
+) -> isize {
+    let result = ffi_call(|| {
+        let game = &unsafe { handle(game) }?.game;
+        let actions = match game.is_terminal_node() {
+            true => Vec::new(),
+            false => game.available_actions(),
+        };
+
+        if !buf.is_null() && len >= actions.len() {
+            let out = unsafe { slice::from_raw_parts_mut(buf, actions.len()) };
+            for (dst, &action) in out.iter_mut().zip(&actions) {
+                *dst = action.into();
+            }
+        }
+
+        Ok(actions.len() as isize)
+    });
+
+    result.unwrap_or(-1)
+}
+
+/// Plays the given action (see [`PostFlopGame::play`]). At a chance node, `action` is the ID of
+/// the dealt card; otherwise, it is the index of the action in [`psf_available_actions`].
+///
+/// Returns `0` on success and `-1` on failure.
+///
+/// # Safety
+/// `game` must be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn psf_play(game: *mut PsfGame, action: usize) -> c_int {
+    let result = ffi_call(|| {
+        let game = &mut unsafe { handle(game) }?.game;
+        play_checked(game, action)?;
+        Ok(0)
+    });
+
+    result.unwrap_or(-1)
+}
+
+/// Moves the current node back to the root and plays the given line of `len` actions, each
+/// interpreted as in [`psf_play`].
+///
+/// Returns `0` on success and `-1` on failure. On failure, the current node is left unchanged.
+///
+/// # Safety
+/// `game` must be null or a valid handle, and `actions` must be valid for `len` reads (it may be
+/// null if `len` is `0`).
+#[no_mangle]
+pub unsafe extern "C" fn psf_apply_line(
+    game: *mut PsfGame,
+    actions: *const usize,
+    len: usize,
+) -> c_int {
+    let result = ffi_call(|| {
+        let game = &mut unsafe { handle(game) }?.game;
+        let line = match len {
+            0 => &[][..],
+            _ if actions.is_null() => {
+                return Err(PostFlopError::InvalidArgument("Null actions".to_string()))
+            }
+            _ => unsafe { slice::from_raw_parts(actions, len) },
+        };
+
+        let history = game.history().to_vec();
+        game.back_to_root();
+
+        for &action in line {
+            if let Err(e) = play_checked(game, action) {
+                game.apply_history(&history);
+                return Err(e);
+            }
+        }
+
+        Ok(0)
+    });
+
+    result.unwrap_or(-1)
+}
+
+/// Moves the current node back to the root. Returns `0` on success and `-1` on failure.
+///
+/// # Safety
+/// `game` must be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn psf_back_to_root(game: *mut PsfGame) -> c_int {
+    let result = ffi_call(|| {
+        unsafe { handle(game) }?.game.back_to_root();
+        Ok(0)
+    });
+
+    result.unwrap_or(-1)
+}
+
+/// Writes the card IDs of the current board to `buf` and returns the number of cards (3, 4, or 5).
+///
+/// The flop cards, the turn card, and the river card, if any, are stored in this order. See
+/// [`psf_get_strategy`] for the buffer contract.
+///
+/// # Safety
+/// `game` must be null or a valid handle, and `buf` must be null or valid for `len` writes.
+#[no_mangle]
+pub unsafe extern "C" fn psf_current_board(game: *mut PsfGame, buf: *mut u8, len: usize) -> isize {
+    let result = ffi_call(|| {
+        let board = unsafe { handle(game) }?.game.current_board();
+        if !buf.is_null() && len >= board.len() {
+            unsafe { ptr::copy_nonoverlapping(board.as_ptr(), buf, board.len()) };
+        }
+        Ok(board.len() as isize)
+    });
+
+    result.unwrap_or(-1)
+}
+
+/// Saves the game to the file at `path`, overwriting it if it exists.
+///
+/// `compression_level` is the zstd compression level, or `0` for no compression; compression
+/// requires the `zstd` feature. Returns [`PSF_OK`] on success and a negative error code on
+/// failure.
+///
+/// # Safety
+/// `game` must be null or a valid handle, and `path` must be null or a valid C string.
+#[cfg(feature = "bincode")]
+#[no_mangle]
+pub unsafe extern "C" fn psf_save(
+    game: *mut PsfGame,
+    path: *const c_char,
+    compression_level: c_int,
+) -> c_int {
+    let result = ffi_call(|| {
+        let game = &unsafe { handle(game) }?.game;
+        let path = unsafe { c_str(path) }?.ok_or_else(|| invalid_argument("Empty path"))?;
+        let compression_level = (compression_level != 0).then_some(compression_level);
+
+        let file = File::create(path)
+            .map_err(|e| PostFlopError::Io(format!("Failed to create file: {e}")))?;
+        let mut writer = BufWriter::new(file);
+        save_data_into_std_write(game, "", &mut writer, compression_level)?;
+        writer
+            .flush()
+            .map_err(|e| PostFlopError::Io(format!("Failed to flush writer: {e}")))?;
+
+        Ok(PSF_OK)
+    });
+
+    result.unwrap_or_else(|| psf_last_error_code())
+}
+
+/// Loads a game saved by [`psf_save`] from the file at `path`.
+///
+/// Returns null on failure; [`psf_last_error_code`] then tells whether the file could not be read
+/// ([`PSF_ERROR_IO`]), is not a saved game ([`PSF_ERROR_INVALID_FILE`]), was saved by an
+/// incompatible version ([`PSF_ERROR_VERSION`]), is corrupted ([`PSF_ERROR_CORRUPTED`]), or needs
+/// an unavailable feature ([`PSF_ERROR_UNSUPPORTED`]). The returned handle must be released by
+/// [`psf_free`].
+///
+/// # Safety
+/// `path` must be null or a valid C string.
+#[cfg(feature = "bincode")]
+#[no_mangle]
+pub unsafe extern "C" fn psf_load(path: *const c_char) -> *mut PsfGame {
+    let result = ffi_call(|| {
+        let path = unsafe { c_str(path) }?.ok_or_else(|| invalid_argument("Empty path"))?;
+
+        let file =
+            File::open(path).map_err(|e| PostFlopError::Io(format!("Failed to open file: {e}")))?;
+        let mut reader = BufReader::new(file);
+        let (game, _) = load_data_with_migration::<PostFlopGame, _>(&mut reader, None)?;
+
+        Ok(Box::into_raw(Box::new(PsfGame::new(game))))
+    });
+
+    result.unwrap_or(ptr::null_mut())
+}
+
+/// Returns the number of available actions at the current node, or `-1` on failure.
+///
+/// At a chance node, isomorphic cards are grouped into one action (see
+/// [`PostFlopGame::available_actions`]).
+///
+/// # Safety
+/// `game` must be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn psf_num_actions(game: *mut PsfGame) -> isize {
+    let result = ffi_call(|| {
+        let game = &unsafe { handle(game) }?.game;
+        Ok(game.available_actions().len() as isize)
+    });
+
+    result.unwrap_or(-1)
+}
+
+/// Returns the current player (`0` = OOP, `1` = IP), or `-1` at a terminal node, at a chance
+/// node, or on failure.
+///
+/// # Safety
+/// `game` must be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn psf_current_player(game: *mut PsfGame) -> c_int {
+    let result = ffi_call(|| {
+        let game = &unsafe { handle(game) }?.game;
+        if game.is_terminal_node() || game.is_chance_node() {
+            return Ok(-1);
+        }
+        Ok(game.current_player() as c_int)
+    });
+
+    result.unwrap_or(-1)
+}
+
+/// Returns the number of private hands of the given player, or `-1` on failure.
+///
+/// # Safety
+/// `game` must be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn psf_num_private_hands(game: *mut PsfGame, player: c_int) -> isize {
+    let result = ffi_call(|| {
+        let game = &unsafe { handle(game) }?.game;
+        Ok(game.num_private_hands(player_index(player)?) as isize)
+    });
+
+    result.unwrap_or(-1)
+}
+
+/// Writes the canonical form of `range` (e.g., `"KK+"` for `"KK, AA"`) to `buf` as a
+/// null-terminated string and returns its length in bytes, including the null terminator.
+///
+/// This can be used to validate a range without creating a game. If `buf` is null or `len` is
+/// smaller than the returned length, nothing is written. Returns `-1` if `range` is invalid.
+///
+/// # Safety
+/// `range` must be null or a valid C string, and `buf` must be null or valid for `len` writes.
+#[no_mangle]
+pub unsafe extern "C" fn psf_range_normalize(
+    range: *const c_char,
+    buf: *mut c_char,
+    len: usize,
+) -> isize {
+    let result = ffi_call(|| {
+        let range = unsafe { c_str(range) }?.unwrap_or_default();
+        let range = CString::new(range.parse::<Range>()?.to_string()).unwrap();
+        let bytes = range.as_bytes_with_nul();
+        if !buf.is_null() && len >= bytes.len() {
+            unsafe { ptr::copy_nonoverlapping(bytes.as_ptr(), buf.cast::<u8>(), bytes.len()) };
+        }
+        Ok(bytes.len() as isize)
+    });
+
+    result.unwrap_or(-1)
+}
+
+/// Writes the combinations of `range` that do not conflict with `board` and their weights, and
+/// returns the number of combinations.
+///
+/// `board` may be null or empty, or consist of up to five cards (e.g., `"Td9d6h"`). Combinations
+/// with zero weight are omitted. The hands are written to `hands_buf` in the format of
+/// [`psf_get_hand_strings`], and the weights are written to `weights_buf` (see
+/// [`psf_get_strategy`] for the buffer contract); each buffer is checked separately. Returns `-1`
+/// on failure.
+///
+/// # Safety
+/// `range` and `board` must be null or valid C strings, `hands_buf` must be null or valid for
+/// `hands_len` writes, and `weights_buf` must be null or valid for `weights_len` writes.
+#[no_mangle]
+pub unsafe extern "C" fn psf_range_combos(
+    range: *const c_char,
+    board: *const c_char,
+    hands_buf: *mut c_char,
+    hands_len: usize,
+    weights_buf: *mut c_float,
+    weights_len: usize,
+) -> isize {
+    let result = ffi_call(|| {
+        let range = unsafe { c_str(range) }?.unwrap_or_default();
+        let range = range.parse::<Range>()?;
+        let board = board_from_str(unsafe { c_str(board) }?.unwrap_or_default())?;
+        if board.len() > 5 {
+            return Err(PostFlopError::InvalidConfig(format!(
+                "Invalid board length: {}",
+                board.len()
+            )));
+        }
+
+        let mask = board.iter().fold(0, |mask, &card| mask | 1 << card);
+        let (hands, weights) = range.get_hands_weights(mask);
+        unsafe { copy_to_buffer(&weights, weights_buf, weights_len) };
+        unsafe { copy_hands_to_buffer(&hands, hands_buf, hands_len) }
+    });
+
+    result.unwrap_or(-1)
+}
+
+/// Computes the range-vs-range equity without creating a game, and returns the equity of OOP
+/// (see [`range_equity`]).
+///
+/// `board` consists of 3 to 5 cards (e.g., `"Td9d6h"`), and the remaining board cards are
+/// enumerated. The equity of each combination of OOP and IP is written to `oop_buf` and `ip_buf`
+/// in the order of [`psf_range_combos`], or NaN if the combination has no valid matchup; each
+/// buffer is written only if it is not null and its length is at least the number of
+/// combinations. Returns NaN on failure.
+///
+/// # Safety
+/// `oop_range`, `ip_range`, and `board` must be null or valid C strings, `oop_buf` must be null
+/// or valid for `oop_len` writes, and `ip_buf` must be null or valid for `ip_len` writes.
+#[no_mangle]
+pub unsafe extern "C" fn psf_range_equity(
+    oop_range: *const c_char,
+    ip_range: *const c_char,
+    board: *const c_char,
+    oop_buf: *mut c_float,
+    oop_len: usize,
+    ip_buf: *mut c_float,
+    ip_len: usize,
+) -> c_double {
+    let result = ffi_call(|| {
+        let oop_range = unsafe { c_str(oop_range) }?.unwrap_or_default();
+        let ip_range = unsafe { c_str(ip_range) }?.unwrap_or_default();
+        let board = board_from_str(unsafe { c_str(board) }?.unwrap_or_default())?;
+        let result = range_equity(&oop_range.parse()?, &ip_range.parse()?, &board)?;
+
+        for (hand_equity, (buf, len)) in result
+            .hand_equity
+            .iter()
+            .zip([(oop_buf, oop_len), (ip_buf, ip_len)])
+        {
+            let values = hand_equity.iter().map(|&x| x as f32).collect::<Vec<_>>();
+            unsafe { copy_to_buffer(&values, buf, len) };
+        }
+
+        Ok(result.equity[0])
+    });
+
+    result.unwrap_or(f64::NAN)
+}
+
+/// Releases the game. Passing null is a no-op.
+///
+/// # Safety
+/// `game` must be null or a valid handle, and the handle must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn psf_free(game: *mut PsfGame) {
+    if !game.is_null() {
+        drop(unsafe { Box::from_raw(game) });
+    }
+}
+
+/// Returns the error code corresponding to the error.
+fn error_code(error: &PostFlopError) -> c_int {
+    match error {
+        PostFlopError::InvalidRange { .. } | PostFlopError::InvalidHand { .. } => {
+            PSF_ERROR_INVALID_RANGE
+        }
+        PostFlopError::InvalidCard { .. } | PostFlopError::BoardConflict { .. } => {
+            PSF_ERROR_INVALID_BOARD
+        }
+        PostFlopError::InvalidBetSize { .. } | PostFlopError::InvalidConfig(_) => {
+            PSF_ERROR_INVALID_CONFIG
+        }
+        PostFlopError::InvalidLine(_) | PostFlopError::InvalidArgument(_) => {
+            PSF_ERROR_INVALID_ARGUMENT
+        }
+        PostFlopError::InvalidState(_) => PSF_ERROR_INVALID_STATE,
+        PostFlopError::Unsupported(_) => PSF_ERROR_UNSUPPORTED,
+        PostFlopError::Io(_) => PSF_ERROR_IO,
+        PostFlopError::MemoryLimit { .. } | PostFlopError::AllocationFailed { .. } => {
+            PSF_ERROR_MEMORY_LIMIT
+        }
+        #[cfg(feature = "bincode")]
+        PostFlopError::Load(error) => load_error_code(error),
+        PostFlopError::Cancelled | PostFlopError::Other(_) => PSF_ERROR,
+    }
+}
+
+/// Calls `f` and records the error message and the error code if it fails or panics.
+fn ffi_call<T>(f: impl FnOnce() -> Result<T, PostFlopError>) -> Option<T> {
+    let (code, message) = match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => return Some(value),
+        Ok(Err(error)) => (error_code(&error), error.to_string()),
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned());
+            (
+                PSF_ERROR,
+                message.unwrap_or_else(|| "Unknown panic".to_string()),
+            )
+        }
+    };
+
+    let message = CString::new(message.replace('\0', "")).unwrap();
+    LAST_ERROR.with(|error| *error.borrow_mut() = Some(message));
+    LAST_ERROR_CODE.with(|error_code| error_code.set(code));
+    None
+}
+
+/// Returns the error code corresponding to the load error.
+#[cfg(feature = "bincode")]
+fn load_error_code(error: &LoadError) -> c_int {
+    match error {
+        LoadError::InvalidMagic | LoadError::DataTypeMismatch => PSF_ERROR_INVALID_FILE,
+        LoadError::UnsupportedFormatVersion(_) | LoadError::IncompatibleData { .. } => {
+            PSF_ERROR_VERSION
+        }
+        LoadError::InvalidCompressionType | LoadError::Corrupted { .. } | LoadError::Decode(_) => {
+            PSF_ERROR_CORRUPTED
+        }
+        LoadError::CompressionNotSupported | LoadError::DictionaryRequired => PSF_ERROR_UNSUPPORTED,
+        LoadError::MemoryLimitExceeded { .. } => PSF_ERROR,
+    }
+}
+
+/// Converts the C string to `&str`. Null and empty strings are converted to `None`.
+unsafe fn c_str<'a>(s: *const c_char) -> Result<Option<&'a str>, PostFlopError> {
+    if s.is_null() {
+        return Ok(None);
+    }
+
+    let s = unsafe { CStr::from_ptr(s) }
+        .to_str()
+        .map_err(|e| PostFlopError::InvalidArgument(format!("Invalid string: {e}")))?;
+
+    Ok(Some(s.trim()).filter(|s| !s.is_empty()))
+}
+
+/// Builds a game whose memory is not yet allocated. The same bet sizes are used for both players
+/// and all streets.
+pub(crate) fn build_game(
+    ranges: [&str; 2],
+    (flop, turn, river): (&str, Option<&str>, Option<&str>),
+    starting_pot: i32,
+    effective_stack: i32,
+    (bet_sizes, raise_sizes): (&str, &str),
+) -> Result<PostFlopGame, PostFlopError> {
+    flop_from_str(flop)?;
+    let board = [Some(flop), turn, river]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    PostFlopGame::builder()
+        .oop_range(ranges[0])
+        .ip_range(ranges[1])
+        .board(&board.join(" "))
+        .pot(starting_pot)
+        .stack(effective_stack)
+        .bet_sizes(bet_sizes, raise_sizes)
+        .build()
+}
+
+/// Plays the given action after checking that it is valid (see [`psf_play`]).
+pub(crate) fn play_checked(game: &mut PostFlopGame, action: usize) -> Result<(), PostFlopError> {
+    if !game.is_solved() {
+        return Err(PostFlopError::InvalidState(
+            "Game is not solved".to_string(),
+        ));
+    }
+    if game.is_terminal_node() {
+        return Err(PostFlopError::InvalidState(
+            "Terminal node is not allowed".to_string(),
+        ));
+    }
+
+    let is_valid = if game.is_chance_node() {
+        action < 52 && game.possible_cards() & (1 << action) != 0
+    } else {
+        action < game.available_actions().len()
+    };
+    if !is_valid {
+        return Err(PostFlopError::InvalidArgument(format!(
+            "Invalid action: {action}"
+        )));
+    }
+
+    game.play(action);
+    Ok(())
+}
+
+/// Copies `values` to `buf` if it has enough length, and returns the length of `values`.
+unsafe fn copy_to_buffer(values: &[f32], buf: *mut c_float, len: usize) -> isize {
+    if !buf.is_null() && len >= values.len() {
+        unsafe { ptr::copy_nonoverlapping(values.as_ptr(), buf, values.len()) };
+    }
+    values.len() as isize
+}
+
+/// Writes `hands` to `buf` in the format of [`psf_get_hand_strings`] if it has enough length, and
+/// returns the number of hands.
+unsafe fn copy_hands_to_buffer(
+    hands: &[(Card, Card)],
+    buf: *mut c_char,
+    len: usize,
+) -> Result<isize, PostFlopError> {
+    if !buf.is_null() && len >= hands.len() * PSF_HAND_STRING_STRIDE {
+        let out = unsafe {
+            slice::from_raw_parts_mut(buf.cast::<u8>(), hands.len() * PSF_HAND_STRING_STRIDE)
+        };
+        for (chunk, &hand) in out.chunks_exact_mut(PSF_HAND_STRING_STRIDE).zip(hands) {
+            let hand = hole_to_string(hand)?;
+            chunk[..4].copy_from_slice(hand.as_bytes());
+            chunk[4] = 0;
+        }
+    }
+    Ok(hands.len() as isize)
+}
+
+/// Converts the player argument to an index.
+fn player_index(player: c_int) -> Result<usize, PostFlopError> {
+    match player {
+        0 | 1 => Ok(player as usize),
+        _ => Err(PostFlopError::InvalidArgument(format!(
+            "Invalid player: {player}"
+        ))),
+    }
+}
+
+/// Converts the pointer to a reference to the handle.
+unsafe fn handle<'a>(game: *mut PsfGame) -> Result<&'a mut PsfGame, PostFlopError> {
+    unsafe { game.as_mut() }.ok_or_else(|| invalid_argument("Null handle"))
+}
+
+/// Creates a [`PostFlopError::InvalidArgument`] error of a null or empty argument.
+#[inline]
+fn invalid_argument(message: &str) -> PostFlopError {
+    PostFlopError::InvalidArgument(message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error() -> String {
+        let error = psf_last_error();
+        assert!(!error.is_null());
+        unsafe { CStr::from_ptr(error) }
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    fn create_game(flop: &str) -> *mut PsfGame {
+        create_game_with_limits(flop, 0, 0)
+    }
+
+    fn create_game_with_limits(
+        flop: &str,
+        max_threads: u32,
+        max_memory_bytes: u64,
+    ) -> *mut PsfGame {
+        let oop_range = CString::new("AA,KK,QQ,AK").unwrap();
+        let ip_range = CString::new("JJ-88,AQ,KQ").unwrap();
+        let flop = CString::new(flop).unwrap();
+        let turn = CString::new("Qc").unwrap();
+        let river = CString::new("").unwrap();
+        let bet_sizes = CString::new("50%").unwrap();
+
+        let config = PsfGameConfig {
+            oop_range: oop_range.as_ptr(),
+            ip_range: ip_range.as_ptr(),
+            flop: flop.as_ptr(),
+            turn: turn.as_ptr(),
+            river: river.as_ptr(),
+            starting_pot: 100,
+            effective_stack: 200,
+            bet_sizes: bet_sizes.as_ptr(),
+            raise_sizes: ptr::null(),
+            use_compression: 0,
+            max_threads,
+            max_memory_bytes,
+        };
+
+        unsafe { psf_game_create(&config) }
+    }
+
+    #[test]
+    fn handle_lifecycle() {
+        unsafe {
+            let game = create_game("Td9d6h");
+            assert!(!game.is_null());
+
+            // not solved yet
+            assert_eq!(psf_get_strategy(game, ptr::null_mut(), 0), -1);
+            assert_eq!(error(), "Game is not solved");
+            assert_eq!(psf_last_error_code(), PSF_ERROR_INVALID_STATE);
+
+            let exploitability = psf_solve(game, 100, 0.01, 0, None, ptr::null_mut(), ptr::null());
+            assert!(exploitability.is_finite());
+
+            let num_hands = psf_num_private_hands(game, 0);
+            let num_actions = psf_num_actions(game);
+            assert_eq!(psf_current_player(game), 0);
+            assert_eq!(num_actions, 2); // check, bet 50%
+
+            let len = psf_get_strategy(game, ptr::null_mut(), 0);
+            assert_eq!(len, num_actions * num_hands);
+            let mut strategy = vec![0.0; len as usize];
+            assert_eq!(
+                psf_get_strategy(game, strategy.as_mut_ptr(), strategy.len()),
+                len
+            );
+            assert_eq!(strategy, (*game).game.strategy());
+
+            // check, check, and deal the river
+            assert_eq!(psf_play(game, 0), 0);
+            assert_eq!(psf_current_player(game), 1);
+            assert_eq!(psf_play(game, 0), 0);
+            assert_eq!(psf_current_player(game), -1);
+            assert_eq!(psf_get_strategy(game, ptr::null_mut(), 0), -1);
+            assert_eq!(psf_play(game, 52), -1);
+            assert_eq!(error(), "Invalid action: 52");
+            assert_eq!(psf_last_error_code(), PSF_ERROR_INVALID_ARGUMENT);
+            assert_eq!(psf_play(game, card_from_str("2s").unwrap() as usize), 0);
+            assert!(psf_get_strategy(game, ptr::null_mut(), 0) > 0);
+
+            assert_eq!(psf_back_to_root(game), 0);
+            assert_eq!(psf_get_strategy(game, ptr::null_mut(), 0), len);
+            psf_free(game);
+
+            // invalid configuration
+            assert!(create_game("Td9d").is_null());
+            assert!(!error().is_empty());
+            assert_eq!(psf_last_error_code(), PSF_ERROR_INVALID_BOARD);
+            assert!(create_game("Td9dTd").is_null());
+            assert_eq!(error(), "Duplicate card: Td");
+            assert_eq!(psf_last_error_code(), PSF_ERROR_INVALID_BOARD);
+
+            assert!(psf_game_create(ptr::null()).is_null());
+            assert_eq!(error(), "Null config");
+            let null = ptr::null_mut();
+            assert!(psf_solve(null, 10, 0.01, 0, None, ptr::null_mut(), ptr::null()).is_nan());
+            assert_eq!(error(), "Null handle");
+            psf_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn resource_limits() {
+        unsafe {
+            assert!(create_game_with_limits("Td9d6h", 0, 1).is_null());
+            assert!(error().starts_with("Memory usage exceeds the limit"));
+            assert_eq!(psf_last_error_code(), PSF_ERROR_MEMORY_LIMIT);
+
+            let game = create_game("Td9d6h");
+            let memory_usage = (*game).game.memory_usage().0;
+            psf_free(game);
+
+            let game = create_game_with_limits("Td9d6h", 2, memory_usage);
+            assert!(!game.is_null());
+            #[cfg(feature = "rayon")]
+            assert_eq!((*game).pool.as_ref().unwrap().current_num_threads(), 2);
+
+            let progress = Progress {
+                iterations: RefCell::new(Vec::new()),
+                cancel_flag: AtomicU8::new(0),
+            };
+            let user_data = &progress as *const Progress as *mut c_void;
+            let exploitability = psf_solve(
+                game,
+                10,
+                0.0,
+                0,
+                Some(record_progress),
+                user_data,
+                ptr::null(),
+            );
+            assert!(exploitability.is_finite());
+            assert_eq!(*progress.iterations.borrow(), (0..=10).collect::<Vec<_>>());
+            psf_free(game);
+        }
+    }
+
+    #[test]
+    fn lock_and_resolve() {
+        unsafe {
+            let game = create_game("Td9d6h");
+            let num_hands = psf_num_private_hands(game, 0) as usize;
+
+            // always check at the root
+            let mut check = vec![0.0; 2 * num_hands];
+            check[..num_hands].fill(1.0);
+            assert_eq!(psf_lock_strategy(game, check.as_ptr(), check.len()), 0);
+            psf_solve(game, 50, 0.0, 0, None, ptr::null_mut(), ptr::null());
+            assert_eq!((*game).game.strategy(), check);
+
+            // always bet after the check, and re-solve
+            assert_eq!(psf_play(game, 0), 0);
+            let num_hands_ip = psf_num_private_hands(game, 1) as usize;
+            let mut bet = vec![0.0; 2 * num_hands_ip];
+            bet[num_hands_ip..].fill(1.0);
+            assert_eq!(psf_lock_strategy(game, bet.as_ptr(), bet.len() - 1), -1);
+            assert_eq!(
+                error(),
+                format!(
+                    "Invalid strategy length: {} (expected: {})",
+                    bet.len() - 1,
+                    bet.len()
+                )
+            );
+            assert_eq!(psf_lock_strategy(game, bet.as_ptr(), bet.len()), 0);
+
+            // the previous solution is kept until re-solved
+            let strategy = (*game).game.strategy();
+            assert!(strategy[num_hands_ip..].iter().sum::<f32>() < num_hands_ip as f32);
+
+            psf_solve(game, 50, 0.0, 0, None, ptr::null_mut(), ptr::null());
+            assert_eq!((*game).game.strategy(), check);
+            assert_eq!(psf_play(game, 0), 0);
+            assert_eq!((*game).game.strategy(), bet);
+
+            // unlock the root
+            psf_back_to_root(game);
+            assert_eq!(psf_unlock_strategy(game), 0);
+            psf_solve(game, 50, 0.0, 0, None, ptr::null_mut(), ptr::null());
+            assert_ne!((*game).game.strategy(), check);
+            assert_eq!(psf_play(game, 0), 0);
+            assert_eq!((*game).game.strategy(), bet);
+
+            // chance node
+            assert_eq!(psf_play(game, 0), 0);
+            assert_eq!(psf_unlock_strategy(game), -1);
+            assert_eq!(error(), "Current node is not a decision node");
+            psf_free(game);
+        }
+    }
+
+    #[test]
+    fn result_buffers() {
+        unsafe {
+            let game = create_game("Td9d6h");
+            psf_solve(game, 100, 0.01, 0, None, ptr::null_mut(), ptr::null());
+
+            let mut sizes = PsfResultSizes::default();
+            assert_eq!(psf_result_sizes(game, &mut sizes), 0);
+            assert_eq!(sizes.num_actions, 2);
+            assert_eq!(
+                sizes.strategy_len as isize,
+                psf_get_strategy(game, ptr::null_mut(), 0)
+            );
+            assert_eq!(sizes.hand_string_stride, PSF_HAND_STRING_STRIDE);
+
+            for player in 0..2 {
+                let num_hands = sizes.num_private_hands[player];
+                let p = player as c_int;
+                assert_eq!(psf_num_private_hands(game, p), num_hands as isize);
+
+                let g = &mut (*game).game;
+                g.cache_normalized_weights();
+                let expected = [
+                    g.expected_values(player),
+                    g.expected_values_detail(player),
+                    g.equity(player),
+                    g.normalized_weights(player).to_vec(),
+                ];
+                let funcs = [
+                    psf_get_expected_values,
+                    psf_get_expected_values_detail,
+                    psf_get_equity,
+                    psf_get_weights,
+                ];
+
+                for (func, expected) in funcs.iter().zip(expected) {
+                    let len = func(game, p, ptr::null_mut(), 0);
+                    assert_eq!(len as usize, expected.len());
+
+                    // too short buffer: nothing is written
+                    let mut buf = vec![-1.0; len as usize];
+                    assert_eq!(func(game, p, buf.as_mut_ptr(), buf.len() - 1), len);
+                    assert!(buf.iter().all(|&x| x == -1.0));
+
+                    assert_eq!(func(game, p, buf.as_mut_ptr(), buf.len()), len);
+                    assert_eq!(buf, expected);
+                }
+
+                let mut buf = vec![0 as c_char; num_hands * PSF_HAND_STRING_STRIDE];
+                assert_eq!(
+                    psf_get_hand_strings(game, p, buf.as_mut_ptr(), buf.len()),
+                    num_hands as isize
+                );
+                let hands = (*game).game.private_cards(player);
+                for (chunk, &hand) in buf.chunks_exact(PSF_HAND_STRING_STRIDE).zip(hands) {
+                    let s = CStr::from_ptr(chunk.as_ptr()).to_str().unwrap();
+                    assert_eq!(s, hole_to_string(hand).unwrap());
+                }
+            }
+
+            assert_eq!(psf_get_equity(game, 2, ptr::null_mut(), 0), -1);
+            assert_eq!(error(), "Invalid player: 2");
+            assert_eq!(psf_result_sizes(game, ptr::null_mut()), -1);
+            assert_eq!(error(), "Null output");
+            psf_free(game);
+        }
+    }
+
+    #[test]
+    fn navigation() {
+        unsafe {
+            let game = create_game("Td9d6h");
+            psf_solve(game, 100, 0.01, 0, None, ptr::null_mut(), ptr::null());
+
+            let mut actions = [PsfAction::default(); 2];
+            assert_eq!(psf_available_actions(game, ptr::null_mut(), 0), 2);
+            assert_eq!(psf_available_actions(game, actions.as_mut_ptr(), 2), 2);
+            assert_eq!(actions[0].kind, PSF_ACTION_CHECK);
+            assert_eq!(actions[1].kind, PSF_ACTION_BET);
+            assert_eq!(actions[1].amount, 50);
+
+            let mut board = [0u8; 5];
+            assert_eq!(psf_current_board(game, board.as_mut_ptr(), 5), 4);
+            assert_eq!(&board[..4], (*game).game.current_board());
+
+            // check, check, 2s
+            let river = card_from_str("2s").unwrap() as usize;
+            let line = [0, 0, river];
+            assert_eq!(psf_apply_line(game, line.as_ptr(), line.len()), 0);
+            assert_eq!(psf_current_board(game, board.as_mut_ptr(), 5), 5);
+            assert_eq!(board[4] as usize, river);
+            assert_eq!((*game).game.history(), line);
+
+            // an invalid line leaves the current node unchanged
+            let invalid = [0, 0, 60];
+            assert_eq!(psf_apply_line(game, invalid.as_ptr(), invalid.len()), -1);
+            assert_eq!(error(), "Invalid action: 60");
+            assert_eq!((*game).game.history(), line);
+
+            // bet, call, and the river chance node
+            assert_eq!(psf_apply_line(game, [1, 1].as_ptr(), 2), 0);
+            assert_eq!(psf_current_player(game), -1);
+            let num_actions = psf_available_actions(game, ptr::null_mut(), 0);
+            let mut chances = vec![PsfAction::default(); num_actions as usize];
+            psf_available_actions(game, chances.as_mut_ptr(), chances.len());
+            assert!(chances.iter().all(|a| a.kind == PSF_ACTION_CHANCE));
+            assert_eq!(psf_play(game, chances[0].amount as usize), 0);
+
+            assert_eq!(psf_apply_line(game, ptr::null(), 0), 0);
+            assert_eq!(psf_current_board(game, ptr::null_mut(), 0), 4);
+            assert_eq!(psf_apply_line(game, ptr::null(), 1), -1);
+            assert_eq!(error(), "Null actions");
+            psf_free(game);
+        }
+    }
+
+    #[test]
+    fn node_json() {
+        unsafe {
+            let game = create_game("Td9d6h");
+            assert!(psf_node_json(game, ptr::null(), 2, 1, 0).is_null());
+            assert_eq!(error(), "Game is not solved");
+
+            psf_solve(game, 100, 0.01, 0, None, ptr::null_mut(), ptr::null());
+            let json = psf_node_json(game, ptr::null(), 2, 1, 0);
+            assert!(!json.is_null());
+            let root = CStr::from_ptr(json).to_str().unwrap().to_string();
+            psf_string_free(json);
+            let options = ExportOptions {
+                precision: 2,
+                ..Default::default()
+            };
+            assert_eq!(root, (*game).game.export_node_json(&options));
+
+            // the current node is not changed
+            let line = CString::new("X X 2s").unwrap();
+            let json = psf_node_json(game, line.as_ptr(), 4, 1, 1);
+            let river = CStr::from_ptr(json).to_str().unwrap().to_string();
+            psf_string_free(json);
+            assert!(river.starts_with("{\"line\":[\"X\",\"X\",\"2s\"],"));
+            assert!(river.contains("\"equity\":"));
+            assert!((*game).game.history().is_empty());
+
+            let line = CString::new("X Z").unwrap();
+            assert!(psf_node_json(game, line.as_ptr(), 4, 1, 1).is_null());
+            assert!(!error().is_empty());
+            psf_string_free(ptr::null_mut());
+            psf_free(game);
+        }
+    }
+
+    #[test]
+    fn range_utilities() {
+        unsafe {
+            let range = CString::new("KK, AA").unwrap();
+            let mut buf = [0 as c_char; 8];
+            assert_eq!(psf_range_normalize(range.as_ptr(), ptr::null_mut(), 0), 4);
+            assert_eq!(psf_range_normalize(range.as_ptr(), buf.as_mut_ptr(), 8), 4);
+            assert_eq!(CStr::from_ptr(buf.as_ptr()).to_str().unwrap(), "KK+");
+
+            let invalid = CString::new("AAA").unwrap();
+            assert_eq!(
+                psf_range_normalize(invalid.as_ptr(), ptr::null_mut(), 0),
+                -1
+            );
+            assert!(!error().is_empty());
+
+            // combinations blocked by the board are excluded
+            let range = CString::new("AA,KK:0.5").unwrap();
+            let board = CString::new("As Kh 2c").unwrap();
+            let n = psf_range_combos(
+                range.as_ptr(),
+                ptr::null(),
+                ptr::null_mut(),
+                0,
+                ptr::null_mut(),
+                0,
+            );
+            assert_eq!(n, 12);
+            let n = psf_range_combos(
+                range.as_ptr(),
+                board.as_ptr(),
+                ptr::null_mut(),
+                0,
+                ptr::null_mut(),
+                0,
+            );
+            assert_eq!(n, 6);
+
+            let mut hands = vec![0 as c_char; 6 * PSF_HAND_STRING_STRIDE];
+            let mut weights = vec![0.0; 6];
+            let n = psf_range_combos(
+                range.as_ptr(),
+                board.as_ptr(),
+                hands.as_mut_ptr(),
+                hands.len(),
+                weights.as_mut_ptr(),
+                weights.len(),
+            );
+            assert_eq!(n, 6);
+            let hand = CStr::from_ptr(hands.as_ptr().add(3 * PSF_HAND_STRING_STRIDE));
+            assert_eq!(hand.to_str().unwrap(), "AdAc");
+            assert_eq!(weights, [0.5, 0.5, 0.5, 1.0, 1.0, 1.0]);
+
+            let board = CString::new("AsAs").unwrap();
+            let n = psf_range_combos(
+                range.as_ptr(),
+                board.as_ptr(),
+                ptr::null_mut(),
+                0,
+                ptr::null_mut(),
+                0,
+            );
+            assert_eq!(n, -1);
+            assert_eq!(error(), "Duplicate card: As");
+
+            // the same as the analysis API
+            let oop_range = CString::new("AA,KK,QQ,AK").unwrap();
+            let ip_range = CString::new("JJ-88,AQ,KQ").unwrap();
+            let board = CString::new("Td9d6hQc").unwrap();
+            let expected = range_equity(
+                &"AA,KK,QQ,AK".parse().unwrap(),
+                &"JJ-88,AQ,KQ".parse().unwrap(),
+                &[34, 29, 18, 40],
+            )
+            .unwrap();
+
+            let mut oop_equity = vec![0.0; expected.hands[0].len()];
+            let equity = psf_range_equity(
+                oop_range.as_ptr(),
+                ip_range.as_ptr(),
+                board.as_ptr(),
+                oop_equity.as_mut_ptr(),
+                oop_equity.len(),
+                ptr::null_mut(),
+                0,
+            );
+            assert_eq!(equity, expected.equity[0]);
+            assert_eq!(oop_equity[0], expected.hand_equity[0][0] as f32);
+
+            let board = CString::new("Td9d").unwrap();
+            let equity = psf_range_equity(
+                oop_range.as_ptr(),
+                ip_range.as_ptr(),
+                board.as_ptr(),
+                ptr::null_mut(),
+                0,
+                ptr::null_mut(),
+                0,
+            );
+            assert!(equity.is_nan());
+            assert_eq!(error(), "Invalid board length: 2");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "bincode")]
+    fn save_and_load() {
+        unsafe {
+            let game = create_game("Td9d6h");
+            let path = CString::new("tmpfile-ffi.flop").unwrap();
+
+            assert_eq!(psf_save(game, path.as_ptr(), 0), PSF_ERROR_INVALID_STATE);
+            assert_eq!(error(), "Data is not ready to save");
+
+            psf_solve(game, 100, 0.01, 0, None, ptr::null_mut(), ptr::null());
+            assert_eq!(psf_save(game, path.as_ptr(), 0), PSF_OK);
+
+            let loaded = psf_load(path.as_ptr());
+            assert!(!loaded.is_null());
+            assert_eq!(psf_num_actions(loaded), 2);
+            assert_eq!((*loaded).game.strategy(), (*game).game.strategy());
+            assert_eq!(psf_apply_line(loaded, [0, 0].as_ptr(), 2), 0);
+            psf_free(loaded);
+            psf_free(game);
+
+            let data = std::fs::read("tmpfile-ffi.flop").unwrap();
+            let last = data.len() - 1;
+            let load_modified = |f: &dyn Fn(&mut Vec<u8>)| {
+                let mut modified = data.clone();
+                f(&mut modified);
+                std::fs::write("tmpfile-ffi.flop", modified).unwrap();
+                let loaded = psf_load(path.as_ptr());
+                assert!(loaded.is_null());
+                psf_last_error_code()
+            };
+
+            assert_eq!(load_modified(&|d| d[1] ^= 1), PSF_ERROR_INVALID_FILE);
+            assert_eq!(load_modified(&|d| d[5] = 100), PSF_ERROR_VERSION);
+            assert_eq!(load_modified(&|d| d[last] ^= 1), PSF_ERROR_CORRUPTED);
+            std::fs::remove_file("tmpfile-ffi.flop").unwrap();
+
+            assert!(psf_load(path.as_ptr()).is_null());
+            assert_eq!(psf_last_error_code(), PSF_ERROR_IO);
+            assert!(psf_load(ptr::null()).is_null());
+            assert_eq!(psf_last_error_code(), PSF_ERROR_INVALID_ARGUMENT);
+            assert_eq!(error(), "Empty path");
+        }
+    }
+
+    struct Progress {
+        iterations: RefCell<Vec<u32>>,
+        cancel_flag: AtomicU8,
+    }
+
+    unsafe extern "C" fn record_progress(iteration: c_uint, _: c_float, user_data: *mut c_void) {
+        let progress = unsafe { &*(user_data as *const Progress) };
+        progress.iterations.borrow_mut().push(iteration);
+        if iteration == 5 {
+            progress.cancel_flag.store(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn solve_progress_and_cancel() {
+        let progress = Progress {
+            iterations: RefCell::new(Vec::new()),
+            cancel_flag: AtomicU8::new(0),
+        };
+
+        unsafe {
+            let game = create_game("Td9d6h");
+            let user_data = &progress as *const Progress as *mut c_void;
+            let cancel_flag = progress.cancel_flag.as_ptr();
+            let exploitability = psf_solve(
+                game,
+                1000,
+                0.0,
+                0,
+                Some(record_progress),
+                user_data,
+                cancel_flag,
+            );
+
+            // stopped after the 5th iteration, and the game is usable
+            assert!(exploitability.is_finite());
+            assert!(psf_get_strategy(game, ptr::null_mut(), 0) > 0);
+            assert_eq!((*game).game.solve_summary().unwrap().num_iterations, 5);
+            psf_free(game);
+        }
+
+        assert_eq!(*progress.iterations.borrow(), [0, 1, 2, 3, 4, 5]);
+    }
+}
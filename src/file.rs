@@ -1,24 +1,59 @@
 // [File format]
 // The file consists of a header and a body. The header is as follows:
 //  - Magic number (4 bytes): 90 57 f1 09
-//  - Version number (1 byte): 1
-//  - Compression type (1 byte): 0 (none), 1 (zstd)
+//  - Version number (1 byte): 7 (1 for legacy files)
+//  - Compression type (1 byte): 0 (none), 1 (zstd), 2 (zstd with dictionary)
 //  - Data type (1 byte): 0 (game), 1 (bunching)
+//  - Crate version string (not in legacy files)
+//  - Data version string (not in legacy files)
 //  - Estimated memory usage (`VarIntEncoding`)
 //  - Memo string
+//  - Game metadata (`Option<GameMetadata>`, not in legacy files)
+//
+// The body is the encoded data, compressed if specified, followed by the checksum of the encoded
+// data before compression (XXH64, 8 bytes, little endian, not in legacy files).
 //
 // `VarIntEncoding`: https://github.com/bincode-org/bincode/blob/trunk/docs/spec.md#varintencoding
+//
+// [Chunked file format]
+// The chunked file of a game consists of a header, chunks, and an index. The header is as follows:
+//  - Magic number (4 bytes): 90 57 f1 09
+//  - Version number (1 byte): 8
+//  - Compression type (1 byte): 0 (none), 1 (zstd)
+//  - Data type (1 byte): 0 (game)
+//  - Crate version string
+//  - Data version string
+//  - Estimated memory usage when loading up to each street (`[u64; 3]`, `VarIntEncoding`)
+//  - Memo string
+//  - Game metadata (`Option<GameMetadata>`)
+//
+// The header is followed by the chunks: the metadata, and then the nodes and the four global
+// storages of the flop, turn, and river, in this order. Each chunk is compressed independently. The
+// storage chunks are raw bytes, aligned to 8 bytes if not compressed. The chunks are followed by
+// the index, a list of (position, length) pairs of the chunks, and the checksums of the stored
+// bytes of the chunks (`Vec<u64>`, XXH64). The file ends with the position of the
+// index (8 bytes, little endian).
 
+use crate::action_tree::{BoardState, TreeConfig};
 use crate::bunching::*;
+use crate::card::CardConfig;
+use crate::error::*;
 use crate::game::*;
 use crate::interface::*;
 use bincode::{Decode, Encode};
+use std::fmt;
 use std::fs::File;
-use std::io::{BufReader, BufWriter};
+use std::hash::Hasher;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
 use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use twox_hash::XxHash64;
 
 const MAGIC: u32 = 0x09f15790;
-const VERSION: u8 = 1;
+const VERSION: u8 = 7;
+const VERSION_CHUNKED: u8 = 8;
+const VERSION_LEGACY: u8 = 1;
+const CRATE_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[doc(hidden)]
 pub enum DataType {
@@ -31,6 +66,12 @@ pub trait FileData: Decode<()> + Encode {
     #[doc(hidden)]
     fn data_type() -> DataType;
     #[doc(hidden)]
+    fn data_version() -> &'static str;
+    #[doc(hidden)]
+    fn metadata(&self) -> Option<GameMetadata>;
+    #[doc(hidden)]
+    fn restore_metadata(&mut self, metadata: &GameMetadata);
+    #[doc(hidden)]
     fn is_ready_to_save(&self) -> bool;
     #[doc(hidden)]
     fn estimated_memory_usage(&self) -> u64;
@@ -40,10 +81,129 @@ fn encode_into_std_write<E: Encode, WStdio: std::io::Write>(
     val: E,
     writer: &mut WStdio,
     err_msg: &str,
-) -> Result<(), String> {
+) -> Result<(), PostFlopError> {
     bincode::encode_into_std_write(val, writer, bincode::config::standard())
         .map(|_| ())
-        .map_err(|e| format!("{}: {}", err_msg, e))
+        .map_err(|e| io_error(err_msg, e))
+}
+
+/// Creates a [`PostFlopError::Io`] error with the given context.
+fn io_error(context: &str, error: impl fmt::Display) -> PostFlopError {
+    PostFlopError::Io(format!("{}: {}", context, error))
+}
+
+/// A reader that computes the checksum of the read bytes.
+struct HashingReader<'a, R: Read> {
+    inner: &'a mut R,
+    hasher: XxHash64,
+}
+
+impl<R: Read> Read for HashingReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.hasher.write(&buf[..len]);
+        Ok(len)
+    }
+}
+
+/// Interval of the progress reports (in bytes).
+const PROGRESS_INTERVAL: u64 = 1 << 20;
+
+/// Progress of saving or loading data, reported to a callback.
+struct Progress<'a> {
+    callback: &'a mut dyn FnMut(u64, u64),
+    processed: u64,
+    reported: u64,
+    total: u64,
+}
+
+impl<'a> Progress<'a> {
+    fn new(callback: &'a mut dyn FnMut(u64, u64), total: u64) -> Self {
+        Self {
+            callback,
+            processed: 0,
+            reported: 0,
+            total,
+        }
+    }
+
+    /// Advances the progress by `len` bytes, and reports it if the interval has elapsed.
+    fn advance(&mut self, len: usize) {
+        self.processed += len as u64;
+        if self.processed >= self.reported + PROGRESS_INTERVAL {
+            self.reported = self.processed;
+            (self.callback)(self.processed.min(self.total), self.total);
+        }
+    }
+
+    /// Reports the completion.
+    fn finish(&mut self) {
+        (self.callback)(self.total, self.total);
+    }
+}
+
+/// A reader or writer that advances the progress by the number of read or written bytes.
+struct ProgressIo<'a, 'b, T> {
+    inner: &'a mut T,
+    progress: Option<&'a mut Progress<'b>>,
+}
+
+impl<T: Read> Read for ProgressIo<'_, '_, T> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        if let Some(progress) = &mut self.progress {
+            progress.advance(len);
+        }
+        Ok(len)
+    }
+}
+
+impl<T: Write> Write for ProgressIo<'_, '_, T> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let len = self.inner.write(buf)?;
+        if let Some(progress) = &mut self.progress {
+            progress.advance(len);
+        }
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Encodes the value into the writer, and returns the checksum of the encoded bytes.
+fn encode_with_checksum<E: Encode, W: Write>(
+    val: E,
+    writer: &mut W,
+    progress: Option<&mut Progress>,
+    err_msg: &str,
+) -> Result<u64, PostFlopError> {
+    let mut writer = ProgressIo {
+        inner: writer,
+        progress,
+    };
+    let mut writer = CountingWriter::new(&mut writer);
+    encode_into_std_write(val, &mut writer, err_msg)?;
+    Ok(writer.hasher.finish())
+}
+
+/// Decodes a value from the reader, and returns it with the checksum of the decoded bytes.
+fn decode_with_checksum<D: Decode<()>, R: Read>(
+    reader: &mut R,
+    progress: Option<&mut Progress>,
+    err_msg: &str,
+) -> Result<(D, u64), LoadError> {
+    let mut reader = ProgressIo {
+        inner: reader,
+        progress,
+    };
+    let mut reader = HashingReader {
+        inner: &mut reader,
+        hasher: XxHash64::with_seed(0),
+    };
+    let data = decode_from_std_read(&mut reader, err_msg)?;
+    Ok((data, reader.hasher.finish()))
 }
 
 /// Saves data into a standard writer.
@@ -58,29 +218,124 @@ fn encode_into_std_write<E: Encode, WStdio: std::io::Write>(
 /// - `memo`: A memo string to be saved with the data.
 /// - `writer`: The writer to write the data into.
 /// - `compression_level`: The zstd compression level to use. If `None`, no compression is used.
-///   `Some(level)` can only be specified if the `zstd` feature is enabled.
+///   `Some(level)` can only be specified if the `zstd` feature is enabled. Higher levels compress
+///   better but slower; `Some(0)` uses the default level of zstd.
 pub fn save_data_into_std_write<T: FileData, WStdio: std::io::Write>(
     data: &T,
     memo: &str,
     writer: &mut WStdio,
     compression_level: Option<i32>,
-) -> Result<(), String> {
-    if !data.is_ready_to_save() {
-        return Err("Data is not ready to save".to_string());
-    }
+) -> Result<(), PostFlopError> {
+    save_data_impl(data, memo, writer, compression_level, None, None)
+}
+
+/// Saves data into a standard writer with zstd compression using a pre-trained dictionary.
+///
+/// A dictionary trained on similar data significantly improves the compression ratio of small
+/// files. The same dictionary must be passed to [`load_data_with_dictionary`] to load the data.
+/// See [`save_data_into_std_write`] for the other arguments.
+#[cfg(feature = "zstd")]
+pub fn save_data_with_dictionary<T: FileData, WStdio: std::io::Write>(
+    data: &T,
+    memo: &str,
+    writer: &mut WStdio,
+    compression_level: i32,
+    dictionary: &[u8],
+) -> Result<(), PostFlopError> {
+    save_data_impl(
+        data,
+        memo,
+        writer,
+        Some(compression_level),
+        Some(dictionary),
+        None,
+    )
+}
+
+/// Saves data into a standard writer, reporting the progress to a callback.
+///
+/// `progress` is called periodically with the number of processed bytes and the estimated total
+/// number of bytes, and finally with both set to the total when the data is written. The counts
+/// refer to the uncompressed data and the total is the estimated memory usage, so the progress is
+/// approximate. See [`save_data_into_std_write`] for the other arguments.
+///
+/// Saving is synchronous and may take a long time for large games. In an async runtime such as
+/// `tokio`, move the data into a blocking task (e.g., `tokio::task::spawn_blocking`) and forward
+/// the progress through a channel, so that the runtime threads are not blocked.
+pub fn save_data_with_progress<T: FileData, WStdio: std::io::Write, F: FnMut(u64, u64)>(
+    data: &T,
+    memo: &str,
+    writer: &mut WStdio,
+    compression_level: Option<i32>,
+    mut progress: F,
+) -> Result<(), PostFlopError> {
+    save_data_impl(
+        data,
+        memo,
+        writer,
+        compression_level,
+        None,
+        Some(&mut progress),
+    )
+}
+
+/// Creates the error returned when the data is not ready to save.
+fn not_ready_to_save() -> PostFlopError {
+    PostFlopError::InvalidState("Data is not ready to save".to_string())
+}
 
+/// Checks that the given compression level is supported.
+fn check_compression_level(compression_level: Option<i32>) -> Result<(), PostFlopError> {
     #[cfg(not(feature = "zstd"))]
     if compression_level.is_some() {
-        return Err("Compression is not supported".to_string());
+        return Err(PostFlopError::Unsupported(
+            "Compression is not supported".to_string(),
+        ));
+    }
+
+    #[cfg(feature = "zstd")]
+    if let Some(compression_level) = compression_level {
+        let range = zstd::compression_level_range();
+        if !range.contains(&compression_level) {
+            return Err(PostFlopError::InvalidArgument(format!(
+                "Compression level must be in the range {}..={}: {}",
+                range.start(),
+                range.end(),
+                compression_level
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn save_data_impl<T: FileData, WStdio: std::io::Write>(
+    data: &T,
+    memo: &str,
+    writer: &mut WStdio,
+    compression_level: Option<i32>,
+    #[cfg_attr(not(feature = "zstd"), allow(unused_variables))] dictionary: Option<&[u8]>,
+    progress: Option<&mut dyn FnMut(u64, u64)>,
+) -> Result<(), PostFlopError> {
+    if !data.is_ready_to_save() {
+        return Err(not_ready_to_save());
     }
 
+    check_compression_level(compression_level)?;
+
     encode_into_std_write(MAGIC, writer, "Failed to write magic number")?;
     encode_into_std_write(VERSION, writer, "Failed to write version number")?;
 
-    let compression_type = compression_level.is_some() as u8;
+    let compression_type = match (compression_level, dictionary) {
+        (None, _) => 0u8,
+        (Some(_), None) => 1,
+        (Some(_), Some(_)) => 2,
+    };
     encode_into_std_write(compression_type, writer, "Failed to write compression type")?;
 
     encode_into_std_write(T::data_type() as u8, writer, "Failed to write data type")?;
+    encode_into_std_write(CRATE_VERSION, writer, "Failed to write crate version")?;
+    encode_into_std_write(T::data_version(), writer, "Failed to write data version")?;
     encode_into_std_write(
         data.estimated_memory_usage(),
         writer,
@@ -88,30 +343,56 @@ pub fn save_data_into_std_write<T: FileData, WStdio: std::io::Write>(
     )?;
 
     encode_into_std_write(memo, writer, "Failed to write memo")?;
+    encode_into_std_write(data.metadata(), writer, "Failed to write metadata")?;
 
-    if compression_level.is_none() {
-        encode_into_std_write(data, writer, "Failed to write data")?;
-        writer
-            .flush()
-            .map_err(|e| format!("Failed to flush writer: {}", e))?;
-    }
+    let total = data.estimated_memory_usage();
+    let mut progress = progress.map(|callback| Progress::new(callback, total));
+
+    #[cfg(not(feature = "zstd"))]
+    let checksum = encode_with_checksum(data, writer, progress.as_mut(), "Failed to write data")?;
 
     #[cfg(feature = "zstd")]
-    if let Some(compression_level) = compression_level {
-        let mut zstd_encoder = zstd::stream::Encoder::new(writer, compression_level)
-            .map_err(|e| format!("Failed to create zstd encoder: {}", e))?;
+    let checksum = if let Some(compression_level) = compression_level {
+        let mut zstd_encoder = match dictionary {
+            None => zstd::stream::Encoder::new(&mut *writer, compression_level),
+            Some(dictionary) => {
+                zstd::stream::Encoder::with_dictionary(&mut *writer, compression_level, dictionary)
+            }
+        }
+        .map_err(|e| io_error("Failed to create zstd encoder", e))?;
 
         #[cfg(feature = "rayon")]
         zstd_encoder
             .multithread(rayon::current_num_threads() as u32)
-            .map_err(|e| format!("Failed to enable multithreaded zstd encoder: {}", e))?;
+            .map_err(|e| io_error("Failed to enable multithreaded zstd encoder", e))?;
 
-        encode_into_std_write(data, &mut zstd_encoder, "Failed to write data")?;
+        zstd_encoder
+            .include_checksum(true)
+            .map_err(|e| io_error("Failed to enable zstd checksum", e))?;
+
+        let checksum = encode_with_checksum(
+            data,
+            &mut zstd_encoder,
+            progress.as_mut(),
+            "Failed to write data",
+        )?;
         zstd_encoder
             .finish()
-            .map_err(|e| format!("Failed to finish zstd encoder: {}", e))?
-            .flush()
-            .map_err(|e| format!("Failed to flush writer: {}", e))?;
+            .map_err(|e| io_error("Failed to finish zstd encoder", e))?;
+        checksum
+    } else {
+        encode_with_checksum(data, writer, progress.as_mut(), "Failed to write data")?
+    };
+
+    writer
+        .write_all(&checksum.to_le_bytes())
+        .map_err(|e| io_error("Failed to write checksum", e))?;
+    writer
+        .flush()
+        .map_err(|e| io_error("Failed to flush writer", e))?;
+
+    if let Some(progress) = &mut progress {
+        progress.finish();
     }
 
     Ok(())
@@ -128,28 +409,259 @@ pub fn save_data_into_std_write<T: FileData, WStdio: std::io::Write>(
 /// - `memo`: A memo string to be saved with the data.
 /// - `path`: The path to the file to save.
 /// - `compression_level`: The zstd compression level to use. If `None`, no compression is used.
-///   `Some(level)` can only be specified if the `zstd` feature is enabled.
+///   `Some(level)` can only be specified if the `zstd` feature is enabled. Higher levels compress
+///   better but slower; `Some(0)` uses the default level of zstd.
 pub fn save_data_to_file<T: FileData, P: AsRef<Path>>(
     data: &T,
     memo: &str,
     path: P,
     compression_level: Option<i32>,
-) -> Result<(), String> {
-    let file = File::create(path).map_err(|e| format!("Failed to create file: {}", e))?;
+) -> Result<(), PostFlopError> {
+    let file = File::create(path).map_err(|e| io_error("Failed to create file", e))?;
     let mut writer = BufWriter::new(file);
     save_data_into_std_write(data, memo, &mut writer, compression_level)
 }
 
-fn decode_from_std_read<DFile: Decode<()>, RStdio: std::io::Read>(reader: &mut RStdio, err_msg: &str) -> Result<DFile, String> {
+fn decode_from_std_read<DFile: Decode<()>, RStdio: std::io::Read>(
+    reader: &mut RStdio,
+    err_msg: &str,
+) -> Result<DFile, LoadError> {
     bincode::decode_from_std_read(reader, bincode::config::standard())
-        .map_err(|e| format!("{}: {}", err_msg, e))
+        .map_err(|e| LoadError::Decode(format!("{}: {}", err_msg, e)))
 }
 
-/// Loads data from a standard reader.
+/// Error returned by [`load_data_with_migration`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadError {
+    /// The data is not saved by this crate.
+    InvalidMagic,
+
+    /// The format version is not supported by this version of the crate.
+    UnsupportedFormatVersion(u8),
+
+    /// The compression type is unknown.
+    InvalidCompressionType,
+
+    /// The data is compressed, but the `zstd` feature is disabled.
+    CompressionNotSupported,
+
+    /// The data is compressed with a dictionary, but no dictionary is given.
+    DictionaryRequired,
+
+    /// The data type does not match the requested type.
+    DataTypeMismatch,
+
+    /// The estimated memory usage exceeds the given limit.
+    MemoryLimitExceeded {
+        /// Estimated memory usage of the data (in bytes).
+        estimated: u64,
+
+        /// Given limit (in bytes).
+        limit: u64,
+    },
+
+    /// The data layout is incompatible with this version of the crate and cannot be migrated.
+    IncompatibleData {
+        /// Data version expected by this version of the crate.
+        expected: String,
+
+        /// Data version of the file.
+        found: String,
+
+        /// Version of the crate that saved the file.
+        crate_version: String,
+    },
+
+    /// The checksum of the given chunk does not match; the data is corrupted. The body of a file
+    /// in the standard format is regarded as chunk 0.
+    Corrupted {
+        /// Index of the corrupted chunk.
+        chunk: usize,
+    },
+
+    /// Reading or decoding the data failed.
+    Decode(String),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidMagic => write!(f, "Magic number is invalid"),
+            Self::UnsupportedFormatVersion(version) => {
+                write!(f, "Version number is invalid: {version}")
+            }
+            Self::InvalidCompressionType => write!(f, "Compression type is invalid"),
+            Self::CompressionNotSupported => write!(f, "Compression is not supported"),
+            Self::DictionaryRequired => write!(f, "Dictionary is required"),
+            Self::DataTypeMismatch => write!(f, "Data type is invalid"),
+            Self::MemoryLimitExceeded { estimated, limit } => write!(
+                f,
+                "Estimated memory usage is too large: {estimated} bytes (limit: {limit} bytes)"
+            ),
+            Self::IncompatibleData {
+                expected,
+                found,
+                crate_version,
+            } => write!(
+                f,
+                "Data version is incompatible: expected {expected}, found {found} \
+                 (saved by version {crate_version})"
+            ),
+            Self::Corrupted { chunk } => {
+                write!(f, "Data is corrupted: checksum mismatch in chunk {chunk}")
+            }
+            Self::Decode(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl From<String> for LoadError {
+    #[inline]
+    fn from(message: String) -> Self {
+        Self::Decode(message)
+    }
+}
+
+/// Metadata of a saved game, which can be read by [`peek_metadata`] without decoding the game.
+#[derive(Debug, Clone, Decode, Encode)]
+pub struct GameMetadata {
+    /// Card configuration of the game, including the ranges.
+    pub card_config: CardConfig,
+
+    /// Tree configuration of the game.
+    pub tree_config: TreeConfig,
+
+    /// Storage mode of the saved game.
+    pub storage_mode: BoardState,
+
+    /// Summary of the solve, or `None` if not recorded.
+    pub solve_summary: Option<SolveSummary>,
+
+    /// Time of the save in seconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+/// Information about a loaded file.
+#[derive(Debug, Clone)]
+pub struct FileInfo {
+    /// Format version of the file.
+    pub format_version: u8,
+
+    /// Version of the crate that saved the file, or `None` for legacy files.
+    pub crate_version: Option<String>,
+
+    /// Memo string saved with the data.
+    pub memo: String,
+
+    /// Metadata of the saved game, or `None` for bunching data and legacy files.
+    pub metadata: Option<GameMetadata>,
+}
+
+impl FileInfo {
+    /// Returns whether the file was migrated from a legacy format. Saving the loaded data again
+    /// writes it in the current format.
+    #[inline]
+    pub fn is_migrated(&self) -> bool {
+        self.format_version != VERSION && self.format_version != VERSION_CHUNKED
+    }
+}
+
+/// Header of a file.
+struct Header {
+    info: FileInfo,
+    is_chunked: bool,
+    has_checksum: bool,
+    compression_type: u8,
+    data_type: u8,
+    memory_usage: [u64; 3],
+}
+
+/// Reads the header of a file of any supported format version.
+fn read_header<R: Read>(reader: &mut R) -> Result<Header, LoadError> {
+    let magic: u32 = decode_from_std_read(reader, "Failed to read magic number")?;
+    if magic != MAGIC {
+        return Err(LoadError::InvalidMagic);
+    }
+
+    let version: u8 = decode_from_std_read(reader, "Failed to read version number")?;
+    let is_chunked = match version {
+        VERSION_LEGACY | VERSION => false,
+        VERSION_CHUNKED => true,
+        _ => return Err(LoadError::UnsupportedFormatVersion(version)),
+    };
+    let is_legacy = version == VERSION_LEGACY;
+
+    let compression_type: u8 = decode_from_std_read(reader, "Failed to read compression type")?;
+    if compression_type > 2 || (is_chunked && compression_type == 2) {
+        return Err(LoadError::InvalidCompressionType);
+    }
+
+    let data_type: u8 = decode_from_std_read(reader, "Failed to read data type")?;
+    let data_version = match data_type {
+        0 => <PostFlopGame as FileData>::data_version(),
+        1 if !is_chunked => <BunchingData as FileData>::data_version(),
+        _ => return Err(LoadError::DataTypeMismatch),
+    };
+
+    let crate_version = if is_legacy {
+        None
+    } else {
+        Some(read_versions(reader, data_version)?)
+    };
+
+    let memory_usage = if is_chunked {
+        decode_from_std_read(reader, "Failed to read memory usage")?
+    } else {
+        [decode_from_std_read(reader, "Failed to read memory usage")?; 3]
+    };
+
+    let memo: String = decode_from_std_read(reader, "Failed to read memo")?;
+
+    let metadata = if is_legacy {
+        None
+    } else {
+        decode_from_std_read(reader, "Failed to read metadata")?
+    };
+
+    let info = FileInfo {
+        format_version: version,
+        crate_version,
+        memo,
+        metadata,
+    };
+
+    Ok(Header {
+        info,
+        is_chunked,
+        has_checksum: !is_legacy,
+        compression_type,
+        data_type,
+        memory_usage,
+    })
+}
+
+/// Reads the crate version and the data version, and checks that the data version matches.
+fn read_versions<R: Read>(reader: &mut R, expected: &str) -> Result<String, LoadError> {
+    let crate_version: String = decode_from_std_read(reader, "Failed to read crate version")?;
+    let data_version: String = decode_from_std_read(reader, "Failed to read data version")?;
+    if data_version != expected {
+        return Err(LoadError::IncompatibleData {
+            expected: expected.to_string(),
+            found: data_version,
+            crate_version,
+        });
+    }
+    Ok(crate_version)
+}
+
+/// Loads data from a standard reader, upgrading older formats where possible.
 ///
-/// This function deserializes the data from the `reader`.
-/// This is useful if you want to load the data from a custom reader like `Vec<u8>`, but if you want
-/// to load the data from a file, use [`load_data_from_file`] instead.
+/// In addition to the current format, this function reads legacy files that were saved without the
+/// crate version, so that they can be saved again in the current format. If the data cannot be
+/// loaded, a typed [`LoadError`] is returned; in particular, [`LoadError::IncompatibleData`]
+/// indicates that the file was saved by a version of the crate with an incompatible data layout.
 ///
 /// # Arguments
 ///
@@ -159,58 +671,188 @@ fn decode_from_std_read<DFile: Decode<()>, RStdio: std::io::Read>(reader: &mut R
 ///
 /// # Returns
 ///
-/// A tuple of the deserialized data (either a [`PostFlopGame`] or a [`BunchingData`]) and the memo
-/// string.
-pub fn load_data_from_std_read<T: FileData, RStdio: std::io::Read>(
+/// A tuple of the deserialized data (either a [`PostFlopGame`] or a [`BunchingData`]) and the
+/// information about the file.
+pub fn load_data_with_migration<T: FileData, RStdio: std::io::Read>(
     reader: &mut RStdio,
     max_memory_usage: Option<u64>,
-) -> Result<(T, String), String> {
-    let magic: u32 = decode_from_std_read(reader, "Failed to read magic number")?;
-    if magic != MAGIC {
-        return Err("Magic number is invalid".to_string());
-    }
+) -> Result<(T, FileInfo), LoadError> {
+    load_data_impl(reader, max_memory_usage, None, None)
+}
 
-    let version: u8 = decode_from_std_read(reader, "Failed to read version number")?;
-    if version != VERSION {
-        return Err("Version number is invalid".to_string());
-    }
+/// Loads data compressed with a pre-trained dictionary from a standard reader.
+///
+/// The `dictionary` must be the same as the one passed to [`save_data_with_dictionary`]. Data
+/// saved without a dictionary can also be loaded. See [`load_data_with_migration`] for the other
+/// arguments and the return value.
+#[cfg(feature = "zstd")]
+pub fn load_data_with_dictionary<T: FileData, RStdio: std::io::Read>(
+    reader: &mut RStdio,
+    max_memory_usage: Option<u64>,
+    dictionary: &[u8],
+) -> Result<(T, FileInfo), LoadError> {
+    load_data_impl(reader, max_memory_usage, Some(dictionary), None)
+}
 
-    let compression_type: u8 = decode_from_std_read(reader, "Failed to read compression type")?;
-    if compression_type > 1 {
-        return Err("Compression type is invalid".to_string());
+/// Loads data from a standard reader, reporting the progress to a callback.
+///
+/// `progress` is called periodically with the number of processed bytes and the estimated total
+/// number of bytes, and finally with both set to the total when the data is loaded. The counts
+/// refer to the uncompressed data and the total is the estimated memory usage stored in the file,
+/// so the progress is approximate. See [`load_data_with_migration`] for the other arguments and
+/// the return value.
+///
+/// As with [`save_data_with_progress`], call this function in a blocking task when using an async
+/// runtime.
+pub fn load_data_with_progress<T: FileData, RStdio: std::io::Read, F: FnMut(u64, u64)>(
+    reader: &mut RStdio,
+    max_memory_usage: Option<u64>,
+    mut progress: F,
+) -> Result<(T, FileInfo), LoadError> {
+    load_data_impl(reader, max_memory_usage, None, Some(&mut progress))
+}
+
+fn load_data_impl<T: FileData, RStdio: std::io::Read>(
+    reader: &mut RStdio,
+    max_memory_usage: Option<u64>,
+    #[cfg_attr(not(feature = "zstd"), allow(unused_variables))] dictionary: Option<&[u8]>,
+    progress: Option<&mut dyn FnMut(u64, u64)>,
+) -> Result<(T, FileInfo), LoadError> {
+    let header = read_header(reader)?;
+    if header.is_chunked {
+        return Err(LoadError::UnsupportedFormatVersion(
+            header.info.format_version,
+        ));
     }
 
     #[cfg(not(feature = "zstd"))]
-    if compression_type == 1 {
-        return Err("Compression is not supported".to_string());
+    if header.compression_type != 0 {
+        return Err(LoadError::CompressionNotSupported);
     }
 
-    let data_type: u8 = decode_from_std_read(reader, "Failed to read data type")?;
-    if data_type != T::data_type() as u8 {
-        return Err("Data type is invalid".to_string());
+    if header.data_type != T::data_type() as u8 {
+        return Err(LoadError::DataTypeMismatch);
     }
 
-    let estimated_memory_usage: u64 = decode_from_std_read(reader, "Failed to read memory usage")?;
     if let Some(max_memory_usage) = max_memory_usage {
-        if estimated_memory_usage > max_memory_usage {
-            return Err("Estimated memory usage is too large".to_string());
+        if header.memory_usage[0] > max_memory_usage {
+            return Err(LoadError::MemoryLimitExceeded {
+                estimated: header.memory_usage[0],
+                limit: max_memory_usage,
+            });
         }
     }
 
-    let memo: String = decode_from_std_read(reader, "Failed to read memo")?;
+    let total = header.memory_usage[0];
+    let mut progress = progress.map(|callback| Progress::new(callback, total));
 
     #[cfg(not(feature = "zstd"))]
-    let data: T = decode_from_std_read(reader, "Failed to read data")?;
+    let (mut data, is_valid): (T, bool) = {
+        let (data, checksum) =
+            decode_with_checksum(reader, progress.as_mut(), "Failed to read data")?;
+        (
+            data,
+            verify_checksum(reader, header.has_checksum, checksum)?,
+        )
+    };
     #[cfg(feature = "zstd")]
-    let data: T = if compression_type == 0 {
-        decode_from_std_read(reader, "Failed to read data")?
-    } else {
-        let mut zstd_decoder = zstd::stream::Decoder::new(reader)
-            .map_err(|e| format!("Failed to create zstd decoder: {}", e))?;
-        decode_from_std_read(&mut zstd_decoder, "Failed to read data")?
+    let (mut data, is_valid): (T, bool) = match header.compression_type {
+        0 => {
+            let (data, checksum) =
+                decode_with_checksum(reader, progress.as_mut(), "Failed to read data")?;
+            (
+                data,
+                verify_checksum(reader, header.has_checksum, checksum)?,
+            )
+        }
+        1 => {
+            let zstd_decoder = zstd::stream::Decoder::new(reader)
+                .map_err(|e| format!("Failed to create zstd decoder: {}", e))?;
+            decode_zstd_with_checksum(zstd_decoder, header.has_checksum, progress.as_mut())?
+        }
+        _ => {
+            let dictionary = dictionary.ok_or(LoadError::DictionaryRequired)?;
+            let zstd_decoder =
+                zstd::stream::Decoder::with_dictionary(BufReader::new(reader), dictionary)
+                    .map_err(|e| format!("Failed to create zstd decoder: {}", e))?;
+            decode_zstd_with_checksum(zstd_decoder, header.has_checksum, progress.as_mut())?
+        }
     };
 
-    Ok((data, memo))
+    if !is_valid {
+        return Err(LoadError::Corrupted { chunk: 0 });
+    }
+
+    if let Some(progress) = &mut progress {
+        progress.finish();
+    }
+
+    if let Some(metadata) = &header.info.metadata {
+        data.restore_metadata(metadata);
+    }
+
+    Ok((data, header.info))
+}
+
+/// Reads the stored checksum following the data, and returns whether it matches `computed`.
+/// Returns `true` without reading if the file has no checksum.
+fn verify_checksum<R: Read>(
+    reader: &mut R,
+    has_checksum: bool,
+    computed: u64,
+) -> Result<bool, LoadError> {
+    if !has_checksum {
+        return Ok(true);
+    }
+    let mut buf = [0; 8];
+    reader
+        .read_exact(&mut buf)
+        .map_err(|e| format!("Failed to read checksum: {}", e))?;
+    Ok(u64::from_le_bytes(buf) == computed)
+}
+
+/// Decodes a zstd frame, and reads the stored checksum following it.
+///
+/// The decoder is drained to the end of the frame, so that the checksum of zstd itself is also
+/// verified. Returns the decoded data and whether the checksum matches.
+#[cfg(feature = "zstd")]
+fn decode_zstd_with_checksum<D: Decode<()>, R: std::io::BufRead>(
+    zstd_decoder: zstd::stream::Decoder<'_, R>,
+    has_checksum: bool,
+    progress: Option<&mut Progress>,
+) -> Result<(D, bool), LoadError> {
+    let mut zstd_decoder = zstd_decoder.single_frame();
+    let (data, checksum) =
+        decode_with_checksum(&mut zstd_decoder, progress, "Failed to read data")?;
+    std::io::copy(&mut zstd_decoder, &mut std::io::sink())
+        .map_err(|e| format!("Failed to read data: {}", e))?;
+    let mut reader = zstd_decoder.finish();
+    Ok((data, verify_checksum(&mut reader, has_checksum, checksum)?))
+}
+
+/// Loads data from a standard reader.
+///
+/// This function deserializes the data from the `reader`.
+/// This is useful if you want to load the data from a custom reader like `Vec<u8>`, but if you want
+/// to load the data from a file, use [`load_data_from_file`] instead. Legacy files are migrated as
+/// in [`load_data_with_migration`].
+///
+/// # Arguments
+///
+/// - `reader`: The reader to read the data from.
+/// - `max_memory_usage`: The maximum memory usage allowed for the data (in bytes). If `None`, no
+///   limit is set. If the estimated memory usage exceeds this value, `Err` is returned.
+///
+/// # Returns
+///
+/// A tuple of the deserialized data (either a [`PostFlopGame`] or a [`BunchingData`]) and the memo
+/// string.
+pub fn load_data_from_std_read<T: FileData, RStdio: std::io::Read>(
+    reader: &mut RStdio,
+    max_memory_usage: Option<u64>,
+) -> Result<(T, String), PostFlopError> {
+    let (data, info) = load_data_with_migration(reader, max_memory_usage)?;
+    Ok((data, info.memo))
 }
 
 /// Loads data from a file.
@@ -230,31 +872,701 @@ pub fn load_data_from_std_read<T: FileData, RStdio: std::io::Read>(
 pub fn load_data_from_file<T: FileData, P: AsRef<Path>>(
     path: P,
     max_memory_usage: Option<u64>,
-) -> Result<(T, String), String> {
-    let file = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+) -> Result<(T, String), PostFlopError> {
+    let file = File::open(path).map_err(|e| io_error("Failed to open file", e))?;
     let mut reader = BufReader::new(file);
     load_data_from_std_read(&mut reader, max_memory_usage)
 }
 
-impl FileData for PostFlopGame {
-    fn data_type() -> DataType {
-        DataType::Game
-    }
+/// Reads the information of a file from a standard reader without decoding the data.
+///
+/// Both the standard and the chunked formats are supported. The reader is left at an unspecified
+/// position. See [`peek_metadata`] for details.
+pub fn peek_metadata_from_std_read<RStdio: std::io::Read>(
+    reader: &mut RStdio,
+) -> Result<FileInfo, LoadError> {
+    read_header(reader).map(|header| header.info)
+}
 
-    fn is_ready_to_save(&self) -> bool {
-        self.is_solved()
-    }
+/// Reads the information of a file without decoding the data.
+///
+/// Only the header of the file is read, so this is fast even for large games and is useful for
+/// cataloging saved games. The returned [`FileInfo`] contains the [`GameMetadata`] (card
+/// configuration, ranges, tree configuration, solve summary, and save time) if the file is a game
+/// saved in the current format.
+pub fn peek_metadata<P: AsRef<Path>>(path: P) -> Result<FileInfo, LoadError> {
+    let file = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    let mut reader = BufReader::new(file);
+    peek_metadata_from_std_read(&mut reader)
+}
 
-    fn estimated_memory_usage(&self) -> u64 {
-        self.target_memory_usage()
-    }
+/// A writer that counts the number of written bytes and computes their checksum.
+struct CountingWriter<'a, W: Write> {
+    inner: &'a mut W,
+    position: u64,
+    hasher: XxHash64,
 }
 
-impl FileData for BunchingData {
+impl<'a, W: Write> CountingWriter<'a, W> {
+    fn new(inner: &'a mut W) -> Self {
+        Self {
+            inner,
+            position: 0,
+            hasher: XxHash64::with_seed(0),
+        }
+    }
+
+    /// Resets the checksum computation.
+    fn reset_hasher(&mut self) {
+        self.hasher = XxHash64::with_seed(0);
+    }
+}
+
+impl<W: Write> Write for CountingWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let len = self.inner.write(buf)?;
+        self.position += len as u64;
+        self.hasher.write(&buf[..len]);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// A writer of the chunks of a chunked file.
+pub(crate) struct ChunkWriter<'a, W: Write> {
+    writer: CountingWriter<'a, W>,
+    compression_level: Option<i32>,
+    index: Vec<(u64, u64)>,
+    checksums: Vec<u64>,
+    target: Option<usize>,
+}
+
+impl<W: Write> ChunkWriter<'_, W> {
+    /// Returns whether the next chunk is skipped because only the target chunk is written.
+    fn skip_chunk(&mut self) -> bool {
+        let skip = self.target.is_some_and(|target| target != self.index.len());
+        if skip {
+            self.index.push((0, 0));
+            self.checksums.push(0);
+        }
+        skip
+    }
+
+    /// Writes the given value as a chunk.
+    pub(crate) fn write_chunk<E: Encode>(&mut self, value: E) -> Result<(), PostFlopError> {
+        if self.skip_chunk() {
+            return Ok(());
+        }
+
+        let start = self.writer.position;
+        self.writer.reset_hasher();
+
+        if self.compression_level.is_none() {
+            encode_into_std_write(&value, &mut self.writer, "Failed to write chunk")?;
+        }
+
+        #[cfg(feature = "zstd")]
+        if let Some(compression_level) = self.compression_level {
+            let mut zstd_encoder = zstd::stream::Encoder::new(&mut self.writer, compression_level)
+                .map_err(|e| io_error("Failed to create zstd encoder", e))?;
+            encode_into_std_write(value, &mut zstd_encoder, "Failed to write chunk")?;
+            zstd_encoder
+                .finish()
+                .map_err(|e| io_error("Failed to finish zstd encoder", e))?;
+        }
+
+        self.index.push((start, self.writer.position - start));
+        self.checksums.push(self.writer.hasher.finish());
+        Ok(())
+    }
+
+    /// Writes the given bytes as a chunk without encoding. If not compressed, the chunk is aligned
+    /// to 8 bytes.
+    pub(crate) fn write_raw_chunk(&mut self, bytes: &[u8]) -> Result<(), PostFlopError> {
+        if self.skip_chunk() {
+            return Ok(());
+        }
+
+        let write_error = |e: std::io::Error| io_error("Failed to write chunk", e);
+
+        if self.compression_level.is_none() {
+            let padding = self.writer.position.wrapping_neg() % 8;
+            self.writer
+                .write_all(&[0; 8][..padding as usize])
+                .map_err(write_error)?;
+        }
+
+        let start = self.writer.position;
+        self.writer.reset_hasher();
+
+        if self.compression_level.is_none() {
+            self.writer.write_all(bytes).map_err(write_error)?;
+        }
+
+        #[cfg(feature = "zstd")]
+        if let Some(compression_level) = self.compression_level {
+            let mut zstd_encoder = zstd::stream::Encoder::new(&mut self.writer, compression_level)
+                .map_err(|e| io_error("Failed to create zstd encoder", e))?;
+            zstd_encoder.write_all(bytes).map_err(write_error)?;
+            zstd_encoder
+                .finish()
+                .map_err(|e| io_error("Failed to finish zstd encoder", e))?;
+        }
+
+        self.index.push((start, self.writer.position - start));
+        self.checksums.push(self.writer.hasher.finish());
+        Ok(())
+    }
+}
+
+/// A seekable reader that can be owned by a game.
+pub(crate) trait ReadSeek: Read + Seek + Send + Sync {}
+
+impl<T: Read + Seek + Send + Sync> ReadSeek for T {}
+
+/// A reader of the chunks of a chunked file.
+pub(crate) struct ChunkReader<R: Read + Seek> {
+    reader: R,
+    #[cfg_attr(not(feature = "zstd"), allow(dead_code))]
+    is_compressed: bool,
+    index: Vec<(u64, u64)>,
+    checksums: Vec<u64>,
+}
+
+impl<R: Read + Seek> ChunkReader<R> {
+    /// Returns the number of chunks.
+    pub(crate) fn num_chunks(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Reads the stored bytes of the `i`-th chunk and verifies their checksum.
+    fn read_stored_chunk(&mut self, i: usize) -> Result<Vec<u8>, LoadError> {
+        let (position, len) = self.index[i];
+        let mut bytes = vec![0; len as usize];
+        self.reader
+            .seek(SeekFrom::Start(position))
+            .and_then(|_| self.reader.read_exact(&mut bytes))
+            .map_err(|e| format!("Failed to read chunk: {}", e))?;
+
+        if XxHash64::oneshot(0, &bytes) != self.checksums[i] {
+            return Err(LoadError::Corrupted { chunk: i });
+        }
+
+        Ok(bytes)
+    }
+
+    /// Reads the `i`-th chunk.
+    pub(crate) fn read_chunk<D: Decode<()>>(&mut self, i: usize) -> Result<D, LoadError> {
+        let bytes = self.read_stored_chunk(i)?;
+
+        #[cfg(not(feature = "zstd"))]
+        let data = decode_from_std_read(&mut &bytes[..], "Failed to read chunk")?;
+        #[cfg(feature = "zstd")]
+        let data = if !self.is_compressed {
+            decode_from_std_read(&mut &bytes[..], "Failed to read chunk")?
+        } else {
+            let mut zstd_decoder = zstd::stream::Decoder::new(&bytes[..])
+                .map_err(|e| format!("Failed to create zstd decoder: {}", e))?;
+            decode_from_std_read(&mut zstd_decoder, "Failed to read chunk")?
+        };
+
+        Ok(data)
+    }
+
+    /// Reads the `i`-th chunk written by [`ChunkWriter::write_raw_chunk`].
+    pub(crate) fn read_raw_chunk(&mut self, i: usize) -> Result<Vec<u8>, LoadError> {
+        let bytes = self.read_stored_chunk(i)?;
+
+        #[cfg(feature = "zstd")]
+        if self.is_compressed {
+            let data = zstd::stream::decode_all(&bytes[..])
+                .map_err(|e| format!("Failed to read chunk: {}", e))?;
+            return Ok(data);
+        }
+
+        Ok(bytes)
+    }
+
+    /// Returns the chunk reader that owns the underlying reader as a trait object.
+    pub(crate) fn into_boxed(self) -> ChunkReader<Box<dyn ReadSeek>>
+    where
+        R: ReadSeek + 'static,
+    {
+        ChunkReader {
+            reader: Box::new(self.reader),
+            is_compressed: self.is_compressed,
+            index: self.index,
+            checksums: self.checksums,
+        }
+    }
+
+    /// Returns the byte range of the `i`-th chunk in the file.
+    pub(crate) fn chunk_range(&self, i: usize) -> std::ops::Range<usize> {
+        let (position, len) = self.index[i];
+        position as usize..(position + len) as usize
+    }
+}
+
+/// Saves a game into a standard writer in the chunked format.
+///
+/// Unlike [`save_data_into_std_write`], the game is split into chunks by street, and each chunk is
+/// written (and compressed) independently with an index. This allows [`load_game_chunked`] to load
+/// only the streets up to a given street. The target storage mode of the game is ignored; all the
+/// stored streets are saved.
+///
+/// # Arguments
+///
+/// - `game`: The game to be saved.
+/// - `memo`: A memo string to be saved with the game.
+/// - `writer`: The writer to write the game into.
+/// - `compression_level`: The zstd compression level to use. If `None`, no compression is used.
+///   `Some(level)` can only be specified if the `zstd` feature is enabled. Higher levels compress
+///   better but slower; `Some(0)` uses the default level of zstd.
+pub fn save_game_chunked<W: Write>(
+    game: &PostFlopGame,
+    memo: &str,
+    writer: &mut W,
+    compression_level: Option<i32>,
+) -> Result<(), PostFlopError> {
+    if !game.is_ready_to_save() {
+        return Err(not_ready_to_save());
+    }
+
+    check_compression_level(compression_level)?;
+
+    let mut writer = CountingWriter::new(writer);
+
+    encode_into_std_write(MAGIC, &mut writer, "Failed to write magic number")?;
+    encode_into_std_write(
+        VERSION_CHUNKED,
+        &mut writer,
+        "Failed to write version number",
+    )?;
+
+    let compression_type = compression_level.is_some() as u8;
+    encode_into_std_write(
+        compression_type,
+        &mut writer,
+        "Failed to write compression type",
+    )?;
+
+    encode_into_std_write(
+        DataType::Game as u8,
+        &mut writer,
+        "Failed to write data type",
+    )?;
+    encode_into_std_write(CRATE_VERSION, &mut writer, "Failed to write crate version")?;
+    encode_into_std_write(
+        PostFlopGame::data_version(),
+        &mut writer,
+        "Failed to write data version",
+    )?;
+    encode_into_std_write(
+        game.chunked_memory_usage(),
+        &mut writer,
+        "Failed to write memory usage",
+    )?;
+
+    encode_into_std_write(memo, &mut writer, "Failed to write memo")?;
+    let metadata = game.metadata().map(|metadata| GameMetadata {
+        storage_mode: game.storage_mode(),
+        ..metadata
+    });
+    encode_into_std_write(metadata, &mut writer, "Failed to write metadata")?;
+
+    let mut chunk_writer = ChunkWriter {
+        writer,
+        compression_level,
+        index: Vec::new(),
+        checksums: Vec::new(),
+        target: None,
+    };
+
+    game.write_chunks(&mut chunk_writer)?;
+
+    let mut writer = chunk_writer.writer;
+    let index_position = writer.position;
+    encode_into_std_write(&chunk_writer.index, &mut writer, "Failed to write index")?;
+    encode_into_std_write(
+        &chunk_writer.checksums,
+        &mut writer,
+        "Failed to write checksums",
+    )?;
+    writer
+        .write_all(&index_position.to_le_bytes())
+        .map_err(|e| io_error("Failed to write index position", e))?;
+    writer
+        .flush()
+        .map_err(|e| io_error("Failed to flush writer", e))?;
+
+    Ok(())
+}
+
+/// Saves a game into a file in the chunked format.
+///
+/// See [`save_game_chunked`] for details. If the file already exists, it will be overwritten.
+pub fn save_game_chunked_to_file<P: AsRef<Path>>(
+    game: &PostFlopGame,
+    memo: &str,
+    path: P,
+    compression_level: Option<i32>,
+) -> Result<(), PostFlopError> {
+    let file = File::create(path).map_err(|e| io_error("Failed to create file", e))?;
+    let mut writer = BufWriter::new(file);
+    save_game_chunked(game, memo, &mut writer, compression_level)
+}
+
+/// Re-saves a game into an existing file in the chunked format, rewriting only the changed chunks.
+///
+/// The chunks of the `game` are compared with the checksums stored in the file, and only the
+/// chunks that differ are written: in place if their length is unchanged, and otherwise appended
+/// to the end of the file. The index at the end of the file is then rewritten. This is much faster
+/// than [`save_game_chunked_to_file`] when only a part of a large game has changed.
+///
+/// The file must have been saved by [`save_game_chunked_to_file`] without compression, from a game
+/// with the same configuration and storage mode. The header of the file, including the memo and
+/// the [`GameMetadata`], is not updated, and the space of the replaced chunks is not reclaimed;
+/// save the game in full to compact the file. Since the file is modified in place, it may be left
+/// corrupted if the process is interrupted.
+///
+/// Returns the number of rewritten chunks.
+pub fn resave_incremental<P: AsRef<Path>>(
+    game: &PostFlopGame,
+    path: P,
+) -> Result<usize, PostFlopError> {
+    if !game.is_ready_to_save() {
+        return Err(not_ready_to_save());
+    }
+
+    if game.deferred_storage_mode().is_some() {
+        return Err(PostFlopError::InvalidState(
+            "Deferred streets are not loaded".to_string(),
+        ));
+    }
+
+    let file = File::options()
+        .read(true)
+        .write(true)
+        .open(path)
+        .map_err(|e| io_error("Failed to open file", e))?;
+
+    let reader = BufReader::new(&file);
+    let (chunk_reader, header) = read_chunked_header(reader, BoardState::River, None)?;
+
+    if chunk_reader.is_compressed {
+        return Err(PostFlopError::Unsupported(
+            "Compressed file cannot be re-saved incrementally".to_string(),
+        ));
+    }
+
+    let mut checksums = chunk_reader.checksums;
+
+    let storage_mode = header.info.metadata.map(|metadata| metadata.storage_mode);
+    if storage_mode != Some(game.storage_mode())
+        || header.memory_usage != game.chunked_memory_usage()
+    {
+        return Err(PostFlopError::InvalidArgument(
+            "Game does not match the file".to_string(),
+        ));
+    }
+
+    // computes the lengths and the checksums of the chunks without writing them
+    let mut sink = std::io::sink();
+    let mut chunk_writer = ChunkWriter {
+        writer: CountingWriter::new(&mut sink),
+        compression_level: None,
+        index: Vec::new(),
+        checksums: Vec::new(),
+        target: None,
+    };
+    game.write_chunks(&mut chunk_writer)?;
+
+    let mut index = chunk_reader.index;
+    if chunk_writer.index.len() != index.len() {
+        return Err(LoadError::Decode("Chunk index is invalid".to_string()).into());
+    }
+
+    let dirty_chunks = (0..index.len())
+        .filter(|&i| {
+            index[i].1 != chunk_writer.index[i].1 || checksums[i] != chunk_writer.checksums[i]
+        })
+        .collect::<Vec<_>>();
+
+    // the old index is overwritten by the appended chunks or the new index
+    let mut end = index.iter().map(|&(pos, len)| pos + len).max().unwrap_or(0);
+    let mut writer = BufWriter::new(&file);
+
+    for &i in &dirty_chunks {
+        let position = if index[i].1 == chunk_writer.index[i].1 {
+            index[i].0
+        } else {
+            end
+        };
+
+        writer
+            .seek(SeekFrom::Start(position))
+            .map_err(|e| io_error("Failed to seek chunk", e))?;
+
+        let mut counting_writer = CountingWriter::new(&mut writer);
+        counting_writer.position = position;
+        let mut target_writer = ChunkWriter {
+            writer: counting_writer,
+            compression_level: None,
+            index: Vec::new(),
+            checksums: Vec::new(),
+            target: Some(i),
+        };
+        game.write_chunks(&mut target_writer)?;
+
+        index[i] = target_writer.index[i];
+        checksums[i] = target_writer.checksums[i];
+        end = end.max(index[i].0 + index[i].1);
+    }
+
+    writer
+        .seek(SeekFrom::Start(end))
+        .map_err(|e| io_error("Failed to seek index", e))?;
+    let mut counting_writer = CountingWriter::new(&mut writer);
+    encode_into_std_write(&index, &mut counting_writer, "Failed to write index")?;
+    encode_into_std_write(
+        &checksums,
+        &mut counting_writer,
+        "Failed to write checksums",
+    )?;
+    counting_writer
+        .write_all(&end.to_le_bytes())
+        .map_err(|e| io_error("Failed to write index position", e))?;
+    let file_len = end + counting_writer.position;
+
+    writer
+        .flush()
+        .map_err(|e| io_error("Failed to flush writer", e))?;
+    file.set_len(file_len)
+        .map_err(|e| io_error("Failed to truncate file", e))?;
+
+    Ok(dirty_chunks.len())
+}
+
+/// Loads a game saved in the chunked format from a seekable reader.
+///
+/// Only the streets up to `max_street` are loaded, as if the game was saved with the target
+/// storage mode of `max_street` (see [`PostFlopGame::set_target_storage_mode`]). If the file
+/// contains fewer streets, all the stored streets are loaded.
+///
+/// # Arguments
+///
+/// - `reader`: The reader to read the game from.
+/// - `max_street`: The deepest street to load. Must not be lower than the initial state.
+/// - `max_memory_usage`: The maximum memory usage allowed for the game (in bytes). If `None`, no
+///   limit is set. If the estimated memory usage exceeds this value, `Err` is returned.
+///
+/// # Returns
+///
+/// A tuple of the deserialized game and the memo string. If a chunk fails the checksum check,
+/// [`LoadError::Corrupted`] is returned with the index of the chunk.
+pub fn load_game_chunked<R: Read + Seek>(
+    reader: &mut R,
+    max_street: BoardState,
+    max_memory_usage: Option<u64>,
+) -> Result<(PostFlopGame, String), LoadError> {
+    let (mut chunk_reader, header) = read_chunked_header(reader, max_street, max_memory_usage)?;
+    let (mut game, _) = PostFlopGame::read_chunks(&mut chunk_reader, max_street, None)?;
+    if let Some(metadata) = &header.info.metadata {
+        game.restore_metadata(metadata);
+    }
+    Ok((game, header.info.memo))
+}
+
+/// Reads the header and the index of a chunked file.
+fn read_chunked_header<R: Read + Seek>(
+    mut reader: R,
+    max_street: BoardState,
+    max_memory_usage: Option<u64>,
+) -> Result<(ChunkReader<R>, Header), LoadError> {
+    let header = read_header(&mut reader)?;
+    if !header.is_chunked {
+        let version = header.info.format_version;
+        return Err(LoadError::UnsupportedFormatVersion(version));
+    }
+
+    #[cfg(not(feature = "zstd"))]
+    if header.compression_type != 0 {
+        return Err(LoadError::CompressionNotSupported);
+    }
+
+    if let Some(max_memory_usage) = max_memory_usage {
+        let estimated = header.memory_usage[max_street as usize];
+        if estimated > max_memory_usage {
+            return Err(LoadError::MemoryLimitExceeded {
+                estimated,
+                limit: max_memory_usage,
+            });
+        }
+    }
+
+    let mut index_position = [0; 8];
+    reader
+        .seek(SeekFrom::End(-8))
+        .and_then(|_| reader.read_exact(&mut index_position))
+        .map_err(|e| format!("Failed to read index position: {}", e))?;
+    reader
+        .seek(SeekFrom::Start(u64::from_le_bytes(index_position)))
+        .map_err(|e| format!("Failed to seek index: {}", e))?;
+    let index: Vec<(u64, u64)> = decode_from_std_read(&mut reader, "Failed to read index")?;
+
+    let checksums: Vec<u64> = decode_from_std_read(&mut reader, "Failed to read checksums")?;
+    if checksums.len() != index.len() {
+        return Err("Chunk index is invalid".to_string().into());
+    }
+
+    let chunk_reader = ChunkReader {
+        reader,
+        is_compressed: header.compression_type != 0,
+        index,
+        checksums,
+    };
+
+    Ok((chunk_reader, header))
+}
+
+/// Loads a game saved in the chunked format from a file.
+///
+/// See [`load_game_chunked`] for details.
+pub fn load_game_chunked_from_file<P: AsRef<Path>>(
+    path: P,
+    max_street: BoardState,
+    max_memory_usage: Option<u64>,
+) -> Result<(PostFlopGame, String), LoadError> {
+    let file = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    let mut reader = BufReader::new(file);
+    load_game_chunked(&mut reader, max_street, max_memory_usage)
+}
+
+/// Loads a game saved in the chunked format from a seekable reader, deferring the streets after
+/// `max_street`.
+///
+/// The game takes the ownership of the reader, and the deferred streets are loaded on demand when
+/// the [`PostFlopGame::play`] method deals a card of a deferred street, or explicitly by the
+/// [`PostFlopGame::load_deferred_streets`] method. Note that `play` panics if the loading fails.
+/// `max_memory_usage` is checked only for the streets loaded upfront. See [`load_game_chunked`] for
+/// the other arguments.
+pub fn load_game_chunked_lazy<R: Read + Seek + Send + Sync + 'static>(
+    reader: R,
+    max_street: BoardState,
+    max_memory_usage: Option<u64>,
+) -> Result<(PostFlopGame, String), LoadError> {
+    let (mut chunk_reader, header) = read_chunked_header(reader, max_street, max_memory_usage)?;
+    let (mut game, storage_mode) = PostFlopGame::read_chunks(&mut chunk_reader, max_street, None)?;
+    if let Some(metadata) = &header.info.metadata {
+        game.restore_metadata(metadata);
+    }
+    if storage_mode > game.storage_mode() {
+        game.defer_chunks(chunk_reader.into_boxed(), storage_mode);
+    }
+    Ok((game, header.info.memo))
+}
+
+/// Loads a game saved in the chunked format from a file, deferring the streets after
+/// `max_street`.
+///
+/// See [`load_game_chunked_lazy`] for details.
+pub fn load_game_chunked_lazy_from_file<P: AsRef<Path>>(
+    path: P,
+    max_street: BoardState,
+    max_memory_usage: Option<u64>,
+) -> Result<(PostFlopGame, String), LoadError> {
+    let file = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    load_game_chunked_lazy(BufReader::new(file), max_street, max_memory_usage)
+}
+
+/// Loads a game saved in the chunked format from a file by memory-mapping it.
+///
+/// The global storages of the game are not copied into heap memory; the nodes refer to the mapped
+/// file directly, and the operating system pages in the data on demand. This allows serving many
+/// large solved games at once. The loaded game supports navigation and all the query methods, but
+/// it cannot be saved, and the file must not be modified while the game is alive.
+///
+/// Only uncompressed files can be mapped. The checksums of the storage chunks are not verified,
+/// since that would require reading the whole file. See [`load_game_chunked`] for the other
+/// arguments.
+#[cfg(feature = "mmap")]
+pub fn load_game_chunked_mmap<P: AsRef<Path>>(
+    path: P,
+    max_street: BoardState,
+) -> Result<(PostFlopGame, String), LoadError> {
+    let file = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    let mmap =
+        unsafe { memmap2::Mmap::map(&file) }.map_err(|e| format!("Failed to map file: {}", e))?;
+
+    let cursor = std::io::Cursor::new(&mmap[..]);
+    let (mut chunk_reader, header) = read_chunked_header(cursor, max_street, None)?;
+    if chunk_reader.is_compressed {
+        return Err("Compressed file cannot be memory-mapped".to_string().into());
+    }
+
+    let (mut game, _) = PostFlopGame::read_chunks(&mut chunk_reader, max_street, Some(&mmap))?;
+    if let Some(metadata) = &header.info.metadata {
+        game.restore_metadata(metadata);
+    }
+    game.set_mapped_file(mmap);
+    Ok((game, header.info.memo))
+}
+
+impl FileData for PostFlopGame {
+    fn data_type() -> DataType {
+        DataType::Game
+    }
+
+    fn data_version() -> &'static str {
+        PostFlopGame::data_version()
+    }
+
+    fn metadata(&self) -> Option<GameMetadata> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        Some(GameMetadata {
+            card_config: self.card_config().clone(),
+            tree_config: self.tree_config().clone(),
+            storage_mode: self.target_storage_mode(),
+            solve_summary: self.solve_summary(),
+            timestamp,
+        })
+    }
+
+    fn restore_metadata(&mut self, metadata: &GameMetadata) {
+        if let Some(summary) = metadata.solve_summary {
+            self.set_solve_summary(summary);
+        }
+    }
+
+    fn is_ready_to_save(&self) -> bool {
+        self.is_solved()
+    }
+
+    fn estimated_memory_usage(&self) -> u64 {
+        self.target_memory_usage()
+    }
+}
+
+impl FileData for BunchingData {
     fn data_type() -> DataType {
         DataType::Bunching
     }
 
+    fn data_version() -> &'static str {
+        "2023-03-19"
+    }
+
+    fn metadata(&self) -> Option<GameMetadata> {
+        None
+    }
+
+    fn restore_metadata(&mut self, _metadata: &GameMetadata) {}
+
     fn is_ready_to_save(&self) -> bool {
         self.is_ready()
     }
@@ -268,8 +1580,10 @@ impl FileData for BunchingData {
 mod tests {
     use super::*;
     use crate::action_tree::*;
+    use crate::bet_size::*;
     use crate::card::*;
     use crate::range::*;
+    use crate::solver::*;
     use crate::utility::*;
 
     #[test]
@@ -291,7 +1605,7 @@ mod tests {
         let action_tree = ActionTree::new(tree_config).unwrap();
         let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
 
-        game.allocate_memory(false);
+        game.allocate_memory(false, None).unwrap();
         finalize(&mut game);
 
         // save
@@ -317,6 +1631,13 @@ mod tests {
         // remove tmpfile
         std::fs::remove_file("tmpfile.flop").unwrap();
 
+        assert_eq!(
+            game.set_weight(0, 0, 0.5),
+            Err(PostFlopError::InvalidState(
+                "Game tree is partially loaded".to_string()
+            ))
+        );
+
         game.cache_normalized_weights();
         let weights_oop = game.normalized_weights(0);
         let weights_ip = game.normalized_weights(1);
@@ -332,48 +1653,822 @@ mod tests {
     }
 
     #[test]
-    #[cfg(feature = "zstd")]
-    fn save_and_load_file_compressed() {
+    fn save_and_load_strategy_only() {
         let card_config = CardConfig {
-            range: [Range::ones(); 2],
+            range: [
+                "AA,KK,QQ,AK,AQ".parse().unwrap(),
+                "JJ-88,AJ,KQ".parse().unwrap(),
+            ],
             flop: flop_from_str("Td9d6h").unwrap(),
+            turn: card_from_str("Qc").unwrap(),
             ..Default::default()
         };
 
         let tree_config = TreeConfig {
+            initial_state: BoardState::Turn,
             starting_pot: 60,
             effective_stack: 970,
-            flop_bet_sizes: [("50%", "").try_into().unwrap(), Default::default()],
             turn_bet_sizes: [("50%", "").try_into().unwrap(), Default::default()],
+            river_bet_sizes: [("50%", "").try_into().unwrap(), Default::default()],
             ..Default::default()
         };
 
         let action_tree = ActionTree::new(tree_config).unwrap();
         let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
 
-        game.allocate_memory(false);
-        finalize(&mut game);
+        game.allocate_memory(false, None).unwrap();
+        solve(&mut game, 20, 0.0, false);
 
-        // save
-        save_data_to_file(&game, "", "tmpfile-zstd.flop", Some(3)).unwrap();
+        let mut full = Vec::new();
+        save_data_into_std_write(&game, "", &mut full, None).unwrap();
 
-        // load
-        let mut game: PostFlopGame = load_data_from_file("tmpfile-zstd.flop", None).unwrap().0;
+        game.set_target_storage_mode(BoardState::Turn).unwrap();
+        assert!(game.set_target_strategy_only(true).is_err());
+        game.set_target_storage_mode(BoardState::River).unwrap();
+        game.set_target_strategy_only(true).unwrap();
 
-        // remove tmpfile
-        std::fs::remove_file("tmpfile-zstd.flop").unwrap();
+        let mut small = Vec::new();
+        save_data_into_std_write(&game, "", &mut small, None).unwrap();
+        assert!(small.len() < full.len() * 2 / 3);
 
-        game.cache_normalized_weights();
-        let weights_oop = game.normalized_weights(0);
-        let weights_ip = game.normalized_weights(1);
-        let root_equity_oop = compute_average(&game.equity(0), weights_oop);
-        let root_equity_ip = compute_average(&game.equity(1), weights_ip);
-        let root_ev_oop = compute_average(&game.expected_values(0), weights_oop);
-        let root_ev_ip = compute_average(&game.expected_values(1), weights_ip);
+        let mut loaded: PostFlopGame = load_data_from_std_read(&mut small.as_slice(), None)
+            .unwrap()
+            .0;
+        assert!(loaded.is_solved());
 
-        assert!((root_equity_oop - 0.5).abs() < 1e-5);
-        assert!((root_equity_ip - 0.5).abs() < 1e-5);
-        assert!((root_ev_oop - 45.0).abs() < 1e-4);
-        assert!((root_ev_ip - 15.0).abs() < 1e-4);
+        for history in [&[][..], &[1], &[0, 0, 2]] {
+            game.apply_history(history);
+            loaded.apply_history(history);
+            game.cache_normalized_weights();
+            loaded.cache_normalized_weights();
+
+            let diff = |a: &[f32], b: &[f32]| a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-3);
+            assert!(diff(&loaded.strategy(), &game.strategy()));
+            for player in 0..2 {
+                let evs = game.expected_values(player);
+                let loaded_evs = loaded.expected_values(player);
+                assert!(evs
+                    .iter()
+                    .zip(&loaded_evs)
+                    .all(|(x, y)| (x - y).abs() < 1e-2));
+            }
+        }
+    }
+
+    #[test]
+    fn save_and_load_8bit() {
+        let card_config = CardConfig {
+            range: [
+                "AA,KK,QQ,AK,AQ".parse().unwrap(),
+                "JJ-88,AJ,KQ".parse().unwrap(),
+            ],
+            flop: flop_from_str("Td9d6h").unwrap(),
+            turn: card_from_str("Qc").unwrap(),
+            ..Default::default()
+        };
+
+        let tree_config = TreeConfig {
+            initial_state: BoardState::Turn,
+            starting_pot: 60,
+            effective_stack: 970,
+            turn_bet_sizes: [("50%", "").try_into().unwrap(), Default::default()],
+            river_bet_sizes: [("50%", "").try_into().unwrap(), Default::default()],
+            ..Default::default()
+        };
+
+        let action_tree = ActionTree::new(tree_config).unwrap();
+        let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
+
+        game.allocate_memory_with_format(StorageFormat::Int8, None)
+            .unwrap();
+        solve(&mut game, 20, 0.0, false);
+
+        let mut full = Vec::new();
+        save_data_into_std_write(&game, "", &mut full, None).unwrap();
+
+        game.set_target_strategy_only(true).unwrap();
+        let mut strategy_only = Vec::new();
+        save_data_into_std_write(&game, "", &mut strategy_only, None).unwrap();
+
+        for data in [full, strategy_only] {
+            let mut loaded: PostFlopGame = load_data_from_std_read(&mut data.as_slice(), None)
+                .unwrap()
+                .0;
+            assert_eq!(
+                loaded.allocated_storage_formats(),
+                Some((StorageFormat::Int8, StorageFormat::Int8))
+            );
+
+            for history in [&[][..], &[1], &[0, 0, 2]] {
+                game.apply_history(history);
+                loaded.apply_history(history);
+                assert_eq!(loaded.strategy(), game.strategy());
+            }
+        }
+    }
+
+    #[test]
+    fn save_trimmed_game() {
+        let card_config = CardConfig {
+            range: [
+                "AA,KK,QQ,AK,AQ".parse().unwrap(),
+                "JJ-88,AJ,KQ".parse().unwrap(),
+            ],
+            flop: flop_from_str("Td9d6h").unwrap(),
+            turn: card_from_str("Qc").unwrap(),
+            ..Default::default()
+        };
+
+        let tree_config = TreeConfig {
+            initial_state: BoardState::Turn,
+            starting_pot: 60,
+            effective_stack: 970,
+            turn_bet_sizes: [("50%", "").try_into().unwrap(), Default::default()],
+            river_bet_sizes: [("50%", "").try_into().unwrap(), Default::default()],
+            ..Default::default()
+        };
+
+        let action_tree = ActionTree::new(tree_config).unwrap();
+        let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
+
+        game.allocate_memory(false, None).unwrap();
+        solve(&mut game, 20, 0.0, false);
+        game.trim_to_strategy(true).unwrap();
+
+        let mut data = Vec::new();
+        save_data_into_std_write(&game, "", &mut data, None).unwrap();
+        let mut loaded: PostFlopGame = load_data_from_std_read(&mut data.as_slice(), None)
+            .unwrap()
+            .0;
+        assert_eq!(
+            loaded.allocated_storage_formats(),
+            Some((StorageFormat::Int16, StorageFormat::Int16))
+        );
+
+        for history in [&[][..], &[1], &[0, 0, 2]] {
+            game.apply_history(history);
+            loaded.apply_history(history);
+            assert_eq!(loaded.strategy(), game.strategy());
+        }
+
+        game.trim_to_strategy(false).unwrap();
+        assert!(save_data_into_std_write(&game, "", &mut Vec::new(), None).is_err());
+    }
+
+    #[test]
+    fn save_and_load_chunked() {
+        let card_config = CardConfig {
+            range: ["AA,KK,QQ,AK".parse().unwrap(), "JJ-99,KQ".parse().unwrap()],
+            flop: flop_from_str("Td9d6h").unwrap(),
+            ..Default::default()
+        };
+
+        let bet_sizes: BetSizeOptions = ("50%", "").try_into().unwrap();
+        let tree_config = TreeConfig {
+            starting_pot: 60,
+            effective_stack: 970,
+            flop_bet_sizes: [bet_sizes.clone(), Default::default()],
+            turn_bet_sizes: [bet_sizes.clone(), Default::default()],
+            river_bet_sizes: [bet_sizes, Default::default()],
+            ..Default::default()
+        };
+
+        let action_tree = ActionTree::new(tree_config).unwrap();
+        let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
+
+        game.allocate_memory(false, None).unwrap();
+        solve(&mut game, 10, 0.0, false);
+
+        let mut buf = Vec::new();
+        save_game_chunked(&game, "memo", &mut buf, None).unwrap();
+
+        let mut reader = std::io::Cursor::new(&buf);
+        let (_, memo) = load_game_chunked(&mut reader, BoardState::River, None).unwrap();
+        assert_eq!(memo, "memo");
+
+        let river_usage = game.chunked_memory_usage()[2];
+        let mut reader = std::io::Cursor::new(&buf);
+        assert!(load_game_chunked(&mut reader, BoardState::River, Some(river_usage - 1)).is_err());
+
+        for street in [BoardState::Flop, BoardState::Turn, BoardState::River] {
+            let mut reader = std::io::Cursor::new(&buf);
+            let mut loaded = load_game_chunked(&mut reader, street, Some(river_usage))
+                .unwrap()
+                .0;
+            assert_eq!(loaded.storage_mode(), street);
+
+            // root, turn (2c), and river (2d) after check-check
+            let histories = [&[][..], &[0, 0, 0], &[0, 0, 0, 0, 0, 1]];
+            for &history in &histories[..=street as usize] {
+                game.apply_history(history);
+                loaded.apply_history(history);
+                game.cache_normalized_weights();
+                loaded.cache_normalized_weights();
+                assert_eq!(loaded.strategy(), game.strategy());
+                for player in 0..2 {
+                    assert_eq!(loaded.expected_values(player), game.expected_values(player));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn save_and_load_mixed_formats() {
+        let card_config = CardConfig {
+            range: ["AA,KK,QQ,AK".parse().unwrap(), "JJ-99,KQ".parse().unwrap()],
+            flop: flop_from_str("Td9d6h").unwrap(),
+            ..Default::default()
+        };
+
+        let bet_sizes: BetSizeOptions = ("50%", "").try_into().unwrap();
+        let tree_config = TreeConfig {
+            starting_pot: 60,
+            effective_stack: 970,
+            flop_bet_sizes: [bet_sizes.clone(), Default::default()],
+            turn_bet_sizes: [bet_sizes.clone(), Default::default()],
+            river_bet_sizes: [bet_sizes, Default::default()],
+            ..Default::default()
+        };
+
+        let action_tree = ActionTree::new(tree_config).unwrap();
+        let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
+
+        let formats = (StorageFormat::Float16, StorageFormat::Float32);
+        game.allocate_memory_with_formats(formats.0, formats.1, None)
+            .unwrap();
+        solve(&mut game, 10, 0.0, false);
+
+        let mut chunked = Vec::new();
+        save_game_chunked(&game, "", &mut chunked, None).unwrap();
+        let mut reader = std::io::Cursor::new(&chunked);
+        let mut loaded_chunked = load_game_chunked(&mut reader, BoardState::River, None)
+            .unwrap()
+            .0;
+
+        game.set_target_storage_mode(BoardState::Turn).unwrap();
+        let mut data = Vec::new();
+        save_data_into_std_write(&game, "", &mut data, None).unwrap();
+        let mut loaded: PostFlopGame = load_data_from_std_read(&mut data.as_slice(), None)
+            .unwrap()
+            .0;
+
+        for other in [&mut loaded_chunked, &mut loaded] {
+            assert_eq!(other.allocated_storage_formats(), Some(formats));
+
+            // root and turn (2c) after check-check
+            for history in [&[][..], &[0, 0, 0]] {
+                game.apply_history(history);
+                other.apply_history(history);
+                game.cache_normalized_weights();
+                other.cache_normalized_weights();
+                assert_eq!(other.strategy(), game.strategy());
+                for player in 0..2 {
+                    assert_eq!(other.expected_values(player), game.expected_values(player));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn peek_metadata_of_saved_game() {
+        let card_config = CardConfig {
+            range: ["AA,KK,QQ,AK".parse().unwrap(), "JJ-99,KQ".parse().unwrap()],
+            flop: flop_from_str("Td9d6h").unwrap(),
+            ..Default::default()
+        };
+
+        let tree_config = TreeConfig {
+            starting_pot: 60,
+            effective_stack: 970,
+            flop_bet_sizes: [("50%", "").try_into().unwrap(), Default::default()],
+            ..Default::default()
+        };
+
+        let action_tree = ActionTree::new(tree_config).unwrap();
+        let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
+
+        game.allocate_memory(false, None).unwrap();
+        assert_eq!(game.solve_summary(), None);
+        let exploitability = solve(&mut game, 10, 0.0, false);
+
+        let summary = game.solve_summary().unwrap();
+        assert_eq!(summary.num_iterations, 10);
+        assert_eq!(summary.exploitability, exploitability);
+
+        // standard format
+        game.set_target_storage_mode(BoardState::Turn).unwrap();
+        save_data_to_file(&game, "memo", "tmpfile-peek.flop", None).unwrap();
+        let info = peek_metadata("tmpfile-peek.flop").unwrap();
+        assert_eq!(info.format_version, VERSION);
+        assert_eq!(info.memo, "memo");
+
+        let metadata = info.metadata.unwrap();
+        assert_eq!(metadata.card_config.range[0].to_string(), "QQ+,AK");
+        assert_eq!(metadata.card_config.flop, game.card_config().flop);
+        assert_eq!(metadata.tree_config.starting_pot, 60);
+        assert_eq!(metadata.tree_config.effective_stack, 970);
+        assert_eq!(metadata.storage_mode, BoardState::Turn);
+        assert_eq!(metadata.solve_summary, Some(summary));
+        assert!(metadata.timestamp > 0);
+
+        let loaded: PostFlopGame = load_data_from_file("tmpfile-peek.flop", None).unwrap().0;
+        assert_eq!(loaded.solve_summary(), Some(summary));
+        std::fs::remove_file("tmpfile-peek.flop").unwrap();
+
+        // chunked format
+        let mut buf = Vec::new();
+        save_game_chunked(&game, "", &mut buf, None).unwrap();
+        let info = peek_metadata_from_std_read(&mut buf.as_slice()).unwrap();
+        assert_eq!(info.format_version, VERSION_CHUNKED);
+        assert_eq!(info.metadata.unwrap().storage_mode, BoardState::River);
+
+        let mut reader = std::io::Cursor::new(&buf);
+        let loaded = load_game_chunked(&mut reader, BoardState::Flop, None)
+            .unwrap()
+            .0;
+        assert_eq!(loaded.solve_summary(), Some(summary));
+    }
+
+    #[test]
+    fn load_chunked_lazy() {
+        let card_config = CardConfig {
+            range: ["AA,KK,QQ,AK".parse().unwrap(), "JJ-99,KQ".parse().unwrap()],
+            flop: flop_from_str("Td9d6h").unwrap(),
+            ..Default::default()
+        };
+
+        let bet_sizes: BetSizeOptions = ("50%", "").try_into().unwrap();
+        let tree_config = TreeConfig {
+            starting_pot: 60,
+            effective_stack: 970,
+            flop_bet_sizes: [bet_sizes.clone(), Default::default()],
+            turn_bet_sizes: [bet_sizes.clone(), Default::default()],
+            river_bet_sizes: [bet_sizes, Default::default()],
+            ..Default::default()
+        };
+
+        let action_tree = ActionTree::new(tree_config).unwrap();
+        let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
+
+        game.allocate_memory(false, None).unwrap();
+        solve(&mut game, 10, 0.0, false);
+
+        let mut buf = Vec::new();
+        save_game_chunked(&game, "", &mut buf, None).unwrap();
+
+        let reader = std::io::Cursor::new(buf);
+        let mut loaded = load_game_chunked_lazy(reader, BoardState::Flop, None)
+            .unwrap()
+            .0;
+        assert_eq!(loaded.storage_mode(), BoardState::Flop);
+        assert_eq!(loaded.deferred_storage_mode(), Some(BoardState::River));
+
+        // root, turn (2c), and river (2d) after check-check; the root is checked again after the
+        // deferred streets are loaded
+        let histories = [&[][..], &[0, 0, 0], &[0, 0, 0, 0, 0, 1], &[]];
+        let storage_modes = [
+            BoardState::Flop,
+            BoardState::Turn,
+            BoardState::River,
+            BoardState::River,
+        ];
+
+        for (&history, &storage_mode) in histories.iter().zip(&storage_modes) {
+            game.apply_history(history);
+            loaded.apply_history(history);
+            assert_eq!(loaded.storage_mode(), storage_mode);
+            game.cache_normalized_weights();
+            loaded.cache_normalized_weights();
+            assert_eq!(loaded.strategy(), game.strategy());
+            for player in 0..2 {
+                assert_eq!(loaded.expected_values(player), game.expected_values(player));
+            }
+        }
+
+        assert_eq!(loaded.deferred_storage_mode(), None);
+        assert!(loaded.load_deferred_streets(BoardState::River).is_ok());
+    }
+
+    #[test]
+    fn resave_chunked_incremental() {
+        let card_config = CardConfig {
+            range: ["AA,KK,QQ,AK".parse().unwrap(), "JJ-99,KQ".parse().unwrap()],
+            flop: flop_from_str("Td9d6h").unwrap(),
+            ..Default::default()
+        };
+
+        let bet_sizes: BetSizeOptions = ("50%", "").try_into().unwrap();
+        let tree_config = TreeConfig {
+            starting_pot: 60,
+            effective_stack: 970,
+            flop_bet_sizes: [bet_sizes.clone(), Default::default()],
+            turn_bet_sizes: [bet_sizes.clone(), Default::default()],
+            river_bet_sizes: [bet_sizes, Default::default()],
+            ..Default::default()
+        };
+
+        let action_tree = ActionTree::new(tree_config.clone()).unwrap();
+        let mut game = PostFlopGame::with_config(card_config.clone(), action_tree).unwrap();
+        let action_tree = ActionTree::new(tree_config).unwrap();
+        let mut locked = PostFlopGame::with_config(card_config, action_tree).unwrap();
+
+        game.allocate_memory(false, None).unwrap();
+        solve(&mut game, 10, 0.0, false);
+
+        let path = "tmpfile-incremental.flop";
+        save_game_chunked_to_file(&game, "", path, None).unwrap();
+        assert_eq!(resave_incremental(&game, path), Ok(0));
+
+        // always check at the root
+        locked.allocate_memory(false, None).unwrap();
+        let num_hands = locked.num_private_hands(0);
+        let mut strategy = vec![0.0; 2 * num_hands];
+        strategy[..num_hands].fill(1.0);
+        locked.lock_current_strategy(&strategy);
+        solve(&mut locked, 20, 0.0, false);
+
+        assert!(resave_incremental(&locked, path).unwrap() > 0);
+        assert_eq!(resave_incremental(&locked, path), Ok(0));
+
+        let mut loaded = load_game_chunked_from_file(path, BoardState::River, None)
+            .unwrap()
+            .0;
+        std::fs::remove_file(path).unwrap();
+
+        // root, turn (2c), and river (2d) after check-check
+        for history in [&[][..], &[0, 0, 0], &[0, 0, 0, 0, 0, 1]] {
+            locked.apply_history(history);
+            loaded.apply_history(history);
+            assert_eq!(loaded.strategy(), locked.strategy());
+            assert_eq!(
+                loaded.current_locking_strategy(),
+                locked.current_locking_strategy()
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "mmap")]
+    fn load_chunked_mmap() {
+        let card_config = CardConfig {
+            range: ["AA,KK,QQ,AK".parse().unwrap(), "JJ-99,KQ".parse().unwrap()],
+            flop: flop_from_str("Td9d6h").unwrap(),
+            turn: card_from_str("Qc").unwrap(),
+            ..Default::default()
+        };
+
+        let tree_config = TreeConfig {
+            initial_state: BoardState::Turn,
+            starting_pot: 60,
+            effective_stack: 970,
+            turn_bet_sizes: [("50%", "").try_into().unwrap(), Default::default()],
+            river_bet_sizes: [("50%", "").try_into().unwrap(), Default::default()],
+            ..Default::default()
+        };
+
+        let action_tree = ActionTree::new(tree_config).unwrap();
+        let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
+
+        game.allocate_memory(false, None).unwrap();
+        solve(&mut game, 10, 0.0, false);
+
+        save_game_chunked_to_file(&game, "memo", "tmpfile-mmap.flop", None).unwrap();
+        let (mut loaded, memo) =
+            load_game_chunked_mmap("tmpfile-mmap.flop", BoardState::River).unwrap();
+        assert_eq!(memo, "memo");
+        assert!(loaded.is_memory_mapped());
+        assert!(save_data_into_std_write(&loaded, "", &mut Vec::new(), None).is_err());
+        assert_eq!(
+            loaded.set_weight(0, 0, 0.5),
+            Err(PostFlopError::Unsupported(
+                "Memory-mapped game cannot be modified".to_string()
+            ))
+        );
+
+        // bet, call, and deal 2c
+        for history in [&[][..], &[1], &[1, 1, 0]] {
+            game.apply_history(history);
+            loaded.apply_history(history);
+            game.cache_normalized_weights();
+            loaded.cache_normalized_weights();
+            assert_eq!(loaded.strategy(), game.strategy());
+            for player in 0..2 {
+                assert_eq!(loaded.expected_values(player), game.expected_values(player));
+            }
+        }
+
+        drop(loaded);
+        std::fs::remove_file("tmpfile-mmap.flop").unwrap();
+    }
+
+    #[test]
+    fn save_and_load_bunching() {
+        let flop = flop_from_str("Td9d6h").unwrap();
+        let mut data = BunchingData::new(&["22+,A2+,K9+".parse().unwrap()], flop).unwrap();
+
+        assert!(save_data_into_std_write(&data, "", &mut Vec::new(), None).is_err());
+        data.process(false);
+
+        // save
+        let mut buf = Vec::new();
+        save_data_into_std_write(&data, "memo", &mut buf, None).unwrap();
+
+        // load
+        let (loaded, memo): (BunchingData, _) =
+            load_data_from_std_read(&mut buf.as_slice(), None).unwrap();
+
+        assert_eq!(memo, "memo");
+        assert!(loaded.is_ready());
+        assert_eq!(loaded.flop(), data.flop());
+        assert_eq!(loaded.fold_ranges(), data.fold_ranges());
+
+        for i in 0..12 {
+            let mask4 = (1 << i) | (1 << (i + 2)) | (1 << (40 + i % 8)) | (1 << (50 - i % 3));
+            let mask5 = mask4 | (1 << (34 + i % 5));
+            assert_eq!(loaded.result_4cards(mask4), data.result_4cards(mask4));
+            assert_eq!(loaded.result_5cards(mask5), data.result_5cards(mask5));
+        }
+    }
+
+    #[test]
+    fn load_with_migration() {
+        let card_config = CardConfig {
+            range: [Range::ones(); 2],
+            flop: flop_from_str("Td9d6h").unwrap(),
+            ..Default::default()
+        };
+
+        let tree_config = TreeConfig {
+            starting_pot: 60,
+            effective_stack: 970,
+            flop_bet_sizes: [("50%", "").try_into().unwrap(), Default::default()],
+            turn_bet_sizes: [("50%", "").try_into().unwrap(), Default::default()],
+            ..Default::default()
+        };
+
+        let action_tree = ActionTree::new(tree_config).unwrap();
+        let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
+
+        game.allocate_memory(false, None).unwrap();
+        finalize(&mut game);
+
+        // current format
+        let mut buf = Vec::new();
+        save_data_into_std_write(&game, "memo", &mut buf, None).unwrap();
+        let (_, info): (PostFlopGame, _) =
+            load_data_with_migration(&mut buf.as_slice(), None).unwrap();
+        assert_eq!(info.format_version, VERSION);
+        assert_eq!(info.crate_version.as_deref(), Some(CRATE_VERSION));
+        assert_eq!(info.memo, "memo");
+        assert!(!info.is_migrated());
+
+        // memory limit
+        let result: Result<(PostFlopGame, _), _> =
+            load_data_with_migration(&mut buf.as_slice(), Some(1));
+        assert!(matches!(
+            result.err().unwrap(),
+            LoadError::MemoryLimitExceeded { limit: 1, .. }
+        ));
+
+        // legacy format
+        let mut legacy = Vec::new();
+        let header = (MAGIC, VERSION_LEGACY, 0u8, DataType::Game as u8);
+        encode_into_std_write(header, &mut legacy, "").unwrap();
+        encode_into_std_write(game.estimated_memory_usage(), &mut legacy, "").unwrap();
+        encode_into_std_write("legacy", &mut legacy, "").unwrap();
+        encode_into_std_write(&game, &mut legacy, "").unwrap();
+
+        let (mut migrated, info): (PostFlopGame, _) =
+            load_data_with_migration(&mut legacy.as_slice(), None).unwrap();
+        assert_eq!(info.format_version, VERSION_LEGACY);
+        assert_eq!(info.crate_version, None);
+        assert_eq!(info.memo, "legacy");
+        assert!(info.is_migrated());
+
+        migrated.cache_normalized_weights();
+        let weights_oop = migrated.normalized_weights(0);
+        let root_ev_oop = compute_average(&migrated.expected_values(0), weights_oop);
+        assert!((root_ev_oop - 45.0).abs() < 1e-4);
+
+        // incompatible data version
+        let mut incompatible = Vec::new();
+        let header = (MAGIC, VERSION, 0u8, DataType::Game as u8);
+        encode_into_std_write(header, &mut incompatible, "").unwrap();
+        encode_into_std_write("0.0.0", &mut incompatible, "").unwrap();
+        encode_into_std_write("1970-01-01", &mut incompatible, "").unwrap();
+
+        let result: Result<(PostFlopGame, _), _> =
+            load_data_with_migration(&mut incompatible.as_slice(), None);
+        assert_eq!(
+            result.err().unwrap(),
+            LoadError::IncompatibleData {
+                expected: PostFlopGame::data_version().to_string(),
+                found: "1970-01-01".to_string(),
+                crate_version: "0.0.0".to_string(),
+            }
+        );
+
+        // unsupported format versions
+        for version in [0, 2, 3, 4, 5, 6, 255] {
+            let mut unsupported = Vec::new();
+            encode_into_std_write((MAGIC, version), &mut unsupported, "").unwrap();
+            let result: Result<(PostFlopGame, _), _> =
+                load_data_with_migration(&mut unsupported.as_slice(), None);
+            assert_eq!(
+                result.err().unwrap(),
+                LoadError::UnsupportedFormatVersion(version)
+            );
+        }
+    }
+
+    #[test]
+    fn save_and_load_with_progress() {
+        let card_config = CardConfig {
+            range: [Range::ones(); 2],
+            flop: flop_from_str("Td9d6h").unwrap(),
+            ..Default::default()
+        };
+
+        let tree_config = TreeConfig {
+            starting_pot: 60,
+            effective_stack: 970,
+            flop_bet_sizes: [("50%", "").try_into().unwrap(), Default::default()],
+            turn_bet_sizes: [("50%", "").try_into().unwrap(), Default::default()],
+            ..Default::default()
+        };
+
+        let action_tree = ActionTree::new(tree_config).unwrap();
+        let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
+
+        game.allocate_memory(false, None).unwrap();
+        finalize(&mut game);
+
+        // save and load on a separate thread as in a blocking task of an async runtime
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let handle = std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            let report = |processed, total| sender.send((processed, total)).unwrap();
+            save_data_with_progress(&game, "", &mut buf, None, report).unwrap();
+            let report = |processed, total| sender.send((processed, total)).unwrap();
+            load_data_with_progress::<PostFlopGame, _, _>(&mut buf.as_slice(), None, report)
+                .unwrap()
+                .0
+        });
+
+        let mut loaded = handle.join().unwrap();
+        let reports = receiver.into_iter().collect::<Vec<_>>();
+        assert!(reports.len() > 2);
+        assert!(reports.iter().all(|&(processed, total)| processed <= total));
+        assert!(reports.iter().filter(|&&(p, t)| p == t).count() >= 2);
+
+        loaded.cache_normalized_weights();
+        let weights_oop = loaded.normalized_weights(0);
+        let root_ev_oop = compute_average(&loaded.expected_values(0), weights_oop);
+        assert!((root_ev_oop - 45.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn detect_corrupted_data() {
+        let card_config = CardConfig {
+            range: [Range::ones(); 2],
+            flop: flop_from_str("Td9d6h").unwrap(),
+            ..Default::default()
+        };
+
+        let tree_config = TreeConfig {
+            starting_pot: 60,
+            effective_stack: 970,
+            flop_bet_sizes: [("50%", "").try_into().unwrap(), Default::default()],
+            turn_bet_sizes: [("50%", "").try_into().unwrap(), Default::default()],
+            ..Default::default()
+        };
+
+        let action_tree = ActionTree::new(tree_config).unwrap();
+        let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
+
+        game.allocate_memory(false, None).unwrap();
+        finalize(&mut game);
+
+        // standard format
+        let mut buf = Vec::new();
+        save_data_into_std_write(&game, "", &mut buf, None).unwrap();
+        let len = buf.len();
+        buf[len - 1] ^= 1;
+        let result: Result<(PostFlopGame, _), _> =
+            load_data_with_migration(&mut buf.as_slice(), None);
+        assert_eq!(result.err().unwrap(), LoadError::Corrupted { chunk: 0 });
+
+        // chunked format
+        let mut buf = Vec::new();
+        save_game_chunked(&game, "", &mut buf, None).unwrap();
+        let reader = std::io::Cursor::new(&buf);
+        let (chunk_reader, _) = read_chunked_header(reader, BoardState::River, None).unwrap();
+        let position = chunk_reader.chunk_range(2).start;
+        buf[position] ^= 1;
+
+        let mut reader = std::io::Cursor::new(&buf);
+        let result = load_game_chunked(&mut reader, BoardState::River, None);
+        assert_eq!(result.err().unwrap(), LoadError::Corrupted { chunk: 2 });
+    }
+
+    #[test]
+    #[cfg(feature = "zstd")]
+    fn save_and_load_file_compressed() {
+        let card_config = CardConfig {
+            range: [Range::ones(); 2],
+            flop: flop_from_str("Td9d6h").unwrap(),
+            ..Default::default()
+        };
+
+        let tree_config = TreeConfig {
+            starting_pot: 60,
+            effective_stack: 970,
+            flop_bet_sizes: [("50%", "").try_into().unwrap(), Default::default()],
+            turn_bet_sizes: [("50%", "").try_into().unwrap(), Default::default()],
+            ..Default::default()
+        };
+
+        let action_tree = ActionTree::new(tree_config).unwrap();
+        let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
+
+        game.allocate_memory(false, None).unwrap();
+        finalize(&mut game);
+
+        // save
+        save_data_to_file(&game, "", "tmpfile-zstd.flop", Some(3)).unwrap();
+
+        // load
+        let mut game: PostFlopGame = load_data_from_file("tmpfile-zstd.flop", None).unwrap().0;
+
+        // remove tmpfile
+        std::fs::remove_file("tmpfile-zstd.flop").unwrap();
+
+        game.cache_normalized_weights();
+        let weights_oop = game.normalized_weights(0);
+        let weights_ip = game.normalized_weights(1);
+        let root_equity_oop = compute_average(&game.equity(0), weights_oop);
+        let root_equity_ip = compute_average(&game.equity(1), weights_ip);
+        let root_ev_oop = compute_average(&game.expected_values(0), weights_oop);
+        let root_ev_ip = compute_average(&game.expected_values(1), weights_ip);
+
+        assert!((root_equity_oop - 0.5).abs() < 1e-5);
+        assert!((root_equity_ip - 0.5).abs() < 1e-5);
+        assert!((root_ev_oop - 45.0).abs() < 1e-4);
+        assert!((root_ev_ip - 15.0).abs() < 1e-4);
+    }
+
+    #[test]
+    #[cfg(feature = "zstd")]
+    fn save_and_load_with_dictionary() {
+        let card_config = CardConfig {
+            range: [Range::ones(); 2],
+            flop: flop_from_str("Td9d6h").unwrap(),
+            ..Default::default()
+        };
+
+        let tree_config = TreeConfig {
+            starting_pot: 60,
+            effective_stack: 970,
+            flop_bet_sizes: [("50%", "").try_into().unwrap(), Default::default()],
+            turn_bet_sizes: [("50%", "").try_into().unwrap(), Default::default()],
+            ..Default::default()
+        };
+
+        let action_tree = ActionTree::new(tree_config).unwrap();
+        let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
+
+        game.allocate_memory(false, None).unwrap();
+        finalize(&mut game);
+
+        let range = zstd::compression_level_range();
+        let invalid_level = Some(range.end() + 1);
+        assert!(save_data_into_std_write(&game, "", &mut Vec::new(), invalid_level).is_err());
+
+        // raw content dictionary
+        let mut dictionary = Vec::new();
+        save_data_into_std_write(&game, "", &mut dictionary, None).unwrap();
+
+        let mut plain = Vec::new();
+        save_data_into_std_write(&game, "", &mut plain, Some(3)).unwrap();
+        let mut buf = Vec::new();
+        save_data_with_dictionary(&game, "", &mut buf, 3, &dictionary).unwrap();
+
+        let result: Result<(PostFlopGame, _), _> =
+            load_data_with_migration(&mut buf.as_slice(), None);
+        assert_eq!(result.err().unwrap(), LoadError::DictionaryRequired);
+
+        let mut loaded: PostFlopGame =
+            load_data_with_dictionary(&mut buf.as_slice(), None, &dictionary)
+                .unwrap()
+                .0;
+        loaded.cache_normalized_weights();
+        let weights_oop = loaded.normalized_weights(0);
+        let root_ev_oop = compute_average(&loaded.expected_values(0), weights_oop);
+        assert!((root_ev_oop - 45.0).abs() < 1e-4);
+
+        // data without a dictionary
+        let (_, info): (PostFlopGame, _) =
+            load_data_with_dictionary(&mut plain.as_slice(), None, &dictionary).unwrap();
+        assert_eq!(info.format_version, VERSION);
     }
 }
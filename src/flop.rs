@@ -0,0 +1,502 @@
+use crate::analysis::*;
+use crate::card::*;
+use crate::error::*;
+use crate::range::*;
+use crate::utility::*;
+use std::collections::BTreeMap;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// Number of runouts sampled per flop when computing range-equity features.
+const CLUSTER_NUM_RUNOUTS: usize = 24;
+
+/// Maximum number of iterations of the k-means clustering.
+const CLUSTER_MAX_ITERATIONS: usize = 100;
+
+/// Scaling factor of the equity feature relative to the texture features.
+const CLUSTER_EQUITY_SCALE: f64 = 4.0;
+
+/// All 24 permutations of the four suits.
+const SUIT_PERMUTATIONS: [[u8; 4]; 24] = [
+    [0, 1, 2, 3],
+    [0, 1, 3, 2],
+    [0, 2, 1, 3],
+    [0, 2, 3, 1],
+    [0, 3, 1, 2],
+    [0, 3, 2, 1],
+    [1, 0, 2, 3],
+    [1, 0, 3, 2],
+    [1, 2, 0, 3],
+    [1, 2, 3, 0],
+    [1, 3, 0, 2],
+    [1, 3, 2, 0],
+    [2, 0, 1, 3],
+    [2, 0, 3, 1],
+    [2, 1, 0, 3],
+    [2, 1, 3, 0],
+    [2, 3, 0, 1],
+    [2, 3, 1, 0],
+    [3, 0, 1, 2],
+    [3, 0, 2, 1],
+    [3, 1, 0, 2],
+    [3, 1, 2, 0],
+    [3, 2, 0, 1],
+    [3, 2, 1, 0],
+];
+
+/// Applies a suit mapping to the given card.
+///
+/// `suit_mapping[suit]` is the suit that `suit` is mapped to.
+#[inline]
+pub fn map_card_suit(card: Card, suit_mapping: &[u8; 4]) -> Card {
+    (card & !3) | suit_mapping[(card & 3) as usize]
+}
+
+/// Returns the canonical representative of the given flop and the suit mapping that transforms the
+/// given flop into it.
+///
+/// Two flops are strategically isomorphic if and only if their canonical representatives are equal.
+/// The returned flop is sorted in ascending order of card IDs, like [`flop_from_str`].
+/// `suit_mapping[suit]` is the suit that `suit` is mapped to; suits that do not appear on the flop
+/// are also mapped so that the mapping is always a permutation.
+///
+/// Undefined behavior if the flop contains invalid or duplicate cards.
+///
+/// # Examples
+/// ```
+/// use postflop_solver_ffi::*;
+///
+/// let (canonical, _) = canonicalize_flop(flop_from_str("Td9d6h").unwrap());
+/// let (canonical2, _) = canonicalize_flop(flop_from_str("Ts9s6c").unwrap());
+/// assert_eq!(canonical, canonical2);
+/// ```
+///
+/// [`flop_from_str`]: crate::flop_from_str
+pub fn canonicalize_flop(flop: [Card; 3]) -> ([Card; 3], [u8; 4]) {
+    let mut best = ([Card::MAX; 3], [0; 4]);
+
+    for mapping in &SUIT_PERMUTATIONS {
+        let mut mapped = flop.map(|card| map_card_suit(card, mapping));
+        mapped.sort_unstable();
+        if mapped < best.0 {
+            best = (mapped, *mapping);
+        }
+    }
+
+    best
+}
+
+/// Returns the list of all strategically distinct flops with their weights.
+///
+/// The list contains 1755 flops sorted in ascending order. The weight of each flop is the number
+/// of suit-isomorphic flops it represents, so the weights sum up to 22100 (= 52 choose 3).
+///
+/// # Examples
+/// ```
+/// use postflop_solver_ffi::all_canonical_flops;
+///
+/// let flops = all_canonical_flops();
+/// assert_eq!(flops.len(), 1755);
+/// assert_eq!(flops.iter().map(|&(_, w)| w).sum::<u32>(), 22100);
+/// ```
+pub fn all_canonical_flops() -> Vec<([Card; 3], u32)> {
+    let mut counts = BTreeMap::new();
+
+    for card1 in 0..52 {
+        for card2 in card1 + 1..52 {
+            for card3 in card2 + 1..52 {
+                let (canonical, _) = canonicalize_flop([card1, card2, card3]);
+                *counts.entry(canonical).or_insert(0) += 1;
+            }
+        }
+    }
+
+    counts.into_iter().collect()
+}
+
+/// A weighted subset of flops used for aggregation over all flops.
+///
+/// The weight of each flop is the number of flops it represents, so the weights of a subset that
+/// covers the whole game sum up to 22100.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlopSubset {
+    /// Representative flops, each sorted in ascending order of card IDs.
+    pub flops: Vec<[Card; 3]>,
+
+    /// Weight of each flop.
+    pub weights: Vec<f64>,
+}
+
+impl FlopSubset {
+    /// Returns the subset of all 1755 canonical flops (see [`all_canonical_flops`]).
+    pub fn full() -> Self {
+        let (flops, weights) = all_canonical_flops()
+            .into_iter()
+            .map(|(flop, weight)| (flop, weight as f64))
+            .unzip();
+        Self { flops, weights }
+    }
+
+    /// Selects `n` representative flops by clustering all 1755 canonical flops.
+    ///
+    /// Each flop is described by texture features (ranks, pairedness, suitedness, and straight
+    /// potential). If `ranges` is specified, the equity of `ranges[0]` against `ranges[1]` on the
+    /// flop is also used as a feature, which makes the subset range-aware. The flops are clustered
+    /// by the weighted k-means algorithm, and the flop closest to the center of each cluster is
+    /// selected as its representative with the total weight of the cluster.
+    ///
+    /// The result is deterministic. The equity is estimated from a fixed set of sampled runouts.
+    ///
+    /// **Time complexity:**
+    /// - (texture only) *O*(1755 * `n`) per iteration
+    /// - (with ranges) additionally *O*(1755 * #(runouts) * #(hands) log #(hands))
+    ///
+    /// # Examples
+    /// ```
+    /// use postflop_solver_ffi::FlopSubset;
+    ///
+    /// let subset = FlopSubset::cluster(30, None).unwrap();
+    /// assert_eq!(subset.len(), 30);
+    /// assert_eq!(subset.total_weight(), 22100.0);
+    /// ```
+    pub fn cluster(n: usize, ranges: Option<&[Range; 2]>) -> Result<Self, PostFlopError> {
+        let all_flops = all_canonical_flops();
+        if n == 0 || n > all_flops.len() {
+            return Err(PostFlopError::InvalidArgument(format!(
+                "Number of flops must be in [1, {}]",
+                all_flops.len()
+            )));
+        }
+
+        if let Some(ranges) = ranges {
+            if ranges.iter().any(|range| range.is_empty()) {
+                return Err(PostFlopError::InvalidArgument("Range is empty".to_string()));
+            }
+        }
+
+        let points = into_par_iter(0..all_flops.len())
+            .map(|i| {
+                let flop = &all_flops[i].0;
+                let mut features = texture_features(flop);
+                if let Some(ranges) = ranges {
+                    features.push(CLUSTER_EQUITY_SCALE * sampled_flop_equity(flop, ranges));
+                }
+                features
+            })
+            .collect::<Vec<_>>();
+
+        let weights = all_flops.iter().map(|&(_, w)| w as f64).collect::<Vec<_>>();
+        let (assignment, centroids) = weighted_kmeans(&points, &weights, n);
+
+        let mut result = (0..n)
+            .map(|cluster| {
+                let mut best = (f64::INFINITY, 0);
+                let mut weight = 0.0;
+                for (i, point) in points.iter().enumerate() {
+                    if assignment[i] == cluster {
+                        let dist = squared_distance(point, &centroids[cluster]);
+                        if dist < best.0 {
+                            best = (dist, i);
+                        }
+                        weight += weights[i];
+                    }
+                }
+                (all_flops[best.1].0, weight)
+            })
+            .collect::<Vec<_>>();
+
+        result.sort_unstable_by_key(|item| item.0);
+        let (flops, weights) = result.into_iter().unzip();
+        Ok(Self { flops, weights })
+    }
+
+    /// Returns the number of flops in the subset.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.flops.len()
+    }
+
+    /// Returns whether the subset is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.flops.is_empty()
+    }
+
+    /// Returns the sum of the weights.
+    #[inline]
+    pub fn total_weight(&self) -> f64 {
+        self.weights.iter().sum()
+    }
+
+    /// Returns an iterator over the flops and their weights.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = ([Card; 3], f64)> + '_ {
+        self.flops.iter().copied().zip(self.weights.iter().copied())
+    }
+}
+
+/// Checks that the sorted flop consists of three distinct valid cards.
+pub(crate) fn check_sorted_flop(flop: &[Card; 3]) -> Result<(), PostFlopError> {
+    if flop[2] >= 52 {
+        return Err(PostFlopError::InvalidCard {
+            input: flop[2].to_string(),
+            reason: "Invalid flop".to_string(),
+        });
+    }
+    if flop[0] == flop[1] || flop[1] == flop[2] {
+        return Err(PostFlopError::BoardConflict { card: flop[1] });
+    }
+    Ok(())
+}
+
+/// Computes the texture features of the flop.
+fn texture_features(flop: &[Card; 3]) -> Vec<f64> {
+    let mut ranks = flop.map(|card| card >> 2);
+    ranks.sort_unstable_by(|a, b| b.cmp(a));
+
+    let rank_mask = ranks.iter().fold(0u16, |acc, &rank| acc | (1 << rank));
+    let num_ranks = rank_mask.count_ones();
+    let num_suits = flop
+        .iter()
+        .fold(0u8, |acc, &c| acc | (1 << (c & 3)))
+        .count_ones();
+
+    // number of straights that contain all flop ranks
+    let mut num_straights = 0;
+    if num_ranks == 3 {
+        for low in 0..10 {
+            // the lowest straight is A-2-3-4-5
+            let window = if low == 0 {
+                0b1_0000_0000_1111
+            } else {
+                0b11111 << (low - 1)
+            };
+            if rank_mask & window == rank_mask {
+                num_straights += 1;
+            }
+        }
+    }
+
+    vec![
+        ranks[0] as f64 / 12.0,
+        ranks[1] as f64 / 12.0,
+        ranks[2] as f64 / 12.0,
+        (num_ranks == 2) as u8 as f64,
+        (num_ranks == 1) as u8 as f64,
+        (num_suits == 1) as u8 as f64,
+        (num_suits == 2) as u8 as f64,
+        num_straights as f64 / 3.0,
+    ]
+}
+
+/// Estimates the equity of `ranges[0]` against `ranges[1]` on the flop from sampled runouts.
+fn sampled_flop_equity(flop: &[Card; 3], ranges: &[Range; 2]) -> f64 {
+    let flop_mask: u64 = (1 << flop[0]) | (1 << flop[1]) | (1 << flop[2]);
+    let runouts = (0..52)
+        .flat_map(|turn| (turn + 1..52).map(move |river| (turn, river)))
+        .filter(|&(turn, river)| flop_mask & ((1 << turn) | (1 << river)) == 0)
+        .collect::<Vec<(Card, Card)>>();
+
+    let mut numerator = 0.0;
+    let mut denominator = 0.0;
+
+    for k in 0..CLUSTER_NUM_RUNOUTS {
+        let (turn, river) = runouts[k * runouts.len() / CLUSTER_NUM_RUNOUTS];
+        let board = [flop[0], flop[1], flop[2], turn, river];
+        let (num, den) = board_equity(&board, ranges);
+        numerator += num;
+        denominator += den;
+    }
+
+    if denominator > 0.0 {
+        numerator / denominator
+    } else {
+        0.5
+    }
+}
+
+/// Computes the unnormalized equity of `ranges[0]` against `ranges[1]` on the river board.
+///
+/// Returns the pair of (sum of winning weights, sum of all matchup weights).
+fn board_equity(board: &[Card; 5], ranges: &[Range; 2]) -> (f64, f64) {
+    let board_mask = board.iter().fold(0u64, |acc, &c| acc | (1 << c));
+    let (hands0, weights0) = ranges[0].get_hands_weights(board_mask);
+    let (hands1, weights1) = ranges[1].get_hands_weights(board_mask);
+    let sums = river_equity_sums(board, [&hands0, &hands1], [&weights0, &weights1]);
+    sums[0]
+        .iter()
+        .zip(weights0.iter())
+        .fold((0.0, 0.0), |(num, den), (&(win, matchups), &w)| {
+            (num + w as f64 * win, den + w as f64 * matchups)
+        })
+}
+
+#[inline]
+fn squared_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
+
+/// Performs the weighted k-means clustering and returns the assignment and the centroids.
+///
+/// The initial centroids are the weighted medians of `k` consecutive blocks of equal weight.
+fn weighted_kmeans(points: &[Vec<f64>], weights: &[f64], k: usize) -> (Vec<usize>, Vec<Vec<f64>>) {
+    let total_weight = weights.iter().sum::<f64>();
+    let mut centroids = Vec::with_capacity(k);
+    let mut cumulative = 0.0;
+    for (point, &weight) in points.iter().zip(weights) {
+        cumulative += weight;
+        while centroids.len() < k
+            && cumulative >= (centroids.len() as f64 + 0.5) * total_weight / k as f64
+        {
+            centroids.push(point.clone());
+        }
+    }
+
+    let mut assignment = vec![usize::MAX; points.len()];
+
+    for _ in 0..CLUSTER_MAX_ITERATIONS {
+        let mut changed = false;
+        for (i, point) in points.iter().enumerate() {
+            let nearest = (0..k)
+                .min_by(|&a, &b| {
+                    let dist_a = squared_distance(point, &centroids[a]);
+                    let dist_b = squared_distance(point, &centroids[b]);
+                    dist_a.partial_cmp(&dist_b).unwrap()
+                })
+                .unwrap();
+            if assignment[i] != nearest {
+                assignment[i] = nearest;
+                changed = true;
+            }
+        }
+
+        // move the farthest point into each empty cluster
+        for cluster in 0..k {
+            if !assignment.contains(&cluster) {
+                let farthest = (0..points.len())
+                    .filter(|&i| assignment.iter().filter(|&&a| a == assignment[i]).count() > 1)
+                    .max_by(|&a, &b| {
+                        let dist_a = squared_distance(&points[a], &centroids[assignment[a]]);
+                        let dist_b = squared_distance(&points[b], &centroids[assignment[b]]);
+                        dist_a.partial_cmp(&dist_b).unwrap()
+                    })
+                    .unwrap();
+                assignment[farthest] = cluster;
+                centroids[cluster] = points[farthest].clone();
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+
+        let dim = points[0].len();
+        let mut sums = vec![vec![0.0; dim]; k];
+        let mut cluster_weights = vec![0.0; k];
+        for (i, point) in points.iter().enumerate() {
+            let cluster = assignment[i];
+            cluster_weights[cluster] += weights[i];
+            for (sum, &x) in sums[cluster].iter_mut().zip(point) {
+                *sum += weights[i] * x;
+            }
+        }
+        for cluster in 0..k {
+            for (c, &sum) in centroids[cluster].iter_mut().zip(&sums[cluster]) {
+                *c = sum / cluster_weights[cluster];
+            }
+        }
+    }
+
+    (assignment, centroids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical_flops() {
+        let flops = all_canonical_flops();
+        assert_eq!(flops.len(), 1755);
+        assert_eq!(flops.iter().map(|&(_, w)| w).sum::<u32>(), 22100);
+
+        for &(flop, _) in &flops {
+            assert_eq!(canonicalize_flop(flop).0, flop);
+        }
+
+        let weight_of = |s: &str| {
+            let (flop, _) = canonicalize_flop(flop_from_str(s).unwrap());
+            flops.iter().find(|&&(f, _)| f == flop).unwrap().1
+        };
+
+        assert_eq!(weight_of("AhKhQh"), 4);
+        assert_eq!(weight_of("AhAdAc"), 4);
+        assert_eq!(weight_of("AhKhQd"), 12);
+        assert_eq!(weight_of("AhKdQc"), 24);
+        assert_eq!(weight_of("AhAdKh"), 12);
+        assert_eq!(weight_of("AhAdKc"), 12);
+    }
+
+    #[test]
+    fn flop_subset_full() {
+        let full = FlopSubset::full();
+        assert_eq!(full.len(), 1755);
+        assert_eq!(full.total_weight(), 22100.0);
+        for (flop, _) in full.iter() {
+            assert_eq!(canonicalize_flop(flop).0, flop);
+        }
+    }
+
+    #[test]
+    fn flop_subset_cluster() {
+        let subset = FlopSubset::cluster(40, None).unwrap();
+        assert_eq!(subset.len(), 40);
+        assert_eq!(subset.total_weight(), 22100.0);
+        assert!(FlopSubset::cluster(0, None).is_err());
+        assert!(FlopSubset::cluster(1756, None).is_err());
+
+        let ranges = [
+            "AA,KK,AK".parse().unwrap(),
+            "QQ-99,AQs,KQs".parse().unwrap(),
+        ];
+        let subset = FlopSubset::cluster(10, Some(&ranges)).unwrap();
+        assert_eq!(subset.len(), 10);
+        assert_eq!(subset.total_weight(), 22100.0);
+        for (i, &flop) in subset.flops.iter().enumerate() {
+            assert_eq!(canonicalize_flop(flop).0, flop);
+            assert!(!subset.flops[..i].contains(&flop));
+        }
+    }
+
+    #[test]
+    fn board_equity_symmetric() {
+        let board = [0, 9, 18, 27, 40];
+        let range: Range = "TT+,AJs+,KQo".parse().unwrap();
+        let (num, den) = board_equity(&board, &[range, range]);
+        assert!((num / den - 0.5).abs() < 1e-9);
+
+        let strong: Range = "AA".parse().unwrap();
+        let weak: Range = "22".parse().unwrap();
+        let board = flop_from_str("Kc8d5h").unwrap();
+        let board = [board[0], board[1], board[2], 43, 21];
+        let (num, den) = board_equity(&board, &[strong, weak]);
+        assert_eq!(num / den, 1.0);
+    }
+
+    #[test]
+    fn canonicalize_mapping() {
+        for s in ["Td9d6h", "AsKsQs", "7c7h2d", "KdKs5d"] {
+            let flop = flop_from_str(s).unwrap();
+            let (canonical, mapping) = canonicalize_flop(flop);
+            let mut mapped = flop.map(|card| map_card_suit(card, &mapping));
+            mapped.sort_unstable();
+            assert_eq!(mapped, canonical);
+
+            let mut suits = mapping;
+            suits.sort_unstable();
+            assert_eq!(suits, [0, 1, 2, 3]);
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use super::*;
+use crate::card::card_pair_to_index;
+use std::mem;
+
+impl PostFlopGame {
+    /// Returns whether the hole-card suit abstraction is enabled.
+    #[inline]
+    pub fn is_hand_abstracted(&self) -> bool {
+        self.is_hand_abstracted
+    }
+
+    /// Enables or disables the hole-card suit abstraction.
+    ///
+    /// If enabled, the private hands of each player that are mapped to each other by a suit
+    /// permutation preserving the board are merged into a single hand. The merged hand is
+    /// represented by its first combination in [`full_private_cards`], and its weight is the sum
+    /// of the weights of the merged combinations. For example, on a monotone flop, the three other
+    /// suits are interchangeable, so up to six combinations are merged.
+    ///
+    /// This shrinks the private-hand dimension of the game tree, but the abstraction is lossy:
+    /// the card removal effects and the hand strengths of the merged hands are computed with the
+    /// representative combination. Use [`expand_hand_values`] to expand the per-hand results to
+    /// the full combinations.
+    ///
+    /// The game tree is rebuilt, so the memory must be allocated again. Returns an error if the
+    /// bunching effect is enabled. A game with the abstraction enabled cannot be saved.
+    ///
+    /// [`full_private_cards`]: #method.full_private_cards
+    /// [`expand_hand_values`]: #method.expand_hand_values
+    pub fn set_hand_abstraction(&mut self, enable: bool) -> Result<(), PostFlopError> {
+        if self.state <= State::Uninitialized {
+            return Err(PostFlopError::InvalidState(
+                "Game is not successfully initialized".to_string(),
+            ));
+        }
+
+        if enable && self.bunching_num_dead_cards > 0 {
+            return Err(PostFlopError::Unsupported(
+                "Hand abstraction cannot be used with the bunching effect".to_string(),
+            ));
+        }
+
+        if self.is_hand_abstracted == enable {
+            return Ok(());
+        }
+
+        self.state = State::ConfigError;
+        self.is_hand_abstracted = enable;
+        self.locking_strategy.clear();
+
+        self.check_card_config()?;
+        self.init_card_fields();
+        self.init_root()?;
+
+        self.state = State::TreeBuilt;
+
+        self.init_interpreter();
+
+        Ok(())
+    }
+
+    /// Returns the full list of private hands of the given player.
+    ///
+    /// If the hand abstraction is disabled, this is the same as [`private_cards`].
+    ///
+    /// [`private_cards`]: #method.private_cards
+    #[inline]
+    pub fn full_private_cards(&self, player: usize) -> &[(Card, Card)] {
+        if self.state <= State::Uninitialized {
+            panic!("Game is not successfully initialized");
+        }
+
+        match self.is_hand_abstracted {
+            false => &self.private_cards[player],
+            true => &self.full_private_cards[player],
+        }
+    }
+
+    /// Returns the index in [`private_cards`] of the merged hand of each combination of
+    /// [`full_private_cards`].
+    ///
+    /// If the hand abstraction is disabled, returns an empty slice.
+    ///
+    /// [`private_cards`]: #method.private_cards
+    /// [`full_private_cards`]: #method.full_private_cards
+    #[inline]
+    pub fn abstract_hand_indices(&self, player: usize) -> &[u16] {
+        if self.state <= State::Uninitialized {
+            panic!("Game is not successfully initialized");
+        }
+
+        &self.abstract_hand_index[player]
+    }
+
+    /// Expands the per-hand values of the given player to the combinations of
+    /// [`full_private_cards`].
+    ///
+    /// `values` must consist of rows of `#(private hands)` elements, as returned by methods like
+    /// [`strategy`] and [`expected_values_detail`]. Each combination receives the value of its
+    /// merged hand, and the rows are preserved. If the hand abstraction is disabled, the values
+    /// are returned as they are.
+    ///
+    /// [`full_private_cards`]: #method.full_private_cards
+    /// [`strategy`]: #method.strategy
+    /// [`expected_values_detail`]: #method.expected_values_detail
+    pub fn expand_hand_values(&self, player: usize, values: &[f32]) -> Vec<f32> {
+        let num_hands = self.num_private_hands(player);
+        if !values.len().is_multiple_of(num_hands) {
+            panic!("Invalid length of values");
+        }
+
+        if !self.is_hand_abstracted {
+            return values.to_vec();
+        }
+
+        let indices = &self.abstract_hand_index[player];
+        values
+            .chunks_exact(num_hands)
+            .flat_map(|row| indices.iter().map(|&index| row[index as usize]))
+            .collect()
+    }
+
+    /// Merges the suit-isomorphic private hands. Called after the full hands are initialized.
+    pub(super) fn abstract_hands(&mut self, board: &[Card]) {
+        let mut rankset = [0u16; 4];
+        for &card in board {
+            rankset[card as usize & 3] |= 1 << (card >> 2);
+        }
+
+        let permutations = suit_permutations()
+            .into_iter()
+            .filter(|perm| (0..4).all(|suit| rankset[perm[suit] as usize] == rankset[suit]))
+            .collect::<Vec<_>>();
+
+        let permute = |perm: &[u8; 4], card: Card| card - (card & 3) + perm[card as usize & 3];
+
+        for player in 0..2 {
+            let hands = mem::take(&mut self.private_cards[player]);
+            let weights = mem::take(&mut self.initial_weights[player]);
+
+            // indices of the merged hands, keyed by the canonical combination
+            let mut class_index = vec![u16::MAX; 52 * 51 / 2];
+            let mut indices = Vec::with_capacity(hands.len());
+
+            for (&(c1, c2), &weight) in hands.iter().zip(&weights) {
+                let canonical = permutations
+                    .iter()
+                    .map(|perm| card_pair_to_index(permute(perm, c1), permute(perm, c2)))
+                    .min()
+                    .unwrap();
+
+                let index = &mut class_index[canonical];
+                if *index == u16::MAX {
+                    *index = self.private_cards[player].len() as u16;
+                    self.private_cards[player].push((c1, c2));
+                    self.initial_weights[player].push(0.0);
+                }
+
+                self.initial_weights[player][*index as usize] += weight;
+                indices.push(*index);
+            }
+
+            self.private_cards[player].shrink_to_fit();
+            self.initial_weights[player].shrink_to_fit();
+            self.full_private_cards[player] = hands;
+            self.abstract_hand_index[player] = indices;
+        }
+    }
+}
+
+/// Returns all the 24 permutations of the suits.
+fn suit_permutations() -> Vec<[u8; 4]> {
+    let mut ret = Vec::with_capacity(24);
+    for s0 in 0..4 {
+        for s1 in (0..4).filter(|&s| s != s0) {
+            for s2 in (0..4).filter(|&s| s != s0 && s != s1) {
+                ret.push([s0, s1, s2, 6 - s0 - s1 - s2]);
+            }
+        }
+    }
+    ret
+}
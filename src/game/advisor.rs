@@ -0,0 +1,212 @@
+use super::*;
+use crate::bet_size::*;
+use crate::solver::*;
+
+/// Kind of a configured bet size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BetSizeKind {
+    /// Size of the first bet ([`BetSizeOptions::bet`]).
+    Bet,
+
+    /// Size of a raise ([`BetSizeOptions::raise`]).
+    Raise,
+
+    /// Size of a donk bet ([`DonkSizeOptions::donk`]).
+    Donk,
+}
+
+/// EV loss caused by removing a bet size, an entry of [`SimplificationReport`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BetSizeEvLoss {
+    /// Player whose bet size is removed (`0` = OOP, `1` = IP).
+    pub player: usize,
+
+    /// Kind of the removed bet size.
+    pub kind: BetSizeKind,
+
+    /// Index of the removed bet size in the configured options.
+    pub index: usize,
+
+    /// Removed bet size.
+    pub size: BetSize,
+
+    /// Average root expected value of the player after removing the bet size.
+    pub expected_value: f32,
+
+    /// EV loss of the player caused by removing the bet size (original minus removed).
+    pub ev_loss: f32,
+
+    /// Exploitability of the re-solved game.
+    pub exploitability: f32,
+}
+
+/// Report of the bet sizes of a street, returned by [`PostFlopGame::simplification_report`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimplificationReport {
+    /// Street of the bet sizes.
+    pub street: BoardState,
+
+    /// Average root expected value of each player in the original game.
+    pub expected_values: [f32; 2],
+
+    /// EV loss of each configured bet size of the street, in ascending order of the EV loss. The
+    /// first entries are the best candidates to drop.
+    pub entries: Vec<BetSizeEvLoss>,
+}
+
+impl PostFlopGame {
+    /// Estimates the EV lost by removing each configured bet size of the given street.
+    ///
+    /// For each bet, raise, and donk size of both players on the street, the game is rebuilt
+    /// without the size and quickly re-solved by [`solve`] with the given parameters, and the
+    /// average root expected value of the player owning the size is compared with that of this
+    /// game. Because the re-solved games are only approximate equilibria, EV losses smaller than
+    /// their exploitability are within the noise; this game should be solved at least as
+    /// accurately. The hand abstraction, the card bucketing, and the weights modified by
+    /// [`set_weights`] are carried over to the re-solved games. The current node is not changed.
+    ///
+    /// Returns an error if the street is before the initial state of the tree, the tree has edited
+    /// lines, or the bunching effect is enabled. Panics if the game is not solved or the expected
+    /// values are trimmed.
+    ///
+    /// **Time complexity:** #(bet sizes of the street) * (building and solving the game).
+    ///
+    /// [`set_weights`]: #method.set_weights
+    pub fn simplification_report(
+        &mut self,
+        street: BoardState,
+        max_num_iterations: u32,
+        target_exploitability: f32,
+    ) -> Result<SimplificationReport, PostFlopError> {
+        if self.state != State::Solved {
+            panic!("Game is not solved");
+        }
+
+        if street < self.tree_config.initial_state {
+            return Err(PostFlopError::InvalidArgument(format!(
+                "Street is before the initial state: {street:?}"
+            )));
+        }
+
+        if !self.added_lines.is_empty() || !self.removed_lines.is_empty() {
+            return Err(PostFlopError::Unsupported(
+                "Game with edited lines is not supported".to_string(),
+            ));
+        }
+
+        if self.bunching_num_dead_cards != 0 {
+            return Err(PostFlopError::Unsupported(
+                "Bunching effect is not supported".to_string(),
+            ));
+        }
+
+        let saved = self.save_navigation();
+        self.back_to_root();
+        let expected_values = self.node_summary().average_expected_value;
+        self.restore_navigation(&saved);
+
+        let mut entries = Vec::new();
+        for (player, kind, index, size) in bet_size_slots(&self.tree_config, street) {
+            let mut tree_config = self.tree_config.clone();
+            remove_bet_size(&mut tree_config, street, player, kind, index);
+
+            let action_tree = ActionTree::new(tree_config)?;
+            let mut game = self.rebuild_with_action_tree(action_tree)?;
+            let exploitability = solve(&mut game, max_num_iterations, target_exploitability, false);
+            let expected_value = game.node_summary().average_expected_value[player];
+
+            entries.push(BetSizeEvLoss {
+                player,
+                kind,
+                index,
+                size,
+                expected_value,
+                ev_loss: expected_values[player] - expected_value,
+                exploitability,
+            });
+        }
+
+        entries.sort_by(|a, b| a.ev_loss.total_cmp(&b.ev_loss));
+
+        Ok(SimplificationReport {
+            street,
+            expected_values,
+            entries,
+        })
+    }
+
+    /// Returns a new game with the same cards and the given action tree, ready to solve.
+    pub(super) fn rebuild_with_action_tree(
+        &self,
+        action_tree: ActionTree,
+    ) -> Result<PostFlopGame, PostFlopError> {
+        let mut game = PostFlopGame::with_config(self.card_config.clone(), action_tree)?;
+
+        game.set_hand_abstraction(self.is_hand_abstracted)?;
+        game.set_card_buckets(&self.card_buckets)?;
+        if self.is_weight_modified {
+            for player in 0..2 {
+                game.set_weights(player, &self.initial_weights[player])?;
+            }
+        }
+
+        game.allocate_memory_with_formats(self.strategy_format, self.value_format, None)?;
+        Ok(game)
+    }
+}
+
+/// Returns the configured bet sizes of the street as `(player, kind, index, size)`.
+fn bet_size_slots(
+    tree_config: &TreeConfig,
+    street: BoardState,
+) -> Vec<(usize, BetSizeKind, usize, BetSize)> {
+    let (bet_sizes, donk_sizes) = match street {
+        BoardState::Flop => (&tree_config.flop_bet_sizes, &None),
+        BoardState::Turn => (&tree_config.turn_bet_sizes, &tree_config.turn_donk_sizes),
+        BoardState::River => (&tree_config.river_bet_sizes, &tree_config.river_donk_sizes),
+    };
+
+    let mut ret = Vec::new();
+    for (player, options) in bet_sizes.iter().enumerate() {
+        for (kind, sizes) in [
+            (BetSizeKind::Bet, &options.bet),
+            (BetSizeKind::Raise, &options.raise),
+        ] {
+            ret.extend(sizes.iter().enumerate().map(|(i, &s)| (player, kind, i, s)));
+        }
+    }
+
+    if let Some(donk_sizes) = donk_sizes {
+        let sizes = donk_sizes.donk.iter().enumerate();
+        ret.extend(sizes.map(|(i, &s)| (0, BetSizeKind::Donk, i, s)));
+    }
+
+    ret
+}
+
+/// Removes the specified bet size from the tree configuration.
+fn remove_bet_size(
+    tree_config: &mut TreeConfig,
+    street: BoardState,
+    player: usize,
+    kind: BetSizeKind,
+    index: usize,
+) {
+    let (bet_sizes, donk_sizes) = match street {
+        BoardState::Flop => (&mut tree_config.flop_bet_sizes, &mut None),
+        BoardState::Turn => (
+            &mut tree_config.turn_bet_sizes,
+            &mut tree_config.turn_donk_sizes,
+        ),
+        BoardState::River => (
+            &mut tree_config.river_bet_sizes,
+            &mut tree_config.river_donk_sizes,
+        ),
+    };
+
+    match kind {
+        BetSizeKind::Bet => bet_sizes[player].bet.remove(index),
+        BetSizeKind::Raise => bet_sizes[player].raise.remove(index),
+        BetSizeKind::Donk => donk_sizes.as_mut().unwrap().donk.remove(index),
+    };
+}
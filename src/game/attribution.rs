@@ -0,0 +1,234 @@
+use super::*;
+use crate::interface::*;
+use crate::sliceop::*;
+use crate::utility::*;
+
+/// Attribution of the expected values to the decisions of each street, returned by
+/// [`PostFlopGame::street_ev_attribution`].
+///
+/// For each private hand, `expected_values` equals `baseline_expected_values` plus the sum of
+/// `street_gains` over the streets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreetAttribution {
+    /// Player whose decisions are attributed (`0` = OOP, `1` = IP).
+    pub player: usize,
+
+    /// Expected value of each private hand under the solved strategy.
+    pub expected_values: Vec<f32>,
+
+    /// Expected value of each private hand when the player chooses every action uniformly at
+    /// random.
+    pub baseline_expected_values: Vec<f32>,
+
+    /// EV gained by the decisions of each street (flop, turn, river) for each private hand.
+    pub street_gains: [Vec<f32>; 3],
+
+    /// Average of the street gains weighted by the normalized weights.
+    pub average_street_gains: [f32; 3],
+}
+
+impl PostFlopGame {
+    /// Attributes the expected value of each private hand of the given player at the current
+    /// node to the decisions of the player on each street.
+    ///
+    /// At every decision node of the player in the subtree, the counterfactual value of the
+    /// solved strategy is compared with the average counterfactual value of the actions, both
+    /// assuming the solved strategy afterwards. The differences are weighted by the probability
+    /// of reaching the node when the player chooses the actions uniformly at random, and summed
+    /// up by street. The street gains thus add up to the difference between the expected value
+    /// and the expected value of the uniformly random strategy. The opponent keeps the solved
+    /// strategy everywhere.
+    ///
+    /// Panics if the game is not solved or the strategies of all streets are not stored.
+    ///
+    /// After mutating the current node, you must call the [`cache_normalized_weights`] method
+    /// before calling this method.
+    ///
+    /// **Time complexity:** *O*(#(nodes in the subtree) * #(private hands)).
+    ///
+    /// [`cache_normalized_weights`]: #method.cache_normalized_weights
+    pub fn street_ev_attribution(&self, player: usize) -> StreetAttribution {
+        if self.state != State::Solved {
+            panic!("Game is not solved");
+        }
+
+        if !self.is_normalized_weight_cached {
+            panic!("Normalized weights are not cached");
+        }
+
+        if self.storage_mode != BoardState::River {
+            panic!("Strategies of all streets are not stored");
+        }
+
+        let node = self.node();
+        let mut cfreach = self.weights[player ^ 1].clone();
+        self.apply_swap(&mut cfreach, player ^ 1, true);
+
+        let (mut values, mut gains) = self.attribution_recursive(&node, player, &cfreach);
+        self.apply_swap(&mut values, player, false);
+        for street_gains in &mut gains {
+            self.apply_swap(street_gains, player, false);
+        }
+
+        let normalizer = self.expected_value_normalizer(false) as f64;
+        let total_bet_amount = self.total_bet_amount();
+        let bias = (total_bet_amount[player] - total_bet_amount[player ^ 1]).max(0);
+        let offset = self.tree_config.starting_pot as f64 * 0.5 + (node.amount + bias) as f64;
+
+        let scales = self.weights[player]
+            .iter()
+            .zip(self.normalized_weights[player].iter())
+            .map(|(&w_raw, &w_normalized)| {
+                if w_normalized == 0.0 {
+                    0.0
+                } else {
+                    normalizer * (w_raw / w_normalized) as f64
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let expected_values = values
+            .iter()
+            .zip(&scales)
+            .map(|(&v, &scale)| {
+                if scale == 0.0 {
+                    0.0
+                } else {
+                    v * scale + offset
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let street_gains = gains.map(|street_gains| {
+            street_gains
+                .iter()
+                .zip(&scales)
+                .map(|(&g, &scale)| (g * scale) as f32)
+                .collect::<Vec<_>>()
+        });
+
+        let baseline_expected_values = expected_values
+            .iter()
+            .enumerate()
+            .map(|(hand, &ev)| ev - street_gains.iter().map(|g| g[hand] as f64).sum::<f64>())
+            .map(|ev| ev as f32)
+            .collect();
+
+        let weights = &self.normalized_weights[player];
+        StreetAttribution {
+            player,
+            expected_values: expected_values.iter().map(|&ev| ev as f32).collect(),
+            baseline_expected_values,
+            average_street_gains: [0, 1, 2].map(|s| compute_average(&street_gains[s], weights)),
+            street_gains,
+        }
+    }
+
+    /// Recursive function to compute the counterfactual values and the street gains.
+    fn attribution_recursive(
+        &self,
+        node: &PostFlopNode,
+        player: usize,
+        cfreach: &[f32],
+    ) -> (Vec<f64>, [Vec<f64>; 3]) {
+        let num_hands = self.num_private_hands(player);
+        let mut values = vec![0.0; num_hands];
+        let mut gains = [0, 1, 2].map(|_| vec![0.0; num_hands]);
+
+        // terminal node
+        if node.is_terminal() {
+            let mut result = Vec::with_capacity(num_hands);
+            self.evaluate_moment(result.spare_capacity_mut(), node, player, cfreach, false);
+            unsafe { result.set_len(num_hands) };
+            let values = result.iter().map(|&v: &f32| v as f64).collect();
+            return (values, gains);
+        }
+
+        let num_actions = node.num_actions();
+        let add = |dst: &mut [f64], src: &[f64], scale: f64| {
+            dst.iter_mut().zip(src).for_each(|(d, &s)| *d += s * scale);
+        };
+
+        // chance node
+        if node.is_chance() {
+            let scalar = 1.0 / self.chance_factor(node) as f32;
+            let cfreach = cfreach.iter().map(|&r| r * scalar).collect::<Vec<_>>();
+
+            let children = (0..num_actions)
+                .map(|action| self.attribution_recursive(&node.play(action), player, &cfreach))
+                .collect::<Vec<_>>();
+
+            for (child_values, child_gains) in &children {
+                add(&mut values, child_values, 1.0);
+                for (g, child_g) in gains.iter_mut().zip(child_gains) {
+                    add(g, child_g, 1.0);
+                }
+            }
+
+            // process isomorphic chances
+            for (i, &isomorphic_index) in self.isomorphic_chances(node).iter().enumerate() {
+                let swap_list = &self.isomorphic_swap(node, i)[player];
+                let (mut child_values, mut child_gains) =
+                    children[isomorphic_index as usize].clone();
+                apply_swap(&mut child_values, swap_list);
+                add(&mut values, &child_values, 1.0);
+                for (g, child_g) in gains.iter_mut().zip(&mut child_gains) {
+                    apply_swap(child_g, swap_list);
+                    add(g, child_g, 1.0);
+                }
+            }
+
+            return (values, gains);
+        }
+
+        // player node
+        let node_player = node.player();
+        let num_node_hands = self.num_private_hands(node_player);
+        let strategy = self.node_strategy(node);
+
+        if node_player != player {
+            for action in 0..num_actions {
+                let mut cfreach = cfreach.to_vec();
+                mul_slice(&mut cfreach, row(&strategy, action, num_node_hands));
+                let (child_values, child_gains) =
+                    self.attribution_recursive(&node.play(action), player, &cfreach);
+                add(&mut values, &child_values, 1.0);
+                for (g, child_g) in gains.iter_mut().zip(&child_gains) {
+                    add(g, child_g, 1.0);
+                }
+            }
+            return (values, gains);
+        }
+
+        let street = match (node.turn, node.river) {
+            (NOT_DEALT, _) => 0,
+            (_, NOT_DEALT) => 1,
+            _ => 2,
+        };
+
+        // the gains of the descendants are weighted by the uniformly random strategy
+        let uniform = 1.0 / num_actions as f64;
+        let mut mean_values = vec![0.0; num_hands];
+        for action in 0..num_actions {
+            let (child_values, child_gains) =
+                self.attribution_recursive(&node.play(action), player, cfreach);
+            let action_strategy = row(&strategy, action, num_hands);
+            values
+                .iter_mut()
+                .zip(&child_values)
+                .zip(action_strategy)
+                .for_each(|((v, &cv), &s)| *v += cv * s as f64);
+            add(&mut mean_values, &child_values, uniform);
+            for (g, child_g) in gains.iter_mut().zip(&child_gains) {
+                add(g, child_g, uniform);
+            }
+        }
+
+        gains[street]
+            .iter_mut()
+            .zip(values.iter().zip(&mean_values))
+            .for_each(|(g, (&v, &m))| *g += v - m);
+
+        (values, gains)
+    }
+}
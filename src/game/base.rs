@@ -1,6 +1,7 @@
 use super::*;
 use crate::bunching::*;
 use crate::interface::*;
+use crate::range::*;
 use crate::utility::*;
 use std::mem::{self, MaybeUninit};
 
@@ -21,7 +22,7 @@ impl Game for PostFlopGame {
     type Node = PostFlopNode;
 
     #[inline]
-    fn root(&self) -> MutexGuardLike<Self::Node> {
+    fn root(&self) -> MutexGuardLike<'_, Self::Node> {
         self.node_arena[0].lock()
     }
 
@@ -43,11 +44,7 @@ impl Game for PostFlopGame {
         player: usize,
         cfreach: &[f32],
     ) {
-        if self.bunching_num_dead_cards == 0 {
-            self.evaluate_internal(result, node, player, cfreach);
-        } else {
-            self.evaluate_internal_bunching(result, node, player, cfreach);
-        }
+        self.evaluate_moment(result, node, player, cfreach, false);
     }
 
     #[inline]
@@ -83,7 +80,9 @@ impl Game for PostFlopGame {
 
     #[inline]
     fn isomorphic_chances(&self, node: &Self::Node) -> &[u8] {
-        if node.turn == NOT_DEALT {
+        if !self.card_buckets.is_empty() {
+            self.bucket_chances(node.turn).0
+        } else if node.turn == NOT_DEALT {
             &self.isomorphism_ref_turn
         } else {
             &self.isomorphism_ref_river[node.turn as usize]
@@ -92,7 +91,10 @@ impl Game for PostFlopGame {
 
     #[inline]
     fn isomorphic_swap(&self, node: &Self::Node, index: usize) -> &[Vec<(u16, u16)>; 2] {
-        if node.turn == NOT_DEALT {
+        if !self.card_buckets.is_empty() {
+            let (card, repr_card) = self.bucket_chances(node.turn).1[index];
+            &self.bucket_swap[card_pair_to_index(card, repr_card)]
+        } else if node.turn == NOT_DEALT {
             &self.isomorphism_swap_turn[self.isomorphism_card_turn[index] as usize & 3]
         } else {
             &self.isomorphism_swap_river[node.turn as usize & 3]
@@ -112,7 +114,29 @@ impl Game for PostFlopGame {
 
     #[inline]
     fn is_compression_enabled(&self) -> bool {
-        self.is_compression_enabled
+        self.strategy_format != StorageFormat::Float32
+            || self.value_format != StorageFormat::Float32
+    }
+
+    #[inline]
+    fn strategy_format(&self) -> StorageFormat {
+        self.strategy_format
+    }
+
+    #[inline]
+    fn value_format(&self) -> StorageFormat {
+        self.value_format
+    }
+
+    #[inline]
+    fn set_solve_summary(&mut self, summary: SolveSummary) {
+        self.solve_summary = Some(summary);
+    }
+
+    #[cfg(feature = "profile")]
+    #[inline]
+    fn set_solver_stats(&mut self, stats: SolverStats) {
+        self.solver_stats = Some(stats);
     }
 }
 
@@ -132,7 +156,10 @@ impl PostFlopGame {
 
     /// Creates a new [`PostFlopGame`] with the specified configuration.
     #[inline]
-    pub fn with_config(card_config: CardConfig, action_tree: ActionTree) -> Result<Self, String> {
+    pub fn with_config(
+        card_config: CardConfig,
+        action_tree: ActionTree,
+    ) -> Result<Self, PostFlopError> {
         let mut game = Self::new();
         game.update_config(card_config, action_tree)?;
         Ok(game)
@@ -144,11 +171,13 @@ impl PostFlopGame {
         &mut self,
         card_config: CardConfig,
         action_tree: ActionTree,
-    ) -> Result<(), String> {
+    ) -> Result<(), PostFlopError> {
         self.state = State::ConfigError;
 
         if !action_tree.invalid_terminals().is_empty() {
-            return Err("Invalid terminal is found in action tree".to_string());
+            return Err(PostFlopError::InvalidConfig(
+                "Invalid terminal is found in action tree".to_string(),
+            ));
         }
 
         self.card_config = card_config;
@@ -177,24 +206,43 @@ impl PostFlopGame {
     /// Specifically, the computational complexity of the terminal evaluation will increase from
     /// *O*(#(OOP private hands) + #(IP private hands)) to *O*(#(OOP private hands) * #(IP private
     /// hands)).
+    ///
+    /// The flop of `bunching_data` does not have to be identical to the flop of the game; it is
+    /// sufficient that they are suit-isomorphic, in which case the suits are mapped by
+    /// [`BunchingData::suit_mapping_from`].
     #[inline]
-    pub fn set_bunching_effect(&mut self, bunching_data: &BunchingData) -> Result<(), String> {
+    pub fn set_bunching_effect(
+        &mut self,
+        bunching_data: &BunchingData,
+    ) -> Result<(), PostFlopError> {
         if self.state <= State::Uninitialized {
-            return Err("Game is not successfully initialized".to_string());
+            return Err(PostFlopError::InvalidState(
+                "Game is not successfully initialized".to_string(),
+            ));
         }
 
         if !bunching_data.is_ready() {
-            return Err("Bunching configuration is not ready".to_string());
+            return Err(PostFlopError::InvalidState(
+                "Bunching configuration is not ready".to_string(),
+            ));
+        }
+
+        if self.is_hand_abstracted {
+            return Err(PostFlopError::Unsupported(
+                "Bunching effect cannot be used with the hand abstraction".to_string(),
+            ));
         }
 
-        let mut flop_sorted = self.card_config.flop;
-        flop_sorted.sort_unstable();
-        if flop_sorted != bunching_data.flop() {
-            return Err("Flop cards do not match".to_string());
+        if !self.card_buckets.is_empty() {
+            return Err(PostFlopError::Unsupported(
+                "Bunching effect cannot be used with the card bucketing".to_string(),
+            ));
         }
 
+        let suit_mapping = bunching_data.suit_mapping_from(self.card_config.flop)?;
+
         self.reset_bunching_effect();
-        self.set_bunching_effect_internal(bunching_data)?;
+        self.set_bunching_effect_internal(bunching_data, suit_mapping)?;
 
         Ok(())
     }
@@ -226,6 +274,27 @@ impl PostFlopGame {
         &self.tree_config
     }
 
+    /// Returns the summary of the solve, or `None` if not recorded.
+    ///
+    /// The summary is recorded by [`solve`] and is preserved through saving and loading.
+    ///
+    /// [`solve`]: crate::solve
+    #[inline]
+    pub fn solve_summary(&self) -> Option<SolveSummary> {
+        self.solve_summary
+    }
+
+    /// Returns the timing statistics of the last [`solve`] run, or `None` if not recorded.
+    ///
+    /// The statistics are not saved to files.
+    ///
+    /// [`solve`]: crate::solve
+    #[cfg(feature = "profile")]
+    #[inline]
+    pub fn solver_stats(&self) -> Option<&SolverStats> {
+        self.solver_stats.as_ref()
+    }
+
     /// Obtains the added lines.
     #[inline]
     pub fn added_lines(&self) -> &[Vec<Action>] {
@@ -254,7 +323,36 @@ impl PostFlopGame {
         &self.private_cards[player]
     }
 
+    /// Returns the indices in [`private_cards`] of the hands matching the given hand string.
+    ///
+    /// The hand string is parsed by [`holes_from_str`], so both specific combinations (e.g.,
+    /// "AhKd") and hand classes (e.g., "AKs", "77") are accepted. Combinations that are not in the
+    /// player's range are ignored. The returned indices are sorted in ascending order.
+    ///
+    /// [`private_cards`]: #method.private_cards
+    /// [`holes_from_str`]: crate::holes_from_str
+    pub fn private_card_indices(
+        &self,
+        player: usize,
+        hand: &str,
+    ) -> Result<Vec<usize>, PostFlopError> {
+        if self.state <= State::Uninitialized {
+            panic!("Game is not successfully initialized");
+        }
+
+        let holes = holes_from_str(hand)?;
+        let private_cards = &self.private_cards[player];
+        Ok(holes
+            .iter()
+            .filter_map(|hole| private_cards.binary_search(hole).ok())
+            .collect())
+    }
+
     /// Returns the estimated memory usage in bytes (uncompressed, compressed).
+    ///
+    /// See [`memory_usage_detailed`] for the breakdown by buffer type and street.
+    ///
+    /// [`memory_usage_detailed`]: #method.memory_usage_detailed
     #[inline]
     pub fn memory_usage(&self) -> (u64, u64) {
         if self.state <= State::Uninitialized {
@@ -268,6 +366,30 @@ impl PostFlopGame {
         (uncompressed, compressed)
     }
 
+    /// Returns the estimated memory usage in bytes when the values are stored in the specified
+    /// format.
+    #[inline]
+    pub fn memory_usage_with_format(&self, format: StorageFormat) -> u64 {
+        self.memory_usage_with_formats(format, format)
+    }
+
+    /// Returns the estimated memory usage in bytes when the strategies and the other values
+    /// (i.e., the regrets and the counterfactual values) are stored in the specified formats.
+    pub fn memory_usage_with_formats(
+        &self,
+        strategy_format: StorageFormat,
+        value_format: StorageFormat,
+    ) -> u64 {
+        if self.state <= State::Uninitialized {
+            panic!("Game is not successfully initialized");
+        }
+
+        let num_values = self.num_storage + self.num_storage_ip + self.num_storage_chance;
+        strategy_format.num_bytes() as u64 * self.num_storage
+            + value_format.num_bytes() as u64 * num_values
+            + self.misc_memory_usage
+    }
+
     /// Returns the estimated additional memory usage in bytes when the bunching effect is enabled.
     #[inline]
     pub fn memory_usage_bunching(&self) -> u64 {
@@ -282,11 +404,15 @@ impl PostFlopGame {
     ///
     /// This allows the removal of chance-specific lines (e.g., remove overbets on board-pairing
     /// turns) which we cannot do while building an action tree.
-    pub fn remove_lines(&mut self, lines: &[Vec<Action>]) -> Result<(), String> {
+    pub fn remove_lines(&mut self, lines: &[Vec<Action>]) -> Result<(), PostFlopError> {
         if self.state <= State::Uninitialized {
-            return Err("Game is not successfully initialized".to_string());
+            return Err(PostFlopError::InvalidState(
+                "Game is not successfully initialized".to_string(),
+            ));
         } else if self.state >= State::MemoryAllocated {
-            return Err("Game has already been allocated".to_string());
+            return Err(PostFlopError::InvalidState(
+                "Game has already been allocated".to_string(),
+            ));
         }
 
         for line in lines {
@@ -309,101 +435,258 @@ impl PostFlopGame {
         if self.state <= State::TreeBuilt {
             None
         } else {
-            Some(self.is_compression_enabled)
+            Some(self.is_compression_enabled())
+        }
+    }
+
+    /// Returns the formats of the stored strategies and the other values (i.e., the regrets and
+    /// the counterfactual values), or `None` if the memory is not allocated.
+    #[inline]
+    pub fn allocated_storage_formats(&self) -> Option<(StorageFormat, StorageFormat)> {
+        if self.state <= State::TreeBuilt {
+            None
+        } else {
+            Some((self.strategy_format, self.value_format))
         }
     }
 
     /// Allocates the memory.
-    pub fn allocate_memory(&mut self, enable_compression: bool) {
+    ///
+    /// If `enable_compression` is `true`, the values are stored as 16-bit integers
+    /// ([`StorageFormat::Int16`]); otherwise, they are stored as 32-bit floats.
+    ///
+    /// If `memory_limit` is specified and the estimated memory usage (see
+    /// [`memory_usage_with_formats`]) exceeds it, [`PostFlopError::MemoryLimit`] is returned and the
+    /// game is not changed. If the allocation fails, [`PostFlopError::AllocationFailed`] is
+    /// returned instead of aborting the process, and the previously allocated memory is freed, so
+    /// the memory must be allocated again before solving.
+    ///
+    /// [`memory_usage_with_formats`]: #method.memory_usage_with_formats
+    #[inline]
+    pub fn allocate_memory(
+        &mut self,
+        enable_compression: bool,
+        memory_limit: Option<u64>,
+    ) -> Result<(), PostFlopError> {
+        let format = match enable_compression {
+            false => StorageFormat::Float32,
+            true => StorageFormat::Int16,
+        };
+        self.allocate_memory_with_formats(format, format, memory_limit)
+    }
+
+    /// Allocates the memory with the specified storage format (see [`allocate_memory`]).
+    ///
+    /// [`allocate_memory`]: #method.allocate_memory
+    #[inline]
+    pub fn allocate_memory_with_format(
+        &mut self,
+        format: StorageFormat,
+        memory_limit: Option<u64>,
+    ) -> Result<(), PostFlopError> {
+        self.allocate_memory_with_formats(format, format, memory_limit)
+    }
+
+    /// Allocates the memory with the specified storage formats of the strategies and the other
+    /// values (i.e., the regrets and the counterfactual values).
+    ///
+    /// For example, `allocate_memory_with_formats(StorageFormat::Float16, StorageFormat::Float32,
+    /// None)` stores the cumulative strategies in half precision while keeping the regrets in
+    /// 32-bit floats.
+    ///
+    /// The storages are allocated with the backend set by [`set_storage_backend`]. Returns an error
+    /// if the memory or the scratch files of the backend cannot be allocated (see
+    /// [`allocate_memory`]).
+    ///
+    /// [`set_storage_backend`]: #method.set_storage_backend
+    /// [`allocate_memory`]: #method.allocate_memory
+    pub fn allocate_memory_with_formats(
+        &mut self,
+        strategy_format: StorageFormat,
+        value_format: StorageFormat,
+        memory_limit: Option<u64>,
+    ) -> Result<(), PostFlopError> {
         if self.state <= State::Uninitialized {
             panic!("Game is not successfully initialized");
         }
 
+        let required = self.memory_usage_with_formats(strategy_format, value_format);
+        if let Some(limit) = memory_limit.filter(|&limit| required > limit) {
+            return Err(PostFlopError::MemoryLimit {
+                required,
+                available: limit,
+            });
+        }
+
         if self.state == State::MemoryAllocated
             && self.storage_mode == BoardState::River
-            && self.is_compression_enabled == enable_compression
+            && self.strategy_format == strategy_format
+            && self.value_format == value_format
         {
-            return;
+            return Ok(());
         }
 
-        let num_bytes = if enable_compression { 2 } else { 4 };
-        if num_bytes * self.num_storage > isize::MAX as u64
-            || num_bytes * self.num_storage_chance > isize::MAX as u64
+        let strategy_bytes = strategy_format.num_bytes() as u64;
+        let value_bytes = value_format.num_bytes() as u64;
+        if strategy_bytes.max(value_bytes) * self.num_storage > isize::MAX as u64
+            || value_bytes * self.num_storage_chance > isize::MAX as u64
         {
-            panic!("Memory usage exceeds maximum size");
+            return Err(PostFlopError::AllocationFailed {
+                required,
+                reason: "Memory usage exceeds maximum size".to_string(),
+            });
         }
 
-        self.state = State::MemoryAllocated;
-        self.is_compression_enabled = enable_compression;
+        self.state = State::TreeBuilt;
+        self.solve_summary = None;
+        #[cfg(feature = "profile")]
+        {
+            self.solver_stats = None;
+        }
 
         self.clear_storage();
 
-        let storage_bytes = (num_bytes * self.num_storage) as usize;
-        let storage_ip_bytes = (num_bytes * self.num_storage_ip) as usize;
-        let storage_chance_bytes = (num_bytes * self.num_storage_chance) as usize;
+        let storage1_bytes = (strategy_bytes * self.num_storage) as usize;
+        let storage2_bytes = (value_bytes * self.num_storage) as usize;
+        let storage_ip_bytes = (value_bytes * self.num_storage_ip) as usize;
+        let storage_chance_bytes = (value_bytes * self.num_storage_chance) as usize;
 
-        self.storage1 = vec![0; storage_bytes];
-        self.storage2 = vec![0; storage_bytes];
-        self.storage_ip = vec![0; storage_ip_bytes];
-        self.storage_chance = vec![0; storage_chance_bytes];
+        let allocate = |len| {
+            StorageBuffer::new(len, &self.storage_backend).map_err(|e| match e {
+                PostFlopError::AllocationFailed { reason, .. } => {
+                    PostFlopError::AllocationFailed { required, reason }
+                }
+                e => e,
+            })
+        };
+
+        let storage1 = allocate(storage1_bytes)?;
+        let storage2 = allocate(storage2_bytes)?;
+        let storage_ip = allocate(storage_ip_bytes)?;
+        let storage_chance = allocate(storage_chance_bytes)?;
+
+        self.state = State::MemoryAllocated;
+        self.strategy_format = strategy_format;
+        self.value_format = value_format;
+        self.storage1 = storage1;
+        self.storage2 = storage2;
+        self.storage_ip = storage_ip;
+        self.storage_chance = storage_chance;
 
         self.allocate_memory_nodes();
 
         self.storage_mode = BoardState::River;
         self.target_storage_mode = BoardState::River;
+
+        Ok(())
+    }
+
+    /// Allocates the memory with the most accurate storage formats whose estimated memory usage
+    /// does not exceed `max_bytes`, and returns the chosen formats of the strategies and the other
+    /// values (see [`allocate_memory_with_formats`]).
+    ///
+    /// The following pairs of formats are tried in this order:
+    ///
+    /// | Strategies                  | Other values                  | Bytes per element |
+    /// |-----------------------------|-------------------------------|-------------------|
+    /// | [`StorageFormat::Float32`]  | [`StorageFormat::Float32`]    | 4 + 4             |
+    /// | [`StorageFormat::Int16`]    | [`StorageFormat::Float32`]    | 2 + 4             |
+    /// | [`StorageFormat::Int16`]    | [`StorageFormat::Float16`]    | 2 + 2             |
+    /// | [`StorageFormat::Int16`]    | [`StorageFormat::Int16`]      | 2 + 2             |
+    /// | [`StorageFormat::Int8`]     | [`StorageFormat::Float16`]    | 1 + 2             |
+    /// | [`StorageFormat::Int8`]     | [`StorageFormat::Int8`]       | 1 + 1             |
+    ///
+    /// The other values (i.e., the regrets and the counterfactual values) are kept in floating
+    /// point numbers as long as possible, because [`StorageFormat::Float16`] keeps them almost as
+    /// accurate as the 32-bit floats. [`StorageFormat::Float16`] is not used for the strategies,
+    /// because the cumulative strategies lose small increments as they grow; the scaled integers
+    /// are more accurate for them at the same size. The 16-bit compression of both (i.e.,
+    /// `allocate_memory(true, ..)`) is tried after the half-precision values at the same memory
+    /// usage in case the allocation fails.
+    ///
+    /// If the allocation of a pair fails, the next pair is tried. Returns the error of the last
+    /// pair if no pair can be allocated (see [`allocate_memory`]).
+    ///
+    /// [`allocate_memory_with_formats`]: #method.allocate_memory_with_formats
+    /// [`allocate_memory`]: #method.allocate_memory
+    pub fn allocate_memory_auto(
+        &mut self,
+        max_bytes: u64,
+    ) -> Result<(StorageFormat, StorageFormat), PostFlopError> {
+        use StorageFormat::*;
+        let mut error = None;
+
+        for formats in [
+            (Float32, Float32),
+            (Int16, Float32),
+            (Int16, Float16),
+            (Int16, Int16),
+            (Int8, Float16),
+            (Int8, Int8),
+        ] {
+            match self.allocate_memory_with_formats(formats.0, formats.1, Some(max_bytes)) {
+                Ok(()) => return Ok(formats),
+                Err(e) => error = Some(e),
+            }
+        }
+
+        Err(error.unwrap())
     }
 
     /// Checks the card configuration.
-    pub(crate) fn check_card_config(&mut self) -> Result<(), String> {
+    pub(crate) fn check_card_config(&mut self) -> Result<(), PostFlopError> {
         let config = &self.card_config;
         let (flop, turn, river) = (config.flop, config.turn, config.river);
         let range = &config.range;
 
         if flop.contains(&NOT_DEALT) {
-            return Err("Flop cards not initialized".to_string());
+            return Err(PostFlopError::InvalidConfig(
+                "Flop cards not initialized".to_string(),
+            ));
         }
 
         if flop.iter().any(|&c| 52 <= c) {
-            return Err(format!("Flop cards must be in [0, 52): flop = {flop:?}"));
+            return Err(PostFlopError::InvalidConfig(format!(
+                "Flop cards must be in [0, 52): flop = {flop:?}"
+            )));
         }
 
         if flop[0] == flop[1] || flop[0] == flop[2] || flop[1] == flop[2] {
-            return Err(format!("Flop cards must be unique: flop = {flop:?}"));
+            let card = if flop[0] == flop[1] { flop[0] } else { flop[2] };
+            return Err(PostFlopError::BoardConflict { card });
         }
 
         if turn != NOT_DEALT {
             if 52 <= turn {
-                return Err(format!("Turn card must be in [0, 52): turn = {turn}"));
+                return Err(PostFlopError::InvalidConfig(format!(
+                    "Turn card must be in [0, 52): turn = {turn}"
+                )));
             }
 
             if flop.contains(&turn) {
-                return Err(format!(
-                    "Turn card must be different from flop cards: turn = {turn}"
-                ));
+                return Err(PostFlopError::BoardConflict { card: turn });
             }
         }
 
         if river != NOT_DEALT {
             if 52 <= river {
-                return Err(format!("River card must be in [0, 52): river = {river}"));
+                return Err(PostFlopError::InvalidConfig(format!(
+                    "River card must be in [0, 52): river = {river}"
+                )));
             }
 
             if flop.contains(&river) {
-                return Err(format!(
-                    "River card must be different from flop cards: river = {river}"
-                ));
+                return Err(PostFlopError::BoardConflict { card: river });
             }
 
             if turn == river {
-                return Err(format!(
-                    "River card must be different from turn card: river = {river}"
-                ));
+                return Err(PostFlopError::BoardConflict { card: river });
             }
 
             if turn == NOT_DEALT {
-                return Err(format!(
+                return Err(PostFlopError::InvalidConfig(format!(
                     "River card specified without turn card: river = {river}"
-                ));
+                )));
             }
         }
 
@@ -414,29 +697,38 @@ impl PostFlopGame {
         };
 
         if self.tree_config.initial_state != expected_state {
-            return Err(format!(
+            return Err(PostFlopError::InvalidConfig(format!(
                 "Invalid initial state of `tree_config`: expected = {:?}, actual = {:?}",
                 expected_state, self.tree_config.initial_state
-            ));
+            )));
         }
 
         if range[0].is_empty() {
-            return Err("OOP range is empty".to_string());
+            return Err(PostFlopError::InvalidConfig(
+                "OOP range is empty".to_string(),
+            ));
         }
 
         if range[1].is_empty() {
-            return Err("IP range is empty".to_string());
+            return Err(PostFlopError::InvalidConfig(
+                "IP range is empty".to_string(),
+            ));
         }
 
         if !range[0].is_valid() {
-            return Err("OOP range is invalid (loaded broken data?)".to_string());
+            return Err(PostFlopError::InvalidConfig(
+                "OOP range is invalid (loaded broken data?)".to_string(),
+            ));
         }
 
         if !range[1].is_valid() {
-            return Err("IP range is invalid (loaded broken data?)".to_string());
+            return Err(PostFlopError::InvalidConfig(
+                "IP range is invalid (loaded broken data?)".to_string(),
+            ));
         }
 
         self.init_hands();
+        self.is_weight_modified = false;
         self.num_combinations = 0.0;
 
         for (&(c1, c2), &w1) in self.private_cards[0]
@@ -456,13 +748,16 @@ impl PostFlopGame {
         }
 
         if self.num_combinations == 0.0 {
-            return Err("Valid card assignment does not exist".to_string());
+            return Err(PostFlopError::InvalidConfig(
+                "Valid card assignment does not exist".to_string(),
+            ));
         }
 
         Ok(())
     }
 
-    /// Initializes fields `initial_weights` and `private_cards`.
+    /// Initializes fields `initial_weights` and `private_cards` (and the fields of the hand
+    /// abstraction if enabled).
     #[inline]
     fn init_hands(&mut self) {
         let config = &self.card_config;
@@ -482,6 +777,15 @@ impl PostFlopGame {
             self.initial_weights[player] = weights;
             self.private_cards[player] = hands;
         }
+
+        if self.is_hand_abstracted {
+            let board = [flop[0], flop[1], flop[2], turn, river];
+            let num_cards = 3 + (turn != NOT_DEALT) as usize + (river != NOT_DEALT) as usize;
+            self.abstract_hands(&board[..num_cards]);
+        } else {
+            self.full_private_cards = Default::default();
+            self.abstract_hand_index = Default::default();
+        }
     }
 
     /// Initializes fields related to cards.
@@ -509,29 +813,33 @@ impl PostFlopGame {
 
         self.hand_strength = self.card_config.hand_strength(&self.private_cards);
 
-        (
-            self.isomorphism_ref_turn,
-            self.isomorphism_card_turn,
-            self.isomorphism_swap_turn,
-            self.isomorphism_ref_river,
-            self.isomorphism_card_river,
-            self.isomorphism_swap_river,
-        ) = self.card_config.isomorphism(&self.private_cards);
+        if self.card_buckets.is_empty() {
+            (
+                self.isomorphism_ref_turn,
+                self.isomorphism_card_turn,
+                self.isomorphism_swap_turn,
+                self.isomorphism_ref_river,
+                self.isomorphism_card_river,
+                self.isomorphism_swap_river,
+            ) = self.card_config.isomorphism(&self.private_cards);
+        }
+
+        self.init_buckets();
     }
 
     /// Initializes the root node of game tree.
-    fn init_root(&mut self) -> Result<(), String> {
+    pub(super) fn init_root(&mut self) -> Result<(), PostFlopError> {
         let num_nodes = self.count_num_nodes();
         let total_num_nodes = num_nodes[0] + num_nodes[1] + num_nodes[2];
 
         if total_num_nodes > u32::MAX as u64
             || mem::size_of::<PostFlopNode>() as u64 * total_num_nodes > isize::MAX as u64
         {
-            return Err("Too many nodes".to_string());
+            return Err(PostFlopError::InvalidConfig("Too many nodes".to_string()));
         }
 
         self.num_nodes = num_nodes;
-        self.node_arena = (0..total_num_nodes)
+        self.node_arena = into_par_iter(0..total_num_nodes as usize)
             .map(|_| MutexLike::new(PostFlopNode::default()))
             .collect::<Vec<_>>();
         self.clear_storage();
@@ -578,30 +886,41 @@ impl PostFlopGame {
     /// Clears the storage.
     #[inline]
     fn clear_storage(&mut self) {
-        self.storage1 = Vec::new();
-        self.storage2 = Vec::new();
-        self.storage_ip = Vec::new();
-        self.storage_chance = Vec::new();
+        self.storage1 = Default::default();
+        self.storage2 = Default::default();
+        self.storage_ip = Default::default();
+        self.storage_chance = Default::default();
+        self.is_cfvalues_trimmed = false;
+
+        #[cfg(feature = "mmap")]
+        {
+            self.mapped_file = None;
+        }
+
+        #[cfg(feature = "bincode")]
+        {
+            self.deferred_chunks = None;
+        }
     }
 
     /// Counts the number of nodes in the game tree.
     #[inline]
     fn count_num_nodes(&self) -> [u64; 3] {
+        let num_rivers = |turn: Card| 48 - self.skip_chance_mask(turn).count_ones() as usize;
         let (turn_coef, river_coef) = match (self.card_config.turn, self.card_config.river) {
             (NOT_DEALT, _) => {
                 let mut river_coef = 0;
                 let flop = self.card_config.flop;
-                let skip_cards = &self.isomorphism_card_turn;
                 let flop_mask: u64 = (1 << flop[0]) | (1 << flop[1]) | (1 << flop[2]);
-                let skip_mask: u64 = skip_cards.iter().map(|&card| 1 << card).sum();
+                let skip_mask = self.skip_chance_mask(NOT_DEALT);
                 for turn in 0..52 {
                     if (1 << turn) & (flop_mask | skip_mask) == 0 {
-                        river_coef += 48 - self.isomorphism_card_river[turn & 3].len();
+                        river_coef += num_rivers(turn);
                     }
                 }
-                (49 - self.isomorphism_card_turn.len(), river_coef)
+                (49 - skip_mask.count_ones() as usize, river_coef)
             }
-            (turn, NOT_DEALT) => (1, 48 - self.isomorphism_card_river[turn as usize & 3].len()),
+            (turn, NOT_DEALT) => (1, num_rivers(turn)),
             _ => (0, 1),
         };
 
@@ -645,10 +964,31 @@ impl PostFlopGame {
             memory_usage += vec_memory_usage(cards);
         }
 
+        memory_usage += vec_memory_usage(&self.card_buckets);
+        memory_usage += vec_memory_usage(&self.bucket_ref_turn);
+        memory_usage += vec_memory_usage(&self.bucket_card_turn);
+        memory_usage += vec_memory_usage(&self.bucket_ref_river);
+        memory_usage += vec_memory_usage(&self.bucket_card_river);
+        memory_usage += vec_memory_usage(&self.bucket_swap);
+
+        for bucket in &self.card_buckets {
+            memory_usage += vec_memory_usage(bucket);
+        }
+
+        for refs in &self.bucket_ref_river {
+            memory_usage += vec_memory_usage(refs);
+        }
+
+        for cards in &self.bucket_card_river {
+            memory_usage += vec_memory_usage(cards);
+        }
+
         for player in 0..2 {
             memory_usage += vec_memory_usage(&self.initial_weights[player]);
             memory_usage += vec_memory_usage(&self.private_cards[player]);
             memory_usage += vec_memory_usage(&self.same_hand_index[player]);
+            memory_usage += vec_memory_usage(&self.full_private_cards[player]);
+            memory_usage += vec_memory_usage(&self.abstract_hand_index[player]);
             memory_usage += vec_memory_usage(&self.valid_indices_flop[player]);
             for indices in &self.valid_indices_turn {
                 memory_usage += vec_memory_usage(&indices[player]);
@@ -667,6 +1007,9 @@ impl PostFlopGame {
                     memory_usage += vec_memory_usage(&swap[player]);
                 }
             }
+            for swap in &self.bucket_swap {
+                memory_usage += vec_memory_usage(&swap[player]);
+            }
         }
 
         memory_usage += vec_memory_usage(&self.node_arena);
@@ -691,10 +1034,7 @@ impl PostFlopGame {
 
         if node.is_chance() {
             self.push_chances(node_index, info);
-            for action_index in 0..node.num_actions() {
-                let child_index = node_index + node.children_offset as usize + action_index;
-                self.build_tree_recursive(child_index, &action_node.children[0].lock(), info);
-            }
+            self.build_chance_children(node_index, &action_node.children[0].lock(), info);
         } else {
             self.push_actions(node_index, action_node, info);
             for action_index in 0..node.num_actions() {
@@ -708,6 +1048,65 @@ impl PostFlopGame {
         }
     }
 
+    /// Builds the subtrees of the children of the chance node in parallel.
+    ///
+    /// The numbers of nodes in the subtrees are computed in advance, so the nodes are placed at
+    /// the same indices as in the sequential construction.
+    fn build_chance_children(
+        &self,
+        node_index: usize,
+        action_node: &ActionTreeNode,
+        info: &mut BuildTreeInfo,
+    ) {
+        let node = self.node_arena[node_index].lock();
+        let children_index = node_index + node.children_offset as usize;
+
+        // the children themselves are already pushed by `push_chances`
+        let mut num_nodes = [0, 0, 0];
+        count_num_action_nodes_recursive(action_node, 0, &mut num_nodes);
+        num_nodes[0] -= 1;
+
+        // start indices of the subtrees, followed by the end indices
+        let mut starts = Vec::with_capacity(node.num_actions() + 1);
+        starts.push((info.turn_index, info.river_index));
+        for child in node.children() {
+            let (mut turn_index, mut river_index) = *starts.last().unwrap();
+            if node.turn == NOT_DEALT {
+                let skip_mask = self.skip_chance_mask(child.lock().turn);
+                turn_index += num_nodes[0] as usize;
+                river_index += num_nodes[1] as usize * (48 - skip_mask.count_ones() as usize);
+            } else {
+                river_index += num_nodes[0] as usize;
+            }
+            starts.push((turn_index, river_index));
+        }
+
+        let child_infos = into_par_iter(0..node.num_actions())
+            .map(|action_index| {
+                let (turn_index, river_index) = starts[action_index];
+                let mut child_info = BuildTreeInfo {
+                    turn_index,
+                    river_index,
+                    ..Default::default()
+                };
+                let child_index = children_index + action_index;
+                self.build_tree_recursive(child_index, action_node, &mut child_info);
+                debug_assert_eq!(
+                    (child_info.turn_index, child_info.river_index),
+                    starts[action_index + 1]
+                );
+                child_info
+            })
+            .collect::<Vec<_>>();
+
+        (info.turn_index, info.river_index) = *starts.last().unwrap();
+        for child_info in child_infos {
+            info.num_storage += child_info.num_storage;
+            info.num_storage_ip += child_info.num_storage_ip;
+            info.num_storage_chance += child_info.num_storage_chance;
+        }
+    }
+
     /// Pushes the chance actions to the `node`.
     fn push_chances(&self, node_index: usize, info: &mut BuildTreeInfo) {
         let mut node = self.node_arena[node_index].lock();
@@ -716,8 +1115,7 @@ impl PostFlopGame {
 
         // deal turn
         if node.turn == NOT_DEALT {
-            let skip_cards = &self.isomorphism_card_turn;
-            let skip_mask: u64 = skip_cards.iter().map(|&card| 1 << card).sum();
+            let skip_mask = self.skip_chance_mask(NOT_DEALT);
 
             node.children_offset = (info.turn_index - node_index) as u32;
             for card in 0..52 {
@@ -734,8 +1132,7 @@ impl PostFlopGame {
         // deal river
         else {
             let turn_mask = flop_mask | (1 << node.turn);
-            let skip_cards = &self.isomorphism_card_river[node.turn as usize & 3];
-            let skip_mask: u64 = skip_cards.iter().map(|&card| 1 << card).sum();
+            let skip_mask = self.skip_chance_mask(node.turn);
 
             node.children_offset = (info.river_index - node_index) as u32;
             for card in 0..52 {
@@ -802,10 +1199,16 @@ impl PostFlopGame {
     }
 
     /// Sets the bunching effect.
-    fn set_bunching_effect_internal(&mut self, bunching_data: &BunchingData) -> Result<(), String> {
+    fn set_bunching_effect_internal(
+        &mut self,
+        bunching_data: &BunchingData,
+        suit_mapping: [u8; 4],
+    ) -> Result<(), PostFlopError> {
         self.bunching_num_dead_cards = bunching_data.fold_ranges().len() * 2;
         let mut arena = vec![0.0]; // store dummy element
 
+        let map_mask = |mask: u64| BunchingData::map_mask(mask, &suit_mapping);
+
         // hand strength
         self.bunching_strength = self
             .hand_strength
@@ -848,7 +1251,7 @@ impl PostFlopGame {
                             arena.push(0.0);
                         } else {
                             let mask = player_mask | opponent_mask;
-                            arena.push(bunching_data.result_4cards(mask));
+                            arena.push(bunching_data.result_4cards(map_mask(mask)));
                         }
                     }
                 }
@@ -857,7 +1260,9 @@ impl PostFlopGame {
                     self.bunching_num_combinations = arena.iter().fold(0.0, |a, &x| a + x as f64);
                     if self.bunching_num_combinations == 0.0 {
                         self.reset_bunching_effect();
-                        return Err("Valid combination not found".to_string());
+                        return Err(PostFlopError::InvalidConfig(
+                            "Valid combination not found".to_string(),
+                        ));
                     }
                 }
 
@@ -900,7 +1305,7 @@ impl PostFlopGame {
                                     inner.push(0.0);
                                 } else {
                                     let mask = player_mask | opponent_mask | bit_turn;
-                                    inner.push(bunching_data.result_5cards(mask));
+                                    inner.push(bunching_data.result_5cards(map_mask(mask)));
                                 }
                             }
 
@@ -917,7 +1322,9 @@ impl PostFlopGame {
                     self.bunching_num_combinations = arena.iter().fold(0.0, |a, &x| a + x as f64);
                     if self.bunching_num_combinations == 0.0 {
                         self.reset_bunching_effect();
-                        return Err("Valid combination not found".to_string());
+                        return Err(PostFlopError::InvalidConfig(
+                            "Valid combination not found".to_string(),
+                        ));
                     }
                 }
             }
@@ -964,7 +1371,7 @@ impl PostFlopGame {
                                 inner.push(0.0);
                             } else {
                                 let mask = player_mask | opponent_mask | board_mask;
-                                inner.push(bunching_data.result_6cards(mask));
+                                inner.push(bunching_data.result_6cards(map_mask(mask)));
                             }
                         }
 
@@ -981,7 +1388,9 @@ impl PostFlopGame {
                 self.bunching_num_combinations = arena.iter().fold(0.0, |a, &x| a + x as f64);
                 if self.bunching_num_combinations == 0.0 {
                     self.reset_bunching_effect();
-                    return Err("Valid combination not found".to_string());
+                    return Err(PostFlopError::InvalidConfig(
+                        "Valid combination not found".to_string(),
+                    ));
                 }
             }
         }
@@ -1186,7 +1595,7 @@ impl PostFlopGame {
     }
 
     /// Sets the bunching effect.
-    fn memory_usage_bunching_internal(&self) -> u64 {
+    pub(super) fn memory_usage_bunching_internal(&self) -> u64 {
         let mut ret = 4;
 
         let oop_len = self.num_private_hands(0);
@@ -1352,13 +1761,15 @@ impl PostFlopGame {
         &self,
         node: &mut PostFlopNode,
         line: &[Action],
-    ) -> Result<BuildTreeInfo, String> {
+    ) -> Result<BuildTreeInfo, PostFlopError> {
         if line.is_empty() {
-            return Err("Empty line".to_string());
+            return Err(PostFlopError::InvalidArgument("Empty line".to_string()));
         }
 
         if node.is_terminal() {
-            return Err("Unexpected terminal node".to_string());
+            return Err(PostFlopError::InvalidArgument(
+                "Unexpected terminal node".to_string(),
+            ));
         }
 
         let action = line[0];
@@ -1367,7 +1778,9 @@ impl PostFlopGame {
             .binary_search_by(|child| child.lock().prev_action.cmp(&action));
 
         if search_result.is_err() {
-            return Err(format!("Action does not exist: {action:?}"));
+            return Err(PostFlopError::InvalidArgument(format!(
+                "Action does not exist: {action:?}"
+            )));
         }
 
         let index = search_result.unwrap();
@@ -1377,11 +1790,15 @@ impl PostFlopGame {
         }
 
         if node.is_chance() {
-            return Err("Cannot remove a line ending in a chance action".to_string());
+            return Err(PostFlopError::InvalidArgument(
+                "Cannot remove a line ending in a chance action".to_string(),
+            ));
         }
 
         if node.num_actions() <= 1 {
-            return Err("Cannot remove the last action from a node".to_string());
+            return Err(PostFlopError::InvalidArgument(
+                "Cannot remove the last action from a node".to_string(),
+            ));
         }
 
         // Remove action/children at index. To do this we must
@@ -1418,7 +1835,9 @@ impl PostFlopGame {
 
     /// Allocates memory recursively.
     fn allocate_memory_nodes(&mut self) {
-        let num_bytes = if self.is_compression_enabled { 2 } else { 4 };
+        let strategy_bytes = self.strategy_format.num_bytes();
+        let value_bytes = self.value_format.num_bytes();
+        let mut strategy_counter = 0;
         let mut action_counter = 0;
         let mut ip_counter = 0;
         let mut chance_counter = 0;
@@ -1432,18 +1851,19 @@ impl PostFlopGame {
                     let ptr = self.storage_chance.as_mut_ptr();
                     node.storage1 = ptr.add(chance_counter);
                 }
-                chance_counter += num_bytes * node.num_elements as usize;
+                chance_counter += value_bytes * node.num_elements as usize;
             } else {
                 unsafe {
                     let ptr1 = self.storage1.as_mut_ptr();
                     let ptr2 = self.storage2.as_mut_ptr();
                     let ptr3 = self.storage_ip.as_mut_ptr();
-                    node.storage1 = ptr1.add(action_counter);
+                    node.storage1 = ptr1.add(strategy_counter);
                     node.storage2 = ptr2.add(action_counter);
                     node.storage3 = ptr3.add(ip_counter);
                 }
-                action_counter += num_bytes * node.num_elements as usize;
-                ip_counter += num_bytes * node.num_elements_ip as usize;
+                strategy_counter += strategy_bytes * node.num_elements as usize;
+                action_counter += value_bytes * node.num_elements as usize;
+                ip_counter += value_bytes * node.num_elements_ip as usize;
             }
         }
     }
@@ -0,0 +1,246 @@
+use super::*;
+use crate::card::card_pair_to_index;
+
+impl PostFlopGame {
+    /// Returns the rank buckets of the card bucketing, or an empty slice if disabled.
+    #[inline]
+    pub fn card_buckets(&self) -> &[Vec<u8>] {
+        &self.card_buckets
+    }
+
+    /// Sets the rank buckets of the turn/river card bucketing. Passing an empty slice disables the
+    /// bucketing.
+    ///
+    /// Each bucket is a list of ranks (`0` = deuce, ..., `12` = ace), and the turn/river cards of
+    /// the same suit whose ranks belong to the same bucket are merged into one chance. The first
+    /// rank in the bucket that can be dealt is the representative, and the other cards are solved
+    /// with the subtree of the representative, where the two cards are exchanged (like the suit
+    /// isomorphism). Ranks not included in any bucket are dealt as usual.
+    ///
+    /// This shrinks the game tree roughly by the ratio of the number of buckets to the number of
+    /// ranks per street, but the abstraction is lossy: a bucketed card is treated as if its rank
+    /// were that of the representative. When the results are queried, playing a bucketed card
+    /// moves to the subtree of the representative and the hands are mapped accordingly. For the
+    /// exact results of a specific runout, solve a game whose turn (and river) is dealt in the
+    /// [`CardConfig`].
+    ///
+    /// The suit isomorphism is not used while the bucketing is enabled. The game tree is rebuilt,
+    /// so the memory must be allocated again. Returns an error if the buckets are invalid or if
+    /// the bunching effect is enabled. A game with the bucketing enabled cannot be saved.
+    pub fn set_card_buckets(&mut self, rank_buckets: &[Vec<u8>]) -> Result<(), PostFlopError> {
+        if self.state <= State::Uninitialized {
+            return Err(PostFlopError::InvalidState(
+                "Game is not successfully initialized".to_string(),
+            ));
+        }
+
+        if !rank_buckets.is_empty() && self.bunching_num_dead_cards > 0 {
+            return Err(PostFlopError::Unsupported(
+                "Card bucketing cannot be used with the bunching effect".to_string(),
+            ));
+        }
+
+        let mut rank_mask = 0u16;
+        for bucket in rank_buckets {
+            if bucket.is_empty() {
+                return Err(PostFlopError::InvalidArgument(
+                    "Bucket must not be empty".to_string(),
+                ));
+            }
+            for &rank in bucket {
+                if rank >= 13 {
+                    return Err(PostFlopError::InvalidArgument(format!(
+                        "Invalid rank: {rank}"
+                    )));
+                }
+                if rank_mask & (1 << rank) != 0 {
+                    return Err(PostFlopError::InvalidArgument(format!(
+                        "Rank appears in multiple buckets: {rank}"
+                    )));
+                }
+                rank_mask |= 1 << rank;
+            }
+        }
+
+        if self.card_buckets == rank_buckets {
+            return Ok(());
+        }
+
+        self.state = State::ConfigError;
+        self.card_buckets = rank_buckets.to_vec();
+        self.locking_strategy.clear();
+
+        self.check_card_config()?;
+        self.init_card_fields();
+        self.init_root()?;
+
+        self.state = State::TreeBuilt;
+
+        self.init_interpreter();
+        self.back_to_root();
+
+        Ok(())
+    }
+
+    /// Initializes the fields of the card bucketing. Called after the hands are initialized.
+    pub(super) fn init_buckets(&mut self) {
+        self.bucket_ref_turn = Vec::new();
+        self.bucket_card_turn = Vec::new();
+        self.bucket_ref_river = Vec::new();
+        self.bucket_card_river = Vec::new();
+        self.bucket_swap = Vec::new();
+
+        if self.card_buckets.is_empty() {
+            return;
+        }
+
+        self.isomorphism_ref_turn = Vec::new();
+        self.isomorphism_card_turn = Vec::new();
+        self.isomorphism_swap_turn = Default::default();
+        self.isomorphism_ref_river = vec![Vec::new(); 52];
+        self.isomorphism_card_river = Default::default();
+        self.isomorphism_swap_river = Default::default();
+
+        let mut bucket_index = [usize::MAX; 13];
+        for (i, bucket) in self.card_buckets.iter().enumerate() {
+            for &rank in bucket {
+                bucket_index[rank as usize] = i;
+            }
+        }
+
+        let config = &self.card_config;
+        let flop_mask: u64 = config.flop.iter().map(|&card| 1 << card).sum();
+
+        if config.turn == NOT_DEALT {
+            (self.bucket_ref_turn, self.bucket_card_turn) =
+                self.bucket_chances_internal(flop_mask, &bucket_index);
+        }
+
+        self.bucket_ref_river = vec![Vec::new(); 52];
+        self.bucket_card_river = vec![Vec::new(); 52];
+
+        if config.river == NOT_DEALT {
+            for turn in 0..52 {
+                if (1 << turn) & flop_mask != 0 || (config.turn != NOT_DEALT && config.turn != turn)
+                {
+                    continue;
+                }
+                (
+                    self.bucket_ref_river[turn as usize],
+                    self.bucket_card_river[turn as usize],
+                ) = self.bucket_chances_internal(flop_mask | (1 << turn), &bucket_index);
+            }
+        }
+
+        self.bucket_swap = vec![Default::default(); 52 * 51 / 2];
+        let pairs = self
+            .bucket_card_turn
+            .iter()
+            .chain(self.bucket_card_river.iter().flatten())
+            .copied()
+            .collect::<Vec<_>>();
+
+        let mut reverse_table = vec![usize::MAX; 52 * 51 / 2];
+        for (card1, card2) in pairs {
+            let swap_list = &mut self.bucket_swap[card_pair_to_index(card1, card2)];
+            if swap_list.iter().any(|swap| !swap.is_empty()) {
+                continue;
+            }
+
+            let replacer = |card: Card| match card {
+                _ if card == card1 => card2,
+                _ if card == card2 => card1,
+                _ => card,
+            };
+
+            for player in 0..2 {
+                reverse_table.fill(usize::MAX);
+                let cards = &self.private_cards[player];
+
+                for (i, &(c1, c2)) in cards.iter().enumerate() {
+                    reverse_table[card_pair_to_index(c1, c2)] = i;
+                }
+
+                // a hand without its counterpart is mapped to itself
+                for (i, &(c1, c2)) in cards.iter().enumerate() {
+                    let index = reverse_table[card_pair_to_index(replacer(c1), replacer(c2))];
+                    if i < index && index != usize::MAX {
+                        swap_list[player].push((i as u16, index as u16));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the mask of the cards that are not dealt as children of the chance node with the
+    /// given turn card (`NOT_DEALT` for the turn chance node).
+    #[inline]
+    pub(super) fn skip_chance_mask(&self, turn: Card) -> u64 {
+        if !self.card_buckets.is_empty() {
+            let cards = self.bucket_chances(turn).1;
+            cards.iter().map(|&(card, _)| 1 << card).sum()
+        } else if turn == NOT_DEALT {
+            self.isomorphism_card_turn
+                .iter()
+                .map(|&card| 1 << card)
+                .sum()
+        } else {
+            let cards = &self.isomorphism_card_river[turn as usize & 3];
+            cards.iter().map(|&card| 1 << card).sum()
+        }
+    }
+
+    /// Returns the indices to which the bucketed cards refer and the pairs of the bucketed card
+    /// and its representative card of the chance node with the given turn card.
+    #[inline]
+    pub(super) fn bucket_chances(&self, turn: Card) -> (&[u8], &[(Card, Card)]) {
+        if turn == NOT_DEALT {
+            (&self.bucket_ref_turn, &self.bucket_card_turn)
+        } else {
+            (
+                &self.bucket_ref_river[turn as usize],
+                &self.bucket_card_river[turn as usize],
+            )
+        }
+    }
+
+    /// Computes the bucketed chances of the chance node with the given board mask.
+    fn bucket_chances_internal(
+        &self,
+        board_mask: u64,
+        bucket_index: &[usize; 13],
+    ) -> (Vec<u8>, Vec<(Card, Card)>) {
+        let mut refs = Vec::new();
+        let mut cards = Vec::new();
+        let mut counter = 0;
+        let mut indices = [0; 52];
+
+        for card in 0..52 {
+            if (1 << card) & board_mask != 0 {
+                continue;
+            }
+
+            let repr_card = match bucket_index[card as usize >> 2] {
+                usize::MAX => card,
+                index => self.card_buckets[index]
+                    .iter()
+                    .map(|&rank| (rank << 2) | (card & 3))
+                    .find(|&c| (1 << c) & board_mask == 0)
+                    .unwrap(),
+            };
+
+            if repr_card == card {
+                indices[card as usize] = counter;
+                counter += 1;
+            } else {
+                cards.push((card, repr_card));
+            }
+        }
+
+        for &(_, repr_card) in &cards {
+            refs.push(indices[repr_card as usize]);
+        }
+
+        (refs, cards)
+    }
+}
@@ -0,0 +1,197 @@
+use super::*;
+use crate::bet_size::*;
+use crate::range::*;
+
+/// Fluent builder of [`PostFlopGame`], obtained by [`PostFlopGame::builder`].
+///
+/// Each method validates its argument immediately, and the first error is returned by [`build`]
+/// after the remaining calls are ignored. The same bet sizes are used for both players and all
+/// streets; use [`CardConfig`] and [`TreeConfig`] directly for finer control.
+///
+/// # Examples
+/// ```
+/// use postflop_solver_ffi::*;
+///
+/// let mut game = PostFlopGame::builder()
+///     .oop_range("66+,A8s+,A5s-A4s,AJo+,K9s+,KQo,QTs+,JTs,96s+,85s+,75s+,65s,54s")
+///     .ip_range("QQ-22,AQs-A2s,ATo+,K5s+,KJo+,Q8s+,J8s+,T7s+,96s+,86s+,75s+,64s+,53s+")
+///     .board("Td9d6h Qc")
+///     .pot(200)
+///     .stack(900)
+///     .bet_sizes("60%, e, a", "2.5x")
+///     .build()
+///     .unwrap();
+///
+/// game.allocate_memory(false, None).unwrap();
+/// ```
+///
+/// [`build`]: #method.build
+#[derive(Debug, Clone, Default)]
+pub struct PostFlopGameBuilder {
+    range: [Option<Range>; 2],
+    board: Option<Vec<Card>>,
+    tree_config: TreeConfig,
+    error: Option<PostFlopError>,
+}
+
+impl PostFlopGame {
+    /// Returns a [`PostFlopGameBuilder`] to construct a game step by step.
+    #[inline]
+    pub fn builder() -> PostFlopGameBuilder {
+        PostFlopGameBuilder::default()
+    }
+}
+
+impl PostFlopGameBuilder {
+    /// Sets the range of OOP (e.g., `"66+,A8s+,AJo+"`).
+    #[inline]
+    pub fn oop_range(self, range: &str) -> Self {
+        self.range(0, range)
+    }
+
+    /// Sets the range of IP.
+    #[inline]
+    pub fn ip_range(self, range: &str) -> Self {
+        self.range(1, range)
+    }
+
+    /// Sets the board: the flop cards and optionally the turn and river cards (e.g.,
+    /// `"Td9d6h"` or `"Td9d6h Qc"`). The initial street is determined by the number of cards.
+    pub fn board(mut self, board: &str) -> Self {
+        self.try_set(|builder| {
+            let cards = board_from_str(board)?;
+            builder.tree_config.initial_state = match cards.len() {
+                3 => BoardState::Flop,
+                4 => BoardState::Turn,
+                5 => BoardState::River,
+                n => {
+                    return Err(PostFlopError::InvalidConfig(format!(
+                        "Board must have 3 to 5 cards: {n}"
+                    )))
+                }
+            };
+            builder.board = Some(cards);
+            Ok(())
+        });
+        self
+    }
+
+    /// Sets the starting pot size.
+    pub fn pot(mut self, starting_pot: i32) -> Self {
+        self.try_set(|builder| {
+            if starting_pot <= 0 {
+                return Err(PostFlopError::InvalidConfig(format!(
+                    "Starting pot must be positive: {starting_pot}"
+                )));
+            }
+            builder.tree_config.starting_pot = starting_pot;
+            Ok(())
+        });
+        self
+    }
+
+    /// Sets the initial effective stack.
+    pub fn stack(mut self, effective_stack: i32) -> Self {
+        self.try_set(|builder| {
+            if effective_stack <= 0 {
+                return Err(PostFlopError::InvalidConfig(format!(
+                    "Effective stack must be positive: {effective_stack}"
+                )));
+            }
+            builder.tree_config.effective_stack = effective_stack;
+            Ok(())
+        });
+        self
+    }
+
+    /// Sets the bet and raise sizes of both players and all streets (see [`BetSizeOptions`]).
+    /// If not set, only all-in is used.
+    pub fn bet_sizes(mut self, bet: &str, raise: &str) -> Self {
+        self.try_set(|builder| {
+            let sizes = BetSizeOptions::try_from((bet, raise))?;
+            let config = &mut builder.tree_config;
+            config.flop_bet_sizes = [sizes.clone(), sizes.clone()];
+            config.turn_bet_sizes = [sizes.clone(), sizes.clone()];
+            config.river_bet_sizes = [sizes.clone(), sizes];
+            Ok(())
+        });
+        self
+    }
+
+    /// Sets the rake rate and the rake cap.
+    pub fn rake(mut self, rate: f64, cap: f64) -> Self {
+        self.try_set(|builder| {
+            if !(0.0..=1.0).contains(&rate) {
+                return Err(PostFlopError::InvalidConfig(format!(
+                    "Rake rate must be in [0, 1]: {rate}"
+                )));
+            }
+            if cap.is_nan() || cap < 0.0 {
+                return Err(PostFlopError::InvalidConfig(format!(
+                    "Rake cap must be non-negative: {cap}"
+                )));
+            }
+            builder.tree_config.rake_rate = rate;
+            builder.tree_config.rake_cap = cap;
+            Ok(())
+        });
+        self
+    }
+
+    /// Builds the game, whose memory is not yet allocated.
+    ///
+    /// Returns the first error of the previous calls, or an error if the ranges, the board, the
+    /// pot, or the stack are not set.
+    pub fn build(self) -> Result<PostFlopGame, PostFlopError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let [oop_range, ip_range] = self.range;
+        let oop_range = oop_range
+            .ok_or_else(|| PostFlopError::InvalidConfig("OOP range is not set".to_string()))?;
+        let ip_range = ip_range
+            .ok_or_else(|| PostFlopError::InvalidConfig("IP range is not set".to_string()))?;
+        let board = self
+            .board
+            .ok_or_else(|| PostFlopError::InvalidConfig("Board is not set".to_string()))?;
+        if self.tree_config.starting_pot == 0 {
+            return Err(PostFlopError::InvalidConfig(
+                "Starting pot is not set".to_string(),
+            ));
+        }
+        if self.tree_config.effective_stack == 0 {
+            return Err(PostFlopError::InvalidConfig(
+                "Effective stack is not set".to_string(),
+            ));
+        }
+
+        let mut flop = [board[0], board[1], board[2]];
+        flop.sort_unstable();
+        let card_config = CardConfig {
+            range: [oop_range, ip_range],
+            flop,
+            turn: board.get(3).copied().unwrap_or(NOT_DEALT),
+            river: board.get(4).copied().unwrap_or(NOT_DEALT),
+        };
+
+        let action_tree = ActionTree::new(self.tree_config)?;
+        PostFlopGame::with_config(card_config, action_tree)
+    }
+
+    /// Sets the range of the given player.
+    fn range(mut self, player: usize, range: &str) -> Self {
+        self.try_set(|builder| {
+            builder.range[player] = Some(range.parse()?);
+            Ok(())
+        });
+        self
+    }
+
+    /// Applies `f` unless an error has already occurred, and records the error of `f`.
+    fn try_set(&mut self, f: impl FnOnce(&mut Self) -> Result<(), PostFlopError>) {
+        if self.error.is_none() {
+            self.error = f(self).err();
+        }
+    }
+}
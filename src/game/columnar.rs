@@ -0,0 +1,140 @@
+use super::export::TableRow;
+use super::*;
+use crate::range::*;
+use arrow_array::{ArrayRef, Float32Array, RecordBatch, StringArray, UInt8Array};
+use parquet::arrow::ArrowWriter;
+use std::io::Write;
+use std::mem;
+use std::sync::Arc;
+
+/// Maximum number of rows of a record batch.
+const BATCH_SIZE: usize = 1 << 16;
+
+/// Column buffers of a record batch.
+#[derive(Default)]
+struct TableBuilder {
+    line: Vec<String>,
+    board: Vec<String>,
+    player: Vec<u8>,
+    hand: Vec<String>,
+    weight: Vec<f32>,
+    action: Vec<String>,
+    frequency: Vec<f32>,
+    ev: Vec<Option<f32>>,
+    equity: Vec<Option<f32>>,
+}
+
+impl TableBuilder {
+    /// Appends a row.
+    fn push(&mut self, row: &TableRow) {
+        self.line.push(row.line.to_string());
+        self.board.push(row.board.to_string());
+        self.player.push(row.player as u8);
+        self.hand.push(row.hand.to_string());
+        self.weight.push(row.weight);
+        self.action.push(row.action.to_string());
+        self.frequency.push(row.frequency);
+        self.ev.push(row.expected_value);
+        self.equity.push(row.equity);
+    }
+
+    /// Returns the number of buffered rows.
+    fn len(&self) -> usize {
+        self.line.len()
+    }
+
+    /// Builds a record batch from the buffered rows and clears the buffers.
+    fn finish(&mut self) -> RecordBatch {
+        let table = mem::take(self);
+        let columns: [(&str, ArrayRef); 9] = [
+            ("line", Arc::new(StringArray::from(table.line))),
+            ("board", Arc::new(StringArray::from(table.board))),
+            ("player", Arc::new(UInt8Array::from(table.player))),
+            ("hand", Arc::new(StringArray::from(table.hand))),
+            ("weight", Arc::new(Float32Array::from(table.weight))),
+            ("action", Arc::new(StringArray::from(table.action))),
+            ("frequency", Arc::new(Float32Array::from(table.frequency))),
+            ("ev", Arc::new(Float32Array::from(table.ev))),
+            ("equity", Arc::new(Float32Array::from(table.equity))),
+        ];
+        RecordBatch::try_from_iter(columns).unwrap()
+    }
+}
+
+impl PostFlopGame {
+    /// Returns the solved tree as Arrow record batches.
+    ///
+    /// The columns are the same as [`export_csv`]; `ev` and `equity` are null if not exported.
+    /// Each batch contains at most 65536 rows. The `precision` option is ignored. The current node
+    /// is not changed.
+    ///
+    /// Returns an error if a line of the options is invalid. Panics if the game is not solved.
+    ///
+    /// [`export_csv`]: #method.export_csv
+    pub fn export_record_batches(
+        &mut self,
+        options: &ExportOptions,
+    ) -> Result<Vec<RecordBatch>, PostFlopError> {
+        let mut batches = Vec::new();
+        self.export_batches(options, |batch| {
+            batches.push(batch);
+            Ok(())
+        })?;
+        Ok(batches)
+    }
+
+    /// Writes the solved tree in Parquet format into the given writer.
+    ///
+    /// The record batches of [`export_record_batches`] are written one by one, so the whole table
+    /// is never held in memory. The current node is not changed.
+    ///
+    /// Returns an error if a line of the options is invalid or writing fails. Panics if the game is
+    /// not solved.
+    ///
+    /// [`export_record_batches`]: #method.export_record_batches
+    pub fn export_parquet<W: Write + Send>(
+        &mut self,
+        writer: W,
+        options: &ExportOptions,
+    ) -> Result<(), PostFlopError> {
+        let write_error = |e: parquet::errors::ParquetError| {
+            PostFlopError::Io(format!("Failed to write Parquet: {e}"))
+        };
+
+        let schema = TableBuilder::default().finish().schema();
+        let mut parquet_writer = ArrowWriter::try_new(writer, schema, None).map_err(write_error)?;
+        self.export_batches(options, |batch| {
+            parquet_writer.write(&batch).map_err(write_error)
+        })?;
+        parquet_writer.close().map_err(write_error)?;
+
+        Ok(())
+    }
+
+    /// Passes the record batches of the selected nodes to the given function.
+    fn export_batches<F: FnMut(RecordBatch) -> Result<(), PostFlopError>>(
+        &mut self,
+        options: &ExportOptions,
+        mut f: F,
+    ) -> Result<(), PostFlopError> {
+        let hands = [0, 1].map(|player| holes_to_strings(&self.private_cards[player]).unwrap());
+        let mut table = TableBuilder::default();
+
+        self.export_nodes(options, |node| {
+            node.for_each_row(&hands, |row| {
+                table.push(row);
+                Ok(())
+            })?;
+            if table.len() >= BATCH_SIZE {
+                f(table.finish())?;
+            }
+            Ok(())
+        })?;
+
+        if table.len() > 0 {
+            f(table.finish())?;
+        }
+
+        Ok(())
+    }
+}
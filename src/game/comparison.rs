@@ -0,0 +1,289 @@
+use super::*;
+use crate::utility::*;
+
+/// Comparison of two solved games, returned by [`PostFlopGame::compare_scenario`].
+///
+/// The first game is the one `compare_scenario` is called on, and the second is the argument.
+/// Fields indexed by the game use `0` for the first game and `1` for the second, and all deltas
+/// are "second - first".
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioComparison {
+    /// Root expected values of the private hands contained in both games, for each player
+    /// (`0` = OOP, `1` = IP).
+    pub hands: [Vec<HandComparison>; 2],
+
+    /// Aggregate strategies of the lines shared by both games, in depth-first order.
+    pub lines: Vec<LineComparison>,
+
+    /// Average root expected value of each player (outer index) in each game (inner index),
+    /// weighted by the normalized weights.
+    pub average_expected_value: [[f32; 2]; 2],
+
+    /// Difference of the average root expected value of each player.
+    pub average_ev_delta: [f32; 2],
+
+    /// Mean of the L1 distances over all shared lines.
+    pub mean_l1: f32,
+
+    /// Mean of the L1 distances weighted by the reach probability and the pot size of each line.
+    pub weighted_l1: f32,
+}
+
+/// Root expected values of a private hand in both games.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandComparison {
+    /// Hole cards of the hand.
+    pub hand: (Card, Card),
+
+    /// Normalized weight of the hand in each game.
+    pub weights: [f32; 2],
+
+    /// Expected value of the hand in each game.
+    pub expected_values: [f32; 2],
+
+    /// Difference of the expected values.
+    pub ev_delta: f32,
+}
+
+/// Aggregate strategies of a line in both games.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineComparison {
+    /// Actions leading to the node from the root node.
+    pub line: Vec<Action>,
+
+    /// Current player (`0` = OOP, `1` = IP).
+    pub player: usize,
+
+    /// Pot size at the node in the first game, including the starting pot.
+    pub pot: i32,
+
+    /// Union of the available actions of both games: the actions of the first game followed by
+    /// the actions available only in the second game.
+    pub actions: Vec<Action>,
+
+    /// Aggregate frequency of each action of [`actions`](Self::actions) in each game, weighted
+    /// by the normalized weights. Actions unavailable in a game have zero frequency.
+    pub frequencies: [Vec<f32>; 2],
+
+    /// Probability of reaching the node, averaged over the two games.
+    pub reach: f32,
+
+    /// L1 distance between the aggregate frequencies. The value ranges from 0 (identical) to 2
+    /// (disjoint).
+    pub l1: f32,
+}
+
+impl LineComparison {
+    /// Returns the difference of the frequency of each action.
+    #[inline]
+    pub fn frequency_deltas(&self) -> Vec<f32> {
+        let [frequencies1, frequencies2] = &self.frequencies;
+        frequencies1
+            .iter()
+            .zip(frequencies2)
+            .map(|(&f1, &f2)| f2 - f1)
+            .collect()
+    }
+}
+
+impl PostFlopGame {
+    /// Compares this game with `other`, e.g., games solved with different trees or ranges.
+    ///
+    /// The games must have the same board, but the trees and the ranges may differ. The private
+    /// hands are matched by their hole cards, and only the hands reaching the root node in both
+    /// games are compared. The lines are compared on the street of the root node: the nodes
+    /// reached by the actions available in both games are compared, while the lines after a
+    /// chance node are not. A line is skipped if either game never reaches it. The current nodes
+    /// of both games are not changed.
+    ///
+    /// Returns an error if the boards do not match. Panics if either game is not solved or the
+    /// expected values are trimmed.
+    ///
+    /// **Time complexity:** *O*(#(shared lines) * #(actions) * #(private hands)) in addition to
+    /// the computation of the root expected values of both games.
+    pub fn compare_scenario(
+        &mut self,
+        other: &mut PostFlopGame,
+    ) -> Result<ScenarioComparison, PostFlopError> {
+        if self.state != State::Solved || other.state != State::Solved {
+            panic!("Game is not solved");
+        }
+
+        let (config1, config2) = (&self.card_config, &other.card_config);
+        if (config1.flop, config1.turn, config1.river)
+            != (config2.flop, config2.turn, config2.river)
+        {
+            return Err(PostFlopError::InvalidArgument(
+                "Boards do not match".to_string(),
+            ));
+        }
+
+        let saved = [self.save_navigation(), other.save_navigation()];
+        self.back_to_root();
+        other.back_to_root();
+
+        let summary = [self.node_summary(), other.node_summary()];
+
+        let hands = [0, 1].map(|player| {
+            let mut hands = Vec::new();
+            let private_cards = [&self.private_cards[player], &other.private_cards[player]];
+            for (index1, &hand) in private_cards[0].iter().enumerate() {
+                let Ok(index2) = private_cards[1].binary_search(&hand) else {
+                    continue;
+                };
+                let weights = [
+                    summary[0].normalized_weights[player][index1],
+                    summary[1].normalized_weights[player][index2],
+                ];
+                if weights[0] > 0.0 && weights[1] > 0.0 {
+                    let expected_values = [
+                        summary[0].expected_values[player][index1],
+                        summary[1].expected_values[player][index2],
+                    ];
+                    hands.push(HandComparison {
+                        hand,
+                        weights,
+                        expected_values,
+                        ev_delta: expected_values[1] - expected_values[0],
+                    });
+                }
+            }
+            hands
+        });
+
+        let average_expected_value = [0, 1].map(|player| {
+            [
+                summary[0].average_expected_value[player],
+                summary[1].average_expected_value[player],
+            ]
+        });
+
+        let mut lines = Vec::new();
+        compare_lines_recursive([self, other], &mut Vec::new(), &mut lines);
+
+        let mean_l1 = lines.iter().map(|l| l.l1 as f64).sum::<f64>() / lines.len().max(1) as f64;
+
+        let (mut weighted_sum, mut weight_sum) = (0.0, 0.0);
+        for line in &lines {
+            let weight = line.reach as f64 * line.pot as f64;
+            weighted_sum += weight * line.l1 as f64;
+            weight_sum += weight;
+        }
+
+        self.restore_navigation(&saved[0]);
+        other.restore_navigation(&saved[1]);
+
+        Ok(ScenarioComparison {
+            hands,
+            lines,
+            average_expected_value,
+            average_ev_delta: average_expected_value.map(|[ev1, ev2]| ev2 - ev1),
+            mean_l1: mean_l1 as f32,
+            weighted_l1: if weight_sum > 0.0 {
+                (weighted_sum / weight_sum) as f32
+            } else {
+                0.0
+            },
+        })
+    }
+
+    /// Returns the probability of reaching the current node, ignoring the card removal effect
+    /// between the players.
+    fn reach_probability(&self) -> f64 {
+        let mut reach = 1.0;
+        for player in 0..2 {
+            let sum = |weights: &[f32]| weights.iter().map(|&w| w as f64).sum::<f64>();
+            let initial = sum(&self.initial_weights[player]);
+            if initial > 0.0 {
+                reach *= sum(&self.weights[player]) / initial;
+            }
+        }
+        reach
+    }
+}
+
+/// Recursive function to compare the aggregate strategies of the shared lines.
+fn compare_lines_recursive(
+    games: [&mut PostFlopGame; 2],
+    line: &mut Vec<Action>,
+    lines: &mut Vec<LineComparison>,
+) {
+    let [game1, game2] = games;
+    if game1.is_terminal_node() || game1.is_chance_node() {
+        return;
+    }
+    if game2.is_terminal_node() || game2.is_chance_node() {
+        return;
+    }
+
+    let player = game1.current_player();
+    if player != game2.current_player() {
+        return;
+    }
+
+    let reach = [game1.reach_probability(), game2.reach_probability()];
+    if reach[0] == 0.0 || reach[1] == 0.0 {
+        return;
+    }
+
+    let actions1 = game1.available_actions();
+    let actions2 = game2.available_actions();
+    let mut actions = actions1.clone();
+    actions.extend(actions2.iter().filter(|a| !actions1.contains(a)));
+
+    let frequencies = [
+        aggregate_frequencies(game1, &actions1, &actions),
+        aggregate_frequencies(game2, &actions2, &actions),
+    ];
+
+    let l1 = frequencies[0]
+        .iter()
+        .zip(&frequencies[1])
+        .map(|(&f1, &f2)| (f1 - f2).abs())
+        .sum::<f32>();
+
+    let total_bet_amount = game1.total_bet_amount();
+    lines.push(LineComparison {
+        line: line.clone(),
+        player,
+        pot: game1.tree_config.starting_pot + total_bet_amount[0] + total_bet_amount[1],
+        actions,
+        frequencies,
+        reach: ((reach[0] + reach[1]) * 0.5) as f32,
+        l1,
+    });
+
+    for (index1, &action) in actions1.iter().enumerate() {
+        let Some(index2) = actions2.iter().position(|&a| a == action) else {
+            continue;
+        };
+
+        let saved = [game1.save_navigation(), game2.save_navigation()];
+        game1.play(index1);
+        game2.play(index2);
+        line.push(action);
+        compare_lines_recursive([&mut *game1, &mut *game2], line, lines);
+        line.pop();
+        game1.restore_navigation(&saved[0]);
+        game2.restore_navigation(&saved[1]);
+    }
+}
+
+/// Returns the aggregate frequency of each action of `actions` at the current node of the game.
+fn aggregate_frequencies(
+    game: &mut PostFlopGame,
+    game_actions: &[Action],
+    actions: &[Action],
+) -> Vec<f32> {
+    game.cache_normalized_weights();
+    let strategy = game.strategy();
+    let weights = game.normalized_weights(game.current_player());
+    let num_hands = weights.len();
+
+    let mut frequencies = vec![0.0; actions.len()];
+    for (row, action) in strategy.chunks_exact(num_hands).zip(game_actions) {
+        let index = actions.iter().position(|a| a == action).unwrap();
+        frequencies[index] = compute_average(row, weights);
+    }
+    frequencies
+}
@@ -0,0 +1,252 @@
+use super::*;
+use crate::utility::*;
+
+/// Decodes the buffer of the node stored in the given format to the `f32` vector.
+macro_rules! decode_buffer {
+    ($format:expr, $node:expr, $f32:ident, $f16:ident, $int16:ident, $int8:ident, $scale:ident) => {
+        match $format {
+            StorageFormat::Float32 => $node.$f32().to_vec(),
+            StorageFormat::Float16 => decode_f16_slice($node.$f16()),
+            StorageFormat::Int16 => decode_quantized_slice($node.$int16(), $node.$scale()),
+            StorageFormat::Int8 => decode_quantized_slice($node.$int8(), $node.$scale()),
+        }
+    };
+}
+
+/// Encodes the `f32` slice to the buffer of the node in the given format.
+macro_rules! encode_buffer {
+    (
+        $format:expr, $node:expr, $values:expr, $encode:ident,
+        $f32:ident, $f16:ident, $int16:ident, $int8:ident, $set_scale:ident
+    ) => {
+        match $format {
+            StorageFormat::Float32 => $node.$f32().copy_from_slice($values),
+            StorageFormat::Float16 => encode_f16_slice($node.$f16(), $values),
+            StorageFormat::Int16 => {
+                let scale = $encode($node.$int16(), $values);
+                $node.$set_scale(scale);
+            }
+            StorageFormat::Int8 => {
+                let scale = $encode($node.$int8(), $values);
+                $node.$set_scale(scale);
+            }
+        }
+    };
+}
+
+impl PostFlopGame {
+    /// Converts the allocated storage to the compressed or uncompressed format in place.
+    ///
+    /// See [`convert_memory_with_formats`] for details.
+    ///
+    /// [`convert_memory_with_formats`]: #method.convert_memory_with_formats
+    #[inline]
+    pub fn convert_memory(&mut self, enable_compression: bool) -> Result<(), PostFlopError> {
+        let format = match enable_compression {
+            false => StorageFormat::Float32,
+            true => StorageFormat::Int16,
+        };
+        self.convert_memory_with_formats(format, format)
+    }
+
+    /// Converts the allocated storage to the given formats of the strategies and the other values
+    /// in place, without re-solving.
+    ///
+    /// The stored values are decoded and re-encoded in the new formats, so converting to a
+    /// format of lower precision loses accuracy. The game can be solved further after the
+    /// conversion. The new storages are allocated with the current backend.
+    ///
+    /// Returns an error if the memory is not allocated, if the game is memory-mapped or not
+    /// fully loaded, or if the value format is changed after the counterfactual values are
+    /// freed by [`trim_to_strategy`].
+    ///
+    /// [`trim_to_strategy`]: #method.trim_to_strategy
+    pub fn convert_memory_with_formats(
+        &mut self,
+        strategy_format: StorageFormat,
+        value_format: StorageFormat,
+    ) -> Result<(), PostFlopError> {
+        if self.state < State::MemoryAllocated {
+            return Err(PostFlopError::InvalidState(
+                "Memory is not allocated".to_string(),
+            ));
+        }
+
+        #[cfg(feature = "bincode")]
+        if self.is_memory_mapped() {
+            return Err(PostFlopError::Unsupported(
+                "Memory-mapped game cannot be converted".to_string(),
+            ));
+        }
+
+        if self.storage_mode != BoardState::River {
+            return Err(PostFlopError::InvalidState(
+                "Game is not fully loaded".to_string(),
+            ));
+        }
+
+        if self.is_cfvalues_trimmed && value_format != self.value_format {
+            return Err(PostFlopError::InvalidState(
+                "Expected values are trimmed".to_string(),
+            ));
+        }
+
+        if strategy_format != self.strategy_format {
+            self.convert_strategy(strategy_format)?;
+        }
+
+        if value_format != self.value_format {
+            self.convert_values(value_format)?;
+        }
+
+        Ok(())
+    }
+
+    /// Converts the strategy storage to the given format.
+    fn convert_strategy(&mut self, format: StorageFormat) -> Result<(), PostFlopError> {
+        let (old_bytes, new_bytes) = (self.strategy_format.num_bytes(), format.num_bytes());
+        let len = new_bytes * self.num_storage as usize;
+        let mut storage1 = StorageBuffer::new(len, &self.storage_backend)?;
+
+        for node in &self.node_arena {
+            let mut node = node.lock();
+            if node.is_terminal() || node.is_chance() {
+                continue;
+            }
+
+            let strategy = decode_buffer!(
+                self.strategy_format,
+                node,
+                strategy,
+                strategy_f16,
+                strategy_compressed,
+                strategy_8bit,
+                strategy_scale
+            );
+
+            let offset = unsafe { node.storage1.offset_from(self.storage1.as_ptr()) } as usize;
+            node.storage1 = unsafe { storage1.as_mut_ptr().add(offset / old_bytes * new_bytes) };
+
+            encode_buffer!(
+                format,
+                node,
+                &strategy,
+                encode_unsigned_slice,
+                strategy_mut,
+                strategy_f16_mut,
+                strategy_compressed_mut,
+                strategy_8bit_mut,
+                set_strategy_scale
+            );
+        }
+
+        self.storage1 = storage1;
+        self.strategy_format = format;
+        Ok(())
+    }
+
+    /// Converts the storages of the regrets and the counterfactual values to the given format.
+    pub(super) fn convert_values(&mut self, format: StorageFormat) -> Result<(), PostFlopError> {
+        let (old_bytes, new_bytes) = (self.value_format.num_bytes(), format.num_bytes());
+        let backend = &self.storage_backend;
+        let mut storage2 = StorageBuffer::new(new_bytes * self.num_storage as usize, backend)?;
+        let mut storage_ip = StorageBuffer::new(new_bytes * self.num_storage_ip as usize, backend)?;
+        let mut storage_chance =
+            StorageBuffer::new(new_bytes * self.num_storage_chance as usize, backend)?;
+
+        for node in &self.node_arena {
+            let mut node = node.lock();
+            if node.is_terminal() {
+                continue;
+            }
+
+            if node.is_chance() {
+                let values = decode_buffer!(
+                    self.value_format,
+                    node,
+                    cfvalues_chance,
+                    cfvalues_chance_f16,
+                    cfvalues_chance_compressed,
+                    cfvalues_chance_8bit,
+                    cfvalue_chance_scale
+                );
+
+                let base = self.storage_chance.as_ptr();
+                let offset = unsafe { node.storage1.offset_from(base) } as usize;
+                let offset = offset / old_bytes * new_bytes;
+                node.storage1 = unsafe { storage_chance.as_mut_ptr().add(offset) };
+
+                encode_buffer!(
+                    format,
+                    node,
+                    &values,
+                    encode_signed_slice,
+                    cfvalues_chance_mut,
+                    cfvalues_chance_f16_mut,
+                    cfvalues_chance_compressed_mut,
+                    cfvalues_chance_8bit_mut,
+                    set_cfvalue_chance_scale
+                );
+                continue;
+            }
+
+            // the regrets and the counterfactual values share the same buffer
+            let values = decode_buffer!(
+                self.value_format,
+                node,
+                regrets,
+                regrets_f16,
+                regrets_compressed,
+                regrets_8bit,
+                regret_scale
+            );
+            let values_ip = decode_buffer!(
+                self.value_format,
+                node,
+                cfvalues_ip,
+                cfvalues_ip_f16,
+                cfvalues_ip_compressed,
+                cfvalues_ip_8bit,
+                cfvalue_ip_scale
+            );
+
+            let offset = unsafe { node.storage2.offset_from(self.storage2.as_ptr()) } as usize;
+            let offset_ip = unsafe { node.storage3.offset_from(self.storage_ip.as_ptr()) } as usize;
+            node.storage2 = unsafe { storage2.as_mut_ptr().add(offset / old_bytes * new_bytes) };
+            node.storage3 = unsafe {
+                storage_ip
+                    .as_mut_ptr()
+                    .add(offset_ip / old_bytes * new_bytes)
+            };
+
+            encode_buffer!(
+                format,
+                node,
+                &values,
+                encode_signed_slice,
+                regrets_mut,
+                regrets_f16_mut,
+                regrets_compressed_mut,
+                regrets_8bit_mut,
+                set_regret_scale
+            );
+            encode_buffer!(
+                format,
+                node,
+                &values_ip,
+                encode_signed_slice,
+                cfvalues_ip_mut,
+                cfvalues_ip_f16_mut,
+                cfvalues_ip_compressed_mut,
+                cfvalues_ip_8bit_mut,
+                set_cfvalue_ip_scale
+            );
+        }
+
+        self.storage2 = storage2;
+        self.storage_ip = storage_ip;
+        self.storage_chance = storage_chance;
+        self.value_format = format;
+        Ok(())
+    }
+}
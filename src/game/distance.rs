@@ -0,0 +1,356 @@
+use super::visitor::update_bet_amount;
+use super::*;
+use crate::interface::*;
+use crate::sliceop::*;
+
+/// Strategy divergence between two games, returned by [`PostFlopGame::strategy_distance`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrategyDistance {
+    /// Divergence of each decision node, in depth-first order.
+    pub nodes: Vec<NodeDistance>,
+
+    /// Mean of the L1 distances over all decision nodes.
+    pub mean_l1: f32,
+
+    /// Mean of the L1 distances weighted by the reach probability and the pot size of each node.
+    pub weighted_l1: f32,
+}
+
+/// Strategy divergence of a decision node.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeDistance {
+    /// Action history from the root node (see [`PostFlopGame::history`]).
+    pub history: Vec<usize>,
+
+    /// Current player (`0` = OOP, `1` = IP).
+    pub player: usize,
+
+    /// Pot size at the node, including the starting pot.
+    pub pot: i32,
+
+    /// Probability of reaching the node, averaged over the two games.
+    pub reach: f32,
+
+    /// L1 distance between the strategies, averaged over the private hands of the current player.
+    /// The value ranges from 0 (identical) to 2 (disjoint).
+    pub l1: f32,
+}
+
+/// Mutable state of the traversal.
+struct DistanceState {
+    history: Vec<usize>,
+    total_bet_amount: [i32; 2],
+    chance_probability: f64,
+    reach: [[Vec<f32>; 2]; 2],
+}
+
+impl PostFlopGame {
+    /// Compares the strategies of two games with the same board, private hands, and tree
+    /// structure.
+    ///
+    /// The private hands are weighted by their initial weights multiplied by the reach
+    /// probabilities, averaged over the two games. The reach probability of a node is computed
+    /// from these weights, ignoring the card removal effect between the players. Returns an error
+    /// if the boards, the private hands, or the tree structures do not match.
+    ///
+    /// Panics if either game is not solved.
+    ///
+    /// **Time complexity:** *O*(#(nodes) * #(private hands)).
+    pub fn strategy_distance(
+        &self,
+        other: &PostFlopGame,
+    ) -> Result<StrategyDistance, PostFlopError> {
+        if self.state != State::Solved || other.state != State::Solved {
+            panic!("Game is not solved");
+        }
+
+        let (config1, config2) = (&self.card_config, &other.card_config);
+        if (config1.flop, config1.turn, config1.river)
+            != (config2.flop, config2.turn, config2.river)
+        {
+            return Err(PostFlopError::InvalidArgument(
+                "Boards do not match".to_string(),
+            ));
+        }
+
+        if self.private_cards != other.private_cards {
+            return Err(PostFlopError::InvalidArgument(
+                "Private hands do not match".to_string(),
+            ));
+        }
+
+        if self.storage_mode != other.storage_mode {
+            return Err(PostFlopError::InvalidArgument(
+                "Storage modes do not match".to_string(),
+            ));
+        }
+
+        let mut board_mask: u64 = 0;
+        for card in [config1.flop[0], config1.flop[1], config1.flop[2]] {
+            board_mask |= 1 << card;
+        }
+        for card in [config1.turn, config1.river] {
+            if card != NOT_DEALT {
+                board_mask |= 1 << card;
+            }
+        }
+
+        let reach = [0, 1].map(|player| {
+            self.private_cards[player]
+                .iter()
+                .map(|&(c1, c2)| {
+                    let mask: u64 = (1 << c1) | (1 << c2);
+                    if mask & board_mask == 0 {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                })
+                .collect::<Vec<_>>()
+        });
+
+        let mut state = DistanceState {
+            history: Vec::new(),
+            total_bet_amount: [0, 0],
+            chance_probability: 1.0,
+            reach: [reach.clone(), reach],
+        };
+
+        let root_mass = [0, 1].map(|player| self.reach_mass(other, &state.reach, player));
+
+        let mut nodes = Vec::new();
+        self.strategy_distance_recursive(
+            other,
+            &self.root(),
+            &other.root(),
+            &mut state,
+            &root_mass,
+            &mut nodes,
+        )?;
+
+        let mean_l1 = nodes.iter().map(|n| n.l1 as f64).sum::<f64>() / nodes.len().max(1) as f64;
+
+        let (mut weighted_sum, mut weight_sum) = (0.0, 0.0);
+        for node in &nodes {
+            let weight = node.reach as f64 * node.pot as f64;
+            weighted_sum += weight * node.l1 as f64;
+            weight_sum += weight;
+        }
+
+        Ok(StrategyDistance {
+            nodes,
+            mean_l1: mean_l1 as f32,
+            weighted_l1: if weight_sum > 0.0 {
+                (weighted_sum / weight_sum) as f32
+            } else {
+                0.0
+            },
+        })
+    }
+
+    /// Recursive function to compare the strategies.
+    fn strategy_distance_recursive(
+        &self,
+        other: &PostFlopGame,
+        node1: &PostFlopNode,
+        node2: &PostFlopNode,
+        state: &mut DistanceState,
+        root_mass: &[f64; 2],
+        nodes: &mut Vec<NodeDistance>,
+    ) -> Result<(), PostFlopError> {
+        let structure_error = |history: &[usize]| {
+            Err(PostFlopError::InvalidArgument(format!(
+                "Tree structures do not match: history = {history:?}"
+            )))
+        };
+
+        if node1.player != node2.player
+            || node1.amount != node2.amount
+            || node1.num_actions() != node2.num_actions()
+        {
+            return structure_error(&state.history);
+        }
+
+        if node1.is_terminal() || node1.amount == self.tree_config.effective_stack {
+            return Ok(());
+        }
+
+        let actions = node1
+            .children()
+            .iter()
+            .zip(node2.children())
+            .map(|(child1, child2)| {
+                let action = child1.lock().prev_action;
+                (action == child2.lock().prev_action).then_some(action)
+            })
+            .collect::<Option<Vec<_>>>();
+
+        let Some(actions) = actions else {
+            return structure_error(&state.history);
+        };
+
+        // chance node
+        if node1.is_chance() {
+            let is_turn = node1.turn == NOT_DEALT;
+            if self.storage_mode == BoardState::Flop
+                || (!is_turn && self.storage_mode == BoardState::Turn)
+            {
+                return Ok(());
+            }
+
+            let isomorphic_chances = self.isomorphic_chances(node1);
+            if isomorphic_chances != other.isomorphic_chances(node2) {
+                return structure_error(&state.history);
+            }
+
+            let num_cards = actions.len() + isomorphic_chances.len();
+            let saved_probability = state.chance_probability;
+
+            for (index, &action) in actions.iter().enumerate() {
+                let card = match action {
+                    Action::Chance(card) => card,
+                    _ => unreachable!(),
+                };
+
+                let num_isomorphic = isomorphic_chances
+                    .iter()
+                    .filter(|&&i| i as usize == index)
+                    .count();
+
+                let saved_reach = state.reach.clone();
+                for reach in state.reach.iter_mut() {
+                    for player in 0..2 {
+                        let cards = &self.private_cards[player];
+                        for (r, &(c1, c2)) in reach[player].iter_mut().zip(cards) {
+                            if c1 == card || c2 == card {
+                                *r = 0.0;
+                            }
+                        }
+                    }
+                }
+
+                state.chance_probability =
+                    saved_probability * (1 + num_isomorphic) as f64 / num_cards as f64;
+                state.history.push(card as usize);
+                self.strategy_distance_recursive(
+                    other,
+                    &node1.play(index),
+                    &node2.play(index),
+                    state,
+                    root_mass,
+                    nodes,
+                )?;
+                state.history.pop();
+                state.reach = saved_reach;
+            }
+
+            state.chance_probability = saved_probability;
+            return Ok(());
+        }
+
+        // player node
+        let player = node1.player();
+        let num_hands = self.num_private_hands(player);
+        let strategy1 = self.node_strategy(node1);
+        let strategy2 = other.node_strategy(node2);
+
+        let (mut weighted_sum, mut weight_sum) = (0.0, 0.0);
+        for hand in 0..num_hands {
+            let weight = self.hand_weight(other, &state.reach, player, hand);
+            if weight > 0.0 {
+                let l1 = (0..actions.len())
+                    .map(|action| {
+                        let index = action * num_hands + hand;
+                        (strategy1[index] - strategy2[index]).abs() as f64
+                    })
+                    .sum::<f64>();
+                weighted_sum += weight * l1;
+                weight_sum += weight;
+            }
+        }
+
+        let mut reach = state.chance_probability;
+        for p in 0..2 {
+            if root_mass[p] > 0.0 {
+                reach *= self.reach_mass(other, &state.reach, p) / root_mass[p];
+            }
+        }
+
+        let [bet_oop, bet_ip] = state.total_bet_amount;
+        nodes.push(NodeDistance {
+            history: state.history.clone(),
+            player,
+            pot: self.tree_config.starting_pot + bet_oop + bet_ip,
+            reach: reach as f32,
+            l1: if weight_sum > 0.0 {
+                (weighted_sum / weight_sum) as f32
+            } else {
+                0.0
+            },
+        });
+
+        for (index, &action) in actions.iter().enumerate() {
+            let saved_reach = [
+                state.reach[0][player].clone(),
+                state.reach[1][player].clone(),
+            ];
+            let saved_bet_amount = state.total_bet_amount;
+
+            if actions.len() > 1 {
+                mul_slice(
+                    &mut state.reach[0][player],
+                    row(&strategy1, index, num_hands),
+                );
+                mul_slice(
+                    &mut state.reach[1][player],
+                    row(&strategy2, index, num_hands),
+                );
+            }
+            update_bet_amount(
+                &mut state.total_bet_amount,
+                player,
+                node1.prev_action,
+                action,
+            );
+
+            state.history.push(index);
+            self.strategy_distance_recursive(
+                other,
+                &node1.play(index),
+                &node2.play(index),
+                state,
+                root_mass,
+                nodes,
+            )?;
+            state.history.pop();
+
+            let [reach1, reach2] = saved_reach;
+            state.reach[0][player] = reach1;
+            state.reach[1][player] = reach2;
+            state.total_bet_amount = saved_bet_amount;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the weight of the given private hand, averaged over the two games.
+    #[inline]
+    fn hand_weight(
+        &self,
+        other: &PostFlopGame,
+        reach: &[[Vec<f32>; 2]; 2],
+        player: usize,
+        hand: usize,
+    ) -> f64 {
+        let weight1 = self.initial_weights[player][hand] * reach[0][player][hand];
+        let weight2 = other.initial_weights[player][hand] * reach[1][player][hand];
+        (weight1 as f64 + weight2 as f64) * 0.5
+    }
+
+    /// Returns the sum of the weights of the given player, averaged over the two games.
+    fn reach_mass(&self, other: &PostFlopGame, reach: &[[Vec<f32>; 2]; 2], player: usize) -> f64 {
+        (0..self.num_private_hands(player))
+            .map(|hand| self.hand_weight(other, reach, player, hand))
+            .sum()
+    }
+}
@@ -12,18 +12,48 @@ fn min(x: f64, y: f64) -> f64 {
 }
 
 impl PostFlopGame {
+    /// Returns the payoffs of the terminal node when the player wins, ties, and loses, or their
+    /// squares if `squared` is `true`.
+    #[inline]
+    pub(super) fn terminal_payoffs(&self, node: &PostFlopNode, squared: bool) -> [f64; 3] {
+        let pot = (self.tree_config.starting_pot + 2 * node.amount) as f64;
+        let half_pot = 0.5 * pot;
+        let rake = min(pot * self.tree_config.rake_rate, self.tree_config.rake_cap);
+        let payoffs = [half_pot - rake, -0.5 * rake, -half_pot];
+        if squared {
+            payoffs.map(|x| x * x)
+        } else {
+            payoffs
+        }
+    }
+
+    /// Evaluates the terminal node, or the second moment of its payoffs if `squared` is `true`.
+    pub(super) fn evaluate_moment(
+        &self,
+        result: &mut [MaybeUninit<f32>],
+        node: &PostFlopNode,
+        player: usize,
+        cfreach: &[f32],
+        squared: bool,
+    ) {
+        if self.bunching_num_dead_cards == 0 {
+            self.evaluate_internal(result, node, player, cfreach, squared);
+        } else {
+            self.evaluate_internal_bunching(result, node, player, cfreach, squared);
+        }
+    }
+
     pub(super) fn evaluate_internal(
         &self,
         result: &mut [MaybeUninit<f32>],
         node: &PostFlopNode,
         player: usize,
         cfreach: &[f32],
+        squared: bool,
     ) {
-        let pot = (self.tree_config.starting_pot + 2 * node.amount) as f64;
-        let half_pot = 0.5 * pot;
-        let rake = min(pot * self.tree_config.rake_rate, self.tree_config.rake_cap);
-        let amount_win = (half_pot - rake) / self.num_combinations;
-        let amount_lose = -half_pot / self.num_combinations;
+        let [amount_win, amount_tie, amount_lose] = self
+            .terminal_payoffs(node, squared)
+            .map(|x| x / self.num_combinations);
 
         let player_cards = &self.private_cards[player];
         let opponent_cards = &self.private_cards[player ^ 1];
@@ -92,7 +122,7 @@ impl PostFlopGame {
             }
         }
         // showdown (optimized for no rake; 2-pass)
-        else if rake == 0.0 {
+        else if amount_tie == 0.0 {
             let pair_index = card_pair_to_index(node.turn, node.river);
             let hand_strength = &self.hand_strength[pair_index];
             let player_strength = &hand_strength[player];
@@ -151,7 +181,6 @@ impl PostFlopGame {
         }
         // showdown (raked; 3-pass)
         else {
-            let amount_tie = -0.5 * rake / self.num_combinations;
             let same_hand_index = &self.same_hand_index[player];
 
             let pair_index = card_pair_to_index(node.turn, node.river);
@@ -258,13 +287,11 @@ impl PostFlopGame {
         node: &PostFlopNode,
         player: usize,
         cfreach: &[f32],
+        squared: bool,
     ) {
-        let pot = (self.tree_config.starting_pot + 2 * node.amount) as f64;
-        let half_pot = 0.5 * pot;
-        let rake = min(pot * self.tree_config.rake_rate, self.tree_config.rake_cap);
-        let amount_win = ((half_pot - rake) / self.bunching_num_combinations) as f32;
-        let amount_lose = (-half_pot / self.bunching_num_combinations) as f32;
-        let amount_tie = (-0.5 * rake / self.bunching_num_combinations) as f32;
+        let [amount_win, amount_tie, amount_lose] = self
+            .terminal_payoffs(node, squared)
+            .map(|x| (x / self.bunching_num_combinations) as f32);
         let opponent_len = self.private_cards[player ^ 1].len();
 
         // someone folded
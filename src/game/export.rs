@@ -0,0 +1,544 @@
+use super::line::action_to_token;
+use super::*;
+use crate::range::*;
+use std::io::Write;
+
+/// Options of the exporters of the solved tree (see [`PostFlopGame::export_json`] and
+/// [`PostFlopGame::export_csv`]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportOptions {
+    /// Streets to export. Nodes of the other streets are not exported, but their descendants are
+    /// still visited.
+    pub streets: Vec<BoardState>,
+
+    /// Lines to export, in the format of [`PostFlopGame::apply_line_str`]. If not empty, only the
+    /// nodes whose line starts with one of the lines are exported. Dealt cards must be specified
+    /// by the representative cards of [`PostFlopGame::available_actions`].
+    pub lines: Vec<String>,
+
+    /// Number of digits after the decimal point of the floating-point values.
+    pub precision: usize,
+
+    /// Whether to export the expected values.
+    pub expected_values: bool,
+
+    /// Whether to export the equities. Computing the equities on the flop is expensive.
+    pub equity: bool,
+}
+
+impl Default for ExportOptions {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            streets: vec![BoardState::Flop, BoardState::Turn, BoardState::River],
+            lines: Vec::new(),
+            precision: 4,
+            expected_values: true,
+            equity: false,
+        }
+    }
+}
+
+/// Data of a decision node passed to the writer of an exporter.
+pub(super) struct ExportedNode<'a> {
+    pub(super) line: &'a [Action],
+    pub(super) board: &'a [Card],
+    pub(super) player: usize,
+    pub(super) pot: i32,
+    pub(super) actions: &'a [Action],
+    pub(super) weights: &'a [f32],
+    pub(super) strategy: &'a [f32],
+    pub(super) expected_values: Option<&'a [f32]>,
+    pub(super) equity: Option<&'a [f32]>,
+}
+
+/// Row of the tabular exporters.
+pub(super) struct TableRow<'a> {
+    pub(super) line: &'a str,
+    pub(super) board: &'a str,
+    pub(super) player: usize,
+    pub(super) hand: &'a str,
+    pub(super) weight: f32,
+    pub(super) action: &'a str,
+    pub(super) frequency: f32,
+    pub(super) expected_value: Option<f32>,
+    pub(super) equity: Option<f32>,
+}
+
+impl<'a> ExportedNode<'a> {
+    /// Passes the rows of the node to the given function. Hands with zero weight are skipped.
+    pub(super) fn for_each_row<F: FnMut(&TableRow) -> Result<(), PostFlopError>>(
+        &self,
+        hands: &[Vec<String>; 2],
+        mut f: F,
+    ) -> Result<(), PostFlopError> {
+        let line = self
+            .line
+            .iter()
+            .map(|&a| action_to_token(a))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let board = self
+            .board
+            .iter()
+            .map(|&card| card_to_string(card).unwrap())
+            .collect::<String>();
+        let actions = self
+            .actions
+            .iter()
+            .map(|&a| action_to_token(a))
+            .collect::<Vec<_>>();
+        let num_hands = self.weights.len();
+
+        for (hand, &weight) in self.weights.iter().enumerate() {
+            if weight == 0.0 {
+                continue;
+            }
+
+            for (action, action_token) in actions.iter().enumerate() {
+                let index = action * num_hands + hand;
+                f(&TableRow {
+                    line: &line,
+                    board: &board,
+                    player: self.player,
+                    hand: &hands[self.player][hand],
+                    weight,
+                    action: action_token,
+                    frequency: self.strategy[index],
+                    expected_value: self.expected_values.map(|ev| ev[index]),
+                    equity: self.equity.map(|equity| equity[hand]),
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl PostFlopGame {
+    /// Writes the solved tree in JSON format into the given writer.
+    ///
+    /// The nodes are written one by one as they are visited, so the whole output is never held in
+    /// memory. The output has the following structure:
+    ///
+    /// ```text
+    /// {
+    ///   "board": ["Td", "9d", "6h"],            // board cards of the root node
+    ///   "starting_pot": 100,
+    ///   "effective_stack": 400,
+    ///   "private_cards": [["AsAh", ...], [...]], // private hands of OOP and IP
+    ///   "nodes": [
+    ///     {
+    ///       "line": ["X", "B50", "Qc"],         // actions from the root node
+    ///       "board": ["Td", "9d", "6h", "Qc"],
+    ///       "player": 0,                        // 0 = OOP, 1 = IP
+    ///       "pot": 200,
+    ///       "actions": ["X", "B100", "A350"],
+    ///       "weights": [...],                   // normalized weights of the current player
+    ///       "strategy": [[...], ...],           // per action, per private hand
+    ///       "expected_values": [[...], ...],    // per action, per private hand (optional)
+    ///       "equity": [...]                     // per private hand (optional)
+    ///     },
+    ///     ...
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// The actions and the lines use the tokens of [`apply_line_str`]. At chance nodes, isomorphic
+    /// cards are grouped into one representative card, as in [`available_actions`]. Streets whose
+    /// strategies are not stored are not exported. The current node is not changed.
+    ///
+    /// Returns an error if a line of the options is invalid or writing fails. Panics if the game is
+    /// not solved.
+    ///
+    /// [`apply_line_str`]: #method.apply_line_str
+    /// [`available_actions`]: #method.available_actions
+    pub fn export_json<W: Write>(
+        &mut self,
+        writer: &mut W,
+        options: &ExportOptions,
+    ) -> Result<(), PostFlopError> {
+        let write_error =
+            |e: std::io::Error| PostFlopError::Io(format!("Failed to write JSON: {e}"));
+        let precision = options.precision;
+
+        let board = self.current_root_board();
+        let private_cards = [0, 1].map(|player| {
+            let hands = holes_to_strings(&self.private_cards[player]).unwrap();
+            json_strings(&hands)
+        });
+
+        write!(
+            writer,
+            "{{\"board\":{},\"starting_pot\":{},\"effective_stack\":{},\
+             \"private_cards\":[{},{}],\"nodes\":[",
+            json_cards(&board),
+            self.tree_config.starting_pot,
+            self.tree_config.effective_stack,
+            private_cards[0],
+            private_cards[1],
+        )
+        .map_err(write_error)?;
+
+        let mut is_first = true;
+        self.export_nodes(options, |node| {
+            if !is_first {
+                writer.write_all(b",").map_err(write_error)?;
+            }
+            is_first = false;
+
+            let node = json_node(node, precision);
+            writer.write_all(node.as_bytes()).map_err(write_error)
+        })?;
+
+        writer.write_all(b"]}").map_err(write_error)?;
+        writer.flush().map_err(write_error)
+    }
+
+    /// Returns the current node in JSON format.
+    ///
+    /// At a decision node, the output is the same as an element of `"nodes"` of [`export_json`],
+    /// except that the line contains the actual dealt cards (see [`history_actions`]). At a chance
+    /// node and a terminal node, only `"line"`, `"board"`, `"pot"`, and `"actions"` are written,
+    /// and `"player"` is `null`; `"actions"` is empty at a terminal node. The `streets` and
+    /// `lines` of the options are ignored.
+    ///
+    /// Panics if the game is not solved.
+    ///
+    /// [`export_json`]: #method.export_json
+    /// [`history_actions`]: #method.history_actions
+    pub fn export_node_json(&mut self, options: &ExportOptions) -> String {
+        if self.state != State::Solved {
+            panic!("Game is not solved");
+        }
+
+        let line = self.history_actions();
+        let board = self.current_board();
+        let total_bet_amount = self.total_bet_amount();
+        let pot = self.tree_config.starting_pot + total_bet_amount[0] + total_bet_amount[1];
+
+        if self.is_terminal_node() || self.is_chance_node() {
+            let actions = match self.is_terminal_node() {
+                true => Vec::new(),
+                false => self.available_actions(),
+            };
+            let line = line.iter().map(|&a| action_to_token(a)).collect::<Vec<_>>();
+            let actions = actions
+                .iter()
+                .map(|&a| action_to_token(a))
+                .collect::<Vec<_>>();
+            return format!(
+                "{{\"line\":{},\"board\":{},\"player\":null,\"pot\":{pot},\"actions\":{}}}",
+                json_strings(&line),
+                json_cards(&board),
+                json_strings(&actions),
+            );
+        }
+
+        self.cache_normalized_weights();
+        let player = self.current_player();
+        let actions = self.available_actions();
+        let strategy = self.strategy();
+        let expected_values = options
+            .expected_values
+            .then(|| self.expected_values_detail(player));
+        let equity = options.equity.then(|| self.equity(player));
+
+        let node = ExportedNode {
+            line: &line,
+            board: &board,
+            player,
+            pot,
+            actions: &actions,
+            weights: self.normalized_weights(player),
+            strategy: &strategy,
+            expected_values: expected_values.as_deref(),
+            equity: equity.as_deref(),
+        };
+
+        json_node(&node, options.precision)
+    }
+
+    /// Writes the solved tree in CSV format into the given writer.
+    ///
+    /// Each row corresponds to a pair of a private hand of the current player and an action at a
+    /// decision node, with the following columns:
+    ///
+    /// - `line`: actions from the root node, separated by spaces (see [`apply_line_str`]).
+    /// - `board`: board cards at the node (e.g., `Td9d6hQc`).
+    /// - `player`: current player (`0` = OOP, `1` = IP).
+    /// - `hand`: private hand of the current player (e.g., `AsAh`).
+    /// - `weight`: normalized weight of the hand.
+    /// - `action`: action token.
+    /// - `frequency`: probability of taking the action with the hand.
+    /// - `ev`: expected value of the action with the hand (empty if not exported).
+    /// - `equity`: equity of the hand (empty if not exported).
+    ///
+    /// Hands with zero normalized weight are omitted. The nodes are selected in the same way as
+    /// [`export_json`]. The current node is not changed.
+    ///
+    /// Returns an error if a line of the options is invalid or writing fails. Panics if the game is
+    /// not solved.
+    ///
+    /// [`apply_line_str`]: #method.apply_line_str
+    /// [`export_json`]: #method.export_json
+    pub fn export_csv<W: Write>(
+        &mut self,
+        writer: &mut W,
+        options: &ExportOptions,
+    ) -> Result<(), PostFlopError> {
+        let write_error =
+            |e: std::io::Error| PostFlopError::Io(format!("Failed to write CSV: {e}"));
+        let precision = options.precision;
+
+        writeln!(
+            writer,
+            "line,board,player,hand,weight,action,frequency,ev,equity"
+        )
+        .map_err(write_error)?;
+
+        let hands = [0, 1].map(|player| holes_to_strings(&self.private_cards[player]).unwrap());
+
+        self.export_nodes(options, |node| {
+            node.for_each_row(&hands, |row| {
+                let ev = row
+                    .expected_value
+                    .map_or(String::new(), |ev| format!("{ev:.precision$}"));
+                let equity = row
+                    .equity
+                    .map_or(String::new(), |equity| format!("{equity:.precision$}"));
+                writeln!(
+                    writer,
+                    "{},{},{},{},{:.precision$},{},{:.precision$},{ev},{equity}",
+                    row.line,
+                    row.board,
+                    row.player,
+                    row.hand,
+                    row.weight,
+                    row.action,
+                    row.frequency,
+                )
+                .map_err(write_error)
+            })
+        })?;
+
+        writer.flush().map_err(write_error)
+    }
+
+    /// Visits the decision nodes selected by the options in depth-first order and passes them to
+    /// the given writer. The current node is not changed.
+    pub(super) fn export_nodes<F: FnMut(&ExportedNode) -> Result<(), PostFlopError>>(
+        &mut self,
+        options: &ExportOptions,
+        mut write_node: F,
+    ) -> Result<(), PostFlopError> {
+        if self.state != State::Solved {
+            panic!("Game is not solved");
+        }
+
+        let saved = self.save_navigation();
+
+        let mut filters = Vec::with_capacity(options.lines.len());
+        for line in &options.lines {
+            if let Err(error) = self.apply_line_str(line) {
+                self.restore_navigation(&saved);
+                return Err(error.into());
+            }
+            filters.push(self.history_actions());
+        }
+
+        self.back_to_root();
+        let mut line = Vec::new();
+        let result = self.export_nodes_recursive(options, &filters, &mut line, &mut write_node);
+        self.restore_navigation(&saved);
+        result
+    }
+
+    /// Recursive function to visit the nodes to export.
+    fn export_nodes_recursive<F: FnMut(&ExportedNode) -> Result<(), PostFlopError>>(
+        &mut self,
+        options: &ExportOptions,
+        filters: &[Vec<Action>],
+        line: &mut Vec<Action>,
+        write_node: &mut F,
+    ) -> Result<(), PostFlopError> {
+        if self.is_terminal_node() {
+            return Ok(());
+        }
+
+        let is_prefix = |a: &[Action], b: &[Action]| a.len() <= b.len() && a == &b[..a.len()];
+        if !filters.is_empty()
+            && !filters
+                .iter()
+                .any(|f| is_prefix(line, f) || is_prefix(f, line))
+        {
+            return Ok(());
+        }
+
+        let actions = self.available_actions();
+
+        // chance node
+        if self.is_chance_node() {
+            let is_turn = self.turn == NOT_DEALT;
+            if self.storage_mode == BoardState::Flop
+                || (!is_turn && self.storage_mode == BoardState::Turn)
+            {
+                return Ok(());
+            }
+
+            for action in actions {
+                let card = match action {
+                    Action::Chance(card) => card,
+                    _ => unreachable!(),
+                };
+                let saved = self.save_navigation();
+                self.play(card as usize);
+                line.push(action);
+                let result = self.export_nodes_recursive(options, filters, line, write_node);
+                line.pop();
+                self.restore_navigation(&saved);
+                result?;
+            }
+
+            return Ok(());
+        }
+
+        // player node
+        let board = self.current_board();
+        let street = match board.len() {
+            3 => BoardState::Flop,
+            4 => BoardState::Turn,
+            _ => BoardState::River,
+        };
+
+        let is_selected = filters.is_empty() || filters.iter().any(|f| is_prefix(f, line));
+        if is_selected && options.streets.contains(&street) {
+            self.cache_normalized_weights();
+            let player = self.current_player();
+            let strategy = self.strategy();
+            let expected_values = options
+                .expected_values
+                .then(|| self.expected_values_detail(player));
+            let equity = options.equity.then(|| self.equity(player));
+            let total_bet_amount = self.total_bet_amount();
+
+            write_node(&ExportedNode {
+                line,
+                board: &board,
+                player,
+                pot: self.tree_config.starting_pot + total_bet_amount[0] + total_bet_amount[1],
+                actions: &actions,
+                weights: self.normalized_weights(player),
+                strategy: &strategy,
+                expected_values: expected_values.as_deref(),
+                equity: equity.as_deref(),
+            })?;
+        }
+
+        for (index, &action) in actions.iter().enumerate() {
+            let saved = self.save_navigation();
+            self.play(index);
+            line.push(action);
+            let result = self.export_nodes_recursive(options, filters, line, write_node);
+            line.pop();
+            self.restore_navigation(&saved);
+            result?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the board cards of the root node.
+    fn current_root_board(&self) -> Vec<Card> {
+        let mut board = self.card_config.flop.to_vec();
+        for card in [self.card_config.turn, self.card_config.river] {
+            if card != NOT_DEALT {
+                board.push(card);
+            }
+        }
+        board
+    }
+}
+
+/// Returns a JSON object of the given decision node.
+fn json_node(node: &ExportedNode, precision: usize) -> String {
+    let tokens = node
+        .line
+        .iter()
+        .map(|&a| action_to_token(a))
+        .collect::<Vec<_>>();
+    let actions = node
+        .actions
+        .iter()
+        .map(|&a| action_to_token(a))
+        .collect::<Vec<_>>();
+    let num_hands = node.weights.len();
+
+    let mut json = format!(
+        "{{\"line\":{},\"board\":{},\"player\":{},\"pot\":{},\"actions\":{},\
+         \"weights\":{},\"strategy\":{}",
+        json_strings(&tokens),
+        json_cards(node.board),
+        node.player,
+        node.pot,
+        json_strings(&actions),
+        json_floats(node.weights, precision),
+        json_matrix(node.strategy, num_hands, precision),
+    );
+
+    if let Some(expected_values) = node.expected_values {
+        let matrix = json_matrix(expected_values, num_hands, precision);
+        json.push_str(&format!(",\"expected_values\":{matrix}"));
+    }
+
+    if let Some(equity) = node.equity {
+        let equity = json_floats(equity, precision);
+        json.push_str(&format!(",\"equity\":{equity}"));
+    }
+
+    json.push('}');
+    json
+}
+
+/// Returns a JSON array of the given strings.
+fn json_strings(strings: &[String]) -> String {
+    let quoted = strings
+        .iter()
+        .map(|s| format!("\"{s}\""))
+        .collect::<Vec<_>>();
+    format!("[{}]", quoted.join(","))
+}
+
+/// Returns a JSON array of the given cards.
+fn json_cards(cards: &[Card]) -> String {
+    let cards = cards
+        .iter()
+        .map(|&card| card_to_string(card).unwrap())
+        .collect::<Vec<_>>();
+    json_strings(&cards)
+}
+
+/// Returns a JSON array of the given floating-point values. Non-finite values are written as
+/// `null`.
+fn json_floats(values: &[f32], precision: usize) -> String {
+    let values = values
+        .iter()
+        .map(|&v| {
+            if v.is_finite() {
+                format!("{v:.precision$}")
+            } else {
+                "null".to_string()
+            }
+        })
+        .collect::<Vec<_>>();
+    format!("[{}]", values.join(","))
+}
+
+/// Returns a JSON array of the rows of the given matrix.
+fn json_matrix(values: &[f32], row_size: usize, precision: usize) -> String {
+    let rows = values
+        .chunks_exact(row_size)
+        .map(|row| json_floats(row, precision))
+        .collect::<Vec<_>>();
+    format!("[{}]", rows.join(","))
+}
@@ -0,0 +1,104 @@
+use super::line::line_tokens;
+use super::*;
+use crate::utility::*;
+
+/// Result of forcing a line, returned by [`PostFlopGame::forced_line_ev`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForcedLineReport {
+    /// Player whose actions are forced (`0` = OOP, `1` = IP).
+    pub player: usize,
+
+    /// Expected value of each private hand under the solved strategy.
+    pub expected_values: Vec<f32>,
+
+    /// Expected value of each private hand when the line is forced.
+    pub forced_expected_values: Vec<f32>,
+
+    /// EV loss of each private hand caused by forcing the line (solved minus forced).
+    pub ev_loss: Vec<f32>,
+
+    /// Average of the EV losses weighted by the normalized weights.
+    pub average_ev_loss: f32,
+}
+
+impl PostFlopGame {
+    /// Forces the current player to take the actions of the given line with every private hand,
+    /// and returns the resulting EV loss of each private hand without re-solving.
+    ///
+    /// The line is parsed from the current node in the same format as [`apply_line_str`] (e.g.,
+    /// `"B33"` forces a bet of 33, and `"X B50 C 5d B"` forces a check-call and a bet on the next
+    /// street). Only the actions of the current player are forced; the actions of the opponent and
+    /// the dealt cards select the path, and the opponent keeps the solved strategy everywhere.
+    /// Isomorphic cards share the forced nodes. The current node is not changed.
+    ///
+    /// Returns an error if the line is invalid or does not contain any action of the current
+    /// player. Panics if the game is not solved, the strategies of all streets are not stored, or
+    /// the current node is a terminal node or a chance node.
+    ///
+    /// **Time complexity:** *O*(#(nodes in the subtree) * #(private hands)).
+    ///
+    /// [`apply_line_str`]: #method.apply_line_str
+    pub fn forced_line_ev(&mut self, line: &str) -> Result<ForcedLineReport, PostFlopError> {
+        if self.state != State::Solved {
+            panic!("Game is not solved");
+        }
+
+        if self.storage_mode != BoardState::River {
+            panic!("Strategies of all streets are not stored");
+        }
+
+        if self.is_terminal_node() {
+            panic!("Terminal node is not allowed");
+        }
+
+        if self.is_chance_node() {
+            panic!("Chance node is not allowed");
+        }
+
+        let player = self.current_player();
+        let saved = self.save_navigation();
+        let mut forced = BTreeMap::new();
+
+        for (position, token) in line_tokens(line).enumerate() {
+            let is_player_node = !self.is_terminal_node()
+                && !self.is_chance_node()
+                && self.current_player() == player;
+            let node_index = self.node_index(&self.node());
+
+            if let Err(error) = self.apply_line_token(position, token) {
+                self.restore_navigation(&saved);
+                return Err(error.into());
+            }
+
+            if is_player_node {
+                forced.insert(node_index, *self.action_history.last().unwrap());
+            }
+        }
+
+        self.restore_navigation(&saved);
+
+        if forced.is_empty() {
+            return Err(PostFlopError::InvalidArgument(format!(
+                "Line does not contain any action of the current player: {line}"
+            )));
+        }
+
+        self.cache_normalized_weights();
+        let expected_values = self.subtree_expected_values(player, &BTreeMap::new());
+        let forced_expected_values = self.subtree_expected_values(player, &forced);
+
+        let ev_loss = expected_values
+            .iter()
+            .zip(&forced_expected_values)
+            .map(|(&ev, &forced_ev)| ev - forced_ev)
+            .collect::<Vec<_>>();
+
+        Ok(ForcedLineReport {
+            player,
+            average_ev_loss: compute_average(&ev_loss, &self.normalized_weights[player]),
+            expected_values,
+            forced_expected_values,
+            ev_loss,
+        })
+    }
+}
@@ -1,13 +1,104 @@
+use super::visitor::update_bet_amount;
 use super::*;
 use crate::interface::*;
+use crate::pool::*;
 use crate::sliceop::*;
 use crate::utility::*;
+use std::mem;
+
+/// A snapshot of the navigation state of the result interpreter.
+#[derive(Clone)]
+pub(super) struct NavigationState {
+    action_history: Vec<usize>,
+    node_history: Vec<usize>,
+    is_normalized_weight_cached: bool,
+    turn: Card,
+    river: Card,
+    turn_swapped_suit: Option<(u8, u8)>,
+    turn_swap: Option<u8>,
+    river_swap: Option<(u8, u8)>,
+    turn_bucket: Option<(Card, Card)>,
+    river_bucket: Option<(Card, Card)>,
+    total_bet_amount: [i32; 2],
+    weights: [Vec<f32>; 2],
+    normalized_weights: [Vec<f32>; 2],
+    cfvalues_cache: [Vec<f32>; 2],
+}
 
-/// Decodes the encoded `i16` slice to the `f32` slice.
-#[inline]
-fn decode_signed_slice(slice: &[i16], scale: f32) -> Vec<f32> {
-    let decoder = scale / i16::MAX as f32;
-    slice.iter().map(|&x| x as f32 * decoder).collect()
+/// A bookmark of a node of a [`PostFlopGame`], obtained by [`PostFlopGame::bookmark`].
+///
+/// The handle also stores the weights of the node, so that [`PostFlopGame::goto`] does not need to
+/// replay the history. A handle is valid only for the game that created it, and it becomes stale
+/// if the strategies of the game are changed (e.g., by solving or locking strategies).
+#[derive(Clone)]
+pub struct NodeHandle {
+    num_nodes: usize,
+    state: NavigationState,
+}
+
+/// Recommended action of a hand, returned by [`PostFlopGame::best_action_for`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandRecommendation {
+    /// Hole cards of the hand.
+    pub hand: (Card, Card),
+
+    /// Index of the action with the highest expected value.
+    pub best_action: usize,
+
+    /// Action with the highest expected value.
+    pub action: Action,
+
+    /// Difference between the expected values of the best and the second best actions. Zero if
+    /// there is only one action.
+    pub ev_margin: f32,
+
+    /// Probability of each action.
+    pub strategy: Vec<f32>,
+
+    /// Expected value of each action.
+    pub expected_values: Vec<f32>,
+}
+
+/// Information of the current node, returned by [`PostFlopGame::node_info`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeInfo {
+    /// Street of the node.
+    pub street: BoardState,
+
+    /// Board cards at the node.
+    pub board: Vec<Card>,
+
+    /// Current player (`0` = OOP, `1` = IP), or `None` if the node is a terminal node or a
+    /// chance node.
+    pub player: Option<usize>,
+
+    /// Pot size at the node, including the starting pot.
+    pub pot: i32,
+
+    /// Remaining stack of each player (OOP, IP).
+    pub stacks: [i32; 2],
+
+    /// Amount the current player needs to call. Zero if there is no bet to face or the node is a
+    /// terminal node or a chance node.
+    pub amount_to_call: i32,
+
+    /// Action leading to the node. Dealt cards are represented as [`Action::Chance`] with the
+    /// actual card, and the root node has [`Action::None`].
+    pub previous_action: Action,
+
+    /// Whether the node is a terminal node (see [`PostFlopGame::is_terminal_node`]).
+    pub is_terminal: bool,
+
+    /// Whether the node is a chance node (see [`PostFlopGame::is_chance_node`]).
+    pub is_chance: bool,
+}
+
+impl NodeHandle {
+    /// Returns the history of the bookmarked node (see [`PostFlopGame::history`]).
+    #[inline]
+    pub fn history(&self) -> &[usize] {
+        &self.state.action_history
+    }
 }
 
 impl PostFlopGame {
@@ -26,6 +117,8 @@ impl PostFlopGame {
         self.turn_swapped_suit = None;
         self.turn_swap = None;
         self.river_swap = None;
+        self.turn_bucket = None;
+        self.river_bucket = None;
         self.total_bet_amount = [0, 0];
 
         self.weights[0].copy_from_slice(&self.initial_weights[0]);
@@ -48,6 +141,96 @@ impl PostFlopGame {
         &self.action_history
     }
 
+    /// Returns the actions leading to the current node from the root node.
+    ///
+    /// Unlike [`history`], the actions are returned as [`Action`] values; dealt cards are
+    /// represented as [`Action::Chance`] with the actual card (not the isomorphic representative).
+    ///
+    /// **Time complexity:** *O*(#(actions in history)).
+    ///
+    /// [`history`]: #method.history
+    pub fn history_actions(&self) -> Vec<Action> {
+        if self.state <= State::Uninitialized {
+            panic!("Game is not successfully initialized");
+        }
+
+        self.action_history
+            .iter()
+            .zip(&self.node_history)
+            .map(
+                |(&action, &node_index)| match self.node_arena[node_index].lock().prev_action {
+                    Action::Chance(_) => Action::Chance(action as Card),
+                    prev_action => prev_action,
+                },
+            )
+            .collect()
+    }
+
+    /// Moves the current node back to its parent node, undoing the last action or dealt card.
+    ///
+    /// Returns the undone action in the same format as [`history_actions`], or `None` if the
+    /// current node is the root node.
+    ///
+    /// **Time complexity:** *O*(#(actions in history) * #(private hands)).
+    ///
+    /// [`history_actions`]: #method.history_actions
+    pub fn undo(&mut self) -> Option<Action> {
+        if self.state <= State::Uninitialized {
+            panic!("Game is not successfully initialized");
+        }
+
+        let action = *self.history_actions().last()?;
+        let mut history = self.action_history.clone();
+        history.pop();
+        self.apply_history(&history);
+        Some(action)
+    }
+
+    /// Returns a handle of the current node, which can be passed to [`goto`] later.
+    ///
+    /// **Time complexity:** *O*(#(OOP private hands) + #(IP private hands)).
+    ///
+    /// [`goto`]: #method.goto
+    pub fn bookmark(&self) -> NodeHandle {
+        if self.state < State::MemoryAllocated {
+            panic!("Memory is not allocated");
+        }
+
+        NodeHandle {
+            num_nodes: self.node_arena.len(),
+            state: self.save_navigation(),
+        }
+    }
+
+    /// Moves the current node to the node of the given handle obtained by [`bookmark`].
+    ///
+    /// The cached normalized weights are also restored if they were cached when the handle was
+    /// created.
+    ///
+    /// Panics if the handle was not created by this game.
+    ///
+    /// **Time complexity:** *O*(#(OOP private hands) + #(IP private hands)).
+    ///
+    /// [`bookmark`]: #method.bookmark
+    pub fn goto(&mut self, handle: &NodeHandle) {
+        if self.state < State::MemoryAllocated {
+            panic!("Memory is not allocated");
+        }
+
+        let state = &handle.state;
+        if handle.num_nodes != self.node_arena.len()
+            || state
+                .node_history
+                .iter()
+                .any(|&i| i >= self.node_arena.len())
+            || (0..2).any(|player| state.weights[player].len() != self.num_private_hands(player))
+        {
+            panic!("Invalid node handle");
+        }
+
+        self.restore_navigation(state);
+    }
+
     /// Applies the given history from the root node.
     ///
     /// This method first calls [`back_to_root`] and then calls [`play`] for each action in the
@@ -283,9 +466,12 @@ impl PostFlopGame {
         // chance node
         if self.is_chance_node() {
             let is_turn = self.turn == NOT_DEALT;
-            if self.storage_mode == BoardState::Flop
-                || (!is_turn && self.storage_mode == BoardState::Turn)
-            {
+            let street = if is_turn {
+                BoardState::Turn
+            } else {
+                BoardState::River
+            };
+            if !self.ensure_street_loaded(street) {
                 panic!("Storage mode is not compatible");
             }
 
@@ -308,6 +494,13 @@ impl PostFlopGame {
                 actual_card
             };
 
+            // exchange the cards if the turn card was bucketed
+            let action_card = match self.turn_bucket {
+                Some((card1, card2)) if action_card == card1 => card2,
+                Some((card1, card2)) if action_card == card2 => card1,
+                _ => action_card,
+            };
+
             let actions = self.available_actions();
             let mut action_index = usize::MAX;
 
@@ -319,8 +512,25 @@ impl PostFlopGame {
                 }
             }
 
+            // find the action index from bucketed chances
+            if action_index == usize::MAX && !self.card_buckets.is_empty() {
+                let (refs, cards) = self.bucket_chances(self.node().turn);
+                let bucket = cards
+                    .iter()
+                    .position(|&(card, _)| card == action_card)
+                    .map(|i| (refs[i] as usize, cards[i]));
+                if let Some((repr_index, cards)) = bucket {
+                    action_index = repr_index;
+                    if is_turn {
+                        self.turn_bucket = Some(cards);
+                    } else {
+                        self.river_bucket = Some(cards);
+                    }
+                }
+            }
+
             // find the action index from isomorphic chances
-            if action_index == usize::MAX {
+            if action_index == usize::MAX && self.card_buckets.is_empty() {
                 let node = self.node();
                 let isomorphism = self.isomorphic_chances(&node);
                 let isomorphic_cards = if node.turn == NOT_DEALT {
@@ -367,6 +577,8 @@ impl PostFlopGame {
 
             // update the weights
             self.assign_zero_weights();
+
+            self.action_history.push(actual_card as usize);
         }
         // player node
         else {
@@ -387,15 +599,24 @@ impl PostFlopGame {
             }
 
             // cache the counterfactual values
-            let node = self.node();
-            let vec = if self.is_compression_enabled {
-                let slice = row(node.cfvalues_compressed(), action, num_hands);
+            if !self.is_cfvalues_trimmed {
+                let node = self.node();
                 let scale = node.cfvalue_scale();
-                decode_signed_slice(slice, scale)
-            } else {
-                row(node.cfvalues(), action, num_hands).to_vec()
-            };
-            self.cfvalues_cache[player].copy_from_slice(&vec);
+                let vec = match self.value_format {
+                    StorageFormat::Float32 => row(node.cfvalues(), action, num_hands).to_vec(),
+                    StorageFormat::Float16 => {
+                        decode_f16_slice(row(node.cfvalues_f16(), action, num_hands))
+                    }
+                    StorageFormat::Int16 => decode_quantized_slice(
+                        row(node.cfvalues_compressed(), action, num_hands),
+                        scale,
+                    ),
+                    StorageFormat::Int8 => {
+                        decode_quantized_slice(row(node.cfvalues_8bit(), action, num_hands), scale)
+                    }
+                };
+                self.cfvalues_cache[player].copy_from_slice(&vec);
+            }
 
             // update the bet amounts
             let node = self.node();
@@ -417,9 +638,9 @@ impl PostFlopGame {
             // update the node
             let node_index = self.node_index(&self.node().play(action));
             self.node_history.push(node_index);
+            self.action_history.push(action);
         }
 
-        self.action_history.push(action);
         self.is_normalized_weight_cached = false;
     }
 
@@ -445,6 +666,18 @@ impl PostFlopGame {
             return;
         }
 
+        let mut normalized_weights = mem::take(&mut self.normalized_weights);
+        self.normalized_weights_into(
+            &mut normalized_weights,
+            [&self.weights[0], &self.weights[1]],
+        );
+        self.normalized_weights = normalized_weights;
+        self.is_normalized_weight_cached = true;
+    }
+
+    /// Computes the normalized weights of the current node for the given weights of each player
+    /// and writes them to `out`.
+    fn normalized_weights_into(&self, out: &mut [Vec<f32>; 2], weights: [&[f32]; 2]) {
         // no bunching
         if self.bunching_num_dead_cards == 0 {
             let mut board_mask: u64 = 0;
@@ -463,7 +696,7 @@ impl PostFlopGame {
                 let weight_sum_minus_player = &mut weight_sum_minus[player];
                 self.private_cards[player]
                     .iter()
-                    .zip(self.weights[player].iter())
+                    .zip(weights[player].iter())
                     .for_each(|(&(c1, c2), &w)| {
                         let mask: u64 = (1 << c1) | (1 << c2);
                         if mask & board_mask == 0 {
@@ -478,45 +711,45 @@ impl PostFlopGame {
             for player in 0..2 {
                 let player_cards = &self.private_cards[player];
                 let same_hand_index = &self.same_hand_index[player];
-                let player_weights = &self.weights[player];
-                let opponent_weights = &self.weights[player ^ 1];
+                let player_weights = weights[player];
+                let opponent_weights = weights[player ^ 1];
                 let opponent_weight_sum = weight_sum[player ^ 1];
                 let opponent_weight_sum_minus = &weight_sum_minus[player ^ 1];
 
-                self.normalized_weights[player]
-                    .iter_mut()
-                    .enumerate()
-                    .for_each(|(i, w)| {
-                        let (c1, c2) = player_cards[i];
-                        let mask: u64 = (1 << c1) | (1 << c2);
-                        if mask & board_mask == 0 {
-                            let same_i = same_hand_index[i];
-                            let opponent_weight_same = if same_i == u16::MAX {
-                                0.0
-                            } else {
-                                opponent_weights[same_i as usize] as f64
-                            };
-                            let opponent_weight = opponent_weight_sum + opponent_weight_same
-                                - opponent_weight_sum_minus[c1 as usize]
-                                - opponent_weight_sum_minus[c2 as usize];
-                            *w = player_weights[i] * opponent_weight as f32;
+                out[player].iter_mut().enumerate().for_each(|(i, w)| {
+                    let (c1, c2) = player_cards[i];
+                    let mask: u64 = (1 << c1) | (1 << c2);
+                    if mask & board_mask == 0 {
+                        let same_i = same_hand_index[i];
+                        let opponent_weight_same = if same_i == u16::MAX {
+                            0.0
                         } else {
-                            *w = 0.0;
-                        }
-                    });
+                            opponent_weights[same_i as usize] as f64
+                        };
+                        let opponent_weight = opponent_weight_sum + opponent_weight_same
+                            - opponent_weight_sum_minus[c1 as usize]
+                            - opponent_weight_sum_minus[c2 as usize];
+                        *w = player_weights[i] * opponent_weight as f32;
+                    } else {
+                        *w = 0.0;
+                    }
+                });
             }
         }
         // bunching
         else {
-            let mut weights_buf = [Vec::new(), Vec::new()];
+            let mut weights_buf = [
+                PooledVec::with_capacity(weights[0].len()),
+                PooledVec::with_capacity(weights[1].len()),
+            ];
             let weights = if self.turn_swap.is_none() && self.river_swap.is_none() {
-                &self.weights
+                weights
             } else {
-                weights_buf[0].extend_from_slice(&self.weights[0]);
-                weights_buf[1].extend_from_slice(&self.weights[1]);
+                weights_buf[0].extend_from_slice(weights[0]);
+                weights_buf[1].extend_from_slice(weights[1]);
                 self.apply_swap(&mut weights_buf[0], 0, true);
                 self.apply_swap(&mut weights_buf[1], 1, true);
-                &weights_buf
+                [&weights_buf[0][..], &weights_buf[1][..]]
             };
 
             for player in 0..2 {
@@ -530,25 +763,22 @@ impl PostFlopGame {
                 };
 
                 let opponent_len = self.num_private_hands(player ^ 1);
-                let mut normalized_weights = indices
-                    .iter()
-                    .zip(weights[player].iter())
-                    .map(|(&index, &w)| {
+                let normalized_weights = &mut out[player];
+                normalized_weights.clear();
+                normalized_weights.extend(indices.iter().zip(weights[player].iter()).map(
+                    |(&index, &w)| {
                         if index != 0 {
                             let slice = &self.bunching_arena[index..index + opponent_len];
-                            w * inner_product(&weights[player ^ 1], slice)
+                            w * inner_product(weights[player ^ 1], slice)
                         } else {
                             0.0
                         }
-                    })
-                    .collect::<Vec<_>>();
+                    },
+                ));
 
-                self.apply_swap(&mut normalized_weights, player, false);
-                self.normalized_weights[player] = normalized_weights;
+                self.apply_swap(normalized_weights, player, false);
             }
         }
-
-        self.is_normalized_weight_cached = true;
     }
 
     /// Returns the weights of each private hand of the given player.
@@ -589,6 +819,55 @@ impl PostFlopGame {
         &self.normalized_weights[player]
     }
 
+    /// Returns the probability that each private hand of the given player takes the actions
+    /// leading to the current node under the computed strategies.
+    ///
+    /// The initial weights of the ranges are not included, i.e., the [`weights`] are the initial
+    /// weights multiplied by the returned values. Hands overlapping with the board have a
+    /// probability of zero. The current node is not changed.
+    ///
+    /// Panics if the memory is not yet allocated.
+    ///
+    /// **Time complexity:** *O*(#(actions in history) * #(private hands)).
+    ///
+    /// [`weights`]: #method.weights
+    pub fn reach_probabilities(&mut self, player: usize) -> Vec<f32> {
+        if self.state < State::MemoryAllocated {
+            panic!("Memory is not allocated");
+        }
+
+        let saved = self.save_navigation();
+        let history = self.action_history.clone();
+        let num_hands = self.num_private_hands(player);
+        let mut ret = vec![1.0; num_hands];
+
+        self.back_to_root();
+        for &action in &history {
+            if !self.is_chance_node()
+                && self.current_player() == player
+                && self.node().num_actions() > 1
+            {
+                let strategy = self.strategy();
+                mul_slice(&mut ret, row(&strategy, action, num_hands));
+            }
+            self.play(action);
+        }
+
+        let mut board_mask: u64 = 0;
+        for card in self.current_board() {
+            board_mask |= 1 << card;
+        }
+        for (r, &(c1, c2)) in ret.iter_mut().zip(&self.private_cards[player]) {
+            let mask: u64 = (1 << c1) | (1 << c2);
+            if mask & board_mask != 0 {
+                *r = 0.0;
+            }
+        }
+
+        self.restore_navigation(&saved);
+        ret
+    }
+
     /// Returns the equity of each private hand of the given player.
     ///
     /// After mutating the current node, you must call the [`cache_normalized_weights`] method
@@ -600,6 +879,18 @@ impl PostFlopGame {
     ///
     /// [`cache_normalized_weights`]: #method.cache_normalized_weights
     pub fn equity(&self, player: usize) -> Vec<f32> {
+        let mut ret = Vec::new();
+        self.equity_into(player, &mut ret);
+        ret
+    }
+
+    /// Writes the equity of each private hand of the given player to `out`.
+    ///
+    /// This is the same as the [`equity`] method except that the buffer of `out` is reused, so
+    /// that repeated queries do not allocate once the buffer has grown to the required size.
+    ///
+    /// [`equity`]: #method.equity
+    pub fn equity_into(&self, player: usize, out: &mut Vec<f32>) {
         if self.state <= State::Uninitialized {
             panic!("Game is not successfully initialized");
         }
@@ -609,9 +900,11 @@ impl PostFlopGame {
         }
 
         let num_hands = self.num_private_hands(player);
+        out.clear();
 
-        let tmp = if self.bunching_num_dead_cards == 0 {
-            let mut tmp = vec![0.0; num_hands];
+        if self.bunching_num_dead_cards == 0 {
+            let mut tmp = PooledVec::with_capacity(num_hands);
+            tmp.resize(num_hands, 0.0);
             if self.river != NOT_DEALT {
                 self.equity_internal(&mut tmp, player, self.turn, self.river, 0.5);
             } else if self.turn != NOT_DEALT {
@@ -627,24 +920,22 @@ impl PostFlopGame {
                     }
                 }
             }
-            tmp.into_iter().map(|v| v as f32).collect()
+            out.extend(tmp.iter().map(|&v| v as f32));
         } else {
-            let mut tmp = self.equity_internal_bunching(player);
-            self.apply_swap(&mut tmp, player, false);
-            tmp
-        };
+            self.equity_internal_bunching(out, player);
+            self.apply_swap(out, player, false);
+        }
 
-        tmp.iter()
+        out.iter_mut()
             .zip(self.weights[player].iter())
             .zip(self.normalized_weights[player].iter())
-            .map(|((&v, &w_raw), &w_normalized)| {
+            .for_each(|((v, &w_raw), &w_normalized)| {
                 if w_normalized > 0.0 {
-                    v * (w_raw / w_normalized) + 0.5
+                    *v = *v * (w_raw / w_normalized) + 0.5;
                 } else {
-                    0.0
+                    *v = 0.0;
                 }
-            })
-            .collect()
+            });
     }
 
     /// Returns the expected values of each private hand of the given player.
@@ -659,6 +950,19 @@ impl PostFlopGame {
     /// [`cache_normalized_weights`]: #method.cache_normalized_weights
     /// [`expected_values_detail`]: #method.expected_values_detail
     pub fn expected_values(&self, player: usize) -> Vec<f32> {
+        let mut ret = Vec::new();
+        self.expected_values_into(player, &mut ret);
+        ret
+    }
+
+    /// Writes the expected values of each private hand of the given player to `out`.
+    ///
+    /// This is the same as the [`expected_values`] method except that the buffer of `out` is
+    /// reused, so that repeated queries do not allocate once the buffer has grown to the required
+    /// size.
+    ///
+    /// [`expected_values`]: #method.expected_values
+    pub fn expected_values_into(&self, player: usize, out: &mut Vec<f32>) {
         if self.state != State::Solved {
             panic!("Game is not solved");
         }
@@ -667,27 +971,28 @@ impl PostFlopGame {
             panic!("Normalized weights are not cached");
         }
 
-        let expected_value_detail = self.expected_values_detail(player);
-
         if self.is_terminal_node() || self.is_chance_node() || self.current_player() != player {
-            return expected_value_detail;
+            self.expected_values_detail_into(player, out);
+            return;
         }
 
         let num_actions = self.node().num_actions();
         let num_hands = self.num_private_hands(player);
-        let strategy = self.strategy();
 
-        let mut ret = Vec::with_capacity(num_hands);
-        for i in 0..num_hands {
+        let mut expected_value_detail = PooledVec::with_capacity(num_actions * num_hands);
+        self.expected_values_detail_into(player, &mut expected_value_detail);
+        let mut strategy = PooledVec::with_capacity(num_actions * num_hands);
+        self.strategy_into(&mut strategy);
+
+        out.clear();
+        out.extend((0..num_hands).map(|i| {
             let mut expected_value = 0.0;
             for j in 0..num_actions {
                 let index = i + j * num_hands;
                 expected_value += expected_value_detail[index] * strategy[index];
             }
-            ret.push(expected_value);
-        }
-
-        ret
+            expected_value
+        }));
     }
 
     /// Returns the expected values of each action of each private hand of the given player.
@@ -699,7 +1004,8 @@ impl PostFlopGame {
     /// Otherwise, this method is the same as the [`expected_values`] method, so the return vector
     /// is the length of `#(private hands)`.
     ///
-    /// Panics if the game is not solved.
+    /// Panics if the game is not solved or the expected values are trimmed by
+    /// [`trim_to_strategy`].
     ///
     /// After mutating the current node, you must call the [`cache_normalized_weights`] method
     /// before calling this method.
@@ -709,12 +1015,31 @@ impl PostFlopGame {
     /// - (otherwise) *O*(#(actions) * #(private hands)).
     ///
     /// [`expected_values`]: #method.expected_value
+    /// [`trim_to_strategy`]: #method.trim_to_strategy
     /// [`cache_normalized_weights`]: #method.cache_normalized_weights
     pub fn expected_values_detail(&self, player: usize) -> Vec<f32> {
+        let mut ret = Vec::new();
+        self.expected_values_detail_into(player, &mut ret);
+        ret
+    }
+
+    /// Writes the expected values of each action of each private hand of the given player to
+    /// `out`.
+    ///
+    /// This is the same as the [`expected_values_detail`] method except that the buffer of `out`
+    /// is reused, so that repeated queries do not allocate once the buffer has grown to the
+    /// required size.
+    ///
+    /// [`expected_values_detail`]: #method.expected_values_detail
+    pub fn expected_values_detail_into(&self, player: usize, out: &mut Vec<f32>) {
         if self.state != State::Solved {
             panic!("Game is not solved");
         }
 
+        if self.is_cfvalues_trimmed {
+            panic!("Expected values are trimmed");
+        }
+
         if !self.is_normalized_weight_cached {
             panic!("Normalized weights are not cached");
         }
@@ -722,64 +1047,65 @@ impl PostFlopGame {
         let node = self.node();
         let num_hands = self.num_private_hands(player);
 
-        let mut chance_factor = 1;
-        if self.card_config.turn == NOT_DEALT && self.turn != NOT_DEALT {
-            chance_factor *= 45 - self.bunching_num_dead_cards;
-        }
-        if self.card_config.river == NOT_DEALT && self.river != NOT_DEALT {
-            chance_factor *= 44 - self.bunching_num_dead_cards;
-        }
-
-        let num_combinations = match self.bunching_num_dead_cards {
-            0 => self.num_combinations,
-            _ => self.bunching_num_combinations,
-        };
-
         let mut have_actions = false;
-        let mut normalizer = (num_combinations * chance_factor as f64) as f32;
+        let mut normalizer = self.expected_value_normalizer(true);
+        out.clear();
 
-        let mut ret = if node.is_terminal() {
-            normalizer = num_combinations as f32;
-            let mut ret = Vec::with_capacity(num_hands);
-            let mut cfreach = self.weights[player ^ 1].clone();
+        if node.is_terminal() {
+            normalizer = self.expected_value_normalizer(false);
+            let mut cfreach = PooledVec::with_capacity(num_hands);
+            cfreach.extend_from_slice(&self.weights[player ^ 1]);
             self.apply_swap(&mut cfreach, player ^ 1, true);
-            self.evaluate(ret.spare_capacity_mut(), &node, player, &cfreach);
-            unsafe { ret.set_len(num_hands) };
-            ret
+            out.reserve(num_hands);
+            self.evaluate(
+                &mut out.spare_capacity_mut()[..num_hands],
+                &node,
+                player,
+                &cfreach,
+            );
+            unsafe { out.set_len(num_hands) };
         } else if node.is_chance() && node.cfvalue_storage_player() == Some(player) {
-            if self.is_compression_enabled {
-                let slice = node.cfvalues_chance_compressed();
-                let scale = node.cfvalue_chance_scale();
-                decode_signed_slice(slice, scale)
-            } else {
-                node.cfvalues_chance().to_vec()
+            let scale = node.cfvalue_chance_scale();
+            match self.value_format {
+                StorageFormat::Float32 => out.extend_from_slice(node.cfvalues_chance()),
+                StorageFormat::Float16 => decode_f16_into(out, node.cfvalues_chance_f16()),
+                StorageFormat::Int16 => {
+                    decode_quantized_into(out, node.cfvalues_chance_compressed(), scale)
+                }
+                StorageFormat::Int8 => {
+                    decode_quantized_into(out, node.cfvalues_chance_8bit(), scale)
+                }
             }
         } else if node.has_cfvalues_ip() && player == PLAYER_IP as usize {
-            if self.is_compression_enabled {
-                let slice = node.cfvalues_ip_compressed();
-                let scale = node.cfvalue_ip_scale();
-                decode_signed_slice(slice, scale)
-            } else {
-                node.cfvalues_ip().to_vec()
+            let scale = node.cfvalue_ip_scale();
+            match self.value_format {
+                StorageFormat::Float32 => out.extend_from_slice(node.cfvalues_ip()),
+                StorageFormat::Float16 => decode_f16_into(out, node.cfvalues_ip_f16()),
+                StorageFormat::Int16 => {
+                    decode_quantized_into(out, node.cfvalues_ip_compressed(), scale)
+                }
+                StorageFormat::Int8 => decode_quantized_into(out, node.cfvalues_ip_8bit(), scale),
             }
         } else if player == self.current_player() {
             have_actions = true;
-            if self.is_compression_enabled {
-                let slice = node.cfvalues_compressed();
-                let scale = node.cfvalue_scale();
-                decode_signed_slice(slice, scale)
-            } else {
-                node.cfvalues().to_vec()
+            let scale = node.cfvalue_scale();
+            match self.value_format {
+                StorageFormat::Float32 => out.extend_from_slice(node.cfvalues()),
+                StorageFormat::Float16 => decode_f16_into(out, node.cfvalues_f16()),
+                StorageFormat::Int16 => {
+                    decode_quantized_into(out, node.cfvalues_compressed(), scale)
+                }
+                StorageFormat::Int8 => decode_quantized_into(out, node.cfvalues_8bit(), scale),
             }
         } else {
-            self.cfvalues_cache[player].to_vec()
-        };
+            out.extend_from_slice(&self.cfvalues_cache[player]);
+        }
 
         let starting_pot = self.tree_config.starting_pot;
         let total_bet_amount = self.total_bet_amount();
         let bias = (total_bet_amount[player] - total_bet_amount[player ^ 1]).max(0);
 
-        ret.chunks_exact_mut(num_hands)
+        out.chunks_exact_mut(num_hands)
             .enumerate()
             .for_each(|(action, row)| {
                 let is_fold = have_actions && self.node().play(action).prev_action == Action::Fold;
@@ -796,10 +1122,371 @@ impl PostFlopGame {
                         }
                     });
             });
+    }
+
+    /// Returns the expected values of each private hand of the given player after each action of
+    /// the current node.
+    ///
+    /// The return value is a vector of the length of `#(actions) * #(private hands)`. The expected
+    /// value of the `j`-th private hand after the `i`-th action is stored in the
+    /// `i * #(private hands) + j`-th element.
+    ///
+    /// If the given player is the current player, this method is the same as the
+    /// [`expected_values_detail`] method. Otherwise, the expected values conditioned on reaching
+    /// each child node are computed from the stored counterfactual values of the child nodes in a
+    /// single pass, without moving the current node. The normalized weights of the current node
+    /// are cached.
+    ///
+    /// Panics if the game is not solved, the expected values are trimmed, or the current node is a
+    /// terminal node or a chance node.
+    ///
+    /// **Time complexity:**
+    /// - (no bunching) *O*(#(actions) * (#(OOP private hands) + #(IP private hands))).
+    /// - (bunching) *O*(#(actions) * #(OOP private hands) * #(IP private hands)).
+    ///
+    /// [`expected_values_detail`]: #method.expected_values_detail
+    pub fn action_evs(&mut self, player: usize) -> Vec<f32> {
+        if self.state != State::Solved {
+            panic!("Game is not solved");
+        }
+
+        if self.is_cfvalues_trimmed {
+            panic!("Expected values are trimmed");
+        }
+
+        if self.is_terminal_node() {
+            panic!("Terminal node is not allowed");
+        }
+
+        if self.is_chance_node() {
+            panic!("Chance node is not allowed");
+        }
+
+        self.cache_normalized_weights();
+
+        if player == self.current_player() {
+            return self.expected_values_detail(player);
+        }
+
+        let node = self.node();
+        let opponent = player ^ 1;
+        let num_actions = node.num_actions();
+        let num_hands = self.num_private_hands(player);
+        let num_opponent_hands = self.num_private_hands(opponent);
+        let strategy = self.strategy();
+        let starting_pot = self.tree_config.starting_pot;
+
+        let mut ret = Vec::with_capacity(num_actions * num_hands);
+        let mut normalized_weights = [0, 1].map(|p| vec![0.0; self.num_private_hands(p)]);
+        let mut opponent_weights = vec![0.0; num_opponent_hands];
+        let mut cfvalues = Vec::with_capacity(num_hands);
+        let mut probabilities = vec![1.0; num_hands];
+
+        for action in 0..num_actions {
+            let child = node.play(action);
+
+            // weights after the opponent takes the action
+            opponent_weights.copy_from_slice(&self.weights[opponent]);
+            mul_slice(
+                &mut opponent_weights,
+                row(&strategy, action, num_opponent_hands),
+            );
+            let mut weights = [&self.weights[player][..], &opponent_weights[..]];
+            if player == 1 {
+                weights.swap(0, 1);
+            }
+            self.normalized_weights_into(&mut normalized_weights, weights);
+
+            // counterfactual values of the child node and the probabilities of the actions whose
+            // expected values are not zero, both in the order of the stored private hands
+            let mut normalizer = self.expected_value_normalizer(true);
+            probabilities.fill(1.0);
+            cfvalues.clear();
+
+            if child.is_terminal() {
+                normalizer = self.expected_value_normalizer(false);
+                let mut cfreach = opponent_weights.clone();
+                self.apply_swap(&mut cfreach, opponent, true);
+                self.evaluate(
+                    &mut cfvalues.spare_capacity_mut()[..num_hands],
+                    &child,
+                    player,
+                    &cfreach,
+                );
+                unsafe { cfvalues.set_len(num_hands) };
+            } else if child.is_chance() {
+                let scale = child.cfvalue_chance_scale();
+                match self.value_format {
+                    StorageFormat::Float32 => cfvalues.extend_from_slice(child.cfvalues_chance()),
+                    StorageFormat::Float16 => {
+                        decode_f16_into(&mut cfvalues, child.cfvalues_chance_f16())
+                    }
+                    StorageFormat::Int16 => decode_quantized_into(
+                        &mut cfvalues,
+                        child.cfvalues_chance_compressed(),
+                        scale,
+                    ),
+                    StorageFormat::Int8 => {
+                        decode_quantized_into(&mut cfvalues, child.cfvalues_chance_8bit(), scale)
+                    }
+                }
+            } else {
+                let mut detail = Vec::new();
+                let scale = child.cfvalue_scale();
+                match self.value_format {
+                    StorageFormat::Float32 => detail.extend_from_slice(child.cfvalues()),
+                    StorageFormat::Float16 => decode_f16_into(&mut detail, child.cfvalues_f16()),
+                    StorageFormat::Int16 => {
+                        decode_quantized_into(&mut detail, child.cfvalues_compressed(), scale)
+                    }
+                    StorageFormat::Int8 => {
+                        decode_quantized_into(&mut detail, child.cfvalues_8bit(), scale)
+                    }
+                }
+
+                // the expected value of folding is zero
+                let child_strategy = self.node_strategy(&child);
+                probabilities.fill(0.0);
+                cfvalues.resize(num_hands, 0.0);
+                for child_action in 0..child.num_actions() {
+                    if child.play(child_action).prev_action == Action::Fold {
+                        continue;
+                    }
+                    let strategy_row = row(&child_strategy, child_action, num_hands);
+                    let detail_row = row(&detail, child_action, num_hands);
+                    cfvalues
+                        .iter_mut()
+                        .zip(probabilities.iter_mut())
+                        .zip(strategy_row.iter().zip(detail_row))
+                        .for_each(|((v, p), (&s, &d))| {
+                            *v += s * d;
+                            *p += s;
+                        });
+                }
+            }
+
+            self.apply_swap(&mut cfvalues, player, false);
+            self.apply_swap(&mut probabilities, player, false);
+
+            let mut total_bet_amount = self.total_bet_amount();
+            update_bet_amount(
+                &mut total_bet_amount,
+                opponent,
+                node.prev_action,
+                child.prev_action,
+            );
+            let bias = (total_bet_amount[player] - total_bet_amount[opponent]).max(0);
+            let base = starting_pot as f32 * 0.5 + (child.amount + bias) as f32;
+
+            ret.extend(
+                cfvalues
+                    .iter()
+                    .zip(probabilities.iter())
+                    .zip(self.weights[player].iter())
+                    .zip(normalized_weights[player].iter())
+                    .map(|(((&v, &p), &w_raw), &w_normalized)| {
+                        if w_normalized == 0.0 {
+                            0.0
+                        } else {
+                            v * normalizer * (w_raw / w_normalized) + p * base
+                        }
+                    }),
+            );
+        }
 
         ret
     }
 
+    /// Returns the recommended action of the given hand of the current player.
+    ///
+    /// The `hand` argument must specify a single combination in the range of the current player
+    /// (e.g., "AhKd"; see [`private_card_indices`]). Only the values of the given hand are
+    /// computed, so this method is cheaper than calling [`strategy`] and
+    /// [`expected_values_detail`].
+    ///
+    /// Returns an error if the hand is invalid or does not reach the current node. Panics if the
+    /// game is not solved or the current node is a terminal node or a chance node.
+    ///
+    /// **Time complexity:** *O*(#(actions) + [`cache_normalized_weights`]).
+    ///
+    /// [`private_card_indices`]: #method.private_card_indices
+    /// [`strategy`]: #method.strategy
+    /// [`expected_values_detail`]: #method.expected_values_detail
+    /// [`cache_normalized_weights`]: #method.cache_normalized_weights
+    pub fn best_action_for(&mut self, hand: &str) -> Result<HandRecommendation, PostFlopError> {
+        if self.state != State::Solved {
+            panic!("Game is not solved");
+        }
+
+        if self.is_cfvalues_trimmed {
+            panic!("Expected values are trimmed");
+        }
+
+        if self.is_terminal_node() {
+            panic!("Terminal node is not allowed");
+        }
+
+        if self.is_chance_node() {
+            panic!("Chance node is not allowed");
+        }
+
+        let player = self.current_player();
+        let index = match self.private_card_indices(player, hand)?[..] {
+            [index] => index,
+            _ => {
+                return Err(invalid_hand(
+                    hand,
+                    format!("Hand must be a single combination in the range: {hand}"),
+                ))
+            }
+        };
+
+        self.cache_normalized_weights();
+        let w_raw = self.weights[player][index];
+        let w_normalized = self.normalized_weights[player][index];
+        if w_normalized == 0.0 {
+            return Err(invalid_hand(
+                hand,
+                format!("Hand does not reach the current node: {hand}"),
+            ));
+        }
+
+        let node = self.node();
+        let num_actions = node.num_actions();
+        let num_hands = self.num_private_hands(player);
+        let stored_index = self.stored_hand_index(index, player);
+
+        let strategy = self.hand_strategy(&node, stored_index);
+
+        // expected values
+        let normalizer = self.expected_value_normalizer(true);
+        let bias = (self.total_bet_amount[player] - self.total_bet_amount[player ^ 1]).max(0);
+        let offset = self.tree_config.starting_pot as f32 * 0.5 + (node.amount + bias) as f32;
+        let expected_values = (0..num_actions)
+            .map(|action| {
+                if node.play(action).prev_action == Action::Fold {
+                    return 0.0;
+                }
+                let i = action * num_hands + stored_index;
+                let cfvalue = match self.value_format {
+                    StorageFormat::Float32 => node.cfvalues()[i],
+                    StorageFormat::Float16 => node.cfvalues_f16()[i].to_f32(),
+                    StorageFormat::Int16 => {
+                        let decoder = node.cfvalue_scale() / i16::MAX as f32;
+                        node.cfvalues_compressed()[i] as f32 * decoder
+                    }
+                    StorageFormat::Int8 => {
+                        let decoder = node.cfvalue_scale() / i8::MAX as f32;
+                        node.cfvalues_8bit()[i] as f32 * decoder
+                    }
+                };
+                cfvalue * normalizer * (w_raw / w_normalized) + offset
+            })
+            .collect::<Vec<_>>();
+
+        let mut order = (0..num_actions).collect::<Vec<_>>();
+        order.sort_by(|&i, &j| expected_values[j].total_cmp(&expected_values[i]));
+        let best_action = order[0];
+        let ev_margin = match order.get(1) {
+            Some(&second) => expected_values[best_action] - expected_values[second],
+            None => 0.0,
+        };
+
+        Ok(HandRecommendation {
+            hand: self.private_cards[player][index],
+            best_action,
+            action: node.play(best_action).prev_action,
+            ev_margin,
+            strategy,
+            expected_values,
+        })
+    }
+
+    /// Samples an action of the current player with the given hand from the strategy of the
+    /// current node and returns its index.
+    ///
+    /// The `hand` argument must specify a single combination in the range of the current player
+    /// (e.g., "AhKd"). The `temperature` argument reshapes the strategy by raising each
+    /// probability to the power of `1 / temperature`: `1.0` samples from the strategy as is,
+    /// larger values flatten the mixing, and smaller values sharpen it. If `temperature` is zero
+    /// or negative, the most frequent action is returned (the first one on a tie). Actions with a
+    /// probability of zero are never sampled.
+    ///
+    /// Returns an error if the hand is invalid or overlaps with the board. Panics if the current
+    /// node is a terminal node or a chance node. Also, panics if the memory is not yet allocated.
+    ///
+    /// **Time complexity:** *O*(#(actions)).
+    pub fn sample_action(
+        &self,
+        hand: &str,
+        temperature: f32,
+        rng: &mut Rng,
+    ) -> Result<usize, PostFlopError> {
+        if self.state < State::MemoryAllocated {
+            panic!("Memory is not allocated");
+        }
+
+        if self.is_terminal_node() {
+            panic!("Terminal node is not allowed");
+        }
+
+        if self.is_chance_node() {
+            panic!("Chance node is not allowed");
+        }
+
+        let player = self.current_player();
+        let index = match self.private_card_indices(player, hand)?[..] {
+            [index] => index,
+            _ => {
+                return Err(invalid_hand(
+                    hand,
+                    format!("Hand must be a single combination in the range: {hand}"),
+                ))
+            }
+        };
+
+        let (c1, c2) = self.private_cards[player][index];
+        if self.current_board().iter().any(|&c| c == c1 || c == c2) {
+            return Err(invalid_hand(
+                hand,
+                format!("Hand overlaps with the board: {hand}"),
+            ));
+        }
+
+        let node = self.node();
+        let strategy = self.hand_strategy(&node, self.stored_hand_index(index, player));
+        let max_prob = strategy.iter().fold(0.0f32, |acc, &p| acc.max(p));
+
+        // most frequent action
+        if temperature.is_nan() || temperature <= 0.0 || max_prob == 0.0 {
+            return Ok(strategy.iter().position(|&p| p == max_prob).unwrap());
+        }
+
+        // computes the reshaped weights in the log space to avoid underflow
+        let weights = strategy
+            .iter()
+            .map(|&p| {
+                if p > 0.0 {
+                    ((p.ln() - max_prob.ln()) as f64 / temperature as f64).exp()
+                } else {
+                    0.0
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let r = rng.next_f64() * weights.iter().sum::<f64>();
+        let mut cum_weight = 0.0;
+        let action = weights
+            .iter()
+            .position(|&w| {
+                cum_weight += w;
+                w > 0.0 && r < cum_weight
+            })
+            .unwrap_or_else(|| weights.iter().rposition(|&w| w > 0.0).unwrap());
+
+        Ok(action)
+    }
+
     /// Returns the strategy of the current player.
     ///
     /// The return value is a vector of the length of `#(actions) * #(private hands)`.
@@ -813,6 +1500,18 @@ impl PostFlopGame {
     ///
     /// **Time complexity:** *O*(#(actions) * #(private hands)).
     pub fn strategy(&self) -> Vec<f32> {
+        let mut ret = Vec::new();
+        self.strategy_into(&mut ret);
+        ret
+    }
+
+    /// Writes the strategy of the current player to `out`.
+    ///
+    /// This is the same as the [`strategy`] method except that the buffer of `out` is reused, so
+    /// that repeated queries do not allocate once the buffer has grown to the required size.
+    ///
+    /// [`strategy`]: #method.strategy
+    pub fn strategy_into(&self, out: &mut Vec<f32>) {
         if self.state < State::MemoryAllocated {
             panic!("Memory is not allocated");
         }
@@ -830,20 +1529,85 @@ impl PostFlopGame {
         let num_actions = node.num_actions();
         let num_hands = self.num_private_hands(player);
 
-        let mut ret = if self.is_compression_enabled {
-            normalized_strategy_compressed(node.strategy_compressed(), num_actions)
-        } else {
-            normalized_strategy(node.strategy(), num_actions)
-        };
+        match self.strategy_format {
+            StorageFormat::Float32 => normalized_strategy_into(out, node.strategy(), num_actions),
+            StorageFormat::Float16 => {
+                normalized_strategy_compressed_into(out, node.strategy_f16(), num_actions)
+            }
+            StorageFormat::Int16 => {
+                normalized_strategy_compressed_into(out, node.strategy_compressed(), num_actions)
+            }
+            StorageFormat::Int8 => {
+                normalized_strategy_compressed_into(out, node.strategy_8bit(), num_actions)
+            }
+        }
 
         let locking = self.locking_strategy(&node);
-        apply_locking_strategy(&mut ret, locking);
+        apply_locking_strategy(out, locking);
 
-        ret.chunks_exact_mut(num_hands).for_each(|chunk| {
+        out.chunks_exact_mut(num_hands).for_each(|chunk| {
             self.apply_swap(chunk, player, false);
         });
+    }
 
-        ret
+    /// Loads the deferred streets up to the given street if any, and returns whether the street
+    /// is available.
+    pub(super) fn ensure_street_loaded(&mut self, street: BoardState) -> bool {
+        #[cfg(feature = "bincode")]
+        if self.deferred_chunks.is_some() {
+            if let Err(e) = self.load_deferred_streets(street) {
+                panic!("Failed to load deferred streets: {e}");
+            }
+        }
+
+        self.storage_mode >= street
+    }
+
+    /// Returns the information of the current node.
+    ///
+    /// **Time complexity:** *O*(1).
+    pub fn node_info(&self) -> NodeInfo {
+        if self.state <= State::Uninitialized {
+            panic!("Game is not successfully initialized");
+        }
+
+        let board = self.current_board();
+        let street = match board.len() {
+            3 => BoardState::Flop,
+            4 => BoardState::Turn,
+            _ => BoardState::River,
+        };
+
+        let is_terminal = self.is_terminal_node();
+        let is_chance = self.is_chance_node();
+        let player = (!is_terminal && !is_chance).then(|| self.current_player());
+
+        let [bet_oop, bet_ip] = self.total_bet_amount;
+        let amount_to_call = match player {
+            Some(player) => {
+                (self.total_bet_amount[player ^ 1] - self.total_bet_amount[player]).max(0)
+            }
+            None => 0,
+        };
+
+        let previous_action = match self.node().prev_action {
+            Action::Chance(_) => Action::Chance(*self.action_history.last().unwrap() as Card),
+            prev_action => prev_action,
+        };
+
+        NodeInfo {
+            street,
+            board,
+            player,
+            pot: self.tree_config.starting_pot + bet_oop + bet_ip,
+            stacks: self
+                .total_bet_amount
+                .map(|amount| self.tree_config.effective_stack - amount),
+            amount_to_call,
+            previous_action,
+            is_terminal,
+            is_chance,
+        }
     }
 
     /// Returns the total bet amount of each player (OOP, IP).
@@ -852,6 +1616,20 @@ impl PostFlopGame {
         self.total_bet_amount
     }
 
+    /// Returns the amount of the last bet (including raise and all-in) on the current street, or
+    /// zero if there is none.
+    pub(crate) fn last_bet_amount(&self) -> i32 {
+        self.history_actions()
+            .iter()
+            .rev()
+            .take_while(|action| !matches!(action, Action::Chance(_)))
+            .find_map(|&action| match action {
+                Action::Bet(a) | Action::Raise(a) | Action::AllIn(a) => Some(a),
+                _ => None,
+            })
+            .unwrap_or(0)
+    }
+
     /// Locks the strategy of the current node.
     ///
     /// The `strategy` argument must be a slice of the length of `#(actions) * #(private hands)`.
@@ -992,9 +1770,51 @@ impl PostFlopGame {
         })
     }
 
+    /// Saves the navigation state.
+    pub(super) fn save_navigation(&self) -> NavigationState {
+        NavigationState {
+            action_history: self.action_history.clone(),
+            node_history: self.node_history.clone(),
+            is_normalized_weight_cached: self.is_normalized_weight_cached,
+            turn: self.turn,
+            river: self.river,
+            turn_swapped_suit: self.turn_swapped_suit,
+            turn_swap: self.turn_swap,
+            river_swap: self.river_swap,
+            turn_bucket: self.turn_bucket,
+            river_bucket: self.river_bucket,
+            total_bet_amount: self.total_bet_amount,
+            weights: self.weights.clone(),
+            normalized_weights: self.normalized_weights.clone(),
+            cfvalues_cache: self.cfvalues_cache.clone(),
+        }
+    }
+
+    /// Restores the navigation state saved by [`save_navigation`].
+    ///
+    /// [`save_navigation`]: #method.save_navigation
+    pub(super) fn restore_navigation(&mut self, state: &NavigationState) {
+        self.action_history.clone_from(&state.action_history);
+        self.node_history.clone_from(&state.node_history);
+        self.is_normalized_weight_cached = state.is_normalized_weight_cached;
+        self.turn = state.turn;
+        self.river = state.river;
+        self.turn_swapped_suit = state.turn_swapped_suit;
+        self.turn_swap = state.turn_swap;
+        self.river_swap = state.river_swap;
+        self.turn_bucket = state.turn_bucket;
+        self.river_bucket = state.river_bucket;
+        self.total_bet_amount = state.total_bet_amount;
+        for player in 0..2 {
+            self.weights[player].copy_from_slice(&state.weights[player]);
+            self.normalized_weights[player].clone_from(&state.normalized_weights[player]);
+            self.cfvalues_cache[player].copy_from_slice(&state.cfvalues_cache[player]);
+        }
+    }
+
     /// Returns the reference to the current node.
     #[inline]
-    fn node(&self) -> MutexGuardLike<PostFlopNode> {
+    pub(super) fn node(&self) -> MutexGuardLike<'_, PostFlopNode> {
         self.node_arena[self.node_history.last().cloned().unwrap_or(0)].lock()
     }
 
@@ -1083,15 +1903,8 @@ impl PostFlopGame {
 
     /// Applies the swap.
     #[inline]
-    fn apply_swap(&self, slice: &mut [f32], player: usize, reverse: bool) {
-        let turn_swap = self
-            .turn_swap
-            .map(|suit| &self.isomorphism_swap_turn[suit as usize][player]);
-
-        let river_swap = self.river_swap.map(|(turn_suit, suit)| {
-            &self.isomorphism_swap_river[turn_suit as usize][suit as usize][player]
-        });
-
+    pub(super) fn apply_swap<T>(&self, slice: &mut [T], player: usize, reverse: bool) {
+        let [turn_swap, river_swap] = self.swap_lists(player);
         let swaps = if !reverse {
             [turn_swap, river_swap]
         } else {
@@ -1105,6 +1918,71 @@ impl PostFlopGame {
         }
     }
 
+    /// Returns the swap lists of the turn and the river applied by [`apply_swap`].
+    ///
+    /// [`apply_swap`]: #method.apply_swap
+    #[inline]
+    fn swap_lists(&self, player: usize) -> [Option<&Vec<(u16, u16)>>; 2] {
+        let bucket_swap = |&(card1, card2): &(Card, Card)| {
+            &self.bucket_swap[card_pair_to_index(card1, card2)][player]
+        };
+
+        let turn_swap = self
+            .turn_swap
+            .map(|suit| &self.isomorphism_swap_turn[suit as usize][player])
+            .or(self.turn_bucket.as_ref().map(bucket_swap));
+
+        let river_swap = self
+            .river_swap
+            .map(|(turn_suit, suit)| {
+                &self.isomorphism_swap_river[turn_suit as usize][suit as usize][player]
+            })
+            .or(self.river_bucket.as_ref().map(bucket_swap));
+
+        [turn_swap, river_swap]
+    }
+
+    /// Returns the index of the given hand in the storage of the current node, i.e., before the
+    /// suit swapping of [`apply_swap`].
+    ///
+    /// [`apply_swap`]: #method.apply_swap
+    fn stored_hand_index(&self, index: usize, player: usize) -> usize {
+        let [turn_swap, river_swap] = self.swap_lists(player);
+        let mut index = index;
+        for swap in [river_swap, turn_swap].into_iter().flatten() {
+            for &(i, j) in swap.iter().rev() {
+                if index == i as usize {
+                    index = j as usize;
+                } else if index == j as usize {
+                    index = i as usize;
+                }
+            }
+        }
+
+        index
+    }
+
+    /// Returns the normalizer of the counterfactual values used to compute the expected values.
+    /// If `with_chance_factor` is `false`, the chance factor of the dealt cards is not applied.
+    pub(super) fn expected_value_normalizer(&self, with_chance_factor: bool) -> f32 {
+        let mut chance_factor = 1;
+        if with_chance_factor {
+            if self.card_config.turn == NOT_DEALT && self.turn != NOT_DEALT {
+                chance_factor *= 45 - self.bunching_num_dead_cards;
+            }
+            if self.card_config.river == NOT_DEALT && self.river != NOT_DEALT {
+                chance_factor *= 44 - self.bunching_num_dead_cards;
+            }
+        }
+
+        let num_combinations = match self.bunching_num_dead_cards {
+            0 => self.num_combinations,
+            _ => self.bunching_num_combinations,
+        };
+
+        (num_combinations * chance_factor as f64) as f32
+    }
+
     /// Internal method for calculating the equity.
     fn equity_internal(
         &self,
@@ -1180,8 +2058,8 @@ impl PostFlopGame {
     }
 
     /// Internal method for calculating the equity.
-    fn equity_internal_bunching(&self, player: usize) -> Vec<f32> {
-        let mut weights_buf = Vec::new();
+    fn equity_internal_bunching(&self, result: &mut Vec<f32>, player: usize) {
+        let mut weights_buf = PooledVec::with_capacity(self.weights[player ^ 1].len());
         let opponent_weights = if self.turn_swap.is_none() && self.river_swap.is_none() {
             &self.weights[player ^ 1]
         } else {
@@ -1200,17 +2078,14 @@ impl PostFlopGame {
                 &self.bunching_coef_flop[player]
             };
 
-            indices
-                .iter()
-                .map(|&index| {
-                    if index != 0 {
-                        let slice = &self.bunching_arena[index..index + opponent_len];
-                        0.5 * inner_product(opponent_weights, slice)
-                    } else {
-                        0.0
-                    }
-                })
-                .collect()
+            result.extend(indices.iter().map(|&index| {
+                if index != 0 {
+                    let slice = &self.bunching_arena[index..index + opponent_len];
+                    0.5 * inner_product(opponent_weights, slice)
+                } else {
+                    0.0
+                }
+            }));
         }
         // showdown
         else {
@@ -1219,25 +2094,35 @@ impl PostFlopGame {
             let player_strength = &self.bunching_strength[pair_index][player];
             let opponent_strength = &self.bunching_strength[pair_index][player ^ 1];
 
-            indices
-                .iter()
-                .zip(player_strength)
-                .map(|(&index, &strength)| {
-                    if index != 0 {
-                        inner_product_cond(
-                            opponent_weights,
-                            &self.bunching_arena[index..index + opponent_len],
-                            opponent_strength,
-                            strength,
-                            0.5,
-                            -0.5,
-                            0.0,
-                        )
-                    } else {
-                        0.0
-                    }
-                })
-                .collect()
+            result.extend(
+                indices
+                    .iter()
+                    .zip(player_strength)
+                    .map(|(&index, &strength)| {
+                        if index != 0 {
+                            inner_product_cond(
+                                opponent_weights,
+                                &self.bunching_arena[index..index + opponent_len],
+                                opponent_strength,
+                                strength,
+                                0.5,
+                                -0.5,
+                                0.0,
+                            )
+                        } else {
+                            0.0
+                        }
+                    }),
+            );
         }
     }
 }
+
+/// Creates a [`PostFlopError::InvalidHand`] error of the given hand.
+#[inline]
+fn invalid_hand(hand: &str, reason: String) -> PostFlopError {
+    PostFlopError::InvalidHand {
+        input: hand.to_string(),
+        reason,
+    }
+}
@@ -0,0 +1,284 @@
+use super::*;
+use crate::range::*;
+use std::fmt;
+
+/// Error returned by [`PostFlopGame::apply_line_str`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineError {
+    /// Kind of the error.
+    pub kind: LineErrorKind,
+
+    /// Zero-based index of the offending token.
+    pub position: usize,
+
+    /// Offending token.
+    pub token: String,
+}
+
+/// Kind of [`LineError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineErrorKind {
+    /// The token could not be parsed as an action or a card.
+    InvalidToken,
+
+    /// The action is not available at the node.
+    ActionNotAvailable,
+
+    /// The action matches multiple available actions; the amount must be specified.
+    AmbiguousAction,
+
+    /// The card cannot be dealt at the chance node.
+    CardNotAvailable,
+
+    /// The line continues after reaching a terminal node.
+    TerminalNode,
+}
+
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self.kind {
+            LineErrorKind::InvalidToken => "Invalid token",
+            LineErrorKind::ActionNotAvailable => "Action is not available",
+            LineErrorKind::AmbiguousAction => "Ambiguous action",
+            LineErrorKind::CardNotAvailable => "Card cannot be dealt",
+            LineErrorKind::TerminalNode => "Line continues after terminal node",
+        };
+        write!(f, "{message}: {} (token {})", self.token, self.position)
+    }
+}
+
+impl std::error::Error for LineError {}
+
+/// Action token of a line.
+enum LineToken {
+    Fold,
+    Check,
+    Call,
+    Aggressive(Option<i32>),
+    AllIn,
+}
+
+impl PostFlopGame {
+    /// Applies the given line from the root node.
+    ///
+    /// The line is a list of the following tokens (case-insensitive), separated by whitespace or
+    /// `-`:
+    ///
+    /// - `F`: fold.
+    /// - `X`: check.
+    /// - `C`: call.
+    /// - `B<amount>` or `R<amount>`: bet or raise (or all-in) of the given amount, in the same
+    ///   convention as [`Action::Bet`] and [`Action::Raise`]. The amount can be omitted if there is
+    ///   only one bet or raise action available.
+    /// - `A` or `A<amount>`: all-in.
+    /// - A card such as `5d`: the turn or river card to deal at a chance node.
+    ///
+    /// For example, `"X B75 C 5d B"` means: check, bet 75, call, deal 5d, and bet. The output of
+    /// [`format_line`] is also accepted. If an error occurs, the current node is not changed.
+    ///
+    /// Panics if the memory is not yet allocated.
+    pub fn apply_line_str(&mut self, line: &str) -> Result<(), LineError> {
+        if self.state < State::MemoryAllocated {
+            panic!("Memory is not allocated");
+        }
+
+        let saved = self.save_navigation();
+        self.back_to_root();
+
+        for (position, token) in line_tokens(line).enumerate() {
+            if let Err(error) = self.apply_line_token(position, token) {
+                self.restore_navigation(&saved);
+                return Err(error);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies a single token of a line.
+    pub(super) fn apply_line_token(
+        &mut self,
+        position: usize,
+        token: &str,
+    ) -> Result<(), LineError> {
+        let error = |kind| LineError {
+            kind,
+            position,
+            token: token.to_string(),
+        };
+
+        if self.is_terminal_node() {
+            return Err(error(LineErrorKind::TerminalNode));
+        }
+
+        // chance node
+        if self.is_chance_node() {
+            let card = card_from_str(token).map_err(|_| error(LineErrorKind::InvalidToken))?;
+            if self.possible_cards() & (1 << card) == 0 {
+                return Err(error(LineErrorKind::CardNotAvailable));
+            }
+            self.play(card as usize);
+            return Ok(());
+        }
+
+        // player node
+        let parsed = parse_line_token(token).ok_or_else(|| error(LineErrorKind::InvalidToken))?;
+
+        let candidates = self
+            .available_actions()
+            .iter()
+            .enumerate()
+            .filter(|(_, &action)| match (&parsed, action) {
+                (LineToken::Fold, Action::Fold) => true,
+                (LineToken::Check, Action::Check) => true,
+                (LineToken::Call, Action::Call) => true,
+                (LineToken::AllIn, Action::AllIn(_)) => true,
+                (LineToken::Aggressive(None), Action::Bet(_) | Action::Raise(_)) => true,
+                (
+                    LineToken::Aggressive(Some(amount)),
+                    Action::Bet(a) | Action::Raise(a) | Action::AllIn(a),
+                ) => *amount == a,
+                _ => false,
+            })
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+
+        match candidates[..] {
+            [index] => {
+                self.play(index);
+                Ok(())
+            }
+            [] => Err(error(LineErrorKind::ActionNotAvailable)),
+            _ => Err(error(LineErrorKind::AmbiguousAction)),
+        }
+    }
+
+    /// Returns the line of the current node in the format of [`format_line`].
+    ///
+    /// **Time complexity:** *O*(#(actions in history)).
+    #[inline]
+    pub fn history_line(&self) -> String {
+        format_line(&self.history_actions())
+    }
+}
+
+/// Splits the given line into tokens.
+pub(super) fn line_tokens(line: &str) -> impl Iterator<Item = &str> {
+    line.split(|c: char| c.is_whitespace() || c == '-')
+        .filter(|token| !token.is_empty())
+}
+
+/// Parses an action token of a line.
+fn parse_line_token(token: &str) -> Option<LineToken> {
+    let token = token.to_ascii_uppercase();
+    match token.as_str() {
+        "F" => Some(LineToken::Fold),
+        "X" => Some(LineToken::Check),
+        "C" => Some(LineToken::Call),
+        "A" => Some(LineToken::AllIn),
+        "B" | "R" => Some(LineToken::Aggressive(None)),
+        _ => {
+            let amount = token
+                .strip_prefix('B')
+                .or_else(|| token.strip_prefix('R'))
+                .or_else(|| token.strip_prefix('A'))?;
+            amount
+                .parse::<i32>()
+                .ok()
+                .filter(|&amount| amount > 0)
+                .map(|amount| LineToken::Aggressive(Some(amount)))
+        }
+    }
+}
+
+/// Returns the token of the given action in the format of [`PostFlopGame::apply_line_str`].
+pub fn action_to_token(action: Action) -> String {
+    match action {
+        Action::None => String::new(),
+        Action::Fold => "F".to_string(),
+        Action::Check => "X".to_string(),
+        Action::Call => "C".to_string(),
+        Action::Bet(amount) => format!("B{amount}"),
+        Action::Raise(amount) => format!("R{amount}"),
+        Action::AllIn(amount) => format!("A{amount}"),
+        Action::Chance(card) => card_to_string(card).unwrap(),
+    }
+}
+
+/// Returns the compact line string of the given actions, e.g., `"X-B75-C-5d"`.
+///
+/// Each action is converted by [`action_to_token`] and the tokens are joined with `-`. The result
+/// is canonical, i.e., the same actions always produce the same string, so it can be used as a key
+/// of a line. It can be converted back by [`parse_line`] or applied to a game by
+/// [`PostFlopGame::apply_line_str`].
+///
+/// # Examples
+/// ```
+/// use postflop_solver_ffi::*;
+///
+/// let line = [Action::Check, Action::Bet(75), Action::Call, Action::Chance(12)];
+/// assert_eq!(format_line(&line), "X-B75-C-5c");
+/// ```
+pub fn format_line(actions: &[Action]) -> String {
+    actions
+        .iter()
+        .map(|&action| action_to_token(action))
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Parses the output of [`format_line`] into the actions.
+///
+/// Unlike [`PostFlopGame::apply_line_str`], the line is parsed without a game, so each token must
+/// specify the action exactly: `F`, `X`, `C`, `B<amount>`, `R<amount>`, `A<amount>`, or a card.
+/// Tokens may also be separated by whitespace, and they are case-insensitive.
+///
+/// # Examples
+/// ```
+/// use postflop_solver_ffi::*;
+///
+/// let actions = parse_line("X-B75-C-5c").unwrap();
+/// assert_eq!(actions, [Action::Check, Action::Bet(75), Action::Call, Action::Chance(12)]);
+/// assert!(parse_line("X-B").is_err()); // amount is required
+/// ```
+pub fn parse_line(line: &str) -> Result<Vec<Action>, LineError> {
+    line_tokens(line)
+        .enumerate()
+        .map(|(position, token)| {
+            parse_action_token(token).ok_or_else(|| LineError {
+                kind: LineErrorKind::InvalidToken,
+                position,
+                token: token.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Parses a token of [`format_line`] into an action.
+fn parse_action_token(token: &str) -> Option<Action> {
+    if let Ok(card) = card_from_str(token) {
+        return Some(Action::Chance(card));
+    }
+
+    let upper = token.to_ascii_uppercase();
+    match upper.as_str() {
+        "F" => return Some(Action::Fold),
+        "X" => return Some(Action::Check),
+        "C" => return Some(Action::Call),
+        _ => {}
+    }
+
+    let mut chars = upper.chars();
+    let kind = chars.next()?;
+    let amount = chars
+        .as_str()
+        .parse::<i32>()
+        .ok()
+        .filter(|&amount| amount > 0)?;
+    match kind {
+        'B' => Some(Action::Bet(amount)),
+        'R' => Some(Action::Raise(amount)),
+        'A' => Some(Action::AllIn(amount)),
+        _ => None,
+    }
+}
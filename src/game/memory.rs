@@ -0,0 +1,99 @@
+use super::*;
+
+/// Breakdown of the estimated memory usage in bytes, returned by
+/// [`PostFlopGame::memory_usage_detailed`].
+///
+/// The buffers of the nodes are indexed by the street of the nodes (flop, turn, river).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryUsageDetail {
+    /// Cumulative strategies of the player nodes.
+    pub strategy: [u64; 3],
+
+    /// Cumulative regrets of the player nodes, which are replaced by the counterfactual values
+    /// after solving.
+    pub regrets: [u64; 3],
+
+    /// Counterfactual values of the IP player stored at the OOP nodes.
+    pub cfvalues_ip: [u64; 3],
+
+    /// Counterfactual values stored at the chance nodes.
+    pub cfvalues_chance: [u64; 3],
+
+    /// Game tree and the other tables.
+    pub misc: u64,
+
+    /// Tables of the bunching effect (`0` if the bunching effect is disabled).
+    pub bunching: u64,
+}
+
+impl MemoryUsageDetail {
+    /// Returns the total memory usage of the buffers of the nodes on the given street.
+    #[inline]
+    pub fn street_total(&self, street: BoardState) -> u64 {
+        let index = street as usize;
+        self.strategy[index]
+            + self.regrets[index]
+            + self.cfvalues_ip[index]
+            + self.cfvalues_chance[index]
+    }
+
+    /// Returns the total memory usage.
+    #[inline]
+    pub fn total(&self) -> u64 {
+        let streets = [BoardState::Flop, BoardState::Turn, BoardState::River];
+        streets
+            .map(|street| self.street_total(street))
+            .iter()
+            .sum::<u64>()
+            + self.misc
+            + self.bunching
+    }
+}
+
+impl PostFlopGame {
+    /// Returns the breakdown of the estimated memory usage in bytes by buffer type and street.
+    ///
+    /// The buffers are estimated in the allocated storage formats, or in 32-bit floats if the
+    /// memory is not allocated. The counterfactual values freed by [`trim_to_strategy`] are not
+    /// counted.
+    ///
+    /// [`trim_to_strategy`]: #method.trim_to_strategy
+    pub fn memory_usage_detailed(&self) -> MemoryUsageDetail {
+        if self.state <= State::Uninitialized {
+            panic!("Game is not successfully initialized");
+        }
+
+        let strategy_bytes = self.strategy_format.num_bytes() as u64;
+        let value_bytes = match self.is_cfvalues_trimmed {
+            false => self.value_format.num_bytes() as u64,
+            true => 0,
+        };
+
+        let mut ret = MemoryUsageDetail {
+            misc: self.misc_memory_usage,
+            ..Default::default()
+        };
+
+        if self.bunching_num_dead_cards > 0 {
+            ret.bunching = self.memory_usage_bunching_internal();
+        }
+
+        for node in &self.node_arena {
+            let node = node.lock();
+            let street = node.street();
+
+            let num_elements = node.num_elements as u64;
+            if node.is_terminal() {
+                // do nothing
+            } else if node.is_chance() {
+                ret.cfvalues_chance[street] += value_bytes * num_elements;
+            } else {
+                ret.strategy[street] += strategy_bytes * num_elements;
+                ret.regrets[street] += value_bytes * num_elements;
+                ret.cfvalues_ip[street] += value_bytes * node.num_elements_ip as u64;
+            }
+        }
+
+        ret
+    }
+}
@@ -1,7 +1,35 @@
+mod abstraction;
+mod advisor;
+mod attribution;
 mod base;
+mod bucketing;
+mod builder;
+mod comparison;
+mod convert;
+mod distance;
 mod evaluation;
+mod export;
+mod forced;
 mod interpreter;
+mod line;
+mod memory;
 mod node;
+mod optimizer;
+mod remap;
+mod report;
+mod simplify;
+mod simulation;
+mod snapshot;
+mod storage;
+mod structure;
+mod subtree;
+mod trim;
+mod variance;
+mod visitor;
+mod weights;
+
+#[cfg(feature = "arrow")]
+mod columnar;
 
 #[cfg(feature = "bincode")]
 mod serialization;
@@ -11,8 +39,36 @@ mod tests;
 
 use crate::action_tree::*;
 use crate::card::*;
+use crate::error::*;
+use crate::interface::*;
 use crate::mutex_like::*;
 use std::collections::BTreeMap;
+use storage::StorageBuffer;
+
+#[cfg(feature = "profile")]
+use crate::profile::*;
+
+pub use advisor::{BetSizeEvLoss, BetSizeKind, SimplificationReport};
+pub use attribution::StreetAttribution;
+pub use builder::PostFlopGameBuilder;
+pub use comparison::{HandComparison, LineComparison, ScenarioComparison};
+pub use distance::{NodeDistance, StrategyDistance};
+pub use export::ExportOptions;
+pub use forced::ForcedLineReport;
+pub use interpreter::{HandRecommendation, NodeHandle, NodeInfo};
+pub use line::{action_to_token, format_line, parse_line, LineError, LineErrorKind};
+pub use memory::MemoryUsageDetail;
+pub use optimizer::{BetSizeOptimization, BetSizePoint, BetSizeSearch};
+pub use remap::RemappedGame;
+pub use report::{
+    BetReport, ClassStrategy, ComboStrategy, DefenseReport, EquityDistribution, EvBreakdown,
+    LineReport, MixednessReport, NodeMixedness, NodeSummary, RunoutReport,
+};
+pub use simplify::SimplifiedTree;
+pub use simulation::{DecisionContext, LineFrequency, SimulationResult};
+pub use snapshot::NodeSnapshot;
+pub use storage::StorageBackend;
+pub use visitor::NodeView;
 
 #[cfg(feature = "bincode")]
 use bincode::{Decode, Encode};
@@ -34,6 +90,9 @@ enum State {
 pub struct PostFlopGame {
     // state
     state: State,
+    solve_summary: Option<SolveSummary>,
+    #[cfg(feature = "profile")]
+    solver_stats: Option<SolverStats>,
 
     // postflop game configurations
     card_config: CardConfig,
@@ -45,9 +104,15 @@ pub struct PostFlopGame {
     // computed from configurations
     num_combinations: f64,
     initial_weights: [Vec<f32>; 2],
+    is_weight_modified: bool,
     private_cards: [Vec<(Card, Card)>; 2],
     same_hand_index: [Vec<u16>; 2],
 
+    // hole-card suit abstraction: full list of hands and their indices in `private_cards`
+    is_hand_abstracted: bool,
+    full_private_cards: [Vec<(Card, Card)>; 2],
+    abstract_hand_index: [Vec<u16>; 2],
+
     // indices in `private_cards` that do not conflict with the specified board cards
     valid_indices_flop: [Vec<u16>; 2],
     valid_indices_turn: Vec<[Vec<u16>; 2]>,
@@ -68,6 +133,19 @@ pub struct PostFlopGame {
     isomorphism_card_river: [Vec<Card>; 4],
     isomorphism_swap_river: [[SwapList; 4]; 4],
 
+    // card bucketing (the suit isomorphism is not used if enabled)
+    // - `card_buckets`: groups of ranks whose turn/river cards of the same suit are merged
+    // - `bucket_ref_*`: indices to which the bucketed cards should refer
+    // - `bucket_card_*`: pairs of the bucketed card and its representative card
+    // - `bucket_swap`: list of hand index pairs that should be swapped when exchanging two cards
+    //                  (indexed by `card_pair_to_index`)
+    card_buckets: Vec<Vec<u8>>,
+    bucket_ref_turn: Vec<u8>,
+    bucket_card_turn: Vec<(Card, Card)>,
+    bucket_ref_river: Vec<Vec<u8>>,
+    bucket_card_river: Vec<Vec<(Card, Card)>>,
+    bucket_swap: Vec<SwapList>,
+
     // bunching effect
     bunching_num_dead_cards: usize,
     bunching_num_combinations: f64,
@@ -82,22 +160,31 @@ pub struct PostFlopGame {
     // store options
     storage_mode: BoardState,
     target_storage_mode: BoardState,
+    #[cfg(feature = "bincode")]
+    is_target_strategy_only: bool,
     num_nodes: [u64; 3],
-    is_compression_enabled: bool,
+    strategy_format: StorageFormat,
+    value_format: StorageFormat,
     num_storage: u64,
     num_storage_ip: u64,
     num_storage_chance: u64,
     misc_memory_usage: u64,
+    storage_backend: StorageBackend,
 
     // global storage
     // `storage*` are used as a global storage and are referenced by `PostFlopNode::storage*`.
     // Methods like `PostFlopNode::strategy` define how the storage is used.
     node_arena: Vec<MutexLike<PostFlopNode>>,
-    storage1: Vec<u8>,
-    storage2: Vec<u8>,
-    storage_ip: Vec<u8>,
-    storage_chance: Vec<u8>,
+    storage1: StorageBuffer,
+    storage2: StorageBuffer,
+    storage_ip: StorageBuffer,
+    storage_chance: StorageBuffer,
+    is_cfvalues_trimmed: bool,
     locking_strategy: BTreeMap<usize, Vec<f32>>,
+    #[cfg(feature = "mmap")]
+    mapped_file: Option<memmap2::Mmap>,
+    #[cfg(feature = "bincode")]
+    deferred_chunks: Option<serialization::DeferredChunks>,
 
     // result interpreter
     action_history: Vec<usize>,
@@ -108,6 +195,8 @@ pub struct PostFlopGame {
     turn_swapped_suit: Option<(u8, u8)>,
     turn_swap: Option<u8>,
     river_swap: Option<(u8, u8)>,
+    turn_bucket: Option<(Card, Card)>,
+    river_bucket: Option<(Card, Card)>,
     total_bet_amount: [i32; 2],
     weights: [Vec<f32>; 2],
     normalized_weights: [Vec<f32>; 2],
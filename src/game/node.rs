@@ -1,5 +1,6 @@
 use super::*;
 use crate::interface::*;
+use half::f16;
 use std::ptr;
 use std::slice;
 
@@ -35,7 +36,7 @@ impl GameNode for PostFlopNode {
     }
 
     #[inline]
-    fn play(&self, action: usize) -> MutexGuardLike<Self> {
+    fn play(&self, action: usize) -> MutexGuardLike<'_, Self> {
         self.children()[action].lock()
     }
 
@@ -148,6 +149,110 @@ impl GameNode for PostFlopNode {
         unsafe { slice::from_raw_parts_mut(self.storage1 as *mut i16, self.num_elements as usize) }
     }
 
+    #[inline]
+    fn strategy_8bit(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.storage1 as *const u8, self.num_elements as usize) }
+    }
+
+    #[inline]
+    fn strategy_8bit_mut(&mut self) -> &mut [u8] {
+        unsafe { slice::from_raw_parts_mut(self.storage1, self.num_elements as usize) }
+    }
+
+    #[inline]
+    fn regrets_8bit(&self) -> &[i8] {
+        unsafe { slice::from_raw_parts(self.storage2 as *const i8, self.num_elements as usize) }
+    }
+
+    #[inline]
+    fn regrets_8bit_mut(&mut self) -> &mut [i8] {
+        unsafe { slice::from_raw_parts_mut(self.storage2 as *mut i8, self.num_elements as usize) }
+    }
+
+    #[inline]
+    fn cfvalues_8bit(&self) -> &[i8] {
+        unsafe { slice::from_raw_parts(self.storage2 as *const i8, self.num_elements as usize) }
+    }
+
+    #[inline]
+    fn cfvalues_8bit_mut(&mut self) -> &mut [i8] {
+        unsafe { slice::from_raw_parts_mut(self.storage2 as *mut i8, self.num_elements as usize) }
+    }
+
+    #[inline]
+    fn cfvalues_ip_8bit(&self) -> &[i8] {
+        unsafe { slice::from_raw_parts(self.storage3 as *const i8, self.num_elements_ip as usize) }
+    }
+
+    #[inline]
+    fn cfvalues_ip_8bit_mut(&mut self) -> &mut [i8] {
+        unsafe {
+            slice::from_raw_parts_mut(self.storage3 as *mut i8, self.num_elements_ip as usize)
+        }
+    }
+
+    #[inline]
+    fn cfvalues_chance_8bit(&self) -> &[i8] {
+        unsafe { slice::from_raw_parts(self.storage1 as *const i8, self.num_elements as usize) }
+    }
+
+    #[inline]
+    fn cfvalues_chance_8bit_mut(&mut self) -> &mut [i8] {
+        unsafe { slice::from_raw_parts_mut(self.storage1 as *mut i8, self.num_elements as usize) }
+    }
+
+    #[inline]
+    fn strategy_f16(&self) -> &[f16] {
+        unsafe { slice::from_raw_parts(self.storage1 as *const f16, self.num_elements as usize) }
+    }
+
+    #[inline]
+    fn strategy_f16_mut(&mut self) -> &mut [f16] {
+        unsafe { slice::from_raw_parts_mut(self.storage1 as *mut f16, self.num_elements as usize) }
+    }
+
+    #[inline]
+    fn regrets_f16(&self) -> &[f16] {
+        unsafe { slice::from_raw_parts(self.storage2 as *const f16, self.num_elements as usize) }
+    }
+
+    #[inline]
+    fn regrets_f16_mut(&mut self) -> &mut [f16] {
+        unsafe { slice::from_raw_parts_mut(self.storage2 as *mut f16, self.num_elements as usize) }
+    }
+
+    #[inline]
+    fn cfvalues_f16(&self) -> &[f16] {
+        unsafe { slice::from_raw_parts(self.storage2 as *const f16, self.num_elements as usize) }
+    }
+
+    #[inline]
+    fn cfvalues_f16_mut(&mut self) -> &mut [f16] {
+        unsafe { slice::from_raw_parts_mut(self.storage2 as *mut f16, self.num_elements as usize) }
+    }
+
+    #[inline]
+    fn cfvalues_ip_f16(&self) -> &[f16] {
+        unsafe { slice::from_raw_parts(self.storage3 as *const f16, self.num_elements_ip as usize) }
+    }
+
+    #[inline]
+    fn cfvalues_ip_f16_mut(&mut self) -> &mut [f16] {
+        unsafe {
+            slice::from_raw_parts_mut(self.storage3 as *mut f16, self.num_elements_ip as usize)
+        }
+    }
+
+    #[inline]
+    fn cfvalues_chance_f16(&self) -> &[f16] {
+        unsafe { slice::from_raw_parts(self.storage1 as *const f16, self.num_elements as usize) }
+    }
+
+    #[inline]
+    fn cfvalues_chance_f16_mut(&mut self) -> &mut [f16] {
+        unsafe { slice::from_raw_parts_mut(self.storage1 as *mut f16, self.num_elements as usize) }
+    }
+
     #[inline]
     fn strategy_scale(&self) -> f32 {
         self.scale1
@@ -202,6 +307,22 @@ impl GameNode for PostFlopNode {
     fn enable_parallelization(&self) -> bool {
         self.river == NOT_DEALT
     }
+
+    #[inline]
+    fn street(&self) -> usize {
+        if self.river != NOT_DEALT {
+            2
+        } else if self.turn != NOT_DEALT {
+            1
+        } else {
+            0
+        }
+    }
+
+    #[inline]
+    fn river_chance_turn(&self) -> Option<u8> {
+        (self.is_chance() && self.turn != NOT_DEALT).then_some(self.turn)
+    }
 }
 
 impl Default for PostFlopNode {
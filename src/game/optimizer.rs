@@ -0,0 +1,264 @@
+use super::*;
+use crate::solver::*;
+
+/// Search strategy of [`PostFlopGame::optimize_bet_size`].
+///
+/// The sizes are fractions of the pot after calling (e.g., `0.75` = 75%). When facing a bet, a
+/// size is the raise over the previous bet, which is the same convention as the pot-relative raise
+/// sizes of [`BetSizeOptions`](crate::BetSizeOptions).
+#[derive(Debug, Clone, PartialEq)]
+pub enum BetSizeSearch {
+    /// Evaluates each of the given sizes.
+    Grid(Vec<f64>),
+
+    /// Golden-section search for the best size in `[min_ratio, max_ratio]`, evaluating the given
+    /// number of sizes. Assumes that the EV curve is unimodal.
+    GoldenSection {
+        min_ratio: f64,
+        max_ratio: f64,
+        num_evaluations: usize,
+    },
+}
+
+/// Point of the EV curve, an entry of [`BetSizeOptimization`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BetSizePoint {
+    /// Requested size as a fraction of the pot.
+    pub ratio: f64,
+
+    /// Bet action actually added to the tree. The amount is clamped to the valid range, and a bet
+    /// reaching the stack is an all-in.
+    pub action: Action,
+
+    /// Average root expected value of the betting player.
+    pub expected_value: f32,
+
+    /// Exploitability of the re-solved game.
+    pub exploitability: f32,
+}
+
+/// Result of [`PostFlopGame::optimize_bet_size`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BetSizeOptimization {
+    /// Betting player (`0` = OOP, `1` = IP).
+    pub player: usize,
+
+    /// Pot size after calling at the node, the reference of the ratios.
+    pub pot: i32,
+
+    /// Evaluated points of the EV curve, in ascending order of the ratio.
+    pub points: Vec<BetSizePoint>,
+
+    /// Index of the point with the highest expected value.
+    pub best: usize,
+}
+
+impl BetSizeOptimization {
+    /// Returns the point with the highest expected value.
+    #[inline]
+    pub fn best_point(&self) -> &BetSizePoint {
+        &self.points[self.best]
+    }
+}
+
+impl PostFlopGame {
+    /// Searches for the bet size of the current node that maximizes the EV of the current player.
+    ///
+    /// For each candidate size, the game is rebuilt with the candidate as the only bet size of the
+    /// current node (the check, fold, and call actions are kept), and re-solved by [`solve`] with
+    /// the given parameters, so that the opponent responds to the size and both players adjust
+    /// the rest of the tree. The candidate is compared by the average root expected value of the
+    /// player. The node is identified by its action line, so the size applies to all the runouts of
+    /// the line. Candidates clamped to the same action are solved only once. The current node is
+    /// not changed.
+    ///
+    /// Returns an error if the search parameters are invalid, the current node has no bet action,
+    /// the tree has edited lines, or the bunching effect is enabled. Panics if the game is not
+    /// solved, the expected values are trimmed, or the current node is a terminal node or a
+    /// chance node.
+    ///
+    /// **Time complexity:** #(evaluations) * (building and solving the game).
+    pub fn optimize_bet_size(
+        &mut self,
+        search: &BetSizeSearch,
+        max_num_iterations: u32,
+        target_exploitability: f32,
+    ) -> Result<BetSizeOptimization, PostFlopError> {
+        if self.state != State::Solved {
+            panic!("Game is not solved");
+        }
+
+        if self.is_terminal_node() {
+            panic!("Terminal node is not allowed");
+        }
+
+        if self.is_chance_node() {
+            panic!("Chance node is not allowed");
+        }
+
+        match search {
+            BetSizeSearch::Grid(ratios) => {
+                if ratios.is_empty() {
+                    return Err(PostFlopError::InvalidArgument(
+                        "Grid must not be empty".to_string(),
+                    ));
+                }
+                if let Some(ratio) = ratios.iter().find(|r| !(r.is_finite() && **r > 0.0)) {
+                    return Err(PostFlopError::InvalidArgument(format!(
+                        "Invalid ratio: {ratio}"
+                    )));
+                }
+            }
+            &BetSizeSearch::GoldenSection {
+                min_ratio,
+                max_ratio,
+                num_evaluations,
+            } => {
+                if !(min_ratio > 0.0 && min_ratio < max_ratio && max_ratio.is_finite()) {
+                    return Err(PostFlopError::InvalidArgument(format!(
+                        "Invalid range: [{min_ratio}, {max_ratio}]"
+                    )));
+                }
+                if num_evaluations < 2 {
+                    return Err(PostFlopError::InvalidArgument(format!(
+                        "Too few evaluations: {num_evaluations}"
+                    )));
+                }
+            }
+        }
+
+        if !self.added_lines.is_empty() || !self.removed_lines.is_empty() {
+            return Err(PostFlopError::Unsupported(
+                "Game with edited lines is not supported".to_string(),
+            ));
+        }
+
+        if self.bunching_num_dead_cards != 0 {
+            return Err(PostFlopError::Unsupported(
+                "Bunching effect is not supported".to_string(),
+            ));
+        }
+
+        let is_bet = |action: &Action| {
+            matches!(action, Action::Bet(_) | Action::Raise(_) | Action::AllIn(_))
+        };
+
+        if !self.available_actions().iter().any(is_bet) {
+            return Err(PostFlopError::InvalidState(
+                "Current node has no bet action".to_string(),
+            ));
+        }
+
+        let player = self.current_player();
+        let line = self
+            .history_actions()
+            .into_iter()
+            .filter(|action| !matches!(action, Action::Chance(_)))
+            .collect::<Vec<_>>();
+
+        // amounts on the current street
+        let total_bet_amount = self.total_bet_amount();
+        let max_bet = total_bet_amount[0].max(total_bet_amount[1]);
+        let prev_amount = self.last_bet_amount();
+        let street_base = max_bet - prev_amount;
+        let to_call = max_bet - total_bet_amount[player];
+        let max_amount = self.tree_config.effective_stack - street_base;
+        let min_amount = (prev_amount + to_call).clamp(1, max_amount);
+        let pot = self.tree_config.starting_pot + 2 * max_bet;
+
+        let candidate = |ratio: f64| {
+            let amount = prev_amount + (pot as f64 * ratio).round() as i32;
+            let amount = amount.clamp(min_amount, max_amount);
+            match (amount == max_amount, prev_amount) {
+                (true, _) => Action::AllIn(max_amount),
+                (false, 0) => Action::Bet(amount),
+                (false, _) => Action::Raise(amount),
+            }
+        };
+
+        let mut cache = BTreeMap::new();
+        let mut points = Vec::new();
+        let mut evaluate = |ratio: f64| -> Result<f32, PostFlopError> {
+            let action = candidate(ratio);
+            let (expected_value, exploitability) = match cache.get(&action) {
+                Some(&value) => value,
+                None => {
+                    let mut action_tree = ActionTree::new(self.tree_config.clone())?;
+                    action_tree.apply_history(&line)?;
+                    let bets = action_tree.available_actions().to_vec();
+                    let mut new_line = line.clone();
+                    for &bet in bets.iter().filter(|a| is_bet(a)) {
+                        new_line.push(bet);
+                        action_tree.remove_line(&new_line)?;
+                        new_line.pop();
+                    }
+                    new_line.push(action);
+                    action_tree.add_line(&new_line)?;
+
+                    let mut game = self.rebuild_with_action_tree(action_tree)?;
+                    let exploitability =
+                        solve(&mut game, max_num_iterations, target_exploitability, false);
+                    let expected_value = game.node_summary().average_expected_value[player];
+                    *cache
+                        .entry(action)
+                        .or_insert((expected_value, exploitability))
+                }
+            };
+
+            points.push(BetSizePoint {
+                ratio,
+                action,
+                expected_value,
+                exploitability,
+            });
+
+            Ok(expected_value)
+        };
+
+        match search {
+            BetSizeSearch::Grid(ratios) => {
+                for &ratio in ratios {
+                    evaluate(ratio)?;
+                }
+            }
+            &BetSizeSearch::GoldenSection {
+                min_ratio,
+                max_ratio,
+                num_evaluations,
+            } => {
+                let inv_phi = (5f64.sqrt() - 1.0) * 0.5;
+                let (mut a, mut b) = (min_ratio, max_ratio);
+                let mut c = b - (b - a) * inv_phi;
+                let mut d = a + (b - a) * inv_phi;
+                let mut fc = evaluate(c)?;
+                let mut fd = evaluate(d)?;
+                for _ in 2..num_evaluations {
+                    if fc > fd {
+                        (b, d, fd) = (d, c, fc);
+                        c = b - (b - a) * inv_phi;
+                        fc = evaluate(c)?;
+                    } else {
+                        (a, c, fc) = (c, d, fd);
+                        d = a + (b - a) * inv_phi;
+                        fd = evaluate(d)?;
+                    }
+                }
+            }
+        }
+
+        points.sort_by(|a, b| a.ratio.total_cmp(&b.ratio));
+        let best = (0..points.len())
+            .max_by(|&i, &j| {
+                let (a, b) = (&points[i], &points[j]);
+                a.expected_value.total_cmp(&b.expected_value)
+            })
+            .unwrap();
+
+        Ok(BetSizeOptimization {
+            player,
+            pot,
+            points,
+            best,
+        })
+    }
+}
@@ -0,0 +1,210 @@
+use super::*;
+use crate::flop::*;
+use crate::range::*;
+
+/// A view of a [`PostFlopGame`] that behaves as if the flop were a suit-isomorphic flop.
+///
+/// This view is obtained by [`PostFlopGame::remap_flop`]. All cards passed to or returned from
+/// this view are expressed in terms of the target flop, and they are translated into the cards of
+/// the underlying game internally.
+///
+/// The private hands are not reordered: the `i`-th hand of [`private_cards`] corresponds to the
+/// `i`-th hand of the underlying game. Therefore, per-hand results such as
+/// [`PostFlopGame::strategy`] and [`PostFlopGame::expected_values`] can be read from [`game`]
+/// as they are.
+///
+/// [`private_cards`]: #method.private_cards
+/// [`game`]: #method.game
+pub struct RemappedGame<'a> {
+    game: &'a mut PostFlopGame,
+    suit_mapping: [u8; 4],
+    inverse_mapping: [u8; 4],
+}
+
+impl PostFlopGame {
+    /// Returns a view of the game in which the flop is replaced with the given suit-isomorphic
+    /// flop.
+    ///
+    /// The ranges, the private hands, and the chance cards are remapped accordingly. Returns an
+    /// error if `flop` is not suit-isomorphic to the flop of the game.
+    pub fn remap_flop(&mut self, flop: [Card; 3]) -> Result<RemappedGame<'_>, PostFlopError> {
+        if self.state <= State::Uninitialized {
+            panic!("Game is not successfully initialized");
+        }
+
+        let mut target = flop;
+        target.sort_unstable();
+        check_sorted_flop(&target)?;
+
+        let (canonical_src, mapping_src) = canonicalize_flop(self.card_config.flop);
+        let (canonical_dst, mapping_dst) = canonicalize_flop(target);
+        if canonical_src != canonical_dst {
+            return Err(PostFlopError::InvalidArgument(format!(
+                "Flop is not suit-isomorphic: {} and {}",
+                board_to_string(&self.card_config.flop)?,
+                board_to_string(&target)?,
+            )));
+        }
+
+        let mut inverse_dst = [0; 4];
+        for suit in 0..4 {
+            inverse_dst[mapping_dst[suit] as usize] = suit as u8;
+        }
+
+        let mut suit_mapping = [0; 4];
+        let mut inverse_mapping = [0; 4];
+        for suit in 0..4 {
+            suit_mapping[suit] = inverse_dst[mapping_src[suit] as usize];
+            inverse_mapping[suit_mapping[suit] as usize] = suit as u8;
+        }
+
+        Ok(RemappedGame {
+            game: self,
+            suit_mapping,
+            inverse_mapping,
+        })
+    }
+}
+
+/// Converts a list of board cards into a string.
+fn board_to_string(cards: &[Card]) -> Result<String, PostFlopError> {
+    cards.iter().map(|&c| card_to_string(c)).collect()
+}
+
+impl<'a> RemappedGame<'a> {
+    /// Returns the underlying game.
+    #[inline]
+    pub fn game(&self) -> &PostFlopGame {
+        self.game
+    }
+
+    /// Returns the mutable reference to the underlying game.
+    #[inline]
+    pub fn game_mut(&mut self) -> &mut PostFlopGame {
+        self.game
+    }
+
+    /// Returns the suit mapping from the underlying game to this view.
+    ///
+    /// `suit_mapping()[suit]` is the suit that `suit` of the underlying game is mapped to.
+    #[inline]
+    pub fn suit_mapping(&self) -> [u8; 4] {
+        self.suit_mapping
+    }
+
+    /// Maps a card of the underlying game to the corresponding card of this view.
+    #[inline]
+    pub fn map_card(&self, card: Card) -> Card {
+        if card == NOT_DEALT {
+            card
+        } else {
+            map_card_suit(card, &self.suit_mapping)
+        }
+    }
+
+    /// Maps a card of this view to the corresponding card of the underlying game.
+    #[inline]
+    pub fn unmap_card(&self, card: Card) -> Card {
+        if card == NOT_DEALT {
+            card
+        } else {
+            map_card_suit(card, &self.inverse_mapping)
+        }
+    }
+
+    /// Returns the flop of this view (sorted in ascending order).
+    #[inline]
+    pub fn flop(&self) -> [Card; 3] {
+        let mut flop = self.game.card_config.flop.map(|c| self.map_card(c));
+        flop.sort_unstable();
+        flop
+    }
+
+    /// Returns the initial range of the given player, remapped to this view.
+    pub fn range(&self, player: usize) -> Range {
+        let src = &self.game.card_config.range[player];
+        let mut range = Range::new();
+        for card1 in 0..52 {
+            for card2 in card1 + 1..52 {
+                let weight = src.get_weight_by_cards(card1, card2);
+                if weight > 0.0 {
+                    range.set_weight_by_cards(self.map_card(card1), self.map_card(card2), weight);
+                }
+            }
+        }
+        range
+    }
+
+    /// Returns the card list of private hands of the given player, remapped to this view.
+    ///
+    /// Each card pair has IDs in `(low_id, high_id)` order, but unlike
+    /// [`PostFlopGame::private_cards`], the list is not necessarily sorted because the order of
+    /// the underlying game is preserved.
+    pub fn private_cards(&self, player: usize) -> Vec<(Card, Card)> {
+        self.game
+            .private_cards(player)
+            .iter()
+            .map(|&(c1, c2)| {
+                let (c1, c2) = (self.map_card(c1), self.map_card(c2));
+                (c1.min(c2), c1.max(c2))
+            })
+            .collect()
+    }
+
+    /// Moves the current node back to the root node.
+    #[inline]
+    pub fn back_to_root(&mut self) {
+        self.game.back_to_root();
+    }
+
+    /// Returns the available actions for the current node, remapped to this view.
+    pub fn available_actions(&self) -> Vec<Action> {
+        self.game
+            .available_actions()
+            .into_iter()
+            .map(|action| match action {
+                Action::Chance(card) => Action::Chance(self.map_card(card)),
+                _ => action,
+            })
+            .collect()
+    }
+
+    /// If the current node is a chance node, returns a list of cards that can be dealt, remapped
+    /// to this view.
+    pub fn possible_cards(&self) -> u64 {
+        let mask = self.game.possible_cards();
+        let mut ret = 0;
+        for card in 0..52 {
+            if mask & (1 << card) != 0 {
+                ret |= 1 << self.map_card(card);
+            }
+        }
+        ret
+    }
+
+    /// Returns the current board, remapped to this view.
+    ///
+    /// Unlike [`PostFlopGame::current_board`], the flop cards are sorted in ascending order.
+    pub fn current_board(&self) -> Vec<Card> {
+        let mut ret = self.flop().to_vec();
+        ret.extend(
+            self.game.current_board()[3..]
+                .iter()
+                .map(|&c| self.map_card(c)),
+        );
+        ret
+    }
+
+    /// Plays the given action.
+    ///
+    /// If the current node is a chance node, `action` is a card of this view (or `usize::MAX`).
+    /// Otherwise, this method is equivalent to [`PostFlopGame::play`].
+    pub fn play(&mut self, action: usize) {
+        if action != usize::MAX && self.game.is_chance_node() {
+            let card = self.unmap_card(action as Card);
+            self.game.play(card as usize);
+        } else {
+            self.game.play(action);
+        }
+    }
+}
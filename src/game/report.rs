@@ -0,0 +1,1017 @@
+use super::*;
+use crate::analysis::*;
+use crate::sliceop::*;
+use crate::utility::*;
+
+/// Aggregated statistics of a decision node, returned by [`PostFlopGame::line_report`].
+///
+/// All averages are weighted by the normalized weights of the corresponding player, i.e., by the
+/// number of combinations of each private hand reaching the node.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineReport {
+    /// Action history from the root node (see [`PostFlopGame::history`]).
+    pub history: Vec<usize>,
+
+    /// Actions leading to the node from the root node. Dealt cards are represented as
+    /// [`Action::Chance`].
+    pub line: Vec<Action>,
+
+    /// Current player (`0` = OOP, `1` = IP).
+    pub player: usize,
+
+    /// Pot size at the node, including the starting pot.
+    pub pot: i32,
+
+    /// Available actions of the current player.
+    pub actions: Vec<Action>,
+
+    /// Aggregate frequency of each action of the current player.
+    pub frequencies: Vec<f32>,
+
+    /// Number of combinations of each player reaching the node.
+    pub combos: [f32; 2],
+
+    /// Average equity of each player.
+    pub equity: [f32; 2],
+
+    /// Average expected value of each player.
+    pub expected_value: [f32; 2],
+}
+
+impl PostFlopGame {
+    /// Returns the aggregated statistics of the current node and all decision nodes below it.
+    ///
+    /// The nodes are listed in depth-first order, starting from the current node. The traversal
+    /// passes through chance nodes, dealing each possible card in ascending order of the card ID;
+    /// unlike [`available_actions`], isomorphic cards are reported individually, and the dealt
+    /// cards are recorded in [`LineReport::line`] as [`Action::Chance`]. Chance nodes and terminal
+    /// nodes themselves are not reported. The current node is not changed.
+    ///
+    /// - `max_street`: If `Some(street)`, the traversal does not deal cards of the streets after
+    ///   `street`; e.g., `Some(BoardState::Flop)` reports only the flop nodes. If `None`, the whole
+    ///   subtree is reported.
+    ///
+    /// The traversal also stops before the streets that are not stored in this instance (e.g., a
+    /// game loaded with a lower storage mode).
+    ///
+    /// Panics if the game is not solved.
+    ///
+    /// **Time complexity:** *O*(#(reported nodes) * [`equity`]).
+    ///
+    /// [`available_actions`]: #method.available_actions
+    /// [`equity`]: #method.equity
+    pub fn line_report(&mut self, max_street: Option<BoardState>) -> Vec<LineReport> {
+        if self.state != State::Solved {
+            panic!("Game is not solved");
+        }
+
+        let saved = self.save_navigation();
+        let mut ret = Vec::new();
+        let mut line = self.history_actions();
+        let max_street = max_street.unwrap_or(BoardState::River);
+        self.line_report_recursive(&mut ret, &mut line, max_street);
+
+        self.restore_navigation(&saved);
+        ret
+    }
+
+    /// Recursive function to build the line report.
+    fn line_report_recursive(
+        &mut self,
+        ret: &mut Vec<LineReport>,
+        line: &mut Vec<Action>,
+        max_street: BoardState,
+    ) {
+        if self.is_terminal_node() {
+            return;
+        }
+
+        let saved = self.save_navigation();
+
+        if self.is_chance_node() {
+            let street = if self.turn == NOT_DEALT {
+                BoardState::Turn
+            } else {
+                BoardState::River
+            };
+
+            if street > max_street || !self.ensure_street_loaded(street) {
+                return;
+            }
+
+            let possible_cards = self.possible_cards();
+            for card in 0..52 {
+                if possible_cards & (1 << card) != 0 {
+                    self.play(card as usize);
+                    line.push(Action::Chance(card));
+                    self.line_report_recursive(ret, line, max_street);
+                    line.pop();
+                    self.restore_navigation(&saved);
+                }
+            }
+
+            return;
+        }
+
+        self.cache_normalized_weights();
+
+        let player = self.current_player();
+        let actions = self.available_actions();
+
+        let (combos, equity, expected_value) = self.average_statistics();
+        let frequencies = self.average_frequencies(combos[player]);
+
+        let [pot_oop, pot_ip] = self.total_bet_amount;
+        ret.push(LineReport {
+            history: self.action_history.clone(),
+            line: line.clone(),
+            player,
+            pot: self.tree_config.starting_pot + pot_oop + pot_ip,
+            actions: actions.clone(),
+            frequencies,
+            combos,
+            equity,
+            expected_value,
+        });
+
+        for (index, &action) in actions.iter().enumerate() {
+            self.play(index);
+            line.push(action);
+            self.line_report_recursive(ret, line, max_street);
+            line.pop();
+            self.restore_navigation(&saved);
+        }
+    }
+}
+
+/// Aggregated statistics of a turn or river card, returned by [`PostFlopGame::runout_report`].
+///
+/// All averages are weighted by the normalized weights of the corresponding player.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunoutReport {
+    /// Dealt card.
+    pub card: Card,
+
+    /// Current player after the card is dealt. `None` if no decision is made after the card is
+    /// dealt (e.g., when a player is all-in).
+    pub player: Option<usize>,
+
+    /// Available actions of the current player after the card is dealt.
+    pub actions: Vec<Action>,
+
+    /// Aggregate frequency of each action of the current player.
+    pub frequencies: Vec<f32>,
+
+    /// Number of combinations of each player.
+    pub combos: [f32; 2],
+
+    /// Average equity of each player.
+    pub equity: [f32; 2],
+
+    /// Average expected value of each player.
+    pub expected_value: [f32; 2],
+}
+
+/// Breakdown of the expected values of an action by the responses of the opponent, returned by
+/// [`PostFlopGame::ev_breakdown`].
+///
+/// The vectors are of the length of `#(responses) * #(private hands)`, and the value of the
+/// `j`-th private hand for the `i`-th response is stored in the `i * #(private hands) + j`-th
+/// element. The private hands are those of the player taking the action.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvBreakdown {
+    /// Responses of the opponent.
+    pub responses: Vec<Action>,
+
+    /// Probability of each response given each private hand.
+    pub probabilities: Vec<f32>,
+
+    /// Expected value of each private hand after each response. As with
+    /// [`PostFlopGame::expected_values_detail`], the expected values are measured at the node
+    /// where the action is taken; that is, the amount of the action is already subtracted.
+    pub expected_values: Vec<f32>,
+}
+
+impl EvBreakdown {
+    /// Returns the contribution of the fold response to the expected value of each private hand,
+    /// i.e., the fold equity. If the opponent cannot fold, returns zeros.
+    pub fn fold_component(&self) -> Vec<f32> {
+        let num_hands = self.probabilities.len() / self.responses.len();
+        match self.responses.iter().position(|&a| a == Action::Fold) {
+            Some(i) => row(&self.probabilities, i, num_hands)
+                .iter()
+                .zip(row(&self.expected_values, i, num_hands))
+                .map(|(&p, &ev)| p * ev)
+                .collect(),
+            None => vec![0.0; num_hands],
+        }
+    }
+
+    /// Returns the contribution of the non-fold responses (i.e., calls and raises) to the expected
+    /// value of each private hand.
+    pub fn continue_component(&self) -> Vec<f32> {
+        let num_hands = self.probabilities.len() / self.responses.len();
+        let mut ret = vec![0.0; num_hands];
+        for (i, &action) in self.responses.iter().enumerate() {
+            if action != Action::Fold {
+                let probabilities = row(&self.probabilities, i, num_hands);
+                let expected_values = row(&self.expected_values, i, num_hands);
+                for (r, (&p, &ev)) in ret
+                    .iter_mut()
+                    .zip(probabilities.iter().zip(expected_values))
+                {
+                    *r += p * ev;
+                }
+            }
+        }
+        ret
+    }
+}
+
+impl PostFlopGame {
+    /// Returns the breakdown of the expected values of the given action of the current player by
+    /// the responses of the opponent.
+    ///
+    /// The sum of [`EvBreakdown::fold_component`] and [`EvBreakdown::continue_component`] equals
+    /// the expected value of the action returned by [`expected_values_detail`]. The probabilities
+    /// take the card removal effect (and the bunching effect, if set) into account. The current
+    /// node is not changed.
+    ///
+    /// Panics if the game is not solved, the current node is a terminal node or a chance node, or
+    /// the action does not lead to a decision node of the opponent.
+    ///
+    /// **Time complexity:** *O*(#(responses) * [`expected_values`]).
+    ///
+    /// [`expected_values`]: #method.expected_values
+    /// [`expected_values_detail`]: #method.expected_values_detail
+    pub fn ev_breakdown(&mut self, action: usize) -> EvBreakdown {
+        if self.state != State::Solved {
+            panic!("Game is not solved");
+        }
+
+        if self.is_terminal_node() {
+            panic!("Terminal node is not allowed");
+        }
+
+        if self.is_chance_node() {
+            panic!("Chance node is not allowed");
+        }
+
+        let saved = self.save_navigation();
+        let player = self.current_player();
+        let bet_amount = self.total_bet_amount[player];
+
+        self.play(action);
+        if self.is_terminal_node() || self.is_chance_node() {
+            self.restore_navigation(&saved);
+            panic!("Action does not lead to a decision node of the opponent");
+        }
+
+        // with the weights of the player set to one, the expected values are also available for
+        // the hands that do not take the action, and the normalized weights are the sums of the
+        // opponent's weights compatible with each hand, so their ratio is the probability of the
+        // response (the weights are restored by `restore_navigation`)
+        self.weights[player].fill(1.0);
+        self.is_normalized_weight_cached = false;
+
+        let responses = self.available_actions();
+
+        // measure the expected values from the current node, as in `expected_values_detail`
+        let bet_diff = (self.total_bet_amount[player] - bet_amount) as f32;
+        let mut expected_values = self.action_evs(player);
+        expected_values.iter_mut().for_each(|ev| *ev -= bet_diff);
+
+        self.cache_normalized_weights();
+        let base_weights = self.normalized_weights(player).to_vec();
+
+        let response_saved = self.save_navigation();
+        let mut probabilities = Vec::with_capacity(expected_values.len());
+        for response in 0..responses.len() {
+            self.play(response);
+            self.cache_normalized_weights();
+            let weights = self.normalized_weights(player);
+            probabilities.extend(weights.iter().zip(&base_weights).map(|(&w, &base)| {
+                if base > 0.0 {
+                    w / base
+                } else {
+                    0.0
+                }
+            }));
+            self.restore_navigation(&response_saved);
+        }
+
+        self.restore_navigation(&saved);
+
+        EvBreakdown {
+            responses,
+            probabilities,
+            expected_values,
+        }
+    }
+}
+
+impl PostFlopGame {
+    /// Returns the aggregated statistics of each card that can be dealt at the current chance
+    /// node, in ascending order of the card ID.
+    ///
+    /// Unlike [`available_actions`], isomorphic cards are reported individually. The current node
+    /// is not changed.
+    ///
+    /// Panics if the game is not solved or the current node is not a chance node.
+    ///
+    /// **Time complexity:** *O*(#(possible cards) * [`equity`]).
+    ///
+    /// [`available_actions`]: #method.available_actions
+    /// [`equity`]: #method.equity
+    pub fn runout_report(&mut self) -> Vec<RunoutReport> {
+        if self.state != State::Solved {
+            panic!("Game is not solved");
+        }
+
+        if !self.is_chance_node() {
+            panic!("Current node is not a chance node");
+        }
+
+        let saved = self.save_navigation();
+        let possible_cards = self.possible_cards();
+        let mut ret = Vec::with_capacity(possible_cards.count_ones() as usize);
+
+        for card in 0..52 {
+            if possible_cards & (1 << card) == 0 {
+                continue;
+            }
+
+            self.play(card as usize);
+            self.cache_normalized_weights();
+
+            let (combos, equity, expected_value) = self.average_statistics();
+            let (player, actions, frequencies) = if self.is_terminal_node() {
+                (None, Vec::new(), Vec::new())
+            } else {
+                let player = self.current_player();
+                let frequencies = self.average_frequencies(combos[player]);
+                (Some(player), self.available_actions(), frequencies)
+            };
+
+            ret.push(RunoutReport {
+                card,
+                player,
+                actions,
+                frequencies,
+                combos,
+                equity,
+                expected_value,
+            });
+
+            self.restore_navigation(&saved);
+        }
+
+        ret
+    }
+
+    /// Computes the number of combinations, the average equity, and the average expected value of
+    /// each player at the current node. The normalized weights must be cached.
+    fn average_statistics(&self) -> ([f32; 2], [f32; 2], [f32; 2]) {
+        let mut combos = [0.0; 2];
+        let mut equity = [0.0; 2];
+        let mut expected_value = [0.0; 2];
+
+        for player in 0..2 {
+            let weights = self.normalized_weights(player);
+            combos[player] = weights.iter().fold(0.0, |acc, &w| acc + w as f64) as f32;
+            if combos[player] > 0.0 {
+                equity[player] = compute_average(&self.equity(player), weights);
+                expected_value[player] = compute_average(&self.expected_values(player), weights);
+            }
+        }
+
+        (combos, equity, expected_value)
+    }
+
+    /// Computes the aggregate frequency of each action of the current player. The normalized
+    /// weights must be cached.
+    fn average_frequencies(&self, combos: f32) -> Vec<f32> {
+        if combos > 0.0 {
+            let weights = self.normalized_weights(self.current_player());
+            let num_hands = weights.len();
+            self.strategy()
+                .chunks_exact(num_hands)
+                .map(|strategy| compute_average(strategy, weights))
+                .collect()
+        } else {
+            vec![0.0; self.available_actions().len()]
+        }
+    }
+}
+
+/// Minimum defense frequency of the current node, returned by [`PostFlopGame::defense_report`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DefenseReport {
+    /// Pot size excluding the amount to call.
+    pub pot: i32,
+
+    /// Amount to call.
+    pub bet: i32,
+
+    /// Minimum defense frequency (see [`minimum_defense_frequency`]).
+    pub mdf: f32,
+
+    /// Break-even fold frequency of the bettor (see [`alpha`]).
+    pub alpha: f32,
+
+    /// Aggregate frequency with which the current player continues (i.e., does not fold).
+    pub defense_frequency: f32,
+}
+
+/// Bluffing statistics of a bet or raise action, returned by [`PostFlopGame::bet_report`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BetReport {
+    /// Index of the action.
+    pub index: usize,
+
+    /// Bet or raise action.
+    pub action: Action,
+
+    /// Pot size after the action, excluding the amount the opponent has to call. For a raise,
+    /// this includes the amount needed to call the previous bet.
+    pub pot: i32,
+
+    /// Amount the opponent has to call after the action.
+    pub bet: i32,
+
+    /// Aggregate frequency of the action.
+    pub frequency: f32,
+
+    /// Break-even fold frequency of a bluff (see [`alpha`]).
+    pub alpha: f32,
+
+    /// Fraction of bluffs that makes the opponent indifferent (see [`optimal_bluff_ratio`]).
+    pub optimal_bluff_ratio: f32,
+
+    /// Fraction of the combinations taking the action whose equity is less than 50%.
+    pub bluff_ratio: f32,
+}
+
+impl PostFlopGame {
+    /// Returns the minimum defense frequency of the current player against the last bet, along
+    /// with the solved defense frequency.
+    ///
+    /// Returns `None` if the current player is not facing a bet. The current node is not changed.
+    ///
+    /// Panics if the game is not solved or the current node is a terminal node or a chance node.
+    ///
+    /// **Time complexity:** *O*(#(actions) * #(private hands)).
+    pub fn defense_report(&mut self) -> Option<DefenseReport> {
+        if self.state != State::Solved {
+            panic!("Game is not solved");
+        }
+
+        if self.is_terminal_node() {
+            panic!("Terminal node is not allowed");
+        }
+
+        if self.is_chance_node() {
+            panic!("Chance node is not allowed");
+        }
+
+        let player = self.current_player();
+        let bet = self.total_bet_amount[player ^ 1] - self.total_bet_amount[player];
+        if bet <= 0 {
+            return None;
+        }
+
+        let [bet_oop, bet_ip] = self.total_bet_amount;
+        let pot = self.tree_config.starting_pot + bet_oop + bet_ip - bet;
+
+        self.cache_normalized_weights();
+        let combos = self.normalized_weights(player).iter().sum::<f32>();
+        let frequencies = self.average_frequencies(combos);
+        let fold_frequency = self
+            .available_actions()
+            .iter()
+            .zip(&frequencies)
+            .filter(|(&action, _)| action == Action::Fold)
+            .map(|(_, &frequency)| frequency)
+            .sum::<f32>();
+
+        Some(DefenseReport {
+            pot,
+            bet,
+            mdf: minimum_defense_frequency(pot, bet),
+            alpha: alpha(pot, bet),
+            defense_frequency: 1.0 - fold_frequency,
+        })
+    }
+
+    /// Returns the bluffing statistics of each bet or raise action of the current player.
+    ///
+    /// A combination is counted as a bluff if its equity against the opponent's range at the
+    /// current node is less than 50%; this is an approximation, as the equity against the
+    /// calling range is not considered. The current node is not changed.
+    ///
+    /// Panics if the game is not solved or the current node is a terminal node or a chance node.
+    ///
+    /// **Time complexity:** [`equity`] + *O*(#(actions) * #(private hands)).
+    ///
+    /// [`equity`]: #method.equity
+    pub fn bet_report(&mut self) -> Vec<BetReport> {
+        if self.state != State::Solved {
+            panic!("Game is not solved");
+        }
+
+        if self.is_terminal_node() {
+            panic!("Terminal node is not allowed");
+        }
+
+        if self.is_chance_node() {
+            panic!("Chance node is not allowed");
+        }
+
+        self.cache_normalized_weights();
+
+        let player = self.current_player();
+        let actions = self.available_actions();
+        let strategy = self.strategy();
+        let equity = self.equity(player);
+        let weights = self.normalized_weights(player).to_vec();
+        let num_hands = weights.len();
+        let total_weight = weights.iter().map(|&w| w as f64).sum::<f64>();
+
+        let saved = self.save_navigation();
+        let mut ret = Vec::new();
+
+        for (index, &action) in actions.iter().enumerate() {
+            if !matches!(action, Action::Bet(_) | Action::Raise(_) | Action::AllIn(_)) {
+                continue;
+            }
+
+            self.play(index);
+            let [bet_oop, bet_ip] = self.total_bet_amount;
+            let bet = self.total_bet_amount[player] - self.total_bet_amount[player ^ 1];
+            let pot = self.tree_config.starting_pot + bet_oop + bet_ip - bet;
+            self.restore_navigation(&saved);
+
+            let (mut action_weight, mut bluff_weight) = (0.0, 0.0);
+            let row = row(&strategy, index, num_hands);
+            for ((&w, &s), &e) in weights.iter().zip(row).zip(&equity) {
+                let w = w as f64 * s as f64;
+                action_weight += w;
+                if e < 0.5 {
+                    bluff_weight += w;
+                }
+            }
+
+            let ratio = |numer: f64, denom: f64| {
+                if denom > 0.0 {
+                    (numer / denom) as f32
+                } else {
+                    0.0
+                }
+            };
+
+            ret.push(BetReport {
+                index,
+                action,
+                pot,
+                bet,
+                frequency: ratio(action_weight, total_weight),
+                alpha: alpha(pot, bet),
+                optimal_bluff_ratio: optimal_bluff_ratio(pot, bet),
+                bluff_ratio: ratio(bluff_weight, action_weight),
+            });
+        }
+
+        ret
+    }
+}
+
+/// Distribution of the equities of a range, returned by [`PostFlopGame::equity_buckets`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct EquityDistribution {
+    /// Fraction of the combinations in each bucket. The `i`-th bucket covers the equities in
+    /// `[i / n, (i + 1) / n)`, where `n` is the number of buckets; the last bucket also includes
+    /// the equity of 1.
+    pub buckets: Vec<f32>,
+
+    /// Equity graph: pairs of (cumulative fraction of the combinations, equity), sorted in
+    /// descending order of equity. Hands that do not reach the current node are excluded.
+    pub curve: Vec<(f32, f32)>,
+}
+
+impl PostFlopGame {
+    /// Returns the distribution of the equities of the given player's range at the current node,
+    /// weighted by the normalized weights.
+    ///
+    /// The current node is not changed. Panics if `num_buckets` is zero or the current node is a
+    /// terminal node.
+    ///
+    /// **Time complexity:** [`equity`] + *O*(#(private hands) * log(#(private hands))).
+    ///
+    /// [`equity`]: #method.equity
+    pub fn equity_buckets(&mut self, player: usize, num_buckets: usize) -> EquityDistribution {
+        if num_buckets == 0 {
+            panic!("Number of buckets must be positive");
+        }
+
+        if self.is_terminal_node() {
+            panic!("Terminal node is not allowed");
+        }
+
+        self.cache_normalized_weights();
+        let equity = self.equity(player);
+        let weights = self.normalized_weights(player);
+        let total_weight = weights.iter().map(|&w| w as f64).sum::<f64>();
+
+        let mut buckets = vec![0.0; num_buckets];
+        let mut hands = Vec::new();
+        for (&e, &w) in equity.iter().zip(weights) {
+            if w > 0.0 {
+                let bucket = ((e * num_buckets as f32) as usize).min(num_buckets - 1);
+                buckets[bucket] += w as f64;
+                hands.push((e, w));
+            }
+        }
+
+        hands.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let mut cumulative = 0.0;
+        let curve = hands
+            .iter()
+            .map(|&(e, w)| {
+                cumulative += w as f64;
+                ((cumulative / total_weight) as f32, e)
+            })
+            .collect();
+
+        let buckets = buckets
+            .iter()
+            .map(|&b| {
+                if total_weight > 0.0 {
+                    (b / total_weight) as f32
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+
+        EquityDistribution { buckets, curve }
+    }
+}
+
+/// Strategy mixedness of the game, returned by [`PostFlopGame::strategy_mixedness`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MixednessReport {
+    /// Mixedness of each decision node, in depth-first order.
+    pub nodes: Vec<NodeMixedness>,
+
+    /// Average entropy over all decision nodes, weighted by the reach probability of each node.
+    pub entropy: f32,
+
+    /// Average fraction of pure combinations over all decision nodes, weighted by the reach
+    /// probability of each node.
+    pub pure_fraction: f32,
+}
+
+/// Strategy mixedness of a decision node.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeMixedness {
+    /// Action history from the root node (see [`PostFlopGame::history`]).
+    pub history: Vec<usize>,
+
+    /// Current player (`0` = OOP, `1` = IP).
+    pub player: usize,
+
+    /// Probability that the current player reaches the node, including the chance probability.
+    pub reach: f32,
+
+    /// Entropy of the strategy in bits, averaged over the combinations of the current player.
+    /// The value ranges from 0 (pure) to log2(#(actions)) (uniform).
+    pub entropy: f32,
+
+    /// Fraction of the combinations of the current player whose most frequent action exceeds the
+    /// threshold.
+    pub pure_fraction: f32,
+}
+
+impl PostFlopGame {
+    /// Returns the mixedness of the strategy at each decision node and its aggregate.
+    ///
+    /// A combination is considered pure if its most frequent action is taken with a probability
+    /// greater than `pure_threshold` (e.g., `0.9`). The combinations are weighted by their initial
+    /// weights multiplied by the reach probabilities, ignoring the card removal effect between
+    /// the players. Streets whose strategies are not stored are not included.
+    ///
+    /// Panics if the game is not solved.
+    ///
+    /// **Time complexity:** *O*(#(nodes) * #(private hands)).
+    pub fn strategy_mixedness(&self, pure_threshold: f32) -> MixednessReport {
+        if self.state != State::Solved {
+            panic!("Game is not solved");
+        }
+
+        let root_mass = [0, 1].map(|player| {
+            let mut board_mask: u64 = 0;
+            for &card in &self.card_config.flop {
+                board_mask |= 1 << card;
+            }
+            for card in [self.card_config.turn, self.card_config.river] {
+                if card != NOT_DEALT {
+                    board_mask |= 1 << card;
+                }
+            }
+            self.private_cards[player]
+                .iter()
+                .zip(&self.initial_weights[player])
+                .filter(|&(&(c1, c2), _)| ((1u64 << c1) | (1u64 << c2)) & board_mask == 0)
+                .map(|(_, &w)| w as f64)
+                .sum::<f64>()
+        });
+
+        let mut nodes = Vec::new();
+        self.visit_nodes(|view| {
+            let player = view.current_player();
+            let num_actions = view.available_actions().len();
+            let strategy = view.strategy();
+            let reach = view.reach_probabilities(player);
+            let num_hands = reach.len();
+
+            let (mut entropy, mut pure, mut mass) = (0.0, 0.0, 0.0);
+            for hand in 0..num_hands {
+                let weight = (self.initial_weights[player][hand] * reach[hand]) as f64;
+                if weight > 0.0 {
+                    let (mut hand_entropy, mut max_prob) = (0.0, 0.0f32);
+                    for action in 0..num_actions {
+                        let prob = strategy[action * num_hands + hand];
+                        if prob > 0.0 {
+                            hand_entropy -= prob as f64 * (prob as f64).log2();
+                        }
+                        max_prob = max_prob.max(prob);
+                    }
+                    entropy += weight * hand_entropy;
+                    if max_prob > pure_threshold {
+                        pure += weight;
+                    }
+                    mass += weight;
+                }
+            }
+
+            let normalize = |x: f64| if mass > 0.0 { (x / mass) as f32 } else { 0.0 };
+            let reach = if root_mass[player] > 0.0 {
+                view.chance_probability() * mass / root_mass[player]
+            } else {
+                0.0
+            };
+
+            nodes.push(NodeMixedness {
+                history: view.history().to_vec(),
+                player,
+                reach: reach as f32,
+                entropy: normalize(entropy),
+                pure_fraction: normalize(pure),
+            });
+        });
+
+        let (mut entropy, mut pure, mut weight_sum) = (0.0, 0.0, 0.0);
+        for node in &nodes {
+            let weight = node.reach as f64;
+            entropy += weight * node.entropy as f64;
+            pure += weight * node.pure_fraction as f64;
+            weight_sum += weight;
+        }
+
+        let normalize = |x: f64| {
+            if weight_sum > 0.0 {
+                (x / weight_sum) as f32
+            } else {
+                0.0
+            }
+        };
+
+        MixednessReport {
+            entropy: normalize(entropy),
+            pure_fraction: normalize(pure),
+            nodes,
+        }
+    }
+}
+
+/// Summary of the current node for both players, returned by [`PostFlopGame::node_summary`].
+///
+/// Each field is indexed by the player (`0` = OOP, `1` = IP).
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeSummary {
+    /// Normalized weights of each private hand (see [`PostFlopGame::normalized_weights`]).
+    pub normalized_weights: [Vec<f32>; 2],
+
+    /// Equity of each private hand (see [`PostFlopGame::equity`]).
+    pub equity: [Vec<f32>; 2],
+
+    /// Expected value of each private hand (see [`PostFlopGame::expected_values`]).
+    pub expected_values: [Vec<f32>; 2],
+
+    /// Average equity of each player, weighted by the normalized weights.
+    pub average_equity: [f32; 2],
+
+    /// Average expected value of each player, weighted by the normalized weights.
+    pub average_expected_value: [f32; 2],
+}
+
+impl PostFlopGame {
+    /// Returns the normalized weights, the equities, and the expected values of both players at
+    /// the current node.
+    ///
+    /// The normalized weights are cached only once, so this method is cheaper than calling
+    /// [`cache_normalized_weights`], [`equity`], and [`expected_values`] separately for each
+    /// player.
+    ///
+    /// Panics if the game is not solved.
+    ///
+    /// **Time complexity:** [`cache_normalized_weights`] + 2 * ([`equity`] + [`expected_values`]).
+    ///
+    /// [`cache_normalized_weights`]: #method.cache_normalized_weights
+    /// [`equity`]: #method.equity
+    /// [`expected_values`]: #method.expected_values
+    pub fn node_summary(&mut self) -> NodeSummary {
+        if self.state != State::Solved {
+            panic!("Game is not solved");
+        }
+
+        self.cache_normalized_weights();
+
+        let normalized_weights = [0, 1].map(|player| self.normalized_weights(player).to_vec());
+        let equity = [0, 1].map(|player| self.equity(player));
+        let expected_values = [0, 1].map(|player| self.expected_values(player));
+
+        let average_equity =
+            [0, 1].map(|player| compute_average(&equity[player], &normalized_weights[player]));
+        let average_expected_value = [0, 1]
+            .map(|player| compute_average(&expected_values[player], &normalized_weights[player]));
+
+        NodeSummary {
+            normalized_weights,
+            equity,
+            expected_values,
+            average_equity,
+            average_expected_value,
+        }
+    }
+}
+
+/// Aggregated strategy of a hand class, returned by [`PostFlopGame::strategy_by_class`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassStrategy {
+    /// Label of the hand class (e.g., "AA", "AKs", "AKo").
+    pub label: String,
+
+    /// Number of combinations reaching the current node (sum of the normalized weights).
+    pub combos: f32,
+
+    /// Frequency of each action, weighted by the normalized weights. All zero if no combination
+    /// reaches the current node.
+    pub frequencies: Vec<f32>,
+}
+
+/// Strategy of a combination, returned by [`PostFlopGame::strategy_by_suits`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComboStrategy {
+    /// Hole cards of the combination.
+    pub hand: (Card, Card),
+
+    /// Normalized weight of the combination.
+    pub weight: f32,
+
+    /// Frequency of each action.
+    pub frequencies: Vec<f32>,
+}
+
+impl PostFlopGame {
+    /// Returns the strategy of the current player aggregated into the 13x13 grid of hand classes.
+    ///
+    /// The return value has 169 elements in row-major order, where both the rows and the columns
+    /// run from A to 2. Pairs are on the diagonal, suited hands are above it (e.g., "AKs" is in
+    /// row 0, column 1), and offsuit hands are below it.
+    ///
+    /// Panics if the current node is a terminal node or a chance node. Also, panics if the memory
+    /// is not yet allocated.
+    ///
+    /// **Time complexity:** *O*(#(actions) * #(private hands)).
+    pub fn strategy_by_class(&mut self) -> Vec<ClassStrategy> {
+        let (player, num_actions, strategy) = self.class_strategy_internal();
+        let num_hands = self.private_cards[player].len();
+
+        let mut combos = vec![0.0f64; 169];
+        let mut sums = vec![vec![0.0f64; num_actions]; 169];
+        let weights = self.normalized_weights(player);
+
+        for (hand, &(c1, c2)) in self.private_cards[player].iter().enumerate() {
+            let weight = weights[hand] as f64;
+            if weight > 0.0 {
+                let index = class_grid_index(c1, c2);
+                combos[index] += weight;
+                for (action, sum) in sums[index].iter_mut().enumerate() {
+                    *sum += weight * strategy[action * num_hands + hand] as f64;
+                }
+            }
+        }
+
+        (0..169)
+            .map(|index| ClassStrategy {
+                label: class_label(index),
+                combos: combos[index] as f32,
+                frequencies: sums[index]
+                    .iter()
+                    .map(|&sum| {
+                        if combos[index] > 0.0 {
+                            (sum / combos[index]) as f32
+                        } else {
+                            0.0
+                        }
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// Returns the strategy of each combination of the given hand class (e.g., "AKs") of the
+    /// current player.
+    ///
+    /// Combinations that are not in the range or overlap with the board are excluded.
+    ///
+    /// Returns an error if the hand class is invalid. Panics if the current node is a terminal
+    /// node or a chance node. Also, panics if the memory is not yet allocated.
+    ///
+    /// **Time complexity:** *O*(#(actions) * #(private hands)).
+    pub fn strategy_by_suits(&mut self, class: &str) -> Result<Vec<ComboStrategy>, PostFlopError> {
+        let class = class.trim();
+        let index = (0..169)
+            .find(|&index| class_label(index) == class)
+            .ok_or_else(|| PostFlopError::InvalidHand {
+                input: class.to_string(),
+                reason: format!("Invalid hand class: {class}"),
+            })?;
+
+        let (player, num_actions, strategy) = self.class_strategy_internal();
+        let num_hands = self.private_cards[player].len();
+        let board = self.current_board();
+        let weights = self.normalized_weights(player);
+
+        Ok(self.private_cards[player]
+            .iter()
+            .enumerate()
+            .filter(|&(_, &(c1, c2))| {
+                class_grid_index(c1, c2) == index && !board.contains(&c1) && !board.contains(&c2)
+            })
+            .map(|(hand, &(c1, c2))| ComboStrategy {
+                hand: (c1, c2),
+                weight: weights[hand],
+                frequencies: (0..num_actions)
+                    .map(|action| strategy[action * num_hands + hand])
+                    .collect(),
+            })
+            .collect())
+    }
+
+    /// Caches the normalized weights and returns the current player, the number of actions, and
+    /// the strategy.
+    fn class_strategy_internal(&mut self) -> (usize, usize, Vec<f32>) {
+        if self.state < State::MemoryAllocated {
+            panic!("Memory is not allocated");
+        }
+
+        if self.is_terminal_node() {
+            panic!("Terminal node is not allowed");
+        }
+
+        if self.is_chance_node() {
+            panic!("Chance node is not allowed");
+        }
+
+        self.cache_normalized_weights();
+        let player = self.current_player();
+        let num_actions = self.available_actions().len();
+        (player, num_actions, self.strategy())
+    }
+}
+
+/// Returns the index of the hand class of the given hole cards in the 13x13 grid.
+fn class_grid_index(card1: Card, card2: Card) -> usize {
+    let (rank1, rank2) = (card1 as usize / 4, card2 as usize / 4);
+    let (high, low) = (12 - rank1.max(rank2), 12 - rank1.min(rank2));
+    if card1 & 3 == card2 & 3 {
+        high * 13 + low
+    } else {
+        low * 13 + high
+    }
+}
+
+/// Returns the label of the hand class at the given index of the 13x13 grid.
+fn class_label(index: usize) -> String {
+    const RANKS: &[u8; 13] = b"AKQJT98765432";
+    let (row, col) = (index / 13, index % 13);
+    let (high, low) = (RANKS[row.min(col)] as char, RANKS[row.max(col)] as char);
+    match row.cmp(&col) {
+        std::cmp::Ordering::Equal => format!("{high}{low}"),
+        std::cmp::Ordering::Less => format!("{high}{low}s"),
+        std::cmp::Ordering::Greater => format!("{high}{low}o"),
+    }
+}
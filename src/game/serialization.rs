@@ -1,8 +1,11 @@
 use super::*;
 
+use crate::file::{ChunkReader, ChunkWriter, LoadError, ReadSeek};
 use crate::interface::*;
+use crate::mutex_like::*;
 use crate::utility::*;
 use std::cell::Cell;
+use std::io::{Read, Seek, Write};
 use std::ptr;
 
 use bincode::{
@@ -30,27 +33,74 @@ impl PostFlopGame {
 
     /// Sets the target storage mode.
     #[inline]
-    pub fn set_target_storage_mode(&mut self, mode: BoardState) -> Result<(), String> {
+    pub fn set_target_storage_mode(&mut self, mode: BoardState) -> Result<(), PostFlopError> {
         if mode > self.storage_mode {
-            return Err("Cannot set target to a higher value than the current storage".to_string());
+            return Err(PostFlopError::InvalidArgument(
+                "Cannot set target to a higher value than the current storage".to_string(),
+            ));
         }
 
         if mode < self.tree_config.initial_state {
-            return Err("Cannot set target to a lower value than the initial state".to_string());
+            return Err(PostFlopError::InvalidArgument(
+                "Cannot set target to a lower value than the initial state".to_string(),
+            ));
         }
 
         self.target_storage_mode = mode;
         Ok(())
     }
 
+    /// Returns whether the game is loaded by memory-mapping a file.
+    #[inline]
+    pub fn is_memory_mapped(&self) -> bool {
+        #[cfg(feature = "mmap")]
+        return self.mapped_file.is_some();
+        #[cfg(not(feature = "mmap"))]
+        false
+    }
+
+    /// Sets the memory-mapped file that the nodes refer to.
+    #[cfg(feature = "mmap")]
+    pub(crate) fn set_mapped_file(&mut self, mmap: memmap2::Mmap) {
+        self.mapped_file = Some(mmap);
+    }
+
+    /// Returns whether only the strategies are stored in serialization.
+    #[inline]
+    pub fn target_strategy_only(&self) -> bool {
+        self.is_target_strategy_only
+    }
+
+    /// Sets whether only the strategies are stored in serialization.
+    ///
+    /// If enabled, the normalized strategies are stored in the compressed (16-bit) format
+    /// regardless of the compression setting, and nothing else is stored in the global storage.
+    /// The counterfactual values are recomputed when loading, so the loaded game supports all the
+    /// query methods, but it cannot be solved further. The target storage mode must be
+    /// `BoardState::River`.
+    #[inline]
+    pub fn set_target_strategy_only(&mut self, enable: bool) -> Result<(), PostFlopError> {
+        if enable && self.target_storage_mode != BoardState::River {
+            return Err(PostFlopError::InvalidState(
+                "Strategy-only mode requires the river storage mode".to_string(),
+            ));
+        }
+
+        self.is_target_strategy_only = enable;
+        Ok(())
+    }
+
     /// Returns the memory usage when the target storage mode is used for serialization.
     #[inline]
     pub fn target_memory_usage(&self) -> u64 {
+        if self.is_target_strategy_only {
+            return self.memory_usage_with_format(self.strategy_only_format());
+        }
+
         match self.target_storage_mode {
-            BoardState::River => match self.is_compression_enabled {
-                false => self.memory_usage().0,
-                true => self.memory_usage().1,
-            },
+            BoardState::River => {
+                self.memory_usage_with_formats(self.strategy_format, self.value_format)
+            }
             _ => {
                 let num_target_storage = self.num_target_storage();
                 num_target_storage.iter().map(|&x| x as u64).sum::<u64>() + self.misc_memory_usage
@@ -64,49 +114,508 @@ impl PostFlopGame {
             return [0; 4];
         }
 
-        let num_bytes = if self.is_compression_enabled { 2 } else { 4 };
+        if self.is_target_strategy_only {
+            let num_bytes = self.strategy_only_format().num_bytes();
+            return [num_bytes * self.num_storage as usize, 0, 0, 0];
+        }
+
         if self.target_storage_mode == BoardState::River {
             // omit storing the counterfactual values
+            let num_bytes = self.strategy_format.num_bytes();
             return [num_bytes * self.num_storage as usize, 0, 0, 0];
         }
 
-        let mut node_index = match self.target_storage_mode {
+        self.storage_prefix(self.target_storage_mode)
+    }
+
+    /// Returns the length of each global storage used by the nodes up to the given street.
+    fn storage_prefix(&self, street: BoardState) -> [usize; 4] {
+        if street < self.tree_config.initial_state {
+            return [0; 4];
+        }
+
+        if street >= self.storage_mode {
+            return [
+                self.storage1.len(),
+                self.storage2.len(),
+                self.storage_ip.len(),
+                self.storage_chance.len(),
+            ];
+        }
+
+        let strategy_bytes = self.strategy_format.num_bytes();
+        let value_bytes = self.value_format.num_bytes();
+        let mut node_index = match street {
             BoardState::Flop => self.num_nodes[0],
             _ => self.num_nodes[0] + self.num_nodes[1],
         } as usize;
 
         let mut num_storage = [0; 4];
 
-        while num_storage.iter().any(|&x| x == 0) {
+        // the counterfactual values are not used if trimmed
+        if self.is_cfvalues_trimmed {
+            num_storage[1..].fill(usize::MAX);
+        }
+
+        while num_storage.contains(&0) {
             node_index -= 1;
             let node = self.node_arena[node_index].lock();
             if num_storage[0] == 0 && !node.is_terminal() && !node.is_chance() {
-                let offset = unsafe { node.storage1.offset_from(self.storage1.as_ptr()) };
-                let offset_ip = unsafe { node.storage3.offset_from(self.storage_ip.as_ptr()) };
-                let len = num_bytes * node.num_elements as usize;
-                let len_ip = num_bytes * node.num_elements_ip as usize;
-                num_storage[0] = offset as usize + len;
-                num_storage[1] = offset as usize + len;
-                num_storage[2] = offset_ip as usize + len_ip;
+                let offset1 = unsafe { node.storage1.offset_from(self.storage1.as_ptr()) };
+                let num_elements = node.num_elements as usize;
+                num_storage[0] = offset1 as usize + strategy_bytes * num_elements;
+                if !self.is_cfvalues_trimmed {
+                    let offset2 = unsafe { node.storage2.offset_from(self.storage2.as_ptr()) };
+                    let offset_ip = unsafe { node.storage3.offset_from(self.storage_ip.as_ptr()) };
+                    let num_elements_ip = node.num_elements_ip as usize;
+                    num_storage[1] = offset2 as usize + value_bytes * num_elements;
+                    num_storage[2] = offset_ip as usize + value_bytes * num_elements_ip;
+                }
             }
             if num_storage[3] == 0 && node.is_chance() {
                 let offset = unsafe { node.storage1.offset_from(self.storage_chance.as_ptr()) };
-                let len = num_bytes * node.num_elements as usize;
+                let len = value_bytes * node.num_elements as usize;
                 num_storage[3] = offset as usize + len;
             }
         }
 
+        if self.is_cfvalues_trimmed {
+            num_storage[1..].fill(0);
+        }
+
         num_storage
     }
+
+    /// Returns the storage formats of the strategies and the other values.
+    #[inline]
+    fn storage_formats(&self) -> StorageFormats {
+        StorageFormats {
+            strategy: self.strategy_format,
+            value: self.value_format,
+        }
+    }
+
+    /// Returns the version string of the serialized game data.
+    #[inline]
+    pub(crate) fn data_version() -> &'static str {
+        VERSION_STR
+    }
 }
 
 static VERSION_STR: &str = "2023-03-19";
 
 thread_local! {
-    static PTR_BASE: Cell<[*const u8; 2]> = Cell::new([ptr::null(); 2]);
-    static CHANCE_BASE: Cell<*const u8> = Cell::new(ptr::null());
-    static PTR_BASE_MUT: Cell<[*mut u8; 3]> = Cell::new([ptr::null_mut(); 3]);
-    static CHANCE_BASE_MUT: Cell<*mut u8> = Cell::new(ptr::null_mut());
+    static PTR_BASE: Cell<[*const u8; 2]> = const { Cell::new([ptr::null(); 2]) };
+    static CHANCE_BASE: Cell<*const u8> = const { Cell::new(ptr::null()) };
+    static OFFSET_SHIFT: Cell<u32> = const { Cell::new(0) };
+    static PTR_BASE_MUT: Cell<[*mut u8; 3]> = const { Cell::new([ptr::null_mut(); 3]) };
+    static CHANCE_BASE_MUT: Cell<*mut u8> = const { Cell::new(ptr::null_mut()) };
+    static NUM_BYTES_MUT: Cell<[isize; 2]> = const { Cell::new([1; 2]) };
+}
+
+/// Number of chunks in the chunked format: the metadata, and the nodes and the four global storages
+/// of each street.
+const NUM_CHUNKS: usize = 16;
+
+/// Metadata chunk of the chunked format.
+type MetadataChunk = (
+    String,
+    State,
+    CardConfig,
+    TreeConfig,
+    Vec<Vec<Action>>,
+    Vec<Vec<Action>>,
+    Box<MutexLike<ActionTreeNode>>,
+    BoardState,
+    [u64; 3],
+    StorageFormats,
+    [u64; 3],
+    u64,
+    BTreeMap<usize, Vec<f32>>,
+);
+
+/// Chunks of the streets that are not loaded yet.
+pub(super) struct DeferredChunks {
+    reader: ChunkReader<Box<dyn ReadSeek>>,
+    storage_mode: BoardState,
+}
+
+impl PostFlopGame {
+    /// Returns the deepest street that can be loaded by [`load_deferred_streets`], or `None` if
+    /// there is no deferred street.
+    ///
+    /// [`load_deferred_streets`]: #method.load_deferred_streets
+    #[inline]
+    pub fn deferred_storage_mode(&self) -> Option<BoardState> {
+        self.deferred_chunks.as_ref().map(|d| d.storage_mode)
+    }
+
+    /// Loads the streets deferred by [`load_game_chunked_lazy`] up to the given street.
+    ///
+    /// Returns an error if the given street is not stored in the file or reading the file fails;
+    /// in particular, [`LoadError::Corrupted`] is returned if a chunk fails the checksum check.
+    ///
+    /// [`load_game_chunked_lazy`]: crate::load_game_chunked_lazy
+    /// [`LoadError::Corrupted`]: crate::LoadError::Corrupted
+    pub fn load_deferred_streets(&mut self, street: BoardState) -> Result<(), LoadError> {
+        if street <= self.storage_mode {
+            return Ok(());
+        }
+
+        let Some(mut deferred) = self.deferred_chunks.take() else {
+            return Err("Street is not stored".to_string().into());
+        };
+
+        let target = street.min(deferred.storage_mode);
+        let streets = [BoardState::Flop, BoardState::Turn, BoardState::River];
+
+        for &next in &streets[self.storage_mode as usize + 1..=target as usize] {
+            self.read_street_chunks(&mut deferred.reader, next)?;
+            self.storage_mode = next;
+            self.target_storage_mode = next;
+        }
+
+        if target < deferred.storage_mode {
+            self.deferred_chunks = Some(deferred);
+        }
+
+        if street > target {
+            return Err("Street is not stored".to_string().into());
+        }
+
+        Ok(())
+    }
+
+    /// Keeps the chunk reader to load the deferred streets later.
+    pub(crate) fn defer_chunks(
+        &mut self,
+        reader: ChunkReader<Box<dyn ReadSeek>>,
+        storage_mode: BoardState,
+    ) {
+        self.deferred_chunks = Some(DeferredChunks {
+            reader,
+            storage_mode,
+        });
+    }
+
+    /// Returns the estimated memory usage when loading the chunked format up to each street.
+    pub(crate) fn chunked_memory_usage(&self) -> [u64; 3] {
+        [BoardState::Flop, BoardState::Turn, BoardState::River].map(|street| {
+            let street = street.min(self.storage_mode);
+            let prefix = self.storage_prefix(street);
+            prefix.iter().map(|&x| x as u64).sum::<u64>() + self.misc_memory_usage
+        })
+    }
+
+    /// Writes the game in the chunked format.
+    pub(crate) fn write_chunks<W: Write>(
+        &self,
+        writer: &mut ChunkWriter<W>,
+    ) -> Result<(), PostFlopError> {
+        if self.is_memory_mapped() {
+            return Err(PostFlopError::Unsupported(
+                "Memory-mapped game cannot be saved".to_string(),
+            ));
+        }
+
+        if self.is_cfvalues_trimmed {
+            return Err(PostFlopError::Unsupported(
+                "Trimmed game cannot be saved".to_string(),
+            ));
+        }
+
+        if self.is_weight_modified {
+            return Err(PostFlopError::Unsupported(
+                "Game with modified weights cannot be saved".to_string(),
+            ));
+        }
+
+        if self.is_hand_abstracted {
+            return Err(PostFlopError::Unsupported(
+                "Game with hand abstraction cannot be saved".to_string(),
+            ));
+        }
+
+        if !self.card_buckets.is_empty() {
+            return Err(PostFlopError::Unsupported(
+                "Game with card bucketing cannot be saved".to_string(),
+            ));
+        }
+
+        writer.write_chunk((
+            VERSION_STR,
+            &self.state,
+            &self.card_config,
+            &self.tree_config,
+            &self.added_lines,
+            &self.removed_lines,
+            &self.action_root,
+            &self.storage_mode,
+            &self.num_nodes,
+            &self.storage_formats(),
+            &[
+                self.num_storage,
+                self.num_storage_ip,
+                self.num_storage_chance,
+            ],
+            &self.misc_memory_usage,
+            &self.locking_strategy,
+        ))?;
+
+        PTR_BASE.with(|c| c.set([self.storage1.as_ptr(), self.storage_ip.as_ptr()]));
+        CHANCE_BASE.with(|c| c.set(self.storage_chance.as_ptr()));
+        OFFSET_SHIFT.with(|c| c.set(0));
+
+        let mut node_start = 0;
+        let mut prev_prefix = [0; 4];
+
+        for street in [BoardState::Flop, BoardState::Turn, BoardState::River] {
+            // streets that are not stored are written as empty chunks
+            let (node_end, prefix) = if street <= self.storage_mode {
+                let num_nodes = self.num_nodes[street as usize] as usize;
+                (node_start + num_nodes, self.storage_prefix(street))
+            } else {
+                (node_start, prev_prefix)
+            };
+
+            writer.write_chunk(&self.node_arena[node_start..node_end])?;
+            writer.write_raw_chunk(&self.storage1[prev_prefix[0]..prefix[0]])?;
+            writer.write_raw_chunk(&self.storage2[prev_prefix[1]..prefix[1]])?;
+            writer.write_raw_chunk(&self.storage_ip[prev_prefix[2]..prefix[2]])?;
+            writer.write_raw_chunk(&self.storage_chance[prev_prefix[3]..prefix[3]])?;
+
+            node_start = node_end;
+            prev_prefix = prefix;
+        }
+
+        Ok(())
+    }
+
+    /// Reads the game in the chunked format up to the given street, and returns it with the
+    /// storage mode of the file.
+    ///
+    /// If `mapped` is given, it must be the whole file, and the nodes refer to the storage chunks
+    /// in it instead of copying them into the global storage.
+    pub(crate) fn read_chunks<R: Read + Seek>(
+        reader: &mut ChunkReader<R>,
+        max_street: BoardState,
+        mapped: Option<&[u8]>,
+    ) -> Result<(Self, BoardState), LoadError> {
+        if reader.num_chunks() != NUM_CHUNKS {
+            return Err("Chunk index is invalid".to_string().into());
+        }
+
+        let (
+            version,
+            state,
+            card_config,
+            tree_config,
+            added_lines,
+            removed_lines,
+            action_root,
+            storage_mode,
+            num_nodes,
+            formats,
+            [num_storage, num_storage_ip, num_storage_chance],
+            misc_memory_usage,
+            locking_strategy,
+        ): MetadataChunk = reader.read_chunk(0)?;
+
+        if version != VERSION_STR {
+            return Err(
+                format!("Version mismatch: expected '{VERSION_STR}', but got '{version}'").into(),
+            );
+        }
+
+        if max_street < tree_config.initial_state {
+            let message = "Cannot load a lower street than the initial state";
+            return Err(message.to_string().into());
+        }
+
+        let file_storage_mode = storage_mode;
+        let storage_mode = storage_mode.min(max_street);
+
+        let mut game = Self {
+            state,
+            card_config,
+            tree_config,
+            added_lines,
+            removed_lines,
+            action_root,
+            storage_mode,
+            target_storage_mode: storage_mode,
+            num_nodes,
+            strategy_format: formats.strategy,
+            value_format: formats.value,
+            num_storage,
+            num_storage_ip,
+            num_storage_chance,
+            misc_memory_usage,
+            locking_strategy,
+            ..Default::default()
+        };
+
+        let streets = [BoardState::Flop, BoardState::Turn, BoardState::River];
+        let mut prefix = [0; 4];
+
+        for &street in &streets[..=storage_mode as usize] {
+            let Some(mapped) = mapped else {
+                game.read_street_chunks(reader, street)?;
+                continue;
+            };
+
+            // base pointers such that the offsets from the beginning of the global storages point
+            // to the storage chunks of this street
+            let chunk = 2 + 5 * street as usize;
+            let bases = [0, 1, 2, 3].map(|i| {
+                let range = reader.chunk_range(chunk + i);
+                let len = range.len();
+                let ptr = mapped[range].as_ptr().wrapping_sub(prefix[i]);
+                prefix[i] += len;
+                ptr as *mut u8
+            });
+
+            game.set_decode_bases(bases);
+
+            let nodes: Vec<MutexLike<PostFlopNode>> = reader.read_chunk(chunk - 1)?;
+            game.node_arena.extend(nodes);
+        }
+
+        // initialization
+        game.check_card_config().map_err(String::from)?;
+        game.init_card_fields();
+        game.init_interpreter();
+        game.back_to_root();
+
+        Ok((game, file_storage_mode))
+    }
+
+    /// Sets the base pointers of the global storages, which are used when decoding the nodes.
+    fn set_decode_bases(&self, bases: [*mut u8; 4]) {
+        let num_bytes = [self.strategy_format, self.value_format].map(|f| f.num_bytes() as isize);
+        PTR_BASE_MUT.with(|c| c.set([bases[0], bases[1], bases[2]]));
+        CHANCE_BASE_MUT.with(|c| c.set(bases[3]));
+        NUM_BYTES_MUT.with(|c| c.set(num_bytes));
+    }
+
+    /// Appends the nodes and the global storages of the given street read from the chunks.
+    fn read_street_chunks<R: Read + Seek>(
+        &mut self,
+        reader: &mut ChunkReader<R>,
+        street: BoardState,
+    ) -> Result<(), LoadError> {
+        let chunk = 2 + 5 * street as usize;
+        let old_bases = [
+            self.storage1.as_ptr(),
+            self.storage2.as_ptr(),
+            self.storage_ip.as_ptr(),
+            self.storage_chance.as_ptr(),
+        ];
+
+        self.storage1.append(&mut reader.read_raw_chunk(chunk)?);
+        self.storage2.append(&mut reader.read_raw_chunk(chunk + 1)?);
+        self.storage_ip
+            .append(&mut reader.read_raw_chunk(chunk + 2)?);
+        self.storage_chance
+            .append(&mut reader.read_raw_chunk(chunk + 3)?);
+
+        let bases = [
+            self.storage1.as_mut_ptr(),
+            self.storage2.as_mut_ptr(),
+            self.storage_ip.as_mut_ptr(),
+            self.storage_chance.as_mut_ptr(),
+        ];
+
+        // the global storages may have been reallocated
+        let rebase = |ptr: &mut *mut u8, i: usize| {
+            *ptr = bases[i].wrapping_add(*ptr as usize - old_bases[i] as usize);
+        };
+
+        for node in &self.node_arena {
+            let mut node = node.lock();
+            if node.is_terminal() {
+                // do nothing
+            } else if node.is_chance() {
+                rebase(&mut node.storage1, 3);
+            } else {
+                rebase(&mut node.storage1, 0);
+                rebase(&mut node.storage2, 1);
+                rebase(&mut node.storage3, 2);
+            }
+        }
+
+        self.set_decode_bases(bases);
+
+        let nodes: Vec<MutexLike<PostFlopNode>> = reader.read_chunk(chunk - 1)?;
+        self.node_arena.extend(nodes);
+
+        Ok(())
+    }
+}
+
+/// Storage formats of the strategies and the other values.
+#[derive(Clone, Copy)]
+struct StorageFormats {
+    strategy: StorageFormat,
+    value: StorageFormat,
+}
+
+impl StorageFormats {
+    #[inline]
+    fn code(format: StorageFormat) -> u8 {
+        match format {
+            StorageFormat::Float32 => 0,
+            StorageFormat::Int16 => 1,
+            StorageFormat::Int8 => 2,
+            StorageFormat::Float16 => 3,
+        }
+    }
+
+    #[inline]
+    fn from_code(code: u8) -> Result<StorageFormat, DecodeError> {
+        match code {
+            0 => Ok(StorageFormat::Float32),
+            1 => Ok(StorageFormat::Int16),
+            2 => Ok(StorageFormat::Int8),
+            3 => Ok(StorageFormat::Float16),
+            _ => Err(DecodeError::OtherString(format!(
+                "Invalid storage format: {code}"
+            ))),
+        }
+    }
+}
+
+// encoded as a `u8` so that `Float32` and `Int16` are compatible with the former `bool` flag of
+// whether the compression is enabled: the lower 4 bits are the value format, and the upper 4 bits
+// are the strategy format plus one if it differs from the value format (zero otherwise)
+impl Encode for StorageFormats {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        let mut code = Self::code(self.value);
+        if self.strategy != self.value {
+            code |= (Self::code(self.strategy) + 1) << 4;
+        }
+        code.encode(encoder)
+    }
+}
+
+impl<C> Decode<C> for StorageFormats {
+    fn decode<D: Decoder<Context = C>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        let code = u8::decode(decoder)?;
+        let value = Self::from_code(code & 0xf)?;
+        let strategy = match code >> 4 {
+            0 => value,
+            upper => Self::from_code(upper - 1)?,
+        };
+        Ok(Self { strategy, value })
+    }
+}
+
+// loaded storages are always allocated in RAM
+impl<C> Decode<C> for StorageBuffer {
+    #[inline]
+    fn decode<D: Decoder<Context = C>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        Vec::<u8>::decode(decoder).map(Self::from)
+    }
 }
 
 impl Encode for PostFlopGame {
@@ -115,6 +624,39 @@ impl Encode for PostFlopGame {
             return Err(EncodeError::Other("Game is not successfully initialized"));
         }
 
+        if self.is_memory_mapped() {
+            return Err(EncodeError::Other("Memory-mapped game cannot be saved"));
+        }
+
+        if self.is_cfvalues_trimmed {
+            return Err(EncodeError::Other("Trimmed game cannot be saved"));
+        }
+
+        if self.is_weight_modified {
+            return Err(EncodeError::Other(
+                "Game with modified weights cannot be saved",
+            ));
+        }
+
+        if self.is_hand_abstracted {
+            return Err(EncodeError::Other(
+                "Game with hand abstraction cannot be saved",
+            ));
+        }
+
+        if !self.card_buckets.is_empty() {
+            return Err(EncodeError::Other(
+                "Game with card bucketing cannot be saved",
+            ));
+        }
+
+        let strategy_only = self.is_target_strategy_only;
+        if strategy_only && self.target_storage_mode != BoardState::River {
+            return Err(EncodeError::Other(
+                "Strategy-only mode requires the river storage mode",
+            ));
+        }
+
         let num_storage = self.num_target_storage();
 
         // version
@@ -129,12 +671,26 @@ impl Encode for PostFlopGame {
         self.action_root.encode(encoder)?;
         self.target_storage_mode.encode(encoder)?;
         self.num_nodes.encode(encoder)?;
-        self.is_compression_enabled.encode(encoder)?;
+        match strategy_only {
+            false => self.storage_formats().encode(encoder)?,
+            true => {
+                let format = self.strategy_only_format();
+                StorageFormats {
+                    strategy: format,
+                    value: format,
+                }
+                .encode(encoder)?
+            }
+        }
         self.num_storage.encode(encoder)?;
         self.num_storage_ip.encode(encoder)?;
         self.num_storage_chance.encode(encoder)?;
         self.misc_memory_usage.encode(encoder)?;
-        self.storage1[0..num_storage[0]].encode(encoder)?;
+        if strategy_only {
+            self.quantized_strategy().encode(encoder)?;
+        } else {
+            self.storage1[0..num_storage[0]].encode(encoder)?;
+        }
         self.storage2[0..num_storage[1]].encode(encoder)?;
         self.storage_ip[0..num_storage[2]].encode(encoder)?;
         self.storage_chance[0..num_storage[3]].encode(encoder)?;
@@ -167,6 +723,14 @@ impl Encode for PostFlopGame {
             }
         });
 
+        // 32-bit offsets are converted to 16-bit offsets in the strategy-only mode
+        let shift = match strategy_only {
+            false => 0,
+            true => (self.strategy_format.num_bytes() / self.strategy_only_format().num_bytes())
+                .trailing_zeros(),
+        };
+        OFFSET_SHIFT.with(|c| c.set(shift));
+
         // game tree
         self.node_arena[0..num_nodes].encode(encoder)?;
 
@@ -194,7 +758,13 @@ impl Decode<()> for PostFlopGame {
             action_root: Decode::decode(decoder)?,
             storage_mode: Decode::decode(decoder)?,
             num_nodes: Decode::decode(decoder)?,
-            is_compression_enabled: Decode::decode(decoder)?,
+            ..Default::default()
+        };
+
+        let formats = StorageFormats::decode(decoder)?;
+        game = Self {
+            strategy_format: formats.strategy,
+            value_format: formats.value,
             num_storage: Decode::decode(decoder)?,
             num_storage_ip: Decode::decode(decoder)?,
             num_storage_chance: Decode::decode(decoder)?,
@@ -204,43 +774,36 @@ impl Decode<()> for PostFlopGame {
             storage_ip: Decode::decode(decoder)?,
             storage_chance: Decode::decode(decoder)?,
             locking_strategy: Decode::decode(decoder)?,
-            ..Default::default()
+            ..game
         };
 
         game.target_storage_mode = game.storage_mode;
         if game.storage_mode == BoardState::River && game.state >= State::MemoryAllocated {
-            let num_bytes = if game.is_compression_enabled { 2 } else { 4 };
-            game.storage2 = vec![0; (num_bytes * game.num_storage) as usize];
-            game.storage_ip = vec![0; (num_bytes * game.num_storage_ip) as usize];
-            game.storage_chance = vec![0; (num_bytes * game.num_storage_chance) as usize];
+            let num_bytes = game.value_format.num_bytes() as u64;
+            game.storage2 = vec![0; (num_bytes * game.num_storage) as usize].into();
+            game.storage_ip = vec![0; (num_bytes * game.num_storage_ip) as usize].into();
+            game.storage_chance = vec![0; (num_bytes * game.num_storage_chance) as usize].into();
         }
 
         // store base pointers
-        PTR_BASE_MUT.with(|c| {
-            if game.state >= State::MemoryAllocated {
-                c.set([
-                    game.storage1.as_mut_ptr(),
-                    game.storage2.as_mut_ptr(),
-                    game.storage_ip.as_mut_ptr(),
-                ]);
-            } else {
-                c.set([ptr::null_mut(); 3]);
-            }
-        });
-
-        CHANCE_BASE_MUT.with(|c| {
-            if game.state >= State::MemoryAllocated {
-                c.set(game.storage_chance.as_mut_ptr());
-            } else {
-                c.set(ptr::null_mut());
-            }
-        });
+        let bases = if game.state >= State::MemoryAllocated {
+            [
+                game.storage1.as_mut_ptr(),
+                game.storage2.as_mut_ptr(),
+                game.storage_ip.as_mut_ptr(),
+                game.storage_chance.as_mut_ptr(),
+            ]
+        } else {
+            [ptr::null_mut(); 4]
+        };
+        game.set_decode_bases(bases);
 
         // game tree
         game.node_arena = Decode::decode(decoder)?;
 
         // initialization
-        game.check_card_config().map_err(DecodeError::OtherString)?;
+        game.check_card_config()
+            .map_err(|e| DecodeError::OtherString(e.to_string()))?;
         game.init_card_fields();
         game.init_interpreter();
         game.back_to_root();
@@ -278,12 +841,14 @@ impl Encode for PostFlopNode {
                 // do nothing
             } else if self.is_chance() {
                 let base = CHANCE_BASE.with(|c| c.get());
-                unsafe { self.storage1.offset_from(base).encode(encoder)? };
+                let shift = OFFSET_SHIFT.with(|c| c.get());
+                unsafe { (self.storage1.offset_from(base) >> shift).encode(encoder)? };
             } else {
                 let bases = PTR_BASE.with(|c| c.get());
+                let shift = OFFSET_SHIFT.with(|c| c.get());
                 unsafe {
-                    self.storage1.offset_from(bases[0]).encode(encoder)?;
-                    self.storage3.offset_from(bases[1]).encode(encoder)?;
+                    (self.storage1.offset_from(bases[0]) >> shift).encode(encoder)?;
+                    (self.storage3.offset_from(bases[1]) >> shift).encode(encoder)?;
                 }
             }
         }
@@ -318,16 +883,18 @@ impl Decode<()> for PostFlopNode {
         } else if node.is_chance() {
             let base = CHANCE_BASE_MUT.with(|c| c.get());
             if !base.is_null() {
-                node.storage1 = unsafe { base.offset(isize::decode(decoder)?) };
+                node.storage1 = base.wrapping_offset(isize::decode(decoder)?);
             }
         } else {
             let bases = PTR_BASE_MUT.with(|c| c.get());
             if !bases[0].is_null() {
                 let offset = isize::decode(decoder)?;
                 let offset_ip = isize::decode(decoder)?;
-                node.storage1 = unsafe { bases[0].offset(offset) };
-                node.storage2 = unsafe { bases[1].offset(offset) };
-                node.storage3 = unsafe { bases[2].offset(offset_ip) };
+                node.storage1 = bases[0].wrapping_offset(offset);
+                // the offsets of the regrets are scaled when the storage formats differ
+                let [strategy_bytes, value_bytes] = NUM_BYTES_MUT.with(|c| c.get());
+                node.storage2 = bases[1].wrapping_offset(offset / strategy_bytes * value_bytes);
+                node.storage3 = bases[2].wrapping_offset(offset_ip);
             }
         }
 
@@ -0,0 +1,338 @@
+use super::*;
+use crate::sliceop::*;
+use crate::solver::*;
+use crate::utility::*;
+
+/// Number of iterations represented by the warm-start values. The re-solve continues the
+/// discounting schedule from this iteration, so the warm-start values are not discarded by the
+/// first discount.
+const WARM_START_ITERATIONS: u32 = 2;
+
+/// Result of [`PostFlopGame::simplify_tree`].
+pub struct SimplifiedTree {
+    /// Simplified game, solved.
+    pub game: PostFlopGame,
+
+    /// Lines removed from the action tree (the chance actions are omitted, as in
+    /// [`ActionTree::remove_line`]).
+    pub removed_lines: Vec<Vec<Action>>,
+
+    /// Average root expected value of each player in the original game.
+    pub expected_values: [f32; 2],
+
+    /// Average root expected value of each player in the simplified game.
+    pub simplified_expected_values: [f32; 2],
+
+    /// Difference of the average root expected value of each player (simplified minus original).
+    pub ev_deltas: [f32; 2],
+
+    /// Exploitability of the simplified game.
+    pub exploitability: f32,
+
+    /// Number of iterations of the re-solve.
+    pub num_iterations: u32,
+}
+
+/// Aggregate frequency of the actions of a line: the actions, the reach-weighted mass of each
+/// action, and the total mass.
+type LineFrequency = (Vec<Action>, Vec<f64>, f64);
+
+impl PostFlopGame {
+    /// Removes the bet actions taken less often than `threshold` and re-solves the simplified
+    /// tree, warm-started from the strategies of this game.
+    ///
+    /// The frequency of an action is aggregated over all the nodes of its line (i.e., all the
+    /// runouts), weighted by the reach probabilities of both players, ignoring the card removal
+    /// effect between the players. The bet, raise, and all-in actions whose frequency is below
+    /// `threshold` are removed from the action tree; the check, fold, and call actions and the
+    /// lines never reached are kept. The simplified game is then initialized with the strategies of
+    /// this game projected onto the remaining actions (the frequencies of the removed actions are
+    /// redistributed proportionally) and solved by Discounted CFR with the given parameters. The
+    /// hand abstraction, the card bucketing, and the weights modified by [`set_weights`] are carried
+    /// over. The current node is not changed.
+    ///
+    /// Returns an error if `threshold` is not in the range `[0.0, 1.0)`, the tree has edited
+    /// lines, or the bunching effect is enabled. Panics if the game is not solved, the strategies
+    /// of all streets are not stored, or the expected values are trimmed.
+    ///
+    /// **Time complexity:** *O*(#(nodes) * #(private hands)) + (solving the simplified game).
+    ///
+    /// [`set_weights`]: #method.set_weights
+    pub fn simplify_tree(
+        &mut self,
+        threshold: f32,
+        max_num_iterations: u32,
+        target_exploitability: f32,
+    ) -> Result<SimplifiedTree, PostFlopError> {
+        if self.state != State::Solved {
+            panic!("Game is not solved");
+        }
+
+        if self.storage_mode != BoardState::River {
+            panic!("Strategies of all streets are not stored");
+        }
+
+        if !(0.0..1.0).contains(&threshold) {
+            return Err(PostFlopError::InvalidArgument(format!(
+                "Invalid threshold: {threshold}"
+            )));
+        }
+
+        if !self.added_lines.is_empty() || !self.removed_lines.is_empty() {
+            return Err(PostFlopError::Unsupported(
+                "Game with edited lines is not supported".to_string(),
+            ));
+        }
+
+        if self.bunching_num_dead_cards != 0 {
+            return Err(PostFlopError::Unsupported(
+                "Bunching effect is not supported".to_string(),
+            ));
+        }
+
+        let saved = self.save_navigation();
+        self.back_to_root();
+        let expected_values = self.node_summary().average_expected_value;
+        self.restore_navigation(&saved);
+
+        // aggregate the frequencies of the lines
+        let mut frequencies = BTreeMap::new();
+        self.line_frequency_recursive(
+            &self.root(),
+            &mut Vec::new(),
+            &self.initial_weights,
+            &mut frequencies,
+        );
+
+        // `BTreeMap` visits a line before its descendants
+        let mut removed_lines = Vec::<Vec<Action>>::new();
+        for (line, (actions, masses, total)) in &frequencies {
+            if *total <= 0.0 || removed_lines.iter().any(|l| line.starts_with(l)) {
+                continue;
+            }
+            for (&action, &mass) in actions.iter().zip(masses) {
+                let is_bet = matches!(action, Action::Bet(_) | Action::Raise(_) | Action::AllIn(_));
+                if is_bet && mass / total < threshold as f64 {
+                    let mut removed_line = line.clone();
+                    removed_line.push(action);
+                    removed_lines.push(removed_line);
+                }
+            }
+        }
+
+        let mut action_tree = ActionTree::new(self.tree_config.clone())?;
+        for line in &removed_lines {
+            action_tree.remove_line(line)?;
+        }
+
+        let mut game = self.rebuild_with_action_tree(action_tree)?;
+        let initial_weights = game.initial_weights.clone();
+        self.warm_start_recursive(&game, &self.root(), &mut game.root(), &initial_weights);
+
+        // Discounted CFR continuing from the warm-start iteration
+        let mut exploitability = compute_exploitability(&game);
+        let mut num_iterations = 0;
+        for t in 0..max_num_iterations {
+            if exploitability <= target_exploitability {
+                break;
+            }
+
+            solve_step(&game, WARM_START_ITERATIONS + t);
+            num_iterations += 1;
+
+            if (t + 1) % 10 == 0 || t + 1 == max_num_iterations {
+                exploitability = compute_exploitability(&game);
+            }
+        }
+
+        finalize(&mut game);
+
+        let simplified_expected_values = game.node_summary().average_expected_value;
+        let ev_deltas = [0, 1].map(|p| simplified_expected_values[p] - expected_values[p]);
+
+        Ok(SimplifiedTree {
+            game,
+            removed_lines,
+            expected_values,
+            simplified_expected_values,
+            ev_deltas,
+            exploitability,
+            num_iterations,
+        })
+    }
+
+    /// Recursive function to aggregate the frequencies of the actions of each line.
+    fn line_frequency_recursive(
+        &self,
+        node: &PostFlopNode,
+        line: &mut Vec<Action>,
+        reach: &[Vec<f32>; 2],
+        frequencies: &mut BTreeMap<Vec<Action>, LineFrequency>,
+    ) {
+        if node.is_terminal() {
+            return;
+        }
+
+        if node.is_chance() {
+            // the isomorphic chances have the same frequencies as their representatives
+            let mut multiplicities = vec![1.0; node.num_actions()];
+            for &index in self.isomorphic_chances(node) {
+                multiplicities[index as usize] += 1.0;
+            }
+
+            let factor = 1.0 / self.chance_factor(node) as f32;
+            for (action, &multiplicity) in multiplicities.iter().enumerate() {
+                let mut reach = reach.clone();
+                reach[0]
+                    .iter_mut()
+                    .for_each(|r| *r *= factor * multiplicity);
+                self.line_frequency_recursive(&node.play(action), line, &reach, frequencies);
+            }
+
+            return;
+        }
+
+        let player = node.player();
+        let num_actions = node.num_actions();
+        let num_hands = reach[player].len();
+        let strategy = self.node_strategy(node);
+        let opponent_mass = reach[player ^ 1].iter().map(|&r| r as f64).sum::<f64>();
+
+        let (_, masses, total) = frequencies.entry(line.clone()).or_insert_with(|| {
+            let actions = node.children().iter().map(|c| c.lock().prev_action);
+            (actions.collect(), vec![0.0; num_actions], 0.0)
+        });
+
+        *total += opponent_mass * reach[player].iter().map(|&r| r as f64).sum::<f64>();
+        for (mass, row) in masses.iter_mut().zip(strategy.chunks_exact(num_hands)) {
+            let dot = reach[player].iter().zip(row);
+            *mass += opponent_mass * dot.map(|(&r, &s)| r as f64 * s as f64).sum::<f64>();
+        }
+
+        for action in 0..num_actions {
+            let child = node.play(action);
+            let mut reach = reach.clone();
+            mul_slice(&mut reach[player], row(&strategy, action, num_hands));
+            line.push(child.prev_action);
+            self.line_frequency_recursive(&child, line, &reach, frequencies);
+            line.pop();
+        }
+    }
+
+    /// Recursive function to initialize the cumulative strategies and regrets of `new_node` of
+    /// `game` with the strategy of `node` projected onto the remaining actions.
+    fn warm_start_recursive(
+        &self,
+        game: &PostFlopGame,
+        node: &PostFlopNode,
+        new_node: &mut PostFlopNode,
+        reach: &[Vec<f32>; 2],
+    ) {
+        if new_node.is_terminal() {
+            return;
+        }
+
+        if new_node.is_chance() {
+            let factor = 1.0 / self.chance_factor(node) as f32;
+            let mut reach = reach.clone();
+            reach[0].iter_mut().for_each(|r| *r *= factor);
+            for action in 0..new_node.num_actions() {
+                let child = node.play(action);
+                self.warm_start_recursive(game, &child, &mut new_node.play(action), &reach);
+            }
+            return;
+        }
+
+        let player = new_node.player();
+        let num_hands = reach[player].len();
+        let strategy = self.node_strategy(node);
+
+        // index of each remaining action in `node`
+        let prev_actions = |node: &PostFlopNode| {
+            let children = node.children().iter();
+            children.map(|c| c.lock().prev_action).collect::<Vec<_>>()
+        };
+        let actions = prev_actions(node);
+        let indices = prev_actions(new_node)
+            .iter()
+            .map(|a| actions.iter().position(|b| b == a).unwrap())
+            .collect::<Vec<_>>();
+
+        // project the strategy onto the remaining actions
+        let num_actions = indices.len();
+        let mut projected = vec![0.0; num_actions * num_hands];
+        for (action, &index) in indices.iter().enumerate() {
+            row_mut(&mut projected, action, num_hands)
+                .copy_from_slice(row(&strategy, index, num_hands));
+        }
+        for hand in 0..num_hands {
+            let sum = (0..num_actions)
+                .map(|action| projected[action * num_hands + hand])
+                .sum::<f32>();
+            for action in 0..num_actions {
+                let p = &mut projected[action * num_hands + hand];
+                *p = if sum > 0.0 {
+                    *p / sum
+                } else {
+                    1.0 / num_actions as f32
+                };
+            }
+        }
+
+        // the regrets are scaled like the counterfactual values accumulated over the iterations
+        let pot = (self.tree_config.starting_pot + 2 * new_node.amount) as f64;
+        let opponent_mass = reach[player ^ 1].iter().map(|&r| r as f64).sum::<f64>();
+        let regret_scale =
+            WARM_START_ITERATIONS as f64 * pot * opponent_mass / self.num_combinations;
+        let cum_strategy = projected
+            .iter()
+            .map(|&p| p * WARM_START_ITERATIONS as f32)
+            .collect::<Vec<_>>();
+        let regrets = projected
+            .iter()
+            .map(|&p| (p as f64 * regret_scale) as f32)
+            .collect::<Vec<_>>();
+        write_warm_start(game, new_node, &cum_strategy, &regrets);
+
+        for (action, &index) in indices.iter().enumerate() {
+            let mut reach = reach.clone();
+            mul_slice(&mut reach[player], row(&projected, action, num_hands));
+            let child = node.play(index);
+            self.warm_start_recursive(game, &child, &mut new_node.play(action), &reach);
+        }
+    }
+}
+
+/// Writes the cumulative strategy and the regrets to the storage of `node` of `game`.
+fn write_warm_start(
+    game: &PostFlopGame,
+    node: &mut PostFlopNode,
+    cum_strategy: &[f32],
+    regrets: &[f32],
+) {
+    match game.strategy_format {
+        StorageFormat::Float32 => node.strategy_mut().copy_from_slice(cum_strategy),
+        StorageFormat::Float16 => encode_f16_slice(node.strategy_f16_mut(), cum_strategy),
+        StorageFormat::Int16 => {
+            let scale = encode_unsigned_slice(node.strategy_compressed_mut(), cum_strategy);
+            node.set_strategy_scale(scale);
+        }
+        StorageFormat::Int8 => {
+            let scale = encode_unsigned_slice(node.strategy_8bit_mut(), cum_strategy);
+            node.set_strategy_scale(scale);
+        }
+    }
+
+    match game.value_format {
+        StorageFormat::Float32 => node.regrets_mut().copy_from_slice(regrets),
+        StorageFormat::Float16 => encode_f16_slice(node.regrets_f16_mut(), regrets),
+        StorageFormat::Int16 => {
+            let scale = encode_signed_slice(node.regrets_compressed_mut(), regrets);
+            node.set_regret_scale(scale);
+        }
+        StorageFormat::Int8 => {
+            let scale = encode_signed_slice(node.regrets_8bit_mut(), regrets);
+            node.set_regret_scale(scale);
+        }
+    }
+}
@@ -0,0 +1,389 @@
+use super::visitor::update_bet_amount;
+use super::*;
+use crate::hand::*;
+use crate::interface::*;
+use crate::utility::*;
+
+/// Information of a decision of the villain, passed to the policy of
+/// [`PostFlopGame::simulate_against`].
+///
+/// The board and the private hand may be suit-isomorphic to the dealt cards, but they are always
+/// consistent with each other.
+#[derive(Debug, Clone)]
+pub struct DecisionContext<'a> {
+    /// Current player (`0` = OOP, `1` = IP).
+    pub player: usize,
+
+    /// Private hand of the current player.
+    pub hand: (Card, Card),
+
+    /// Board cards at the node.
+    pub board: &'a [Card],
+
+    /// Actions of both players taken so far in this hand. Dealt cards are not included.
+    pub line: &'a [Action],
+
+    /// Available actions at the node.
+    pub actions: &'a [Action],
+
+    /// Solved strategy of the private hand at the node.
+    pub strategy: &'a [f32],
+
+    /// Total bet amount of each player (OOP, IP).
+    pub total_bet_amount: [i32; 2],
+
+    /// Pot size at the node, including the starting pot.
+    pub pot: i32,
+}
+
+/// Result of a simulation, returned by [`PostFlopGame::simulate`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationResult {
+    /// Number of simulated hands.
+    pub num_hands: usize,
+
+    /// Average payoff of the hero per hand.
+    pub mean_payoff: f32,
+
+    /// Standard deviation of the payoff of the hero per hand.
+    pub stdev: f32,
+
+    /// Standard error of [`mean_payoff`](Self::mean_payoff).
+    pub standard_error: f32,
+
+    /// Frequency of each line, in descending order of the number of occurrences.
+    pub lines: Vec<LineFrequency>,
+}
+
+/// Frequency of a line in a simulation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineFrequency {
+    /// Actions of both players. Dealt cards are not included.
+    pub line: Vec<Action>,
+
+    /// Number of hands that took the line.
+    pub count: usize,
+
+    /// Fraction of hands that took the line.
+    pub frequency: f32,
+
+    /// Average payoff of the hero in the hands that took the line.
+    pub mean_payoff: f32,
+}
+
+/// Mutable state of a simulated hand.
+struct SimulatedHand {
+    hands: [usize; 2],
+    board: Vec<Card>,
+    line: Vec<Action>,
+    total_bet_amount: [i32; 2],
+}
+
+impl PostFlopGame {
+    /// Simulates `num_hands` hands in which both players follow the solved strategies and returns
+    /// the results of the given `hero` player.
+    ///
+    /// The payoff of a hand is the amount of chips the hero wins from the pot minus the amount the
+    /// hero put in, plus half of the starting pot; its mean converges to the expected value of the
+    /// hero at the root node. The private hands are dealt from the initial weights without the
+    /// bunching effect. The results are deterministic for the same `seed`.
+    ///
+    /// Returns an error if `num_hands` is zero or the ranges have no compatible hands. Panics if
+    /// the game is not solved or the strategies of all streets are not stored.
+    ///
+    /// **Time complexity:** *O*(`num_hands` * (depth of the tree) * #(actions)).
+    pub fn simulate(
+        &self,
+        hero: usize,
+        num_hands: usize,
+        seed: u64,
+    ) -> Result<SimulationResult, PostFlopError> {
+        self.simulate_internal(hero, num_hands, seed, None::<fn(&DecisionContext) -> usize>)
+    }
+
+    /// Simulates `num_hands` hands in which the `hero` player follows the solved strategy and the
+    /// villain follows the given policy, and returns the results of the hero.
+    ///
+    /// The policy returns the index of the action to take. See [`simulate`] for details.
+    ///
+    /// Panics if the policy returns an invalid action index.
+    ///
+    /// [`simulate`]: #method.simulate
+    pub fn simulate_against<F: FnMut(&DecisionContext) -> usize>(
+        &self,
+        hero: usize,
+        num_hands: usize,
+        seed: u64,
+        villain: F,
+    ) -> Result<SimulationResult, PostFlopError> {
+        self.simulate_internal(hero, num_hands, seed, Some(villain))
+    }
+
+    /// Internal method for the simulations.
+    fn simulate_internal<F: FnMut(&DecisionContext) -> usize>(
+        &self,
+        hero: usize,
+        num_hands: usize,
+        seed: u64,
+        mut villain: Option<F>,
+    ) -> Result<SimulationResult, PostFlopError> {
+        if self.state != State::Solved {
+            panic!("Game is not solved");
+        }
+
+        if self.storage_mode != BoardState::River {
+            panic!("Strategies of all streets are not stored");
+        }
+
+        if num_hands == 0 {
+            return Err(PostFlopError::InvalidArgument(
+                "Number of hands must be positive".to_string(),
+            ));
+        }
+
+        let mut board = self.card_config.flop.to_vec();
+        for card in [self.card_config.turn, self.card_config.river] {
+            if card != NOT_DEALT {
+                board.push(card);
+            }
+        }
+
+        let board_mask = board.iter().fold(0u64, |mask, &card| mask | (1 << card));
+
+        // cumulative weights of the private hands
+        let cum_weights = [0, 1].map(|player| {
+            let mut cum_weight = 0.0;
+            self.private_cards[player]
+                .iter()
+                .zip(&self.initial_weights[player])
+                .map(|(&(c1, c2), &weight)| {
+                    if ((1u64 << c1) | (1u64 << c2)) & board_mask == 0 {
+                        cum_weight += weight as f64;
+                    }
+                    cum_weight
+                })
+                .collect::<Vec<_>>()
+        });
+
+        let hands = [0, 1].map(|player| {
+            self.private_cards[player]
+                .iter()
+                .zip(&self.initial_weights[player])
+                .filter(|&(_, &weight)| weight > 0.0)
+                .map(|(&(c1, c2), _)| (1u64 << c1) | (1u64 << c2))
+                .filter(|&mask| mask & board_mask == 0)
+                .collect::<Vec<_>>()
+        });
+
+        let has_compatible_hands = hands[0]
+            .iter()
+            .any(|&mask1| hands[1].iter().any(|&mask2| mask1 & mask2 == 0));
+
+        if !has_compatible_hands {
+            return Err(PostFlopError::InvalidConfig(
+                "Ranges have no compatible hands".to_string(),
+            ));
+        }
+
+        let mut rng = Rng::new(seed);
+        let mut lines = BTreeMap::<Vec<Action>, (usize, f64)>::new();
+        let (mut sum, mut sum_sq) = (0.0, 0.0);
+
+        for _ in 0..num_hands {
+            // deal the private hands
+            let hands = loop {
+                let hands = [0, 1].map(|player| {
+                    let cum_weight = &cum_weights[player];
+                    let r = rng.next_f64() * cum_weight.last().unwrap();
+                    let index = cum_weight.partition_point(|&w| w <= r);
+                    index.min(cum_weight.len() - 1)
+                });
+                let (c1, c2) = self.private_cards[0][hands[0]];
+                let (c3, c4) = self.private_cards[1][hands[1]];
+                let mask = (1u64 << c1) | (1u64 << c2) | (1u64 << c3) | (1u64 << c4);
+                if mask.count_ones() == 4 && mask & board_mask == 0 {
+                    break hands;
+                }
+            };
+
+            let mut state = SimulatedHand {
+                hands,
+                board: board.clone(),
+                line: Vec::new(),
+                total_bet_amount: [0, 0],
+            };
+
+            let payoff = self.simulate_hand(hero, &mut state, &mut rng, &mut villain);
+            sum += payoff;
+            sum_sq += payoff * payoff;
+
+            let entry = lines.entry(state.line).or_insert((0, 0.0));
+            entry.0 += 1;
+            entry.1 += payoff;
+        }
+
+        let mean = sum / num_hands as f64;
+        let variance = (sum_sq / num_hands as f64 - mean * mean).max(0.0);
+
+        let mut lines = lines
+            .into_iter()
+            .map(|(line, (count, payoff_sum))| LineFrequency {
+                line,
+                count,
+                frequency: (count as f64 / num_hands as f64) as f32,
+                mean_payoff: (payoff_sum / count as f64) as f32,
+            })
+            .collect::<Vec<_>>();
+        lines.sort_by_key(|line| std::cmp::Reverse(line.count));
+
+        Ok(SimulationResult {
+            num_hands,
+            mean_payoff: mean as f32,
+            stdev: variance.sqrt() as f32,
+            standard_error: (variance / num_hands as f64).sqrt() as f32,
+            lines,
+        })
+    }
+
+    /// Simulates a hand from the root node and returns the payoff of the hero.
+    fn simulate_hand<F: FnMut(&DecisionContext) -> usize>(
+        &self,
+        hero: usize,
+        state: &mut SimulatedHand,
+        rng: &mut Rng,
+        villain: &mut Option<F>,
+    ) -> f64 {
+        let mut node_index = 0;
+
+        loop {
+            let node = self.node_arena[node_index].lock();
+
+            // terminal node
+            if node.is_terminal() {
+                let [win, tie, lose] = self.terminal_payoffs(&node, false);
+                let payoff = if node.player & PLAYER_FOLD_FLAG == PLAYER_FOLD_FLAG {
+                    let folded_player = (node.player & PLAYER_MASK) as usize;
+                    if folded_player == hero {
+                        lose
+                    } else {
+                        win
+                    }
+                } else {
+                    let strength = [0, 1].map(|player| {
+                        let (c1, c2) = self.private_cards[player][state.hands[player]];
+                        state
+                            .board
+                            .iter()
+                            .chain([c1, c2].iter())
+                            .fold(Hand::new(), |hand, &card| hand.add_card(card as usize))
+                            .evaluate()
+                    });
+                    match strength[hero].cmp(&strength[hero ^ 1]) {
+                        std::cmp::Ordering::Greater => win,
+                        std::cmp::Ordering::Equal => tie,
+                        std::cmp::Ordering::Less => lose,
+                    }
+                };
+                return payoff + self.tree_config.starting_pot as f64 * 0.5;
+            }
+
+            // chance node
+            if node.is_chance() {
+                let mut dead_mask = state.board.iter().fold(0u64, |m, &c| m | (1 << c));
+                for player in 0..2 {
+                    let (c1, c2) = self.private_cards[player][state.hands[player]];
+                    dead_mask |= (1 << c1) | (1 << c2);
+                }
+
+                let mut cards = (0..52).filter(|&c| dead_mask & (1 << c) == 0);
+                let num_cards = cards.clone().count();
+                let card = cards.nth(rng.next_u64() as usize % num_cards).unwrap() as Card;
+
+                let mut action_index = node
+                    .children()
+                    .iter()
+                    .position(|child| child.lock().prev_action == Action::Chance(card));
+
+                // isomorphic card: move to the representative card and swap the private hands
+                if action_index.is_none() {
+                    let isomorphic_cards = if node.turn == NOT_DEALT {
+                        &self.isomorphism_card_turn
+                    } else {
+                        &self.isomorphism_card_river[node.turn as usize & 3]
+                    };
+                    let i = isomorphic_cards.iter().position(|&c| c == card).unwrap();
+                    action_index = Some(self.isomorphic_chances(&node)[i] as usize);
+                    let swap_list = self.isomorphic_swap(&node, i);
+                    for player in 0..2 {
+                        for &(a, b) in swap_list[player].iter().rev() {
+                            let hand = &mut state.hands[player];
+                            if *hand == a as usize {
+                                *hand = b as usize;
+                            } else if *hand == b as usize {
+                                *hand = a as usize;
+                            }
+                        }
+                    }
+                }
+
+                let next = node.play(action_index.unwrap());
+                state.board.push(match next.prev_action {
+                    Action::Chance(card) => card,
+                    _ => unreachable!(),
+                });
+                node_index = self.node_index(&next);
+                continue;
+            }
+
+            // player node
+            let player = node.player();
+            let strategy = self.hand_strategy(&node, state.hands[player]);
+
+            let action = match villain {
+                Some(policy) if player != hero => {
+                    let actions = node
+                        .children()
+                        .iter()
+                        .map(|child| child.lock().prev_action)
+                        .collect::<Vec<_>>();
+                    let [bet_oop, bet_ip] = state.total_bet_amount;
+                    let action = policy(&DecisionContext {
+                        player,
+                        hand: self.private_cards[player][state.hands[player]],
+                        board: &state.board,
+                        line: &state.line,
+                        actions: &actions,
+                        strategy: &strategy,
+                        total_bet_amount: state.total_bet_amount,
+                        pot: self.tree_config.starting_pot + bet_oop + bet_ip,
+                    });
+                    if action >= actions.len() {
+                        panic!("Invalid action index: {action}");
+                    }
+                    action
+                }
+                _ => {
+                    let r = rng.next_f64() as f32;
+                    let mut cum_prob = 0.0;
+                    strategy
+                        .iter()
+                        .position(|&prob| {
+                            cum_prob += prob;
+                            r < cum_prob
+                        })
+                        .unwrap_or(strategy.len() - 1)
+                }
+            };
+
+            let next = node.play(action);
+            update_bet_amount(
+                &mut state.total_bet_amount,
+                player,
+                node.prev_action,
+                next.prev_action,
+            );
+            state.line.push(next.prev_action);
+            node_index = self.node_index(&next);
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use super::*;
+use crate::range::*;
+use crate::utility::*;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Self-contained view of the current node, returned by [`PostFlopGame::node_snapshot`].
+///
+/// The snapshot holds everything needed to display the node without further calls to the game:
+/// cards and actions are stored as strings, and the per-hand values are already computed with
+/// the normalized weights cached. Fields indexed by the player use `0` for OOP and `1` for IP, and
+/// the per-hand vectors are in the order of [`private_cards`](Self::private_cards).
+///
+/// The values of a hand whose normalized weight is zero are not meaningful (they may be NaN).
+/// With the `serde` feature, this struct implements `Serialize`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct NodeSnapshot {
+    /// Action history from the root node (see [`PostFlopGame::history`]).
+    pub history: Vec<usize>,
+
+    /// Actions leading to the node from the root node, with the actual dealt cards (see
+    /// [`action_to_token`]).
+    pub line: Vec<String>,
+
+    /// Board cards at the node.
+    pub board: Vec<String>,
+
+    /// Pot size at the node, including the starting pot.
+    pub pot: i32,
+
+    /// Total bet amount of each player (see [`PostFlopGame::total_bet_amount`]).
+    pub total_bet_amount: [i32; 2],
+
+    /// Player to act, or `None` at a terminal node and a chance node.
+    pub player: Option<usize>,
+
+    /// Whether the node is a terminal node.
+    pub is_terminal: bool,
+
+    /// Whether the node is a chance node.
+    pub is_chance: bool,
+
+    /// Available actions (see [`action_to_token`]). At a chance node, isomorphic cards are grouped
+    /// into one representative card. Empty at a terminal node.
+    pub actions: Vec<String>,
+
+    /// Private hands of each player.
+    pub private_cards: [Vec<String>; 2],
+
+    /// Normalized weights of each private hand (see [`PostFlopGame::normalized_weights`]).
+    pub weights: [Vec<f32>; 2],
+
+    /// Equity of each private hand.
+    pub equity: [Vec<f32>; 2],
+
+    /// Expected value of each private hand.
+    pub expected_values: [Vec<f32>; 2],
+
+    /// Average equity of each player, weighted by the normalized weights.
+    pub average_equity: [f32; 2],
+
+    /// Average expected value of each player, weighted by the normalized weights.
+    pub average_expected_value: [f32; 2],
+
+    /// Strategy of the player to act. The frequency of the `i`-th action with the `j`-th private
+    /// hand is stored in the `i * #(private hands) + j`-th element. Empty if there is no player to
+    /// act.
+    pub strategy: Vec<f32>,
+
+    /// Expected value of each action of each private hand of the player to act, in the same layout
+    /// as [`strategy`](Self::strategy). Empty if there is no player to act.
+    pub action_expected_values: Vec<f32>,
+
+    /// Aggregate frequency of each action, weighted by the normalized weights of the player to
+    /// act. Empty if there is no player to act.
+    pub action_frequencies: Vec<f32>,
+}
+
+impl PostFlopGame {
+    /// Returns a [`NodeSnapshot`] of the current node.
+    ///
+    /// Panics if the game is not solved or the expected values are trimmed.
+    ///
+    /// **Time complexity:** [`node_summary`] + *O*(#(actions) * #(private hands)).
+    ///
+    /// [`node_summary`]: #method.node_summary
+    pub fn node_snapshot(&mut self) -> NodeSnapshot {
+        let summary = self.node_summary();
+        let is_terminal = self.is_terminal_node();
+        let is_chance = self.is_chance_node();
+        let total_bet_amount = self.total_bet_amount();
+
+        let tokens = |actions: &[Action]| actions.iter().map(|&a| action_to_token(a)).collect();
+        let line = tokens(&self.history_actions());
+        let actions = match is_terminal {
+            true => Vec::new(),
+            false => tokens(&self.available_actions()),
+        };
+
+        let board = self
+            .current_board()
+            .into_iter()
+            .map(|card| card_to_string(card).unwrap())
+            .collect();
+        let private_cards =
+            [0, 1].map(|player| holes_to_strings(&self.private_cards[player]).unwrap());
+
+        let mut player = None;
+        let mut strategy = Vec::new();
+        let mut action_expected_values = Vec::new();
+        let mut action_frequencies = Vec::new();
+
+        if !is_terminal && !is_chance {
+            let current = self.current_player();
+            let weights = &summary.normalized_weights[current];
+            let num_hands = weights.len();
+            strategy = self.strategy();
+            action_expected_values = self.expected_values_detail(current);
+            action_frequencies = strategy
+                .chunks_exact(num_hands)
+                .map(|row| compute_average(row, weights))
+                .collect();
+            player = Some(current);
+        }
+
+        NodeSnapshot {
+            history: self.history().to_vec(),
+            line,
+            board,
+            pot: self.tree_config.starting_pot + total_bet_amount[0] + total_bet_amount[1],
+            total_bet_amount,
+            player,
+            is_terminal,
+            is_chance,
+            actions,
+            private_cards,
+            weights: summary.normalized_weights,
+            equity: summary.equity,
+            expected_values: summary.expected_values,
+            average_equity: summary.average_equity,
+            average_expected_value: summary.average_expected_value,
+            strategy,
+            action_expected_values,
+            action_frequencies,
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use super::*;
+use std::alloc::{self, Layout};
+use std::ops::{Deref, DerefMut};
+
+#[cfg(feature = "mmap")]
+use std::fs::{self, OpenOptions};
+#[cfg(feature = "mmap")]
+use std::path::PathBuf;
+#[cfg(feature = "mmap")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Backend of the global storages that hold the buffers of the nodes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum StorageBackend {
+    /// Allocates the storages in RAM.
+    #[default]
+    Memory,
+
+    /// Allocates the storages in memory-mapped scratch files created in the given directory.
+    ///
+    /// The operating system keeps the recently accessed pages in RAM as the working set and
+    /// writes the others back to the files, so a game whose storage exceeds RAM can be solved at
+    /// reduced speed. The scratch files are removed when the storages are freed.
+    #[cfg(feature = "mmap")]
+    ScratchFile(PathBuf),
+
+    /// Allocates the storages in RAM with their pages interleaved across the NUMA nodes.
+    ///
+    /// On multi-socket systems, this balances the memory traffic of the threads running on
+    /// different sockets. Falls back to [`Memory`](Self::Memory) on single-node systems.
+    #[cfg(feature = "numa")]
+    Interleaved,
+}
+
+/// A global storage that holds the buffers of the nodes.
+pub(super) enum StorageBuffer {
+    Memory(Vec<u8>),
+    #[cfg(feature = "mmap")]
+    File(ScratchFile),
+}
+
+/// A memory-mapped scratch file, which is removed when dropped.
+#[cfg(feature = "mmap")]
+pub(super) struct ScratchFile {
+    mmap: Option<memmap2::MmapMut>,
+    path: PathBuf,
+}
+
+#[cfg(feature = "mmap")]
+static SCRATCH_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+impl StorageBuffer {
+    /// Allocates a zero-filled storage of `len` bytes with the given backend.
+    pub(super) fn new(len: usize, backend: &StorageBackend) -> Result<Self, PostFlopError> {
+        match backend {
+            StorageBackend::Memory => Ok(Self::Memory(zeroed_vec(len)?)),
+            #[cfg(feature = "numa")]
+            StorageBackend::Interleaved => {
+                let mut vec = zeroed_vec(len)?;
+                crate::numa::interleave_memory(&mut vec)?;
+                Ok(Self::Memory(vec))
+            }
+            #[cfg(feature = "mmap")]
+            StorageBackend::ScratchFile(_) if len == 0 => Ok(Self::default()),
+            #[cfg(feature = "mmap")]
+            StorageBackend::ScratchFile(dir) => {
+                let counter = SCRATCH_FILE_COUNTER.fetch_add(1, Ordering::Relaxed);
+                let name = format!("postflop-solver-{}-{}.tmp", std::process::id(), counter);
+                let path = dir.join(name);
+
+                let file = OpenOptions::new()
+                    .read(true)
+                    .write(true)
+                    .create_new(true)
+                    .open(&path)
+                    .map_err(|e| {
+                        PostFlopError::Io(format!("Failed to create scratch file: {}", e))
+                    })?;
+
+                // the scratch file is removed on failure when `scratch` is dropped
+                let mut scratch = ScratchFile { mmap: None, path };
+                file.set_len(len as u64).map_err(|e| {
+                    PostFlopError::Io(format!("Failed to extend scratch file: {}", e))
+                })?;
+                let mmap = unsafe { memmap2::MmapMut::map_mut(&file) }
+                    .map_err(|e| PostFlopError::Io(format!("Failed to map scratch file: {}", e)))?;
+                scratch.mmap = Some(mmap);
+
+                Ok(Self::File(scratch))
+            }
+        }
+    }
+
+    /// Appends the bytes of `other` to the storage, moving the storage into RAM if needed.
+    #[cfg(feature = "bincode")]
+    pub(super) fn append(&mut self, other: &mut Vec<u8>) {
+        match self {
+            Self::Memory(vec) => vec.append(other),
+            #[cfg(feature = "mmap")]
+            Self::File(_) => {
+                let mut vec = self.to_vec();
+                vec.append(other);
+                *self = Self::Memory(vec);
+            }
+        }
+    }
+
+    /// Returns whether the storage is backed by a scratch file.
+    #[inline]
+    pub(super) fn is_file_backed(&self) -> bool {
+        #[cfg(feature = "mmap")]
+        return matches!(self, Self::File(_));
+        #[cfg(not(feature = "mmap"))]
+        false
+    }
+}
+
+/// Allocates a zero-filled vector of `len` bytes, returning an error instead of aborting if the
+/// allocation fails.
+fn zeroed_vec(len: usize) -> Result<Vec<u8>, PostFlopError> {
+    if len == 0 {
+        return Ok(Vec::new());
+    }
+
+    let allocation_failed = |reason: String| PostFlopError::AllocationFailed {
+        required: len as u64,
+        reason,
+    };
+
+    let layout = Layout::array::<u8>(len).map_err(|e| allocation_failed(e.to_string()))?;
+    let ptr = unsafe { alloc::alloc_zeroed(layout) };
+    if ptr.is_null() {
+        return Err(allocation_failed("Out of memory".to_string()));
+    }
+
+    // `ptr` is allocated by the global allocator with the layout of `[u8; len]`
+    Ok(unsafe { Vec::from_raw_parts(ptr, len, len) })
+}
+
+impl Default for StorageBuffer {
+    #[inline]
+    fn default() -> Self {
+        Self::Memory(Vec::new())
+    }
+}
+
+impl From<Vec<u8>> for StorageBuffer {
+    #[inline]
+    fn from(vec: Vec<u8>) -> Self {
+        Self::Memory(vec)
+    }
+}
+
+impl Deref for StorageBuffer {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Memory(vec) => vec,
+            #[cfg(feature = "mmap")]
+            Self::File(scratch) => scratch.mmap.as_deref().unwrap_or(&[]),
+        }
+    }
+}
+
+impl DerefMut for StorageBuffer {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [u8] {
+        match self {
+            Self::Memory(vec) => vec,
+            #[cfg(feature = "mmap")]
+            Self::File(scratch) => scratch.mmap.as_deref_mut().unwrap_or(&mut []),
+        }
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl Drop for ScratchFile {
+    fn drop(&mut self) {
+        // the mapping must be released before removing the file on some platforms
+        self.mmap = None;
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+impl PostFlopGame {
+    /// Returns the backend of the global storages.
+    #[inline]
+    pub fn storage_backend(&self) -> &StorageBackend {
+        &self.storage_backend
+    }
+
+    /// Sets the backend of the global storages, which is used by the next call of
+    /// [`allocate_memory`].
+    ///
+    /// Returns an error if the memory has already been allocated or if the scratch directory does
+    /// not exist.
+    ///
+    /// [`allocate_memory`]: #method.allocate_memory
+    pub fn set_storage_backend(&mut self, backend: StorageBackend) -> Result<(), PostFlopError> {
+        if self.state >= State::MemoryAllocated {
+            return Err(PostFlopError::InvalidState(
+                "Memory has already been allocated".to_string(),
+            ));
+        }
+
+        #[cfg(feature = "mmap")]
+        if let StorageBackend::ScratchFile(dir) = &backend {
+            if !dir.is_dir() {
+                return Err(PostFlopError::InvalidArgument(format!(
+                    "Scratch directory not found: {}",
+                    dir.display()
+                )));
+            }
+        }
+
+        self.storage_backend = backend;
+        Ok(())
+    }
+
+    /// Returns whether the global storages are backed by scratch files.
+    #[inline]
+    pub fn is_file_backed(&self) -> bool {
+        [
+            &self.storage1,
+            &self.storage2,
+            &self.storage_ip,
+            &self.storage_chance,
+        ]
+        .iter()
+        .any(|storage| storage.is_file_backed())
+    }
+}
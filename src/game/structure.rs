@@ -0,0 +1,104 @@
+use super::*;
+use std::ptr;
+
+impl PostFlopGame {
+    /// Returns a new game with the same tree and card configuration, without the storages.
+    ///
+    /// The game tree, the data computed from the card configuration (including the hand
+    /// abstraction, the card bucketing, the bunching effect, and the weights modified by
+    /// [`set_weights`]), the storage backend, and the locked strategies are copied, so the new game
+    /// does not repeat the tree construction. The memory is not allocated; call
+    /// [`allocate_memory`] (possibly with a different format) before solving. The solved result and
+    /// the current node are not copied.
+    ///
+    /// Panics if the game is not successfully initialized.
+    ///
+    /// **Time complexity:** *O*(#(nodes)) if all the streets are stored; otherwise, the game tree
+    /// is rebuilt.
+    ///
+    /// [`set_weights`]: #method.set_weights
+    /// [`allocate_memory`]: #method.allocate_memory
+    pub fn clone_structure(&self) -> PostFlopGame {
+        if self.state <= State::Uninitialized {
+            panic!("Game is not successfully initialized");
+        }
+
+        let mut game = PostFlopGame {
+            state: State::TreeBuilt,
+            card_config: self.card_config.clone(),
+            tree_config: self.tree_config.clone(),
+            added_lines: self.added_lines.clone(),
+            removed_lines: self.removed_lines.clone(),
+            action_root: Box::new(MutexLike::new(copy_subtree(&self.action_root.lock(), &[]))),
+            num_combinations: self.num_combinations,
+            initial_weights: self.initial_weights.clone(),
+            is_weight_modified: self.is_weight_modified,
+            private_cards: self.private_cards.clone(),
+            same_hand_index: self.same_hand_index.clone(),
+            is_hand_abstracted: self.is_hand_abstracted,
+            full_private_cards: self.full_private_cards.clone(),
+            abstract_hand_index: self.abstract_hand_index.clone(),
+            valid_indices_flop: self.valid_indices_flop.clone(),
+            valid_indices_turn: self.valid_indices_turn.clone(),
+            valid_indices_river: self.valid_indices_river.clone(),
+            hand_strength: self.hand_strength.clone(),
+            isomorphism_ref_turn: self.isomorphism_ref_turn.clone(),
+            isomorphism_card_turn: self.isomorphism_card_turn.clone(),
+            isomorphism_swap_turn: self.isomorphism_swap_turn.clone(),
+            isomorphism_ref_river: self.isomorphism_ref_river.clone(),
+            isomorphism_card_river: self.isomorphism_card_river.clone(),
+            isomorphism_swap_river: self.isomorphism_swap_river.clone(),
+            card_buckets: self.card_buckets.clone(),
+            bucket_ref_turn: self.bucket_ref_turn.clone(),
+            bucket_card_turn: self.bucket_card_turn.clone(),
+            bucket_ref_river: self.bucket_ref_river.clone(),
+            bucket_card_river: self.bucket_card_river.clone(),
+            bucket_swap: self.bucket_swap.clone(),
+            bunching_num_dead_cards: self.bunching_num_dead_cards,
+            bunching_num_combinations: self.bunching_num_combinations,
+            bunching_arena: self.bunching_arena.clone(),
+            bunching_strength: self.bunching_strength.clone(),
+            bunching_num_flop: self.bunching_num_flop.clone(),
+            bunching_num_turn: self.bunching_num_turn.clone(),
+            bunching_num_river: self.bunching_num_river.clone(),
+            bunching_coef_flop: self.bunching_coef_flop.clone(),
+            bunching_coef_turn: self.bunching_coef_turn.clone(),
+            num_nodes: self.num_nodes,
+            num_storage: self.num_storage,
+            num_storage_ip: self.num_storage_ip,
+            num_storage_chance: self.num_storage_chance,
+            misc_memory_usage: self.misc_memory_usage,
+            storage_backend: self.storage_backend.clone(),
+            locking_strategy: self.locking_strategy.clone(),
+            ..Default::default()
+        };
+
+        // the nodes of the later streets are not loaded if the storage mode is not `River`
+        let total_num_nodes = self.num_nodes.iter().sum::<u64>();
+        if self.node_arena.len() as u64 == total_num_nodes {
+            game.node_arena = self
+                .node_arena
+                .iter()
+                .map(|node| {
+                    let mut node = *node.lock();
+                    node.scale1 = 0.0;
+                    node.scale2 = 0.0;
+                    node.scale3 = 0.0;
+                    node.storage1 = ptr::null_mut();
+                    node.storage2 = ptr::null_mut();
+                    node.storage3 = ptr::null_mut();
+                    MutexLike::new(node)
+                })
+                .collect();
+        } else {
+            game.init_root().unwrap();
+            for &index in self.locking_strategy.keys() {
+                game.node_arena[index].lock().is_locked = true;
+            }
+        }
+
+        game.init_interpreter();
+        game.back_to_root();
+        game
+    }
+}
@@ -0,0 +1,172 @@
+use super::*;
+use crate::range::*;
+use crate::utility::*;
+use half::f16;
+
+impl PostFlopGame {
+    /// Returns a standalone game rooted at the current node.
+    ///
+    /// The returned game is configured with the board, the pot, and the remaining stacks at the
+    /// current node, and the ranges are the [`weights`] of the players at the current node. Its game
+    /// tree is a copy of the subtree below the current node, and the strategies of the subtree are
+    /// copied and finalized, so the returned game is already solved. Saving it (e.g., by
+    /// [`save_data_to_file`]) produces a much smaller "endgame" archive that can be loaded as a
+    /// standalone game.
+    ///
+    /// The current node must be the first decision of a street. The bunching effect and the card
+    /// bucketing are not supported. The current node of this game is not changed.
+    ///
+    /// Panics if the game is not solved, if the strategies of all streets are not stored, or if the
+    /// current node is a terminal node or a chance node.
+    ///
+    /// [`weights`]: #method.weights
+    /// [`save_data_to_file`]: crate::save_data_to_file
+    pub fn subtree_game(&mut self) -> Result<PostFlopGame, PostFlopError> {
+        if self.state != State::Solved {
+            panic!("Game is not solved");
+        }
+
+        if self.storage_mode != BoardState::River {
+            panic!("Strategies of all streets are not stored");
+        }
+
+        if self.is_terminal_node() {
+            panic!("Terminal node is not allowed");
+        }
+
+        if self.is_chance_node() {
+            panic!("Chance node is not allowed");
+        }
+
+        if self.bunching_num_dead_cards != 0 {
+            return Err(PostFlopError::Unsupported(
+                "Bunching effect is not supported".to_string(),
+            ));
+        }
+
+        if !self.card_buckets.is_empty() {
+            return Err(PostFlopError::Unsupported(
+                "Card bucketing is not supported".to_string(),
+            ));
+        }
+
+        let (prev_action, amount) = {
+            let node = self.node();
+            (node.prev_action, node.amount)
+        };
+
+        if !matches!(prev_action, Action::None | Action::Chance(_)) {
+            return Err(PostFlopError::InvalidState(
+                "Current node is not the first decision of a street".to_string(),
+            ));
+        }
+
+        let street = match (self.turn, self.river) {
+            (NOT_DEALT, _) => BoardState::Flop,
+            (_, NOT_DEALT) => BoardState::Turn,
+            _ => BoardState::River,
+        };
+
+        let mut range = [Range::new(); 2];
+        for player in 0..2 {
+            let weights = self.weights[player].iter().map(|&w| w.min(1.0));
+            range[player] = Range::from_hands_weights(
+                &self.private_cards[player],
+                &weights.collect::<Vec<_>>(),
+            )?;
+        }
+
+        let card_config = CardConfig {
+            range,
+            flop: self.card_config.flop,
+            turn: self.turn,
+            river: self.river,
+        };
+
+        let tree_config = TreeConfig {
+            initial_state: street,
+            starting_pot: self.tree_config.starting_pot + 2 * amount,
+            effective_stack: self.tree_config.effective_stack - amount,
+            ..self.tree_config.clone()
+        };
+
+        let action_tree =
+            ActionTree::from_subtree(tree_config, &self.action_root.lock(), &self.action_history)?;
+        let mut game = PostFlopGame::with_config(card_config, action_tree)?;
+        game.allocate_memory_with_formats(self.strategy_format, self.value_format, None)?;
+
+        // indices of the private hands of `game` in `self`
+        let hand_indices = [0, 1].map(|player| {
+            game.private_cards[player]
+                .iter()
+                .map(|hand| self.private_cards[player].binary_search(hand).unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        let saved = self.save_navigation();
+        self.copy_strategy_recursive(&game, &mut game.root(), &hand_indices);
+        self.restore_navigation(&saved);
+
+        finalize(&mut game);
+        Ok(game)
+    }
+
+    /// Copies the strategies of the subtree below the current node into `node` of `game`.
+    fn copy_strategy_recursive(
+        &mut self,
+        game: &PostFlopGame,
+        node: &mut PostFlopNode,
+        hand_indices: &[Vec<usize>; 2],
+    ) {
+        if node.is_terminal() {
+            return;
+        }
+
+        if !node.is_chance() {
+            let strategy = self.strategy();
+            let player = node.player();
+            let num_hands = hand_indices[player].len();
+            let num_hands_self = self.num_private_hands(player);
+            let value = |i: usize| {
+                let (action, hand) = (i / num_hands, i % num_hands);
+                strategy[action * num_hands_self + hand_indices[player][hand]]
+            };
+
+            match game.strategy_format {
+                StorageFormat::Float32 => {
+                    let dst = node.strategy_mut().iter_mut();
+                    dst.enumerate().for_each(|(i, d)| *d = value(i));
+                }
+                StorageFormat::Float16 => {
+                    let dst = node.strategy_f16_mut().iter_mut();
+                    dst.enumerate()
+                        .for_each(|(i, d)| *d = f16::from_f32(value(i)));
+                }
+                StorageFormat::Int16 => {
+                    let dst = node.strategy_compressed_mut().iter_mut();
+                    dst.enumerate().for_each(|(i, d)| {
+                        *d = (value(i) * u16::MAX as f32).round() as u16;
+                    });
+                }
+                StorageFormat::Int8 => {
+                    let dst = node.strategy_8bit_mut().iter_mut();
+                    dst.enumerate().for_each(|(i, d)| {
+                        *d = (value(i) * u8::MAX as f32).round() as u8;
+                    });
+                }
+            }
+        }
+
+        let saved = self.save_navigation();
+
+        for action in 0..node.num_actions() {
+            let mut child = node.play(action);
+            match child.prev_action {
+                Action::Chance(card) => self.play(card as usize),
+                _ => self.play(action),
+            }
+            self.copy_strategy_recursive(game, &mut child, hand_indices);
+            self.restore_navigation(&saved);
+        }
+    }
+}
@@ -1,9 +1,17 @@
 use super::*;
+use crate::bet_size::*;
+use crate::interface::*;
 use crate::range::*;
 use crate::solver::*;
 use crate::utility::*;
 use crate::BunchingData;
 
+#[cfg(feature = "numa")]
+use crate::numa::*;
+
+#[cfg(feature = "profile")]
+use std::time::Duration;
+
 #[test]
 fn all_check_all_range() {
     let card_config = CardConfig {
@@ -21,7 +29,7 @@ fn all_check_all_range() {
     let action_tree = ActionTree::new(tree_config).unwrap();
     let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
 
-    game.allocate_memory(false);
+    game.allocate_memory(false, None).unwrap();
     finalize(&mut game);
 
     game.cache_normalized_weights();
@@ -114,7 +122,7 @@ fn one_raise_all_range() {
     let action_tree = ActionTree::new(tree_config).unwrap();
     let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
 
-    game.allocate_memory(false);
+    game.allocate_memory(false, None).unwrap();
     finalize(&mut game);
 
     game.cache_normalized_weights();
@@ -219,7 +227,7 @@ fn one_raise_all_range_compressed() {
     let action_tree = ActionTree::new(tree_config).unwrap();
     let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
 
-    game.allocate_memory(true);
+    game.allocate_memory(true, None).unwrap();
     finalize(&mut game);
 
     game.cache_normalized_weights();
@@ -326,7 +334,7 @@ fn one_raise_all_range_with_turn() {
     let action_tree = ActionTree::new(tree_config).unwrap();
     let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
 
-    game.allocate_memory(false);
+    game.allocate_memory(false, None).unwrap();
     finalize(&mut game);
 
     game.cache_normalized_weights();
@@ -363,7 +371,7 @@ fn one_raise_all_range_with_river() {
     let action_tree = ActionTree::new(tree_config).unwrap();
     let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
 
-    game.allocate_memory(false);
+    game.allocate_memory(false, None).unwrap();
     finalize(&mut game);
 
     game.cache_normalized_weights();
@@ -454,7 +462,7 @@ fn always_win() {
     let action_tree = ActionTree::new(tree_config).unwrap();
     let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
 
-    game.allocate_memory(false);
+    game.allocate_memory(false, None).unwrap();
     finalize(&mut game);
 
     game.cache_normalized_weights();
@@ -515,7 +523,7 @@ fn always_win_raked() {
     let action_tree = ActionTree::new(tree_config).unwrap();
     let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
 
-    game.allocate_memory(false);
+    game.allocate_memory(false, None).unwrap();
     finalize(&mut game);
 
     game.cache_normalized_weights();
@@ -566,7 +574,7 @@ fn always_lose() {
     let action_tree = ActionTree::new(tree_config).unwrap();
     let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
 
-    game.allocate_memory(false);
+    game.allocate_memory(false, None).unwrap();
     finalize(&mut game);
 
     game.cache_normalized_weights();
@@ -604,7 +612,7 @@ fn always_lose_raked() {
     let action_tree = ActionTree::new(tree_config).unwrap();
     let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
 
-    game.allocate_memory(false);
+    game.allocate_memory(false, None).unwrap();
     finalize(&mut game);
 
     game.cache_normalized_weights();
@@ -634,7 +642,7 @@ fn always_tie() {
     let action_tree = ActionTree::new(tree_config).unwrap();
     let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
 
-    game.allocate_memory(false);
+    game.allocate_memory(false, None).unwrap();
     finalize(&mut game);
 
     game.cache_normalized_weights();
@@ -670,7 +678,7 @@ fn always_tie_raked() {
     let action_tree = ActionTree::new(tree_config).unwrap();
     let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
 
-    game.allocate_memory(false);
+    game.allocate_memory(false, None).unwrap();
     finalize(&mut game);
 
     game.cache_normalized_weights();
@@ -750,7 +758,7 @@ fn remove_lines() {
     let res = game.remove_lines(&lines);
     assert!(res.is_ok());
 
-    game.allocate_memory(false);
+    game.allocate_memory(false, None).unwrap();
 
     // check that the turn line is removed
     game.apply_history(&[0, 0, 2]);
@@ -804,7 +812,7 @@ fn isomorphism_monotone() {
     let action_tree = ActionTree::new(tree_config).unwrap();
     let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
 
-    game.allocate_memory(false);
+    game.allocate_memory(false, None).unwrap();
     finalize(&mut game);
 
     let mut check = |history: &[usize],
@@ -867,7 +875,7 @@ fn node_locking() {
     let action_tree = ActionTree::new(tree_config).unwrap();
     let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
 
-    game.allocate_memory(false);
+    game.allocate_memory(false, None).unwrap();
     game.play(1); // all-in
     game.lock_current_strategy(&[0.25, 0.75]); // 25% fold, 75% call
     game.back_to_root();
@@ -887,7 +895,7 @@ fn node_locking() {
     assert!((strategy_oop[2] - 0.0).abs() < 1e-3); // QQ bet
     assert!((strategy_oop[3] - 1.0).abs() < 1e-3); // AA bet
 
-    game.allocate_memory(false);
+    game.allocate_memory(false, None).unwrap();
     game.play(1); // all-in
     game.lock_current_strategy(&[0.5, 0.5]); // 50% fold, 50% call
     game.back_to_root();
@@ -928,7 +936,7 @@ fn node_locking_partial() {
     let action_tree = ActionTree::new(tree_config).unwrap();
     let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
 
-    game.allocate_memory(false);
+    game.allocate_memory(false, None).unwrap();
     game.lock_current_strategy(&[0.8, 0.0, 0.0, 0.2, 0.0, 0.0]); // JJ -> 80% check, 20% all-in
 
     solve(&mut game, 1000, 0.0, false);
@@ -968,7 +976,7 @@ fn node_locking_isomorphism() {
     let action_tree = ActionTree::new(tree_config).unwrap();
     let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
 
-    game.allocate_memory(false);
+    game.allocate_memory(false, None).unwrap();
     game.apply_history(&[0, 0, 15, 0, 0, 14]); // Turn: Spades, River: Hearts
     game.lock_current_strategy(&[0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0]); // AhKh -> check
 
@@ -1043,8 +1051,14 @@ fn set_bunching_effect() {
 
     bunching_data.process(false);
     game.set_bunching_effect(&bunching_data).unwrap();
+    assert_eq!(
+        game.set_weight(0, 0, 0.5),
+        Err(PostFlopError::Unsupported(
+            "Weights cannot be changed with bunching effect".to_string()
+        ))
+    );
 
-    game.allocate_memory(false);
+    game.allocate_memory(false, None).unwrap();
     finalize(&mut game);
 
     let current_ev = compute_current_ev(&game);
@@ -1097,7 +1111,7 @@ fn set_bunching_effect_always_win() {
     bunching_data.process(false);
     game.set_bunching_effect(&bunching_data).unwrap();
 
-    game.allocate_memory(false);
+    game.allocate_memory(false, None).unwrap();
     finalize(&mut game);
 
     let current_ev = compute_current_ev(&game);
@@ -1141,6 +1155,185 @@ fn set_bunching_effect_always_win() {
     assert!((ev_ip - 0.0).abs() < 1e-4);
 }
 
+#[test]
+fn set_bunching_effect_isomorphic() {
+    let co_range = "33:0.59,22:0.635,A8o:0.265,A7o-A6o,A5o:0.445,A4o-A2o,K2s,K9o:0.905,K8o-K2o,Q4s-Q2s,Q9o-Q2o,J6s-J2s,J9o:0.88,J8o-J2o,T7s:0.405,T6s-T2s,T9o:0.96,T8o-T2o,96s-92s,92o+,86s:0.57,85s-82s,82o+,76s:0.37,75s-72s,72o+,65s:0.475,64s-62s,62o+,54s:0.68,53s-52s,52o+,42+,32";
+    let sb_range = "66:0.46,55:0.821,44:0.92,33:0.93,22:0.925,A6s:0.73,A3s:0.47,A2s,ATo:0.105,A9o-A2o,K8s:0.795,K7s,K6s:0.85,K5s:0.965,K4s-K2s,KJo:0.085,KTo:0.645,K9o-K2o,Q8s-Q2s,QJo:0.765,QTo-Q2o,J8s-J2s,J2o+,T8s:0.69,T7s-T2s,T2o+,98s:0.905,97s-92s,92o+,87s:0.78,86s-82s,82o+,76s:0.77,75s-72s,72o+,65s:0.845,64s-62s,62o+,54s:0.735,53s-52s,52o+,42+,32";
+
+    let mut bunching_data = BunchingData::new(
+        &[co_range.parse().unwrap(), sb_range.parse().unwrap()],
+        flop_from_str("Td9d6h").unwrap(),
+    )
+    .unwrap();
+
+    bunching_data.process(false);
+
+    let build = |flop: &str| {
+        let card_config = CardConfig {
+            range: [Range::ones(); 2],
+            flop: flop_from_str(flop).unwrap(),
+            turn: card_from_str("Qc").unwrap(),
+            river: card_from_str("2s").unwrap(),
+        };
+
+        let tree_config = TreeConfig {
+            initial_state: BoardState::River,
+            starting_pot: 60,
+            effective_stack: 970,
+            ..Default::default()
+        };
+
+        let action_tree = ActionTree::new(tree_config).unwrap();
+        let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
+        game.set_bunching_effect(&bunching_data).unwrap();
+        game.allocate_memory(false, None).unwrap();
+        finalize(&mut game);
+        game.cache_normalized_weights();
+        game
+    };
+
+    // diamonds and hearts are swapped
+    let game1 = build("Td9d6h");
+    let game2 = build("Th9h6d");
+
+    for (hand1, hand2) in [("AdKd", "AhKh"), ("Jh8h", "Jd8d"), ("Ac7h", "Ac7d")] {
+        let index1 = game1.private_card_indices(0, hand1).unwrap()[0];
+        let index2 = game2.private_card_indices(0, hand2).unwrap()[0];
+        let weight1 = game1.normalized_weights(0)[index1];
+        let weight2 = game2.normalized_weights(0)[index2];
+        let equity1 = game1.equity(0)[index1];
+        let equity2 = game2.equity(0)[index2];
+        assert!((weight1 - weight2).abs() < 1e-6);
+        assert!((equity1 - equity2).abs() < 1e-6);
+    }
+
+    // not isomorphic
+    let card_config = CardConfig {
+        range: [Range::ones(); 2],
+        flop: flop_from_str("Td9d6d").unwrap(),
+        ..Default::default()
+    };
+
+    let tree_config = TreeConfig {
+        starting_pot: 60,
+        effective_stack: 970,
+        ..Default::default()
+    };
+
+    let action_tree = ActionTree::new(tree_config).unwrap();
+    let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
+    assert!(game.set_bunching_effect(&bunching_data).is_err());
+}
+
+fn build_remap_game(flop: &str, oop_range: &str, ip_range: &str) -> PostFlopGame {
+    let card_config = CardConfig {
+        range: [oop_range.parse().unwrap(), ip_range.parse().unwrap()],
+        flop: flop_from_str(flop).unwrap(),
+        ..Default::default()
+    };
+    let bet_sizes: BetSizeOptions = ("50%", "").try_into().unwrap();
+    let tree_config = TreeConfig {
+        initial_state: BoardState::Flop,
+        starting_pot: 60,
+        effective_stack: 200,
+        flop_bet_sizes: [bet_sizes.clone(), bet_sizes.clone()],
+        turn_bet_sizes: [bet_sizes.clone(), bet_sizes.clone()],
+        river_bet_sizes: [bet_sizes.clone(), bet_sizes],
+        ..Default::default()
+    };
+    let action_tree = ActionTree::new(tree_config).unwrap();
+    let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
+    game.allocate_memory(false, None).unwrap();
+    solve(&mut game, 20, 0.0, false);
+    game
+}
+
+#[test]
+fn private_card_indices() {
+    let card_config = CardConfig {
+        range: ["AKs,QQ".parse().unwrap(), "AKo".parse().unwrap()],
+        flop: flop_from_str("Qh7c2d").unwrap(),
+        ..Default::default()
+    };
+    let action_tree = ActionTree::new(TreeConfig {
+        starting_pot: 60,
+        effective_stack: 970,
+        ..Default::default()
+    })
+    .unwrap();
+    let game = PostFlopGame::with_config(card_config, action_tree).unwrap();
+
+    let indices = game.private_card_indices(0, "AK").unwrap();
+    assert_eq!(indices.len(), 4);
+    for &i in &indices {
+        let (c1, c2) = game.private_cards(0)[i];
+        assert_eq!(c1 & 3, c2 & 3);
+    }
+
+    let indices = game.private_card_indices(0, "QsQc").unwrap();
+    assert_eq!(indices.len(), 1);
+    assert_eq!(
+        hole_to_string(game.private_cards(0)[indices[0]]).unwrap(),
+        "QsQc"
+    );
+
+    assert_eq!(game.private_card_indices(1, "AKo").unwrap().len(), 12);
+    assert!(game.private_card_indices(1, "AKs").unwrap().is_empty());
+    assert!(game.private_card_indices(1, "AKx").is_err());
+}
+
+#[test]
+fn remap_flop() {
+    let mut game_src = build_remap_game(
+        "Td9d6h",
+        "AdKd,QhQs,QhQc,8d7d",
+        "AhAs,AhAc,JdTc,JdTs,9c8c,9s8s",
+    );
+    let mut game_dst = build_remap_game(
+        "Ts9s6c",
+        "AsKs,QcQd,QcQh,8s7s",
+        "AcAd,AcAh,JsTh,JsTd,9h8h,9d8d",
+    );
+
+    assert!(game_src
+        .remap_flop(flop_from_str("Td9h6h").unwrap())
+        .is_err());
+
+    let target = flop_from_str("Ts9s6c").unwrap();
+    let mut view = game_src.remap_flop(target).unwrap();
+    assert_eq!(view.flop(), target);
+    assert_eq!(view.range(0), game_dst.card_config().range[0]);
+    assert_eq!(view.range(1), game_dst.card_config().range[1]);
+
+    let private_cards = [view.private_cards(0), view.private_cards(1)];
+    let order = |player: usize, hand: (Card, Card)| {
+        private_cards[player]
+            .iter()
+            .position(|&h| h == hand)
+            .unwrap()
+    };
+
+    // check -> check -> Kh
+    for game in [view.game_mut(), &mut game_dst] {
+        game.play(0);
+        game.play(0);
+    }
+    view.play(card_from_str("Kh").unwrap() as usize);
+    game_dst.play(card_from_str("Kh").unwrap() as usize);
+    assert_eq!(view.current_board(), game_dst.current_board());
+    assert_eq!(view.possible_cards(), game_dst.possible_cards());
+
+    view.game_mut().cache_normalized_weights();
+    game_dst.cache_normalized_weights();
+    for player in 0..2 {
+        let ev_src = view.game().expected_values(player);
+        let ev_dst = game_dst.expected_values(player);
+        for (i, &hand) in game_dst.private_cards(player).iter().enumerate() {
+            assert!((ev_src[order(player, hand)] - ev_dst[i]).abs() < 1e-3);
+        }
+    }
+}
+
 #[test]
 #[ignore]
 fn solve_pio_preset_normal() {
@@ -1177,7 +1370,7 @@ fn solve_pio_preset_normal() {
         "memory usage: {:.2}GB",
         game.memory_usage().0 as f64 / (1024.0 * 1024.0 * 1024.0)
     );
-    game.allocate_memory(false);
+    game.allocate_memory(false, None).unwrap();
 
     solve(&mut game, 1000, 180.0 * 0.001, true);
 
@@ -1234,7 +1427,7 @@ fn solve_pio_preset_raked() {
         "memory usage: {:.2}GB",
         game.memory_usage().0 as f64 / (1024.0 * 1024.0 * 1024.0)
     );
-    game.allocate_memory(false);
+    game.allocate_memory(false, None).unwrap();
 
     solve(&mut game, 1000, 180.0 * 0.001, true);
 
@@ -1248,3 +1441,2666 @@ fn solve_pio_preset_raked() {
     assert!((root_ev_oop - 95.57).abs() < 0.2);
     assert!((root_ev_ip - 66.98).abs() < 0.2);
 }
+
+fn build_river_game() -> PostFlopGame {
+    let card_config = CardConfig {
+        range: [
+            "AA-22,AK-A2,KQ-K9,QJ-Q9,JT-J9,T9".parse().unwrap(),
+            "AA-66,AK-A8,KQ-KT,QJ-QT,JTs,T9s,98s".parse().unwrap(),
+        ],
+        flop: flop_from_str("Td9d6h").unwrap(),
+        turn: card_from_str("Qc").unwrap(),
+        river: card_from_str("3s").unwrap(),
+    };
+    let tree_config = TreeConfig {
+        initial_state: BoardState::River,
+        starting_pot: 100,
+        effective_stack: 400,
+        river_bet_sizes: [
+            ("50%, 100%", "60%").try_into().unwrap(),
+            ("50%, 100%", "60%").try_into().unwrap(),
+        ],
+        ..Default::default()
+    };
+    let action_tree = ActionTree::new(tree_config).unwrap();
+    let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
+    game.allocate_memory(false, None).unwrap();
+    solve(&mut game, 200, 0.0, false);
+    game
+}
+
+#[test]
+fn action_evs() {
+    let mut game = build_river_game();
+    game.play(0); // check
+    game.cache_normalized_weights();
+
+    // current player
+    let evs = game.action_evs(1);
+    assert_eq!(evs, game.expected_values_detail(1));
+
+    // opponent: terminal and player children
+    let history = game.history().to_vec();
+    let evs = game.action_evs(0);
+    assert_eq!(game.history(), history);
+    assert!(!game.normalized_weights(0).is_empty()); // still cached
+    assert_action_evs(&mut game, 0, &evs);
+
+    game.back_to_root();
+    let evs = game.action_evs(1);
+    assert_action_evs(&mut game, 1, &evs);
+
+    // opponent: chance children
+    let mut game = build_turn_game();
+    game.play(0); // check
+    let evs = game.action_evs(0);
+    assert_action_evs(&mut game, 0, &evs);
+}
+
+/// Asserts that `evs` are the expected values of `player` after each action of the current node.
+fn assert_action_evs(game: &mut PostFlopGame, player: usize, evs: &[f32]) {
+    let history = game.history().to_vec();
+    let num_actions = game.available_actions().len();
+    let num_hands = game.private_cards(player).len();
+    assert_eq!(evs.len(), num_actions * num_hands);
+
+    for action in 0..num_actions {
+        game.apply_history(&history);
+        game.play(action);
+        game.cache_normalized_weights();
+        let expected = game.expected_values(player);
+        let actual = &evs[action * num_hands..(action + 1) * num_hands];
+        for (&a, &e) in actual.iter().zip(&expected) {
+            assert!((a - e).abs() < 1e-3 * e.abs().max(1.0), "{a} != {e}");
+        }
+    }
+
+    game.apply_history(&history);
+}
+
+#[test]
+fn line_report() {
+    let mut game = build_river_game();
+    game.play(0); // check
+    let history = game.history().to_vec();
+
+    let report = game.line_report(None);
+    assert_eq!(game.history(), history);
+    assert_eq!(report[0].history, history);
+    assert_eq!(report[0].line, vec![Action::Check]);
+    assert_eq!(report[0].pot, 100);
+
+    for entry in &report {
+        assert!(entry.history.starts_with(&history));
+        assert_eq!(entry.line.len(), entry.history.len());
+        assert_eq!(entry.frequencies.len(), entry.actions.len());
+        if entry.combos[entry.player] > 0.0 {
+            let sum = entry.frequencies.iter().sum::<f32>();
+            assert!((sum - 1.0).abs() < 1e-4);
+        }
+
+        game.apply_history(&entry.history);
+        game.cache_normalized_weights();
+        assert_eq!(game.current_player(), entry.player);
+        assert_eq!(game.available_actions(), entry.actions);
+        for player in 0..2 {
+            let weights = game.normalized_weights(player);
+            let ev = compute_average(&game.expected_values(player), weights);
+            let equity = compute_average(&game.equity(player), weights);
+            assert!((entry.expected_value[player] - ev).abs() < 1e-3);
+            assert!((entry.equity[player] - equity).abs() < 1e-5);
+        }
+    }
+
+    // every non-terminal decision node below the current node is reported
+    let mut count = 0;
+    let mut stack = vec![history];
+    while let Some(h) = stack.pop() {
+        game.apply_history(&h);
+        if game.is_terminal_node() || game.is_chance_node() {
+            continue;
+        }
+        count += 1;
+        for action in 0..game.available_actions().len() {
+            let mut next = h.clone();
+            next.push(action);
+            stack.push(next);
+        }
+    }
+    assert_eq!(report.len(), count);
+}
+
+#[test]
+fn line_report_through_chance() {
+    let mut game = build_turn_game();
+    let turn = game.line_report(Some(BoardState::Turn));
+    let report = game.line_report(None);
+    assert!(game.history().is_empty());
+    assert!(turn.len() < report.len());
+
+    // the turn entries are the entries without dealt cards
+    let is_chance = |action: &Action| matches!(action, Action::Chance(_));
+    let without_chance = report
+        .iter()
+        .filter(|entry| !entry.line.iter().any(is_chance));
+    assert!(without_chance.eq(turn.iter()));
+
+    // the dealt card is recorded in the line, and the statistics match the node
+    let entry = report
+        .iter()
+        .find(|e| e.line.iter().any(is_chance))
+        .unwrap();
+    let position = entry.line.iter().position(is_chance).unwrap();
+    assert_eq!(
+        entry.line[position],
+        Action::Chance(entry.history[position] as Card)
+    );
+    game.apply_history(&entry.history);
+    game.cache_normalized_weights();
+    assert_eq!(game.current_board().len(), 5);
+    assert_eq!(game.available_actions(), entry.actions);
+    for player in 0..2 {
+        let weights = game.normalized_weights(player);
+        let ev = compute_average(&game.expected_values(player), weights);
+        assert!((entry.expected_value[player] - ev).abs() < 1e-3);
+    }
+
+    // every decision node of the whole tree is reported
+    let mut count = 0;
+    let mut stack = vec![Vec::new()];
+    while let Some(h) = stack.pop() {
+        game.apply_history(&h);
+        if game.is_terminal_node() {
+            continue;
+        }
+        let children = if game.is_chance_node() {
+            let possible_cards = game.possible_cards();
+            (0..52)
+                .filter(|&c| possible_cards & (1 << c) != 0)
+                .collect()
+        } else {
+            count += 1;
+            (0..game.available_actions().len()).collect::<Vec<_>>()
+        };
+        for action in children {
+            let mut next = h.clone();
+            next.push(action);
+            stack.push(next);
+        }
+    }
+    assert_eq!(report.len(), count);
+}
+
+fn build_turn_game() -> PostFlopGame {
+    let mut game = build_unsolved_turn_game();
+    solve(&mut game, 20, 0.0, false);
+    game
+}
+
+fn build_unsolved_turn_game() -> PostFlopGame {
+    let card_config = CardConfig {
+        range: [
+            "AA,KK,QQ,AK,T9,98s".parse().unwrap(),
+            "JJ,TT,AQ,KQ,J9".parse().unwrap(),
+        ],
+        flop: flop_from_str("Td9d6h").unwrap(),
+        turn: card_from_str("Qd").unwrap(),
+        ..Default::default()
+    };
+    let tree_config = TreeConfig {
+        initial_state: BoardState::Turn,
+        starting_pot: 100,
+        effective_stack: 400,
+        turn_bet_sizes: [("50%", "60%").try_into().unwrap(), Default::default()],
+        river_bet_sizes: [("50%", "").try_into().unwrap(), Default::default()],
+        ..Default::default()
+    };
+    let action_tree = ActionTree::new(tree_config).unwrap();
+    let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
+    game.allocate_memory(false, None).unwrap();
+    game
+}
+
+#[test]
+fn visit_nodes() {
+    let mut game = build_turn_game();
+    game.play(0); // check
+
+    let mut views = Vec::new();
+    game.visit_nodes(|view| {
+        views.push((
+            view.history().to_vec(),
+            view.line().to_vec(),
+            view.board().to_vec(),
+            view.pot(),
+            view.current_player(),
+            view.available_actions().to_vec(),
+            view.strategy().to_vec(),
+            [0, 1].map(|player| view.reach_probabilities(player).to_vec()),
+        ));
+    });
+    assert_eq!(game.history(), [0]); // the current node is not changed
+
+    let mut num_river_nodes = 0;
+    for (history, line, board, pot, player, actions, strategy, reach) in views {
+        assert_eq!(line.len(), history.len());
+        game.apply_history(&history);
+        assert_eq!(game.current_board(), board);
+        assert_eq!(game.current_player(), player);
+        assert_eq!(game.available_actions(), actions);
+        assert_eq!(game.strategy(), strategy);
+
+        let [bet_oop, bet_ip] = game.total_bet_amount();
+        assert_eq!(pot, 100 + bet_oop + bet_ip);
+
+        for p in 0..2 {
+            for ((&w, &r), &init) in game
+                .weights(p)
+                .iter()
+                .zip(&reach[p])
+                .zip(&game.initial_weights[p])
+            {
+                if w > 0.0 {
+                    assert!((w - init * r).abs() < 1e-6);
+                }
+            }
+        }
+
+        if board.len() == 5 {
+            num_river_nodes += 1;
+        }
+    }
+    assert!(num_river_nodes > 0);
+}
+
+#[test]
+fn reach_probabilities() {
+    let mut game = build_river_game();
+    game.play(0); // check
+    game.play(1); // bet
+    let history = game.history().to_vec();
+
+    for player in 0..2 {
+        let reach = game.reach_probabilities(player);
+        assert_eq!(game.history(), history);
+        assert_eq!(reach.len(), game.private_cards(player).len());
+
+        let weights = game.weights(player);
+        let initial_weights = &game.initial_weights[player];
+        for ((&w, &r), &init) in weights.iter().zip(&reach).zip(initial_weights) {
+            assert!((0.0..=1.0).contains(&r));
+            if w > 0.0 {
+                assert!((w - init * r).abs() < 1e-6);
+            }
+        }
+    }
+
+    // no action has been taken at the root
+    game.back_to_root();
+    let reach_oop = game.reach_probabilities(0);
+    let board = game.current_board();
+    for (&r, &(c1, c2)) in reach_oop.iter().zip(game.private_cards(0)) {
+        let expected = if board.contains(&c1) || board.contains(&c2) {
+            0.0
+        } else {
+            1.0
+        };
+        assert_eq!(r, expected);
+    }
+}
+
+#[test]
+fn ev_breakdown() {
+    let mut game = build_river_game();
+    game.cache_normalized_weights();
+
+    let bet = 1; // OOP bets 50%
+    let num_hands = game.private_cards(0).len();
+    let detail = game.expected_values_detail(0);
+    let expected = &detail[bet * num_hands..(bet + 1) * num_hands];
+
+    let breakdown = game.ev_breakdown(bet);
+    assert!(game.history().is_empty());
+    assert!(breakdown.responses.contains(&Action::Fold));
+
+    let fold = breakdown.fold_component();
+    let cont = breakdown.continue_component();
+    let weights = game.normalized_weights(0);
+    let num_responses = breakdown.responses.len();
+
+    for i in 0..num_hands {
+        if weights[i] > 0.0 {
+            let probability = (0..num_responses)
+                .map(|r| breakdown.probabilities[r * num_hands + i])
+                .sum::<f32>();
+            assert!((probability - 1.0).abs() < 1e-4);
+            assert!((fold[i] + cont[i] - expected[i]).abs() < 1e-2);
+        }
+    }
+
+    // check does not lead to the opponent's decision after a fold is possible
+    let check = game.ev_breakdown(0);
+    assert!(!check.responses.contains(&Action::Fold));
+    assert!(check.fold_component().iter().all(|&v| v == 0.0));
+}
+
+#[test]
+fn runout_report() {
+    let mut game = build_turn_game();
+    game.play(0); // check
+    game.play(0); // check
+    assert!(game.is_chance_node());
+    let history = game.history().to_vec();
+
+    let report = game.runout_report();
+    assert_eq!(game.history(), history);
+    assert_eq!(report.len(), game.possible_cards().count_ones() as usize);
+
+    for entry in &report {
+        let mut next = history.clone();
+        next.push(entry.card as usize);
+        game.apply_history(&next);
+        game.cache_normalized_weights();
+
+        assert_eq!(entry.player, Some(game.current_player()));
+        assert_eq!(entry.actions, game.available_actions());
+        for player in 0..2 {
+            let weights = game.normalized_weights(player);
+            let ev = compute_average(&game.expected_values(player), weights);
+            assert!((entry.expected_value[player] - ev).abs() < 1e-3);
+        }
+    }
+
+    // isomorphic rivers have the same statistics
+    let find = |card: &str| {
+        let card = card_from_str(card).unwrap();
+        report.iter().find(|entry| entry.card == card).unwrap()
+    };
+    let (two_clubs, two_spades) = (find("2c"), find("2s"));
+    assert_eq!(two_clubs.frequencies, two_spades.frequencies);
+    assert!((two_clubs.equity[0] - two_spades.equity[0]).abs() < 1e-5);
+}
+
+#[test]
+fn strategy_distance() {
+    let game = build_river_game();
+
+    let distance = game.strategy_distance(&game).unwrap();
+    assert!(!distance.nodes.is_empty());
+    assert!(distance.nodes.iter().all(|node| node.l1 == 0.0));
+    assert_eq!(distance.nodes[0].reach, 1.0);
+    assert_eq!(distance.nodes[0].pot, 100);
+
+    let card_config = game.card_config().clone();
+    let action_tree = ActionTree::new(game.tree_config().clone()).unwrap();
+    let mut game2 = PostFlopGame::with_config(card_config.clone(), action_tree).unwrap();
+    game2.allocate_memory(false, None).unwrap();
+    solve(&mut game2, 3, 0.0, false);
+
+    let distance = game.strategy_distance(&game2).unwrap();
+    assert!(distance.mean_l1 > 0.0);
+    assert!(distance.weighted_l1 > 0.0);
+    assert!(distance
+        .nodes
+        .iter()
+        .all(|node| (0.0..=2.0).contains(&node.l1)));
+    assert!(distance
+        .nodes
+        .iter()
+        .all(|node| (0.0..=1.0).contains(&node.reach)));
+
+    let tree_config = TreeConfig {
+        river_bet_sizes: [("50%", "60%").try_into().unwrap(), Default::default()],
+        ..game.tree_config().clone()
+    };
+    let action_tree = ActionTree::new(tree_config).unwrap();
+    let mut game3 = PostFlopGame::with_config(card_config, action_tree).unwrap();
+    game3.allocate_memory(false, None).unwrap();
+    finalize(&mut game3);
+    assert!(game.strategy_distance(&game3).is_err());
+}
+
+#[test]
+fn apply_line_str() {
+    let mut game = build_turn_game();
+
+    game.apply_line_str("X x 5c B").unwrap();
+    let history = game.history().to_vec();
+    game.apply_history(&[0, 0, card_from_str("5c").unwrap() as usize]);
+    let bet = game
+        .available_actions()
+        .iter()
+        .position(|a| matches!(a, Action::Bet(_)))
+        .unwrap();
+    game.play(bet);
+    assert_eq!(game.history(), history);
+
+    game.back_to_root();
+    let bet_amount = match game.available_actions()[1] {
+        Action::Bet(amount) => amount,
+        _ => unreachable!(),
+    };
+    game.apply_line_str(&format!("B{bet_amount} C")).unwrap();
+    assert!(game.is_chance_node());
+
+    let cases = [
+        ("X B999", LineErrorKind::ActionNotAvailable, 1, "B999"),
+        ("X X Qd", LineErrorKind::CardNotAvailable, 2, "Qd"),
+        ("X X Zz", LineErrorKind::InvalidToken, 2, "Zz"),
+        ("X Z", LineErrorKind::InvalidToken, 1, "Z"),
+        ("B F X", LineErrorKind::TerminalNode, 2, "X"),
+    ];
+    for (line, kind, position, token) in cases {
+        let history = game.history().to_vec();
+        let error = game.apply_line_str(line).unwrap_err();
+        assert_eq!(error.kind, kind, "{line}");
+        assert_eq!(error.position, position, "{line}");
+        assert_eq!(error.token, token, "{line}");
+        assert_eq!(game.history(), history); // not changed
+    }
+
+    let mut game = build_river_game();
+    let error = game.apply_line_str("B").unwrap_err();
+    assert_eq!(error.kind, LineErrorKind::AmbiguousAction);
+}
+
+#[test]
+fn format_line() {
+    let mut game = build_turn_game();
+    game.apply_line_str("X X 5c B").unwrap();
+    let line = game.history_line();
+    assert!(line.starts_with("X-X-5c-B"));
+    assert_eq!(parse_line(&line).unwrap(), game.history_actions());
+
+    // the formatted line can be applied again
+    let history = game.history().to_vec();
+    game.apply_line_str(&line).unwrap();
+    assert_eq!(game.history(), history);
+
+    let actions = [
+        Action::Fold,
+        Action::Check,
+        Action::Call,
+        Action::Bet(50),
+        Action::Raise(150),
+        Action::AllIn(400),
+        Action::Chance(card_from_str("Ah").unwrap()),
+    ];
+    let line = super::format_line(&actions);
+    assert_eq!(line, "F-X-C-B50-R150-A400-Ah");
+    assert_eq!(parse_line(&line).unwrap(), actions);
+    assert_eq!(parse_line("x c b50  r150").unwrap(), actions[1..5]);
+    assert_eq!(parse_line("").unwrap(), []);
+
+    for (line, position, token) in [("X-B", 1, "B"), ("X-R0", 1, "R0"), ("Zz", 0, "Zz")] {
+        let error = parse_line(line).unwrap_err();
+        assert_eq!(error.kind, LineErrorKind::InvalidToken);
+        assert_eq!((error.position, error.token.as_str()), (position, token));
+    }
+}
+
+#[test]
+fn undo() {
+    let mut game = build_turn_game();
+    assert_eq!(game.undo(), None);
+
+    game.apply_line_str("X X").unwrap();
+    game.play(usize::MAX); // first possible card
+    let card = game.history()[2] as Card;
+    assert_ne!(card, NOT_DEALT);
+    game.play(0);
+
+    let actions = game.history_actions();
+    assert_eq!(actions.len(), 4);
+    assert_eq!(
+        actions[..3],
+        [Action::Check, Action::Check, Action::Chance(card)]
+    );
+
+    game.cache_normalized_weights();
+    let weights = game.normalized_weights(1).to_vec();
+    game.play(0);
+    assert_eq!(game.undo(), Some(Action::Check));
+    game.cache_normalized_weights();
+    assert_eq!(game.normalized_weights(1), weights);
+
+    assert_eq!(game.undo(), Some(actions[3]));
+    assert_eq!(game.undo(), Some(Action::Chance(card)));
+    assert!(game.is_chance_node());
+    assert_eq!(game.history(), [0, 0]);
+    assert_eq!(game.undo(), Some(Action::Check));
+    assert_eq!(game.undo(), Some(Action::Check));
+    assert_eq!(game.undo(), None);
+}
+
+#[test]
+fn history_with_random_card() {
+    let mut game = build_turn_game();
+    game.apply_line_str("X X").unwrap();
+    let card = game.possible_cards().trailing_zeros() as usize;
+
+    // the dealt card is recorded instead of `usize::MAX`
+    game.play(usize::MAX);
+    assert_eq!(game.history(), [0, 0, card]);
+    game.play(0);
+    let history = game.history().to_vec();
+    game.cache_normalized_weights();
+    let weights = game.normalized_weights(0).to_vec();
+
+    // histories recorded before this change still round-trip
+    game.apply_history(&[0, 0, usize::MAX, 0]);
+    assert_eq!(game.history(), history);
+    game.cache_normalized_weights();
+    assert_eq!(game.normalized_weights(0), weights);
+
+    game.apply_history(&history);
+    assert_eq!(game.history(), history);
+}
+
+#[test]
+fn bookmark() {
+    let mut game = build_turn_game();
+    game.apply_line_str("X X").unwrap();
+    game.play(usize::MAX);
+    game.cache_normalized_weights();
+    let handle = game.bookmark();
+    let history = game.history().to_vec();
+    game.cache_normalized_weights();
+    let weights = game.normalized_weights(0).to_vec();
+    let equity = game.equity(0);
+    assert_eq!(handle.history(), history);
+
+    game.back_to_root();
+    game.play(1);
+    game.goto(&handle);
+    assert_eq!(game.history(), history);
+    assert_eq!(game.normalized_weights(0), weights); // still cached
+    assert_eq!(game.equity(0), equity);
+
+    game.play(0);
+    game.cache_normalized_weights();
+    let child_weights = game.normalized_weights(1).to_vec();
+    game.goto(&handle);
+    game.play(0);
+    game.cache_normalized_weights();
+    assert_eq!(game.normalized_weights(1), child_weights);
+}
+
+#[test]
+#[should_panic(expected = "Invalid node handle")]
+fn bookmark_other_game() {
+    let handle = build_turn_game().bookmark();
+    build_river_game().goto(&handle);
+}
+
+#[test]
+fn best_action_for() {
+    let mut game = build_turn_game();
+
+    // one of the rivers is dealt via suit isomorphism
+    for river in ["2c", "2s"] {
+        game.apply_line_str(&format!("X X {river}")).unwrap();
+        game.cache_normalized_weights();
+
+        let player = game.current_player();
+        let num_hands = game.private_cards(player).len();
+        let num_actions = game.available_actions().len();
+        let strategy = game.strategy();
+        let detail = game.expected_values_detail(player);
+        let weights = game.normalized_weights(player).to_vec();
+
+        for (index, &(c1, c2)) in game.private_cards(player).to_vec().iter().enumerate() {
+            let hand = card_to_string(c1).unwrap() + &card_to_string(c2).unwrap();
+            if weights[index] == 0.0 {
+                assert!(game.best_action_for(&hand).is_err());
+                continue;
+            }
+
+            let result = game.best_action_for(&hand).unwrap();
+            assert_eq!(result.hand, (c1, c2));
+            for action in 0..num_actions {
+                let i = action * num_hands + index;
+                assert!((result.strategy[action] - strategy[i]).abs() < 1e-6);
+                assert!((result.expected_values[action] - detail[i]).abs() < 1e-3);
+            }
+
+            let best = result.expected_values[result.best_action];
+            assert!(result.expected_values.iter().all(|&ev| ev <= best));
+            assert!(result.ev_margin >= 0.0);
+        }
+    }
+
+    assert!(game.best_action_for("AK").is_err());
+}
+
+#[test]
+fn defense_and_bet_report() {
+    let mut game = build_river_game();
+    assert_eq!(game.defense_report(), None);
+
+    let bets = game.bet_report();
+    assert!(!bets.is_empty());
+    for report in &bets {
+        assert_eq!(report.pot, 100);
+        assert!(matches!(report.action, Action::Bet(_) | Action::AllIn(_)));
+        assert!((0.0..=1.0).contains(&report.frequency));
+        assert!((0.0..=1.0).contains(&report.bluff_ratio));
+        assert_eq!(report.alpha, crate::alpha(100, report.bet));
+    }
+
+    // OOP bets 50
+    let bet = &bets[0];
+    assert_eq!(bet.action, Action::Bet(50));
+    game.play(bet.index);
+    let defense = game.defense_report().unwrap();
+    assert_eq!((defense.pot, defense.bet), (100, 50));
+    assert!((defense.mdf - 2.0 / 3.0).abs() < 1e-6);
+
+    let report = game.line_report(None);
+    let fold = report[0]
+        .actions
+        .iter()
+        .position(|&a| a == Action::Fold)
+        .unwrap();
+    let expected = 1.0 - report[0].frequencies[fold];
+    assert!((defense.defense_frequency - expected).abs() < 1e-5);
+
+    // IP raises
+    let raises = game.bet_report();
+    let raise = &raises[0];
+    assert_eq!(raise.pot, 200); // includes the call of 50
+    game.play(raise.index);
+    let defense = game.defense_report().unwrap();
+    assert_eq!((defense.pot, defense.bet), (raise.pot, raise.bet));
+}
+
+#[test]
+fn equity_buckets() {
+    let mut game = build_river_game();
+    game.play(1); // bet
+    let distribution = game.equity_buckets(1, 10);
+    assert_eq!(distribution.buckets.len(), 10);
+    assert!((distribution.buckets.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+
+    let curve = &distribution.curve;
+    assert!((curve.last().unwrap().0 - 1.0).abs() < 1e-5);
+    assert!(curve
+        .windows(2)
+        .all(|w| w[0].0 <= w[1].0 && w[0].1 >= w[1].1));
+
+    // the average equity matches the curve
+    game.cache_normalized_weights();
+    let average = compute_average(&game.equity(1), game.normalized_weights(1));
+    let mut prev = 0.0;
+    let from_curve = curve
+        .iter()
+        .map(|&(x, e)| {
+            let area = (x - prev) * e;
+            prev = x;
+            area
+        })
+        .sum::<f32>();
+    assert!((average - from_curve).abs() < 1e-4);
+
+    // buckets match the curve
+    let high = curve
+        .iter()
+        .take_while(|&&(_, e)| e >= 0.9)
+        .last()
+        .map_or(0.0, |p| p.0);
+    assert!((distribution.buckets[9] - high).abs() < 1e-5);
+}
+
+#[test]
+fn strategy_mixedness() {
+    let game = build_turn_game();
+    let report = game.strategy_mixedness(0.9);
+
+    let mut num_nodes = 0;
+    game.visit_nodes(|_| num_nodes += 1);
+    assert_eq!(report.nodes.len(), num_nodes);
+
+    // the root node is reached with probability 1
+    let root = &report.nodes[0];
+    assert!(root.history.is_empty());
+    assert!((root.reach - 1.0).abs() < 1e-5);
+
+    for node in &report.nodes {
+        assert!(node.entropy >= 0.0);
+        assert!((0.0..=1.0).contains(&node.pure_fraction));
+        assert!((0.0..=1.0 + 1e-5).contains(&node.reach));
+    }
+
+    // the chance probabilities of the river nodes after check-check sum up to 1
+    let mut chance_probability = 0.0;
+    game.visit_nodes(|view| {
+        if view.history().len() == 3 && view.history()[..2] == [0, 0] {
+            chance_probability += view.chance_probability();
+        }
+    });
+    assert!((chance_probability - 1.0).abs() < 1e-6);
+
+    let river_reach = report
+        .nodes
+        .iter()
+        .filter(|node| node.history.len() == 3 && node.history[..2] == [0, 0])
+        .map(|node| node.reach)
+        .sum::<f32>();
+    assert!(river_reach > 0.0 && river_reach <= 1.0 + 1e-5);
+
+    // every combination is pure with a threshold of zero
+    let report = game.strategy_mixedness(0.0);
+    assert!((report.pure_fraction - 1.0).abs() < 1e-5);
+    assert!(report.entropy >= 0.0);
+}
+
+#[test]
+fn expected_value_variances() {
+    let mut game = build_river_game();
+
+    // law of total variance at the root node
+    game.cache_normalized_weights();
+    let num_actions = game.available_actions().len();
+    let num_hands = game.num_private_hands(0);
+    let strategy = game.strategy();
+    let evs = game.expected_values_detail(0);
+    let variance = game.expected_value_variances(0);
+    let mut child_moments = vec![0.0; num_hands];
+    let mut mean = vec![0.0; num_hands];
+    for action in 0..num_actions {
+        game.play(action);
+        game.cache_normalized_weights();
+        let child_variance = game.expected_value_variances(0);
+        for hand in 0..num_hands {
+            let index = action * num_hands + hand;
+            let ev = evs[index] as f64;
+            let prob = strategy[index] as f64;
+            child_moments[hand] += prob * (child_variance[hand] as f64 + ev * ev);
+            mean[hand] += prob * ev;
+        }
+        game.back_to_root();
+    }
+    game.cache_normalized_weights();
+    let weights = game.normalized_weights(0);
+    for hand in 0..num_hands {
+        if weights[hand] > 0.0 {
+            let expected = child_moments[hand] - mean[hand] * mean[hand];
+            assert!((variance[hand] as f64 - expected).abs() < 10.0);
+        }
+    }
+
+    let stdevs = game.expected_value_stdevs(0);
+    for (&s, &v) in stdevs.iter().zip(&variance) {
+        assert!((s * s - v).abs() <= 1e-3 * v.max(1.0));
+    }
+
+    // bet-call: the payoff is either +-half pot or zero
+    game.play(1);
+    game.play(1);
+    assert!(game.is_terminal_node());
+    game.cache_normalized_weights();
+    let half_pot = 0.5 * (100 + game.total_bet_amount().iter().sum::<i32>()) as f32;
+    let evs = game.expected_values(1);
+    let variance = game.expected_value_variances(1);
+    let weights = game.normalized_weights(1);
+    let mut max_ratio = 0.0f32;
+    for hand in 0..game.num_private_hands(1) {
+        if weights[hand] > 0.0 {
+            let mean = evs[hand] - half_pot;
+            let second_moment = variance[hand] + mean * mean;
+            assert!(second_moment <= half_pot * half_pot * 1.001);
+            max_ratio = max_ratio.max(second_moment / (half_pot * half_pot));
+        }
+    }
+    assert!((max_ratio - 1.0).abs() < 1e-3);
+
+    // bet-fold: the payoff is determined
+    game.back_to_root();
+    game.play(1);
+    game.play(0);
+    game.cache_normalized_weights();
+    assert!(game.expected_value_variances(0).iter().all(|&v| v < 1e-2));
+}
+
+#[test]
+fn simulate() {
+    for mut game in [build_river_game(), build_turn_game()] {
+        game.cache_normalized_weights();
+        let exploitability = compute_exploitability(&game);
+
+        for hero in 0..2 {
+            let ev = compute_average(&game.expected_values(hero), game.normalized_weights(hero));
+
+            let result = game.simulate(hero, 20000, 42).unwrap();
+            assert_eq!(result.num_hands, 20000);
+            assert!((result.mean_payoff - ev).abs() < 4.0 * result.standard_error);
+            assert_eq!(result, game.simulate(hero, 20000, 42).unwrap());
+
+            let total_frequency = result.lines.iter().map(|l| l.frequency).sum::<f32>();
+            let total_count = result.lines.iter().map(|l| l.count).sum::<usize>();
+            assert!((total_frequency - 1.0).abs() < 1e-4);
+            assert_eq!(total_count, 20000);
+            assert!(result.lines.windows(2).all(|w| w[0].count >= w[1].count));
+
+            // the solved strategy is not exploited by a villain who always folds to a bet
+            let result = game
+                .simulate_against(hero, 20000, 42, |context| {
+                    assert_eq!(context.player, hero ^ 1);
+                    assert_eq!(context.strategy.len(), context.actions.len());
+                    context
+                        .actions
+                        .iter()
+                        .position(|&action| action == Action::Fold)
+                        .unwrap_or(0)
+                })
+                .unwrap();
+            let tolerance = exploitability + 4.0 * result.standard_error;
+            assert!(result.mean_payoff > ev - tolerance);
+        }
+    }
+
+    let game = build_river_game();
+    assert!(game.simulate(0, 0, 0).is_err());
+}
+
+#[test]
+fn sample_action() {
+    let game = build_river_game();
+    let num_actions = game.available_actions().len();
+    let num_hands = game.num_private_hands(0);
+    let strategy = game.strategy();
+    let board = game.current_board();
+
+    // find the most mixed hand
+    let hand = (0..num_hands)
+        .filter(|&i| {
+            let (c1, c2) = game.private_cards(0)[i];
+            !board.contains(&c1) && !board.contains(&c2)
+        })
+        .max_by(|&i, &j| {
+            let max_i = (0..num_actions).map(|a| strategy[a * num_hands + i]);
+            let max_j = (0..num_actions).map(|a| strategy[a * num_hands + j]);
+            let max_i = max_i.fold(0.0f32, f32::max);
+            let max_j = max_j.fold(0.0f32, f32::max);
+            max_j.total_cmp(&max_i)
+        })
+        .unwrap();
+    let (c1, c2) = game.private_cards(0)[hand];
+    let hand_str = format!(
+        "{}{}",
+        card_to_string(c1).unwrap(),
+        card_to_string(c2).unwrap()
+    );
+
+    let sample = |temperature: f32, seed: u64| {
+        let mut rng = Rng::new(seed);
+        let mut counts = vec![0; num_actions];
+        for _ in 0..10000 {
+            counts[game
+                .sample_action(&hand_str, temperature, &mut rng)
+                .unwrap()] += 1;
+        }
+        counts
+    };
+
+    let counts = sample(1.0, 1);
+    for action in 0..num_actions {
+        let freq = counts[action] as f32 / 10000.0;
+        assert!((freq - strategy[action * num_hands + hand]).abs() < 0.02);
+    }
+    assert_eq!(counts, sample(1.0, 1));
+
+    // zero temperature always returns the most frequent action
+    let hand_strategy = (0..num_actions)
+        .map(|action| strategy[action * num_hands + hand])
+        .collect::<Vec<_>>();
+    let max_prob = hand_strategy.iter().fold(0.0f32, |acc, &p| acc.max(p));
+    let best = hand_strategy.iter().position(|&p| p == max_prob).unwrap();
+    assert_eq!(sample(0.0, 2)[best], 10000);
+
+    // high temperature flattens the strategy
+    let flat = sample(100.0, 3);
+    assert!(flat[best] < counts[best]);
+    for action in 0..num_actions {
+        if hand_strategy[action] == 0.0 {
+            assert_eq!(flat[action], 0);
+        }
+    }
+
+    let mut rng = Rng::new(0);
+    assert!(game.sample_action("AA", 1.0, &mut rng).is_err());
+    assert!(game.sample_action("TdTs", 1.0, &mut rng).is_err());
+}
+
+#[test]
+fn forced_line_ev() {
+    let mut game = build_river_game();
+    game.cache_normalized_weights();
+    let expected_values = game.expected_values(0);
+    let expected_values_detail = game.expected_values_detail(0);
+    let num_hands = game.num_private_hands(0);
+    let bet = game
+        .available_actions()
+        .iter()
+        .position(|&action| action == Action::Bet(50))
+        .unwrap();
+
+    let report = game.forced_line_ev("B50").unwrap();
+    assert_eq!(report.player, 0);
+    assert!(game.history().is_empty());
+
+    let weights = game.normalized_weights(0);
+    for hand in 0..num_hands {
+        if weights[hand] > 0.0 {
+            let detail = expected_values_detail[bet * num_hands + hand];
+            assert!((report.expected_values[hand] - expected_values[hand]).abs() < 0.5);
+            assert!((report.forced_expected_values[hand] - detail).abs() < 0.5);
+        }
+    }
+    assert!(report.average_ev_loss > -0.1);
+
+    // force check-call
+    let report = game.forced_line_ev("X B50 C").unwrap();
+    assert!(report.average_ev_loss > -0.1);
+    assert_eq!(game.forced_line_ev("X-B50-C").unwrap(), report);
+    let weights = game.normalized_weights(0);
+    for hand in 0..num_hands {
+        if weights[hand] == 0.0 {
+            assert_eq!(report.ev_loss[hand], 0.0);
+        }
+    }
+
+    assert!(game.forced_line_ev("").is_err());
+    assert!(game.forced_line_ev("Z").is_err());
+    assert!(game.forced_line_ev("X B50 C X").is_err());
+    assert!(game.history().is_empty());
+}
+
+#[test]
+fn node_summary() {
+    let mut game = build_turn_game();
+    game.play(0); // check
+    let summary = game.node_summary();
+    assert!(game.is_normalized_weight_cached);
+
+    for player in 0..2 {
+        assert_eq!(
+            summary.normalized_weights[player],
+            game.normalized_weights(player)
+        );
+        assert_eq!(summary.equity[player], game.equity(player));
+        assert_eq!(
+            summary.expected_values[player],
+            game.expected_values(player)
+        );
+    }
+
+    let pot = 100 + game.total_bet_amount().iter().sum::<i32>();
+    let total_equity = summary.average_equity[0] + summary.average_equity[1];
+    let total_ev = summary.average_expected_value[0] + summary.average_expected_value[1];
+    assert!((total_equity - 1.0).abs() < 1e-4);
+    assert!((total_ev - pot as f32).abs() < 1e-2 * pot as f32);
+}
+
+#[test]
+fn strategy_by_class() {
+    let mut game = build_river_game();
+    let grid = game.strategy_by_class();
+    assert_eq!(grid.len(), 169);
+    assert_eq!(grid[0].label, "AA");
+    assert_eq!(grid[1].label, "AKs");
+    assert_eq!(grid[13].label, "AKo");
+    assert_eq!(grid[168].label, "22");
+
+    let num_actions = game.available_actions().len();
+    for class in &grid {
+        let sum = class.frequencies.iter().sum::<f32>();
+        if class.combos > 0.0 {
+            assert!((sum - 1.0).abs() < 1e-4);
+        } else {
+            assert_eq!(sum, 0.0);
+        }
+        assert_eq!(class.frequencies.len(), num_actions);
+    }
+
+    // total combos
+    let total = grid.iter().map(|class| class.combos).sum::<f32>();
+    let expected = game.normalized_weights(0).iter().sum::<f32>();
+    assert!((total - expected).abs() < 1e-3 * expected);
+
+    // per-suit breakdown matches the class
+    let aks = game.strategy_by_suits("AKs").unwrap();
+    assert_eq!(aks.len(), 4);
+    let combos = aks.iter().map(|combo| combo.weight).sum::<f32>();
+    assert!((combos - grid[1].combos).abs() < 1e-4);
+    for action in 0..num_actions {
+        let weighted = aks
+            .iter()
+            .map(|combo| combo.weight * combo.frequencies[action])
+            .sum::<f32>();
+        assert!((weighted / combos - grid[1].frequencies[action]).abs() < 1e-4);
+    }
+
+    // Td9d6h Qc 3s: QQ has three combos left
+    assert_eq!(game.strategy_by_suits("QQ").unwrap().len(), 3);
+    assert!(game.strategy_by_suits("AKx").is_err());
+}
+
+#[test]
+fn export_json() {
+    let mut game = build_turn_game();
+    let mut num_nodes = 0;
+    game.visit_nodes(|_| num_nodes += 1);
+
+    let export = |game: &mut PostFlopGame, options: &ExportOptions| {
+        let mut buf = Vec::new();
+        game.export_json(&mut buf, options).unwrap();
+        String::from_utf8(buf).unwrap()
+    };
+
+    let json = export(&mut game, &ExportOptions::default());
+    assert!(json.starts_with("{\"board\":[\"6h\",\"9d\",\"Td\",\"Qd\"],\"starting_pot\":100"));
+    assert!(json.ends_with("]}"));
+    assert_eq!(json.matches("\"line\":").count(), num_nodes);
+    assert_eq!(json.matches("\"expected_values\":").count(), num_nodes);
+    assert_eq!(json.matches('[').count(), json.matches(']').count());
+    assert_eq!(json.matches('{').count(), json.matches('}').count());
+    assert!(!json.contains("\"equity\":"));
+    assert!(game.history().is_empty());
+
+    // every exported line can be applied
+    let lines = json
+        .split("\"line\":[")
+        .skip(1)
+        .map(|s| s[..s.find(']').unwrap()].replace(['"', ','], " "))
+        .collect::<Vec<_>>();
+    for line in &lines {
+        game.apply_line_str(line).unwrap();
+    }
+    game.back_to_root();
+
+    // filters
+    let options = ExportOptions {
+        streets: vec![BoardState::River],
+        lines: vec!["X X".to_string()],
+        precision: 2,
+        expected_values: false,
+        equity: true,
+    };
+    let json = export(&mut game, &options);
+    let num_filtered = json.matches("\"line\":").count();
+    assert!(num_filtered > 0);
+    assert_eq!(json.matches("\"line\":[\"X\",\"X\",").count(), num_filtered);
+    assert_eq!(json.matches("\"equity\":").count(), num_filtered);
+    assert!(!json.contains("\"expected_values\":"));
+    assert!(json.contains("\"weights\":[0.00,"));
+
+    let options = ExportOptions {
+        lines: vec!["Z".to_string()],
+        ..Default::default()
+    };
+    assert!(game.export_json(&mut Vec::new(), &options).is_err());
+}
+
+#[test]
+fn export_node_json() {
+    let mut game = build_turn_game();
+    let options = ExportOptions::default();
+
+    // the root node is the first node of the full export
+    let mut buf = Vec::new();
+    game.export_json(&mut buf, &options).unwrap();
+    let full = String::from_utf8(buf).unwrap();
+    let json = game.export_node_json(&options);
+    assert!(full.contains(&format!("\"nodes\":[{json}")));
+    assert!(json.starts_with("{\"line\":[],\"board\":[\"6h\",\"9d\",\"Td\",\"Qd\"],\"player\":0,"));
+
+    // chance node
+    game.apply_line_str("X X").unwrap();
+    let json = game.export_node_json(&options);
+    assert!(json.starts_with("{\"line\":[\"X\",\"X\"],\"board\":[\"6h\",\"9d\",\"Td\",\"Qd\"],\"player\":null,\"pot\":100,\"actions\":[\"2c\","));
+    assert!(!json.contains("\"strategy\""));
+
+    // the line contains the actual dealt card
+    game.apply_line_str("X X 2s").unwrap();
+    let json = game.export_node_json(&ExportOptions {
+        equity: true,
+        ..options.clone()
+    });
+    assert!(json.starts_with("{\"line\":[\"X\",\"X\",\"2s\"],"));
+    assert!(json.contains("\"equity\":"));
+
+    // terminal node
+    game.apply_line_str("B50 F").unwrap();
+    assert_eq!(
+        game.export_node_json(&options),
+        "{\"line\":[\"B50\",\"F\"],\"board\":[\"6h\",\"9d\",\"Td\",\"Qd\"],\"player\":null,\"pot\":150,\"actions\":[]}"
+    );
+}
+
+#[test]
+fn export_csv() {
+    let mut game = build_river_game();
+    let options = ExportOptions {
+        lines: vec!["B50".to_string()],
+        equity: true,
+        ..Default::default()
+    };
+
+    let mut buf = Vec::new();
+    game.export_csv(&mut buf, &options).unwrap();
+    let csv = String::from_utf8(buf).unwrap();
+    let mut rows = csv.lines();
+    assert_eq!(
+        rows.next(),
+        Some("line,board,player,hand,weight,action,frequency,ev,equity")
+    );
+
+    // IP node facing the bet of 50
+    game.play(1);
+    game.cache_normalized_weights();
+    let num_actions = game.available_actions().len();
+    let strategy = game.strategy();
+    let evs = game.expected_values_detail(1);
+    let equity = game.equity(1);
+    let weights = game.normalized_weights(1);
+    let hands = holes_to_strings(game.private_cards(1)).unwrap();
+
+    let node_rows = rows
+        .clone()
+        .filter(|row| row.starts_with("B50,"))
+        .collect::<Vec<_>>();
+    let num_valid_hands = weights.iter().filter(|&&w| w > 0.0).count();
+    assert_eq!(node_rows.len(), num_valid_hands * num_actions);
+
+    let hand = weights.iter().position(|&w| w > 0.0).unwrap();
+    let columns = node_rows[0].split(',').collect::<Vec<_>>();
+    assert_eq!(columns[1], "6h9dTdQc3s");
+    assert_eq!(columns[2], "1");
+    assert_eq!(columns[3], hands[hand]);
+    assert_eq!(columns[5], "F");
+    let parse = |s: &str| s.parse::<f32>().unwrap();
+    assert!((parse(columns[4]) - weights[hand]).abs() < 1e-3);
+    assert!((parse(columns[6]) - strategy[hand]).abs() < 1e-3);
+    assert!((parse(columns[7]) - evs[hand]).abs() < 1e-3);
+    assert!((parse(columns[8]) - equity[hand]).abs() < 1e-3);
+
+    // every row belongs to the filtered subtree
+    assert!(rows.all(|row| row.starts_with("B50")));
+
+    // the current node is not changed
+    game.export_csv(&mut Vec::new(), &options).unwrap();
+    assert_eq!(game.history(), &[1]);
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn export_record_batches() {
+    use arrow_array::{Array, Float32Array, StringArray};
+
+    let mut game = build_river_game();
+    let options = ExportOptions {
+        lines: vec!["B50".to_string()],
+        ..Default::default()
+    };
+
+    let mut buf = Vec::new();
+    game.export_csv(&mut buf, &options).unwrap();
+    let csv = String::from_utf8(buf).unwrap();
+    let csv_rows = csv.lines().skip(1).collect::<Vec<_>>();
+
+    let batches = game.export_record_batches(&options).unwrap();
+    let num_rows = batches.iter().map(|batch| batch.num_rows()).sum::<usize>();
+    assert_eq!(num_rows, csv_rows.len());
+
+    let batch = &batches[0];
+    let schema = batch.schema();
+    let names = schema.fields().iter().map(|f| f.name().as_str());
+    assert!(names.eq([
+        "line",
+        "board",
+        "player",
+        "hand",
+        "weight",
+        "action",
+        "frequency",
+        "ev",
+        "equity"
+    ]));
+
+    let columns = csv_rows[0].split(',').collect::<Vec<_>>();
+    let column = |name| batch.column_by_name(name).unwrap();
+    let strings = |name| {
+        column(name)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap()
+            .clone()
+    };
+    let floats = |name| {
+        column(name)
+            .as_any()
+            .downcast_ref::<Float32Array>()
+            .unwrap()
+            .clone()
+    };
+    assert_eq!(strings("line").value(0), columns[0]);
+    assert_eq!(strings("hand").value(0), columns[3]);
+    assert_eq!(strings("action").value(0), columns[5]);
+    let parse = |s: &str| s.parse::<f32>().unwrap();
+    assert!((floats("frequency").value(0) - parse(columns[6])).abs() < 1e-3);
+    assert!((floats("ev").value(0) - parse(columns[7])).abs() < 1e-3);
+    assert_eq!(floats("equity").null_count(), batch.num_rows());
+
+    let mut parquet = Vec::new();
+    game.export_parquet(&mut parquet, &options).unwrap();
+    assert!(parquet.starts_with(b"PAR1") && parquet.ends_with(b"PAR1"));
+}
+
+#[test]
+fn street_ev_attribution() {
+    let mut game = build_turn_game();
+    game.cache_normalized_weights();
+    let expected_values = game.expected_values(0);
+    let report = game.street_ev_attribution(0);
+    assert_eq!(report.player, 0);
+
+    let weights = game.normalized_weights(0);
+    for hand in 0..game.num_private_hands(0) {
+        assert_eq!(report.street_gains[0][hand], 0.0);
+        let total_gain = report.street_gains.iter().map(|g| g[hand]).sum::<f32>();
+        let diff = report.expected_values[hand] - report.baseline_expected_values[hand];
+        assert!((diff - total_gain).abs() < 1e-3);
+        if weights[hand] > 0.0 {
+            assert!((report.expected_values[hand] - expected_values[hand]).abs() < 0.5);
+        }
+    }
+    assert!(report.average_street_gains[1] > 0.0);
+    assert!(report.average_street_gains[2] > 0.0);
+
+    // raise until the current player can only fold or call: the baseline is the average action EV
+    let mut game = build_river_game();
+    while game.available_actions() != [Action::Fold, Action::Call] {
+        game.play(game.available_actions().len() - 1);
+    }
+    let player = game.current_player();
+    game.cache_normalized_weights();
+    let num_actions = game.available_actions().len();
+    let num_hands = game.num_private_hands(player);
+    let detail = game.expected_values_detail(player);
+    let report = game.street_ev_attribution(player);
+
+    let weights = game.normalized_weights(player);
+    for hand in 0..num_hands {
+        if weights[hand] > 0.0 {
+            let baseline = (0..num_actions)
+                .map(|action| detail[action * num_hands + hand])
+                .sum::<f32>()
+                / num_actions as f32;
+            assert!((report.baseline_expected_values[hand] - baseline).abs() < 0.5);
+        }
+    }
+    assert!(report.average_street_gains[2] > 0.0);
+}
+
+#[test]
+fn node_info() {
+    let mut game = build_river_game();
+    let info = game.node_info();
+    assert_eq!(info.street, BoardState::River);
+    assert_eq!(info.board, game.current_board());
+    assert_eq!(info.player, Some(0));
+    assert_eq!(info.pot, 100);
+    assert_eq!(info.stacks, [400, 400]);
+    assert_eq!(info.amount_to_call, 0);
+    assert_eq!(info.previous_action, Action::None);
+    assert!(!info.is_terminal && !info.is_chance);
+
+    game.play(1);
+    let info = game.node_info();
+    assert_eq!(info.player, Some(1));
+    assert_eq!(info.pot, 150);
+    assert_eq!(info.stacks, [350, 400]);
+    assert_eq!(info.amount_to_call, 50);
+    assert_eq!(info.previous_action, Action::Bet(50));
+
+    game.play(0);
+    let info = game.node_info();
+    assert!(info.is_terminal);
+    assert_eq!(info.player, None);
+    assert_eq!(info.amount_to_call, 0);
+    assert_eq!(info.previous_action, Action::Fold);
+
+    let mut game = build_turn_game();
+    assert_eq!(game.node_info().street, BoardState::Turn);
+    game.apply_line_str("X X").unwrap();
+    let info = game.node_info();
+    assert!(info.is_chance);
+    assert_eq!(info.player, None);
+
+    let card = (0..52)
+        .find(|&c| game.possible_cards() & (1 << c) != 0)
+        .unwrap();
+    game.play(card);
+    let info = game.node_info();
+    assert_eq!(info.street, BoardState::River);
+    assert_eq!(info.previous_action, Action::Chance(card as Card));
+}
+
+#[test]
+fn subtree_game() {
+    let mut game = build_turn_game();
+
+    // rooted at the root node
+    let mut subtree = game.subtree_game().unwrap();
+    game.cache_normalized_weights();
+    subtree.cache_normalized_weights();
+    for player in 0..2 {
+        let expected_values = game.expected_values(player);
+        let subtree_expected_values = subtree.expected_values(player);
+        for (x, y) in subtree_expected_values.iter().zip(&expected_values) {
+            assert!((x - y).abs() < 1e-3);
+        }
+    }
+
+    game.apply_line_str("B50 C").unwrap();
+    let card = (0..52)
+        .find(|&c| game.possible_cards() & (1 << c) != 0)
+        .unwrap();
+    game.play(card);
+
+    let history = game.history().to_vec();
+    let mut subtree = game.subtree_game().unwrap();
+    assert_eq!(game.history(), history);
+    assert!(subtree.is_solved());
+    assert!(subtree.memory_usage().0 < game.memory_usage().0);
+
+    assert_eq!(subtree.tree_config().initial_state, BoardState::River);
+    assert_eq!(subtree.tree_config().starting_pot, 200);
+    assert_eq!(subtree.tree_config().effective_stack, 350);
+    assert_eq!(subtree.current_board(), game.current_board());
+    assert_eq!(subtree.available_actions(), game.available_actions());
+
+    game.cache_normalized_weights();
+    subtree.cache_normalized_weights();
+
+    for player in 0..2 {
+        let expected_values = game.expected_values(player);
+        let subtree_expected_values = subtree.expected_values(player);
+        for (i, hand) in subtree.private_cards(player).iter().enumerate() {
+            let j = game.private_cards(player).binary_search(hand).unwrap();
+            assert!((subtree_expected_values[i] - expected_values[j]).abs() < 1e-3);
+        }
+    }
+
+    let strategy = game.strategy();
+    let subtree_strategy = subtree.strategy();
+    let num_hands = game.private_cards(0).len();
+    let subtree_num_hands = subtree.private_cards(0).len();
+    for (i, hand) in subtree.private_cards(0).iter().enumerate() {
+        let j = game.private_cards(0).binary_search(hand).unwrap();
+        for action in 0..subtree.available_actions().len() {
+            let x = subtree_strategy[action * subtree_num_hands + i];
+            let y = strategy[action * num_hands + j];
+            assert!((x - y).abs() < 1e-5);
+        }
+    }
+
+    // not the first decision of a street
+    game.play(1);
+    assert!(game.subtree_game().is_err());
+}
+
+#[test]
+fn solve_8bit_storage() {
+    let mut game = build_river_game();
+    game.allocate_memory_with_format(StorageFormat::Int8, None)
+        .unwrap();
+    assert_eq!(
+        game.allocated_storage_formats(),
+        Some((StorageFormat::Int8, StorageFormat::Int8))
+    );
+    assert_eq!(game.is_memory_allocated(), Some(true));
+
+    let (_, compressed) = game.memory_usage();
+    let usage = game.memory_usage_with_format(StorageFormat::Int8);
+    assert!(usage < compressed);
+
+    let exploitability = solve(&mut game, 1000, 0.0, false);
+    assert!(exploitability < 0.5);
+
+    let strategy = game.strategy();
+    let num_hands = game.num_private_hands(game.current_player());
+    for hand in 0..num_hands {
+        let sum = strategy.iter().skip(hand).step_by(num_hands).sum::<f32>();
+        assert!((sum - 1.0).abs() < 1e-3);
+    }
+
+    game.cache_normalized_weights();
+    let weights = game.normalized_weights(0).to_vec();
+    let ev = compute_average(&game.expected_values(0), &weights);
+    assert!(ev.is_finite());
+}
+
+#[test]
+fn solve_half_precision_storage() {
+    let formats = [
+        (StorageFormat::Float16, StorageFormat::Float16),
+        (StorageFormat::Float32, StorageFormat::Float16),
+        (StorageFormat::Float16, StorageFormat::Int16),
+    ];
+
+    for (strategy_format, value_format) in formats {
+        let mut game = build_river_game();
+        game.allocate_memory_with_formats(strategy_format, value_format, None)
+            .unwrap();
+
+        let (uncompressed, _) = game.memory_usage();
+        let usage = game.memory_usage_with_formats(strategy_format, value_format);
+        assert!(usage < uncompressed);
+
+        let exploitability = solve(&mut game, 1000, 0.0, false);
+        assert!(exploitability < 0.05);
+
+        game.cache_normalized_weights();
+        let weights = game.normalized_weights(0).to_vec();
+        let ev = compute_average(&game.expected_values(0), &weights);
+        assert!(ev.is_finite());
+    }
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn solve_with_scratch_file_storage() {
+    let dir = std::env::temp_dir().join(format!("postflop-solver-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let game = build_river_game();
+    let card_config = game.card_config().clone();
+    let action_tree = ActionTree::new(game.tree_config().clone()).unwrap();
+    let mut file_backed = PostFlopGame::with_config(card_config, action_tree).unwrap();
+    assert!(file_backed
+        .set_storage_backend(StorageBackend::ScratchFile(dir.join("missing")))
+        .is_err());
+
+    file_backed
+        .set_storage_backend(StorageBackend::ScratchFile(dir.clone()))
+        .unwrap();
+    file_backed.allocate_memory(false, None).unwrap();
+    assert!(file_backed.is_file_backed());
+    // the river game has no chance nodes, so the chance storage is not allocated
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 3);
+
+    let exploitability = compute_exploitability(&game);
+    let file_exploitability = solve(&mut file_backed, 200, 0.0, false);
+    assert!((exploitability - file_exploitability).abs() < 1e-4);
+    assert!(file_backed
+        .set_storage_backend(StorageBackend::Memory)
+        .is_err());
+
+    drop(file_backed);
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+    std::fs::remove_dir(&dir).unwrap();
+}
+
+#[test]
+fn trim_to_strategy() {
+    let collect = |game: &mut PostFlopGame| {
+        game.back_to_root();
+        game.cache_normalized_weights();
+        let mut ret = vec![game.strategy()];
+        game.play(0); // check
+        ret.push(game.strategy());
+        game.play(0); // check
+        game.play(card_from_str("2c").unwrap() as usize);
+        ret.push(game.strategy());
+        ret
+    };
+
+    let mut reference = build_turn_game();
+    let strategies = collect(&mut reference);
+    reference.back_to_root();
+    reference.cache_normalized_weights();
+    let ev = reference.expected_values(0);
+
+    let mut game = build_turn_game();
+    let freed = game.trim_to_strategy(true).unwrap();
+    let (uncompressed, compressed) = game.memory_usage();
+    assert_eq!(freed as u64, uncompressed - compressed);
+    assert_eq!(game.strategy_format(), StorageFormat::Int16);
+    assert_eq!(game.value_format(), StorageFormat::Int16);
+
+    for (lhs, rhs) in collect(&mut game).iter().zip(&strategies) {
+        assert!(lhs.iter().zip(rhs).all(|(x, y)| (x - y).abs() < 1e-4));
+    }
+
+    game.back_to_root();
+    game.cache_normalized_weights();
+    let trimmed_ev = game.expected_values(0);
+    assert!(trimmed_ev.iter().zip(&ev).all(|(x, y)| (x - y).abs() < 0.1));
+
+    let mut game = build_turn_game();
+    let freed = game.trim_to_strategy(false).unwrap();
+    assert!(freed as u64 > uncompressed - compressed);
+
+    for (lhs, rhs) in collect(&mut game).iter().zip(&strategies) {
+        assert!(lhs.iter().zip(rhs).all(|(x, y)| (x - y).abs() < 1e-4));
+    }
+
+    game.back_to_root();
+    game.cache_normalized_weights();
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        game.expected_values(0);
+    }));
+    assert!(result.is_err());
+    assert_eq!(game.trim_to_strategy(true), Ok(0));
+    assert_eq!(
+        game.set_weight(0, 0, 0.5),
+        Err(PostFlopError::InvalidState(
+            "Expected values are trimmed".to_string()
+        ))
+    );
+}
+
+#[test]
+fn convert_memory() {
+    let collect = |game: &mut PostFlopGame| {
+        game.back_to_root();
+        game.cache_normalized_weights();
+        let ret = (game.strategy(), game.expected_values(0));
+        game.play(0); // check
+        game.play(0); // check
+        game.play(card_from_str("2c").unwrap() as usize);
+        game.cache_normalized_weights();
+        (ret, (game.strategy(), game.expected_values(1)))
+    };
+    let assert_close = |lhs: &[f32], rhs: &[f32], eps: f32| {
+        assert!(lhs.iter().zip(rhs).all(|(x, y)| (x - y).abs() < eps));
+    };
+
+    let mut game = build_turn_game();
+    let reference = collect(&mut game);
+
+    for enable_compression in [true, false] {
+        game.convert_memory(enable_compression).unwrap();
+        assert_eq!(game.is_compression_enabled(), enable_compression);
+
+        let result = collect(&mut game);
+        for (lhs, rhs) in [(&result.0, &reference.0), (&result.1, &reference.1)] {
+            assert_close(&lhs.0, &rhs.0, 1e-3);
+            assert_close(&lhs.1, &rhs.1, 0.05);
+        }
+    }
+
+    // continue solving after the conversion
+    let reference = build_river_game();
+    let card_config = reference.card_config().clone();
+    let action_tree = ActionTree::new(reference.tree_config().clone()).unwrap();
+    let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
+    game.allocate_memory(false, None).unwrap();
+    for t in 0..100 {
+        solve_step(&game, t);
+    }
+    game.convert_memory_with_formats(StorageFormat::Float16, StorageFormat::Int16)
+        .unwrap();
+    for t in 100..200 {
+        solve_step(&game, t);
+    }
+    finalize(&mut game);
+    let exploitability = compute_exploitability(&game);
+    assert!(exploitability < compute_exploitability(&reference) * 1.5);
+}
+
+#[test]
+fn memory_usage_detailed() {
+    let mut game = build_turn_game();
+    let detail = game.memory_usage_detailed();
+    let (uncompressed, _) = game.memory_usage();
+    assert_eq!(detail.total(), uncompressed);
+    assert_eq!(detail.street_total(BoardState::Flop), 0);
+    assert!(detail.street_total(BoardState::River) > detail.street_total(BoardState::Turn));
+    assert!(detail.cfvalues_chance[1] > 0);
+    assert_eq!(detail.bunching, 0);
+
+    game.convert_memory_with_formats(StorageFormat::Float16, StorageFormat::Int8)
+        .unwrap();
+    let detail = game.memory_usage_detailed();
+    let usage = game.memory_usage_with_formats(StorageFormat::Float16, StorageFormat::Int8);
+    assert_eq!(detail.total(), usage);
+    assert_eq!(detail.strategy[2], 2 * detail.regrets[2]);
+
+    game.trim_to_strategy(false).unwrap();
+    let detail = game.memory_usage_detailed();
+    assert_eq!(detail.regrets, [0; 3]);
+    assert_eq!(detail.cfvalues_chance, [0; 3]);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn solve_with_thread_pool() {
+    let reference = build_river_game();
+    let card_config = reference.card_config().clone();
+    let tree_config = reference.tree_config().clone();
+
+    let mut game = PostFlopGame::with_config(
+        card_config.clone(),
+        ActionTree::new(tree_config.clone()).unwrap(),
+    )
+    .unwrap();
+    game.allocate_memory(false, None).unwrap();
+    let exploitability = solve_with_num_threads(&mut game, 2, 200, 0.0, false).unwrap();
+    assert_eq!(exploitability, compute_exploitability(&reference));
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .unwrap();
+    let mut game =
+        PostFlopGame::with_config(card_config, ActionTree::new(tree_config).unwrap()).unwrap();
+    game.allocate_memory(false, None).unwrap();
+    let exploitability = solve_in_pool(&pool, &mut game, 200, 0.0, false);
+    assert_eq!(exploitability, compute_exploitability(&reference));
+}
+
+#[cfg(all(feature = "numa", target_os = "linux"))]
+#[test]
+fn solve_with_numa() {
+    let nodes = numa_nodes();
+    assert!(!nodes.is_empty());
+    assert!(nodes.iter().all(|cpus| cpus.iter().all(|&cpu| cpu < 1024)));
+
+    let reference = build_river_game();
+    let card_config = reference.card_config().clone();
+    let action_tree = ActionTree::new(reference.tree_config().clone()).unwrap();
+    let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
+    game.set_storage_backend(StorageBackend::Interleaved)
+        .unwrap();
+    game.allocate_memory(false, None).unwrap();
+
+    let pool = pinned_thread_pool(2).unwrap();
+    let exploitability = solve_in_pool(&pool, &mut game, 200, 0.0, false);
+    assert_eq!(exploitability, compute_exploitability(&reference));
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn build_tree_in_parallel() {
+    let card_config = CardConfig {
+        range: ["AA,KK,QQ,AK".parse().unwrap(), "JJ,TT,AQ".parse().unwrap()],
+        flop: flop_from_str("Td9d6h").unwrap(),
+        ..Default::default()
+    };
+    let bet_sizes: BetSizeOptions = ("50%", "").try_into().unwrap();
+    let tree_config = TreeConfig {
+        initial_state: BoardState::Flop,
+        starting_pot: 100,
+        effective_stack: 100,
+        flop_bet_sizes: [bet_sizes.clone(), bet_sizes.clone()],
+        turn_bet_sizes: [bet_sizes.clone(), bet_sizes.clone()],
+        river_bet_sizes: [bet_sizes.clone(), bet_sizes],
+        ..Default::default()
+    };
+
+    let build = |num_threads| {
+        let action_tree = ActionTree::new(tree_config.clone()).unwrap();
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .unwrap()
+            .install(|| PostFlopGame::with_config(card_config.clone(), action_tree).unwrap())
+    };
+    let sequential = build(1);
+    let parallel = build(4);
+
+    assert_eq!(sequential.num_nodes, parallel.num_nodes);
+    assert_eq!(sequential.num_storage, parallel.num_storage);
+    assert_eq!(sequential.num_storage_ip, parallel.num_storage_ip);
+    assert_eq!(sequential.num_storage_chance, parallel.num_storage_chance);
+    assert_eq!(sequential.misc_memory_usage, parallel.misc_memory_usage);
+    assert!(sequential.valid_indices_turn == parallel.valid_indices_turn);
+    assert!(sequential.valid_indices_river == parallel.valid_indices_river);
+    assert!(sequential.hand_strength == parallel.hand_strength);
+
+    let node_info = |game: &PostFlopGame| {
+        game.node_arena
+            .iter()
+            .map(|node| {
+                let node = node.lock();
+                (
+                    node.prev_action,
+                    node.player,
+                    node.turn,
+                    node.river,
+                    node.amount,
+                    node.children_offset,
+                    node.num_children,
+                    node.num_elements,
+                    node.num_elements_ip,
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(node_info(&sequential), node_info(&parallel));
+}
+
+#[test]
+fn query_into_buffers() {
+    let mut game = build_turn_game();
+    let river = card_from_str("2c").unwrap() as usize;
+    let histories: [&[usize]; 4] = [&[], &[0], &[0, 0], &[0, 0, river]];
+
+    let mut buf = Vec::new();
+    for history in histories {
+        game.apply_history(history);
+        game.cache_normalized_weights();
+
+        for player in 0..2 {
+            game.equity_into(player, &mut buf);
+            assert_eq!(buf, game.equity(player));
+            game.expected_values_into(player, &mut buf);
+            assert_eq!(buf, game.expected_values(player));
+            game.expected_values_detail_into(player, &mut buf);
+            assert_eq!(buf, game.expected_values_detail(player));
+        }
+
+        if !game.is_chance_node() {
+            game.strategy_into(&mut buf);
+            assert_eq!(buf, game.strategy());
+        }
+    }
+
+    // once grown, the buffer is reused
+    let (ptr, capacity) = (buf.as_ptr(), buf.capacity());
+    for history in histories {
+        game.apply_history(history);
+        game.cache_normalized_weights();
+        game.expected_values_detail_into(0, &mut buf);
+        game.equity_into(1, &mut buf);
+    }
+    assert_eq!(buf.as_ptr(), ptr);
+    assert_eq!(buf.capacity(), capacity);
+}
+
+#[cfg(feature = "profile")]
+#[test]
+fn solver_stats() {
+    let game = build_turn_game();
+    let summary = game.solve_summary().unwrap();
+    let stats = game.solver_stats().unwrap();
+
+    assert_eq!(stats.iteration_times.len(), summary.num_iterations as usize);
+    assert!(stats.total_iteration_time() > Duration::ZERO);
+    assert!(stats.mean_iteration_time() <= stats.total_iteration_time());
+    assert!(stats.exploitability_time > Duration::ZERO);
+
+    // the game starts at the turn
+    assert_eq!(stats.evaluation_time[0], Duration::ZERO);
+    assert_eq!(stats.traversal_time[0], Duration::ZERO);
+    assert!(stats.evaluation_time[1] > Duration::ZERO);
+    assert!(stats.evaluation_time[2] > Duration::ZERO);
+    assert!(stats.traversal_time[1] > Duration::ZERO);
+    assert!(stats.traversal_time[2] > Duration::ZERO);
+
+    // the statistics are reset by reallocating the memory
+    let mut game = game;
+    game.allocate_memory(true, None).unwrap();
+    assert!(game.solver_stats().is_none());
+}
+
+#[test]
+fn hand_abstraction() {
+    let card_config = CardConfig {
+        range: [
+            "AA,KK,QQ,AK,AQ,KQ".parse().unwrap(),
+            "JJ-88,AJ,KJ,QJ".parse().unwrap(),
+        ],
+        flop: flop_from_str("Td9d6d").unwrap(),
+        turn: card_from_str("2c").unwrap(),
+        ..Default::default()
+    };
+    let bet_sizes: BetSizeOptions = ("50%", "").try_into().unwrap();
+    let tree_config = TreeConfig {
+        initial_state: BoardState::Turn,
+        starting_pot: 100,
+        effective_stack: 200,
+        turn_bet_sizes: [bet_sizes.clone(), bet_sizes.clone()],
+        river_bet_sizes: [bet_sizes.clone(), bet_sizes],
+        ..Default::default()
+    };
+
+    let action_tree = ActionTree::new(tree_config.clone()).unwrap();
+    let mut full = PostFlopGame::with_config(card_config.clone(), action_tree).unwrap();
+    let action_tree = ActionTree::new(tree_config).unwrap();
+    let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
+    game.set_hand_abstraction(true).unwrap();
+    assert!(game.is_hand_abstracted());
+    assert!(game.memory_usage().0 < full.memory_usage().0);
+
+    for player in 0..2 {
+        // hearts and spades are interchangeable
+        assert!(game.num_private_hands(player) < full.num_private_hands(player));
+        assert_eq!(game.full_private_cards(player), full.private_cards(player));
+
+        let indices = game.abstract_hand_indices(player);
+        let hands = game.private_cards(player);
+        let mut weight_sum = vec![0.0; hands.len()];
+        for (i, &(c1, c2)) in full.private_cards(player).iter().enumerate() {
+            let (r1, r2) = hands[indices[i] as usize];
+            assert_eq!((c1 >> 2, c2 >> 2), (r1 >> 2, r2 >> 2));
+            weight_sum[indices[i] as usize] += full.initial_weights[player][i];
+        }
+        assert_eq!(weight_sum, game.initial_weights[player]);
+    }
+
+    full.allocate_memory(false, None).unwrap();
+    game.allocate_memory(false, None).unwrap();
+    solve(&mut full, 300, 0.0, false);
+    solve(&mut game, 300, 0.0, false);
+    full.cache_normalized_weights();
+    game.cache_normalized_weights();
+
+    for player in 0..2 {
+        let ev_full = full.expected_values(player);
+        let ev_game = game.expected_values(player);
+        let average_full = compute_average(&ev_full, full.normalized_weights(player));
+        let average_game = compute_average(&ev_game, game.normalized_weights(player));
+        assert!((average_full - average_game).abs() < 2.0);
+
+        let expanded = game.expand_hand_values(player, &ev_game);
+        assert_eq!(expanded.len(), ev_full.len());
+    }
+
+    let strategy = game.expand_hand_values(0, &game.strategy());
+    assert_eq!(strategy.len(), full.strategy().len());
+
+    #[cfg(feature = "bincode")]
+    assert!(crate::save_data_into_std_write(&game, "", &mut Vec::new(), None).is_err());
+    assert_eq!(
+        game.set_weight(0, 0, 0.5),
+        Err(PostFlopError::Unsupported(
+            "Weights cannot be changed with hand abstraction".to_string()
+        ))
+    );
+
+    // disabling the abstraction rebuilds the full game tree
+    game.set_hand_abstraction(false).unwrap();
+    assert_eq!(game.is_memory_allocated(), None);
+    assert_eq!(game.num_private_hands(0), full.num_private_hands(0));
+    assert!(game.abstract_hand_indices(0).is_empty());
+}
+
+#[test]
+fn card_bucketing() {
+    let card_config = CardConfig {
+        range: [
+            "AA,KK,QQ,AK,AQ".parse().unwrap(),
+            "JJ-88,AJ,KJ".parse().unwrap(),
+        ],
+        flop: flop_from_str("Td9d6h").unwrap(),
+        turn: card_from_str("Qs").unwrap(),
+        ..Default::default()
+    };
+    let bet_sizes: BetSizeOptions = ("50%", "").try_into().unwrap();
+    let tree_config = TreeConfig {
+        initial_state: BoardState::Turn,
+        starting_pot: 100,
+        effective_stack: 200,
+        turn_bet_sizes: [bet_sizes.clone(), bet_sizes.clone()],
+        river_bet_sizes: [bet_sizes.clone(), bet_sizes],
+        ..Default::default()
+    };
+
+    let action_tree = ActionTree::new(tree_config.clone()).unwrap();
+    let mut full = PostFlopGame::with_config(card_config.clone(), action_tree).unwrap();
+    let action_tree = ActionTree::new(tree_config).unwrap();
+    let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
+
+    assert!(game.set_card_buckets(&[vec![13]]).is_err());
+    assert!(game.set_card_buckets(&[vec![0, 1], vec![1]]).is_err());
+    assert!(game.set_card_buckets(&[vec![]]).is_err());
+
+    // 2-5 of each suit are merged, which do not affect the hand strengths of the ranges
+    let buckets = [vec![0, 1, 2, 3]];
+    game.set_card_buckets(&buckets).unwrap();
+    assert_eq!(game.card_buckets(), &buckets);
+    assert!(game.memory_usage().0 < full.memory_usage().0);
+
+    full.allocate_memory(false, None).unwrap();
+    game.allocate_memory(false, None).unwrap();
+    solve(&mut full, 300, 0.0, false);
+    solve(&mut game, 300, 0.0, false);
+    full.cache_normalized_weights();
+    game.cache_normalized_weights();
+
+    for player in 0..2 {
+        let ev_full = full.expected_values(player);
+        let ev_game = game.expected_values(player);
+        let average_full = compute_average(&ev_full, full.normalized_weights(player));
+        let average_game = compute_average(&ev_game, game.normalized_weights(player));
+        assert!((average_full - average_game).abs() < 1e-3);
+    }
+
+    // 3 of the 4 cards of each suit are bucketed
+    game.play(1);
+    game.play(1);
+    assert_eq!(game.available_actions().len(), 48 - 12);
+    assert_eq!(game.possible_cards().count_ones(), 48);
+
+    // 3c is solved with the subtree of 2c
+    let card_2c = card_from_str("2c").unwrap();
+    let card_3c = card_from_str("3c").unwrap();
+    game.play(card_2c as usize);
+    let strategy_2c = game.strategy();
+    game.back_to_root();
+    game.play(1);
+    game.play(1);
+    game.play(card_3c as usize);
+    assert_eq!(game.current_board().last(), Some(&card_3c));
+    let strategy_3c = game.strategy();
+
+    for (i, &(c1, c2)) in game.private_cards(0).iter().enumerate() {
+        let cards = [c1, c2];
+        if !cards.contains(&card_2c) && !cards.contains(&card_3c) {
+            assert_eq!(strategy_2c[i], strategy_3c[i]);
+        }
+    }
+
+    #[cfg(feature = "bincode")]
+    assert!(crate::save_data_into_std_write(&game, "", &mut Vec::new(), None).is_err());
+
+    // disabling the bucketing rebuilds the full game tree
+    game.set_card_buckets(&[]).unwrap();
+    assert!(game.card_buckets().is_empty());
+    assert_eq!(game.is_memory_allocated(), None);
+    assert_eq!(game.memory_usage(), full.memory_usage());
+}
+
+#[test]
+fn card_bucketing_turn() {
+    let card_config = CardConfig {
+        range: ["AA,KK".parse().unwrap(), "QQ,JJ".parse().unwrap()],
+        flop: flop_from_str("Td9d6h").unwrap(),
+        ..Default::default()
+    };
+    let tree_config = TreeConfig {
+        starting_pot: 100,
+        effective_stack: 100,
+        ..Default::default()
+    };
+
+    let action_tree = ActionTree::new(tree_config).unwrap();
+    let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
+    game.set_card_buckets(&[vec![0, 1, 2, 3]]).unwrap();
+    game.allocate_memory(false, None).unwrap();
+    solve(&mut game, 10, 0.0, false);
+
+    // the turn 3c is solved with the subtree of 2c, where the river 2c is the bucketed 3c
+    let card = |s| card_from_str(s).unwrap();
+    game.play(0);
+    game.play(0);
+    assert_eq!(game.available_actions().len(), 49 - 12);
+    game.play(card("3c") as usize);
+    game.play(0);
+    game.play(0);
+    game.play(card("2c") as usize);
+    assert_eq!(game.current_board()[3..], [card("3c"), card("2c")]);
+    game.cache_normalized_weights();
+    assert_eq!(game.expected_values(0).len(), game.num_private_hands(0));
+
+    game.back_to_root();
+    game.play(0);
+    game.play(0);
+    game.play(card("3c") as usize);
+    game.play(0);
+    game.play(0);
+    game.play(card("4c") as usize);
+    assert_eq!(game.current_board()[3..], [card("3c"), card("4c")]);
+}
+
+#[test]
+fn game_builder() {
+    let game = PostFlopGame::builder()
+        .oop_range("AA,KK,QQ,AK")
+        .ip_range("JJ-88,AQ,KQ")
+        .board("Td 9d 6h Qc")
+        .pot(100)
+        .stack(200)
+        .bet_sizes("50%", "2x")
+        .rake(0.05, 10.0)
+        .build()
+        .unwrap();
+
+    let bet_sizes = BetSizeOptions::try_from(("50%", "2x")).unwrap();
+    let card_config = CardConfig {
+        range: [
+            "AA,KK,QQ,AK".parse().unwrap(),
+            "JJ-88,AQ,KQ".parse().unwrap(),
+        ],
+        flop: flop_from_str("Td9d6h").unwrap(),
+        turn: card_from_str("Qc").unwrap(),
+        river: NOT_DEALT,
+    };
+    let tree_config = TreeConfig {
+        initial_state: BoardState::Turn,
+        starting_pot: 100,
+        effective_stack: 200,
+        rake_rate: 0.05,
+        rake_cap: 10.0,
+        turn_bet_sizes: [bet_sizes.clone(), bet_sizes.clone()],
+        river_bet_sizes: [bet_sizes.clone(), bet_sizes.clone()],
+        flop_bet_sizes: [bet_sizes.clone(), bet_sizes],
+        ..Default::default()
+    };
+    let action_tree = ActionTree::new(tree_config).unwrap();
+    let expected = PostFlopGame::with_config(card_config, action_tree).unwrap();
+    let debug = |game: &PostFlopGame| format!("{:?}", (game.card_config(), game.tree_config()));
+    assert_eq!(debug(&game), debug(&expected));
+    assert_eq!(game.available_actions(), expected.available_actions());
+
+    // the first error is reported
+    let error = |builder: PostFlopGameBuilder| builder.build().err().unwrap().to_string();
+    let builder = PostFlopGame::builder().oop_range("AA").ip_range("KK");
+    assert_eq!(error(builder.clone()), "Board is not set");
+    assert_eq!(
+        error(builder.clone().board("Td9d").pot(100)),
+        "Board must have 3 to 5 cards: 2"
+    );
+    assert_eq!(
+        error(builder.clone().board("Td9d6h").pot(0).stack(-1)),
+        "Starting pot must be positive: 0"
+    );
+    assert_eq!(
+        error(builder.clone().board("Td9d6h").pot(100)),
+        "Effective stack is not set"
+    );
+
+    // structured errors
+    let card = |s| card_from_str(s).unwrap();
+    let result = builder.clone().board("TdTd6h").build();
+    assert_eq!(
+        result.err(),
+        Some(PostFlopError::BoardConflict { card: card("Td") })
+    );
+    let result = builder
+        .clone()
+        .board("Td9d6h")
+        .pot(100)
+        .stack(100)
+        .bet_sizes("50", "");
+    assert!(matches!(
+        result.build().err(),
+        Some(PostFlopError::InvalidBetSize { input, .. }) if input == "50"
+    ));
+    let result = PostFlopGame::builder().oop_range("AA,AKx,KK").build();
+    assert!(matches!(
+        result.err(),
+        Some(PostFlopError::InvalidRange { input, position: 1, .. }) if input == "AKx"
+    ));
+}
+
+#[test]
+fn solve_time_estimation() {
+    let new_game = || {
+        let mut game = PostFlopGame::builder()
+            .oop_range("AA,KK,QQ,AK")
+            .ip_range("JJ-88,AQ,KQ")
+            .board("Td9d6hQc")
+            .pot(100)
+            .stack(200)
+            .bet_sizes("50%", "")
+            .build()
+            .unwrap();
+        game.allocate_memory(false, None).unwrap();
+        game
+    };
+
+    let mut game = new_game();
+    let estimate = estimate_solve_time(&game, 0.1);
+    assert_eq!(estimate.num_calibration_iterations, 16);
+    let num_iterations = estimate.num_iterations.unwrap();
+    assert!(num_iterations > 16);
+    assert!(estimate.time.unwrap() >= estimate.time_per_iteration * num_iterations);
+
+    // the calibration iterations are the first iterations of the solve
+    let mut expected = new_game();
+    for t in 0..16 {
+        solve_step(&expected, t);
+    }
+    finalize(&mut expected);
+    finalize(&mut game);
+    assert_eq!(game.strategy(), expected.strategy());
+
+    // the target is reached before the calibration completes
+    let game = new_game();
+    let estimate = estimate_solve_time(&game, 1e9);
+    assert_eq!(estimate.num_calibration_iterations, 0);
+    assert_eq!(estimate.num_iterations, Some(0));
+    assert_eq!(estimate.time, Some(estimate.exploitability_time));
+}
+
+#[test]
+fn fallible_allocation() {
+    let mut game = PostFlopGame::builder()
+        .oop_range("AA,KK,QQ,AK")
+        .ip_range("JJ-88,AQ,KQ")
+        .board("Td9d6hQc")
+        .pot(100)
+        .stack(200)
+        .bet_sizes("50%", "")
+        .build()
+        .unwrap();
+
+    let (uncompressed, compressed) = game.memory_usage();
+    assert_eq!(
+        game.allocate_memory(false, Some(compressed)),
+        Err(PostFlopError::MemoryLimit {
+            required: uncompressed,
+            available: compressed,
+        })
+    );
+    assert!(!game.is_ready());
+
+    assert_eq!(game.allocate_memory(true, Some(compressed)), Ok(()));
+    assert!(game.is_ready());
+    solve(&mut game, 10, 0.0, false);
+
+    // the exceeding request does not discard the solved game
+    assert!(game.allocate_memory(false, Some(compressed)).is_err());
+    assert!(game.is_solved());
+
+    assert_eq!(game.allocate_memory(false, None), Ok(()));
+    assert!(game.is_ready() && !game.is_solved());
+}
+
+#[test]
+fn automatic_storage_format() {
+    let mut game = PostFlopGame::builder()
+        .oop_range("AA,KK,QQ,AK")
+        .ip_range("JJ-88,AQ,KQ")
+        .board("Td9d6hQc")
+        .pot(100)
+        .stack(200)
+        .bet_sizes("50%", "")
+        .build()
+        .unwrap();
+
+    use StorageFormat::*;
+    let usage = |formats: (_, _)| game.memory_usage_with_formats(formats.0, formats.1);
+    let float32 = usage((Float32, Float32));
+    let mixed = usage((Int16, Float32));
+    let half = usage((Int16, Float16));
+    let int8_half = usage((Int8, Float16));
+    let int8 = usage((Int8, Int8));
+    assert_eq!(half, usage((Int16, Int16)));
+    assert!(int8 < int8_half && int8_half < half && half < mixed && mixed < float32);
+
+    for (max_bytes, expected) in [
+        (u64::MAX, (Float32, Float32)),
+        (float32, (Float32, Float32)),
+        (float32 - 1, (Int16, Float32)),
+        (mixed - 1, (Int16, Float16)),
+        (half, (Int16, Float16)),
+        (half - 1, (Int8, Float16)),
+        (int8_half - 1, (Int8, Int8)),
+        (int8, (Int8, Int8)),
+    ] {
+        assert_eq!(game.allocate_memory_auto(max_bytes), Ok(expected));
+        assert_eq!(game.allocated_storage_formats(), Some(expected));
+    }
+
+    // a budget that fits half-precision values does not fall back to the 16-bit compression
+    let (_, compressed) = game.memory_usage();
+    assert_eq!(compressed, half);
+    assert_eq!(game.allocate_memory_auto(compressed), Ok((Int16, Float16)));
+    assert!(solve(&mut game, 10, 0.0, false).is_finite());
+
+    assert_eq!(
+        game.allocate_memory_auto(int8 - 1),
+        Err(PostFlopError::MemoryLimit {
+            required: int8,
+            available: int8 - 1,
+        })
+    );
+}
+
+#[test]
+fn node_snapshot() {
+    let mut game = build_turn_game();
+    game.play(0); // check
+    let snapshot = game.node_snapshot();
+    let summary = game.node_summary();
+
+    let player = game.current_player();
+    let num_hands = game.private_cards(player).len();
+    let num_actions = game.available_actions().len();
+    assert_eq!(snapshot.history, [0]);
+    assert_eq!(snapshot.line, ["X"]);
+    assert_eq!(snapshot.board.len(), 4);
+    assert_eq!(snapshot.pot, 100);
+    assert_eq!(snapshot.player, Some(player));
+    assert_eq!(snapshot.actions.len(), num_actions);
+    assert_eq!(snapshot.private_cards[player].len(), num_hands);
+    assert_eq!(snapshot.weights, summary.normalized_weights);
+    assert_eq!(snapshot.expected_values, summary.expected_values);
+    assert_eq!(snapshot.strategy, game.strategy());
+    assert_eq!(
+        snapshot.action_expected_values,
+        game.expected_values_detail(player)
+    );
+
+    let total_frequency = snapshot.action_frequencies.iter().sum::<f32>();
+    assert_eq!(snapshot.action_frequencies.len(), num_actions);
+    assert!((total_frequency - 1.0).abs() < 1e-4);
+
+    game.play(0); // check
+    let snapshot = game.node_snapshot();
+    assert!(snapshot.is_chance && !snapshot.is_terminal);
+    assert_eq!(snapshot.player, None);
+    assert!(!snapshot.actions.is_empty());
+    assert!(snapshot.strategy.is_empty() && snapshot.action_frequencies.is_empty());
+
+    #[cfg(feature = "serde")]
+    {
+        fn assert_serialize<T: serde::Serialize>(_: &T) {}
+        assert_serialize(&snapshot);
+    }
+}
+
+#[test]
+fn set_weights() {
+    let mut game = build_river_game();
+    let original_weights = game.initial_weights[0].clone();
+    let original_ev = game.node_summary().expected_values;
+    assert!(!game.is_weight_modified());
+
+    assert!(game.set_weight(2, 0, 0.5).is_err());
+    assert!(game.set_weight(0, original_weights.len(), 0.5).is_err());
+    assert!(game.set_weight(0, 0, 1.5).is_err());
+    assert!(game.set_weights(0, &[1.0]).is_err());
+    assert!(game
+        .set_weights(0, &vec![0.0; original_weights.len()])
+        .is_err());
+    assert!(!game.is_weight_modified());
+
+    // remove the pocket pairs from the OOP range
+    let mut weights = original_weights.clone();
+    for (hand, &(c1, c2)) in game.private_cards(0).iter().enumerate() {
+        if c1 >> 2 == c2 >> 2 {
+            weights[hand] = 0.0;
+        }
+    }
+    game.play(0); // check
+    game.set_weights(0, &weights).unwrap();
+    assert!(game.is_weight_modified());
+    assert_eq!(game.history(), [0]);
+    game.back_to_root();
+    assert_eq!(game.weights(0), weights);
+
+    let summary = game.node_summary();
+    assert_ne!(summary.expected_values[1], original_ev[1]);
+    let total_ev = summary.average_expected_value[0] + summary.average_expected_value[1];
+    assert!((total_ev - 100.0).abs() < 1e-2);
+
+    #[cfg(feature = "bincode")]
+    assert!(crate::save_data_into_std_write(&game, "", &mut Vec::new(), None).is_err());
+
+    // the expected values are restored with the original weights
+    game.set_weights(0, &original_weights).unwrap();
+    let summary = game.node_summary();
+    for player in 0..2 {
+        for (&ev, &original) in summary.expected_values[player]
+            .iter()
+            .zip(&original_ev[player])
+        {
+            assert!((ev - original).abs() < 1e-3 || (ev.is_nan() && original.is_nan()));
+        }
+    }
+}
+
+#[test]
+fn set_weight_solved_game() {
+    let mut game = build_river_game();
+    let hand = 0;
+    let weight = game.weights(1)[hand];
+    let (c1, c2) = game.private_cards(1)[hand];
+    let hand_mask: u64 = (1 << c1) | (1 << c2);
+    assert!(weight > 0.0);
+
+    // OOP values, IP values, and the weight sums of the IP hands compatible with each OOP hand
+    let mut collect = |weight: f32| {
+        game.set_weight(1, hand, weight).unwrap();
+        game.cache_normalized_weights();
+        let weight_sums = game.private_cards(0).iter().map(|&(c1, c2)| {
+            let mask: u64 = (1 << c1) | (1 << c2);
+            let hands = game.private_cards(1).iter().zip(game.weights(1));
+            hands
+                .filter(|&(&(c3, c4), _)| mask & ((1 << c3) | (1 << c4)) == 0)
+                .map(|(_, &w)| w)
+                .sum::<f32>()
+        });
+        (
+            [game.expected_values(0), game.equity(0)],
+            [game.expected_values(1), game.equity(1)],
+            weight_sums.collect::<Vec<_>>(),
+        )
+    };
+
+    let (full_oop, full_ip, full_sums) = collect(weight);
+    let (zero_oop, zero_ip, zero_sums) = collect(0.0);
+    let (half_oop, half_ip, half_sums) = collect(0.5 * weight);
+
+    // the strategy is kept, so the OOP values weighted by the IP weights are linear in the weight
+    // of the modified IP hand, and the OOP hands conflicting with it are not affected
+    for (values, (full, zero)) in half_oop.iter().zip(full_oop.iter().zip(&zero_oop)) {
+        for (i, &(c1, c2)) in game.private_cards(0).iter().enumerate() {
+            if hand_mask & ((1 << c1) | (1 << c2)) != 0 {
+                assert!((values[i] - full[i]).abs() < 1e-4);
+            } else {
+                let expected = 0.5 * (full[i] * full_sums[i] + zero[i] * zero_sums[i]);
+                assert!((values[i] * half_sums[i] - expected).abs() < 1e-2);
+            }
+        }
+    }
+
+    // both the expected values and the equity are actually updated
+    assert_ne!(half_oop[0], full_oop[0]);
+    assert_ne!(half_oop[1], full_oop[1]);
+
+    // the IP values of the other hands do not depend on the IP weights
+    for (values, (full, zero)) in half_ip.iter().zip(full_ip.iter().zip(&zero_ip)) {
+        for i in (0..values.len()).filter(|&i| i != hand) {
+            assert!((values[i] - full[i]).abs() < 1e-4);
+            assert!((values[i] - zero[i]).abs() < 1e-4);
+        }
+    }
+}
+
+#[test]
+fn compare_scenario() {
+    let build_game = |oop_sizes: &str, river: &str| {
+        let card_config = CardConfig {
+            range: [
+                "AA-88,AK-AT,KQ,QJ,JT,T9".parse().unwrap(),
+                "AA-66,AK-A8,KQ-KT,QJ-QT,JTs,T9s,98s".parse().unwrap(),
+            ],
+            flop: flop_from_str("Td9d6h").unwrap(),
+            turn: card_from_str("Qc").unwrap(),
+            river: card_from_str(river).unwrap(),
+        };
+        let tree_config = TreeConfig {
+            initial_state: BoardState::River,
+            starting_pot: 100,
+            effective_stack: 400,
+            river_bet_sizes: [
+                (oop_sizes, "60%").try_into().unwrap(),
+                ("50%", "60%").try_into().unwrap(),
+            ],
+            ..Default::default()
+        };
+        let action_tree = ActionTree::new(tree_config).unwrap();
+        let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
+        game.allocate_memory(false, None).unwrap();
+        solve(&mut game, 200, 0.0, false);
+        game
+    };
+
+    let mut game1 = build_game("50%", "3s");
+    let mut game2 = build_game("50%", "3s");
+    let comparison = game1.compare_scenario(&mut game2).unwrap();
+    assert_eq!(comparison.hands[0].len(), game1.private_cards(0).len());
+    assert!(comparison.hands[0].iter().all(|h| h.ev_delta.abs() < 1e-3));
+    assert!(comparison.lines.iter().all(|l| l.l1 < 1e-3));
+    assert!(comparison.average_ev_delta.iter().all(|d| d.abs() < 1e-3));
+
+    let mut game3 = build_game("33%, 100%", "3s");
+    game1.play(0); // check
+    let comparison = game1.compare_scenario(&mut game3).unwrap();
+    assert_eq!(game1.history(), [0]); // not changed
+
+    let root = &comparison.lines[0];
+    assert!(root.line.is_empty());
+    assert_eq!(root.player, 0);
+    assert_eq!(root.actions.len(), 4); // check, bet 50, bet 33, bet 100
+    assert_eq!(root.actions[..2], game1.available_actions()[..2]);
+    for frequencies in &root.frequencies {
+        assert!((frequencies.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+    }
+    assert_eq!(root.frequencies[0][2..], [0.0, 0.0]);
+    assert_eq!(root.frequency_deltas()[1], -root.frequencies[0][1]);
+
+    // the lines after the bets of different sizes are not shared
+    assert!(comparison
+        .lines
+        .iter()
+        .all(|l| l.line.is_empty() || l.line[0] == Action::Check));
+    for (player, [ev1, ev2]) in comparison.average_expected_value.iter().enumerate() {
+        assert_eq!(comparison.average_ev_delta[player], ev2 - ev1);
+    }
+    assert!(comparison.mean_l1 > 0.0);
+
+    let mut game4 = build_game("50%", "4s");
+    assert!(game1.compare_scenario(&mut game4).is_err());
+}
+
+#[test]
+fn river_sampling() {
+    let sampling = RiverSampling {
+        fraction: 0.3,
+        seed: 42,
+        num_refine_iterations: 50,
+    };
+
+    let mut game1 = build_unsolved_turn_game();
+    let exploitability = solve_with_river_sampling(&mut game1, &sampling, 100, 0.0, false);
+    assert!(game1.is_solved());
+    assert!(exploitability < 0.02 * game1.tree_config().starting_pot as f32);
+
+    // the same seed samples the same river cards
+    let mut game2 = build_unsolved_turn_game();
+    solve_with_river_sampling(&mut game2, &sampling, 100, 0.0, false);
+    assert_eq!(game1.strategy(), game2.strategy());
+}
+
+#[test]
+#[should_panic(expected = "Invalid sampling fraction")]
+fn river_sampling_invalid_fraction() {
+    let mut game = build_unsolved_turn_game();
+    let sampling = RiverSampling {
+        fraction: 0.0,
+        ..Default::default()
+    };
+    solve_with_river_sampling(&mut game, &sampling, 10, 0.0, false);
+}
+
+#[test]
+fn simplification_report() {
+    let mut game = build_river_game();
+    game.play(0); // check
+    assert!(game
+        .simplification_report(BoardState::Flop, 0, 0.0)
+        .is_err());
+
+    let report = game
+        .simplification_report(BoardState::River, 200, 0.0)
+        .unwrap();
+    assert_eq!(game.history(), [0]); // not changed
+    assert_eq!(report.street, BoardState::River);
+    assert_eq!(report.entries.len(), 6); // (2 bets + 1 raise) * 2 players
+
+    let pot = game.tree_config().starting_pot as f32;
+    for (i, entry) in report.entries.iter().enumerate() {
+        if i > 0 {
+            assert!(report.entries[i - 1].ev_loss <= entry.ev_loss);
+        }
+        assert_eq!(
+            entry.ev_loss,
+            report.expected_values[entry.player] - entry.expected_value
+        );
+        // removing an option never helps the player beyond the solver noise
+        assert!(entry.ev_loss > -0.01 * pot);
+    }
+
+    let raise = report
+        .entries
+        .iter()
+        .find(|e| e.kind == BetSizeKind::Raise && e.player == 1);
+    assert_eq!(raise.unwrap().size, BetSize::PotRelative(0.6));
+}
+
+#[test]
+fn optimize_bet_size() {
+    let mut game = build_river_game();
+    let grid = BetSizeSearch::Grid(vec![1.0, 0.3, 10.0]);
+    let result = game.optimize_bet_size(&grid, 100, 0.0).unwrap();
+    assert_eq!(game.history(), []); // not changed
+    assert_eq!(result.player, 0);
+    assert_eq!(result.pot, 100);
+
+    let actions = result.points.iter().map(|p| p.action).collect::<Vec<_>>();
+    assert_eq!(
+        actions,
+        [Action::Bet(30), Action::Bet(100), Action::AllIn(400)]
+    );
+    let best = result.best_point();
+    assert!(result
+        .points
+        .iter()
+        .all(|p| p.expected_value <= best.expected_value));
+
+    let golden = BetSizeSearch::GoldenSection {
+        min_ratio: 0.25,
+        max_ratio: 1.5,
+        num_evaluations: 4,
+    };
+    let result = game.optimize_bet_size(&golden, 100, 0.0).unwrap();
+    assert_eq!(result.points.len(), 4);
+    assert!(result.points.windows(2).all(|w| w[0].ratio <= w[1].ratio));
+
+    // raise over the bet of 50: pot after calling = 200
+    game.play(1);
+    let grid = BetSizeSearch::Grid(vec![0.5]);
+    let result = game.optimize_bet_size(&grid, 100, 0.0).unwrap();
+    assert_eq!(result.player, 1);
+    assert_eq!(result.points[0].action, Action::Raise(150));
+
+    assert!(game
+        .optimize_bet_size(&BetSizeSearch::Grid(vec![]), 100, 0.0)
+        .is_err());
+    assert!(game
+        .optimize_bet_size(&BetSizeSearch::Grid(vec![-0.5]), 100, 0.0)
+        .is_err());
+}
+
+#[test]
+fn simplify_tree() {
+    let card_config = CardConfig {
+        range: [
+            "AA-22,AK-A2,KQ-K9,QJ-Q9,JT-J9,T9".parse().unwrap(),
+            "AA-66,AK-A8,KQ-KT,QJ-QT,JTs,T9s,98s".parse().unwrap(),
+        ],
+        flop: flop_from_str("Td9d6h").unwrap(),
+        turn: card_from_str("Qc").unwrap(),
+        ..Default::default()
+    };
+    let tree_config = TreeConfig {
+        initial_state: BoardState::Turn,
+        starting_pot: 100,
+        effective_stack: 400,
+        turn_bet_sizes: [
+            ("25%, 50%, 100%, 200%", "60%").try_into().unwrap(),
+            Default::default(),
+        ],
+        river_bet_sizes: [
+            ("33%, 75%, 150%", "60%").try_into().unwrap(),
+            Default::default(),
+        ],
+        ..Default::default()
+    };
+    let action_tree = ActionTree::new(tree_config).unwrap();
+    let mut game = PostFlopGame::with_config(card_config, action_tree).unwrap();
+    game.allocate_memory(false, None).unwrap();
+    solve(&mut game, 100, 0.0, false);
+    game.play(0); // check
+
+    assert!(game.simplify_tree(1.0, 10, 0.0).is_err());
+
+    let result = game.simplify_tree(0.0, 0, 0.0).unwrap();
+    assert!(result.removed_lines.is_empty());
+    assert_eq!(result.num_iterations, 0);
+
+    let result = game.simplify_tree(0.05, 100, 0.0).unwrap();
+    assert_eq!(game.history(), [0]); // not changed
+    assert!(!result.removed_lines.is_empty());
+    for line in &result.removed_lines {
+        let action = *line.last().unwrap();
+        assert!(matches!(
+            action,
+            Action::Bet(_) | Action::Raise(_) | Action::AllIn(_)
+        ));
+    }
+    assert_eq!(result.game.removed_lines(), result.removed_lines);
+    assert!(result.game.is_solved());
+    assert_eq!(result.num_iterations, 100);
+    assert!(result.exploitability < 0.01 * game.tree_config().starting_pot as f32);
+
+    // zero-sum
+    assert!((result.ev_deltas[0] + result.ev_deltas[1]).abs() < 1e-3);
+    assert_eq!(
+        result.ev_deltas[0],
+        result.simplified_expected_values[0] - result.expected_values[0]
+    );
+}
+
+#[test]
+fn best_response_gains() {
+    let mut game = build_unsolved_turn_game();
+    solve(&mut game, 10, 0.0, false);
+
+    let exploitability = compute_exploitability(&game);
+    let gains = compute_best_response_gains(&game);
+    assert!(gains.iter().all(|&g| g >= -1e-4));
+    assert!(((gains[0] + gains[1]) * 0.5 - exploitability).abs() < 1e-4);
+
+    let street_gains = compute_street_best_response_gains(&game);
+    for player in 0..2 {
+        assert!(street_gains[player][0].abs() < 1e-4); // the game starts from the turn
+        for &gain in &street_gains[player][1..] {
+            assert!(gain >= -1e-4 && gain <= gains[player] + 1e-4);
+        }
+    }
+}
+
+#[test]
+fn clone_structure() {
+    let mut game = build_unsolved_turn_game();
+    let num_actions = game.available_actions().len();
+    let num_hands = game.num_private_hands(0);
+    let mut locking = vec![0.0; num_actions * num_hands];
+    locking[..num_hands].fill(1.0); // always check
+    game.lock_current_strategy(&locking);
+
+    let mut clone = game.clone_structure();
+    assert!(clone.is_memory_allocated().is_none());
+    assert_eq!(clone.memory_usage(), game.memory_usage());
+    assert_eq!(clone.private_cards(0), game.private_cards(0));
+
+    clone.allocate_memory(false, None).unwrap();
+    assert_eq!(
+        clone.current_locking_strategy(),
+        game.current_locking_strategy()
+    );
+    let exploitability = solve(&mut game, 20, 0.0, false);
+    assert_eq!(solve(&mut clone, 20, 0.0, false), exploitability);
+    assert_eq!(clone.strategy(), game.strategy());
+
+    // the clone is independent of the original game
+    let strategy = game.strategy();
+    let mut clone = game.clone_structure();
+    clone.allocate_memory(true, None).unwrap();
+    clone.unlock_current_strategy();
+    solve(&mut clone, 20, 0.0, false);
+    assert_ne!(clone.strategy(), strategy);
+    assert_eq!(game.strategy(), strategy);
+    assert!(game.current_locking_strategy().is_some());
+    assert_eq!(compute_exploitability(&game), exploitability);
+}
@@ -0,0 +1,150 @@
+use super::*;
+use std::ptr;
+
+impl PostFlopGame {
+    /// Frees the buffers that are not needed to query the strategies of the solved game, and
+    /// returns the number of freed bytes.
+    ///
+    /// The strategies stored as floating point numbers are normalized and quantized to 16-bit
+    /// integers. If `keep_expected_values` is `true`, the counterfactual values are also kept
+    /// (quantized to 16-bit integers if stored as floating point numbers) so that the expected
+    /// values can still be queried. Otherwise, the buffers of the counterfactual values are freed,
+    /// and methods like [`expected_values`] panic afterward.
+    ///
+    /// A game whose counterfactual values are freed cannot be saved. Call [`allocate_memory`] to
+    /// solve the game again.
+    ///
+    /// Panics if the game is not solved.
+    ///
+    /// [`expected_values`]: #method.expected_values
+    /// [`allocate_memory`]: #method.allocate_memory
+    pub fn trim_to_strategy(&mut self, keep_expected_values: bool) -> Result<usize, PostFlopError> {
+        if self.state != State::Solved {
+            panic!("Game is not solved");
+        }
+
+        if self.storage_mode != BoardState::River {
+            return Err(PostFlopError::InvalidState(
+                "Strategies of all streets are not stored".to_string(),
+            ));
+        }
+
+        #[cfg(feature = "bincode")]
+        if self.is_memory_mapped() {
+            return Err(PostFlopError::Unsupported(
+                "Memory-mapped game cannot be trimmed".to_string(),
+            ));
+        }
+
+        let before = self.storage_len();
+
+        let strategy_format = self.strategy_only_format();
+        if strategy_format != self.strategy_format {
+            self.rebase_strategy(strategy_format);
+        }
+
+        if self.is_cfvalues_trimmed {
+            // already freed
+        } else if keep_expected_values {
+            let value_format = match self.value_format {
+                StorageFormat::Float32 | StorageFormat::Float16 => StorageFormat::Int16,
+                format => format,
+            };
+            if value_format != self.value_format {
+                self.convert_values(value_format)?;
+            }
+        } else {
+            self.free_cfvalues();
+        }
+
+        Ok(before - self.storage_len())
+    }
+
+    /// Returns the storage format of the strategies in the strategy-only mode.
+    ///
+    /// Floating point numbers are quantized to 16-bit integers; quantized formats are kept as they
+    /// are.
+    #[inline]
+    pub(super) fn strategy_only_format(&self) -> StorageFormat {
+        match self.strategy_format {
+            StorageFormat::Float32 | StorageFormat::Float16 => StorageFormat::Int16,
+            format => format,
+        }
+    }
+
+    /// Returns the normalized strategies of all nodes in the strategy-only format.
+    pub(super) fn quantized_strategy(&self) -> Vec<u8> {
+        let num_bytes = self.strategy_format.num_bytes();
+        let dst_bytes = self.strategy_only_format().num_bytes();
+        let mut ret = vec![0; dst_bytes * self.num_storage as usize];
+
+        for node in &self.node_arena {
+            let node = node.lock();
+            if node.is_terminal() || node.is_chance() {
+                continue;
+            }
+
+            let offset = unsafe { node.storage1.offset_from(self.storage1.as_ptr()) } as usize;
+            let offset = offset / num_bytes * dst_bytes;
+            for (i, &prob) in self.node_strategy(&node).iter().enumerate() {
+                if dst_bytes == 1 {
+                    ret[offset + i] = (prob * u8::MAX as f32).round() as u8;
+                } else {
+                    let value = (prob * u16::MAX as f32).round() as u16;
+                    ret[offset + 2 * i..offset + 2 * i + 2].copy_from_slice(&value.to_ne_bytes());
+                }
+            }
+        }
+
+        ret
+    }
+
+    /// Returns the total length of the global storages in bytes.
+    #[inline]
+    fn storage_len(&self) -> usize {
+        self.storage1.len()
+            + self.storage2.len()
+            + self.storage_ip.len()
+            + self.storage_chance.len()
+    }
+
+    /// Replaces the strategies with the quantized normalized strategies of the given format.
+    fn rebase_strategy(&mut self, format: StorageFormat) {
+        let mut storage1 = self.quantized_strategy();
+        let (num_bytes, dst_bytes) = (self.strategy_format.num_bytes(), format.num_bytes());
+
+        for node in &self.node_arena {
+            let mut node = node.lock();
+            if node.is_terminal() || node.is_chance() {
+                continue;
+            }
+
+            let offset = unsafe { node.storage1.offset_from(self.storage1.as_ptr()) } as usize;
+            node.storage1 = unsafe { storage1.as_mut_ptr().add(offset / num_bytes * dst_bytes) };
+            node.set_strategy_scale(1.0);
+        }
+
+        self.storage1 = storage1.into();
+        self.strategy_format = format;
+    }
+
+    /// Frees the buffers of the counterfactual values.
+    fn free_cfvalues(&mut self) {
+        for node in &self.node_arena {
+            let mut node = node.lock();
+            if node.is_terminal() {
+                // do nothing
+            } else if node.is_chance() {
+                node.storage1 = ptr::null_mut();
+            } else {
+                node.storage2 = ptr::null_mut();
+                node.storage3 = ptr::null_mut();
+            }
+        }
+
+        self.storage2 = Default::default();
+        self.storage_ip = Default::default();
+        self.storage_chance = Default::default();
+        self.is_cfvalues_trimmed = true;
+    }
+}
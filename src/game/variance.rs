@@ -0,0 +1,202 @@
+use super::*;
+use crate::interface::*;
+use crate::sliceop::*;
+use crate::utility::*;
+
+impl PostFlopGame {
+    /// Returns the variance of the payoff of each private hand of the given player from the
+    /// current node.
+    ///
+    /// The payoff is randomized over the remaining board cards, the private hand of the opponent,
+    /// and the actions of both players under the solved strategies. Private hands whose
+    /// normalized weight is zero have a variance of zero.
+    ///
+    /// Panics if the game is not solved or the strategies of all streets are not stored.
+    ///
+    /// After mutating the current node, you must call the [`cache_normalized_weights`] method
+    /// before calling this method.
+    ///
+    /// **Time complexity:** *O*(#(nodes in the subtree) * #(private hands)).
+    ///
+    /// [`cache_normalized_weights`]: #method.cache_normalized_weights
+    pub fn expected_value_variances(&self, player: usize) -> Vec<f32> {
+        if self.state != State::Solved {
+            panic!("Game is not solved");
+        }
+
+        if !self.is_normalized_weight_cached {
+            panic!("Normalized weights are not cached");
+        }
+
+        if self.storage_mode != BoardState::River {
+            panic!("Strategies of all streets are not stored");
+        }
+
+        let node = self.node();
+        let mut cfreach = self.weights[player ^ 1].clone();
+        self.apply_swap(&mut cfreach, player ^ 1, true);
+
+        let no_forced = BTreeMap::new();
+        let mut moments = [false, true].map(|squared| {
+            self.payoff_moment_recursive(&node, player, &cfreach, &no_forced, squared)
+        });
+        for moment in &mut moments {
+            self.apply_swap(moment, player, false);
+        }
+
+        let normalizer = self.expected_value_normalizer(false) as f64;
+        let [first, second] = moments;
+        first
+            .iter()
+            .zip(&second)
+            .zip(self.weights[player].iter())
+            .zip(self.normalized_weights[player].iter())
+            .map(|(((&m1, &m2), &w_raw), &w_normalized)| {
+                if w_normalized == 0.0 {
+                    0.0
+                } else {
+                    let scale = normalizer * (w_raw / w_normalized) as f64;
+                    let mean = m1 * scale;
+                    (m2 * scale - mean * mean).max(0.0) as f32
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the standard deviation of the payoff of each private hand of the given player
+    /// from the current node.
+    ///
+    /// See [`expected_value_variances`] for details.
+    ///
+    /// [`expected_value_variances`]: #method.expected_value_variances
+    pub fn expected_value_stdevs(&self, player: usize) -> Vec<f32> {
+        let mut ret = self.expected_value_variances(player);
+        ret.iter_mut().for_each(|v| *v = v.sqrt());
+        ret
+    }
+
+    /// Returns the expected values of each private hand of the given player at the current node,
+    /// computed by traversing the subtree. At the nodes in `forced`, the mapped action is taken
+    /// with every private hand instead of the solved strategy.
+    pub(super) fn subtree_expected_values(
+        &self,
+        player: usize,
+        forced: &BTreeMap<usize, usize>,
+    ) -> Vec<f32> {
+        let node = self.node();
+        let mut cfreach = self.weights[player ^ 1].clone();
+        self.apply_swap(&mut cfreach, player ^ 1, true);
+
+        let mut ret = self.payoff_moment_recursive(&node, player, &cfreach, forced, false);
+        self.apply_swap(&mut ret, player, false);
+
+        let normalizer = self.expected_value_normalizer(false) as f64;
+        let total_bet_amount = self.total_bet_amount();
+        let bias = (total_bet_amount[player] - total_bet_amount[player ^ 1]).max(0);
+        let offset = self.tree_config.starting_pot as f64 * 0.5 + (node.amount + bias) as f64;
+
+        ret.iter()
+            .zip(self.weights[player].iter())
+            .zip(self.normalized_weights[player].iter())
+            .map(|((&m1, &w_raw), &w_normalized)| {
+                if w_normalized == 0.0 {
+                    0.0
+                } else {
+                    (m1 * normalizer * (w_raw / w_normalized) as f64 + offset) as f32
+                }
+            })
+            .collect()
+    }
+
+    /// Recursive function to compute the counterfactual first or second moment of the payoffs.
+    fn payoff_moment_recursive(
+        &self,
+        node: &PostFlopNode,
+        player: usize,
+        cfreach: &[f32],
+        forced: &BTreeMap<usize, usize>,
+        squared: bool,
+    ) -> Vec<f64> {
+        let num_hands = self.num_private_hands(player);
+
+        // terminal node
+        if node.is_terminal() {
+            let mut result = Vec::with_capacity(num_hands);
+            self.evaluate_moment(result.spare_capacity_mut(), node, player, cfreach, squared);
+            unsafe { result.set_len(num_hands) };
+            return result.iter().map(|&v: &f32| v as f64).collect();
+        }
+
+        let num_actions = node.num_actions();
+        let mut result = vec![0.0; num_hands];
+
+        // chance node
+        if node.is_chance() {
+            let scalar = 1.0 / self.chance_factor(node) as f32;
+            let cfreach = cfreach.iter().map(|&r| r * scalar).collect::<Vec<_>>();
+
+            let children = (0..num_actions)
+                .map(|action| {
+                    self.payoff_moment_recursive(
+                        &node.play(action),
+                        player,
+                        &cfreach,
+                        forced,
+                        squared,
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            for child in &children {
+                result.iter_mut().zip(child).for_each(|(r, &v)| *r += v);
+            }
+
+            // process isomorphic chances
+            for (i, &isomorphic_index) in self.isomorphic_chances(node).iter().enumerate() {
+                let swap_list = &self.isomorphic_swap(node, i)[player];
+                let mut tmp = children[isomorphic_index as usize].clone();
+                apply_swap(&mut tmp, swap_list);
+                result.iter_mut().zip(&tmp).for_each(|(r, &v)| *r += v);
+            }
+
+            return result;
+        }
+
+        // player node
+        let node_player = node.player();
+        let num_node_hands = self.num_private_hands(node_player);
+        let strategy = match forced.get(&self.node_index(node)) {
+            Some(&forced_action) => {
+                let mut strategy = vec![0.0; num_actions * num_node_hands];
+                row_mut(&mut strategy, forced_action, num_node_hands).fill(1.0);
+                strategy
+            }
+            None => self.node_strategy(node),
+        };
+
+        for action in 0..num_actions {
+            let action_strategy = row(&strategy, action, num_node_hands);
+            let child = if node_player == player {
+                let child = self.payoff_moment_recursive(
+                    &node.play(action),
+                    player,
+                    cfreach,
+                    forced,
+                    squared,
+                );
+                child
+                    .iter()
+                    .zip(action_strategy)
+                    .map(|(&v, &s)| v * s as f64)
+                    .collect()
+            } else {
+                let mut cfreach = cfreach.to_vec();
+                mul_slice(&mut cfreach, action_strategy);
+                self.payoff_moment_recursive(&node.play(action), player, &cfreach, forced, squared)
+            };
+            result.iter_mut().zip(&child).for_each(|(r, &v)| *r += v);
+        }
+
+        result
+    }
+}
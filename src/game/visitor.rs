@@ -0,0 +1,355 @@
+use super::*;
+use crate::interface::*;
+use crate::sliceop::*;
+use crate::utility::*;
+
+/// A read-only view of a decision node, passed to the visitor of [`PostFlopGame::visit_nodes`].
+///
+/// The private hands are ordered as in [`PostFlopGame::private_cards`].
+pub struct NodeView<'a> {
+    history: &'a [usize],
+    line: &'a [Action],
+    board: &'a [Card],
+    starting_pot: i32,
+    total_bet_amount: [i32; 2],
+    chance_probability: f64,
+    player: usize,
+    actions: &'a [Action],
+    strategy: &'a [f32],
+    reach: [&'a [f32]; 2],
+}
+
+/// Mutable state of the traversal.
+struct VisitState {
+    history: Vec<usize>,
+    line: Vec<Action>,
+    board: Vec<Card>,
+    total_bet_amount: [i32; 2],
+    chance_probability: f64,
+    reach: [Vec<f32>; 2],
+}
+
+impl<'a> NodeView<'a> {
+    /// Returns the action history from the root node.
+    ///
+    /// The history can be passed to [`PostFlopGame::apply_history`] to move to this node.
+    #[inline]
+    pub fn history(&self) -> &'a [usize] {
+        self.history
+    }
+
+    /// Returns the actions leading to this node from the root node. Dealt cards are represented
+    /// as [`Action::Chance`].
+    #[inline]
+    pub fn line(&self) -> &'a [Action] {
+        self.line
+    }
+
+    /// Returns the board cards at this node.
+    #[inline]
+    pub fn board(&self) -> &'a [Card] {
+        self.board
+    }
+
+    /// Returns the pot size at this node, including the starting pot.
+    #[inline]
+    pub fn pot(&self) -> i32 {
+        self.starting_pot + self.total_bet_amount[0] + self.total_bet_amount[1]
+    }
+
+    /// Returns the total bet amount of each player (OOP, IP).
+    #[inline]
+    pub fn total_bet_amount(&self) -> [i32; 2] {
+        self.total_bet_amount
+    }
+
+    /// Returns the probability that the turn and river cards leading to this node are dealt.
+    ///
+    /// Isomorphic cards are counted as the card of this node.
+    #[inline]
+    pub fn chance_probability(&self) -> f64 {
+        self.chance_probability
+    }
+
+    /// Returns the current player (`0` = OOP, `1` = IP).
+    #[inline]
+    pub fn current_player(&self) -> usize {
+        self.player
+    }
+
+    /// Returns the available actions of the current player.
+    #[inline]
+    pub fn available_actions(&self) -> &'a [Action] {
+        self.actions
+    }
+
+    /// Returns the strategy of the current player in the same format as
+    /// [`PostFlopGame::strategy`].
+    #[inline]
+    pub fn strategy(&self) -> &'a [f32] {
+        self.strategy
+    }
+
+    /// Returns the probability that each private hand of the given player takes the actions
+    /// leading to this node.
+    ///
+    /// The initial weights of the ranges are not included. Hands overlapping with the board have a
+    /// probability of zero.
+    #[inline]
+    pub fn reach_probabilities(&self, player: usize) -> &'a [f32] {
+        self.reach[player]
+    }
+}
+
+impl PostFlopGame {
+    /// Visits all decision nodes of the game tree in depth-first order.
+    ///
+    /// Unlike navigating with [`play`], this method does not change the current node. At chance
+    /// nodes, isomorphic cards are grouped into one representative card, as in
+    /// [`available_actions`]. Streets whose strategies are not stored (see
+    /// [`set_target_storage_mode`]) are not visited.
+    ///
+    /// Panics if the memory is not yet allocated.
+    ///
+    /// [`play`]: #method.play
+    /// [`available_actions`]: #method.available_actions
+    /// [`set_target_storage_mode`]: #method.set_target_storage_mode
+    pub fn visit_nodes<F: FnMut(&NodeView)>(&self, mut visitor: F) {
+        if self.state < State::MemoryAllocated {
+            panic!("Memory is not allocated");
+        }
+
+        let mut board_mask: u64 = 0;
+        let mut board = self.card_config.flop.to_vec();
+        for card in [self.card_config.turn, self.card_config.river] {
+            if card != NOT_DEALT {
+                board.push(card);
+            }
+        }
+        for &card in &board {
+            board_mask |= 1 << card;
+        }
+
+        let reach = [0, 1].map(|player| {
+            self.private_cards[player]
+                .iter()
+                .map(|&(c1, c2)| {
+                    let mask: u64 = (1 << c1) | (1 << c2);
+                    if mask & board_mask == 0 {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                })
+                .collect()
+        });
+
+        let mut state = VisitState {
+            history: Vec::new(),
+            line: Vec::new(),
+            board,
+            total_bet_amount: [0, 0],
+            chance_probability: 1.0,
+            reach,
+        };
+
+        self.visit_nodes_recursive(&self.root(), &mut state, &mut visitor);
+    }
+
+    /// Recursive function to visit the nodes.
+    fn visit_nodes_recursive<F: FnMut(&NodeView)>(
+        &self,
+        node: &PostFlopNode,
+        state: &mut VisitState,
+        visitor: &mut F,
+    ) {
+        if node.is_terminal() || node.amount == self.tree_config.effective_stack {
+            return;
+        }
+
+        // chance node
+        if node.is_chance() {
+            let is_turn = node.turn == NOT_DEALT;
+            if self.storage_mode == BoardState::Flop
+                || (!is_turn && self.storage_mode == BoardState::Turn)
+            {
+                return;
+            }
+
+            let isomorphic_chances = self.isomorphic_chances(node);
+            let num_cards = node.num_actions() + isomorphic_chances.len();
+            let saved_probability = state.chance_probability;
+
+            for (index, child) in node.children().iter().enumerate() {
+                let child = child.lock();
+                let card = match child.prev_action {
+                    Action::Chance(card) => card,
+                    _ => unreachable!(),
+                };
+
+                let saved_reach = state.reach.clone();
+                for player in 0..2 {
+                    let reach = &mut state.reach[player];
+                    for (r, &(c1, c2)) in reach.iter_mut().zip(&self.private_cards[player]) {
+                        if c1 == card || c2 == card {
+                            *r = 0.0;
+                        }
+                    }
+                }
+
+                let num_isomorphic = isomorphic_chances
+                    .iter()
+                    .filter(|&&i| i as usize == index)
+                    .count();
+                state.chance_probability =
+                    saved_probability * (1 + num_isomorphic) as f64 / num_cards as f64;
+
+                state.history.push(card as usize);
+                state.line.push(child.prev_action);
+                state.board.push(card);
+                self.visit_nodes_recursive(&child, state, visitor);
+                state.board.pop();
+                state.line.pop();
+                state.history.pop();
+                state.reach = saved_reach;
+            }
+
+            state.chance_probability = saved_probability;
+            return;
+        }
+
+        // player node
+        let player = node.player();
+        let num_actions = node.num_actions();
+        let num_hands = self.num_private_hands(player);
+
+        let strategy = self.node_strategy(node);
+
+        let actions = node
+            .children()
+            .iter()
+            .map(|child| child.lock().prev_action)
+            .collect::<Vec<_>>();
+
+        visitor(&NodeView {
+            history: &state.history,
+            line: &state.line,
+            board: &state.board,
+            starting_pot: self.tree_config.starting_pot,
+            total_bet_amount: state.total_bet_amount,
+            chance_probability: state.chance_probability,
+            player,
+            actions: &actions,
+            strategy: &strategy,
+            reach: [&state.reach[0], &state.reach[1]],
+        });
+
+        for (action, &prev_action) in actions.iter().enumerate() {
+            let saved_reach = state.reach[player].clone();
+            let saved_bet_amount = state.total_bet_amount;
+
+            if num_actions > 1 {
+                mul_slice(&mut state.reach[player], row(&strategy, action, num_hands));
+            }
+
+            update_bet_amount(
+                &mut state.total_bet_amount,
+                player,
+                node.prev_action,
+                prev_action,
+            );
+
+            state.history.push(action);
+            state.line.push(prev_action);
+            self.visit_nodes_recursive(&node.play(action), state, visitor);
+            state.line.pop();
+            state.history.pop();
+
+            state.reach[player] = saved_reach;
+            state.total_bet_amount = saved_bet_amount;
+        }
+    }
+
+    /// Returns the normalized strategy of the given player node in the order of the private hands
+    /// of the node (i.e., without suit swapping).
+    pub(super) fn node_strategy(&self, node: &PostFlopNode) -> Vec<f32> {
+        let num_actions = node.num_actions();
+        let mut strategy = match self.strategy_format {
+            StorageFormat::Float32 => normalized_strategy(node.strategy(), num_actions),
+            StorageFormat::Float16 => {
+                normalized_strategy_compressed(node.strategy_f16(), num_actions)
+            }
+            StorageFormat::Int16 => {
+                normalized_strategy_compressed(node.strategy_compressed(), num_actions)
+            }
+            StorageFormat::Int8 => {
+                normalized_strategy_compressed(node.strategy_8bit(), num_actions)
+            }
+        };
+        apply_locking_strategy(&mut strategy, self.locking_strategy(node));
+        strategy
+    }
+
+    /// Returns the normalized strategy of the given private hand at the given player node. The
+    /// hand index is in the order of the private hands of the node.
+    pub(super) fn hand_strategy(&self, node: &PostFlopNode, hand: usize) -> Vec<f32> {
+        let num_actions = node.num_actions();
+        let num_hands = self.num_private_hands(node.player());
+
+        let mut strategy = (0..num_actions)
+            .map(|action| {
+                let i = action * num_hands + hand;
+                match self.strategy_format {
+                    StorageFormat::Float32 => node.strategy()[i],
+                    StorageFormat::Float16 => node.strategy_f16()[i].to_f32(),
+                    StorageFormat::Int16 => node.strategy_compressed()[i] as f32,
+                    StorageFormat::Int8 => node.strategy_8bit()[i] as f32,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let denom = strategy.iter().sum::<f32>();
+        strategy.iter_mut().for_each(|v| {
+            *v = if denom > 0.0 {
+                *v / denom
+            } else {
+                1.0 / num_actions as f32
+            };
+        });
+
+        let locking = self.locking_strategy(node);
+        if !locking.is_empty() {
+            for (action, v) in strategy.iter_mut().enumerate() {
+                let s = locking[action * num_hands + hand];
+                if s.is_sign_positive() {
+                    *v = s;
+                }
+            }
+        }
+
+        strategy
+    }
+}
+
+/// Updates the total bet amount of each player after the player takes the given action.
+pub(super) fn update_bet_amount(
+    total_bet_amount: &mut [i32; 2],
+    player: usize,
+    node_action: Action,
+    action: Action,
+) {
+    match action {
+        Action::Call => {
+            total_bet_amount[player] = total_bet_amount[player ^ 1];
+        }
+        Action::Bet(amount) | Action::Raise(amount) | Action::AllIn(amount) => {
+            let prev_bet_amount = match node_action {
+                Action::Bet(a) | Action::Raise(a) | Action::AllIn(a) => a,
+                _ => 0,
+            };
+            let to_call = total_bet_amount[player ^ 1] - total_bet_amount[player];
+            total_bet_amount[player] += amount - prev_bet_amount + to_call;
+        }
+        _ => {}
+    }
+}
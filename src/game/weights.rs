@@ -0,0 +1,160 @@
+use super::*;
+use crate::utility::*;
+
+impl PostFlopGame {
+    /// Returns whether the weights are changed by [`set_weights`] after the game is constructed.
+    ///
+    /// [`set_weights`]: #method.set_weights
+    #[inline]
+    pub fn is_weight_modified(&self) -> bool {
+        self.is_weight_modified
+    }
+
+    /// Sets the weight of the `hand`-th private hand of the given player at the root node.
+    ///
+    /// This is a shorthand of [`set_weights`] changing only one weight. When changing several
+    /// weights of a solved game, prefer [`set_weights`] because each call recomputes the expected
+    /// values.
+    ///
+    /// [`set_weights`]: #method.set_weights
+    pub fn set_weight(
+        &mut self,
+        player: usize,
+        hand: usize,
+        weight: f32,
+    ) -> Result<(), PostFlopError> {
+        if player > 1 {
+            return Err(PostFlopError::InvalidArgument(format!(
+                "Invalid player: {player}"
+            )));
+        }
+
+        let mut weights = self.initial_weights[player].clone();
+        match weights.get_mut(hand) {
+            Some(w) => *w = weight,
+            None => {
+                return Err(PostFlopError::InvalidArgument(format!(
+                    "Invalid hand index: {hand}"
+                )))
+            }
+        }
+
+        self.set_weights(player, &weights)
+    }
+
+    /// Sets the weights of the private hands of the given player at the root node.
+    ///
+    /// The `weights` argument must be a slice of the length of `#(private hands)` in the order of
+    /// [`private_cards`], and each weight must be in the range `[0.0, 1.0]`. The card
+    /// configuration is not changed, so a game with modified weights cannot be saved; call
+    /// [`update_config`] to restore the original weights.
+    ///
+    /// The game is not re-solved. If the game is already solved, the strategy is kept as is and
+    /// the expected values are recomputed against the new weights, so that [`expected_values`] and
+    /// [`equity`] answer how the values change when the range is modified. The current node is
+    /// kept, and the normalized weights must be cached again.
+    ///
+    /// Returns an error if the arguments are invalid, no valid card assignment remains, or the
+    /// weights cannot be changed (the hand abstraction or the bunching effect is enabled, or the
+    /// game is solved but the expected values are trimmed, the tree is partially loaded, or the
+    /// game is memory-mapped).
+    /// Panics if the game is not successfully initialized.
+    ///
+    /// **Time complexity:** *O*(#(OOP private hands) * #(IP private hands)), plus a traversal of
+    /// the whole tree if the game is solved.
+    ///
+    /// [`private_cards`]: #method.private_cards
+    /// [`update_config`]: #method.update_config
+    /// [`expected_values`]: #method.expected_values
+    /// [`equity`]: #method.equity
+    pub fn set_weights(&mut self, player: usize, weights: &[f32]) -> Result<(), PostFlopError> {
+        if self.state <= State::Uninitialized {
+            panic!("Game is not successfully initialized");
+        }
+
+        if player > 1 {
+            return Err(PostFlopError::InvalidArgument(format!(
+                "Invalid player: {player}"
+            )));
+        }
+
+        if self.is_hand_abstracted {
+            return Err(PostFlopError::Unsupported(
+                "Weights cannot be changed with hand abstraction".to_string(),
+            ));
+        }
+
+        if self.bunching_num_dead_cards != 0 {
+            return Err(PostFlopError::Unsupported(
+                "Weights cannot be changed with bunching effect".to_string(),
+            ));
+        }
+
+        let num_hands = self.private_cards[player].len();
+        if weights.len() != num_hands {
+            return Err(PostFlopError::InvalidArgument(format!(
+                "Weights must have {num_hands} elements: {}",
+                weights.len()
+            )));
+        }
+
+        if weights.iter().any(|w| !(0.0..=1.0).contains(w)) {
+            return Err(PostFlopError::InvalidArgument(
+                "Weights must be in the range [0.0, 1.0]".to_string(),
+            ));
+        }
+
+        if self.state == State::Solved {
+            if self.is_cfvalues_trimmed {
+                return Err(PostFlopError::InvalidState(
+                    "Expected values are trimmed".to_string(),
+                ));
+            }
+            if self.storage_mode != BoardState::River {
+                return Err(PostFlopError::InvalidState(
+                    "Game tree is partially loaded".to_string(),
+                ));
+            }
+
+            #[cfg(feature = "bincode")]
+            if self.is_memory_mapped() {
+                return Err(PostFlopError::Unsupported(
+                    "Memory-mapped game cannot be modified".to_string(),
+                ));
+            }
+        }
+
+        let mut new_weights = self.initial_weights.clone();
+        new_weights[player].copy_from_slice(weights);
+
+        let mut num_combinations = 0.0;
+        for (&(c1, c2), &w1) in self.private_cards[0].iter().zip(&new_weights[0]) {
+            let oop_mask: u64 = (1 << c1) | (1 << c2);
+            for (&(c3, c4), &w2) in self.private_cards[1].iter().zip(&new_weights[1]) {
+                let ip_mask: u64 = (1 << c3) | (1 << c4);
+                if oop_mask & ip_mask == 0 {
+                    num_combinations += w1 as f64 * w2 as f64;
+                }
+            }
+        }
+
+        if num_combinations == 0.0 {
+            return Err(PostFlopError::InvalidConfig(
+                "Valid card assignment does not exist".to_string(),
+            ));
+        }
+
+        self.initial_weights = new_weights;
+        self.is_weight_modified = true;
+        self.num_combinations = num_combinations;
+
+        if self.state == State::Solved {
+            save_cfvalues(self);
+        }
+
+        let history = self.action_history.clone();
+        self.apply_history(&history);
+
+        Ok(())
+    }
+}
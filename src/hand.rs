@@ -1,4 +1,7 @@
+use crate::card::*;
+use crate::error::*;
 use crate::hand_table::*;
+use std::fmt;
 
 #[derive(Clone, Copy, Default)]
 pub(crate) struct Hand {
@@ -60,7 +63,7 @@ impl Hand {
         let mut rankset_of_count = [0i32; 5];
         let mut rank_count = [0i32; 13];
 
-        for &card in &self.cards {
+        for &card in &self.cards[..self.num_cards] {
             let rank = card / 4;
             let suit = card % 4;
             rankset |= 1 << rank;
@@ -126,6 +129,113 @@ impl Hand {
     }
 }
 
+/// Category of a poker hand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HandCategory {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+}
+
+impl fmt::Display for HandCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::HighCard => "High Card",
+            Self::OnePair => "One Pair",
+            Self::TwoPair => "Two Pair",
+            Self::ThreeOfAKind => "Three of a Kind",
+            Self::Straight => "Straight",
+            Self::Flush => "Flush",
+            Self::FullHouse => "Full House",
+            Self::FourOfAKind => "Four of a Kind",
+            Self::StraightFlush => "Straight Flush",
+        };
+        f.write_str(s)
+    }
+}
+
+/// Strength of a poker hand returned by [`evaluate_hand`].
+///
+/// A stronger hand compares greater, and hands of equal strength compare equal. The best five
+/// cards are considered when the hand consists of six or seven cards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HandRank(i32);
+
+impl HandRank {
+    /// Returns the category of the hand.
+    #[inline]
+    pub fn category(&self) -> HandCategory {
+        match self.0 >> 26 {
+            0 => HandCategory::HighCard,
+            1 => HandCategory::OnePair,
+            2 => HandCategory::TwoPair,
+            3 => HandCategory::ThreeOfAKind,
+            4 => HandCategory::Straight,
+            5 => HandCategory::Flush,
+            6 => HandCategory::FullHouse,
+            7 => HandCategory::FourOfAKind,
+            _ => HandCategory::StraightFlush,
+        }
+    }
+
+    /// Returns the raw value of the hand strength.
+    ///
+    /// The value is only meaningful for comparison and may change between versions.
+    #[inline]
+    pub fn raw_value(&self) -> i32 {
+        self.0
+    }
+}
+
+/// Evaluates the strength of the given 5, 6, or 7 cards.
+///
+/// # Examples
+/// ```
+/// use postflop_solver_ffi::*;
+///
+/// let cards = [
+///     card_from_str("Ah").unwrap(),
+///     card_from_str("Kh").unwrap(),
+///     card_from_str("Qh").unwrap(),
+///     card_from_str("Jh").unwrap(),
+///     card_from_str("Th").unwrap(),
+/// ];
+/// let rank = evaluate_hand(&cards).unwrap();
+/// assert_eq!(rank.category(), HandCategory::StraightFlush);
+/// ```
+pub fn evaluate_hand(cards: &[Card]) -> Result<HandRank, PostFlopError> {
+    if !(5..=7).contains(&cards.len()) {
+        return Err(PostFlopError::InvalidArgument(format!(
+            "Expected 5 to 7 cards, but got {} cards",
+            cards.len()
+        )));
+    }
+
+    let mut mask: u64 = 0;
+    let mut hand = Hand::new();
+    for &card in cards {
+        if card >= 52 {
+            return Err(PostFlopError::InvalidCard {
+                input: card.to_string(),
+                reason: format!("Invalid card: {card}"),
+            });
+        }
+        if mask & (1 << card) != 0 {
+            return Err(PostFlopError::BoardConflict { card });
+        }
+        mask |= 1 << card;
+        hand = hand.add_card(card as usize);
+    }
+
+    Ok(HandRank(hand.evaluate_internal()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -173,4 +283,53 @@ mod tests {
         assert_eq!(counter[1], 58627800); // one pair
         assert_eq!(counter[0], 23294460); // high card
     }
+
+    #[test]
+    fn test_evaluate_hand() {
+        let eval = |s: &str| {
+            let mut chars = s.chars();
+            let mut cards = Vec::new();
+            while let Ok(card) = crate::range::card_from_chars(&mut chars) {
+                cards.push(card);
+            }
+            evaluate_hand(&cards).unwrap()
+        };
+
+        let tests = [
+            ("AhKd9c7s2h", HandCategory::HighCard),
+            ("AhAd9c7s2h", HandCategory::OnePair),
+            ("AhAd9c9s2h", HandCategory::TwoPair),
+            ("AhAdAc7s2h", HandCategory::ThreeOfAKind),
+            ("5h4d3c2sAh", HandCategory::Straight),
+            ("Ah9h7h4h2h", HandCategory::Flush),
+            ("AhAdAc2s2h", HandCategory::FullHouse),
+            ("AhAdAcAs2h", HandCategory::FourOfAKind),
+            ("5h4h3h2hAh", HandCategory::StraightFlush),
+            ("AhAd9c9s2h2c", HandCategory::TwoPair),
+            ("AhAd9c9s2h2cKs", HandCategory::TwoPair),
+            ("7h6d5c4s3hAhAd", HandCategory::Straight),
+        ];
+
+        for (s, category) in tests {
+            assert_eq!(eval(s).category(), category);
+        }
+
+        assert!(eval("AhAd9c9sKh") > eval("AhAd9c9sQh"));
+        assert!(eval("6h5d4c3s2h") > eval("5h4d3c2sAh"));
+        assert_eq!(eval("AhKd9c7s2h"), eval("AdKc9s7h2d"));
+        assert_eq!(eval("AhAd9c9s2h2cKs"), eval("AhAd9c9sKs"));
+
+        assert!(matches!(
+            evaluate_hand(&[0, 1, 2, 3]),
+            Err(PostFlopError::InvalidArgument(_))
+        ));
+        assert_eq!(
+            evaluate_hand(&[0, 1, 2, 3, 3]),
+            Err(PostFlopError::BoardConflict { card: 3 })
+        );
+        assert!(matches!(
+            evaluate_hand(&[0, 1, 2, 3, 52]),
+            Err(PostFlopError::InvalidCard { .. })
+        ));
+    }
 }
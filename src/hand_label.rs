@@ -0,0 +1,290 @@
+use crate::card::*;
+use crate::error::*;
+use crate::hand::*;
+
+/// Made hand bucket of hole cards on a board.
+///
+/// The discriminants are stable and can be used as array indices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum MadeHand {
+    NoMadeHand = 0,
+    AceHigh = 1,
+    WeakPair = 2,
+    SecondPair = 3,
+    TopPairWeakKicker = 4,
+    TopPairGoodKicker = 5,
+    TopPairTopKicker = 6,
+    Overpair = 7,
+    TwoPair = 8,
+    Trips = 9,
+    Set = 10,
+    Straight = 11,
+    Flush = 12,
+    FullHouse = 13,
+    FourOfAKind = 14,
+    StraightFlush = 15,
+}
+
+/// Drawing hand bucket of hole cards on a board.
+///
+/// The discriminants are stable and can be used as array indices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum DrawType {
+    NoDraw = 0,
+    BackdoorFlushDraw = 1,
+    Gutshot = 2,
+    OpenEnded = 3,
+    FlushDraw = 4,
+    ComboDraw = 5,
+}
+
+/// Classification of hole cards on a board returned by [`classify_hand`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HandClass {
+    /// Made hand bucket.
+    pub made_hand: MadeHand,
+
+    /// Drawing hand bucket. Always [`DrawType::NoDraw`] on the river and for made straights or
+    /// better.
+    pub draw: DrawType,
+}
+
+/// Classifies the hole cards against the board.
+///
+/// The made hand only counts the strength that involves the hole cards; for example, a board pair
+/// alone is not a pair. The buckets are defined as follows:
+///
+/// - Pairs are classified by the rank of the paired board card. A pocket pair above the highest
+///   board card is an overpair, a pocket pair between the highest and second highest board ranks
+///   is a second pair, and lower pocket pairs are weak pairs.
+/// - The kicker of a top pair is "top" if no better kicker is available, "good" if it is one of
+///   the next two best available kickers, and "weak" otherwise.
+/// - An open-ended draw includes double gutshots, i.e., any draw with two or more straight ranks.
+/// - A combo draw is a flush draw combined with any straight draw.
+/// - Backdoor flush draws are only reported on the flop.
+///
+/// # Examples
+/// ```
+/// use postflop_solver_ffi::*;
+///
+/// let board = flop_from_str("Kh9h4c").unwrap();
+/// let hole = (card_from_str("Ah").unwrap(), card_from_str("Kd").unwrap());
+/// let class = classify_hand(hole, &board).unwrap();
+/// assert_eq!(class.made_hand, MadeHand::TopPairTopKicker);
+/// assert_eq!(class.draw, DrawType::BackdoorFlushDraw);
+/// ```
+pub fn classify_hand(hole: (Card, Card), board: &[Card]) -> Result<HandClass, PostFlopError> {
+    if !(3..=5).contains(&board.len()) {
+        return Err(PostFlopError::InvalidArgument(format!(
+            "Invalid board length: {}",
+            board.len()
+        )));
+    }
+
+    let mut cards = board.to_vec();
+    cards.push(hole.0);
+    cards.push(hole.1);
+    let rank = evaluate_hand(&cards)?;
+
+    let board_rank = if board.len() == 5 {
+        Some(evaluate_hand(board)?)
+    } else {
+        None
+    };
+
+    let board_plays = board_rank == Some(rank);
+    let made_hand = match rank.category() {
+        _ if board_plays => pair_bucket(hole, board),
+        HandCategory::StraightFlush => MadeHand::StraightFlush,
+        HandCategory::FourOfAKind => MadeHand::FourOfAKind,
+        HandCategory::FullHouse => MadeHand::FullHouse,
+        HandCategory::Flush => MadeHand::Flush,
+        HandCategory::Straight => MadeHand::Straight,
+        _ => pair_bucket(hole, board),
+    };
+
+    let draw = if board.len() == 5 || made_hand >= MadeHand::Straight {
+        DrawType::NoDraw
+    } else {
+        draw_bucket(hole, board)
+    };
+
+    Ok(HandClass { made_hand, draw })
+}
+
+/// Classifies pair-type made hands (and weaker).
+fn pair_bucket(hole: (Card, Card), board: &[Card]) -> MadeHand {
+    let (rank1, rank2) = (hole.0 >> 2, hole.1 >> 2);
+    let board_count = |rank: u8| board.iter().filter(|&&c| c >> 2 == rank).count();
+
+    let mut board_ranks = board.iter().map(|&c| c >> 2).collect::<Vec<_>>();
+    board_ranks.sort_unstable_by(|a, b| b.cmp(a));
+    board_ranks.dedup();
+
+    // pocket pair
+    if rank1 == rank2 {
+        return match board_count(rank1) {
+            0 if rank1 > board_ranks[0] => MadeHand::Overpair,
+            0 if rank1 > board_ranks[1] => MadeHand::SecondPair,
+            0 => MadeHand::WeakPair,
+            1 => MadeHand::Set,
+            _ => MadeHand::FourOfAKind,
+        };
+    }
+
+    let (count1, count2) = (board_count(rank1), board_count(rank2));
+
+    if count1 >= 1 && count2 >= 1 {
+        return if count1 + count2 >= 3 {
+            MadeHand::FullHouse
+        } else {
+            MadeHand::TwoPair
+        };
+    }
+
+    if count1 == 2 || count2 == 2 {
+        return MadeHand::Trips;
+    }
+
+    let (paired, kicker) = match (count1, count2) {
+        (1, _) => (rank1, rank2),
+        (_, 1) => (rank2, rank1),
+        _ => {
+            return if rank1 == 12 || rank2 == 12 {
+                MadeHand::AceHigh
+            } else {
+                MadeHand::NoMadeHand
+            };
+        }
+    };
+
+    let position = board_ranks.iter().position(|&r| r == paired).unwrap();
+    match position {
+        0 => {
+            let better_kickers = (kicker + 1..13)
+                .filter(|&r| r != paired && !board_ranks.contains(&r))
+                .count();
+            match better_kickers {
+                0 => MadeHand::TopPairTopKicker,
+                1 | 2 => MadeHand::TopPairGoodKicker,
+                _ => MadeHand::TopPairWeakKicker,
+            }
+        }
+        1 => MadeHand::SecondPair,
+        _ => MadeHand::WeakPair,
+    }
+}
+
+/// Classifies drawing hands.
+fn draw_bucket(hole: (Card, Card), board: &[Card]) -> DrawType {
+    let mut suit_count = [0; 4];
+    let mut hole_suits = [false; 4];
+    for &card in board {
+        suit_count[card as usize & 3] += 1;
+    }
+    for card in [hole.0, hole.1] {
+        suit_count[card as usize & 3] += 1;
+        hole_suits[card as usize & 3] = true;
+    }
+
+    let flush_draw = (0..4).any(|suit| hole_suits[suit] && suit_count[suit] == 4);
+    let backdoor = board.len() == 3 && (0..4).any(|suit| hole_suits[suit] && suit_count[suit] == 3);
+
+    let board_mask = board.iter().fold(0u16, |acc, &c| acc | (1 << (c >> 2)));
+    let all_mask = board_mask | (1 << (hole.0 >> 2)) | (1 << (hole.1 >> 2));
+    let num_straight_ranks = (0..13)
+        .filter(|&rank| {
+            let bit = 1 << rank;
+            has_straight(all_mask | bit) && !has_straight(board_mask | bit)
+        })
+        .count();
+
+    match (flush_draw, num_straight_ranks) {
+        (true, 1..) => DrawType::ComboDraw,
+        (true, _) => DrawType::FlushDraw,
+        (false, 2..) => DrawType::OpenEnded,
+        (false, 1) => DrawType::Gutshot,
+        _ if backdoor => DrawType::BackdoorFlushDraw,
+        _ => DrawType::NoDraw,
+    }
+}
+
+/// Returns whether the rank set contains a straight.
+#[inline]
+fn has_straight(rank_mask: u16) -> bool {
+    const WHEEL: u16 = 0b1_0000_0000_1111;
+    let mask = rank_mask as u32;
+    (mask & (mask << 1) & (mask << 2) & (mask << 3) & (mask << 4)) != 0
+        || rank_mask & WHEEL == WHEEL
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::range::*;
+
+    fn classify(hole: &str, board: &str) -> HandClass {
+        let mut chars = hole.chars();
+        let hole = (
+            card_from_chars(&mut chars).unwrap(),
+            card_from_chars(&mut chars).unwrap(),
+        );
+        let mut chars = board.chars();
+        let mut cards = Vec::new();
+        while let Ok(card) = card_from_chars(&mut chars) {
+            cards.push(card);
+        }
+        classify_hand(hole, &cards).unwrap()
+    }
+
+    #[test]
+    fn made_hands() {
+        let tests = [
+            ("AsKd", "Kh9h4c", MadeHand::TopPairTopKicker),
+            ("KdQs", "Kh9h4c", MadeHand::TopPairGoodKicker),
+            ("KdJs", "Kh9h4c", MadeHand::TopPairGoodKicker),
+            ("Kd5s", "Kh9h4c", MadeHand::TopPairWeakKicker),
+            ("AsAd", "Kh9h4c", MadeHand::Overpair),
+            ("QsQd", "Kh9h4c", MadeHand::SecondPair),
+            ("9s8s", "Kh9h4c", MadeHand::SecondPair),
+            ("5s5d", "Kh9h4c", MadeHand::WeakPair),
+            ("4s3s", "Kh9h4c", MadeHand::WeakPair),
+            ("As2d", "Kh9h4c", MadeHand::AceHigh),
+            ("Qs2d", "Kh9h4c", MadeHand::NoMadeHand),
+            ("Ks9s", "Kh9h4c", MadeHand::TwoPair),
+            ("9s9d", "Kh9h4c", MadeHand::Set),
+            ("Ks2d", "KhKc4c", MadeHand::Trips),
+            ("Qs2d", "KhKc4c", MadeHand::NoMadeHand),
+            ("QsJd", "KhTh9c", MadeHand::Straight),
+            ("Ah2h", "Kh9h4h", MadeHand::Flush),
+            ("Ks4s", "KhKc4c", MadeHand::FullHouse),
+            ("AsKd", "QhJhTh3c2c", MadeHand::Straight),
+            ("2s3d", "AhKhQhJhTh", MadeHand::NoMadeHand),
+        ];
+
+        for (hole, board, expected) in tests {
+            assert_eq!(classify(hole, board).made_hand, expected, "{hole} {board}");
+        }
+    }
+
+    #[test]
+    fn draws() {
+        let tests = [
+            ("Ah2h", "Kh9h4c", DrawType::FlushDraw),
+            ("QhJh", "Th9h4c", DrawType::ComboDraw),
+            ("QsJd", "Th9h4c", DrawType::OpenEnded),
+            ("QsJd", "Th8h4c", DrawType::Gutshot),
+            ("Ah2c", "Kh9h4d", DrawType::BackdoorFlushDraw),
+            ("As2c", "Kh9s4d", DrawType::NoDraw),
+            ("As2c", "Kh9s4d3c", DrawType::Gutshot),
+            ("Ah2h", "Kh9h4c3h", DrawType::NoDraw),
+            ("Ah2h", "Kh9h4c3d8d", DrawType::NoDraw),
+        ];
+
+        for (hole, board, expected) in tests {
+            assert_eq!(classify(hole, board).draw, expected, "{hole} {board}");
+        }
+    }
+}
@@ -1,7 +1,68 @@
 use crate::mutex_like::*;
+use half::f16;
 use std::mem::MaybeUninit;
 use std::ops::Range;
 
+#[cfg(feature = "bincode")]
+use bincode::{Decode, Encode};
+
+#[cfg(feature = "profile")]
+use crate::profile::*;
+
+/// Summary of a [`solve`] run.
+///
+/// [`solve`]: crate::solve
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "bincode", derive(Decode, Encode))]
+pub struct SolveSummary {
+    /// Number of performed iterations.
+    pub num_iterations: u32,
+
+    /// Exploitability of the obtained strategy.
+    pub exploitability: f32,
+}
+
+/// Format of the values stored in the game tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StorageFormat {
+    /// 32-bit floating point numbers.
+    #[default]
+    Float32,
+
+    /// 16-bit integers scaled per buffer of each node (i.e., the compression).
+    Int16,
+
+    /// 8-bit integers scaled per buffer of each node.
+    ///
+    /// This halves the memory usage of the 16-bit compression, but the solution is less accurate:
+    /// the cumulative regrets smaller than 1/127 of the largest one in the same node are rounded to
+    /// zero, so the exploitability typically stops decreasing at around 0.1-0.2% of the pot (about
+    /// 50 times larger than the 32-bit floats after 1,000 iterations).
+    Int8,
+
+    /// IEEE 754 half-precision floating point numbers.
+    ///
+    /// The values are converted to 32-bit floats for the computation, and out-of-range values are
+    /// saturated. Unlike the integer formats, small values keep their relative precision, so the
+    /// regrets and the counterfactual values are almost as accurate as the 32-bit floats. The
+    /// cumulative strategies lose small increments as they grow, and the exploitability is
+    /// typically about 10 times larger than the 32-bit floats after 1,000 iterations.
+    Float16,
+}
+
+impl StorageFormat {
+    /// Returns the number of bytes per stored value.
+    #[inline]
+    pub fn num_bytes(self) -> usize {
+        match self {
+            Self::Float32 => 4,
+            Self::Int16 => 2,
+            Self::Int8 => 1,
+            Self::Float16 => 2,
+        }
+    }
+}
+
 /// The trait representing a game.
 pub trait Game: Send + Sync {
     /// The type representing a node in game tree.
@@ -10,7 +71,7 @@ pub trait Game: Send + Sync {
 
     /// Returns the root node of game tree.
     #[doc(hidden)]
-    fn root(&self) -> MutexGuardLike<Self::Node>;
+    fn root(&self) -> MutexGuardLike<'_, Self::Node>;
 
     /// Returns the number of private hands of given player.
     #[doc(hidden)]
@@ -77,6 +138,36 @@ pub trait Game: Send + Sync {
     fn is_compression_enabled(&self) -> bool {
         false
     }
+
+    /// Returns the format of the stored strategies.
+    #[doc(hidden)]
+    fn strategy_format(&self) -> StorageFormat {
+        match self.is_compression_enabled() {
+            false => StorageFormat::Float32,
+            true => StorageFormat::Int16,
+        }
+    }
+
+    /// Returns the format of the stored regrets and counterfactual values.
+    #[doc(hidden)]
+    fn value_format(&self) -> StorageFormat {
+        self.strategy_format()
+    }
+
+    /// Records the summary of the solve. Called by [`solve`] after finalizing the game; call it
+    /// manually when solving with [`solve_step`].
+    ///
+    /// [`solve`]: crate::solve
+    /// [`solve_step`]: crate::solve_step
+    #[inline]
+    fn set_solve_summary(&mut self, _summary: SolveSummary) {}
+
+    /// Records the timing statistics of the solve. Called by [`solve`] after finalizing the game.
+    ///
+    /// [`solve`]: crate::solve
+    #[cfg(feature = "profile")]
+    #[inline]
+    fn set_solver_stats(&mut self, _stats: SolverStats) {}
 }
 
 /// The trait representing a node in game tree.
@@ -99,7 +190,7 @@ pub trait GameNode: Send + Sync {
 
     /// Returns the node after taking the given action.
     #[doc(hidden)]
-    fn play(&self, action: usize) -> MutexGuardLike<Self>;
+    fn play(&self, action: usize) -> MutexGuardLike<'_, Self>;
 
     /// Returns the strategy.
     #[doc(hidden)]
@@ -227,6 +318,126 @@ pub trait GameNode: Send + Sync {
         unreachable!()
     }
 
+    /// Returns the 8-bit compressed strategy.
+    #[doc(hidden)]
+    fn strategy_8bit(&self) -> &[u8] {
+        unreachable!()
+    }
+
+    /// Returns the mutable reference to the 8-bit compressed strategy.
+    #[doc(hidden)]
+    fn strategy_8bit_mut(&mut self) -> &mut [u8] {
+        unreachable!()
+    }
+
+    /// Returns the 8-bit compressed cumulative regrets.
+    #[doc(hidden)]
+    fn regrets_8bit(&self) -> &[i8] {
+        unreachable!()
+    }
+
+    /// Returns the mutable reference to the 8-bit compressed cumulative regrets.
+    #[doc(hidden)]
+    fn regrets_8bit_mut(&mut self) -> &mut [i8] {
+        unreachable!()
+    }
+
+    /// Returns the 8-bit compressed counterfactual values.
+    #[doc(hidden)]
+    fn cfvalues_8bit(&self) -> &[i8] {
+        unreachable!()
+    }
+
+    /// Returns the mutable reference to the 8-bit compressed counterfactual values.
+    #[doc(hidden)]
+    fn cfvalues_8bit_mut(&mut self) -> &mut [i8] {
+        unreachable!()
+    }
+
+    /// Returns IP's 8-bit compressed counterfactual values.
+    #[doc(hidden)]
+    fn cfvalues_ip_8bit(&self) -> &[i8] {
+        unreachable!()
+    }
+
+    /// Returns the mutable reference to IP's 8-bit compressed counterfactual values.
+    #[doc(hidden)]
+    fn cfvalues_ip_8bit_mut(&mut self) -> &mut [i8] {
+        unreachable!()
+    }
+
+    /// Returns the 8-bit compressed buffer for counterfactual values.
+    #[doc(hidden)]
+    fn cfvalues_chance_8bit(&self) -> &[i8] {
+        unreachable!()
+    }
+
+    /// Returns the mutable reference to the 8-bit compressed buffer for counterfactual values.
+    #[doc(hidden)]
+    fn cfvalues_chance_8bit_mut(&mut self) -> &mut [i8] {
+        unreachable!()
+    }
+
+    /// Returns the half-precision strategy.
+    #[doc(hidden)]
+    fn strategy_f16(&self) -> &[f16] {
+        unreachable!()
+    }
+
+    /// Returns the mutable reference to the half-precision strategy.
+    #[doc(hidden)]
+    fn strategy_f16_mut(&mut self) -> &mut [f16] {
+        unreachable!()
+    }
+
+    /// Returns the half-precision cumulative regrets.
+    #[doc(hidden)]
+    fn regrets_f16(&self) -> &[f16] {
+        unreachable!()
+    }
+
+    /// Returns the mutable reference to the half-precision cumulative regrets.
+    #[doc(hidden)]
+    fn regrets_f16_mut(&mut self) -> &mut [f16] {
+        unreachable!()
+    }
+
+    /// Returns the half-precision counterfactual values.
+    #[doc(hidden)]
+    fn cfvalues_f16(&self) -> &[f16] {
+        unreachable!()
+    }
+
+    /// Returns the mutable reference to the half-precision counterfactual values.
+    #[doc(hidden)]
+    fn cfvalues_f16_mut(&mut self) -> &mut [f16] {
+        unreachable!()
+    }
+
+    /// Returns IP's half-precision counterfactual values.
+    #[doc(hidden)]
+    fn cfvalues_ip_f16(&self) -> &[f16] {
+        unreachable!()
+    }
+
+    /// Returns the mutable reference to IP's half-precision counterfactual values.
+    #[doc(hidden)]
+    fn cfvalues_ip_f16_mut(&mut self) -> &mut [f16] {
+        unreachable!()
+    }
+
+    /// Returns the half-precision buffer for counterfactual values.
+    #[doc(hidden)]
+    fn cfvalues_chance_f16(&self) -> &[f16] {
+        unreachable!()
+    }
+
+    /// Returns the mutable reference to the half-precision buffer for counterfactual values.
+    #[doc(hidden)]
+    fn cfvalues_chance_f16_mut(&mut self) -> &mut [f16] {
+        unreachable!()
+    }
+
     /// Returns the scale of the compressed strategy.
     #[doc(hidden)]
     fn strategy_scale(&self) -> f32 {
@@ -292,4 +503,18 @@ pub trait GameNode: Send + Sync {
     fn enable_parallelization(&self) -> bool {
         false
     }
+
+    /// Returns the street of the node (`0`: flop, `1`: turn, `2`: river), used for profiling and
+    /// the per-street best responses. By default, it is set to `0`.
+    #[doc(hidden)]
+    fn street(&self) -> usize {
+        0
+    }
+
+    /// Returns the turn card if the node is a chance node dealing the river card, used for the
+    /// river sampling. By default, it is set to `None`.
+    #[doc(hidden)]
+    fn river_chance_turn(&self) -> Option<u8> {
+        None
+    }
 }
@@ -4,10 +4,10 @@ use std::ops::{Deref, DerefMut};
 
 #[cfg(feature = "bincode")]
 use bincode::{
-    Decode, Encode, BorrowDecode,
-    de::{Decoder, BorrowDecoder},
+    de::{BorrowDecoder, Decoder},
     enc::Encoder,
     error::{DecodeError, EncodeError},
+    BorrowDecode, Decode, Encode,
 };
 
 /// Mutex-like wrapper, but it actually does not perform any locking.
@@ -40,7 +40,7 @@ impl<T> MutexLike<T> {
     ///
     /// # Examples
     /// ```
-    /// use postflop_solver::MutexLike;
+    /// use postflop_solver_ffi::MutexLike;
     ///
     /// let mutex_like = MutexLike::new(0);
     /// ```
@@ -57,19 +57,19 @@ impl<T: ?Sized> MutexLike<T> {
     ///
     /// # Examples
     /// ```
-    /// use postflop_solver::MutexLike;
+    /// use postflop_solver_ffi::MutexLike;
     ///
     /// let mutex_like = MutexLike::new(0);
     /// *mutex_like.lock() = 10;
     /// assert_eq!(*mutex_like.lock(), 10);
     /// ```
     #[inline]
-    pub fn lock(&self) -> MutexGuardLike<T> {
+    pub fn lock(&self) -> MutexGuardLike<'_, T> {
         MutexGuardLike { mutex: self }
     }
 }
 
-impl<T: ?Sized + Default> Default for MutexLike<T> {
+impl<T: Default> Default for MutexLike<T> {
     #[inline]
     fn default() -> Self {
         Self::new(Default::default())
@@ -116,25 +116,27 @@ impl<'de, C, T: BorrowDecode<'de, C>> BorrowDecode<'de, C> for MutexLike<T> {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "bincode"))]
 mod tests {
     use super::*;
 
     #[test]
     fn test_encode_decode() {
+        let config = bincode::config::standard();
         let mutex_like = MutexLike::new(42);
-        let mut encoder = bincode::enc::Encoder::new();
-        mutex_like.encode(&mut encoder).unwrap();
-        let decoded_mutex_like = Decode::decode(&mut bincode::de::Decoder::new(encoder.into_inner())).unwrap();
-        assert_eq!(*decoded_mutex_like.lock(), 42);
+        let encoded = bincode::encode_to_vec(&mutex_like, config).unwrap();
+        let (decoded, _): (MutexLike<i32>, _) =
+            bincode::decode_from_slice(&encoded, config).unwrap();
+        assert_eq!(*decoded.lock(), 42);
     }
 
     #[test]
     fn test_borrow_decode() {
+        let config = bincode::config::standard();
         let mutex_like = MutexLike::new("Hello, world!");
-        let mut encoder = bincode::enc::Encoder::new();
-        mutex_like.encode(&mut encoder).unwrap();
-        let decoded_mutex_like = BorrowDecode::borrow_decode(&mut bincode::de::BorrowDecoder::new(encoder.into_inner())).unwrap();
-        assert_eq!(*decoded_mutex_like.lock(), "Hello, world!");
+        let encoded = bincode::encode_to_vec(&mutex_like, config).unwrap();
+        let (decoded, _): (MutexLike<&str>, _) =
+            bincode::borrow_decode_from_slice(&encoded, config).unwrap();
+        assert_eq!(*decoded.lock(), "Hello, world!");
     }
 }
@@ -0,0 +1,153 @@
+use crate::error::*;
+#[cfg(target_os = "linux")]
+use std::fs;
+
+/// Returns the CPUs of each online NUMA node.
+///
+/// Returns an empty vector if the NUMA topology is not available (e.g., on non-Linux platforms).
+pub fn numa_nodes() -> Vec<Vec<usize>> {
+    topology().into_iter().map(|(_, cpus)| cpus).collect()
+}
+
+/// Creates a thread pool of `num_threads` threads (all CPUs if `0`) whose threads are pinned to
+/// the CPUs.
+///
+/// The threads are assigned to the NUMA nodes in round-robin order, so that the threads are
+/// spread across the sockets. Use the pool with [`solve_in_pool`] and combine it with the
+/// `StorageBackend::Interleaved` backend to spread the node storages as well.
+///
+/// Returns an error if the NUMA topology is not available or if the pool cannot be created.
+///
+/// [`solve_in_pool`]: crate::solve_in_pool
+pub fn pinned_thread_pool(num_threads: usize) -> Result<rayon::ThreadPool, PostFlopError> {
+    let nodes = numa_nodes();
+    let max_len = nodes.iter().map(Vec::len).max().unwrap_or(0);
+    let cpus = (0..max_len)
+        .flat_map(|i| nodes.iter().filter_map(move |cpus| cpus.get(i).copied()))
+        .collect::<Vec<_>>();
+
+    if cpus.is_empty() {
+        return Err(PostFlopError::Unsupported(
+            "NUMA topology is not available".to_string(),
+        ));
+    }
+
+    let num_threads = match num_threads {
+        0 => cpus.len(),
+        n => n,
+    };
+
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .start_handler(move |index| pin_current_thread(cpus[index % cpus.len()]))
+        .build()
+        .map_err(|e| PostFlopError::Other(format!("Failed to create thread pool: {e}")))
+}
+
+/// Sets the memory policy of the pages of `slice` to interleave them across the NUMA nodes.
+///
+/// The pages that are not touched yet are placed when they are first touched; the touched pages
+/// are moved. This is a no-op on single-node systems.
+pub(crate) fn interleave_memory(slice: &mut [u8]) -> Result<(), PostFlopError> {
+    #[cfg(target_os = "linux")]
+    {
+        const MPOL_INTERLEAVE: libc::c_int = 3;
+        const MPOL_MF_MOVE: libc::c_uint = 1 << 1;
+
+        let nodes = read_list("/sys/devices/system/node/has_memory").unwrap_or_default();
+        if nodes.len() <= 1 {
+            return Ok(());
+        }
+
+        // nodes whose IDs do not fit in the mask are not used
+        let mask = nodes
+            .iter()
+            .filter(|&&node| node < u64::BITS as usize)
+            .fold(0u64, |mask, &node| mask | 1 << node);
+
+        // `mbind` requires a page-aligned address
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+        let start = slice.as_mut_ptr() as usize;
+        let end = start + slice.len();
+        let aligned_start = start.next_multiple_of(page_size);
+        let aligned_end = end / page_size * page_size;
+        if aligned_start >= aligned_end {
+            return Ok(());
+        }
+
+        let ret = unsafe {
+            libc::syscall(
+                libc::SYS_mbind,
+                aligned_start,
+                aligned_end - aligned_start,
+                MPOL_INTERLEAVE,
+                &mask as *const u64,
+                u64::BITS as libc::c_ulong + 1,
+                MPOL_MF_MOVE,
+            )
+        };
+
+        if ret != 0 {
+            let error = std::io::Error::last_os_error();
+            return Err(PostFlopError::AllocationFailed {
+                required: slice.len() as u64,
+                reason: format!("Failed to interleave memory: {error}"),
+            });
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    let _ = slice;
+
+    Ok(())
+}
+
+/// Returns the IDs and the CPUs of the online NUMA nodes.
+fn topology() -> Vec<(usize, Vec<usize>)> {
+    #[cfg(target_os = "linux")]
+    {
+        let Some(nodes) = read_list("/sys/devices/system/node/online") else {
+            return Vec::new();
+        };
+
+        nodes
+            .into_iter()
+            .map(|node| {
+                let cpus = read_list(&format!("/sys/devices/system/node/node{node}/cpulist"))?;
+                Some((node, cpus))
+            })
+            .collect::<Option<Vec<_>>>()
+            .unwrap_or_default()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    Vec::new()
+}
+
+/// Pins the current thread to the given CPU. Failures are ignored.
+fn pin_current_thread(cpu: usize) {
+    #[cfg(target_os = "linux")]
+    unsafe {
+        let mut set = std::mem::zeroed::<libc::cpu_set_t>();
+        libc::CPU_ZERO(&mut set);
+        libc::CPU_SET(cpu, &mut set);
+        libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set);
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    let _ = cpu;
+}
+
+/// Reads a list like "0-3,8,10-11" from the given file.
+#[cfg(target_os = "linux")]
+fn read_list(path: &str) -> Option<Vec<usize>> {
+    let s = fs::read_to_string(path).ok()?;
+    let mut ret = Vec::new();
+    for item in s.trim().split(',').filter(|item| !item.is_empty()) {
+        match item.split_once('-') {
+            Some((start, end)) => ret.extend(start.parse::<usize>().ok()?..=end.parse().ok()?),
+            None => ret.push(item.parse().ok()?),
+        }
+    }
+    Some(ret)
+}
@@ -0,0 +1,140 @@
+use std::cell::RefCell;
+use std::mem::{self, MaybeUninit};
+use std::ops::{Deref, DerefMut};
+
+/// Element types of [`PooledVec`], each of which has its own thread-local pool.
+pub(crate) trait PoolElement: Sized + 'static {
+    /// Calls `f` with the pool of the current thread, or returns `None` if the pool has already
+    /// been destroyed.
+    fn with_pool<R>(f: impl FnOnce(&mut Vec<Vec<Self>>) -> R) -> Option<R>;
+}
+
+macro_rules! impl_pool_element {
+    ($($t:ty),*) => {$(
+        impl PoolElement for $t {
+            #[inline]
+            fn with_pool<R>(f: impl FnOnce(&mut Vec<Vec<Self>>) -> R) -> Option<R> {
+                thread_local! {
+                    static POOL: RefCell<Vec<Vec<$t>>> = const { RefCell::new(Vec::new()) };
+                }
+                POOL.try_with(|pool| f(&mut pool.borrow_mut())).ok()
+            }
+        }
+    )*};
+}
+
+impl_pool_element!(f32, f64);
+
+/// A vector whose buffer is taken from a thread-local pool and returned to the pool when dropped.
+///
+/// This is the allocation strategy of the temporary buffers in the solving process on stable
+/// Rust, and of the scratch buffers of the result queries. Unlike the `custom-alloc` feature, the
+/// vectors can be dropped in any order and on any thread, and the buffers are reused once they
+/// have grown to the required capacity.
+pub(crate) struct PooledVec<T: PoolElement> {
+    vec: Vec<T>,
+    capacity: usize,
+}
+
+impl<T: PoolElement> PooledVec<T> {
+    /// Takes an empty vector with at least the specified capacity from the pool.
+    #[inline]
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        let mut vec = T::with_pool(|pool| pool.pop())
+            .flatten()
+            .unwrap_or_default();
+        vec.reserve(capacity);
+        Self { vec, capacity }
+    }
+
+    /// Returns the remaining spare capacity up to the requested capacity, like
+    /// [`Vec::spare_capacity_mut`] of a vector created by [`Vec::with_capacity`].
+    ///
+    /// The reused buffer may be larger than requested, so this shadows the method of `Vec`.
+    #[inline]
+    pub(crate) fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<T>] {
+        let len = self.vec.len();
+        &mut self.vec.spare_capacity_mut()[..self.capacity.saturating_sub(len)]
+    }
+}
+
+impl<T: PoolElement> Drop for PooledVec<T> {
+    #[inline]
+    fn drop(&mut self) {
+        let mut vec = mem::take(&mut self.vec);
+        if vec.capacity() > 0 {
+            vec.clear();
+            T::with_pool(|pool| pool.push(vec));
+        }
+    }
+}
+
+impl<T: PoolElement> Deref for PooledVec<T> {
+    type Target = Vec<T>;
+
+    #[inline]
+    fn deref(&self) -> &Vec<T> {
+        &self.vec
+    }
+}
+
+impl<T: PoolElement> DerefMut for PooledVec<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Vec<T> {
+        &mut self.vec
+    }
+}
+
+impl<'a, T: PoolElement> IntoIterator for &'a PooledVec<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.vec.iter()
+    }
+}
+
+impl<'a, T: PoolElement> IntoIterator for &'a mut PooledVec<T> {
+    type Item = &'a mut T;
+    type IntoIter = std::slice::IterMut<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.vec.iter_mut()
+    }
+}
+
+/// Frees the buffers in the pools of the current thread.
+pub(crate) fn free_pooled_buffers() {
+    f32::with_pool(|pool| *pool = Vec::new());
+    f64::with_pool(|pool| *pool = Vec::new());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reuse_buffers() {
+        free_pooled_buffers();
+
+        let mut vec = PooledVec::<f32>::with_capacity(100);
+        vec.extend([1.0, 2.0, 3.0]);
+        let ptr = vec.as_ptr();
+        drop(vec);
+
+        let mut vec = PooledVec::<f32>::with_capacity(50);
+        assert!(vec.is_empty());
+        assert_eq!(vec.as_ptr(), ptr);
+        assert_eq!(vec.spare_capacity_mut().len(), 50);
+
+        let other = PooledVec::<f32>::with_capacity(50);
+        assert_ne!(other.as_ptr(), ptr);
+        drop((vec, other));
+
+        assert_eq!(f32::with_pool(|pool| pool.len()), Some(2));
+        free_pooled_buffers();
+        assert_eq!(f32::with_pool(|pool| pool.len()), Some(0));
+    }
+}
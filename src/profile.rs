@@ -0,0 +1,161 @@
+use crate::interface::*;
+
+#[cfg(feature = "profile")]
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[cfg(feature = "profile")]
+use std::time::{Duration, Instant};
+
+/// Timing statistics of a [`solve`] run, recorded when the `profile` feature is enabled.
+///
+/// The times of the node operations are summed over the threads, so they can exceed the
+/// wall-clock time when solving in parallel.
+///
+/// [`solve`]: crate::solve
+#[cfg(feature = "profile")]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SolverStats {
+    /// Wall-clock time of each iteration, excluding the exploitability computation.
+    pub iteration_times: Vec<Duration>,
+
+    /// Wall-clock time of the exploitability computations.
+    pub exploitability_time: Duration,
+
+    /// Time of the terminal evaluations of each street (flop, turn, river).
+    pub evaluation_time: [Duration; 3],
+
+    /// Time of the other node operations of each street (flop, turn, river), such as the
+    /// regret matching and the updates of the cumulative values.
+    pub traversal_time: [Duration; 3],
+}
+
+#[cfg(feature = "profile")]
+impl SolverStats {
+    /// Returns the total wall-clock time of the iterations.
+    #[inline]
+    pub fn total_iteration_time(&self) -> Duration {
+        self.iteration_times.iter().sum()
+    }
+
+    /// Returns the mean wall-clock time per iteration, or zero if no iteration was performed.
+    #[inline]
+    pub fn mean_iteration_time(&self) -> Duration {
+        match self.iteration_times.len() {
+            0 => Duration::ZERO,
+            n => self.total_iteration_time() / n as u32,
+        }
+    }
+}
+
+/// Accumulator of the node operation times, shared by the threads of one iteration.
+///
+/// This is a zero-sized no-op when the `profile` feature is disabled.
+#[derive(Default)]
+pub(crate) struct Profiler {
+    #[cfg(feature = "profile")]
+    evaluation_nanos: [AtomicU64; 3],
+    #[cfg(feature = "profile")]
+    traversal_nanos: [AtomicU64; 3],
+}
+
+/// Start time of a measured section.
+pub(crate) struct Timer {
+    #[cfg(feature = "profile")]
+    start: Instant,
+}
+
+/// Recorder of the [`SolverStats`] of a solve run.
+///
+/// This is a zero-sized no-op when the `profile` feature is disabled.
+#[derive(Default)]
+pub(crate) struct StatsRecorder {
+    #[cfg(feature = "profile")]
+    stats: SolverStats,
+}
+
+impl Timer {
+    /// Starts measuring a section.
+    #[inline]
+    pub(crate) fn start() -> Self {
+        Self {
+            #[cfg(feature = "profile")]
+            start: Instant::now(),
+        }
+    }
+}
+
+impl StatsRecorder {
+    /// Calls `compute` and records its time as an exploitability computation.
+    #[inline]
+    pub(crate) fn measure_exploitability(&mut self, compute: impl FnOnce() -> f32) -> f32 {
+        let timer = Timer::start();
+        let exploitability = compute();
+        #[cfg(feature = "profile")]
+        {
+            self.stats.exploitability_time += timer.start.elapsed();
+        }
+        #[cfg(not(feature = "profile"))]
+        let _ = timer;
+        exploitability
+    }
+
+    /// Records an iteration started at `timer` and the node operation times of `profiler`.
+    #[inline]
+    pub(crate) fn record_iteration(&mut self, timer: Timer, profiler: &Profiler) {
+        #[cfg(feature = "profile")]
+        {
+            self.stats.iteration_times.push(timer.start.elapsed());
+            profiler.merge_into(&mut self.stats);
+        }
+        #[cfg(not(feature = "profile"))]
+        let _ = (timer, profiler);
+    }
+
+    /// Stores the recorded statistics in `game`.
+    #[inline]
+    pub(crate) fn finish<T: Game>(self, game: &mut T) {
+        #[cfg(feature = "profile")]
+        game.set_solver_stats(self.stats);
+        #[cfg(not(feature = "profile"))]
+        let _ = game;
+    }
+}
+
+impl Profiler {
+    /// Records the time since `timer` as a terminal evaluation at the street of `node`.
+    #[inline]
+    pub(crate) fn record_evaluation<T: GameNode>(&self, node: &T, timer: Timer) {
+        #[cfg(feature = "profile")]
+        Self::add(&self.evaluation_nanos[node.street()], timer);
+        #[cfg(not(feature = "profile"))]
+        let _ = (node, timer);
+    }
+
+    /// Records the time since `timer` as a node operation at the street of `node`.
+    #[inline]
+    pub(crate) fn record_traversal<T: GameNode>(&self, node: &T, timer: Timer) {
+        #[cfg(feature = "profile")]
+        Self::add(&self.traversal_nanos[node.street()], timer);
+        #[cfg(not(feature = "profile"))]
+        let _ = (node, timer);
+    }
+
+    /// Adds the recorded times to `stats`.
+    #[cfg(feature = "profile")]
+    #[inline]
+    fn merge_into(&self, stats: &mut SolverStats) {
+        for street in 0..3 {
+            let evaluation = self.evaluation_nanos[street].load(Ordering::Relaxed);
+            let traversal = self.traversal_nanos[street].load(Ordering::Relaxed);
+            stats.evaluation_time[street] += Duration::from_nanos(evaluation);
+            stats.traversal_time[street] += Duration::from_nanos(traversal);
+        }
+    }
+
+    #[cfg(feature = "profile")]
+    #[inline]
+    fn add(nanos: &AtomicU64, timer: Timer) {
+        let elapsed = timer.start.elapsed().as_nanos() as u64;
+        nanos.fetch_add(elapsed, Ordering::Relaxed);
+    }
+}
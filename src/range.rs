@@ -1,7 +1,8 @@
 use crate::card::*;
+use crate::error::*;
 use once_cell::sync::Lazy;
 use regex::Regex;
-use std::fmt::Write;
+use std::fmt::{self, Write};
 use std::str::FromStr;
 
 #[cfg(feature = "bincode")]
@@ -21,7 +22,7 @@ use bincode::{Decode, Encode};
 ///
 /// # Examples
 /// ```
-/// use postflop_solver::Range;
+/// use postflop_solver_ffi::Range;
 ///
 /// // construct a range from a string
 /// let range = "QQ+,AKs".parse::<Range>().unwrap();
@@ -193,7 +194,7 @@ fn suit_to_char(suit: u8) -> Result<char, String> {
 ///
 /// # Examples
 /// ```
-/// use postflop_solver::card_to_string;
+/// use postflop_solver_ffi::card_to_string;
 ///
 /// assert_eq!(card_to_string(0), Ok("2c".to_string()));
 /// assert_eq!(card_to_string(5), Ok("3d".to_string()));
@@ -202,11 +203,14 @@ fn suit_to_char(suit: u8) -> Result<char, String> {
 /// assert!(card_to_string(52).is_err());
 /// ```
 #[inline]
-pub fn card_to_string(card: Card) -> Result<String, String> {
-    check_card(card)?;
-    let rank = card >> 2;
-    let suit = card & 3;
-    Ok(format!("{}{}", rank_to_char(rank)?, suit_to_char(suit)?))
+pub fn card_to_string(card: Card) -> Result<String, PostFlopError> {
+    let to_string = || -> Result<String, String> {
+        check_card(card)?;
+        let rank = card >> 2;
+        let suit = card & 3;
+        Ok(format!("{}{}", rank_to_char(rank)?, suit_to_char(suit)?))
+    };
+    to_string().map_err(|reason| invalid_card(&card.to_string(), reason))
 }
 
 /// Attempts to convert hole cards into a string.
@@ -217,14 +221,14 @@ pub fn card_to_string(card: Card) -> Result<String, String> {
 ///
 /// # Examples
 /// ```
-/// use postflop_solver::hole_to_string;
+/// use postflop_solver_ffi::hole_to_string;
 ///
 /// assert_eq!(hole_to_string((0, 5)), Ok("3d2c".to_string()));
 /// assert_eq!(hole_to_string((10, 51)), Ok("As4h".to_string()));
 /// assert!(hole_to_string((52, 53)).is_err());
 /// ```
 #[inline]
-pub fn hole_to_string(hole: (Card, Card)) -> Result<String, String> {
+pub fn hole_to_string(hole: (Card, Card)) -> Result<String, PostFlopError> {
     let max_card = Card::max(hole.0, hole.1);
     let min_card = Card::min(hole.0, hole.1);
     Ok(format!(
@@ -242,7 +246,7 @@ pub fn hole_to_string(hole: (Card, Card)) -> Result<String, String> {
 ///
 /// # Examples
 /// ```
-/// use postflop_solver::holes_to_strings;
+/// use postflop_solver_ffi::holes_to_strings;
 ///
 /// assert_eq!(
 ///     holes_to_strings(&[(0, 5), (10, 51)]),
@@ -251,15 +255,65 @@ pub fn hole_to_string(hole: (Card, Card)) -> Result<String, String> {
 /// assert!(holes_to_strings(&[(52, 53)]).is_err());
 /// ```
 #[inline]
-pub fn holes_to_strings(holes: &[(Card, Card)]) -> Result<Vec<String>, String> {
+pub fn holes_to_strings(holes: &[(Card, Card)]) -> Result<Vec<String>, PostFlopError> {
     holes.iter().map(|&hole| hole_to_string(hole)).collect()
 }
 
+/// Attempts to convert a hand string into a list of hole cards.
+///
+/// The hand string is either a specific combination (e.g., "AhKd") or one of the 169 hand classes
+/// (e.g., "AKs", "AKo", "AK", "77"). A hand class is expanded into all of its member combinations.
+/// Each returned pair has IDs in `(low_id, high_id)` order, and the list is sorted in the
+/// lexicographic order, which is consistent with [`PostFlopGame::private_cards`].
+///
+/// # Examples
+/// ```
+/// use postflop_solver_ffi::holes_from_str;
+///
+/// assert_eq!(holes_from_str("AhKd"), Ok(vec![(45, 50)]));
+/// assert_eq!(holes_from_str("AKs").unwrap().len(), 4);
+/// assert_eq!(holes_from_str("AKo").unwrap().len(), 12);
+/// assert_eq!(holes_from_str("AK").unwrap().len(), 16);
+/// assert_eq!(holes_from_str("77").unwrap().len(), 6);
+/// assert!(holes_from_str("AKx").is_err());
+/// ```
+///
+/// [`PostFlopGame::private_cards`]: crate::PostFlopGame::private_cards
+pub fn holes_from_str(s: &str) -> Result<Vec<(Card, Card)>, PostFlopError> {
+    let s = s.trim();
+
+    let parse = || -> Result<Vec<(Card, Card)>, String> {
+        if s.len() == 4 {
+            let mut chars = s.chars();
+            let card1 = card_from_chars(&mut chars)?;
+            let card2 = card_from_chars(&mut chars)?;
+            if card1 == card2 {
+                return Err(format!("Duplicate cards are not allowed: {s}"));
+            }
+            return Ok(vec![(card1.min(card2), card1.max(card2))]);
+        }
+
+        if !(2..=3).contains(&s.len()) {
+            return Err(format!("Invalid hand: {s}"));
+        }
+
+        let (rank1, rank2, suitedness) = parse_compound_singleton(s)?;
+        let mut indices = indices_with_suitedness(rank1, rank2, suitedness);
+        indices.sort_unstable();
+        Ok(indices.into_iter().map(index_to_card_pair).collect())
+    };
+
+    parse().map_err(|reason| PostFlopError::InvalidHand {
+        input: s.to_string(),
+        reason,
+    })
+}
+
 /// Attempts to read the next card from a char iterator.
 ///
 /// # Examples
 /// ```
-/// use postflop_solver::card_from_chars;
+/// use postflop_solver_ffi::card_from_chars;
 ///
 /// let mut chars = "2c3d4hAs".chars();
 /// assert_eq!(card_from_chars(&mut chars), Ok(0));
@@ -269,21 +323,26 @@ pub fn holes_to_strings(holes: &[(Card, Card)]) -> Result<Vec<String>, String> {
 /// assert!(card_from_chars(&mut chars).is_err());
 /// ```
 #[inline]
-pub fn card_from_chars<T: Iterator<Item = char>>(chars: &mut T) -> Result<Card, String> {
-    let rank_char = chars.next().ok_or_else(|| "Unexpected end".to_string())?;
-    let suit_char = chars.next().ok_or_else(|| "Unexpected end".to_string())?;
-
-    let rank = char_to_rank(rank_char)?;
-    let suit = char_to_suit(suit_char)?;
-
-    Ok((rank << 2) | suit)
+pub fn card_from_chars<T: Iterator<Item = char>>(chars: &mut T) -> Result<Card, PostFlopError> {
+    let (rank_char, suit_char) = (chars.next(), chars.next());
+
+    let parse = || -> Result<Card, String> {
+        let rank = char_to_rank(rank_char.ok_or("Unexpected end")?)?;
+        let suit = char_to_suit(suit_char.ok_or("Unexpected end")?)?;
+        Ok((rank << 2) | suit)
+    };
+
+    parse().map_err(|reason| PostFlopError::InvalidCard {
+        input: rank_char.into_iter().chain(suit_char).collect(),
+        reason,
+    })
 }
 
 /// Attempts to convert a string into a card.
 ///
 /// # Examples
 /// ```
-/// use postflop_solver::card_from_str;
+/// use postflop_solver_ffi::card_from_str;
 ///
 /// assert_eq!(card_from_str("2c"), Ok(0));
 /// assert_eq!(card_from_str("3d"), Ok(5));
@@ -291,12 +350,12 @@ pub fn card_from_chars<T: Iterator<Item = char>>(chars: &mut T) -> Result<Card,
 /// assert_eq!(card_from_str("As"), Ok(51));
 /// ```
 #[inline]
-pub fn card_from_str(s: &str) -> Result<Card, String> {
+pub fn card_from_str(s: &str) -> Result<Card, PostFlopError> {
     let mut chars = s.chars();
-    let result = card_from_chars(&mut chars)?;
+    let result = card_from_chars(&mut chars).map_err(|e| invalid_card(s, e))?;
 
     if chars.next().is_some() {
-        return Err("Expected exactly two characters".to_string());
+        return Err(invalid_card(s, "Expected exactly two characters"));
     }
 
     Ok(result)
@@ -306,34 +365,70 @@ pub fn card_from_str(s: &str) -> Result<Card, String> {
 ///
 /// # Examples
 /// ```
-/// use postflop_solver::flop_from_str;
+/// use postflop_solver_ffi::flop_from_str;
 ///
 /// assert_eq!(flop_from_str("2c3d4h"), Ok([0, 5, 10]));
 /// assert_eq!(flop_from_str("As Ah Ks"), Ok([47, 50, 51]));
 /// assert!(flop_from_str("2c3d4h5s").is_err());
 /// ```
 #[inline]
-pub fn flop_from_str(s: &str) -> Result<[Card; 3], String> {
+pub fn flop_from_str(s: &str) -> Result<[Card; 3], PostFlopError> {
     let mut result = [0; 3];
     let mut chars = s.chars();
 
-    result[0] = card_from_chars(&mut chars)?;
-    result[1] = card_from_chars(&mut chars.by_ref().skip_while(|c| c.is_whitespace()))?;
-    result[2] = card_from_chars(&mut chars.by_ref().skip_while(|c| c.is_whitespace()))?;
+    let mut parse = || -> Result<(), PostFlopError> {
+        result[0] = card_from_chars(&mut chars)?;
+        result[1] = card_from_chars(&mut chars.by_ref().skip_while(|c| c.is_whitespace()))?;
+        result[2] = card_from_chars(&mut chars.by_ref().skip_while(|c| c.is_whitespace()))?;
+        Ok(())
+    };
+    parse().map_err(|e| invalid_card(s, e))?;
 
     if chars.next().is_some() {
-        return Err("Expected exactly three cards".to_string());
+        return Err(invalid_card(s, "Expected exactly three cards"));
     }
 
     result.sort_unstable();
 
     if result[0] == result[1] || result[1] == result[2] {
-        return Err("Cards must be unique".to_string());
+        return Err(PostFlopError::BoardConflict { card: result[1] });
     }
 
     Ok(result)
 }
 
+/// Attempts to convert a string into the board cards in the given order (e.g., `"Td9d6h Qc"`),
+/// ignoring whitespace and checking for duplicates. The number of cards is not checked.
+///
+/// # Examples
+/// ```
+/// use postflop_solver_ffi::board_from_str;
+///
+/// assert_eq!(board_from_str("4h3d2c 5s"), Ok(vec![10, 5, 0, 15]));
+/// assert!(board_from_str("2c3d2c").is_err());
+/// ```
+pub fn board_from_str(s: &str) -> Result<Vec<Card>, PostFlopError> {
+    let mut chars = s.chars().filter(|c| !c.is_whitespace()).peekable();
+    let mut board = Vec::new();
+    while chars.peek().is_some() {
+        let card = card_from_chars(&mut chars).map_err(|e| invalid_card(s, e))?;
+        if board.contains(&card) {
+            return Err(PostFlopError::BoardConflict { card });
+        }
+        board.push(card);
+    }
+    Ok(board)
+}
+
+/// Creates an [`PostFlopError::InvalidCard`] error of the whole input.
+#[inline]
+fn invalid_card(input: &str, reason: impl ToString) -> PostFlopError {
+    PostFlopError::InvalidCard {
+        input: input.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
 #[inline]
 fn parse_singleton(combo: &str) -> Result<(u8, u8, Suitedness), String> {
     if combo.len() == 4 {
@@ -426,13 +521,16 @@ impl Range {
 
     /// Attempts to create a range from raw data.
     #[inline]
-    pub fn from_raw_data(data: &[f32]) -> Result<Self, String> {
+    pub fn from_raw_data(data: &[f32]) -> Result<Self, PostFlopError> {
         if data.len() != 52 * 51 / 2 {
-            return Err(format!("Expected exactly {} elements", 52 * 51 / 2));
+            return Err(PostFlopError::InvalidArgument(format!(
+                "Expected exactly {} elements",
+                52 * 51 / 2
+            )));
         }
 
         for &weight in data {
-            check_weight(weight)?;
+            check_weight(weight).map_err(PostFlopError::InvalidArgument)?;
         }
 
         Ok(Self {
@@ -448,14 +546,21 @@ impl Range {
 
     /// Attempts to create a range from a list of hands with their weights.
     #[inline]
-    pub fn from_hands_weights(hands: &[(Card, Card)], weights: &[f32]) -> Result<Self, String> {
+    pub fn from_hands_weights(
+        hands: &[(Card, Card)],
+        weights: &[f32],
+    ) -> Result<Self, PostFlopError> {
         let mut range = Self::default();
         for (&(card1, card2), &weight) in hands.iter().zip(weights.iter()) {
-            check_card(card1)?;
-            check_card(card2)?;
-            check_weight(weight)?;
+            for card in [card1, card2] {
+                check_card(card).map_err(|reason| invalid_card(&card.to_string(), reason))?;
+            }
+            check_weight(weight).map_err(PostFlopError::InvalidArgument)?;
             if card1 == card2 {
-                return Err("Hand must consist of two different cards".to_string());
+                return Err(PostFlopError::InvalidHand {
+                    input: format!("{card1}{card2}"),
+                    reason: "Hand must consist of two different cards".to_string(),
+                });
             }
             range.set_weight_by_cards(card1, card2, weight);
         }
@@ -492,7 +597,7 @@ impl Range {
     /// "Sanitized" means that the range string does not contain any invalid patterns and whitespace
     /// characters. Therefore, this method can bypass the regular expression processing. If you want
     /// to create a range from a regular string, use `parse::<Range>()` instead.
-    pub fn from_sanitized_str(ranges: &str) -> Result<Self, String> {
+    pub fn from_sanitized_str(ranges: &str) -> Result<Self, PostFlopError> {
         let mut ranges = ranges.split(',').collect::<Vec<_>>();
 
         // remove last empty element if any
@@ -502,26 +607,34 @@ impl Range {
 
         let mut result = Self::new();
 
-        for range in ranges.into_iter().rev() {
-            let mut split = range.split(':');
-            let range = split.next().unwrap();
+        for (position, input) in ranges.into_iter().enumerate().rev() {
+            let mut update = || {
+                let mut split = input.split(':');
+                let range = split.next().unwrap();
 
-            let weight = split
-                .next()
-                .map_or(Ok(1.0), |s| s.parse::<f32>().map_err(|e| e.to_string()))?;
-            check_weight(weight)?;
+                let weight = split
+                    .next()
+                    .map_or(Ok(1.0), |s| s.parse::<f32>().map_err(|e| e.to_string()))?;
+                check_weight(weight)?;
 
-            if split.next().is_some() {
-                return Err(format!("Invalid range: {range}"));
-            }
+                if split.next().is_some() {
+                    return Err(format!("Invalid range: {range}"));
+                }
 
-            if range.contains('-') {
-                result.update_with_dash_range(range, weight)?;
-            } else if range.contains('+') {
-                result.update_with_plus_range(range, weight)?;
-            } else {
-                result.update_with_singleton(range, weight)?;
-            }
+                if range.contains('-') {
+                    result.update_with_dash_range(range, weight)
+                } else if range.contains('+') {
+                    result.update_with_plus_range(range, weight)
+                } else {
+                    result.update_with_singleton(range, weight)
+                }
+            };
+
+            update().map_err(|reason| PostFlopError::InvalidRange {
+                input: input.to_string(),
+                position,
+                reason,
+            })?;
         }
 
         Ok(result)
@@ -953,7 +1066,7 @@ impl Range {
 }
 
 impl FromStr for Range {
-    type Err = String;
+    type Err = PostFlopError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let s = TRIM_REGEX.replace_all(s, "$1").trim().to_string();
@@ -966,38 +1079,46 @@ impl FromStr for Range {
 
         let mut result = Self::new();
 
-        for range in ranges.into_iter().rev() {
-            let caps = RANGE_REGEX
-                .captures(range)
-                .ok_or_else(|| format!("Failed to parse range: {range}"))?;
-
-            let range = caps.name("range").unwrap().as_str();
-            let weight = caps
-                .name("weight")
-                .map_or(1.0, |s| s.as_str().parse().unwrap());
-            check_weight(weight)?;
-
-            if range.contains('-') {
-                result.update_with_dash_range(range, weight)?;
-            } else if range.contains('+') {
-                result.update_with_plus_range(range, weight)?;
-            } else {
-                result.update_with_singleton(range, weight)?;
-            }
+        for (position, input) in ranges.into_iter().enumerate().rev() {
+            let mut update = || {
+                let caps = RANGE_REGEX
+                    .captures(input)
+                    .ok_or_else(|| format!("Failed to parse range: {input}"))?;
+
+                let range = caps.name("range").unwrap().as_str();
+                let weight = caps
+                    .name("weight")
+                    .map_or(1.0, |s| s.as_str().parse().unwrap());
+                check_weight(weight)?;
+
+                if range.contains('-') {
+                    result.update_with_dash_range(range, weight)
+                } else if range.contains('+') {
+                    result.update_with_plus_range(range, weight)
+                } else {
+                    result.update_with_singleton(range, weight)
+                }
+            };
+
+            update().map_err(|reason| PostFlopError::InvalidRange {
+                input: input.to_string(),
+                position,
+                reason,
+            })?;
         }
 
         Ok(result)
     }
 }
 
-impl ToString for Range {
+impl fmt::Display for Range {
     #[inline]
-    fn to_string(&self) -> String {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut result = Vec::new();
         self.pairs_strings(&mut result);
         self.nonpairs_strings(&mut result);
         self.suit_specified_strings(&mut result);
-        result.join(",")
+        f.write_str(&result.join(","))
     }
 }
 
@@ -1163,4 +1284,29 @@ mod tests {
             assert_eq!(range.unwrap().to_string(), expected);
         }
     }
+
+    #[test]
+    fn holes_from_str_roundtrip() {
+        for (s, expected) in [("AhKd", "AhKd"), ("KdAh", "AhKd"), ("7c7s", "7s7c")] {
+            let holes = holes_from_str(s).unwrap();
+            assert_eq!(holes.len(), 1);
+            assert_eq!(hole_to_string(holes[0]).unwrap(), expected);
+        }
+
+        let suited = holes_from_str("T9s").unwrap();
+        assert_eq!(
+            holes_to_strings(&suited).unwrap(),
+            ["Tc9c", "Td9d", "Th9h", "Ts9s"]
+        );
+        assert!(suited.windows(2).all(|w| w[0] < w[1]));
+
+        let all = holes_from_str("T9").unwrap();
+        assert_eq!(all.len(), 16);
+        assert!(all.iter().all(|&(c1, c2)| c1 < c2));
+
+        assert!(holes_from_str("AhAh").is_err());
+        assert!(holes_from_str("77s").is_err());
+        assert!(holes_from_str("9T").is_err());
+        assert!(holes_from_str("AKQ2").is_err());
+    }
 }
@@ -0,0 +1,392 @@
+//! HTTP query server of saved games.
+
+use crate::card::*;
+use crate::error::*;
+use crate::file::*;
+use crate::game::*;
+use crate::interface::*;
+use crate::range::*;
+use axum::extract::{Path, Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use parking_lot::{Mutex, RwLock};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+/// Embeddable HTTP server answering node queries of saved games.
+///
+/// The server holds solved games under names and answers the following requests with JSON
+/// (`Content-Type: application/json`, `Access-Control-Allow-Origin: *`):
+///
+/// - `GET /games`: the names of the games, as `{"games": ["name", ...]}`.
+/// - `GET /games/{name}`: the board cards, the starting pot, the effective stack, and the private
+///   hands of both players.
+/// - `GET /games/{name}/node?line=X%20B50`: the node reached by the line (see
+///   [`PostFlopGame::apply_line_str`]), in the format of [`PostFlopGame::export_node_json`].
+///   The optional parameters are `precision` (default: `4`), `ev` (default: `true`), and
+///   `equity` (default: `false`).
+///
+/// Errors are returned as `{"error": "message"}` with the status code 400 (invalid parameter or
+/// line) or 404 (unknown game).
+///
+/// Games can be added and removed while the server is running. Queries to the same game are
+/// serialized, while queries to different games are processed in parallel.
+///
+/// # Examples
+/// ```no_run
+/// use postflop_solver_ffi::*;
+/// use std::sync::Arc;
+///
+/// # async fn run() -> std::io::Result<()> {
+/// let server = Arc::new(QueryServer::new());
+/// server.load_game("srp_btn_bb", "srp_btn_bb.bin").unwrap();
+///
+/// let listener = tokio::net::TcpListener::bind("127.0.0.1:8080").await?;
+/// server.serve(listener).await
+/// # }
+/// ```
+#[derive(Default)]
+pub struct QueryServer {
+    games: RwLock<BTreeMap<String, Arc<Mutex<PostFlopGame>>>>,
+}
+
+/// Error of a request, converted into a JSON response.
+#[derive(Debug, Clone, PartialEq)]
+enum RequestError {
+    BadRequest(String),
+    NotFound(String),
+}
+
+impl IntoResponse for RequestError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            Self::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
+            Self::NotFound(message) => (StatusCode::NOT_FOUND, message),
+        };
+        json_response(status, format!("{{\"error\":{}}}", json_string(&message)))
+    }
+}
+
+impl QueryServer {
+    /// Creates a server without games.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a solved game under the given name, replacing the game of the same name if any.
+    ///
+    /// Returns an error if the game is not solved.
+    pub fn insert_game(&self, name: &str, game: PostFlopGame) -> Result<(), PostFlopError> {
+        if !game.is_solved() {
+            return Err(PostFlopError::InvalidState(format!(
+                "Game is not solved: {name}"
+            )));
+        }
+
+        let game = Arc::new(Mutex::new(game));
+        self.games.write().insert(name.to_string(), game);
+        Ok(())
+    }
+
+    /// Loads a game saved by [`save_data_to_file`] and adds it under the given name.
+    ///
+    /// Returns an error if loading fails or the game is not solved.
+    pub fn load_game(&self, name: &str, path: &str) -> Result<(), PostFlopError> {
+        let (game, _) = load_data_from_file(path, None)?;
+        self.insert_game(name, game)
+    }
+
+    /// Removes the game of the given name, and returns whether it existed.
+    ///
+    /// Queries already being processed are completed with the removed game.
+    #[inline]
+    pub fn remove_game(&self, name: &str) -> bool {
+        self.games.write().remove(name).is_some()
+    }
+
+    /// Returns the names of the games in ascending order.
+    #[inline]
+    pub fn game_names(&self) -> Vec<String> {
+        self.games.read().keys().cloned().collect()
+    }
+
+    /// Returns the [`Router`] of the server, which can be nested into another application.
+    pub fn router(self: Arc<Self>) -> Router {
+        Router::new()
+            .route("/games", get(list_games))
+            .route("/games/{name}", get(game_info))
+            .route("/games/{name}/node", get(node))
+            .with_state(self)
+    }
+
+    /// Serves the requests accepted by the given listener until an I/O error occurs.
+    pub async fn serve(self: Arc<Self>, listener: TcpListener) -> std::io::Result<()> {
+        axum::serve(listener, self.router()).await
+    }
+
+    /// Returns the game of the given name.
+    fn game(&self, name: &str) -> Result<Arc<Mutex<PostFlopGame>>, RequestError> {
+        let games = self.games.read();
+        let game = games.get(name).cloned();
+        game.ok_or_else(|| RequestError::NotFound(format!("Game not found: {name}")))
+    }
+
+    /// Returns the JSON of the game list.
+    fn list_json(&self) -> String {
+        let names = self.game_names();
+        let names = names.iter().map(|name| json_string(name));
+        format!("{{\"games\":[{}]}}", names.collect::<Vec<_>>().join(","))
+    }
+
+    /// Returns the JSON of the configuration of the game.
+    fn info_json(&self, name: &str) -> Result<String, RequestError> {
+        let game = self.game(name)?;
+        let game = game.lock();
+
+        let card_config = game.card_config();
+        let tree_config = game.tree_config();
+        let mut board = card_config.flop.iter().rev().copied().collect::<Vec<_>>();
+        board.extend([card_config.turn, card_config.river]);
+        board.retain(|&card| card != NOT_DEALT);
+
+        let board = board.iter().map(|&card| card_to_string(card).unwrap());
+        let board = board.map(|card| json_string(&card)).collect::<Vec<_>>();
+        let private_cards = [0, 1].map(|player| {
+            let hands = holes_to_strings(game.private_cards(player)).unwrap();
+            let hands = hands.iter().map(|hand| json_string(hand));
+            format!("[{}]", hands.collect::<Vec<_>>().join(","))
+        });
+
+        Ok(format!(
+            "{{\"name\":{},\"board\":[{}],\"starting_pot\":{},\"effective_stack\":{},\
+             \"private_cards\":[{},{}]}}",
+            json_string(name),
+            board.join(","),
+            tree_config.starting_pot,
+            tree_config.effective_stack,
+            private_cards[0],
+            private_cards[1],
+        ))
+    }
+
+    /// Returns the JSON of the node reached by the line of the parameters.
+    fn node_json(
+        &self,
+        name: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<String, RequestError> {
+        let line = params.get("line").map_or("", String::as_str);
+        let options = ExportOptions {
+            precision: parse_param(params, "precision", 4)?,
+            expected_values: parse_param(params, "ev", true)?,
+            equity: parse_param(params, "equity", false)?,
+            ..Default::default()
+        };
+
+        let game = self.game(name)?;
+        let mut game = game.lock();
+        game.apply_line_str(line)
+            .map_err(|e| RequestError::BadRequest(e.to_string()))?;
+
+        let json = game.export_node_json(&options);
+        game.back_to_root();
+        Ok(json)
+    }
+}
+
+async fn list_games(State(server): State<Arc<QueryServer>>) -> Response {
+    json_response(StatusCode::OK, server.list_json())
+}
+
+async fn game_info(State(server): State<Arc<QueryServer>>, Path(name): Path<String>) -> Response {
+    match server.info_json(&name) {
+        Ok(json) => json_response(StatusCode::OK, json),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn node(
+    State(server): State<Arc<QueryServer>>,
+    Path(name): Path<String>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Response {
+    // computing the equity and the expected values may take a while
+    let result = tokio::task::spawn_blocking(move || server.node_json(&name, &params)).await;
+    match result {
+        Ok(Ok(json)) => json_response(StatusCode::OK, json),
+        Ok(Err(e)) => e.into_response(),
+        Err(e) => {
+            let message = json_string(&format!("Query failed: {e}"));
+            let json = format!("{{\"error\":{message}}}");
+            json_response(StatusCode::INTERNAL_SERVER_ERROR, json)
+        }
+    }
+}
+
+/// Returns a JSON response allowing cross-origin requests.
+fn json_response(status: StatusCode, json: String) -> Response {
+    let headers = [
+        (header::CONTENT_TYPE, "application/json"),
+        (header::ACCESS_CONTROL_ALLOW_ORIGIN, "*"),
+    ];
+    (status, headers, json).into_response()
+}
+
+/// Parses the parameter of the given key, or returns `default` if it is not specified.
+fn parse_param<T: std::str::FromStr>(
+    params: &HashMap<String, String>,
+    key: &str,
+    default: T,
+) -> Result<T, RequestError> {
+    match params.get(key) {
+        None => Ok(default),
+        Some(value) => value
+            .parse()
+            .map_err(|_| RequestError::BadRequest(format!("Invalid parameter: {key}={value}"))),
+    }
+}
+
+/// Returns a JSON string literal of the given string.
+fn json_string(s: &str) -> String {
+    let mut ret = String::with_capacity(s.len() + 2);
+    ret.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => ret.push_str("\\\""),
+            '\\' => ret.push_str("\\\\"),
+            '\n' => ret.push_str("\\n"),
+            '\r' => ret.push_str("\\r"),
+            '\t' => ret.push_str("\\t"),
+            c if (c as u32) < 0x20 => ret.push_str(&format!("\\u{:04x}", c as u32)),
+            c => ret.push(c),
+        }
+    }
+    ret.push('"');
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::*;
+    use std::io::{Read, Write};
+
+    fn solved_game() -> PostFlopGame {
+        let mut game = PostFlopGame::builder()
+            .oop_range("AA,KK,QQ")
+            .ip_range("QQ-JJ,AQs")
+            .board("Td9d6hQc2s")
+            .pot(100)
+            .stack(100)
+            .bet_sizes("50%", "")
+            .build()
+            .unwrap();
+        game.allocate_memory(false, None).unwrap();
+        solve(&mut game, 20, 0.0, false);
+        game
+    }
+
+    fn params(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        let pairs = pairs.iter().map(|&(k, v)| (k.to_string(), v.to_string()));
+        pairs.collect()
+    }
+
+    #[test]
+    fn queries() {
+        let server = QueryServer::new();
+        assert_eq!(server.list_json(), "{\"games\":[]}");
+
+        let unsolved = PostFlopGame::builder()
+            .oop_range("AA")
+            .ip_range("KK")
+            .board("Td9d6h")
+            .pot(100)
+            .stack(100)
+            .build()
+            .unwrap();
+        assert!(server.insert_game("unsolved", unsolved).is_err());
+
+        server.insert_game("river \"srp\"", solved_game()).unwrap();
+        assert_eq!(server.list_json(), "{\"games\":[\"river \\\"srp\\\"\"]}");
+
+        let info = server.info_json("river \"srp\"").unwrap();
+        assert!(info.contains("\"board\":[\"Td\",\"9d\",\"6h\",\"Qc\",\"2s\"]"));
+        assert!(info.contains("\"starting_pot\":100"));
+
+        let name = "river \"srp\"";
+        let root = server.node_json(name, &params(&[])).unwrap();
+        assert!(root.starts_with("{\"line\":[],"));
+        assert!(root.contains("\"expected_values\""));
+        assert!(!root.contains("\"equity\""));
+
+        let json = server
+            .node_json(
+                name,
+                &params(&[("line", "X"), ("ev", "false"), ("equity", "true")]),
+            )
+            .unwrap();
+        assert!(json.starts_with("{\"line\":[\"X\"],"));
+        assert!(json.contains("\"player\":1"));
+        assert!(!json.contains("\"expected_values\""));
+        assert!(json.contains("\"equity\""));
+
+        let terminal = server.node_json(name, &params(&[("line", "X X")])).unwrap();
+        assert!(terminal.contains("\"player\":null"));
+
+        let error = |name: &str, pairs: &[(&str, &str)]| server.node_json(name, &params(pairs));
+        assert!(matches!(error("flop", &[]), Err(RequestError::NotFound(_))));
+        assert!(matches!(
+            error(name, &[("line", "X R1000")]),
+            Err(RequestError::BadRequest(_))
+        ));
+        assert!(matches!(
+            error(name, &[("precision", "high")]),
+            Err(RequestError::BadRequest(_))
+        ));
+
+        assert!(server.remove_game(name));
+        assert!(!server.remove_game(name));
+    }
+
+    #[test]
+    fn http() {
+        let server = Arc::new(QueryServer::new());
+        server.insert_game("river", solved_game()).unwrap();
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let listener = runtime.block_on(TcpListener::bind("127.0.0.1:0")).unwrap();
+        let addr = listener.local_addr().unwrap();
+        runtime.spawn(server.serve(listener));
+
+        let request = |path: &str| {
+            let mut stream = std::net::TcpStream::connect(addr).unwrap();
+            let request =
+                format!("GET {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n");
+            stream.write_all(request.as_bytes()).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+
+        let response = request("/games");
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("content-type: application/json"));
+        assert!(response.contains("access-control-allow-origin: *"));
+        assert!(response.ends_with("{\"games\":[\"river\"]}"));
+
+        let response = request("/games/river/node?line=X%20B50&equity=true");
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("{\"line\":[\"X\",\"B50\"],"));
+
+        let response = request("/games/flop/node");
+        assert!(response.starts_with("HTTP/1.1 404 Not Found"));
+        assert!(response.ends_with("{\"error\":\"Game not found: flop\"}"));
+
+        let response = request("/games/river/node?line=B1000");
+        assert!(response.starts_with("HTTP/1.1 400 Bad Request"));
+    }
+}
@@ -0,0 +1,453 @@
+use std::mem::MaybeUninit;
+use std::sync::OnceLock;
+
+/// Instruction set used by the explicit SIMD code paths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Level {
+    Scalar,
+    #[cfg(target_arch = "x86_64")]
+    Avx512,
+    #[cfg(target_arch = "aarch64")]
+    Neon,
+}
+
+/// Returns the best instruction set available at runtime.
+#[inline]
+fn level() -> Level {
+    static LEVEL: OnceLock<Level> = OnceLock::new();
+
+    *LEVEL.get_or_init(|| {
+        #[cfg(target_arch = "x86_64")]
+        if std::is_x86_feature_detected!("avx512f") {
+            return Level::Avx512;
+        }
+
+        #[cfg(target_arch = "aarch64")]
+        if std::arch::is_aarch64_feature_detected!("neon") {
+            return Level::Neon;
+        }
+
+        Level::Scalar
+    })
+}
+
+/// Number of the `f64` accumulators of the inner products.
+const LANES: usize = 8;
+
+/// Returns the factor of the `i`-th element of a conditional inner product.
+#[inline(always)]
+fn select(c: u16, threshold: u16, less: f32, greater: f32, equal: f32) -> f32 {
+    #[allow(clippy::comparison_chain)]
+    if c < threshold {
+        less
+    } else if c > threshold {
+        greater
+    } else {
+        equal
+    }
+}
+
+/// Adds the remaining products to the first accumulator and sums up the accumulators, in the same
+/// order as the scalar code.
+#[inline(always)]
+fn reduce(mut acc: [f64; LANES], tail: impl Iterator<Item = f32>) -> f32 {
+    tail.for_each(|x| acc[0] += x as f64);
+    acc.iter().sum::<f64>() as f32
+}
+
+/// Defines the kernels of the given instruction set.
+///
+/// The kernels perform the same floating point operations in the same order as the scalar code,
+/// so the results are bit-identical.
+macro_rules! kernels {
+    (
+        $feature:literal, $width:literal, $load:ident, $store:ident,
+        $add:ident, $sub:ident, $mul:ident
+    ) => {
+        kernels!(@assign $feature, $width, $load, $store, add_assign, $add, +);
+        kernels!(@assign $feature, $width, $load, $store, sub_assign, $sub, -);
+        kernels!(@assign $feature, $width, $load, $store, mul_assign, $mul, *);
+
+        /// `dst[i] += src1[i] * src2[i]`
+        #[target_feature(enable = $feature)]
+        pub(super) unsafe fn fma_assign(dst: &mut [f32], src1: &[f32], src2: &[f32]) {
+            let len = dst.len();
+            let len_chunk = len / $width * $width;
+            let (d, s1, s2) = (dst.as_mut_ptr(), src1.as_ptr(), src2.as_ptr());
+            for i in (0..len_chunk).step_by($width) {
+                let product = $mul($load(s1.add(i)), $load(s2.add(i)));
+                $store(d.add(i), $add($load(d.add(i)), product));
+            }
+            for i in len_chunk..len {
+                *d.add(i) += *s1.add(i) * *s2.add(i);
+            }
+        }
+
+        /// `dst[i] = src1[i] * src2[i]`
+        #[target_feature(enable = $feature)]
+        pub(super) unsafe fn mul_uninit(
+            dst: &mut [MaybeUninit<f32>],
+            src1: &[f32],
+            src2: &[f32],
+        ) {
+            let len = dst.len();
+            let len_chunk = len / $width * $width;
+            let (d, s1, s2) = (dst.as_mut_ptr() as *mut f32, src1.as_ptr(), src2.as_ptr());
+            for i in (0..len_chunk).step_by($width) {
+                $store(d.add(i), $mul($load(s1.add(i)), $load(s2.add(i))));
+            }
+            for i in len_chunk..len {
+                *d.add(i) = *s1.add(i) * *s2.add(i);
+            }
+        }
+    };
+
+    (@assign $feature:literal, $width:literal, $load:ident, $store:ident,
+        $name:ident, $vop:ident, $op:tt) => {
+        /// `dst[i] = dst[i] (op) src[i]`
+        #[target_feature(enable = $feature)]
+        pub(super) unsafe fn $name(dst: &mut [f32], src: &[f32]) {
+            let len = dst.len();
+            let len_chunk = len / $width * $width;
+            let (d, s) = (dst.as_mut_ptr(), src.as_ptr());
+            for i in (0..len_chunk).step_by($width) {
+                $store(d.add(i), $vop($load(d.add(i)), $load(s.add(i))));
+            }
+            for i in len_chunk..len {
+                *d.add(i) = *d.add(i) $op *s.add(i);
+            }
+        }
+    };
+}
+
+#[cfg(target_arch = "x86_64")]
+mod avx512 {
+    use super::*;
+    use std::arch::x86_64::*;
+
+    kernels!(
+        "avx512f",
+        16,
+        _mm512_loadu_ps,
+        _mm512_storeu_ps,
+        _mm512_add_ps,
+        _mm512_sub_ps,
+        _mm512_mul_ps
+    );
+
+    /// `sum(src1[i] * src2[i])`
+    #[target_feature(enable = "avx512f")]
+    pub(super) unsafe fn inner_product(src1: &[f32], src2: &[f32]) -> f32 {
+        let len = src1.len();
+        let len_chunk = len / LANES * LANES;
+        let (s1, s2) = (src1.as_ptr(), src2.as_ptr());
+        let mut acc = _mm512_setzero_pd();
+        for i in (0..len_chunk).step_by(LANES) {
+            let product = _mm256_mul_ps(_mm256_loadu_ps(s1.add(i)), _mm256_loadu_ps(s2.add(i)));
+            acc = _mm512_add_pd(acc, _mm512_cvtps_pd(product));
+        }
+        let mut lanes = [0.0; LANES];
+        _mm512_storeu_pd(lanes.as_mut_ptr(), acc);
+        reduce(lanes, (len_chunk..len).map(|i| *s1.add(i) * *s2.add(i)))
+    }
+
+    /// `sum(src1[i] * src2[i] * select(cond[i]))`
+    #[target_feature(enable = "avx512f")]
+    pub(super) unsafe fn inner_product_cond(
+        src1: &[f32],
+        src2: &[f32],
+        cond: &[u16],
+        threshold: u16,
+        less: f32,
+        greater: f32,
+        equal: f32,
+    ) -> f32 {
+        let len = src1.len();
+        let len_chunk = len / LANES * LANES;
+        let (s1, s2, c) = (src1.as_ptr(), src2.as_ptr(), cond.as_ptr());
+        let threshold_vec = _mm256_set1_epi32(threshold as i32);
+        let less_vec = _mm256_set1_ps(less);
+        let greater_vec = _mm256_set1_ps(greater);
+        let equal_vec = _mm256_set1_ps(equal);
+        let mut acc = _mm512_setzero_pd();
+        for i in (0..len_chunk).step_by(LANES) {
+            let c_vec = _mm256_cvtepu16_epi32(_mm_loadu_si128(c.add(i) as *const __m128i));
+            let is_less = _mm256_castsi256_ps(_mm256_cmpgt_epi32(threshold_vec, c_vec));
+            let is_greater = _mm256_castsi256_ps(_mm256_cmpgt_epi32(c_vec, threshold_vec));
+            let factor = _mm256_blendv_ps(
+                _mm256_blendv_ps(equal_vec, greater_vec, is_greater),
+                less_vec,
+                is_less,
+            );
+            let product = _mm256_mul_ps(_mm256_loadu_ps(s1.add(i)), _mm256_loadu_ps(s2.add(i)));
+            let product = _mm256_mul_ps(product, factor);
+            acc = _mm512_add_pd(acc, _mm512_cvtps_pd(product));
+        }
+        let mut lanes = [0.0; LANES];
+        _mm512_storeu_pd(lanes.as_mut_ptr(), acc);
+        let tail = (len_chunk..len)
+            .map(|i| *s1.add(i) * *s2.add(i) * select(*c.add(i), threshold, less, greater, equal));
+        reduce(lanes, tail)
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod neon {
+    use super::*;
+    use std::arch::aarch64::*;
+
+    kernels!("neon", 4, vld1q_f32, vst1q_f32, vaddq_f32, vsubq_f32, vmulq_f32);
+
+    /// Adds the eight products to the accumulators, two lanes per register.
+    #[inline(always)]
+    unsafe fn accumulate(acc: &mut [float64x2_t; 4], low: float32x4_t, high: float32x4_t) {
+        acc[0] = vaddq_f64(acc[0], vcvt_f64_f32(vget_low_f32(low)));
+        acc[1] = vaddq_f64(acc[1], vcvt_high_f64_f32(low));
+        acc[2] = vaddq_f64(acc[2], vcvt_f64_f32(vget_low_f32(high)));
+        acc[3] = vaddq_f64(acc[3], vcvt_high_f64_f32(high));
+    }
+
+    /// Stores the accumulators to an array.
+    #[inline(always)]
+    unsafe fn lanes(acc: &[float64x2_t; 4]) -> [f64; LANES] {
+        let mut lanes = [0.0; LANES];
+        for (k, &a) in acc.iter().enumerate() {
+            vst1q_f64(lanes.as_mut_ptr().add(2 * k), a);
+        }
+        lanes
+    }
+
+    /// `sum(src1[i] * src2[i])`
+    #[target_feature(enable = "neon")]
+    pub(super) unsafe fn inner_product(src1: &[f32], src2: &[f32]) -> f32 {
+        let len = src1.len();
+        let len_chunk = len / LANES * LANES;
+        let (s1, s2) = (src1.as_ptr(), src2.as_ptr());
+        let mut acc = [vdupq_n_f64(0.0); 4];
+        for i in (0..len_chunk).step_by(LANES) {
+            let low = vmulq_f32(vld1q_f32(s1.add(i)), vld1q_f32(s2.add(i)));
+            let high = vmulq_f32(vld1q_f32(s1.add(i + 4)), vld1q_f32(s2.add(i + 4)));
+            accumulate(&mut acc, low, high);
+        }
+        reduce(
+            lanes(&acc),
+            (len_chunk..len).map(|i| *s1.add(i) * *s2.add(i)),
+        )
+    }
+
+    /// `sum(src1[i] * src2[i] * select(cond[i]))`
+    #[target_feature(enable = "neon")]
+    pub(super) unsafe fn inner_product_cond(
+        src1: &[f32],
+        src2: &[f32],
+        cond: &[u16],
+        threshold: u16,
+        less: f32,
+        greater: f32,
+        equal: f32,
+    ) -> f32 {
+        let len = src1.len();
+        let len_chunk = len / LANES * LANES;
+        let (s1, s2, c) = (src1.as_ptr(), src2.as_ptr(), cond.as_ptr());
+        let threshold_vec = vdupq_n_u16(threshold);
+        let less_vec = vdupq_n_f32(less);
+        let greater_vec = vdupq_n_f32(greater);
+        let equal_vec = vdupq_n_f32(equal);
+
+        // widens the 16-bit masks to 32 bits (all ones or all zeros)
+        let widen = |mask: uint16x8_t| {
+            let mask = vreinterpretq_s16_u16(mask);
+            (
+                vreinterpretq_u32_s32(vmovl_s16(vget_low_s16(mask))),
+                vreinterpretq_u32_s32(vmovl_high_s16(mask)),
+            )
+        };
+
+        let mut acc = [vdupq_n_f64(0.0); 4];
+        for i in (0..len_chunk).step_by(LANES) {
+            let c_vec = vld1q_u16(c.add(i));
+            let (is_less_low, is_less_high) = widen(vcltq_u16(c_vec, threshold_vec));
+            let (is_greater_low, is_greater_high) = widen(vcgtq_u16(c_vec, threshold_vec));
+            let factor_low = vbslq_f32(
+                is_less_low,
+                less_vec,
+                vbslq_f32(is_greater_low, greater_vec, equal_vec),
+            );
+            let factor_high = vbslq_f32(
+                is_less_high,
+                less_vec,
+                vbslq_f32(is_greater_high, greater_vec, equal_vec),
+            );
+            let low = vmulq_f32(vld1q_f32(s1.add(i)), vld1q_f32(s2.add(i)));
+            let high = vmulq_f32(vld1q_f32(s1.add(i + 4)), vld1q_f32(s2.add(i + 4)));
+            let low = vmulq_f32(low, factor_low);
+            let high = vmulq_f32(high, factor_high);
+            accumulate(&mut acc, low, high);
+        }
+        let tail = (len_chunk..len)
+            .map(|i| *s1.add(i) * *s2.add(i) * select(*c.add(i), threshold, less, greater, equal));
+        reduce(lanes(&acc), tail)
+    }
+}
+
+/// Dispatches the kernel to the best instruction set, and returns `false` (or `None` for the
+/// `@value` form) if no explicit SIMD code path is available.
+macro_rules! dispatch {
+    (@value $name:ident($($arg:expr),*)) => {
+        match level() {
+            #[cfg(target_arch = "x86_64")]
+            Level::Avx512 => Some(unsafe { avx512::$name($($arg),*) }),
+            #[cfg(target_arch = "aarch64")]
+            Level::Neon => Some(unsafe { neon::$name($($arg),*) }),
+            Level::Scalar => None,
+        }
+    };
+
+    ($name:ident($($arg:expr),*)) => {
+        dispatch!(@value $name($($arg),*)).is_some()
+    };
+}
+
+/// Computes `dst[i] += src[i]`. Returns `false` if no explicit SIMD code path is available.
+#[inline]
+pub(crate) fn add_assign(dst: &mut [f32], src: &[f32]) -> bool {
+    let len = dst.len().min(src.len());
+    dispatch!(add_assign(&mut dst[..len], &src[..len]))
+}
+
+/// Computes `dst[i] -= src[i]`. Returns `false` if no explicit SIMD code path is available.
+#[inline]
+pub(crate) fn sub_assign(dst: &mut [f32], src: &[f32]) -> bool {
+    let len = dst.len().min(src.len());
+    dispatch!(sub_assign(&mut dst[..len], &src[..len]))
+}
+
+/// Computes `dst[i] *= src[i]`. Returns `false` if no explicit SIMD code path is available.
+#[inline]
+pub(crate) fn mul_assign(dst: &mut [f32], src: &[f32]) -> bool {
+    let len = dst.len().min(src.len());
+    dispatch!(mul_assign(&mut dst[..len], &src[..len]))
+}
+
+/// Computes `dst[i] += src1[i] * src2[i]`. Returns `false` if no explicit SIMD code path is
+/// available.
+#[inline]
+pub(crate) fn fma_assign(dst: &mut [f32], src1: &[f32], src2: &[f32]) -> bool {
+    let len = dst.len().min(src1.len()).min(src2.len());
+    dispatch!(fma_assign(&mut dst[..len], &src1[..len], &src2[..len]))
+}
+
+/// Computes `dst[i] = src1[i] * src2[i]`. Returns `false` if no explicit SIMD code path is
+/// available, in which case `dst` is left uninitialized.
+#[inline]
+pub(crate) fn mul_uninit(dst: &mut [MaybeUninit<f32>], src1: &[f32], src2: &[f32]) -> bool {
+    let len = dst.len();
+    assert!(src1.len() >= len && src2.len() >= len);
+    dispatch!(mul_uninit(dst, &src1[..len], &src2[..len]))
+}
+
+/// Computes `sum(src1[i] * src2[i])` with the products accumulated in the same order as the
+/// scalar code. Returns `None` if no explicit SIMD code path is available.
+#[inline]
+pub(crate) fn inner_product(src1: &[f32], src2: &[f32]) -> Option<f32> {
+    let len = src1.len().min(src2.len());
+    dispatch!(@value inner_product(&src1[..len], &src2[..len]))
+}
+
+/// Computes `sum(src1[i] * src2[i] * z)`, where `z` is `less`, `greater`, or `equal` depending on
+/// the comparison of `cond[i]` with `threshold`, with the products accumulated in the same order
+/// as the scalar code. Returns `None` if no explicit SIMD code path is available.
+#[inline]
+pub(crate) fn inner_product_cond(
+    src1: &[f32],
+    src2: &[f32],
+    cond: &[u16],
+    threshold: u16,
+    less: f32,
+    greater: f32,
+    equal: f32,
+) -> Option<f32> {
+    let len = src1.len().min(src2.len()).min(cond.len());
+    let (src1, src2, cond) = (&src1[..len], &src2[..len], &cond[..len]);
+    dispatch!(@value inner_product_cond(src1, src2, cond, threshold, less, greater, equal))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Kernel = fn(&mut [f32], &[f32]) -> bool;
+
+    fn sequence(len: usize, seed: f32) -> Vec<f32> {
+        (0..len).map(|i| (i as f32 * seed).sin() * 10.0).collect()
+    }
+
+    #[test]
+    fn kernels_match_scalar() {
+        for len in [0, 1, 3, 4, 15, 16, 17, 33, 100] {
+            let lhs = sequence(len, 0.7);
+            let rhs = sequence(len, 1.3);
+            let third = sequence(len, 2.1);
+
+            let check = |kernel: Kernel, scalar: fn(f32, f32) -> f32| {
+                let mut dst = lhs.clone();
+                if kernel(&mut dst, &rhs) {
+                    let expected = lhs.iter().zip(&rhs).map(|(&x, &y)| scalar(x, y));
+                    assert!(dst.iter().copied().eq(expected));
+                }
+            };
+
+            check(add_assign, |x, y| x + y);
+            check(sub_assign, |x, y| x - y);
+            check(mul_assign, |x, y| x * y);
+
+            let mut dst = lhs.clone();
+            if fma_assign(&mut dst, &rhs, &third) {
+                let expected = (0..len).map(|i| lhs[i] + rhs[i] * third[i]);
+                assert!(dst.iter().copied().eq(expected));
+            }
+
+            let mut dst = vec![MaybeUninit::uninit(); len];
+            if mul_uninit(&mut dst, &rhs, &third) {
+                let dst = dst.iter().map(|x| unsafe { x.assume_init() });
+                assert!(dst.eq((0..len).map(|i| rhs[i] * third[i])));
+            }
+        }
+    }
+
+    #[test]
+    fn inner_products_match_scalar() {
+        // same accumulation order as `sliceop::inner_product`
+        let scalar = |products: Vec<f32>| {
+            let len_chunk = products.len() / LANES * LANES;
+            let mut acc = [0.0; LANES];
+            for (i, &x) in products[..len_chunk].iter().enumerate() {
+                acc[i % LANES] += x as f64;
+            }
+            reduce(acc, products[len_chunk..].iter().copied())
+        };
+
+        for len in [0, 1, 7, 8, 9, 16, 31, 100, 1326] {
+            let lhs = sequence(len, 0.7);
+            let rhs = sequence(len, 1.3);
+            let cond = (0..len).map(|i| (i * 7 % 11) as u16).collect::<Vec<_>>();
+
+            if let Some(result) = inner_product(&lhs, &rhs) {
+                let expected = scalar((0..len).map(|i| lhs[i] * rhs[i]).collect());
+                assert_eq!(result.to_bits(), expected.to_bits());
+            }
+
+            for threshold in [0, 5, 11] {
+                let (less, greater, equal) = (1.5, -2.5, 0.25);
+                let result = inner_product_cond(&lhs, &rhs, &cond, threshold, less, greater, equal);
+                if let Some(result) = result {
+                    let products = (0..len).map(|i| {
+                        lhs[i] * rhs[i] * select(cond[i], threshold, less, greater, equal)
+                    });
+                    let expected = scalar(products.collect());
+                    assert_eq!(result.to_bits(), expected.to_bits());
+                }
+            }
+        }
+    }
+}
@@ -1,13 +1,26 @@
 use crate::utility::*;
 use std::mem::MaybeUninit;
 
+#[cfg(feature = "simd")]
+use crate::simd;
+
 #[inline]
 pub(crate) fn sub_slice(lhs: &mut [f32], rhs: &[f32]) {
+    #[cfg(feature = "simd")]
+    if simd::sub_assign(lhs, rhs) {
+        return;
+    }
+
     lhs.iter_mut().zip(rhs).for_each(|(l, r)| *l -= *r);
 }
 
 #[inline]
 pub(crate) fn mul_slice(lhs: &mut [f32], rhs: &[f32]) {
+    #[cfg(feature = "simd")]
+    if simd::mul_assign(lhs, rhs) {
+        return;
+    }
+
     lhs.iter_mut().zip(rhs).for_each(|(l, r)| *l *= *r);
 }
 
@@ -47,6 +60,11 @@ pub(crate) fn sum_slices_uninit<'a>(dst: &'a mut [MaybeUninit<f32>], src: &[f32]
     });
     let dst = unsafe { &mut *(dst as *mut _ as *mut [f32]) };
     src[len..].chunks_exact(len).for_each(|s| {
+        #[cfg(feature = "simd")]
+        if simd::add_assign(dst, s) {
+            return;
+        }
+
         dst.iter_mut().zip(s).for_each(|(d, s)| {
             *d += *s;
         });
@@ -79,16 +97,30 @@ pub(crate) fn fma_slices_uninit<'a>(
     src2: &[f32],
 ) -> &'a mut [f32] {
     let len = dst.len();
-    dst.iter_mut()
-        .zip(src1.iter().zip(src2))
-        .for_each(|(d, (s1, s2))| {
-            d.write(*s1 * *s2);
-        });
+
+    #[cfg(feature = "simd")]
+    let is_written = simd::mul_uninit(dst, src1, src2);
+    #[cfg(not(feature = "simd"))]
+    let is_written = false;
+
+    if !is_written {
+        dst.iter_mut()
+            .zip(src1.iter().zip(src2))
+            .for_each(|(d, (s1, s2))| {
+                d.write(*s1 * *s2);
+            });
+    }
+
     let dst = unsafe { &mut *(dst as *mut _ as *mut [f32]) };
     src1[len..]
         .chunks_exact(len)
         .zip(src2[len..].chunks_exact(len))
         .for_each(|(s1, s2)| {
+            #[cfg(feature = "simd")]
+            if simd::fma_assign(dst, s1, s2) {
+                return;
+            }
+
             dst.iter_mut()
                 .zip(s1.iter().zip(s2))
                 .for_each(|(d, (s1, s2))| {
@@ -149,6 +181,11 @@ pub(crate) fn max_fma_slices_uninit<'a>(
 
 #[inline]
 pub(crate) fn inner_product(src1: &[f32], src2: &[f32]) -> f32 {
+    #[cfg(feature = "simd")]
+    if let Some(result) = simd::inner_product(src1, src2) {
+        return result;
+    }
+
     const CHUNK_SIZE: usize = 8;
 
     let len = src1.len();
@@ -186,6 +223,13 @@ pub(crate) fn inner_product_cond(
     greater: f32,
     equal: f32,
 ) -> f32 {
+    #[cfg(feature = "simd")]
+    if let Some(result) =
+        simd::inner_product_cond(src1, src2, cond, threshold, less, greater, equal)
+    {
+        return result;
+    }
+
     const CHUNK_SIZE: usize = 8;
 
     let len = src1.len();
@@ -1,13 +1,21 @@
 use crate::interface::*;
 use crate::mutex_like::*;
+use crate::profile::*;
 use crate::sliceop::*;
 use crate::utility::*;
 use std::io::{self, Write};
 use std::mem::MaybeUninit;
+use std::time::{Duration, Instant};
 
 #[cfg(feature = "custom-alloc")]
 use crate::alloc::*;
 
+#[cfg(feature = "rayon")]
+use crate::error::*;
+
+#[cfg(not(feature = "custom-alloc"))]
+use crate::pool::*;
+
 struct DiscountParams {
     alpha_t: f32,
     beta_t: f32,
@@ -36,6 +44,80 @@ impl DiscountParams {
     }
 }
 
+/// Configuration of the river sampling, used by [`solve_with_river_sampling`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiverSampling {
+    /// Fraction of the river cards visited for each turn card in each iteration, in the range
+    /// `(0.0, 1.0]`. At least one river card is visited.
+    pub fraction: f32,
+
+    /// Seed of the sampling. The same seed always samples the same river cards.
+    pub seed: u64,
+
+    /// Number of iterations on the full tree performed after the sampled iterations.
+    pub num_refine_iterations: u32,
+}
+
+impl Default for RiverSampling {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            fraction: 0.25,
+            seed: 0,
+            num_refine_iterations: 0,
+        }
+    }
+}
+
+/// Parameters of one update of one player.
+struct IterationParams {
+    discount: DiscountParams,
+    sampling: Option<SamplingParams>,
+}
+
+/// River cards sampled in one iteration for one player.
+struct SamplingParams {
+    fraction: f32,
+    key: u64,
+}
+
+impl SamplingParams {
+    fn new(sampling: &RiverSampling, current_iteration: u32, player: usize) -> Self {
+        let seed = sampling.seed ^ ((current_iteration as u64) << 1 | player as u64);
+        Self {
+            fraction: sampling.fraction,
+            key: Rng::new(seed).next_u64(),
+        }
+    }
+
+    /// Returns the bit mask of the sampled children of a chance node dealing the river card after
+    /// the given turn card, and the number of the sampled children. Returns `None` if all children
+    /// are sampled.
+    fn sample(&self, turn: u8, num_actions: usize) -> Option<(u64, usize)> {
+        let num_samples = (num_actions as f32 * self.fraction).ceil() as usize;
+        let num_samples = num_samples.clamp(1, num_actions);
+        if num_samples == num_actions {
+            return None;
+        }
+
+        // partial Fisher-Yates shuffle
+        let mut rng = Rng::new(self.key ^ (turn as u64).wrapping_mul(0x9e3779b97f4a7c15));
+        let mut indices = [0u8; 64];
+        indices
+            .iter_mut()
+            .enumerate()
+            .for_each(|(i, x)| *x = i as u8);
+        let mut mask = 0;
+        for i in 0..num_samples {
+            let j = i + (rng.next_u64() % (num_actions - i) as u64) as usize;
+            indices.swap(i, j);
+            mask |= 1 << indices[i];
+        }
+
+        Some((mask, num_samples))
+    }
+}
+
 /// Performs Discounted CFR algorithm until the given number of iterations or exploitability is
 /// satisfied.
 ///
@@ -45,6 +127,112 @@ pub fn solve<T: Game>(
     max_num_iterations: u32,
     target_exploitability: f32,
     print_progress: bool,
+) -> f32 {
+    solve_printing_progress(
+        game,
+        max_num_iterations,
+        target_exploitability,
+        None,
+        print_progress,
+    )
+}
+
+/// Performs Discounted CFR algorithm visiting only a sampled subset of the river cards.
+///
+/// In each iteration, only `sampling.fraction` of the river cards are visited for each turn card,
+/// and their counterfactual values are weighted by the inverse of the fraction, so that each
+/// iteration is an unbiased estimate of the full iteration. This reduces the time per iteration
+/// of flop trees roughly by the fraction at the cost of noisier updates. After
+/// `max_num_iterations` sampled iterations (or when the target exploitability is reached),
+/// `sampling.num_refine_iterations` iterations on the full tree are performed to refine the
+/// strategy. The exploitability is always computed on the full tree.
+///
+/// Games without river chance nodes are solved as [`solve`]. Panics if `sampling.fraction` is not
+/// in the range `(0.0, 1.0]`.
+///
+/// This method returns the exploitability of the obtained strategy.
+pub fn solve_with_river_sampling<T: Game>(
+    game: &mut T,
+    sampling: &RiverSampling,
+    max_num_iterations: u32,
+    target_exploitability: f32,
+    print_progress: bool,
+) -> f32 {
+    if !(sampling.fraction > 0.0 && sampling.fraction <= 1.0) {
+        panic!("Invalid sampling fraction: {}", sampling.fraction);
+    }
+
+    solve_printing_progress(
+        game,
+        max_num_iterations,
+        target_exploitability,
+        Some(sampling),
+        print_progress,
+    )
+}
+
+/// Performs Discounted CFR algorithm, printing the progress if `print_progress` is `true`.
+fn solve_printing_progress<T: Game>(
+    game: &mut T,
+    max_num_iterations: u32,
+    target_exploitability: f32,
+    sampling: Option<&RiverSampling>,
+    print_progress: bool,
+) -> f32 {
+    let total = max_num_iterations + sampling.map_or(0, |s| s.num_refine_iterations);
+    let exploitability = solve_internal(
+        game,
+        max_num_iterations,
+        target_exploitability,
+        sampling,
+        |iteration, exploitability| {
+            if print_progress {
+                print!("\riteration: {iteration} / {total} ");
+                print!("(exploitability = {exploitability:.4e})");
+                io::stdout().flush().unwrap();
+            }
+            true
+        },
+    );
+
+    if print_progress {
+        println!();
+        io::stdout().flush().unwrap();
+    }
+
+    exploitability
+}
+
+/// Performs Discounted CFR algorithm, reporting the progress to `callback`.
+///
+/// `callback` is called with the number of performed iterations and the latest exploitability
+/// before the first iteration and after each iteration (the exploitability is computed every 10
+/// iterations). If `callback` returns `false`, the solving is stopped early, and the strategy
+/// obtained so far is finalized as usual.
+///
+/// This method returns the exploitability of the obtained strategy.
+pub fn solve_with_callback<T: Game>(
+    game: &mut T,
+    max_num_iterations: u32,
+    target_exploitability: f32,
+    callback: impl FnMut(u32, f32) -> bool,
+) -> f32 {
+    solve_internal(
+        game,
+        max_num_iterations,
+        target_exploitability,
+        None,
+        callback,
+    )
+}
+
+/// Performs Discounted CFR algorithm with the optional river sampling.
+fn solve_internal<T: Game>(
+    game: &mut T,
+    max_num_iterations: u32,
+    target_exploitability: f32,
+    sampling: Option<&RiverSampling>,
+    mut callback: impl FnMut(u32, f32) -> bool,
 ) -> f32 {
     if game.is_solved() {
         panic!("Game is already solved");
@@ -55,23 +243,31 @@ pub fn solve<T: Game>(
     }
 
     let mut root = game.root();
-    let mut exploitability = compute_exploitability(game);
+    let mut recorder = StatsRecorder::default();
+    let mut exploitability = recorder.measure_exploitability(|| compute_exploitability(game));
 
-    if print_progress {
-        print!("iteration: 0 / {max_num_iterations} ");
-        print!("(exploitability = {exploitability:.4e})");
-        io::stdout().flush().unwrap();
-    }
+    let mut is_stopped = !callback(0, exploitability);
+    let mut num_iterations = 0;
+
+    let num_sampled_iterations = sampling.map_or(0, |_| max_num_iterations);
+    let max_num_iterations = max_num_iterations + sampling.map_or(0, |s| s.num_refine_iterations);
 
     for t in 0..max_num_iterations {
-        if exploitability <= target_exploitability {
+        if is_stopped || exploitability <= target_exploitability {
             break;
         }
 
-        let params = DiscountParams::new(t);
+        let profiler = Profiler::default();
+        let timer = Timer::start();
 
         // alternating updates
         for player in 0..2 {
+            let params = IterationParams {
+                discount: DiscountParams::new(t),
+                sampling: sampling
+                    .filter(|_| t < num_sampled_iterations)
+                    .map(|sampling| SamplingParams::new(sampling, t, player)),
+            };
             let mut result = Vec::with_capacity(game.num_private_hands(player));
             solve_recursive(
                 result.spare_capacity_mut(),
@@ -80,30 +276,85 @@ pub fn solve<T: Game>(
                 player,
                 game.initial_weights(player ^ 1),
                 &params,
+                &profiler,
             );
         }
 
-        if (t + 1) % 10 == 0 || t + 1 == max_num_iterations {
-            exploitability = compute_exploitability(game);
-        }
+        recorder.record_iteration(timer, &profiler);
 
-        if print_progress {
-            print!("\riteration: {} / {} ", t + 1, max_num_iterations);
-            print!("(exploitability = {exploitability:.4e})");
-            io::stdout().flush().unwrap();
+        let is_exploitability_updated = (t + 1) % 10 == 0 || t + 1 == max_num_iterations;
+        if is_exploitability_updated {
+            exploitability = recorder.measure_exploitability(|| compute_exploitability(game));
         }
-    }
 
-    if print_progress {
-        println!();
-        io::stdout().flush().unwrap();
+        num_iterations = t + 1;
+        is_stopped = !callback(t + 1, exploitability);
+
+        // the reported exploitability may be outdated
+        if is_stopped && !is_exploitability_updated {
+            exploitability = recorder.measure_exploitability(|| compute_exploitability(game));
+        }
     }
 
     finalize(game);
 
+    game.set_solve_summary(SolveSummary {
+        num_iterations,
+        exploitability,
+    });
+    recorder.finish(game);
+
     exploitability
 }
 
+/// Performs Discounted CFR algorithm in the given thread pool.
+///
+/// This is the same as [`solve`] except that the parallel computation runs in `pool` instead of
+/// the global thread pool, so that concurrent solves do not oversubscribe the cores.
+#[cfg(feature = "rayon")]
+#[inline]
+pub fn solve_in_pool<T: Game>(
+    pool: &rayon::ThreadPool,
+    game: &mut T,
+    max_num_iterations: u32,
+    target_exploitability: f32,
+    print_progress: bool,
+) -> f32 {
+    pool.install(|| {
+        solve(
+            game,
+            max_num_iterations,
+            target_exploitability,
+            print_progress,
+        )
+    })
+}
+
+/// Performs Discounted CFR algorithm with a dedicated thread pool of `num_threads` threads.
+///
+/// Returns an error if the thread pool cannot be created. See [`solve_in_pool`] for details.
+#[cfg(feature = "rayon")]
+pub fn solve_with_num_threads<T: Game>(
+    game: &mut T,
+    num_threads: usize,
+    max_num_iterations: u32,
+    target_exploitability: f32,
+    print_progress: bool,
+) -> Result<f32, PostFlopError> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .map_err(|e| PostFlopError::Other(format!("Failed to create thread pool: {e}")))?;
+
+    Ok(solve_in_pool(
+        &pool,
+        game,
+        max_num_iterations,
+        target_exploitability,
+        print_progress,
+    ))
+}
+
 /// Proceeds Discounted CFR algorithm for one iteration.
 #[inline]
 pub fn solve_step<T: Game>(game: &T, current_iteration: u32) {
@@ -116,7 +367,11 @@ pub fn solve_step<T: Game>(game: &T, current_iteration: u32) {
     }
 
     let mut root = game.root();
-    let params = DiscountParams::new(current_iteration);
+    let params = IterationParams {
+        discount: DiscountParams::new(current_iteration),
+        sampling: None,
+    };
+    let profiler = Profiler::default();
 
     // alternating updates
     for player in 0..2 {
@@ -128,10 +383,138 @@ pub fn solve_step<T: Game>(game: &T, current_iteration: u32) {
             player,
             game.initial_weights(player ^ 1),
             &params,
+            &profiler,
         );
     }
 }
 
+/// Number of calibration iterations performed by [`estimate_solve_time`], and the iterations after
+/// which the exploitability is sampled. The cumulative strategy is reset after 4 and 16
+/// iterations, so the samples are taken within one segment.
+const CALIBRATION_ITERATIONS: u32 = 16;
+const CALIBRATION_SAMPLES: [u32; 3] = [8, 12, 16];
+
+/// Estimated cost of a solve, returned by [`estimate_solve_time`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SolveTimeEstimate {
+    /// Number of calibration iterations performed on the game.
+    pub num_calibration_iterations: u32,
+
+    /// Exploitability after the calibration iterations.
+    pub exploitability: f32,
+
+    /// Average wall-clock time of one iteration.
+    pub time_per_iteration: Duration,
+
+    /// Wall-clock time of one exploitability computation.
+    pub exploitability_time: Duration,
+
+    /// Estimated total number of iterations to reach the target exploitability, or `None` if the
+    /// exploitability did not decrease during the calibration.
+    pub num_iterations: Option<u32>,
+
+    /// Estimated wall-clock time of [`solve`] to reach the target exploitability, including the
+    /// exploitability computations every 10 iterations, or `None` if `num_iterations` is `None`.
+    pub time: Option<Duration>,
+}
+
+/// Runs a few calibration iterations and extrapolates the number of iterations and the
+/// wall-clock time needed to reach `target_exploitability`.
+///
+/// The exploitability is sampled during the calibration and extrapolated by fitting a power law
+/// (exploitability ∝ iterations^(-k)), which is only a rough estimate; the actual convergence is
+/// usually slower for long solves. The calibration stops early if the target is reached.
+///
+/// The calibration iterations are performed on `game` itself as its first iterations. To reuse
+/// them, continue with [`solve_step`] from `num_calibration_iterations` and then call
+/// [`finalize`]; to start over, allocate the memory again.
+///
+/// Panics if the game is not ready or already solved.
+pub fn estimate_solve_time<T: Game>(game: &T, target_exploitability: f32) -> SolveTimeEstimate {
+    if game.is_solved() {
+        panic!("Game is already solved");
+    }
+
+    if !game.is_ready() {
+        panic!("Game is not ready");
+    }
+
+    let start = Instant::now();
+    let mut exploitability = compute_exploitability(game);
+    let exploitability_time = start.elapsed();
+
+    let mut samples = Vec::with_capacity(CALIBRATION_SAMPLES.len());
+    let mut iteration_time = Duration::ZERO;
+    let mut num_iterations = 0;
+
+    while exploitability > target_exploitability && num_iterations < CALIBRATION_ITERATIONS {
+        let start = Instant::now();
+        solve_step(game, num_iterations);
+        iteration_time += start.elapsed();
+        num_iterations += 1;
+
+        if CALIBRATION_SAMPLES.contains(&num_iterations) {
+            exploitability = compute_exploitability(game);
+            samples.push((num_iterations as f64, exploitability as f64));
+        }
+    }
+
+    let time_per_iteration = iteration_time / num_iterations.max(1);
+
+    let estimated_iterations = if exploitability <= target_exploitability {
+        Some(num_iterations)
+    } else {
+        extrapolate_iterations(&samples, target_exploitability as f64)
+            .map(|n| n.max(num_iterations))
+    };
+
+    let time = estimated_iterations.map(|n| {
+        let num_exploitability_computations = 1 + n.div_ceil(10);
+        time_per_iteration * n + exploitability_time * num_exploitability_computations
+    });
+
+    SolveTimeEstimate {
+        num_calibration_iterations: num_iterations,
+        exploitability,
+        time_per_iteration,
+        exploitability_time,
+        num_iterations: estimated_iterations,
+        time,
+    }
+}
+
+/// Fits `ln(exploitability) = a - k * ln(iterations)` to the samples by least squares, and returns
+/// the number of iterations at which the fitted exploitability reaches `target`.
+fn extrapolate_iterations(samples: &[(f64, f64)], target: f64) -> Option<u32> {
+    let points = samples
+        .iter()
+        .filter(|&&(_, e)| e > 0.0)
+        .map(|&(t, e)| (t.ln(), e.ln()))
+        .collect::<Vec<_>>();
+
+    if points.len() < 2 || target <= 0.0 {
+        return None;
+    }
+
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let cov = points
+        .iter()
+        .map(|p| (p.0 - mean_x) * (p.1 - mean_y))
+        .sum::<f64>();
+    let var = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum::<f64>();
+
+    let k = -cov / var;
+    if k.is_nan() || k <= 0.0 {
+        return None;
+    }
+
+    let ln_iterations = mean_x + (mean_y - target.ln()) / k;
+    let iterations = ln_iterations.exp().ceil();
+    Some(iterations.min(u32::MAX as f64) as u32)
+}
+
 /// Recursively solves the counterfactual values.
 fn solve_recursive<T: Game>(
     result: &mut [MaybeUninit<f32>],
@@ -139,11 +522,14 @@ fn solve_recursive<T: Game>(
     node: &mut T::Node,
     player: usize,
     cfreach: &[f32],
-    params: &DiscountParams,
+    params: &IterationParams,
+    profiler: &Profiler,
 ) {
     // return the counterfactual values when the `node` is terminal
     if node.is_terminal() {
+        let timer = Timer::start();
         game.evaluate(result, node, player, cfreach);
+        profiler.record_evaluation(node, timer);
         return;
     }
 
@@ -153,7 +539,7 @@ fn solve_recursive<T: Game>(
     // simply recurse when the number of actions is one
     if num_actions == 1 && !node.is_chance() {
         let child = &mut node.play(0);
-        solve_recursive(result, game, child, player, cfreach, params);
+        solve_recursive(result, game, child, player, cfreach, params, profiler);
         return;
     }
 
@@ -161,24 +547,45 @@ fn solve_recursive<T: Game>(
     #[cfg(feature = "custom-alloc")]
     let cfv_actions = MutexLike::new(Vec::with_capacity_in(num_actions * num_hands, StackAlloc));
     #[cfg(not(feature = "custom-alloc"))]
-    let cfv_actions = MutexLike::new(Vec::with_capacity(num_actions * num_hands));
+    let cfv_actions = MutexLike::new(PooledVec::with_capacity(num_actions * num_hands));
 
     // if the `node` is chance
     if node.is_chance() {
+        let timer = Timer::start();
+
+        // sample the river cards; the sampled ones are weighted by the inverse of the fraction
+        let sampled = match (&params.sampling, node.river_chance_turn()) {
+            (Some(sampling), Some(turn)) => sampling.sample(turn, num_actions),
+            _ => None,
+        };
+        let scale = sampled.map_or(1.0, |(_, num_samples)| {
+            num_actions as f32 / num_samples as f32
+        });
+
         // update the reach probabilities
         #[cfg(feature = "custom-alloc")]
         let mut cfreach_updated = Vec::with_capacity_in(cfreach.len(), StackAlloc);
         #[cfg(not(feature = "custom-alloc"))]
-        let mut cfreach_updated = Vec::with_capacity(cfreach.len());
+        let mut cfreach_updated = PooledVec::with_capacity(cfreach.len());
         mul_slice_scalar_uninit(
             cfreach_updated.spare_capacity_mut(),
             cfreach,
-            1.0 / game.chance_factor(node) as f32,
+            scale / game.chance_factor(node) as f32,
         );
         unsafe { cfreach_updated.set_len(cfreach.len()) };
+        profiler.record_traversal(node, timer);
 
         // compute the counterfactual values of each action
         for_each_child(node, |action| {
+            if sampled.is_some_and(|(mask, _)| mask & (1 << action) == 0) {
+                row_mut(cfv_actions.lock().spare_capacity_mut(), action, num_hands)
+                    .iter_mut()
+                    .for_each(|v| {
+                        v.write(0.0);
+                    });
+                return;
+            }
+
             solve_recursive(
                 row_mut(cfv_actions.lock().spare_capacity_mut(), action, num_hands),
                 game,
@@ -186,14 +593,17 @@ fn solve_recursive<T: Game>(
                 player,
                 &cfreach_updated,
                 params,
+                profiler,
             );
         });
 
+        let timer = Timer::start();
+
         // use 64-bit floating point values
         #[cfg(feature = "custom-alloc")]
         let mut result_f64 = Vec::with_capacity_in(num_hands, StackAlloc);
         #[cfg(not(feature = "custom-alloc"))]
-        let mut result_f64 = Vec::with_capacity(num_hands);
+        let mut result_f64 = PooledVec::with_capacity(num_hands);
 
         // sum up the counterfactual values
         let mut cfv_actions = cfv_actions.lock();
@@ -221,6 +631,8 @@ fn solve_recursive<T: Game>(
         result.iter_mut().zip(&result_f64).for_each(|(r, &v)| {
             r.write(v as f32);
         });
+
+        profiler.record_traversal(node, timer);
     }
     // if the current player is `player`
     else if node.player() == player {
@@ -233,15 +645,14 @@ fn solve_recursive<T: Game>(
                 player,
                 cfreach,
                 params,
+                profiler,
             );
         });
 
+        let timer = Timer::start();
+
         // compute the strategy by regret-maching algorithm
-        let mut strategy = if game.is_compression_enabled() {
-            regret_matching_compressed(node.regrets_compressed(), num_actions)
-        } else {
-            regret_matching(node.regrets(), num_actions)
-        };
+        let mut strategy = node_regret_matching(game, node);
 
         // node-locking
         let locking = game.locking_strategy(node);
@@ -252,79 +663,114 @@ fn solve_recursive<T: Game>(
         unsafe { cfv_actions.set_len(num_actions * num_hands) };
         let result = fma_slices_uninit(result, &strategy, &cfv_actions);
 
-        if game.is_compression_enabled() {
-            // update the cumulative strategy
-            let scale = node.strategy_scale();
-            let decoder = params.gamma_t * scale / u16::MAX as f32;
-            let cum_strategy = node.strategy_compressed_mut();
-
-            strategy.iter_mut().zip(&*cum_strategy).for_each(|(x, y)| {
-                *x += (*y as f32) * decoder;
-            });
-
-            if !locking.is_empty() {
-                strategy.iter_mut().zip(locking).for_each(|(d, s)| {
-                    if s.is_sign_positive() {
-                        *d = 0.0;
-                    }
-                })
+        // update the cumulative strategy
+        match game.strategy_format() {
+            StorageFormat::Float32 => {
+                let gamma = params.discount.gamma_t;
+                let cum_strategy = node.strategy_mut();
+                cum_strategy.iter_mut().zip(&strategy).for_each(|(x, y)| {
+                    *x = *x * gamma + *y;
+                });
             }
-
-            let new_scale = encode_unsigned_slice(cum_strategy, &strategy);
-            node.set_strategy_scale(new_scale);
-
-            // update the cumulative regret
-            let scale = node.regret_scale();
-            let alpha_decoder = params.alpha_t * scale / i16::MAX as f32;
-            let beta_decoder = params.beta_t * scale / i16::MAX as f32;
-            let cum_regret = node.regrets_compressed_mut();
-
-            cfv_actions.iter_mut().zip(&*cum_regret).for_each(|(x, y)| {
-                *x += *y as f32 * if *y >= 0 { alpha_decoder } else { beta_decoder };
-            });
-
-            cfv_actions.chunks_exact_mut(num_hands).for_each(|row| {
-                sub_slice(row, result);
-            });
-
-            if !locking.is_empty() {
-                cfv_actions.iter_mut().zip(locking).for_each(|(d, s)| {
-                    if s.is_sign_positive() {
-                        *d = 0.0;
-                    }
-                })
+            StorageFormat::Float16 => {
+                let gamma = params.discount.gamma_t;
+                strategy
+                    .iter_mut()
+                    .zip(node.strategy_f16())
+                    .for_each(|(x, y)| {
+                        *x += y.to_f32() * gamma;
+                    });
+                encode_f16_slice(node.strategy_f16_mut(), &strategy);
             }
+            StorageFormat::Int16 => {
+                let scale = node.strategy_scale();
+                let cum_strategy = node.strategy_compressed_mut();
+                let new_scale = update_cum_strategy(
+                    cum_strategy,
+                    scale,
+                    &mut strategy,
+                    locking,
+                    &params.discount,
+                );
+                node.set_strategy_scale(new_scale);
+            }
+            StorageFormat::Int8 => {
+                let scale = node.strategy_scale();
+                let cum_strategy = node.strategy_8bit_mut();
+                let new_scale = update_cum_strategy(
+                    cum_strategy,
+                    scale,
+                    &mut strategy,
+                    locking,
+                    &params.discount,
+                );
+                node.set_strategy_scale(new_scale);
+            }
+        }
 
-            let new_scale = encode_signed_slice(cum_regret, &cfv_actions);
-            node.set_regret_scale(new_scale);
-        } else {
-            // update the cumulative strategy
-            let gamma = params.gamma_t;
-            let cum_strategy = node.strategy_mut();
-            cum_strategy.iter_mut().zip(&strategy).for_each(|(x, y)| {
-                *x = *x * gamma + *y;
-            });
-
-            // update the cumulative regret
-            let (alpha, beta) = (params.alpha_t, params.beta_t);
-            let cum_regret = node.regrets_mut();
-            cum_regret.iter_mut().zip(&*cfv_actions).for_each(|(x, y)| {
-                let coef = if x.is_sign_positive() { alpha } else { beta };
-                *x = *x * coef + *y;
-            });
-            cum_regret.chunks_exact_mut(num_hands).for_each(|row| {
-                sub_slice(row, result);
-            });
+        // update the cumulative regret
+        match game.value_format() {
+            StorageFormat::Float32 => {
+                let (alpha, beta) = (params.discount.alpha_t, params.discount.beta_t);
+                let cum_regret = node.regrets_mut();
+                cum_regret.iter_mut().zip(&*cfv_actions).for_each(|(x, y)| {
+                    let coef = if x.is_sign_positive() { alpha } else { beta };
+                    *x = *x * coef + *y;
+                });
+                cum_regret.chunks_exact_mut(num_hands).for_each(|row| {
+                    sub_slice(row, result);
+                });
+            }
+            StorageFormat::Float16 => {
+                let (alpha, beta) = (params.discount.alpha_t, params.discount.beta_t);
+                cfv_actions
+                    .iter_mut()
+                    .zip(node.regrets_f16())
+                    .for_each(|(x, y)| {
+                        let y = y.to_f32();
+                        *x += y * if y.is_sign_positive() { alpha } else { beta };
+                    });
+                cfv_actions.chunks_exact_mut(num_hands).for_each(|row| {
+                    sub_slice(row, result);
+                });
+                encode_f16_slice(node.regrets_f16_mut(), &cfv_actions);
+            }
+            StorageFormat::Int16 => {
+                let scale = node.regret_scale();
+                let cum_regret = node.regrets_compressed_mut();
+                let new_scale = update_cum_regret(
+                    cum_regret,
+                    scale,
+                    &mut cfv_actions,
+                    result,
+                    locking,
+                    &params.discount,
+                );
+                node.set_regret_scale(new_scale);
+            }
+            StorageFormat::Int8 => {
+                let scale = node.regret_scale();
+                let cum_regret = node.regrets_8bit_mut();
+                let new_scale = update_cum_regret(
+                    cum_regret,
+                    scale,
+                    &mut cfv_actions,
+                    result,
+                    locking,
+                    &params.discount,
+                );
+                node.set_regret_scale(new_scale);
+            }
         }
+
+        profiler.record_traversal(node, timer);
     }
     // if the current player is not `player`
     else {
+        let timer = Timer::start();
+
         // compute the strategy by regret-matching algorithm
-        let mut cfreach_actions = if game.is_compression_enabled() {
-            regret_matching_compressed(node.regrets_compressed(), num_actions)
-        } else {
-            regret_matching(node.regrets(), num_actions)
-        };
+        let mut cfreach_actions = node_regret_matching(game, node);
 
         // node-locking
         let locking = game.locking_strategy(node);
@@ -335,6 +781,7 @@ fn solve_recursive<T: Game>(
         cfreach_actions.chunks_exact_mut(row_size).for_each(|row| {
             mul_slice(row, cfreach);
         });
+        profiler.record_traversal(node, timer);
 
         // compute the counterfactual values of each action
         for_each_child(node, |action| {
@@ -345,14 +792,108 @@ fn solve_recursive<T: Game>(
                 player,
                 row(&cfreach_actions, action, row_size),
                 params,
+                profiler,
             );
         });
 
         // sum up the counterfactual values
+        let timer = Timer::start();
         let mut cfv_actions = cfv_actions.lock();
         unsafe { cfv_actions.set_len(num_actions * num_hands) };
         sum_slices_uninit(result, &cfv_actions);
+        profiler.record_traversal(node, timer);
+    }
+}
+
+/// Computes the strategy of the given node by regret-matching algorithm.
+#[cfg(feature = "custom-alloc")]
+#[inline]
+fn node_regret_matching<T: Game>(game: &T, node: &T::Node) -> Vec<f32, StackAlloc> {
+    let num_actions = node.num_actions();
+    match game.value_format() {
+        StorageFormat::Float32 => regret_matching(node.regrets(), num_actions),
+        StorageFormat::Float16 => regret_matching_compressed(node.regrets_f16(), num_actions),
+        StorageFormat::Int16 => regret_matching_compressed(node.regrets_compressed(), num_actions),
+        StorageFormat::Int8 => regret_matching_compressed(node.regrets_8bit(), num_actions),
+    }
+}
+
+/// Computes the strategy of the given node by regret-matching algorithm.
+#[cfg(not(feature = "custom-alloc"))]
+#[inline]
+fn node_regret_matching<T: Game>(game: &T, node: &T::Node) -> PooledVec<f32> {
+    let num_actions = node.num_actions();
+    match game.value_format() {
+        StorageFormat::Float32 => regret_matching(node.regrets(), num_actions),
+        StorageFormat::Float16 => regret_matching_compressed(node.regrets_f16(), num_actions),
+        StorageFormat::Int16 => regret_matching_compressed(node.regrets_compressed(), num_actions),
+        StorageFormat::Int8 => regret_matching_compressed(node.regrets_8bit(), num_actions),
+    }
+}
+
+/// Updates the compressed cumulative strategy with the current strategy, and returns the new
+/// scale. The current strategy is overwritten.
+#[inline]
+fn update_cum_strategy<T: Quantized>(
+    cum_strategy: &mut [T],
+    scale: f32,
+    strategy: &mut [f32],
+    locking: &[f32],
+    params: &DiscountParams,
+) -> f32 {
+    let decoder = params.gamma_t * scale / T::MAX;
+
+    strategy.iter_mut().zip(&*cum_strategy).for_each(|(x, y)| {
+        *x += y.to_f32() * decoder;
+    });
+
+    if !locking.is_empty() {
+        strategy.iter_mut().zip(locking).for_each(|(d, s)| {
+            if s.is_sign_positive() {
+                *d = 0.0;
+            }
+        })
     }
+
+    encode_unsigned_slice(cum_strategy, strategy)
+}
+
+/// Updates the compressed cumulative regret with the counterfactual values of the actions, and
+/// returns the new scale. The counterfactual values are overwritten.
+#[inline]
+fn update_cum_regret<T: Quantized>(
+    cum_regret: &mut [T],
+    scale: f32,
+    cfv_actions: &mut [f32],
+    result: &[f32],
+    locking: &[f32],
+    params: &DiscountParams,
+) -> f32 {
+    let alpha_decoder = params.alpha_t * scale / T::MAX;
+    let beta_decoder = params.beta_t * scale / T::MAX;
+
+    cfv_actions.iter_mut().zip(&*cum_regret).for_each(|(x, y)| {
+        let y = y.to_f32();
+        *x += y * if y >= 0.0 {
+            alpha_decoder
+        } else {
+            beta_decoder
+        };
+    });
+
+    cfv_actions.chunks_exact_mut(result.len()).for_each(|row| {
+        sub_slice(row, result);
+    });
+
+    if !locking.is_empty() {
+        cfv_actions.iter_mut().zip(locking).for_each(|(d, s)| {
+            if s.is_sign_positive() {
+                *d = 0.0;
+            }
+        })
+    }
+
+    encode_signed_slice(cum_regret, cfv_actions)
 }
 
 /// Computes the strategy by regret-matching algorithm.
@@ -382,8 +923,8 @@ fn regret_matching(regret: &[f32], num_actions: usize) -> Vec<f32, StackAlloc> {
 /// Computes the strategy by regret-matching algorithm.
 #[cfg(not(feature = "custom-alloc"))]
 #[inline]
-fn regret_matching(regret: &[f32], num_actions: usize) -> Vec<f32> {
-    let mut strategy = Vec::with_capacity(regret.len());
+fn regret_matching(regret: &[f32], num_actions: usize) -> PooledVec<f32> {
+    let mut strategy = PooledVec::with_capacity(regret.len());
     let uninit = strategy.spare_capacity_mut();
     uninit.iter_mut().zip(regret).for_each(|(s, r)| {
         s.write(max(*r, 0.0));
@@ -391,7 +932,7 @@ fn regret_matching(regret: &[f32], num_actions: usize) -> Vec<f32> {
     unsafe { strategy.set_len(regret.len()) };
 
     let row_size = regret.len() / num_actions;
-    let mut denom = Vec::with_capacity(row_size);
+    let mut denom = PooledVec::with_capacity(row_size);
     sum_slices_uninit(denom.spare_capacity_mut(), &strategy);
     unsafe { denom.set_len(row_size) };
 
@@ -406,9 +947,12 @@ fn regret_matching(regret: &[f32], num_actions: usize) -> Vec<f32> {
 /// Computes the strategy by regret-matching algorithm.
 #[cfg(feature = "custom-alloc")]
 #[inline]
-fn regret_matching_compressed(regret: &[i16], num_actions: usize) -> Vec<f32, StackAlloc> {
+fn regret_matching_compressed<T: Compressed>(
+    regret: &[T],
+    num_actions: usize,
+) -> Vec<f32, StackAlloc> {
     let mut strategy = Vec::with_capacity_in(regret.len(), StackAlloc);
-    strategy.extend(regret.iter().map(|&r| r.max(0) as f32));
+    strategy.extend(regret.iter().map(|&r| r.to_f32().max(0.0)));
 
     let row_size = strategy.len() / num_actions;
     let mut denom = Vec::with_capacity_in(row_size, StackAlloc);
@@ -426,12 +970,12 @@ fn regret_matching_compressed(regret: &[i16], num_actions: usize) -> Vec<f32, St
 /// Computes the strategy by regret-matching algorithm.
 #[cfg(not(feature = "custom-alloc"))]
 #[inline]
-fn regret_matching_compressed(regret: &[i16], num_actions: usize) -> Vec<f32> {
-    let mut strategy = Vec::with_capacity(regret.len());
-    strategy.extend(regret.iter().map(|&r| r.max(0) as f32));
+fn regret_matching_compressed<T: Compressed>(regret: &[T], num_actions: usize) -> PooledVec<f32> {
+    let mut strategy = PooledVec::with_capacity(regret.len());
+    strategy.extend(regret.iter().map(|&r| r.to_f32().max(0.0)));
 
     let row_size = strategy.len() / num_actions;
-    let mut denom = Vec::with_capacity(row_size);
+    let mut denom = PooledVec::with_capacity(row_size);
     sum_slices_uninit(denom.spare_capacity_mut(), &strategy);
     unsafe { denom.set_len(row_size) };
 
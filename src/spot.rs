@@ -0,0 +1,470 @@
+use crate::action_tree::*;
+use crate::bet_size::*;
+use crate::card::*;
+use crate::error::*;
+use crate::range::*;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// Number of chips per big blind in the configurations produced by [`parse_spot`].
+pub const SPOT_CHIPS_PER_BB: i32 = 100;
+
+/// Positions in the postflop order of action, from the first to act.
+const POSITIONS: [&str; 11] = [
+    "SB", "BB", "UTG", "UTG1", "UTG2", "MP", "MP1", "LJ", "HJ", "CO", "BTN",
+];
+
+/// Set of preflop range charts looked up by [`parse_spot`].
+///
+/// Each chart is the range of a position in a pot type and a matchup (e.g., the range of `BB` in
+/// `SRP` of `BTNvsBB`). The keys are case-insensitive.
+///
+/// A chart set can also be parsed from a string with one chart per line in the form of
+/// `<pot type> <matchup> <position>: <range>`. Empty lines and lines starting with `#` are
+/// ignored.
+///
+/// # Examples
+/// ```
+/// use postflop_solver_ffi::*;
+///
+/// let charts = "
+///     ## single-raised pot, BTN open and BB call
+///     SRP BTNvsBB BTN: 22+,A2s+,K5s+,Q8s+,J8s+,T8s+,97s+,87s,76s,65s,A8o+,KTo+,QTo+,JTo
+///     SRP BTNvsBB BB: 99-22,AJs-A2s,KJs-K2s,Q6s+,J7s+,T7s+,97s+,86s+,75s+,65s,AJo-A2o,K9o+,QTo+
+/// ";
+/// let charts = charts.parse::<ChartSet>().unwrap();
+/// assert!(charts.get("srp", "BTNvsBB", "bb").is_some());
+/// assert!(charts.get("3BP", "BTNvsBB", "BB").is_none());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ChartSet {
+    charts: BTreeMap<(String, String, String), Range>,
+}
+
+impl ChartSet {
+    /// Creates an empty chart set.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the range of the position in the given pot type and matchup, replacing the existing
+    /// one if any.
+    ///
+    /// Returns an error if the matchup does not contain the position, or the range is invalid.
+    pub fn insert(
+        &mut self,
+        pot_type: &str,
+        matchup: &str,
+        position: &str,
+        range: &str,
+    ) -> Result<(), PostFlopError> {
+        let key = chart_key(pot_type, matchup, position)?;
+        self.charts.insert(key, range.parse()?);
+        Ok(())
+    }
+
+    /// Returns the range of the position in the given pot type and matchup.
+    #[inline]
+    pub fn get(&self, pot_type: &str, matchup: &str, position: &str) -> Option<&Range> {
+        let key = chart_key(pot_type, matchup, position).ok()?;
+        self.charts.get(&key)
+    }
+
+    /// Returns the number of charts.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.charts.len()
+    }
+
+    /// Returns whether the chart set is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.charts.is_empty()
+    }
+}
+
+impl FromStr for ChartSet {
+    type Err = PostFlopError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut charts = Self::new();
+
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = || {
+                PostFlopError::InvalidConfig(format!(
+                    "Expected `<pot type> <matchup> <position>: <range>`: {line}"
+                ))
+            };
+
+            let (key, range) = line.split_once(':').ok_or_else(invalid)?;
+            let key = key.split_whitespace().collect::<Vec<_>>();
+            let [pot_type, matchup, position] = key[..] else {
+                return Err(invalid());
+            };
+
+            charts.insert(pot_type, matchup, position, range)?;
+        }
+
+        Ok(charts)
+    }
+}
+
+/// Splits the matchup (e.g., `"BTNvsBB"`) into the uppercase positions.
+fn parse_matchup(matchup: &str) -> Result<[String; 2], PostFlopError> {
+    let upper = matchup.to_uppercase();
+    match upper.split_once("VS") {
+        Some((first, second)) if !first.is_empty() && !second.is_empty() && first != second => {
+            Ok([first.to_string(), second.to_string()])
+        }
+        _ => Err(PostFlopError::InvalidConfig(format!(
+            "Invalid matchup (expected e.g. `BTNvsBB`): {matchup}"
+        ))),
+    }
+}
+
+/// Returns the normalized key of a chart.
+fn chart_key(
+    pot_type: &str,
+    matchup: &str,
+    position: &str,
+) -> Result<(String, String, String), PostFlopError> {
+    let positions = parse_matchup(matchup)?;
+    let position = position.to_uppercase();
+    if !positions.contains(&position) {
+        return Err(PostFlopError::InvalidConfig(format!(
+            "Matchup {matchup} does not contain position {position}"
+        )));
+    }
+    Ok((pot_type.to_uppercase(), positions.join("VS"), position))
+}
+
+/// Parses a spot description into the card configuration and the tree configuration.
+///
+/// A spot description is a comma-separated list of clauses. The first clause consists of the pot
+/// type, the starting stack, and the matchup (e.g., `SRP 100bb BTNvsBB`); the ranges of both
+/// players are looked up in `charts` by the pot type and the matchup. The other clauses are the
+/// following (keywords are case-insensitive):
+///
+/// - `pot <bb>`: pot size at the start of the flop (required).
+/// - `flop <cards>`, `turn <card>`, `river <card>`, or `board <cards>`: the board cards. The
+///   flop is required; the initial street is determined by the number of cards.
+/// - `sizes <sizes>`: bet sizes of both players on all streets, separated by `/`. A number is a
+///   percentage of the pot, `geo` is the geometric size, `a` is all-in, and the other sizes are
+///   written as in [`BetSizeOptions`] (e.g., `2e`, `150c`). If not specified, only all-in is used.
+/// - `raises <sizes>`: raise sizes in the same format (e.g., `3x/a`).
+/// - `<street> sizes <sizes>` and `<street> raises <sizes>`: the sizes of a specific street.
+/// - `eff <bb>`: effective stack at the start of the flop. By default, the chips of the players
+///   in the pot are subtracted from the starting stack, where the blinds of the positions not in
+///   the matchup are dead money.
+/// - `rake <percent>% [cap <bb>]`: rake rate and rake cap.
+///
+/// Amounts in big blinds are converted into chips by [`SPOT_CHIPS_PER_BB`]. The out-of-position
+/// player is determined by the positions (e.g., `BB` in `BTNvsBB`).
+///
+/// # Examples
+/// ```
+/// use postflop_solver_ffi::*;
+///
+/// let mut charts = ChartSet::new();
+/// charts.insert("SRP", "BTNvsBB", "BTN", "22+,A2s+,K9s+,ATo+,KJo+").unwrap();
+/// charts.insert("SRP", "BTNvsBB", "BB", "99-22,AJs-A2s,K9s+,AJo-A9o,KJo+").unwrap();
+///
+/// let spot = "SRP 100bb BTNvsBB, pot 5.5, flop Td9d6h, sizes 33/75/geo";
+/// let (card_config, tree_config) = parse_spot(spot, &charts).unwrap();
+/// assert_eq!(card_config.range[0], charts.get("SRP", "BTNvsBB", "BB").unwrap().clone());
+/// assert_eq!(tree_config.starting_pot, 550);
+/// assert_eq!(tree_config.effective_stack, 9750);
+///
+/// let action_tree = ActionTree::new(tree_config).unwrap();
+/// let game = PostFlopGame::with_config(card_config, action_tree).unwrap();
+/// ```
+pub fn parse_spot(
+    description: &str,
+    charts: &ChartSet,
+) -> Result<(CardConfig, TreeConfig), PostFlopError> {
+    let invalid = |message: String| PostFlopError::InvalidConfig(message);
+    let mut clauses = description.split(',').map(str::trim);
+
+    // header: pot type, starting stack, and matchup
+    let header = clauses.next().unwrap_or_default();
+    let mut pot_type = None;
+    let mut stack = None;
+    let mut matchup = None;
+    for token in header.split_whitespace() {
+        let lower = token.to_lowercase();
+        if lower.contains("vs") {
+            matchup = Some(token);
+        } else if lower.ends_with("bb") {
+            stack = Some(parse_bb(&[token], "stack")?);
+        } else if pot_type.is_none() {
+            pot_type = Some(token);
+        } else {
+            return Err(invalid(format!("Unexpected token in the header: {token}")));
+        }
+    }
+
+    let (Some(pot_type), Some(stack), Some(matchup)) = (pot_type, stack, matchup) else {
+        return Err(invalid(format!(
+            "Header must consist of the pot type, the stack, and the matchup \
+             (e.g., `SRP 100bb BTNvsBB`): {header}"
+        )));
+    };
+
+    let positions = parse_matchup(matchup)?;
+    let [first, second] = positions.clone().map(|position| {
+        let index = POSITIONS.iter().position(|&p| p == position);
+        index.ok_or_else(|| invalid(format!("Unknown position: {position}")))
+    });
+    let oop = if first? < second? { 0 } else { 1 };
+    let players = [&positions[oop], &positions[1 - oop]];
+
+    let mut pot = None;
+    let mut effective_stack = None;
+    let mut board = None;
+    let mut streets = [None, None, None];
+    let mut bet_sizes = String::new();
+    let mut raise_sizes = String::new();
+    let mut street_bet_sizes = [None, None, None];
+    let mut street_raise_sizes = [None, None, None];
+    let mut rake = (0.0, 0.0);
+
+    for clause in clauses {
+        let words = clause.split_whitespace().collect::<Vec<_>>();
+        let Some((&keyword, args)) = words.split_first() else {
+            return Err(invalid(format!("Empty clause: {description}")));
+        };
+
+        let keyword = keyword.to_lowercase();
+        let street = ["flop", "turn", "river"].iter().position(|&s| s == keyword);
+        let sub_keyword = args.first().map(|word| word.to_lowercase());
+
+        match (keyword.as_str(), street, sub_keyword.as_deref()) {
+            (_, Some(street), Some("sizes")) => {
+                street_bet_sizes[street] = Some(parse_sizes(&args[1..]));
+            }
+            (_, Some(street), Some("raises")) => {
+                street_raise_sizes[street] = Some(parse_sizes(&args[1..]));
+            }
+            (_, Some(street), _) => streets[street] = Some(args.concat()),
+            ("board", ..) => board = Some(args.concat()),
+            ("pot", ..) => pot = Some(parse_bb(args, "pot")?),
+            ("eff", ..) => effective_stack = Some(parse_bb(args, "eff")?),
+            ("sizes", ..) => bet_sizes = parse_sizes(args),
+            ("raises", ..) => raise_sizes = parse_sizes(args),
+            ("rake", ..) => rake = parse_rake(args)?,
+            _ => return Err(invalid(format!("Unknown clause: {clause}"))),
+        }
+    }
+
+    let board = match (board, streets) {
+        (Some(board), [None, None, None]) => board,
+        (None, [Some(flop), turn, river]) if turn.is_some() || river.is_none() => {
+            flop + &turn.unwrap_or_default() + &river.unwrap_or_default()
+        }
+        (None, [None, ..]) => return Err(invalid("Flop is not specified".to_string())),
+        (None, _) => return Err(invalid("River specified without turn".to_string())),
+        (Some(_), _) => {
+            return Err(invalid(
+                "Board and flop/turn/river cannot be specified together".to_string(),
+            ))
+        }
+    };
+    let board = board_from_str(&board)?;
+    let initial_state = match board.len() {
+        3 => BoardState::Flop,
+        4 => BoardState::Turn,
+        5 => BoardState::River,
+        n => return Err(invalid(format!("Board must have 3 to 5 cards: {n}"))),
+    };
+
+    let pot = pot.ok_or_else(|| invalid("Pot is not specified".to_string()))?;
+    let effective_stack = match effective_stack {
+        Some(effective_stack) => effective_stack,
+        None => {
+            let is_dead = |blind: &str| !positions.iter().any(|p| p == blind);
+            let dead = is_dead("SB") as i32 * SPOT_CHIPS_PER_BB / 2
+                + is_dead("BB") as i32 * SPOT_CHIPS_PER_BB;
+            stack - (pot - dead) / 2
+        }
+    };
+
+    let [oop_range, ip_range] = players.map(|position| {
+        charts
+            .get(pot_type, matchup, position)
+            .cloned()
+            .ok_or_else(|| invalid(format!("Chart not found: {pot_type} {matchup} {position}")))
+    });
+
+    let mut flop = [board[0], board[1], board[2]];
+    flop.sort_unstable();
+    let card_config = CardConfig {
+        range: [oop_range?, ip_range?],
+        flop,
+        turn: board.get(3).copied().unwrap_or(NOT_DEALT),
+        river: board.get(4).copied().unwrap_or(NOT_DEALT),
+    };
+
+    let mut sizes = Vec::with_capacity(3);
+    for street in 0..3 {
+        let bet = street_bet_sizes[street].as_ref().unwrap_or(&bet_sizes);
+        let raise = street_raise_sizes[street].as_ref().unwrap_or(&raise_sizes);
+        let options = BetSizeOptions::try_from((bet.as_str(), raise.as_str()))?;
+        sizes.push([options.clone(), options]);
+    }
+
+    let tree_config = TreeConfig {
+        initial_state,
+        starting_pot: pot,
+        effective_stack,
+        rake_rate: rake.0,
+        rake_cap: rake.1,
+        flop_bet_sizes: sizes[0].clone(),
+        turn_bet_sizes: sizes[1].clone(),
+        river_bet_sizes: sizes[2].clone(),
+        ..Default::default()
+    };
+
+    if tree_config.effective_stack <= 0 {
+        return Err(invalid(format!(
+            "Effective stack must be positive: {}",
+            tree_config.effective_stack
+        )));
+    }
+
+    Ok((card_config, tree_config))
+}
+
+/// Parses an amount in big blinds into chips.
+fn parse_bb(args: &[&str], name: &str) -> Result<i32, PostFlopError> {
+    let arg = args.concat().to_lowercase();
+    let bb = arg.strip_suffix("bb").unwrap_or(&arg);
+    match bb.parse::<f64>() {
+        Ok(bb) if bb > 0.0 && bb * (SPOT_CHIPS_PER_BB as f64) < i32::MAX as f64 => {
+            Ok((bb * SPOT_CHIPS_PER_BB as f64).round() as i32)
+        }
+        _ => Err(PostFlopError::InvalidConfig(format!(
+            "Invalid {name} (expected a positive amount in big blinds): {arg}"
+        ))),
+    }
+}
+
+/// Converts `/`-separated sizes (e.g., `33/75/geo`) into the format of [`BetSizeOptions`].
+fn parse_sizes(args: &[&str]) -> String {
+    let sizes = args.concat();
+    let sizes = sizes
+        .split('/')
+        .map(|size| match size.to_lowercase().as_str() {
+            "geo" => "e".to_string(),
+            "allin" | "ai" => "a".to_string(),
+            size if size.parse::<f64>().is_ok() => format!("{size}%"),
+            size => size.to_string(),
+        });
+    sizes.collect::<Vec<_>>().join(",")
+}
+
+/// Parses the rake rate and the rake cap (e.g., `5% cap 3`).
+fn parse_rake(args: &[&str]) -> Result<(f64, f64), PostFlopError> {
+    let invalid = || {
+        PostFlopError::InvalidConfig(format!(
+            "Invalid rake (expected e.g. `rake 5% cap 3`): {}",
+            args.join(" ")
+        ))
+    };
+
+    let rate = match args.first().and_then(|arg| arg.strip_suffix('%')) {
+        Some(rate) => rate.parse::<f64>().map_err(|_| invalid())? / 100.0,
+        None => return Err(invalid()),
+    };
+
+    let cap = match args[1..] {
+        [] => 0.0,
+        [cap_keyword, cap] if cap_keyword.eq_ignore_ascii_case("cap") => {
+            parse_bb(&[cap], "rake cap")? as f64
+        }
+        _ => return Err(invalid()),
+    };
+
+    Ok((rate, cap))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn charts() -> ChartSet {
+        "SRP BTNvsBB BTN: 22+,A2s+,K9s+,ATo+,KJo+
+         SRP BTNvsBB BB: 99-22,AJs-A2s,K9s+,AJo-A9o,KJo+
+         3BP COvsBTN CO: QQ+,AK
+         3BP COvsBTN BTN: JJ-99,AQs,KQs"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn spot_description() {
+        let charts = charts();
+        assert_eq!(charts.len(), 4);
+
+        let spot = "SRP 100bb BTNvsBB, pot 5.5, flop Td9d6h, sizes 33/75/geo, raises 3x/a";
+        let (card_config, tree_config) = parse_spot(spot, &charts).unwrap();
+        assert_eq!(
+            card_config.range[0],
+            *charts.get("SRP", "btnvsbb", "BB").unwrap()
+        );
+        assert_eq!(
+            card_config.range[1],
+            *charts.get("SRP", "btnvsbb", "BTN").unwrap()
+        );
+        assert_eq!(card_config.flop, flop_from_str("Td9d6h").unwrap());
+        assert_eq!(card_config.turn, NOT_DEALT);
+        assert_eq!(tree_config.initial_state, BoardState::Flop);
+        assert_eq!(tree_config.starting_pot, 550);
+        assert_eq!(tree_config.effective_stack, 9750);
+        let sizes = BetSizeOptions::try_from(("33%, 75%, e", "3x, a")).unwrap();
+        assert_eq!(tree_config.flop_bet_sizes, [sizes.clone(), sizes.clone()]);
+        assert_eq!(tree_config.river_bet_sizes, [sizes.clone(), sizes]);
+
+        // the in-position player is listed first, and both blinds are dead money
+        let spot = "3bp 100BB COvsBTN, pot 22.5, board Td9d6h Qc, rake 5% cap 3, \
+                    river sizes 50/a, turn raises allin, eff 89";
+        let (card_config, tree_config) = parse_spot(spot, &charts).unwrap();
+        assert_eq!(
+            card_config.range[0],
+            *charts.get("3BP", "COvsBTN", "CO").unwrap()
+        );
+        assert_eq!(card_config.turn, card_from_str("Qc").unwrap());
+        assert_eq!(tree_config.initial_state, BoardState::Turn);
+        assert_eq!(tree_config.effective_stack, 8900);
+        assert_eq!((tree_config.rake_rate, tree_config.rake_cap), (0.05, 300.0));
+        let sizes = BetSizeOptions::try_from(("50%, a", "")).unwrap();
+        assert_eq!(tree_config.river_bet_sizes, [sizes.clone(), sizes]);
+        let sizes = BetSizeOptions::try_from(("", "a")).unwrap();
+        assert_eq!(tree_config.turn_bet_sizes, [sizes.clone(), sizes]);
+
+        let spot = "3BP 100bb COvsBTN, pot 22.5, flop Td9d6h";
+        let (_, tree_config) = parse_spot(spot, &charts).unwrap();
+        assert_eq!(tree_config.effective_stack, 10000 - (2250 - 150) / 2);
+
+        let error = |spot: &str| parse_spot(spot, &charts).unwrap_err().to_string();
+        assert!(error("SRP BTNvsBB, pot 5.5, flop Td9d6h").starts_with("Header must"));
+        assert!(error("SRP 100bb BTNvsXX, pot 5.5, flop Td9d6h").contains("Unknown position"));
+        assert!(error("SRP 100bb COvsBB, pot 5.5, flop Td9d6h").contains("Chart not found"));
+        assert!(error("SRP 100bb BTNvsBB, flop Td9d6h").contains("Pot is not"));
+        assert!(error("SRP 100bb BTNvsBB, pot 5.5").contains("Flop is not"));
+        assert!(error("SRP 100bb BTNvsBB, pot 5.5, flop Td9d6h, river 2c").contains("turn"));
+        assert!(error("SRP 100bb BTNvsBB, pot 5.5, flop Td9d6h, limp 2").contains("Unknown"));
+        assert!(error("SRP 100bb BTNvsBB, pot -1, flop Td9d6h").contains("Invalid pot"));
+        assert!(error("SRP 100bb BTNvsBB, pot 5.5, flop TdTd6h").contains("Duplicate card"));
+        assert!(error("SRP 100bb BTNvsBB, pot 5.5, flop Td9d6h, rake 5").contains("rake"));
+        assert!(error("SRP 100bb BTNvsBB, pot 5.5, flop Td9d6h, sizes 33/x").contains("x"));
+        assert!(error("SRP 2bb BTNvsBB, pot 5.5, flop Td9d6h").contains("Effective stack"));
+
+        assert!("SRP BTNvsBB: AA".parse::<ChartSet>().is_err());
+        assert!("SRP BTNvsBB CO: AA".parse::<ChartSet>().is_err());
+    }
+}
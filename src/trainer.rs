@@ -0,0 +1,403 @@
+use crate::action_tree::*;
+use crate::card::*;
+use crate::error::*;
+use crate::game::*;
+use crate::interface::*;
+use crate::utility::*;
+
+/// A decision presented by [`Trainer::next_question`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Question {
+    /// Action history from the root node (see [`PostFlopGame::history`]).
+    pub history: Vec<usize>,
+
+    /// Actions leading to the node from the root node. Dealt cards are represented as
+    /// [`Action::Chance`].
+    pub line: Vec<Action>,
+
+    /// Board cards at the node.
+    pub board: Vec<Card>,
+
+    /// Pot size at the node, including the starting pot.
+    pub pot: i32,
+
+    /// Player to act (`0` = OOP, `1` = IP).
+    pub player: usize,
+
+    /// Private hand of the player to act. The hand may be suit-isomorphic to the dealt cards, but
+    /// it is always consistent with [`board`](Self::board).
+    pub hand: (Card, Card),
+
+    /// Available actions.
+    pub actions: Vec<Action>,
+}
+
+/// Result of an answer, returned by [`Trainer::answer`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grade {
+    /// Index of the chosen action.
+    pub action: usize,
+
+    /// Index of the action with the highest expected value.
+    pub best_action: usize,
+
+    /// Solved strategy of the hand (frequency of each action).
+    pub strategy: Vec<f32>,
+
+    /// Expected value of each action with the hand.
+    pub expected_values: Vec<f32>,
+
+    /// Loss of the expected value compared to the best action (non-negative).
+    pub ev_loss: f32,
+}
+
+impl Grade {
+    /// Returns the solved frequency of the chosen action.
+    #[inline]
+    pub fn frequency(&self) -> f32 {
+        self.strategy[self.action]
+    }
+}
+
+/// Statistics of the answers of a [`Trainer`] session.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrainerStats {
+    /// Number of answered questions.
+    pub num_answers: usize,
+
+    /// Number of answers choosing the action with the highest expected value.
+    pub num_best_answers: usize,
+
+    /// Sum of the EV losses.
+    pub total_ev_loss: f64,
+
+    /// Sum of the EV losses divided by the pot sizes.
+    pub total_pot_relative_ev_loss: f64,
+}
+
+impl TrainerStats {
+    /// Returns the average EV loss per answer.
+    #[inline]
+    pub fn mean_ev_loss(&self) -> f64 {
+        if self.num_answers == 0 {
+            0.0
+        } else {
+            self.total_ev_loss / self.num_answers as f64
+        }
+    }
+
+    /// Returns the average EV loss per answer as a fraction of the pot.
+    #[inline]
+    pub fn mean_pot_relative_ev_loss(&self) -> f64 {
+        if self.num_answers == 0 {
+            0.0
+        } else {
+            self.total_pot_relative_ev_loss / self.num_answers as f64
+        }
+    }
+}
+
+/// Quiz over the decisions of a solved game.
+///
+/// Each question is a decision node sampled with the probability of reaching it when both players
+/// follow the solved strategies, and a private hand of the player to act sampled by its number of
+/// combinations at the node. An answer is graded by its EV loss compared to the action with the
+/// highest expected value, and accumulated into the session statistics. The questions are
+/// deterministic for the same `seed`.
+///
+/// The reach probability of a node is approximated by the product of the reach probabilities of
+/// both ranges, ignoring card removal between the players.
+///
+/// # Examples
+/// ```
+/// use postflop_solver_ffi::*;
+///
+/// let mut game = PostFlopGame::builder()
+///     .oop_range("AA,KK,QQ")
+///     .ip_range("QQ-JJ,AQs")
+///     .board("Td9d6hQc2s")
+///     .pot(100)
+///     .stack(100)
+///     .bet_sizes("50%", "")
+///     .build()
+///     .unwrap();
+/// game.allocate_memory(false, None).unwrap();
+/// solve(&mut game, 100, 0.5, false);
+///
+/// let mut trainer = Trainer::new(game, None, 42).unwrap();
+/// let question = trainer.next_question().clone();
+/// let grade = trainer.answer(0).unwrap();
+/// assert_eq!(grade.expected_values.len(), question.actions.len());
+/// assert_eq!(trainer.stats().num_answers, 1);
+/// ```
+pub struct Trainer {
+    game: PostFlopGame,
+    rng: Rng,
+    nodes: Vec<Vec<usize>>,
+    cum_weights: Vec<f64>,
+    question: Option<(Question, usize)>,
+    stats: TrainerStats,
+}
+
+impl Trainer {
+    /// Creates a trainer of the given solved game.
+    ///
+    /// If `player` is specified, only the decisions of the player are asked. Streets whose
+    /// strategies are not stored (e.g., in a game saved after lowering
+    /// [`PostFlopGame::set_target_storage_mode`]) are not asked.
+    ///
+    /// Returns an error if no decision node can be reached. Panics if the game is not solved.
+    pub fn new(
+        mut game: PostFlopGame,
+        player: Option<usize>,
+        seed: u64,
+    ) -> Result<Self, PostFlopError> {
+        if !game.is_solved() {
+            panic!("Game is not solved");
+        }
+
+        if player.is_some_and(|player| player > 1) {
+            return Err(PostFlopError::InvalidArgument(format!(
+                "Invalid player: {}",
+                player.unwrap()
+            )));
+        }
+
+        game.back_to_root();
+        let root_weights = [0, 1].map(|player| game.weights(player).to_vec());
+        let root_mass = root_weights
+            .each_ref()
+            .map(|weights| weights.iter().map(|&w| w as f64).sum::<f64>());
+
+        let mut nodes = Vec::new();
+        let mut cum_weights = Vec::new();
+        let mut cum_weight = 0.0;
+
+        game.visit_nodes(|view| {
+            if player.is_some_and(|player| player != view.current_player()) {
+                return;
+            }
+
+            let mut weight = view.chance_probability();
+            for player in 0..2 {
+                let reach = view.reach_probabilities(player);
+                let iter = root_weights[player].iter().zip(reach);
+                let mass = iter.map(|(&w, &r)| w as f64 * r as f64).sum::<f64>();
+                weight *= mass / root_mass[player];
+            }
+
+            if weight > 0.0 {
+                cum_weight += weight;
+                nodes.push(view.history().to_vec());
+                cum_weights.push(cum_weight);
+            }
+        });
+
+        if nodes.is_empty() {
+            return Err(PostFlopError::InvalidArgument(
+                "No decision node can be reached".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            game,
+            rng: Rng::new(seed),
+            nodes,
+            cum_weights,
+            question: None,
+            stats: TrainerStats::default(),
+        })
+    }
+
+    /// Samples a new question, discarding the unanswered one if any.
+    ///
+    /// **Time complexity:** *O*(log #(nodes) + (depth of the tree) + #(private hands)).
+    pub fn next_question(&mut self) -> &Question {
+        loop {
+            let r = self.rng.next_f64() * self.cum_weights.last().unwrap();
+            let index = self.cum_weights.partition_point(|&w| w <= r);
+            let history = &self.nodes[index.min(self.nodes.len() - 1)];
+
+            self.game.apply_history(history);
+            self.game.cache_normalized_weights();
+
+            let player = self.game.current_player();
+            let weights = self.game.normalized_weights(player);
+            let total = weights.iter().map(|&w| w as f64).sum::<f64>();
+            if total <= 0.0 {
+                continue;
+            }
+
+            let mut r = self.rng.next_f64() * total;
+            let mut hand_index = weights.len() - 1;
+            for (i, &w) in weights.iter().enumerate() {
+                if r < w as f64 {
+                    hand_index = i;
+                    break;
+                }
+                r -= w as f64;
+            }
+            if weights[hand_index] == 0.0 {
+                hand_index = weights.iter().rposition(|&w| w > 0.0).unwrap();
+            }
+
+            let [bet_oop, bet_ip] = self.game.total_bet_amount();
+            let question = Question {
+                history: history.clone(),
+                line: self.game.history_actions(),
+                board: self.game.current_board(),
+                pot: self.game.tree_config().starting_pot + bet_oop + bet_ip,
+                player,
+                hand: self.game.private_cards(player)[hand_index],
+                actions: self.game.available_actions(),
+            };
+
+            self.question = Some((question, hand_index));
+            return &self.question.as_ref().unwrap().0;
+        }
+    }
+
+    /// Returns the current unanswered question.
+    #[inline]
+    pub fn question(&self) -> Option<&Question> {
+        self.question.as_ref().map(|(question, _)| question)
+    }
+
+    /// Grades the given action index for the current question and updates the statistics.
+    ///
+    /// Returns an error if there is no unanswered question or the action index is invalid.
+    pub fn answer(&mut self, action: usize) -> Result<Grade, PostFlopError> {
+        let Some((question, hand)) = &self.question else {
+            return Err(PostFlopError::InvalidState(
+                "No question to answer".to_string(),
+            ));
+        };
+
+        let num_actions = question.actions.len();
+        if action >= num_actions {
+            return Err(PostFlopError::InvalidArgument(format!(
+                "Invalid action index: {action}"
+            )));
+        }
+
+        let num_hands = self.game.private_cards(question.player).len();
+        let strategy = self.game.strategy();
+        let expected_values = self.game.expected_values_detail(question.player);
+        let strategy = (0..num_actions)
+            .map(|i| strategy[i * num_hands + hand])
+            .collect::<Vec<_>>();
+        let expected_values = (0..num_actions)
+            .map(|i| expected_values[i * num_hands + hand])
+            .collect::<Vec<_>>();
+
+        let best_action = (0..num_actions)
+            .max_by(|&i, &j| expected_values[i].total_cmp(&expected_values[j]))
+            .unwrap();
+        let ev_loss = (expected_values[best_action] - expected_values[action]).max(0.0);
+
+        self.stats.num_answers += 1;
+        self.stats.num_best_answers += (ev_loss == 0.0) as usize;
+        self.stats.total_ev_loss += ev_loss as f64;
+        self.stats.total_pot_relative_ev_loss += ev_loss as f64 / question.pot as f64;
+        self.question = None;
+
+        Ok(Grade {
+            action,
+            best_action,
+            strategy,
+            expected_values,
+            ev_loss,
+        })
+    }
+
+    /// Returns the statistics of the session.
+    #[inline]
+    pub fn stats(&self) -> &TrainerStats {
+        &self.stats
+    }
+
+    /// Resets the statistics of the session.
+    #[inline]
+    pub fn reset_stats(&mut self) {
+        self.stats = TrainerStats::default();
+    }
+
+    /// Returns the number of decision nodes that can be asked.
+    #[inline]
+    pub fn num_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns a reference to the game.
+    #[inline]
+    pub fn game(&self) -> &PostFlopGame {
+        &self.game
+    }
+
+    /// Consumes the trainer and returns the game.
+    #[inline]
+    pub fn into_game(self) -> PostFlopGame {
+        self.game
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::*;
+
+    fn solved_game() -> PostFlopGame {
+        let mut game = PostFlopGame::builder()
+            .oop_range("AA,KK,QQ,JJ")
+            .ip_range("QQ-TT,AQs,KQs")
+            .board("Td9d6hQc2s")
+            .pot(100)
+            .stack(200)
+            .bet_sizes("50%", "a")
+            .build()
+            .unwrap();
+        game.allocate_memory(false, None).unwrap();
+        solve(&mut game, 200, 0.1, false);
+        game
+    }
+
+    #[test]
+    fn trainer() {
+        assert!(Trainer::new(solved_game(), Some(2), 0).is_err());
+
+        let mut trainer = Trainer::new(solved_game(), None, 1).unwrap();
+        assert!(trainer.answer(0).is_err());
+
+        let mut seen_players = [false; 2];
+        let mut num_zero_loss = 0;
+        for _ in 0..200 {
+            let question = trainer.next_question().clone();
+            seen_players[question.player] = true;
+            assert!(!question.actions.is_empty());
+            let (c1, c2) = question.hand;
+            assert!(!question.board.contains(&c1) && !question.board.contains(&c2));
+
+            assert!(trainer.answer(question.actions.len()).is_err());
+
+            let grade = trainer.answer(0).unwrap();
+            assert_eq!(grade.strategy.len(), question.actions.len());
+            assert!((grade.strategy.iter().sum::<f32>() - 1.0).abs() < 1e-3);
+            assert!(grade.ev_loss >= 0.0);
+            assert_eq!(grade.ev_loss == 0.0, grade.action == grade.best_action);
+            num_zero_loss += (grade.ev_loss == 0.0) as usize;
+        }
+        assert_eq!(seen_players, [true, true]);
+        assert_eq!(trainer.stats().num_answers, 200);
+        assert_eq!(trainer.stats().num_best_answers, num_zero_loss);
+        assert!(trainer.question().is_none());
+
+        // the same seed produces the same questions
+        let mut trainer1 = Trainer::new(solved_game(), Some(1), 7).unwrap();
+        let mut trainer2 = Trainer::new(solved_game(), Some(1), 7).unwrap();
+        for _ in 0..10 {
+            let question = trainer1.next_question().clone();
+            assert_eq!(question.player, 1);
+            assert_eq!(&question, trainer2.next_question());
+        }
+    }
+}
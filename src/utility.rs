@@ -1,12 +1,15 @@
 use crate::interface::*;
 use crate::mutex_like::*;
 use crate::sliceop::*;
+use half::f16;
 use std::mem::{self, MaybeUninit};
 use std::ptr;
 
 #[cfg(feature = "custom-alloc")]
 use crate::alloc::*;
 
+use crate::pool::*;
+
 #[cfg(feature = "rayon")]
 use rayon::prelude::*;
 
@@ -57,6 +60,36 @@ pub(crate) fn vec_memory_usage<T>(vec: &Vec<T>) -> u64 {
     vec.capacity() as u64 * mem::size_of::<T>() as u64
 }
 
+/// Deterministic pseudo-random number generator (SplitMix64).
+///
+/// The same seed always produces the same sequence on every platform.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    /// Creates a new generator with the given seed.
+    #[inline]
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// Returns a uniform random 64-bit integer.
+    #[inline]
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a uniform random number in `[0, 1)`.
+    #[inline]
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+}
+
 /// Computes the average with given weights.
 #[inline]
 pub fn compute_average(slice: &[f32], weights: &[f32]) -> f32 {
@@ -202,31 +235,113 @@ fn slice_nonnegative_max(slice: &[f32]) -> f32 {
     }
 }
 
-/// Encodes the `f32` slice to the `i16` slice, and returns the scale.
+/// Types of the compressed values.
+pub(crate) trait Compressed: Copy {
+    /// Converts the value to `f32`.
+    fn to_f32(self) -> f32;
+}
+
+impl Compressed for f16 {
+    #[inline]
+    fn to_f32(self) -> f32 {
+        f16::to_f32(self)
+    }
+}
+
+/// Integer types of the compressed values.
+pub(crate) trait Quantized: Compressed {
+    /// The maximum value as `f32`.
+    const MAX: f32;
+
+    /// Converts the `i32` value, which must be in the range of the type.
+    fn from_i32(value: i32) -> Self;
+}
+
+macro_rules! impl_quantized {
+    ($($t:ty),*) => {$(
+        impl Compressed for $t {
+            #[inline]
+            fn to_f32(self) -> f32 {
+                self as f32
+            }
+        }
+
+        impl Quantized for $t {
+            const MAX: f32 = <$t>::MAX as f32;
+
+            #[inline]
+            fn from_i32(value: i32) -> Self {
+                value as $t
+            }
+        }
+    )*};
+}
+
+impl_quantized!(i8, u8, i16, u16);
+
+/// Encodes the `f32` slice to the half-precision slice. Out-of-range values are saturated.
+#[inline]
+pub(crate) fn encode_f16_slice(dst: &mut [f16], slice: &[f32]) {
+    let max = f16::MAX.to_f32();
+    dst.iter_mut().zip(slice).for_each(|(d, s)| {
+        *d = f16::from_f32(s.clamp(-max, max));
+    });
+}
+
+/// Decodes the half-precision slice to the `f32` slice.
+#[inline]
+pub(crate) fn decode_f16_slice(slice: &[f16]) -> Vec<f32> {
+    let mut ret = Vec::with_capacity(slice.len());
+    decode_f16_into(&mut ret, slice);
+    ret
+}
+
+/// Decodes the half-precision slice and appends the result to `dst`.
 #[inline]
-pub(crate) fn encode_signed_slice(dst: &mut [i16], slice: &[f32]) -> f32 {
+pub(crate) fn decode_f16_into(dst: &mut Vec<f32>, slice: &[f16]) {
+    dst.extend(slice.iter().map(|x| x.to_f32()));
+}
+
+/// Encodes the `f32` slice to the signed integer slice, and returns the scale.
+#[inline]
+pub(crate) fn encode_signed_slice<T: Quantized>(dst: &mut [T], slice: &[f32]) -> f32 {
     let scale = slice_absolute_max(slice);
     let scale_nonzero = if scale == 0.0 { 1.0 } else { scale };
-    let encoder = i16::MAX as f32 / scale_nonzero;
-    dst.iter_mut()
-        .zip(slice)
-        .for_each(|(d, s)| *d = unsafe { (s * encoder).round().to_int_unchecked::<i32>() as i16 });
+    let encoder = T::MAX / scale_nonzero;
+    dst.iter_mut().zip(slice).for_each(|(d, s)| {
+        *d = T::from_i32(unsafe { (s * encoder).round().to_int_unchecked::<i32>() })
+    });
     scale
 }
 
-/// Encodes the `f32` slice to the `u16` slice, and returns the scale.
+/// Encodes the `f32` slice to the unsigned integer slice, and returns the scale.
 #[inline]
-pub(crate) fn encode_unsigned_slice(dst: &mut [u16], slice: &[f32]) -> f32 {
+pub(crate) fn encode_unsigned_slice<T: Quantized>(dst: &mut [T], slice: &[f32]) -> f32 {
     let scale = slice_nonnegative_max(slice);
     let scale_nonzero = if scale == 0.0 { 1.0 } else { scale };
-    let encoder = u16::MAX as f32 / scale_nonzero;
+    let encoder = T::MAX / scale_nonzero;
     // note: 0.49999997 + 0.49999997 = 0.99999994 < 1.0 | 0.5 + 0.49999997 = 1.0
     dst.iter_mut().zip(slice).for_each(|(d, s)| {
-        *d = unsafe { (s * encoder + 0.49999997).to_int_unchecked::<i32>() as u16 }
+        *d = T::from_i32(unsafe { (s * encoder + 0.49999997).to_int_unchecked::<i32>() })
     });
     scale
 }
 
+/// Decodes the quantized slice to the `f32` slice with the given scale.
+#[inline]
+pub(crate) fn decode_quantized_slice<T: Quantized>(slice: &[T], scale: f32) -> Vec<f32> {
+    let mut ret = Vec::with_capacity(slice.len());
+    decode_quantized_into(&mut ret, slice, scale);
+    ret
+}
+
+/// Decodes the quantized slice with the given scale and appends the result to `dst`.
+#[inline]
+pub(crate) fn decode_quantized_into<T: Quantized>(dst: &mut Vec<f32>, slice: &[T], scale: f32) {
+    let decoder = scale / T::MAX;
+    dst.extend(slice.iter().map(|&x| x.to_f32() * decoder));
+}
+
 /// Applies the given swap to the given slice.
 #[inline]
 pub(crate) fn apply_swap<T>(slice: &mut [T], swap_list: &[(u16, u16)]) {
@@ -252,6 +367,21 @@ pub fn finalize<T: Game>(game: &mut T) {
     }
 
     // compute the expected values and save them
+    save_cfvalues(game);
+
+    // set the game solved
+    game.set_solved();
+
+    // free buffer
+    #[cfg(feature = "rayon")]
+    rayon::broadcast(|_| free_thread_buffers());
+    #[cfg(not(feature = "rayon"))]
+    free_thread_buffers();
+}
+
+/// Computes the counterfactual values of the current strategy and saves them to the nodes.
+#[inline]
+pub(crate) fn save_cfvalues<T: Game>(game: &T) {
     for player in 0..2 {
         let mut cfvalues = Vec::with_capacity(game.num_private_hands(player));
         compute_cfvalue_recursive(
@@ -263,15 +393,14 @@ pub fn finalize<T: Game>(game: &mut T) {
             true,
         );
     }
+}
 
-    // set the game solved
-    game.set_solved();
-
-    // free buffer
-    #[cfg(all(feature = "custom-alloc", feature = "rayon"))]
-    rayon::broadcast(|_| free_custom_alloc_buffer());
-    #[cfg(all(feature = "custom-alloc", not(feature = "rayon")))]
+/// Frees the temporary buffers of the current thread.
+#[inline]
+fn free_thread_buffers() {
+    #[cfg(feature = "custom-alloc")]
     free_custom_alloc_buffer();
+    free_pooled_buffers();
 }
 
 /// Computes the exploitability of the current strategy.
@@ -290,6 +419,38 @@ pub fn compute_exploitability<T: Game>(game: &T) -> f32 {
     }
 }
 
+/// Computes the gain of the best response of each player against the current strategy of the
+/// opponent, i.e., how much each player could win by deviating from the current strategy.
+///
+/// The exploitability returned by [`compute_exploitability`] is the average of the two gains, so
+/// this function reveals when one player is much closer to the equilibrium than the other.
+#[inline]
+pub fn compute_best_response_gains<T: Game>(game: &T) -> [f32; 2] {
+    let mes_ev = compute_mes_ev(game);
+    let current_ev = compute_current_ev(game);
+    [mes_ev[0] - current_ev[0], mes_ev[1] - current_ev[1]]
+}
+
+/// Computes the gain of the best response of each player (outer index) when the player deviates
+/// from the current strategy only on each street (inner index; `0`: flop, `1`: turn, `2`: river).
+///
+/// The streets before the initial state of the game have zero gains. The gains of the streets do
+/// not add up to the gain of [`compute_best_response_gains`] in general, because the deviations on
+/// the streets interact with each other.
+///
+/// **Time complexity:** 3 * [`compute_mes_ev`].
+pub fn compute_street_best_response_gains<T: Game>(game: &T) -> [[f32; 3]; 2] {
+    let current_ev = compute_current_ev(game);
+    let mut ret = [[0.0; 3]; 2];
+    for street in 0..3 {
+        let mes_ev = compute_mes_ev_internal(game, 1 << street);
+        for player in 0..2 {
+            ret[player][street] = mes_ev[player] - current_ev[player];
+        }
+    }
+    ret
+}
+
 /// Computes the expected values of the current strategy of each player.
 ///
 /// The bias, i.e., (starting pot) / 2, is already subtracted to increase the significant figures.
@@ -329,6 +490,12 @@ pub fn compute_current_ev<T: Game>(game: &T) -> [f32; 2] {
 /// Therefore, the average of the return value corresponds to the exploitability value if not raked.
 #[inline]
 pub fn compute_mes_ev<T: Game>(game: &T) -> [f32; 2] {
+    compute_mes_ev_internal(game, 0b111)
+}
+
+/// Computes the expected values of each player best-responding on the streets in `street_mask`
+/// (bit `i` = street `i`) and following the current strategy on the other streets.
+fn compute_mes_ev_internal<T: Game>(game: &T, street_mask: u8) -> [f32; 2] {
     if !game.is_ready() && !game.is_solved() {
         panic!("Game is not ready");
     }
@@ -347,6 +514,7 @@ pub fn compute_mes_ev<T: Game>(game: &T) -> [f32; 2] {
             &game.root(),
             player,
             reach[player ^ 1],
+            street_mask,
         );
         unsafe { cfvalues[player].set_len(game.num_private_hands(player)) };
     }
@@ -441,11 +609,18 @@ fn compute_cfvalue_recursive<T: Game>(
         // save the counterfactual values
         if save_cfvalues && node.cfvalue_storage_player() == Some(player) {
             let result = unsafe { &*(result as *const _ as *const [f32]) };
-            if game.is_compression_enabled() {
-                let cfv_scale = encode_signed_slice(node.cfvalues_chance_compressed_mut(), result);
-                node.set_cfvalue_chance_scale(cfv_scale);
-            } else {
-                node.cfvalues_chance_mut().copy_from_slice(result);
+            match game.value_format() {
+                StorageFormat::Float32 => node.cfvalues_chance_mut().copy_from_slice(result),
+                StorageFormat::Float16 => encode_f16_slice(node.cfvalues_chance_f16_mut(), result),
+                StorageFormat::Int16 => {
+                    let cfv_scale =
+                        encode_signed_slice(node.cfvalues_chance_compressed_mut(), result);
+                    node.set_cfvalue_chance_scale(cfv_scale);
+                }
+                StorageFormat::Int8 => {
+                    let cfv_scale = encode_signed_slice(node.cfvalues_chance_8bit_mut(), result);
+                    node.set_cfvalue_chance_scale(cfv_scale);
+                }
             }
         }
     }
@@ -464,18 +639,7 @@ fn compute_cfvalue_recursive<T: Game>(
         });
 
         // obtain the strategy
-        #[cfg(feature = "custom-alloc")]
-        let mut strategy = if game.is_compression_enabled() {
-            normalized_strategy_compressed_custom_alloc(node.strategy_compressed(), num_actions)
-        } else {
-            normalized_strategy_custom_alloc(node.strategy(), num_actions)
-        };
-        #[cfg(not(feature = "custom-alloc"))]
-        let mut strategy = if game.is_compression_enabled() {
-            normalized_strategy_compressed(node.strategy_compressed(), num_actions)
-        } else {
-            normalized_strategy(node.strategy(), num_actions)
-        };
+        let mut strategy = node_normalized_strategy(game, node);
 
         // node-locking
         let locking = game.locking_strategy(node);
@@ -488,11 +652,18 @@ fn compute_cfvalue_recursive<T: Game>(
 
         // save the counterfactual values
         if save_cfvalues {
-            if game.is_compression_enabled() {
-                let cfv_scale = encode_signed_slice(node.cfvalues_compressed_mut(), &cfv_actions);
-                node.set_cfvalue_scale(cfv_scale);
-            } else {
-                node.cfvalues_mut().copy_from_slice(&cfv_actions);
+            match game.value_format() {
+                StorageFormat::Float32 => node.cfvalues_mut().copy_from_slice(&cfv_actions),
+                StorageFormat::Float16 => encode_f16_slice(node.cfvalues_f16_mut(), &cfv_actions),
+                StorageFormat::Int16 => {
+                    let cfv_scale =
+                        encode_signed_slice(node.cfvalues_compressed_mut(), &cfv_actions);
+                    node.set_cfvalue_scale(cfv_scale);
+                }
+                StorageFormat::Int8 => {
+                    let cfv_scale = encode_signed_slice(node.cfvalues_8bit_mut(), &cfv_actions);
+                    node.set_cfvalue_scale(cfv_scale);
+                }
             }
         }
     }
@@ -509,18 +680,7 @@ fn compute_cfvalue_recursive<T: Game>(
         );
     } else {
         // obtain the strategy
-        #[cfg(feature = "custom-alloc")]
-        let mut cfreach_actions = if game.is_compression_enabled() {
-            normalized_strategy_compressed_custom_alloc(node.strategy_compressed(), num_actions)
-        } else {
-            normalized_strategy_custom_alloc(node.strategy(), num_actions)
-        };
-        #[cfg(not(feature = "custom-alloc"))]
-        let mut cfreach_actions = if game.is_compression_enabled() {
-            normalized_strategy_compressed(node.strategy_compressed(), num_actions)
-        } else {
-            normalized_strategy(node.strategy(), num_actions)
-        };
+        let mut cfreach_actions = node_normalized_strategy(game, node);
 
         // node-locking
         let locking = game.locking_strategy(node);
@@ -553,22 +713,30 @@ fn compute_cfvalue_recursive<T: Game>(
     // save the counterfactual values for IP
     if save_cfvalues && node.has_cfvalues_ip() && player == 1 {
         let result = unsafe { &*(result as *const _ as *const [f32]) };
-        if game.is_compression_enabled() {
-            let cfv_scale = encode_signed_slice(node.cfvalues_ip_compressed_mut(), result);
-            node.set_cfvalue_ip_scale(cfv_scale);
-        } else {
-            node.cfvalues_ip_mut().copy_from_slice(result);
+        match game.value_format() {
+            StorageFormat::Float32 => node.cfvalues_ip_mut().copy_from_slice(result),
+            StorageFormat::Float16 => encode_f16_slice(node.cfvalues_ip_f16_mut(), result),
+            StorageFormat::Int16 => {
+                let cfv_scale = encode_signed_slice(node.cfvalues_ip_compressed_mut(), result);
+                node.set_cfvalue_ip_scale(cfv_scale);
+            }
+            StorageFormat::Int8 => {
+                let cfv_scale = encode_signed_slice(node.cfvalues_ip_8bit_mut(), result);
+                node.set_cfvalue_ip_scale(cfv_scale);
+            }
         }
     }
 }
 
-/// The recursive helper function for computing the counterfactual values of best response.
+/// The recursive helper function for computing the counterfactual values of best response. The
+/// player follows the current strategy on the streets not in `street_mask`.
 fn compute_best_cfv_recursive<T: Game>(
     result: &mut [MaybeUninit<f32>],
     game: &T,
     node: &T::Node,
     player: usize,
     cfreach: &[f32],
+    street_mask: u8,
 ) {
     // terminal node
     if node.is_terminal() {
@@ -582,7 +750,7 @@ fn compute_best_cfv_recursive<T: Game>(
     // simply recurse when the number of actions is one
     if num_actions == 1 && !node.is_chance() {
         let child = &node.play(0);
-        compute_best_cfv_recursive(result, game, child, player, cfreach);
+        compute_best_cfv_recursive(result, game, child, player, cfreach, street_mask);
         return;
     }
 
@@ -614,6 +782,7 @@ fn compute_best_cfv_recursive<T: Game>(
                 &node.play(action),
                 player,
                 &cfreach_updated,
+                street_mask,
             )
         });
 
@@ -660,6 +829,7 @@ fn compute_best_cfv_recursive<T: Game>(
                 &node.play(action),
                 player,
                 cfreach,
+                street_mask,
             )
         });
 
@@ -667,7 +837,12 @@ fn compute_best_cfv_recursive<T: Game>(
         let mut cfv_actions = cfv_actions.lock();
         unsafe { cfv_actions.set_len(num_actions * num_hands) };
 
-        if locking.is_empty() {
+        if street_mask & (1 << node.street()) == 0 {
+            // follow the current strategy
+            let mut strategy = node_normalized_strategy(game, node);
+            apply_locking_strategy(&mut strategy, locking);
+            fma_slices_uninit(result, &strategy, &cfv_actions);
+        } else if locking.is_empty() {
             // compute element-wise maximum (take the best response)
             max_slices_uninit(result, &cfv_actions);
         } else {
@@ -678,18 +853,7 @@ fn compute_best_cfv_recursive<T: Game>(
     // opponent node
     else {
         // obtain the strategy
-        #[cfg(feature = "custom-alloc")]
-        let mut cfreach_actions = if game.is_compression_enabled() {
-            normalized_strategy_compressed_custom_alloc(node.strategy_compressed(), num_actions)
-        } else {
-            normalized_strategy_custom_alloc(node.strategy(), num_actions)
-        };
-        #[cfg(not(feature = "custom-alloc"))]
-        let mut cfreach_actions = if game.is_compression_enabled() {
-            normalized_strategy_compressed(node.strategy_compressed(), num_actions)
-        } else {
-            normalized_strategy(node.strategy(), num_actions)
-        };
+        let mut cfreach_actions = node_normalized_strategy(game, node);
 
         // node-locking
         let locking = game.locking_strategy(node);
@@ -709,6 +873,7 @@ fn compute_best_cfv_recursive<T: Game>(
                 &node.play(action),
                 player,
                 row(&cfreach_actions, action, row_size),
+                street_mask,
             );
         });
 
@@ -748,10 +913,19 @@ pub(crate) fn normalized_strategy_custom_alloc(
 #[inline]
 pub(crate) fn normalized_strategy(strategy: &[f32], num_actions: usize) -> Vec<f32> {
     let mut normalized = Vec::with_capacity(strategy.len());
-    let uninit = normalized.spare_capacity_mut();
+    normalized_strategy_into(&mut normalized, strategy, num_actions);
+    normalized
+}
+
+/// Writes the normalized strategy to `dst`, reusing its buffer.
+#[inline]
+pub(crate) fn normalized_strategy_into(dst: &mut Vec<f32>, strategy: &[f32], num_actions: usize) {
+    dst.clear();
+    dst.reserve(strategy.len());
+    let uninit = &mut dst.spare_capacity_mut()[..strategy.len()];
 
     let row_size = strategy.len() / num_actions;
-    let mut denom = Vec::with_capacity(row_size);
+    let mut denom = PooledVec::with_capacity(row_size);
     sum_slices_uninit(denom.spare_capacity_mut(), strategy);
     unsafe { denom.set_len(row_size) };
 
@@ -763,21 +937,54 @@ pub(crate) fn normalized_strategy(strategy: &[f32], num_actions: usize) -> Vec<f
             div_slice_uninit(n, s, &denom, default);
         });
 
-    unsafe { normalized.set_len(strategy.len()) };
-    normalized
+    unsafe { dst.set_len(strategy.len()) };
 }
 
+/// Returns the normalized strategy of the given node.
 #[cfg(feature = "custom-alloc")]
 #[inline]
-pub(crate) fn normalized_strategy_compressed_custom_alloc(
-    strategy: &[u16],
+fn node_normalized_strategy<T: Game>(game: &T, node: &T::Node) -> Vec<f32, StackAlloc> {
+    let num_actions = node.num_actions();
+    match game.strategy_format() {
+        StorageFormat::Float32 => normalized_strategy_custom_alloc(node.strategy(), num_actions),
+        StorageFormat::Float16 => {
+            normalized_strategy_compressed_custom_alloc(node.strategy_f16(), num_actions)
+        }
+        StorageFormat::Int16 => {
+            normalized_strategy_compressed_custom_alloc(node.strategy_compressed(), num_actions)
+        }
+        StorageFormat::Int8 => {
+            normalized_strategy_compressed_custom_alloc(node.strategy_8bit(), num_actions)
+        }
+    }
+}
+
+/// Returns the normalized strategy of the given node.
+#[cfg(not(feature = "custom-alloc"))]
+#[inline]
+fn node_normalized_strategy<T: Game>(game: &T, node: &T::Node) -> Vec<f32> {
+    let num_actions = node.num_actions();
+    match game.strategy_format() {
+        StorageFormat::Float32 => normalized_strategy(node.strategy(), num_actions),
+        StorageFormat::Float16 => normalized_strategy_compressed(node.strategy_f16(), num_actions),
+        StorageFormat::Int16 => {
+            normalized_strategy_compressed(node.strategy_compressed(), num_actions)
+        }
+        StorageFormat::Int8 => normalized_strategy_compressed(node.strategy_8bit(), num_actions),
+    }
+}
+
+#[cfg(feature = "custom-alloc")]
+#[inline]
+pub(crate) fn normalized_strategy_compressed_custom_alloc<T: Compressed>(
+    strategy: &[T],
     num_actions: usize,
 ) -> Vec<f32, StackAlloc> {
     let mut normalized = Vec::with_capacity_in(strategy.len(), StackAlloc);
     let uninit = normalized.spare_capacity_mut();
 
     uninit.iter_mut().zip(strategy).for_each(|(n, s)| {
-        n.write(*s as f32);
+        n.write(s.to_f32());
     });
     unsafe { normalized.set_len(strategy.len()) };
 
@@ -795,26 +1002,34 @@ pub(crate) fn normalized_strategy_compressed_custom_alloc(
 }
 
 #[inline]
-pub(crate) fn normalized_strategy_compressed(strategy: &[u16], num_actions: usize) -> Vec<f32> {
+pub(crate) fn normalized_strategy_compressed<T: Compressed>(
+    strategy: &[T],
+    num_actions: usize,
+) -> Vec<f32> {
     let mut normalized = Vec::with_capacity(strategy.len());
-    let uninit = normalized.spare_capacity_mut();
+    normalized_strategy_compressed_into(&mut normalized, strategy, num_actions);
+    normalized
+}
 
-    uninit.iter_mut().zip(strategy).for_each(|(n, s)| {
-        n.write(*s as f32);
-    });
-    unsafe { normalized.set_len(strategy.len()) };
+/// Writes the normalized strategy to `dst`, reusing its buffer.
+#[inline]
+pub(crate) fn normalized_strategy_compressed_into<T: Compressed>(
+    dst: &mut Vec<f32>,
+    strategy: &[T],
+    num_actions: usize,
+) {
+    dst.clear();
+    dst.extend(strategy.iter().map(|s| s.to_f32()));
 
     let row_size = strategy.len() / num_actions;
-    let mut denom = Vec::with_capacity(row_size);
-    sum_slices_uninit(denom.spare_capacity_mut(), &normalized);
+    let mut denom = PooledVec::with_capacity(row_size);
+    sum_slices_uninit(denom.spare_capacity_mut(), dst);
     unsafe { denom.set_len(row_size) };
 
     let default = 1.0 / num_actions as f32;
-    normalized.chunks_exact_mut(row_size).for_each(|row| {
+    dst.chunks_exact_mut(row_size).for_each(|row| {
         div_slice(row, &denom, default);
     });
-
-    normalized
 }
 
 #[inline]
@@ -0,0 +1,550 @@
+use crate::action_tree::*;
+use crate::bet_size::*;
+use crate::card::*;
+use crate::range::*;
+
+/// Severity of a [`ConfigDiagnostic`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The configuration is valid, but probably not what was intended.
+    Warning,
+
+    /// The configuration is invalid, and the game cannot be constructed.
+    Error,
+}
+
+/// Kind of a [`ConfigDiagnostic`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiagnosticKind {
+    /// A board card is not initialized or out of range, or the river is dealt without the turn.
+    InvalidBoard,
+
+    /// A card appears more than once on the board.
+    BoardConflict {
+        /// The conflicting card.
+        card: Card,
+    },
+
+    /// The range of the player is invalid (loaded broken data?).
+    InvalidRange {
+        /// The player (`0` = OOP, `1` = IP).
+        player: usize,
+    },
+
+    /// The range of the player is empty, or becomes empty after removing the combinations that
+    /// conflict with the board.
+    EmptyRange {
+        /// The player (`0` = OOP, `1` = IP).
+        player: usize,
+    },
+
+    /// Some combinations of the range conflict with the board and are removed.
+    RangeBoardConflict {
+        /// The player (`0` = OOP, `1` = IP).
+        player: usize,
+
+        /// Number of the removed combinations.
+        num_combos: usize,
+    },
+
+    /// No combination of OOP is compatible with any combination of IP.
+    NoValidMatchup,
+
+    /// A numeric field of the tree configuration is out of its valid range.
+    InvalidValue {
+        /// Name of the field (e.g., `"starting_pot"`).
+        field: &'static str,
+    },
+
+    /// A bet size always exceeds the effective stack, so it is always replaced by all-in.
+    BetSizeExceedsStack {
+        /// The street of the bet size.
+        street: BoardState,
+
+        /// The player (`0` = OOP, `1` = IP).
+        player: usize,
+
+        /// The bet size.
+        size: BetSize,
+    },
+}
+
+/// A warning or an error found by [`CardConfig::validate`] or [`TreeConfig::validate`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigDiagnostic {
+    /// Severity of the diagnostic.
+    pub severity: Severity,
+
+    /// Kind of the diagnostic.
+    pub kind: DiagnosticKind,
+
+    /// Human-readable message.
+    pub message: String,
+}
+
+impl ConfigDiagnostic {
+    /// Returns whether the diagnostic is an error.
+    #[inline]
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+/// Collector of the diagnostics.
+#[derive(Default)]
+struct Diagnostics(Vec<ConfigDiagnostic>);
+
+impl Diagnostics {
+    #[inline]
+    fn push(&mut self, severity: Severity, kind: DiagnosticKind, message: String) {
+        self.0.push(ConfigDiagnostic {
+            severity,
+            kind,
+            message,
+        });
+    }
+
+    #[inline]
+    fn error(&mut self, kind: DiagnosticKind, message: String) {
+        self.push(Severity::Error, kind, message);
+    }
+
+    #[inline]
+    fn warning(&mut self, kind: DiagnosticKind, message: String) {
+        self.push(Severity::Warning, kind, message);
+    }
+}
+
+const PLAYER_NAMES: [&str; 2] = ["OOP", "IP"];
+
+impl CardConfig {
+    /// Validates the card configuration without constructing a game, and returns all the errors
+    /// and warnings found.
+    ///
+    /// The errors are the invalid or duplicate board cards, the invalid or empty ranges (also
+    /// after removing the combinations that conflict with the board), and the ranges without any
+    /// valid matchup. The combinations that conflict with the board are reported as warnings.
+    /// [`PostFlopGame::with_config`] fails if and only if this method reports an error or the
+    /// tree configuration is inconsistent with the board.
+    ///
+    /// # Examples
+    /// ```
+    /// use postflop_solver_ffi::*;
+    ///
+    /// let card_config = CardConfig {
+    ///     range: ["AA".parse().unwrap(), "KK".parse().unwrap()],
+    ///     flop: flop_from_str("AsAhKs").unwrap(),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let diagnostics = card_config.validate();
+    /// assert_eq!(diagnostics.len(), 2);
+    /// assert_eq!(
+    ///     diagnostics[0].kind,
+    ///     DiagnosticKind::RangeBoardConflict { player: 0, num_combos: 5 }
+    /// );
+    /// assert!(!diagnostics[0].is_error());
+    /// assert_eq!(diagnostics[1].kind, DiagnosticKind::RangeBoardConflict { player: 1, num_combos: 3 });
+    /// ```
+    ///
+    /// [`PostFlopGame::with_config`]: crate::PostFlopGame::with_config
+    pub fn validate(&self) -> Vec<ConfigDiagnostic> {
+        let mut diagnostics = Diagnostics::default();
+
+        if self.flop.contains(&NOT_DEALT) {
+            diagnostics.error(
+                DiagnosticKind::InvalidBoard,
+                "Flop cards not initialized".to_string(),
+            );
+        }
+
+        let mut board_mask: u64 = 0;
+        let board = self.flop.iter().chain([&self.turn, &self.river]);
+        for &card in board.filter(|&&card| card != NOT_DEALT) {
+            if card >= 52 {
+                diagnostics.error(
+                    DiagnosticKind::InvalidBoard,
+                    format!("Board cards must be in [0, 52): card = {card}"),
+                );
+            } else if board_mask & (1 << card) != 0 {
+                diagnostics.error(
+                    DiagnosticKind::BoardConflict { card },
+                    format!("Duplicate card: {}", card_to_string(card).unwrap()),
+                );
+            } else {
+                board_mask |= 1 << card;
+            }
+        }
+
+        if self.turn == NOT_DEALT && self.river != NOT_DEALT {
+            diagnostics.error(
+                DiagnosticKind::InvalidBoard,
+                "River card specified without turn card".to_string(),
+            );
+        }
+
+        let mut hands = [Vec::new(), Vec::new()];
+
+        for player in 0..2 {
+            let range = &self.range[player];
+            let name = PLAYER_NAMES[player];
+
+            if !range.is_valid() {
+                diagnostics.error(
+                    DiagnosticKind::InvalidRange { player },
+                    format!("{name} range is invalid (loaded broken data?)"),
+                );
+                continue;
+            }
+
+            if range.is_empty() {
+                diagnostics.error(
+                    DiagnosticKind::EmptyRange { player },
+                    format!("{name} range is empty"),
+                );
+                continue;
+            }
+
+            let num_combos = range.get_hands_weights(0).0.len();
+            hands[player] = range.get_hands_weights(board_mask).0;
+
+            if hands[player].is_empty() {
+                diagnostics.error(
+                    DiagnosticKind::EmptyRange { player },
+                    format!("{name} range is empty after removing the combinations that conflict with the board"),
+                );
+            } else if hands[player].len() < num_combos {
+                let num_combos = num_combos - hands[player].len();
+                diagnostics.warning(
+                    DiagnosticKind::RangeBoardConflict { player, num_combos },
+                    format!("{num_combos} combinations of {name} range conflict with the board"),
+                );
+            }
+        }
+
+        if !hands[0].is_empty() && !hands[1].is_empty() {
+            let mask = |(c1, c2): (Card, Card)| (1u64 << c1) | (1u64 << c2);
+            let has_matchup = hands[0].iter().any(|&oop| {
+                let oop_mask = mask(oop);
+                hands[1].iter().any(|&ip| oop_mask & mask(ip) == 0)
+            });
+
+            if !has_matchup {
+                diagnostics.error(
+                    DiagnosticKind::NoValidMatchup,
+                    "Valid card assignment does not exist".to_string(),
+                );
+            }
+        }
+
+        diagnostics.0
+    }
+}
+
+impl TreeConfig {
+    /// Validates the tree configuration without building an action tree, and returns all the
+    /// errors and warnings found.
+    ///
+    /// The errors are the fields out of their valid ranges (e.g., a non-positive starting pot).
+    /// The bet sizes of the streets from the initial state that always exceed the effective stack,
+    /// even when no chips have been added to the starting pot, are reported as warnings because
+    /// they are always replaced by all-in. [`ActionTree::new`] fails if and only if this method
+    /// reports an error.
+    ///
+    /// # Examples
+    /// ```
+    /// use postflop_solver_ffi::*;
+    ///
+    /// let bet_sizes = BetSizeOptions::try_from(("50%, 300%", "")).unwrap();
+    /// let tree_config = TreeConfig {
+    ///     starting_pot: 100,
+    ///     effective_stack: 250,
+    ///     river_bet_sizes: [bet_sizes.clone(), bet_sizes],
+    ///     initial_state: BoardState::River,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let diagnostics = tree_config.validate();
+    /// assert_eq!(diagnostics.len(), 2);
+    /// assert_eq!(
+    ///     diagnostics[0].kind,
+    ///     DiagnosticKind::BetSizeExceedsStack {
+    ///         street: BoardState::River,
+    ///         player: 0,
+    ///         size: BetSize::PotRelative(3.0),
+    ///     }
+    /// );
+    /// ```
+    pub fn validate(&self) -> Vec<ConfigDiagnostic> {
+        let mut diagnostics = Diagnostics::default();
+
+        let mut check = |is_valid: bool, field: &'static str, message: String| {
+            if !is_valid {
+                diagnostics.error(DiagnosticKind::InvalidValue { field }, message);
+            }
+        };
+
+        check(
+            self.starting_pot > 0,
+            "starting_pot",
+            format!("Starting pot must be positive: {}", self.starting_pot),
+        );
+        check(
+            self.effective_stack > 0,
+            "effective_stack",
+            format!("Effective stack must be positive: {}", self.effective_stack),
+        );
+        check(
+            self.rake_rate >= 0.0,
+            "rake_rate",
+            format!("Rake rate must be non-negative: {}", self.rake_rate),
+        );
+        check(
+            self.rake_rate <= 1.0,
+            "rake_rate",
+            format!(
+                "Rake rate must be less than or equal to 1.0: {}",
+                self.rake_rate
+            ),
+        );
+        check(
+            self.rake_cap >= 0.0,
+            "rake_cap",
+            format!("Rake cap must be non-negative: {}", self.rake_cap),
+        );
+        check(
+            self.add_allin_threshold >= 0.0,
+            "add_allin_threshold",
+            format!(
+                "Add all-in threshold must be non-negative: {}",
+                self.add_allin_threshold
+            ),
+        );
+        check(
+            self.force_allin_threshold >= 0.0,
+            "force_allin_threshold",
+            format!(
+                "Force all-in threshold must be non-negative: {}",
+                self.force_allin_threshold
+            ),
+        );
+        check(
+            self.merging_threshold >= 0.0,
+            "merging_threshold",
+            format!(
+                "Merging threshold must be non-negative: {}",
+                self.merging_threshold
+            ),
+        );
+
+        if self.starting_pot > 0 && self.effective_stack > 0 {
+            self.check_bet_sizes(&mut diagnostics);
+        }
+
+        diagnostics.0
+    }
+
+    /// Reports the bet sizes that always exceed the effective stack.
+    fn check_bet_sizes(&self, diagnostics: &mut Diagnostics) {
+        let pot = self.starting_pot as f64;
+        let stack = self.effective_stack as f64;
+
+        let streets = [
+            (BoardState::Flop, &self.flop_bet_sizes, None),
+            (
+                BoardState::Turn,
+                &self.turn_bet_sizes,
+                self.turn_donk_sizes.as_ref(),
+            ),
+            (
+                BoardState::River,
+                &self.river_bet_sizes,
+                self.river_donk_sizes.as_ref(),
+            ),
+        ];
+
+        for (street, bet_sizes, donk_sizes) in streets {
+            if street < self.initial_state {
+                continue;
+            }
+
+            for player in 0..2 {
+                let options = &bet_sizes[player];
+                let donk = match (player, donk_sizes) {
+                    (0, Some(donk_sizes)) => &donk_sizes.donk[..],
+                    _ => &[],
+                };
+
+                let bets = options.bet.iter().chain(donk).map(|size| (size, false));
+                let raises = options.raise.iter().map(|size| (size, true));
+
+                for (&size, is_raise) in bets.chain(raises) {
+                    let amount = match size {
+                        BetSize::PotRelative(ratio) if !is_raise => ratio * pot,
+                        BetSize::Additive(amount, _) => amount as f64,
+                        _ => continue,
+                    };
+
+                    if amount >= stack {
+                        let kind = DiagnosticKind::BetSizeExceedsStack {
+                            street,
+                            player,
+                            size,
+                        };
+                        let message = format!(
+                            "{} {street:?} {} size {} always exceeds the effective stack and is \
+                             replaced by all-in",
+                            PLAYER_NAMES[player],
+                            if is_raise { "raise" } else { "bet" },
+                            bet_size_to_string(size),
+                        );
+                        diagnostics.warning(kind, message);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Converts the pot-relative or additive bet size into the string format of [`BetSizeOptions`].
+fn bet_size_to_string(size: BetSize) -> String {
+    match size {
+        BetSize::PotRelative(ratio) => format!("{}%", ratio * 100.0),
+        BetSize::Additive(amount, _) => format!("{amount}c"),
+        _ => format!("{size:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::*;
+
+    #[test]
+    fn card_config_diagnostics() {
+        let config = |oop: &str, ip: &str, board: &str| {
+            let board = board_from_str(board).unwrap();
+            CardConfig {
+                range: [oop.parse().unwrap(), ip.parse().unwrap()],
+                flop: [board[0], board[1], board[2]],
+                turn: board.get(3).copied().unwrap_or(NOT_DEALT),
+                river: board.get(4).copied().unwrap_or(NOT_DEALT),
+            }
+        };
+        let kinds = |config: &CardConfig| {
+            let diagnostics = config.validate();
+            diagnostics.into_iter().map(|d| d.kind).collect::<Vec<_>>()
+        };
+
+        assert!(config("AA", "KK", "Td9d6h").validate().is_empty());
+
+        // the range of IP is removed by the board
+        let card_config = config("AA,KK", "QQ", "QsQhQd");
+        let diagnostics = card_config.validate();
+        assert_eq!(
+            kinds(&card_config),
+            [DiagnosticKind::EmptyRange { player: 1 }]
+        );
+        assert!(diagnostics[0].is_error());
+        assert!(diagnostics[0]
+            .message
+            .contains("IP range is empty after removing"));
+
+        // no matchup: both players have only AsAh
+        assert_eq!(
+            kinds(&config("AsAh", "AsAh", "2c3d4h")),
+            [DiagnosticKind::NoValidMatchup]
+        );
+
+        let mut card_config = config("AA", "KK", "Td9d6h");
+        card_config.turn = card_config.flop[0];
+        card_config.river = 60;
+        card_config.range[1] = Range::new();
+        assert_eq!(
+            kinds(&card_config),
+            [
+                DiagnosticKind::BoardConflict {
+                    card: card_config.flop[0]
+                },
+                DiagnosticKind::InvalidBoard,
+                DiagnosticKind::EmptyRange { player: 1 },
+            ]
+        );
+
+        // `validate` reports an error if and only if `with_config` fails
+        let tree_config = TreeConfig {
+            starting_pot: 100,
+            effective_stack: 100,
+            ..Default::default()
+        };
+        for card_config in [
+            config("AA", "KK", "Td9d6h"),
+            config("AA,KK", "QQ", "QsQhQd"),
+            config("AsAh", "AsAh", "2c3d4h"),
+            config("AA", "KK", "AsAhKs"),
+        ] {
+            let has_error = card_config.validate().iter().any(|d| d.is_error());
+            let action_tree = ActionTree::new(tree_config.clone()).unwrap();
+            let result = PostFlopGame::with_config(card_config, action_tree);
+            assert_eq!(has_error, result.is_err());
+        }
+    }
+
+    #[test]
+    fn tree_config_diagnostics() {
+        let bet_sizes = BetSizeOptions::try_from(("50%, 300%, 250c", "2x, 300c")).unwrap();
+        let donk_sizes = DonkSizeOptions::try_from("400%").unwrap();
+        let tree_config = TreeConfig {
+            initial_state: BoardState::Turn,
+            starting_pot: 100,
+            effective_stack: 250,
+            flop_bet_sizes: [bet_sizes.clone(), bet_sizes.clone()],
+            turn_bet_sizes: [bet_sizes.clone(), Default::default()],
+            turn_donk_sizes: Some(donk_sizes),
+            ..Default::default()
+        };
+
+        let diagnostics = tree_config.validate();
+        assert!(diagnostics.iter().all(|d| !d.is_error()));
+        let sizes = diagnostics
+            .iter()
+            .map(|d| match d.kind {
+                DiagnosticKind::BetSizeExceedsStack {
+                    street: BoardState::Turn,
+                    player: 0,
+                    size,
+                } => size,
+                _ => panic!("unexpected diagnostic: {d:?}"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sizes,
+            [
+                BetSize::PotRelative(3.0),
+                BetSize::Additive(250, 0),
+                BetSize::PotRelative(4.0),
+                BetSize::Additive(300, 0),
+            ]
+        );
+        assert_eq!(
+            diagnostics[0].message,
+            "OOP Turn bet size 300% always exceeds the effective stack and is replaced by all-in"
+        );
+
+        let tree_config = TreeConfig {
+            starting_pot: 0,
+            rake_rate: 1.5,
+            ..Default::default()
+        };
+        let fields = tree_config
+            .validate()
+            .into_iter()
+            .map(|d| match d.kind {
+                DiagnosticKind::InvalidValue { field } => field,
+                _ => panic!("unexpected diagnostic: {d:?}"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(fields, ["starting_pot", "effective_stack", "rake_rate"]);
+        assert!(ActionTree::new(tree_config).is_err());
+    }
+}
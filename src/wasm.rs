@@ -0,0 +1,254 @@
+//! WebAssembly bindings.
+//!
+//! A [`WasmGame`] is constructed, its memory is allocated, and then it is solved by calling
+//! [`WasmGame::solve_steps`] repeatedly from JavaScript, yielding to the event loop between the
+//! calls so that the page stays responsive:
+//!
+//! ```text
+//! const game = new WasmGame("AA,KK", "QQ,JJ", "Td9d6hQc", 100, 200, "50%", "");
+//! game.allocate_memory(false);
+//! while (game.solve_steps(10) > 0.005 * 100 && game.num_iterations() < 1000) {
+//!   await new Promise((resolve) => setTimeout(resolve));
+//! }
+//! game.finalize();
+//! const root = JSON.parse(game.node_json(4, true, false));
+//! ```
+//!
+//! Build for the web with `--no-default-features --features wasm` (add `bincode` to serialize
+//! games). The `zstd` feature is not supported on WebAssembly.
+
+use crate::ffi::play_checked;
+use crate::game::*;
+use crate::interface::*;
+use crate::range::*;
+use crate::solver::*;
+use crate::utility::*;
+use wasm_bindgen::prelude::*;
+
+#[cfg(all(feature = "zstd", target_arch = "wasm32"))]
+compile_error!("The `zstd` feature is not supported on WebAssembly");
+
+/// Game handle exported to JavaScript.
+#[wasm_bindgen]
+pub struct WasmGame {
+    game: PostFlopGame,
+    num_iterations: u32,
+}
+
+#[wasm_bindgen]
+impl WasmGame {
+    /// Builds a game whose memory is not yet allocated.
+    ///
+    /// `board` consists of the flop cards and optionally the turn and river cards (e.g.,
+    /// `"Td9d6hQc"`). The same bet and raise sizes are used for both players and all streets (see
+    /// [`BetSizeOptions`]).
+    ///
+    /// [`BetSizeOptions`]: crate::BetSizeOptions
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        oop_range: &str,
+        ip_range: &str,
+        board: &str,
+        starting_pot: i32,
+        effective_stack: i32,
+        bet_sizes: &str,
+        raise_sizes: &str,
+    ) -> Result<WasmGame, JsValue> {
+        let game = PostFlopGame::builder()
+            .oop_range(oop_range)
+            .ip_range(ip_range)
+            .board(board)
+            .pot(starting_pot)
+            .stack(effective_stack)
+            .bet_sizes(bet_sizes, raise_sizes)
+            .build()
+            .map_err(String::from)?;
+
+        Ok(Self {
+            game,
+            num_iterations: 0,
+        })
+    }
+
+    /// Returns the estimated memory usage in bytes.
+    pub fn memory_usage(&self, use_compression: bool) -> f64 {
+        let (uncompressed, compressed) = self.game.memory_usage();
+        match use_compression {
+            true => compressed as f64,
+            false => uncompressed as f64,
+        }
+    }
+
+    /// Allocates the memory and resets the progress of the solving. If the memory is already
+    /// allocated with the same option and the game is not solved, this is a no-op.
+    ///
+    /// Returns an error if the memory cannot be allocated.
+    pub fn allocate_memory(&mut self, use_compression: bool) -> Result<(), JsValue> {
+        let format = match use_compression {
+            false => StorageFormat::Float32,
+            true => StorageFormat::Int16,
+        };
+
+        if self.game.allocated_storage_formats() == Some((format, format)) && !self.is_solved() {
+            return Ok(());
+        }
+
+        self.game
+            .allocate_memory(use_compression, None)
+            .map_err(String::from)?;
+        self.num_iterations = 0;
+        Ok(())
+    }
+
+    /// Performs `num_iterations` iterations and returns the exploitability.
+    ///
+    /// Computing the exploitability costs about as much as one iteration, so call this with a few
+    /// iterations at a time.
+    pub fn solve_steps(&mut self, num_iterations: u32) -> Result<f32, JsValue> {
+        if !self.game.is_ready() || self.game.is_solved() {
+            return Err("Game is not ready or already solved".into());
+        }
+
+        for _ in 0..num_iterations {
+            solve_step(&self.game, self.num_iterations);
+            self.num_iterations += 1;
+        }
+
+        Ok(compute_exploitability(&self.game))
+    }
+
+    /// Returns the number of performed iterations.
+    pub fn num_iterations(&self) -> u32 {
+        self.num_iterations
+    }
+
+    /// Finalizes the solving. The results can be queried afterwards.
+    pub fn finalize(&mut self) -> Result<(), JsValue> {
+        if !self.game.is_ready() || self.game.is_solved() {
+            return Err("Game is not ready or already solved".into());
+        }
+
+        let exploitability = compute_exploitability(&self.game);
+        finalize(&mut self.game);
+        self.game.set_solve_summary(SolveSummary {
+            num_iterations: self.num_iterations,
+            exploitability,
+        });
+        Ok(())
+    }
+
+    /// Returns whether the game is solved.
+    pub fn is_solved(&self) -> bool {
+        self.game.is_solved()
+    }
+
+    /// Moves the current node back to the root.
+    pub fn back_to_root(&mut self) {
+        self.game.back_to_root();
+    }
+
+    /// Plays the given action. At a chance node, `action` is the ID of the dealt card.
+    pub fn play(&mut self, action: usize) -> Result<(), JsValue> {
+        Ok(play_checked(&mut self.game, action).map_err(String::from)?)
+    }
+
+    /// Applies the given line from the root (see [`PostFlopGame::apply_line_str`]).
+    pub fn apply_line(&mut self, line: &str) -> Result<(), JsValue> {
+        if !self.game.is_solved() {
+            return Err("Game is not solved".into());
+        }
+        self.game
+            .apply_line_str(line)
+            .map_err(|e| e.to_string().into())
+    }
+
+    /// Returns the current node in JSON format (see [`PostFlopGame::export_node_json`]).
+    pub fn node_json(
+        &mut self,
+        precision: usize,
+        expected_values: bool,
+        equity: bool,
+    ) -> Result<String, JsValue> {
+        if !self.game.is_solved() {
+            return Err("Game is not solved".into());
+        }
+
+        Ok(self.game.export_node_json(&ExportOptions {
+            precision,
+            expected_values,
+            equity,
+            ..Default::default()
+        }))
+    }
+
+    /// Returns the private hands of the given player (e.g., `"AsAh"`).
+    pub fn private_hands(&self, player: usize) -> Result<Vec<String>, JsValue> {
+        if player > 1 {
+            return Err(format!("Invalid player: {player}").into());
+        }
+        Ok(holes_to_strings(self.game.private_cards(player)).map_err(String::from)?)
+    }
+
+    /// Returns the strategy of the current node, laid out as
+    /// `[#(actions)][#(private hands of the current player)]`.
+    pub fn strategy(&self) -> Result<Vec<f32>, JsValue> {
+        if !self.game.is_solved() {
+            return Err("Game is not solved".into());
+        }
+        if self.game.is_terminal_node() || self.game.is_chance_node() {
+            return Err("Current node is not a decision node".into());
+        }
+        Ok(self.game.strategy())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solve_and_query() {
+        let new_game = || {
+            WasmGame::new(
+                "AA,KK,QQ,AK",
+                "JJ-88,AQ,KQ",
+                "Td9d6h Qc",
+                100,
+                200,
+                "50%",
+                "",
+            )
+            .unwrap()
+        };
+
+        let mut game = new_game();
+        assert!(game.memory_usage(true) < game.memory_usage(false));
+        game.allocate_memory(false).unwrap();
+
+        let exploitability = game.solve_steps(10).unwrap();
+        assert!(game.solve_steps(10).unwrap() < exploitability);
+        assert_eq!(game.num_iterations(), 20);
+        game.finalize().unwrap();
+        assert!(game.is_solved());
+
+        // same as solving at once
+        let mut expected = new_game().game;
+        expected.allocate_memory(false, None).unwrap();
+        solve(&mut expected, 20, 0.0, false);
+        assert_eq!(game.strategy().unwrap(), expected.strategy());
+
+        let json = game.node_json(2, true, false).unwrap();
+        assert!(json.starts_with("{\"line\":[],\"board\":[\"6h\",\"9d\",\"Td\",\"Qc\"],"));
+
+        game.apply_line("X X 2s").unwrap();
+        let num_hands = game.private_hands(0).unwrap().len();
+        assert_eq!(game.strategy().unwrap().len(), 2 * num_hands);
+        game.play(0).unwrap();
+        game.back_to_root();
+
+        // reallocating resets the progress
+        game.allocate_memory(false).unwrap();
+        assert!(!game.is_solved());
+        assert_eq!(game.num_iterations(), 0);
+    }
+}
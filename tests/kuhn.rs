@@ -1,5 +1,7 @@
-extern crate postflop_solver;
-use postflop_solver::*;
+#![allow(clippy::needless_range_loop)]
+
+extern crate postflop_solver_ffi;
+use postflop_solver_ffi::*;
 use std::mem::MaybeUninit;
 
 struct KuhnGame {
@@ -41,7 +43,7 @@ impl Game for KuhnGame {
     type Node = KuhnNode;
 
     #[inline]
-    fn root(&self) -> MutexGuardLike<Self::Node> {
+    fn root(&self) -> MutexGuardLike<'_, Self::Node> {
         self.root.lock()
     }
 
@@ -206,7 +208,7 @@ impl GameNode for KuhnNode {
     }
 
     #[inline]
-    fn play(&self, action: usize) -> MutexGuardLike<Self> {
+    fn play(&self, action: usize) -> MutexGuardLike<'_, Self> {
         self.children[action].1.lock()
     }
 
@@ -1,5 +1,7 @@
-extern crate postflop_solver;
-use postflop_solver::*;
+#![allow(clippy::needless_range_loop)]
+
+extern crate postflop_solver_ffi;
+use postflop_solver_ffi::*;
 use std::mem::MaybeUninit;
 use std::slice;
 
@@ -53,7 +55,7 @@ impl Game for LeducGame {
     type Node = LeducNode;
 
     #[inline]
-    fn root(&self) -> MutexGuardLike<Self::Node> {
+    fn root(&self) -> MutexGuardLike<'_, Self::Node> {
         self.root.lock()
     }
 
@@ -335,7 +337,7 @@ impl GameNode for LeducNode {
     }
 
     #[inline]
-    fn play(&self, action: usize) -> MutexGuardLike<Self> {
+    fn play(&self, action: usize) -> MutexGuardLike<'_, Self> {
         self.children[action].1.lock()
     }
 